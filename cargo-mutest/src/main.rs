@@ -1,7 +1,13 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::env;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
 use std::process::{self, Command};
 
+use mutest_runtime::history::History;
+use mutest_runtime::results::SavedResults;
+
 pub mod build {
     pub const RUST_TOOLCHAIN_VERSION: &str = env!("RUST_TOOLCHAIN_VERSION");
 }
@@ -24,10 +30,100 @@ fn strip_arg(args: &mut Vec<String>, has_value: bool, short_arg: Option<&str>, l
     }
 }
 
+/// Parse a features matrix of the form `a,b;a,c` into its feature combinations.
+fn parse_features_matrix(features_matrix: &str) -> Vec<Vec<String>> {
+    features_matrix.split(';')
+        .map(|features| features.split([',', ' ']).map(str::trim).filter(|feature| !feature.is_empty()).map(ToOwned::to_owned).collect())
+        .collect()
+}
+
+fn copy_command(cmd: &Command) -> Command {
+    let mut copy = Command::new(cmd.get_program());
+    copy.args(cmd.get_args());
+    for (key, value) in cmd.get_envs() {
+        match value {
+            Some(value) => { copy.env(key, value); }
+            None => { copy.env_remove(key); }
+        }
+    }
+    copy
+}
+
+/// Mutation summaries are keyed by their crate, location, operator, and description.
+type MutationKey = (String, String, String, String);
+
+/// Read the mutations of all summaries in the directory, or `None` if no summary was written.
+fn read_mutations_summaries(mutations_summary_dir: &Path) -> Option<HashSet<MutationKey>> {
+    use cargo_metadata::serde_json;
+
+    let mut mutations = HashSet::new();
+    let mut mutations_summaries_count = 0;
+
+    let Ok(entries) = fs::read_dir(mutations_summary_dir) else { return None; };
+    for entry in entries {
+        let path = entry.expect("cannot read mutations summary directory").path();
+        if !path.extension().is_some_and(|extension| extension == "json") { continue; }
+        mutations_summaries_count += 1;
+
+        let mutations_summary = fs::read_to_string(&path).expect(&format!("cannot read `{}`", path.display()));
+        let mutations_summary = serde_json::from_str::<serde_json::Value>(&mutations_summary).expect(&format!("invalid mutations summary `{}`", path.display()));
+
+        let crate_name = mutations_summary["crate"].as_str().unwrap_or_default();
+        for mutation in mutations_summary["mutations"].as_array().into_iter().flatten() {
+            mutations.insert((
+                crate_name.to_owned(),
+                mutation["display_location"].as_str().unwrap_or_default().to_owned(),
+                mutation["op_name"].as_str().unwrap_or_default().to_owned(),
+                mutation["display_name"].as_str().unwrap_or_default().to_owned(),
+            ));
+        }
+    }
+
+    if mutations_summaries_count == 0 { return None; }
+    Some(mutations)
+}
+
+fn print_features_matrix_report(features_matrix: &[Vec<String>], mutations_per_features: &[HashSet<MutationKey>]) {
+    let display_features = |features: &Vec<String>| match &features[..] {
+        [] => "no additional features".to_owned(),
+        _ => format!("features `{}`", features.join(",")),
+    };
+
+    let mut features_per_mutation = BTreeMap::<&MutationKey, Vec<usize>>::new();
+    for (i, mutations) in mutations_per_features.iter().enumerate() {
+        for mutation in mutations {
+            features_per_mutation.entry(mutation).or_default().push(i);
+        }
+    }
+
+    println!("\nfeatures matrix: {} configurations", features_matrix.len());
+    for (features, mutations) in features_matrix.iter().zip(mutations_per_features) {
+        println!("  {}: {} mutations", display_features(features), mutations.len());
+    }
+
+    let feature_specific_mutations = features_per_mutation.iter()
+        .filter(|(_, features)| features.len() < features_matrix.len())
+        .collect::<Vec<_>>();
+
+    println!("{total} mutations in total; {common} exist under all configurations; {specific} only exist under some configurations",
+        total = features_per_mutation.len(),
+        common = features_per_mutation.len() - feature_specific_mutations.len(),
+        specific = feature_specific_mutations.len(),
+    );
+
+    for ((crate_name, display_location, op_name, display_name), features) in feature_specific_mutations {
+        println!("  {display_location} [{op_name}] {display_name} (crate `{crate_name}`; only with {features})",
+            features = features.iter().map(|&i| display_features(&features_matrix[i])).collect::<Vec<_>>().join(", "),
+        );
+    }
+}
+
 mod run_print {
     mutest_driver_cli::opts! { ALL, pub(crate) possible_values where
         DETECTION_MATRIX = "detection-matrix"; ["Print test-mutation detection matrix."]
         SUBSUMPTION_MATRIX = "subsumption-matrix"; ["Print mutation subsumption matrix."]
+        TARGET_SCORES = "target-scores"; ["Print mutation score of each target function, sorted by undetected mutations."]
+        FILE_SCORES = "file-scores"; ["Print mutation score of each source file, sorted by undetected mutations."]
     }
 }
 
@@ -43,15 +139,53 @@ fn main() {
             .display_order(0)
             .about("Build and run the test harness.")
             // Evaluation-related Arguments
-            .arg(clap::arg!(--simulate [MUTATION_ID] "Evaluate tests for a single mutation.").value_parser(clap::value_parser!(u32)).conflicts_with_all(["flakes", "exhaustive", "print"]).display_order(110))
+            .arg(clap::arg!(--simulate [MUTATION_ID] "Evaluate tests for a single mutation, given by its id or stable id, even if it was batched with other mutations.").conflicts_with_all(["flakes", "exhaustive", "max-tests-per-mutation", "bench-slowdown-threshold", "flaky-retries", "perf-regression-factor", "baseline", "update-baseline", "fail-under", "fail-on", "history-file", "sarif", "results-file", "tui", "print"]).display_order(110))
             .arg(clap::arg!(--flakes [ITERATIONS_COUNT] "Perform mutation analysis multiple times to find flaky test-mutation pairs.").value_parser(clap::value_parser!(usize)).display_order(111))
+            .arg(clap::arg!(--"flakiness-report" [REPORT_FILE] "Write the flakiness matrix of `--flakes` to the file, as CSV if the file has a `.csv` extension, and as JSON otherwise.").value_parser(clap::value_parser!(PathBuf)).default_missing_value("mutest-flakiness.json").requires("flakes").display_order(111))
+            .arg(clap::arg!(--"fail-on-flaky-tests" "Fail if any test produces different results against a mutation across the iterations of `--flakes`.").requires("flakes").display_order(111))
+            .arg(clap::arg!(--"flaky-retries" [RETRIES_COUNT] "Re-run tests which detect a mutation the given number of times without mutations, discarding their detections if they fail (i.e. they are flaky).").value_parser(clap::value_parser!(usize)).conflicts_with("flakes").display_order(112))
+            .arg(clap::arg!(--"flakes-min-confidence" [COUNT] "Skip running tests against mutations for which they consistently produced the same result in at least the given number of previous runs, reusing their known result.").value_parser(clap::value_parser!(usize)).conflicts_with("simulate").display_order(112))
+            .arg(clap::arg!(--"verdict-cache-file" [VERDICT_CACHE_FILE] "Resume from and record the results of tests against mutations in the verdict cache file, for `--flakes-min-confidence`.").value_parser(clap::value_parser!(PathBuf)).default_missing_value("mutest-verdicts.txt").requires("flakes-min-confidence").display_order(112))
+            .arg(clap::arg!(--mutants [MUTANT_IDS] "Only evaluate the mutants with the given ids, as a comma-separated list of ids and id ranges (e.g. `1,5,9-12`).").conflicts_with_all(["simulate", "update-baseline"]).display_order(113))
+            .arg(clap::arg!(--mutations [MUTATION_IDS] "Only evaluate the mutations with the given ids, as a comma-separated list of ids and id ranges (e.g. `1,5,9-12`).").conflicts_with_all(["simulate", "update-baseline"]).display_order(113))
             .arg(clap::arg!(--exhaustive "Evaluate remaining tests, even if the mutation has already been detected by another test.").display_order(115))
+            .arg(clap::arg!(--"max-tests-per-mutation" [MAX_TESTS] "Stop evaluating a mutation after the given number of tests were evaluated for it, even if it was not detected.").value_parser(clap::value_parser!(usize)).display_order(115))
+            .arg(clap::arg!(--baseline [BASELINE_FILE] "Accept the surviving mutations listed in the baseline file, only failing on new surviving mutations.").value_parser(clap::value_parser!(PathBuf)).default_missing_value("mutest-baseline.toml").conflicts_with("flakes").display_order(117))
+            .arg(clap::arg!(--"update-baseline" "Write the surviving mutations to the baseline file (by default, `mutest-baseline.toml`), accepting all of them.").conflicts_with("flakes").display_order(117))
+            .arg(clap::arg!(--"history-file" [HISTORY_FILE] "Append a summary of the run to the history file, to track the mutation score across runs.").value_parser(clap::value_parser!(PathBuf)).default_missing_value("mutest-history.toml").conflicts_with("flakes").display_order(119))
+            .arg(clap::arg!(--sarif [SARIF_FILE] "Write the undetected mutations to the file as a SARIF log, for code scanning tools.").value_parser(clap::value_parser!(PathBuf)).default_missing_value("mutest.sarif").conflicts_with("flakes").display_order(119))
+            .arg(clap::arg!(--"results-file" [RESULTS_FILE] "Save the results of the run to the file, to regenerate reports from them with `cargo mutest report`.").value_parser(clap::value_parser!(PathBuf)).default_missing_value("mutest-results.json").conflicts_with("flakes").display_order(119))
+            .arg(clap::arg!(--"fail-under" [PERCENT] "Fail if the mutation score is below the given percentage. Unless `--fail-on` is given, undetected mutations no longer fail the run by themselves.").value_parser(clap::value_parser!(f64)).conflicts_with("flakes").display_order(118))
+            .arg(clap::arg!(--"fail-on" [POLICY] "Mutation results which fail the run.").value_parser(["undetected", "timed-out", "never"]).conflicts_with("flakes").display_order(118))
+            .arg(clap::arg!(--"bench-slowdown-threshold" [FACTOR] "Evaluate benchmarks, treating a slowdown of a benchmark by the given factor compared to the reference run as a detection.").value_parser(clap::value_parser!(f64)).display_order(116))
+            .arg(clap::arg!(--"perf-regression-factor" [FACTOR] "Report undetected mutations which slow down tests by more than the given factor compared to the reference run, as they may only affect performance.").value_parser(clap::value_parser!(f64)).conflicts_with("flakes").display_order(116))
+            .arg(clap::arg!(--tui "Show the live status of the evaluation in a terminal UI, and explore the results interactively once finished, re-running mutations on demand.").conflicts_with("flakes").display_order(100))
+            .arg(clap::arg!(--"test-timeout-slack" [FACTOR] "Slack of the test timeouts over the profiled execution time of each test, relative to the execution time (at least one second). [default: set by `--run-profile`]").value_parser(clap::value_parser!(f64)).display_order(116))
             .arg(clap::arg!(--"use-thread-pool" "Evaluate tests in a fixed-size thread pool.").display_order(120))
+            .arg(clap::arg!(--"property-test-cases" [CASES] "Number of cases generated by property-based tests (`proptest`, `quickcheck`). Failing `proptest` cases are not shrunk. [default: 32]").value_parser(clap::value_parser!(usize)).display_order(116))
+            .arg(clap::arg!(--"check-leaks" [POLICY] "Check for threads and file descriptors leaked by the tests of each mutant, and either evaluate subsequent mutants in isolated child processes, or report the mutations whose results may be affected.").value_parser(["isolate", "report"]).default_missing_value("isolate").display_order(120))
+            .arg(clap::arg!(--"test-memory-limit" [MEGABYTES] "Limit the memory of each test, by evaluating all tests in separate processes with limited virtual memory. Benchmarks are not evaluated in separate processes, so they are skipped. Unix only.").value_parser(clap::value_parser!(u64)).display_order(121))
+            .arg(clap::arg!(--"max-test-processes" [COUNT] "Maximum number of test processes running concurrently, independently from the test thread count.").value_parser(clap::value_parser!(usize)).display_order(121))
             // Printing-related Arguments
+            .arg(clap::arg!(--format [FORMAT] "Format of the diagnostics of undetected mutations. `github` emits GitHub Actions annotations, which are displayed inline in pull request diffs.").value_parser(["human", "github"]).default_value("human").display_order(100))
             .arg(clap::arg!(--print [PRINT] "Print additional information during mutation evaluation. Multiple may be specified, separated by commas.").value_delimiter(',').value_parser(run_print::possible_values()).display_order(101))
             // Passed arguments
             .arg(clap::Arg::new("PASSED_ARGS").trailing_var_arg(true).allow_hyphen_values(true))
         )
+        .subcommand(clap::Command::new("history")
+            .display_order(1)
+            .about("Print the trend of mutation scores recorded with `run --history-file`.")
+            .arg(clap::arg!(--"history-file" [HISTORY_FILE] "History file to read.").value_parser(clap::value_parser!(PathBuf)).default_value("mutest-history.toml"))
+        )
+        .subcommand(clap::Command::new("report")
+            .display_order(1)
+            .about("Generate reports from the results saved with `run --results-file`, without re-running the tests.")
+            .arg(clap::arg!(--"results-file" [RESULTS_FILE] "Results file to read.").value_parser(clap::value_parser!(PathBuf)).default_value("mutest-results.json"))
+            .arg(clap::arg!(--html [HTML_FILE] "Write the results to the file as an HTML page.").value_parser(clap::value_parser!(PathBuf)).default_missing_value("mutest-report.html"))
+            .arg(clap::arg!(--sarif [SARIF_FILE] "Write the undetected mutations to the file as a SARIF log, for code scanning tools.").value_parser(clap::value_parser!(PathBuf)).default_missing_value("mutest.sarif"))
+            .arg(clap::arg!(--"matrix-csv" [CSV_FILE] "Write the test-mutation detection matrix to the file as CSV.").value_parser(clap::value_parser!(PathBuf)).default_missing_value("mutest-matrix.csv"))
+            .arg(clap::arg!(--summary "Print a summary of the mutation scores and the undetected mutations. Printed by default if no other report is requested."))
+        )
         // Cargo
         .next_help_heading("Cargo options")
         .arg(clap::arg!(--"manifest-path" [MANIFEST_PATH] "Path to Cargo.toml."))
@@ -61,6 +195,7 @@ fn main() {
         .arg(clap::arg!(-F --features [FEATURES]... "Space or comma separated list of features to activate."))
         .arg(clap::arg!(--"all-features" "Activate all available features."))
         .arg(clap::arg!(--"no-default-features" "Do not activate the `default` feature."))
+        .arg(clap::arg!(--"features-matrix" [FEATURES_MATRIX] "Run once for each of the feature combinations separated by semicolons (e.g. `a,b;a,c`), and report mutations which only exist under some of them. Each combination is analyzed separately, in full.").conflicts_with_all(["features", "all-features"]))
         .arg(clap::arg!(-r --release "Build artifacts in release mode, with optimizations."))
         .arg(clap::arg!(--profile [PROFILE] "Build artifacts with the specified profile."))
         .arg(clap::arg!(--lib "Test only this package's library unit tests."))
@@ -68,19 +203,131 @@ fn main() {
         .arg(clap::arg!(--bins "Test all binaries."))
         .arg(clap::arg!(--"all-targets" "Test all targets."))
         .arg(clap::arg!(--offline "Run without accessing the network."))
+        .arg(clap::arg!(--"message-format" [FMT] "Error format of the diagnostics (e.g. `json`), including the warnings of the mutation analysis."))
         .get_matches_from(&args);
 
+    // The history is printed without building the crate.
+    if let Some(("history", matches)) = matches.subcommand() {
+        let Some(history_path) = matches.get_one::<PathBuf>("history-file") else { unreachable!() };
+        match History::read(history_path) {
+            Ok(history) => history.print_trend(),
+            Err(e) => {
+                eprintln!("error: cannot read history `{}`: {e}", history_path.display());
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // Reports are generated from saved results without building the crate.
+    if let Some(("report", matches)) = matches.subcommand() {
+        let Some(results_path) = matches.get_one::<PathBuf>("results-file") else { unreachable!() };
+        let saved_results = match SavedResults::read(results_path) {
+            Ok(saved_results) => saved_results,
+            Err(e) => {
+                eprintln!("error: cannot read results `{}`: {e}", results_path.display());
+                process::exit(1);
+            }
+        };
+
+        let mut reports = vec![];
+        if let Some(html_path) = matches.get_one::<PathBuf>("html") {
+            reports.push((html_path, "HTML report", saved_results.to_html()));
+        }
+        if let Some(sarif_path) = matches.get_one::<PathBuf>("sarif") {
+            let undetected_mutations = saved_results.mutations.iter().filter(|mutation| mutation.result == mutest_runtime::MutationTestResult::Undetected).collect::<Vec<_>>();
+            reports.push((sarif_path, "SARIF log", mutest_runtime::sarif::saved_results_to_sarif(&undetected_mutations)));
+        }
+        if let Some(matrix_csv_path) = matches.get_one::<PathBuf>("matrix-csv") {
+            reports.push((matrix_csv_path, "detection matrix", saved_results.to_matrix_csv()));
+        }
+
+        if matches.get_flag("summary") || reports.is_empty() {
+            print!("{}", saved_results.to_text_summary());
+        }
+        for (report_path, report_descr, report) in reports {
+            if let Err(e) = fs::write(report_path, report) {
+                eprintln!("error: cannot write {report_descr} `{}`: {e}", report_path.display());
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // Test filters are applied both by the driver, and by the test harness.
+    let test_filter_args = Iterator::chain(
+        matches.get_many::<String>("test-filter").unwrap_or_default().map(|filter| format!("--test-filter={filter}")),
+        matches.get_many::<String>("skip-test").unwrap_or_default().map(|skip| format!("--skip-test={skip}")),
+    ).collect::<Vec<_>>();
+
+    let run_profile = mutest_driver_cli::RunProfile::from_name(matches.get_one::<String>("run-profile").unwrap());
+
     let (cargo_subcommand, cargo_args, mutest_driver_subcommand, passed_args): (_, &[&str], _, _) = match matches.subcommand() {
         Some(("print", _)) => ("check", &["--profile", "test"], "print", None),
+        Some(("print-call-graph", _)) => ("check", &["--profile", "test"], "print-call-graph", None),
+        Some(("print-ops", _)) => ("check", &["--profile", "test"], "print-ops", None),
         Some(("build", _)) => ("test", &["--no-run"], "build", None),
+        Some(("self-check", _)) => ("check", &["--profile", "test"], "self-check", None),
         Some(("run", matches)) => {
             let mut passed_args = matches.get_many::<String>("PASSED_ARGS").unwrap_or_default().map(ToOwned::to_owned).collect::<Vec<_>>();
 
-            if let Some(mutation_id) = matches.get_one::<u32>("simulate") { passed_args.push(format!("--simulate={mutation_id}")); }
+            if let Some(mutation_id) = matches.get_one::<String>("simulate") { passed_args.push(format!("--simulate={mutation_id}")); }
             if let Some(iterations_count) = matches.get_one::<usize>("flakes") { passed_args.push(format!("--flakes={iterations_count}")); }
+            if let Some(mutant_ids) = matches.get_one::<String>("mutants") { passed_args.push(format!("--mutants={mutant_ids}")); }
+            if let Some(mutation_ids) = matches.get_one::<String>("mutations") { passed_args.push(format!("--mutations={mutation_ids}")); }
+            if let Some(max_tests) = matches.get_one::<usize>("max-tests-per-mutation") { passed_args.push(format!("--max-tests-per-mutation={max_tests}")); }
+            if let Some(factor) = matches.get_one::<f64>("perf-regression-factor") { passed_args.push(format!("--perf-regression-factor={factor}")); }
+            if let Some(retries_count) = matches.get_one::<usize>("flaky-retries") { passed_args.push(format!("--flaky-retries={retries_count}")); }
+            if let Some(bench_slowdown_threshold) = matches.get_one::<f64>("bench-slowdown-threshold") { passed_args.push(format!("--bench-slowdown-threshold={bench_slowdown_threshold}")); }
+            if let Some(memory_limit) = matches.get_one::<u64>("test-memory-limit") { passed_args.push(format!("--test-memory-limit={memory_limit}")); }
+            if let Some(max_processes) = matches.get_one::<usize>("max-test-processes") { passed_args.push(format!("--max-test-processes={max_processes}")); }
+            let test_timeout_slack = matches.get_one::<f64>("test-timeout-slack").copied().unwrap_or(run_profile.test_timeout_slack);
+            passed_args.push(format!("--test-timeout-slack={test_timeout_slack}"));
+
+            // The baseline path is resolved relative to the current directory, rather than the directory of the test harness.
+            let baseline_path = matches.get_one::<PathBuf>("baseline").cloned()
+                .or_else(|| matches.get_flag("update-baseline").then(|| PathBuf::from("mutest-baseline.toml")))
+                .map(|baseline_path| env::current_dir().expect("current directory invalid").join(baseline_path));
+            if let Some(baseline_path) = baseline_path { passed_args.push(format!("--baseline={}", baseline_path.display())); }
+            if matches.get_flag("update-baseline") { passed_args.push("--update-baseline".to_owned()); }
+            // The history path is resolved relative to the current directory, rather than the directory of the test harness.
+            if let Some(history_path) = matches.get_one::<PathBuf>("history-file") {
+                let history_path = env::current_dir().expect("current directory invalid").join(history_path);
+                passed_args.push(format!("--history-file={}", history_path.display()));
+            }
+            // The SARIF log path is resolved relative to the current directory, rather than the directory of the test harness.
+            if let Some(sarif_path) = matches.get_one::<PathBuf>("sarif") {
+                let sarif_path = env::current_dir().expect("current directory invalid").join(sarif_path);
+                passed_args.push(format!("--sarif={}", sarif_path.display()));
+            }
+            // The results path is resolved relative to the current directory, rather than the directory of the test harness.
+            if let Some(results_path) = matches.get_one::<PathBuf>("results-file") {
+                let results_path = env::current_dir().expect("current directory invalid").join(results_path);
+                passed_args.push(format!("--results-file={}", results_path.display()));
+            }
+            // The flakiness report path is resolved relative to the current directory, rather than the directory of the test harness.
+            if let Some(flakiness_report_path) = matches.get_one::<PathBuf>("flakiness-report") {
+                let flakiness_report_path = env::current_dir().expect("current directory invalid").join(flakiness_report_path);
+                passed_args.push(format!("--flakiness-report={}", flakiness_report_path.display()));
+            }
+            if matches.get_flag("fail-on-flaky-tests") { passed_args.push("--fail-on-flaky-tests".to_owned()); }
+            if let Some(min_confidence) = matches.get_one::<usize>("flakes-min-confidence") { passed_args.push(format!("--flakes-min-confidence={min_confidence}")); }
+            // The verdict cache path is resolved relative to the current directory, rather than the directory of the test harness.
+            if let Some(verdict_cache_path) = matches.get_one::<PathBuf>("verdict-cache-file") {
+                let verdict_cache_path = env::current_dir().expect("current directory invalid").join(verdict_cache_path);
+                passed_args.push(format!("--verdict-cache-file={}", verdict_cache_path.display()));
+            }
+            if let Some(fail_under) = matches.get_one::<f64>("fail-under") { passed_args.push(format!("--fail-under={fail_under}")); }
+            if let Some(fail_on) = matches.get_one::<String>("fail-on") { passed_args.push(format!("--fail-on={fail_on}")); }
+            if let Some(format) = matches.get_one::<String>("format") { passed_args.push(format!("--format={format}")); }
+
+            passed_args.extend(test_filter_args);
 
             if matches.get_flag("exhaustive") { passed_args.push("--exhaustive".to_owned()); }
             if matches.get_flag("use-thread-pool") { passed_args.push("--use-thread-pool".to_owned()); }
+            if let Some(leak_policy) = matches.get_one::<String>("check-leaks") { passed_args.push(format!("--check-leaks={leak_policy}")); }
+            if let Some(property_test_cases) = matches.get_one::<usize>("property-test-cases") { passed_args.push(format!("--property-test-cases={property_test_cases}")); }
+            if matches.get_flag("tui") { passed_args.push("--tui".to_owned()); }
 
             let mut print_names = matches.get_many::<String>("print").map(|print| print.map(String::as_str).collect::<HashSet<_>>()).unwrap_or_default();
             if print_names.contains("all") { print_names = HashSet::from_iter(run_print::ALL.into_iter().map(|s| *s)); }
@@ -113,6 +360,32 @@ fn main() {
     let i = mutest_args.iter().position(|arg| matches.subcommand_name().is_some_and(|subcommand| arg == subcommand)).expect("subcommand not found in args");
     mutest_args.splice(i.., [mutest_driver_subcommand.to_owned()]);
 
+    // The log file is shared by the driver and the test harness, which are run in different working directories.
+    let log_file = matches.get_one::<PathBuf>("log-file").map(|log_file| env::current_dir().expect("current directory invalid").join(log_file));
+    if let Some(log_file) = &log_file {
+        strip_arg(&mut mutest_args, true, None, Some("log-file"));
+        mutest_args.insert(0, format!("--log-file={}", log_file.display()));
+    }
+
+    // Test timings are recorded by the test harness, and read by the driver, which are run in different working directories.
+    let test_timings_file = matches.get_one::<PathBuf>("test-timings-file").map(|test_timings_file| env::current_dir().expect("current directory invalid").join(test_timings_file));
+    if let Some(test_timings_file) = &test_timings_file {
+        strip_arg(&mut mutest_args, true, None, Some("test-timings-file"));
+        mutest_args.insert(0, format!("--test-timings-file={}", test_timings_file.display()));
+    }
+
+    // The build cache is shared by the drivers of all crates, which are run in different working directories.
+    if let Some(build_cache_dir) = matches.get_one::<PathBuf>("build-cache-dir") {
+        let build_cache_dir = env::current_dir().expect("current directory invalid").join(build_cache_dir);
+        strip_arg(&mut mutest_args, true, None, Some("build-cache-dir"));
+        mutest_args.insert(0, format!("--build-cache-dir={}", build_cache_dir.display()));
+    }
+    if let Some(batching_state_dir) = matches.get_one::<PathBuf>("batching-state-dir") {
+        let batching_state_dir = env::current_dir().expect("current directory invalid").join(batching_state_dir);
+        strip_arg(&mut mutest_args, true, None, Some("batching-state-dir"));
+        mutest_args.insert(0, format!("--batching-state-dir={}", batching_state_dir.display()));
+    }
+
     let mut metadata_cmd = cargo_metadata::MetadataCommand::new();
 
     if let Some(manifest_path) = matches.get_one::<String>("manifest-path") {
@@ -146,6 +419,11 @@ fn main() {
         strip_arg(&mut mutest_args, false, None, Some("no-default-features"));
     }
 
+    let features_matrix = matches.get_one::<String>("features-matrix").map(|features_matrix| parse_features_matrix(features_matrix));
+    if features_matrix.is_some() {
+        strip_arg(&mut mutest_args, true, None, Some("features-matrix"));
+    }
+
     let metadata = metadata_cmd.exec().expect("could not retrieve Cargo metadata");
 
     let target_dir = matches.get_one::<String>("target-dir").map(ToOwned::to_owned)
@@ -183,23 +461,77 @@ fn main() {
         strip_arg(&mut mutest_args, false, None, Some("offline"));
     }
 
+    if let Some(message_format) = matches.get_one::<String>("message-format") {
+        cmd.args(["--message-format", message_format]);
+        strip_arg(&mut mutest_args, true, None, Some("message-format"));
+    }
+
     let mut path = env::current_exe().expect("current executable path invalid");
     path.set_file_name("mutest-driver");
     if cfg!(windows) { path.set_extension("exe"); }
     cmd.env("RUSTC_WORKSPACE_WRAPPER", path);
 
-    cmd.env("MUTEST_ARGS", mutest_args.join(" "));
+    let passed_args = passed_args.map(|passed_args| {
+        let mut args = vec!["--".to_owned()];
+        args.extend((0..matches.get_count("verbose")).map(|_| "-v".to_owned()));
+        if matches.get_one::<String>("timings").is_some() { args.push("--timings".to_owned()); }
+        if let Some(log_file) = &log_file { args.push(format!("--log-file={}", log_file.display())); }
+        if let Some(test_timings_file) = &test_timings_file { args.push(format!("--test-timings-file={}", test_timings_file.display())); }
+        args.extend(passed_args);
+        args
+    });
 
-    if let Some(passed_args) = passed_args {
-        cmd.arg("--");
-        cmd.args((0..matches.get_count("verbose")).map(|_| "-v"));
-        if matches.get_flag("timings") { cmd.arg("--timings"); }
-        cmd.args(&passed_args);
-    }
+    let run_cargo = |mut cmd: Command, mutest_args: &[String]| {
+        cmd.env("MUTEST_ARGS", mutest_args.join(" "));
+        if let Some(passed_args) = &passed_args { cmd.args(passed_args); }
+
+        let exit_status = cmd
+            .spawn().expect("failed to run Cargo")
+            .wait().expect("failed to run Cargo");
+
+        if !exit_status.success() { process::exit(exit_status.code().unwrap_or(-1)); }
+    };
+
+    let Some(features_matrix) = features_matrix else {
+        run_cargo(cmd, &mutest_args);
+        return;
+    };
+
+    // Each feature combination is analyzed in full, as the analysis of crates is not shared between combinations.
+    // Summaries are kept per invocation and feature combination, so that configurations which Cargo considers fresh
+    // (and thus does not analyze again) still have the summary of their previous analysis, and the summaries of
+    // different combinations are never mixed up.
+    let mutations_summary_dir = |features: &Vec<String>| {
+        let mut features = features.clone();
+        features.sort_unstable();
+        features.dedup();
+
+        let mut hasher = DefaultHasher::new();
+        cmd.get_args().collect::<Vec<_>>().hash(&mut hasher);
+        mutest_args.hash(&mut hasher);
+        features.hash(&mut hasher);
+        Path::new(&target_dir).join("features-matrix").join(format!("{:016x}", hasher.finish()))
+    };
+
+    let mut mutations_per_features = Vec::with_capacity(features_matrix.len());
+    for features in &features_matrix {
+        let mutations_summary_dir = mutations_summary_dir(features);
 
-    let exit_status = cmd
-        .spawn().expect("failed to run Cargo")
-        .wait().expect("failed to run Cargo");
+        let mut cmd = copy_command(&cmd);
+        if !features.is_empty() { cmd.args(["--features", &features.join(",")]); }
+
+        let mut mutest_args = mutest_args.clone();
+        mutest_args.insert(0, format!("--mutations-summary-dir={}", mutations_summary_dir.display()));
+
+        run_cargo(cmd, &mutest_args);
+
+        let Some(mutations) = read_mutations_summaries(&mutations_summary_dir) else {
+            eprintln!("error: no mutations summary was written for feature combination `{}` in `{}`", features.join(","), mutations_summary_dir.display());
+            eprintln!("note: the crates may have been considered fresh by a previous build; run `cargo clean` and try again");
+            process::exit(1);
+        };
+        mutations_per_features.push(mutations);
+    }
 
-    process::exit(exit_status.code().unwrap_or(-1));
+    print_features_matrix_report(&features_matrix, &mutations_per_features);
 }
@@ -34,18 +34,160 @@ pub mod mutation_operators {
         BIT_OP_SHIFT_DIR_SWAP = "bit_op_shift_dir_swap";
         BIT_OP_XOR_AND_SWAP = "bit_op_xor_and_swap";
         BOOL_EXPR_NEGATE = "bool_expr_negate";
+        BOOL_RETURN_FLIP = "bool_return_flip";
+        CALL_ARG_DEFAULT_REPLACE = "call_arg_default_replace";
         CALL_DELETE = "call_delete";
         CALL_VALUE_DEFAULT_SHADOW = "call_value_default_shadow";
+        COMBINATOR_NOOP = "combinator_noop";
         CONTINUE_BREAK_SWAP = "continue_break_swap";
         EQ_OP_INVERT = "eq_op_invert";
         LOGICAL_OP_AND_OR_SWAP = "logical_op_and_or_swap";
+        MATCH_ARM_BODY_REPLACE = "match_arm_body_replace";
+        MATCH_ARM_REORDER = "match_arm_reorder";
         MATH_OP_ADD_MUL_SWAP = "math_op_add_mul_swap";
         MATH_OP_ADD_SUB_SWAP = "math_op_add_sub_swap";
         MATH_OP_DIV_REM_SWAP = "math_op_div_rem_swap";
         MATH_OP_MUL_DIV_SWAP = "math_op_mul_div_swap";
+        METHOD_CALL_SWAP = "method_call_swap";
+        OPTION_RESULT_COMBINATOR_SWAP = "option_result_combinator_swap";
         RANGE_LIMIT_SWAP = "range_limit_swap";
+        REFUTABLE_LET_INVERT = "refutable_let_invert";
         RELATIONAL_OP_EQ_SWAP = "relational_op_eq_swap";
         RELATIONAL_OP_INVERT = "relational_op_invert";
+        STRING_LIT_REPLACE = "string_lit_replace";
+        STRUCT_FIELD_REPLACE = "struct_field_replace";
+    }
+}
+
+pub mod mutation_priority {
+    crate::exclusive_opts! { pub(crate) possible_values where
+        HIGH = "high"; ["Only mutations most likely to reveal weaknesses in the tests."]
+        MEDIUM = "medium"; ["Mutations of medium and high priority."]
+        LOW = "low"; ["All mutations."]
+    }
+}
+
+pub mod run_profile {
+    crate::exclusive_opts! { pub(crate) possible_values where
+        QUICK = "quick"; ["Only high priority mutations, sampled, of functions called directly from tests, batched into mutants."]
+        STANDARD = "standard"; ["The defaults of all options."]
+        THOROUGH = "thorough"; ["All mutations of functions up to a greater depth, with more lenient test timeouts."]
+    }
+}
+
+/// Defaults of the options bundled by a run profile, selected with `--run-profile`. Options which are given explicitly
+/// take precedence over the defaults of the profile.
+pub struct RunProfile {
+    pub operators: &'static [&'static str],
+    pub min_priority: &'static str,
+    pub max_mutations_per_target: Option<usize>,
+    pub depth: usize,
+    pub mutant_batch_algorithm: &'static str,
+    pub mutant_batch_size: usize,
+    /// Slack of the test timeouts over the profiled execution time of each test, relative to the execution time.
+    pub test_timeout_slack: f64,
+}
+
+impl RunProfile {
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            run_profile::QUICK => Self {
+                operators: &[
+                    mutation_operators::BOOL_EXPR_NEGATE,
+                    mutation_operators::BOOL_RETURN_FLIP,
+                    mutation_operators::CALL_DELETE,
+                    mutation_operators::EQ_OP_INVERT,
+                    mutation_operators::LOGICAL_OP_AND_OR_SWAP,
+                    mutation_operators::MATH_OP_ADD_SUB_SWAP,
+                    mutation_operators::RELATIONAL_OP_EQ_SWAP,
+                    mutation_operators::RELATIONAL_OP_INVERT,
+                ],
+                min_priority: mutation_priority::HIGH,
+                max_mutations_per_target: Some(4),
+                depth: 1,
+                mutant_batch_algorithm: mutant_batch_algorithm::GREEDY,
+                mutant_batch_size: 8,
+                test_timeout_slack: 0.1,
+            },
+            run_profile::STANDARD => Self {
+                operators: mutation_operators::ALL,
+                min_priority: mutation_priority::LOW,
+                max_mutations_per_target: None,
+                depth: 3,
+                mutant_batch_algorithm: mutant_batch_algorithm::NONE,
+                mutant_batch_size: 1,
+                test_timeout_slack: 0.1,
+            },
+            run_profile::THOROUGH => Self {
+                operators: mutation_operators::ALL,
+                min_priority: mutation_priority::LOW,
+                max_mutations_per_target: None,
+                depth: 5,
+                mutant_batch_algorithm: mutant_batch_algorithm::NONE,
+                mutant_batch_size: 1,
+                test_timeout_slack: 1.0,
+            },
+            _ => unreachable!("invalid run profile name: `{name}`"),
+        }
+    }
+
+    /// Returns `true` if the option was given explicitly on the command line, in which case it takes precedence over
+    /// the defaults of the profile.
+    pub fn is_explicit(matches: &clap::ArgMatches, arg_id: &str) -> bool {
+        matches.value_source(arg_id) == Some(clap::parser::ValueSource::CommandLine)
+    }
+
+    pub fn mutation_operators<'a>(&self, matches: &'a clap::ArgMatches) -> Vec<&'a str> {
+        if !Self::is_explicit(matches, "mutation-operators") { return self.operators.to_vec(); }
+
+        let op_names = matches.get_many::<String>("mutation-operators").unwrap().map(String::as_str).collect::<Vec<_>>();
+        match op_names.contains(&"all") {
+            true => mutation_operators::ALL.to_vec(),
+            false => op_names,
+        }
+    }
+
+    pub fn min_priority<'a>(&self, matches: &'a clap::ArgMatches) -> &'a str {
+        match Self::is_explicit(matches, "min-priority") {
+            true => matches.get_one::<String>("min-priority").unwrap(),
+            false => self.min_priority,
+        }
+    }
+
+    pub fn max_mutations_per_target(&self, matches: &clap::ArgMatches) -> Option<usize> {
+        match Self::is_explicit(matches, "max-mutations-per-target") {
+            true => matches.get_one::<usize>("max-mutations-per-target").copied(),
+            false => self.max_mutations_per_target,
+        }
+    }
+
+    pub fn depth(&self, matches: &clap::ArgMatches) -> usize {
+        match Self::is_explicit(matches, "depth") {
+            true => *matches.get_one::<usize>("depth").unwrap(),
+            false => self.depth,
+        }
+    }
+
+    pub fn mutant_batch_algorithm<'a>(&self, matches: &'a clap::ArgMatches) -> &'a str {
+        match Self::is_explicit(matches, "mutant-batch-algorithm") {
+            true => matches.get_one::<String>("mutant-batch-algorithm").unwrap(),
+            false => self.mutant_batch_algorithm,
+        }
+    }
+
+    pub fn mutant_batch_size(&self, matches: &clap::ArgMatches) -> usize {
+        match Self::is_explicit(matches, "mutant-batch-size") {
+            true => *matches.get_one::<usize>("mutant-batch-size").unwrap(),
+            false => self.mutant_batch_size,
+        }
+    }
+}
+
+pub mod unsafe_taint {
+    crate::exclusive_opts! { pub(crate) possible_values where
+        TRANSITIVE = "transitive"; ["Taint every function called from an unsafe context, directly or transitively."]
+        LOCAL = "local"; ["Like `transitive`, but only unsafe contexts of local functions taint their callees (e.g. not `unsafe` blocks of the standard library)."]
+        DIRECT = "direct"; ["Only taint functions called directly from unsafe contexts of local functions."]
     }
 }
 
@@ -62,7 +204,25 @@ pub mod mutant_batch_greedy_ordering_heuristic {
     crate::exclusive_opts! { pub(crate) possible_values where
         CONFLICTS = "conflicts";
         REVERSE_CONFLICTS = "reverse-conflicts";
+        TARGET_DIVERSITY = "target-diversity";
+        RANDOM = "random";
+        NONE = "none";
+    }
+}
+
+pub mod target_cap_policy {
+    crate::exclusive_opts! { pub(crate) possible_values where
+        SPACED = "spaced";
+        DIVERSE = "diverse";
+        FIRST = "first";
         RANDOM = "random";
+    }
+}
+
+pub mod batch_locality {
+    crate::exclusive_opts! { pub(crate) possible_values where
+        FILE = "file";
+        MODULE = "module";
         NONE = "none";
     }
 }
@@ -74,15 +234,33 @@ pub mod print {
         CALL_GRAPH = "call-graph"; ["Print call graph of test cases."]
         CONFLICT_GRAPH = "conflict-graph"; ["Print mutation conflict graph."]
         COMPATIBILITY_GRAPH = "compatibility-graph"; ["Print mutation compatibility graph (i.e. the complement graph of the conflict graph)."]
+        CONFLICT_GRAPH_STATS = "conflict-graph-stats"; ["Print statistics of the mutation conflict graph, such as its density and degree distribution."]
+        OPS = "ops"; ["Print list of mutation operators, with the number of mutations each generated, and how many were dropped."]
         MUTANTS = "mutants"; ["Print list of generated mutations, grouped into mutant batches."]
+        PATCHES = "patches"; ["Print each generated mutation as a unified diff against the original source code."]
         CODE = "code"; ["Print the generated code of the test harness."]
     }
 }
 
+pub mod timings_format {
+    crate::exclusive_opts! { pub(crate) possible_values where
+        HUMAN = "human";
+        JSON = "json";
+    }
+}
+
 pub mod graph_format {
     crate::exclusive_opts! { pub(crate) possible_values where
         SIMPLE = "simple";
         GRAPHVIZ = "graphviz";
+        JSON = "json";
+    }
+}
+
+pub mod targets_format {
+    crate::exclusive_opts! { pub(crate) possible_values where
+        HUMAN = "human";
+        JSON = "json";
     }
 }
 
@@ -123,30 +301,75 @@ pub fn command() -> clap::Command {
             .display_order(1)
             .about("Build the test harness.")
         )
+        .subcommand(clap::Command::new("print-call-graph")
+            .display_order(3)
+            .about("Print the call graph of test cases, without building. Shorthand for `print --print=call-graph`.")
+        )
+        .subcommand(clap::Command::new("print-ops")
+            .display_order(4)
+            .about("Print the mutation operators, with the number of mutations each generated, without building. Shorthand for `print --print=ops`.")
+        )
+        .subcommand(clap::Command::new("self-check")
+            .display_order(5)
+            .about("Type-check each mutation separately, without building, reporting ill-typed mutations with their operator.")
+        )
         // Mutation-related Arguments
+        .arg(clap::arg!(--"run-profile" [PROFILE] "Profile bundling the defaults of the operators, sampling, call graph depth, mutation batching, and test timeouts. Options given explicitly take precedence.").value_parser(run_profile::possible_values()).default_value(run_profile::STANDARD).display_order(110))
         .arg(clap::arg!(--safe "Avoid mutating code in contexts which contain `unsafe` blocks. [default]").display_order(111))
         .arg(clap::arg!(--cautious "Produce unsafe mutations in contexts which contain `unsafe` blocks.").display_order(112))
         .arg(clap::arg!(--risky "Produce safe mutations in contexts which contain `unsafe` blocks.").display_order(113))
         .arg(clap::arg!(--unsafe "Mutate code in `unsafe` blocks.").display_order(114))
         .group(clap::ArgGroup::new("unsafe-targeting").args(&["safe", "cautious", "risky", "unsafe"]).multiple(false))
+        .arg(clap::arg!(--"unsafe-taint" [POLICY] "How far the unsafety of `unsafe` blocks and functions taints the safe functions called from them.").value_parser(unsafe_taint::possible_values()).default_value(unsafe_taint::TRANSITIVE).display_order(115))
         .arg(clap::arg!(--"mutation-operators" [MUTATION_OPERATORS] "Mutation operators to apply to the code, separated by commas.").value_delimiter(',').value_parser(mutation_operators::possible_values()).default_value("all").display_order(115))
+        .arg(clap::arg!(--"min-priority" [PRIORITY] "Only apply mutations of at least the specified priority. Useful for quick runs with only the most valuable mutations.").value_parser(mutation_priority::possible_values()).default_value(mutation_priority::LOW).display_order(115))
+        .arg(clap::arg!(--"max-mutations-per-target" [MAX_MUTATIONS] "Limit the number of mutations of each function, keeping evenly spaced mutations across its body.").value_parser(clap::value_parser!(usize)).display_order(116))
+        .arg(clap::arg!(--"target-cap-policy" [POLICY] "Mutations of each function kept by `--max-mutations-per-target`: evenly spaced ones, a diverse subset with one mutation per operator per block, the first ones, or random ones (seeded by `--mutant-batch-seed`).").value_parser(target_cap_policy::possible_values()).default_value(target_cap_policy::SPACED).display_order(116))
+        .arg(clap::arg!(--"mutate-const-fns" "Mutate `const fn`s which are never evaluated at compile-time, by removing their `const` qualifier.").display_order(116))
+        .arg(clap::arg!(--"mutate-macro-expansions" "Mutate the code produced by local macros (e.g. `macro_rules`), not just their arguments. Mutations are reported at the macro invocation.").display_order(116))
+        .arg(clap::arg!(--"cfg-mutants" "Build each mutant into a separate executable, with its mutations enabled by `cfg` flags, instead of a single meta-mutant with runtime substitutions. Useful for performance-sensitive code.").display_order(117))
+        .arg(clap::arg!(--"cap-lints" [LEVEL] "Maximum level of lints when building the test harness. Use `none` to keep the lint levels of the crate.").value_parser(["none", "allow", "warn", "deny", "forbid"]).default_value("allow").display_order(130))
+        .arg(clap::arg!(-A --allow [LINT] "Allow the lint when building the test harness. May be specified multiple times.").action(clap::ArgAction::Append).display_order(130))
+        .arg(clap::arg!(--"build-cache-dir" [BUILD_CACHE_DIR] "Cache the compiled test harness in the specified directory, and reuse it when compiling identical code with identical settings, skipping recompilation.").value_parser(clap::value_parser!(std::path::PathBuf)).display_order(131))
+        .arg(clap::arg!(--doctests "Include documentation tests as entry points, by extracting them into regular tests of the crate.").display_order(140))
+        .arg(clap::arg!(--"integration-tests" "Include the integration tests of the package as entry points, by injecting them into the unit tests of its library. Use with `--lib` to avoid also evaluating the integration tests separately.").display_order(140))
+        .arg(clap::arg!(--"custom-test-harnesses" "Analyze test targets with custom test harnesses (`harness = false`), using functions marked with `#[mutest::test]` as tests.").display_order(140))
+        .arg(clap::arg!(--"test-filter" [PATTERN] "Only use tests whose names contain the pattern as entry points, and only evaluate mutations against them. May be specified multiple times.").action(clap::ArgAction::Append).display_order(140))
+        .arg(clap::arg!(--"skip-test" [PATTERN] "Exclude tests whose names contain the pattern, like `--test-filter`. May be specified multiple times.").action(clap::ArgAction::Append).display_order(140))
+        .arg(clap::arg!(--"mutate-only" [PATTERN] "Only mutate functions whose definition path matches the pattern (e.g. `parser::lexer::*` or `parser::lexer::next_token`). May be specified multiple times.").action(clap::ArgAction::Append).display_order(150))
+        .arg(clap::arg!(--"skip-target" [PATTERN] "Do not mutate functions whose definition path matches the pattern, like `--mutate-only`. May be specified multiple times.").action(clap::ArgAction::Append).display_order(150))
         .arg(clap::arg!(--"call-graph-depth" [CALL_GRAPH_DEPTH] "Depth of call graph analysis. [default: mutation depth]").value_parser(clap::value_parser!(usize)).display_order(150))
         .arg(clap::arg!(-d --depth [DEPTH] "Callees of each test function are mutated up to the specified depth.").default_value("3").value_parser(clap::value_parser!(usize)).display_order(150))
         .arg(clap::arg!(--"mutant-batch-algorithm" [MUTANT_BATCH_ALGORITHM] "Algorithm to use to batch mutations into mutants.").value_parser(mutant_batch_algorithm::possible_values()).default_value(mutant_batch_algorithm::NONE).display_order(199))
         .arg(clap::arg!(--"mutant-batch-size" [MUTANT_BATCH_SIZE] "Maximum number of mutations to batch into a single mutant.").default_value("1").value_parser(clap::value_parser!(usize)).display_order(199))
+        .arg(clap::arg!(--"mutant-batch-max-cost" [COST] "Maximum estimated cost of the tests reaching the mutations of a single mutant: their total execution time in seconds if `--test-timings-file` is given, otherwise their number.").value_parser(clap::value_parser!(f64)).display_order(199))
+        .arg(clap::arg!(--"test-timings-file" [TEST_TIMINGS_FILE] "Record the execution times of tests in the profiling run of the test harness to the file, and use the times recorded in previous runs to estimate the cost of mutants.").value_parser(clap::value_parser!(std::path::PathBuf)).display_order(199))
         .arg(clap::arg!(--"mutant-batch-seed" [MUTANT_BATCH_SEED] "Random seed to use for randomness during mutation batching.").display_order(199))
         .arg(clap::arg!(--"mutant-batch-greedy-ordering-heuristic" [MUTANT_BATCH_GREEDY_ORDERING_HEURISTIC] "Ordering heuristic to use for `greedy` mutation batching algorithm.").value_parser(mutant_batch_greedy_ordering_heuristic::possible_values()).default_value(mutant_batch_greedy_ordering_heuristic::REVERSE_CONFLICTS).display_order(199))
         .arg(clap::arg!(--"mutant-batch-greedy-epsilon" [MUTANT_BATCH_GREEDY_EPSILON] "Optional epsilon parameter for `greedy` mutation batching algorithm, used to control the probability of random mutation assignment.").default_value("0").value_parser(clap::value_parser!(f64)).display_order(199))
+        .arg(clap::arg!(--"batch-locality" [LOCALITY] "Prefer batching mutations of the same source file or module into the same mutant, so that the tests of a mutant exercise related code.").value_parser(batch_locality::possible_values()).default_value(batch_locality::NONE).display_order(199))
+        .arg(clap::arg!(--"batching-report" "Compare the mutant counts and estimated evaluation costs of all mutation batching algorithms, before proceeding with the chosen one.").display_order(199))
+        .arg(clap::arg!(--"batching-state-dir" [BATCHING_STATE_DIR] "Reuse the mutant batching of the previous run, stored in the specified directory, for mutations which have not changed, only batching new mutations. Preserves the ids of reused mutants.").value_parser(clap::value_parser!(std::path::PathBuf)).display_order(199))
         // Printing-related Arguments
-        .arg(clap::arg!(--timings "Print timing information for each completed pass.").display_order(100))
+        .arg(clap::arg!(--timings [FORMAT] "Print timing information for each completed pass. The `json` format additionally includes the peak memory usage of each phase (Linux only).").value_parser(timings_format::possible_values()).num_args(0..=1).require_equals(true).default_missing_value(timings_format::HUMAN).display_order(100))
         .arg(clap::arg!(-v --verbose "Print more verbose information during execution.").action(clap::ArgAction::Count).default_value("0").display_order(100))
+        .arg(clap::arg!(--"log-file" [LOG_FILE] "Additionally append all log messages, including verbose ones, with timestamps to the specified file.").value_parser(clap::value_parser!(std::path::PathBuf)).display_order(100))
         .arg(clap::arg!(--print [PRINT] "Print additional information during analysis. Multiple may be specified, separated by commas.").value_delimiter(',').value_parser(print::possible_values()).display_order(101))
+        .arg(clap::arg!(--"emit-code-dir" [EMIT_CODE_DIR] "Write the generated code into the specified directory, instead of printing it. Implies `--print=code`.").value_parser(clap::value_parser!(std::path::PathBuf)).display_order(101))
+        .arg(clap::arg!(--"emit-code-per-mutant" "Additionally write the code of each mutant into a separate file. Requires `--emit-code-dir` and `--mutant-batch-algorithm=none`.").requires("emit-code-dir").display_order(101))
+        .arg(clap::arg!(--"mutations-summary-dir" [MUTATIONS_SUMMARY_DIR] "Write a JSON summary of the generated mutations of each crate into the specified directory.").value_parser(clap::value_parser!(std::path::PathBuf)).display_order(101))
         .arg(clap::arg!(--"graph-exclude-unsafe" "Exclude unsafe mutations from the graph, only listing safe mutations.").display_order(102))
         .arg(clap::arg!(--"graph-format" [GRAPH_FORMAT] "Format to print the graph in.").value_parser(graph_format::possible_values()).default_value(graph_format::SIMPLE).display_order(102))
+        .arg(clap::arg!(--"explain-target" [TARGET_PATH] "Explain how the function with the specified definition path is reached from tests, listing the shortest call path from each test.").display_order(103))
+        .arg(clap::arg!(--explain [MUT_ID] "Explain the mutation with the specified ID: its operator, original and substituted code, target, safety, mutant, and the tests reaching it.").value_parser(clap::value_parser!(u32)).display_order(103))
+        .arg(clap::arg!(--"explain-skipped" "Additionally list the functions which are not targeted for mutation, with the reason each was skipped. Used with `--print=targets`.").display_order(103))
+        .arg(clap::arg!(--"targets-format" [TARGETS_FORMAT] "Format to print the targets in. Used with `--print=targets`.").value_parser(targets_format::possible_values()).default_value(targets_format::HUMAN).display_order(103))
+        .arg(clap::arg!(--"with-tests" "List the tests reaching each target, with their distance, in the output of `--print=targets`. The human-readable output always lists them.").display_order(103))
         .arg(clap::arg!(--"call-graph-non-local-calls" [CALL_GRAPH_NON_LOCAL_CALL_VIEW] "Mode to display non-local calls in the call graph.").value_parser(call_graph_non_local_call_view::possible_values()).default_value(call_graph_non_local_call_view::COLLAPSE).display_order(103))
         // Experimental Flags
         .arg(clap::arg!(--Zverify [VERIFY] "Perform additional checks to verify correctness and completeness. Multiple may be specified, separated by commas.").value_delimiter(',').value_parser(verify::possible_values()).display_order(500))
         .arg(clap::arg!(--"Zno-sanitize-macro-expns" "Skip sanitizing the identifiers and paths in the expanded output of macro invocations. This was the previous behavior and is not recommended.").display_order(500))
+        .arg(clap::arg!(--"no-revert-macros" "Keep the expanded form of non-local macro invocations in the generated code, instead of reverting them to their original invocations. Only applies with `--Zno-sanitize-macro-expns`.").requires("Zno-sanitize-macro-expns").display_order(500))
         // Information
         // FIXME: Regression; the `help` subcommand can no longer be customized, so the about text does not match that
         //        of the help flags.
@@ -155,3 +378,56 @@ pub fn command() -> clap::Command {
 
     cmd
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(args: &[&str]) -> clap::ArgMatches {
+        command().try_get_matches_from(["cargo mutest"].iter().chain(args).chain(&["build"])).unwrap()
+    }
+
+    #[test]
+    fn test_run_profile_defaults() {
+        let matches = matches(&["--run-profile=quick"]);
+        let run_profile = RunProfile::from_name(run_profile::QUICK);
+
+        assert_eq!(run_profile.mutation_operators(&matches), run_profile.operators);
+        assert_eq!(run_profile.min_priority(&matches), mutation_priority::HIGH);
+        assert_eq!(run_profile.max_mutations_per_target(&matches), Some(4));
+        assert_eq!(run_profile.depth(&matches), 1);
+        assert_eq!(run_profile.mutant_batch_algorithm(&matches), mutant_batch_algorithm::GREEDY);
+        assert_eq!(run_profile.mutant_batch_size(&matches), 8);
+    }
+
+    #[test]
+    fn test_explicit_options_override_run_profile() {
+        let matches = matches(&[
+            "--run-profile=quick",
+            "--mutation-operators=call_delete,eq_op_invert,range_limit_swap",
+            "--min-priority=low",
+            "--max-mutations-per-target=16",
+            "--depth=4",
+            "--mutant-batch-algorithm=none",
+            "--mutant-batch-size=1",
+        ]);
+        let run_profile = RunProfile::from_name(run_profile::QUICK);
+
+        assert_eq!(run_profile.mutation_operators(&matches), [mutation_operators::CALL_DELETE, mutation_operators::EQ_OP_INVERT, mutation_operators::RANGE_LIMIT_SWAP]);
+        assert_eq!(run_profile.min_priority(&matches), mutation_priority::LOW);
+        assert_eq!(run_profile.max_mutations_per_target(&matches), Some(16));
+        assert_eq!(run_profile.depth(&matches), 4);
+        assert_eq!(run_profile.mutant_batch_algorithm(&matches), mutant_batch_algorithm::NONE);
+        assert_eq!(run_profile.mutant_batch_size(&matches), 1);
+    }
+
+    #[test]
+    fn test_explicit_options_equal_to_clap_defaults_override_run_profile() {
+        // The explicit values equal the defaults of the options, which must not be mistaken for options not given.
+        let matches = matches(&["--run-profile=quick", "--mutation-operators=all", "--depth=3"]);
+        let run_profile = RunProfile::from_name(run_profile::QUICK);
+
+        assert_eq!(run_profile.mutation_operators(&matches), mutation_operators::ALL);
+        assert_eq!(run_profile.depth(&matches), 3);
+    }
+}
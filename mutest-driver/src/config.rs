@@ -1,12 +1,23 @@
 use std::path::PathBuf;
 
-use mutest_emit::codegen::mutation::{Operators, UnsafeTargeting};
+use mutest_emit::analysis::call_graph::UnsafeTaintPolicy;
+use mutest_emit::codegen::integration_tests::IntegrationTest;
+use mutest_emit::codegen::mutation::{BatchLocality, MutationPriority, Operators, TargetCapPolicy, UnsafeTargeting};
 use rustc_interface::Config as CompilerConfig;
+pub use rustc_lint_defs::Level as LintLevel;
 
 #[derive(Clone, Copy)]
 pub enum GraphFormat {
     Simple,
     Graphviz,
+    Json,
+}
+
+#[derive(Clone, Copy)]
+pub enum TimingsFormat {
+    Human,
+    /// Breakdown of the duration and peak memory usage of each phase, see [`crate::timings::TimingsReport`].
+    Json,
 }
 
 #[derive(Clone, Copy)]
@@ -15,6 +26,20 @@ pub enum CallGraphNonLocalCallView {
     Expand,
 }
 
+#[derive(Clone, Copy)]
+pub enum TargetsFormat {
+    Human,
+    Json,
+}
+
+pub struct TargetsOptions {
+    /// Additionally list the functions which are not targeted for mutation, with the reason they were skipped.
+    pub explain_skipped: bool,
+    pub format: TargetsFormat,
+    /// List the tests reaching each target in the JSON output. The human-readable output always lists them.
+    pub with_tests: bool,
+}
+
 pub struct CallGraphOptions {
     pub format: GraphFormat,
     pub non_local_call_view: CallGraphNonLocalCallView,
@@ -26,14 +51,52 @@ pub struct ConflictGraphOptions {
     pub format: GraphFormat,
 }
 
+pub struct OperatorsOptions {
+    /// Names of the mutation operators applied to the code, in print order.
+    pub op_names: Vec<String>,
+}
+
+pub struct CodeOptions {
+    /// Directory to write the generated code to, instead of printing it.
+    pub emit_dir: Option<PathBuf>,
+    /// Write the code of each mutant into a separate file alongside the code of the complete meta-mutant.
+    pub split_per_mutant: bool,
+}
+
 pub struct PrintOptions {
     pub print_headers: bool,
     pub tests: Option<()>,
-    pub mutation_targets: Option<()>,
+    pub mutation_targets: Option<TargetsOptions>,
     pub call_graph: Option<CallGraphOptions>,
     pub conflict_graph: Option<ConflictGraphOptions>,
+    pub conflict_graph_stats: Option<()>,
+    pub operators: Option<OperatorsOptions>,
     pub mutants: Option<()>,
-    pub code: Option<()>,
+    pub patches: Option<()>,
+    pub code: Option<CodeOptions>,
+    /// Definition path of a function to explain the reachability of from tests.
+    pub explain_target: Option<String>,
+    /// ID of a mutation to explain.
+    pub explain_mutation: Option<u32>,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        Self {
+            print_headers: false,
+            tests: None,
+            mutation_targets: None,
+            call_graph: None,
+            conflict_graph: None,
+            conflict_graph_stats: None,
+            operators: None,
+            mutants: None,
+            patches: None,
+            code: None,
+            explain_target: None,
+            explain_mutation: None,
+        }
+    }
 }
 
 impl PrintOptions {
@@ -43,17 +106,55 @@ impl PrintOptions {
             && self.mutation_targets.is_none()
             && self.call_graph.is_none()
             && self.conflict_graph.is_none()
+            && self.conflict_graph_stats.is_none()
+            && self.operators.is_none()
             && self.mutants.is_none()
+            && self.patches.is_none()
             && self.code.is_none()
+            && self.explain_target.is_none()
+            && self.explain_mutation.is_none()
     }
 }
 
 pub enum Mode {
     Print,
     Build,
+    /// Type-check each mutation separately instead of building the test harness, reporting ill-typed mutations.
+    SelfCheck,
+    /// Only run the analysis pass, collecting the descriptors of the mutations, see [`crate::test_utils`].
+    Collect,
 }
 
 pub use mutest_emit::codegen::mutation::GreedyMutationBatchingOrderingHeuristic;
+pub use mutest_emit::codegen::substitution::SubstDispatch;
+pub use mutest_runtime::test_filter::TestFilter;
+
+/// Filter of the functions which are targeted for mutation, by their definition paths.
+///
+/// Patterns either match a definition path exactly, or end in `::*`, in which case they match all items nested under
+/// the path (e.g. `parser::lexer::*` matches both `parser::lexer::next_token` and `parser::lexer::Lexer::new`).
+/// Note that methods of trait impls have definition paths of the form `<Type as Trait>::method`.
+#[derive(Default)]
+pub struct TargetFilter {
+    /// If non-empty, only functions matching any of these patterns are mutated.
+    pub mutate_only: Vec<String>,
+    /// Functions matching any of these patterns are not mutated.
+    pub skip: Vec<String>,
+}
+
+fn def_path_matches_pattern(def_path: &str, pattern: &str) -> bool {
+    match pattern.strip_suffix("::*") {
+        Some(prefix) => def_path.strip_prefix(prefix).is_some_and(|rest| rest.starts_with("::")),
+        None => pattern == "*" || def_path == pattern,
+    }
+}
+
+impl TargetFilter {
+    pub fn matches(&self, def_path: &str) -> bool {
+        (self.mutate_only.is_empty() || self.mutate_only.iter().any(|pattern| def_path_matches_pattern(def_path, pattern)))
+            && !self.skip.iter().any(|pattern| def_path_matches_pattern(def_path, pattern))
+    }
+}
 
 pub enum MutationBatchingAlgorithm {
     None,
@@ -79,6 +180,7 @@ impl MutationBatchingRandomness {
     }
 }
 
+#[derive(Default)]
 pub struct VerifyOptions {
     pub ast_lowering: bool,
 }
@@ -86,18 +188,52 @@ pub struct VerifyOptions {
 pub struct Options<'op, 'm> {
     pub mode: Mode,
     pub verbosity: u8,
-    pub report_timings: bool,
+    pub log_file: Option<PathBuf>,
+    pub report_timings: Option<TimingsFormat>,
     pub print_opts: PrintOptions,
     pub unsafe_targeting: UnsafeTargeting,
+    pub unsafe_taint_policy: UnsafeTaintPolicy,
     pub operators: Operators<'op, 'm>,
+    pub min_mutation_priority: MutationPriority,
+    pub include_doctests: bool,
+    pub integration_tests: Vec<IntegrationTest>,
+    /// Filter of the tests which are used as entry points of the analysis. Mutations are only generated in functions
+    /// reachable from the included tests.
+    pub test_filter: TestFilter,
+    pub target_filter: TargetFilter,
     pub call_graph_depth: Option<usize>,
     pub mutation_depth: usize,
+    pub mutate_const_fns: bool,
+    pub mutate_macro_expansions: bool,
+    pub max_mutations_per_target: Option<usize>,
+    /// Policy by which the mutations of targets with more than `max_mutations_per_target` mutations are kept.
+    pub target_cap_policy: TargetCapPolicy,
+    pub mutations_summary_dir: Option<PathBuf>,
     pub mutation_batching_algorithm: MutationBatchingAlgorithm,
     pub mutation_batching_randomness: MutationBatchingRandomness,
     pub mutant_max_mutations_count: usize,
+    /// Locality by which mutations are preferably batched into the same mutant.
+    pub batch_locality: BatchLocality,
+    /// Maximum estimated cost of the tests reaching the mutations of a mutant. The cost of each test is its profiled
+    /// execution time in seconds if test timings are available, otherwise each test has a cost of 1.
+    pub mutant_max_cost: Option<f64>,
+    /// Path of the test timings recorded by the test harness in a previous run.
+    pub test_timings_path: Option<PathBuf>,
+    pub report_mutation_batching: bool,
+    /// Directory of the mutant batching of previous runs, which is reused for unchanged mutations.
+    pub batching_state_dir: Option<PathBuf>,
+    pub subst_dispatch: SubstDispatch,
+    /// Maximum level of lints in the compilation of the generated test harness. No cap is applied if `None`.
+    pub lint_cap: Option<LintLevel>,
+    /// Lints allowed in the compilation of the generated test harness, in addition to those allowed by the crate.
+    pub allowed_lints: Vec<String>,
+    /// Directory of cached builds of the generated test harness, reused when compiling identical code.
+    pub build_cache_dir: Option<PathBuf>,
 
     pub verify_opts: VerifyOptions,
     pub sanitize_macro_expns: bool,
+    /// Revert the expansion of non-local macros in the generated code, if macro expansions are not sanitized.
+    pub revert_macros: bool,
 }
 
 pub struct Config<'op, 'm> {
@@ -106,3 +242,286 @@ pub struct Config<'op, 'm> {
     pub mutest_search_path: PathBuf,
     pub opts: Options<'op, 'm>,
 }
+
+/// Builder for a [`Config`], used to drive the analysis and compilation passes programmatically.
+///
+/// All options default to the defaults of the command-line interface, which is itself implemented using this builder.
+/// The resulting configuration is independent of the environment variables used by the rustc wrapper
+/// (`CARGO_PRIMARY_PACKAGE`, `MUTEST_ARGS`, `MUTEST_SEARCH_PATH`), so it can be used by tools embedding mutest-rs:
+///
+/// ```ignore
+/// let compiler_config = mutest_driver::passes::parse_compiler_args(&rustc_args)?.expect("no compiler configuration was generated");
+///
+/// let config = ConfigBuilder::new(compiler_config, mutest_search_path, &operators)
+///     .mode(Mode::Build)
+///     .mutation_depth(2)
+///     .build();
+///
+/// mutest_driver::run(config)?;
+/// ```
+pub struct ConfigBuilder<'op, 'm> {
+    config: Config<'op, 'm>,
+}
+
+impl<'op, 'm> ConfigBuilder<'op, 'm> {
+    /// Create a builder for the crate described by the compiler configuration, mutated with the specified operators.
+    ///
+    /// The `mutest_search_path` must point to the build artifacts of the `mutest-runtime` crate,
+    /// which is linked into the generated test harness.
+    pub fn new(compiler_config: CompilerConfig, mutest_search_path: PathBuf, operators: Operators<'op, 'm>) -> Self {
+        Self {
+            config: Config {
+                compiler_config,
+                invocation_fingerprint: None,
+                mutest_search_path,
+                opts: Options {
+                    mode: Mode::Build,
+                    verbosity: 0,
+                    log_file: None,
+                    report_timings: None,
+                    print_opts: Default::default(),
+                    unsafe_targeting: UnsafeTargeting::None,
+                    unsafe_taint_policy: UnsafeTaintPolicy::Transitive,
+                    operators,
+                    min_mutation_priority: MutationPriority::Low,
+                    include_doctests: false,
+                    integration_tests: vec![],
+                    test_filter: Default::default(),
+                    target_filter: Default::default(),
+                    call_graph_depth: None,
+                    mutation_depth: 3,
+                    mutate_const_fns: false,
+                    mutate_macro_expansions: false,
+                    max_mutations_per_target: None,
+                    target_cap_policy: TargetCapPolicy::Spaced,
+                    mutations_summary_dir: None,
+                    mutation_batching_algorithm: MutationBatchingAlgorithm::None,
+                    mutation_batching_randomness: MutationBatchingRandomness { seed: None },
+                    mutant_max_mutations_count: 1,
+                    batch_locality: BatchLocality::None,
+                    mutant_max_cost: None,
+                    test_timings_path: None,
+                    report_mutation_batching: false,
+                    batching_state_dir: None,
+                    subst_dispatch: SubstDispatch::Runtime,
+                    lint_cap: Some(LintLevel::Allow),
+                    allowed_lints: vec![],
+                    build_cache_dir: None,
+
+                    verify_opts: Default::default(),
+                    sanitize_macro_expns: true,
+                    revert_macros: true,
+                },
+            },
+        }
+    }
+
+    /// Value tracked by Cargo to invalidate previous builds when the invocation changes.
+    pub fn invocation_fingerprint(mut self, invocation_fingerprint: Option<String>) -> Self {
+        self.config.invocation_fingerprint = invocation_fingerprint;
+        self
+    }
+
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.config.opts.mode = mode;
+        self
+    }
+
+    pub fn verbosity(mut self, verbosity: u8) -> Self {
+        self.config.opts.verbosity = verbosity;
+        self
+    }
+
+    pub fn log_file(mut self, log_file: Option<PathBuf>) -> Self {
+        self.config.opts.log_file = log_file;
+        self
+    }
+
+    /// Report the duration (and, in the JSON format, the peak memory usage) of each phase once finished.
+    pub fn report_timings(mut self, report_timings: Option<TimingsFormat>) -> Self {
+        self.config.opts.report_timings = report_timings;
+        self
+    }
+
+    pub fn print_opts(mut self, print_opts: PrintOptions) -> Self {
+        self.config.opts.print_opts = print_opts;
+        self
+    }
+
+    pub fn unsafe_targeting(mut self, unsafe_targeting: UnsafeTargeting) -> Self {
+        self.config.opts.unsafe_targeting = unsafe_targeting;
+        self
+    }
+
+    /// How far the unsafety of call paths taints the functions called along them.
+    pub fn unsafe_taint_policy(mut self, unsafe_taint_policy: UnsafeTaintPolicy) -> Self {
+        self.config.opts.unsafe_taint_policy = unsafe_taint_policy;
+        self
+    }
+
+    /// Only generate mutations of at least the specified priority.
+    pub fn min_mutation_priority(mut self, min_mutation_priority: MutationPriority) -> Self {
+        self.config.opts.min_mutation_priority = min_mutation_priority;
+        self
+    }
+
+    /// Include documentation tests as entry points, by extracting them into regular tests of the crate.
+    pub fn include_doctests(mut self, include_doctests: bool) -> Self {
+        self.config.opts.include_doctests = include_doctests;
+        self
+    }
+
+    /// Integration tests to include as entry points, by injecting them into the unit tests of the library.
+    pub fn integration_tests(mut self, integration_tests: Vec<IntegrationTest>) -> Self {
+        self.config.opts.integration_tests = integration_tests;
+        self
+    }
+
+    /// Filter of the tests which are used as entry points of the analysis, by their names.
+    pub fn test_filter(mut self, test_filter: TestFilter) -> Self {
+        self.config.opts.test_filter = test_filter;
+        self
+    }
+
+    /// Filter of the functions which are targeted for mutation, by their definition paths.
+    pub fn target_filter(mut self, target_filter: TargetFilter) -> Self {
+        self.config.opts.target_filter = target_filter;
+        self
+    }
+
+    /// Depth of call graph analysis. Defaults to the mutation depth if unspecified.
+    pub fn call_graph_depth(mut self, call_graph_depth: Option<usize>) -> Self {
+        self.config.opts.call_graph_depth = call_graph_depth;
+        self
+    }
+
+    pub fn mutation_depth(mut self, mutation_depth: usize) -> Self {
+        self.config.opts.mutation_depth = mutation_depth;
+        self
+    }
+
+    /// Mutate `const fn`s which are never evaluated at compile-time, by removing their `const` qualifier.
+    pub fn mutate_const_fns(mut self, mutate_const_fns: bool) -> Self {
+        self.config.opts.mutate_const_fns = mutate_const_fns;
+        self
+    }
+
+    /// Mutate the code produced by the expansion of local bang macros, reporting the mutations at the macro invocation.
+    pub fn mutate_macro_expansions(mut self, mutate_macro_expansions: bool) -> Self {
+        self.config.opts.mutate_macro_expansions = mutate_macro_expansions;
+        self
+    }
+
+    /// Limit the number of mutations of each target, trimming the rest.
+    pub fn max_mutations_per_target(mut self, max_mutations_per_target: Option<usize>) -> Self {
+        self.config.opts.max_mutations_per_target = max_mutations_per_target;
+        self
+    }
+
+    /// Choose the mutations kept of targets with more mutations than the maximum number of mutations per target.
+    pub fn target_cap_policy(mut self, target_cap_policy: TargetCapPolicy) -> Self {
+        self.config.opts.target_cap_policy = target_cap_policy;
+        self
+    }
+
+    /// Write a summary of the generated mutations of each crate into the directory.
+    pub fn mutations_summary_dir(mut self, mutations_summary_dir: Option<PathBuf>) -> Self {
+        self.config.opts.mutations_summary_dir = mutations_summary_dir;
+        self
+    }
+
+    pub fn mutation_batching_algorithm(mut self, mutation_batching_algorithm: MutationBatchingAlgorithm) -> Self {
+        self.config.opts.mutation_batching_algorithm = mutation_batching_algorithm;
+        self
+    }
+
+    pub fn mutation_batching_randomness(mut self, mutation_batching_randomness: MutationBatchingRandomness) -> Self {
+        self.config.opts.mutation_batching_randomness = mutation_batching_randomness;
+        self
+    }
+
+    pub fn mutant_max_mutations_count(mut self, mutant_max_mutations_count: usize) -> Self {
+        self.config.opts.mutant_max_mutations_count = mutant_max_mutations_count;
+        self
+    }
+
+    pub fn batch_locality(mut self, batch_locality: BatchLocality) -> Self {
+        self.config.opts.batch_locality = batch_locality;
+        self
+    }
+
+    /// Maximum estimated cost of the tests reaching the mutations of a mutant, in addition to the maximum number of
+    /// mutations.
+    pub fn mutant_max_cost(mut self, mutant_max_cost: Option<f64>) -> Self {
+        self.config.opts.mutant_max_cost = mutant_max_cost;
+        self
+    }
+
+    /// Test timings recorded by the test harness in a previous run, used to estimate the cost of tests.
+    pub fn test_timings_path(mut self, test_timings_path: Option<PathBuf>) -> Self {
+        self.config.opts.test_timings_path = test_timings_path;
+        self
+    }
+
+    /// Compare all mutation batching algorithms on the mutations before batching them with the chosen algorithm.
+    pub fn report_mutation_batching(mut self, report_mutation_batching: bool) -> Self {
+        self.config.opts.report_mutation_batching = report_mutation_batching;
+        self
+    }
+
+    /// Reuse the mutant batching of the previous run, stored in the directory, for the mutations which have not changed,
+    /// preserving the ids of their mutants. Only the remaining mutations are batched again, and the resulting batching
+    /// is stored for the next run.
+    pub fn batching_state_dir(mut self, batching_state_dir: Option<PathBuf>) -> Self {
+        self.config.opts.batching_state_dir = batching_state_dir;
+        self
+    }
+
+    /// Mechanism by which the substitutions of mutants are enabled. With [`SubstDispatch::Cfg`], each mutant is built
+    /// into a separate executable, next to the test harness.
+    pub fn subst_dispatch(mut self, subst_dispatch: SubstDispatch) -> Self {
+        self.config.opts.subst_dispatch = subst_dispatch;
+        self
+    }
+
+    /// Maximum level of lints in the compilation of the generated test harness. By default, all lints are allowed.
+    /// The generated code itself does not trigger lints, so a higher cap only reports the lints of the crate.
+    pub fn lint_cap(mut self, lint_cap: Option<LintLevel>) -> Self {
+        self.config.opts.lint_cap = lint_cap;
+        self
+    }
+
+    /// Allow the given lints in the compilation of the generated test harness, like `rustc -A`.
+    pub fn allowed_lints(mut self, allowed_lints: Vec<String>) -> Self {
+        self.config.opts.allowed_lints = allowed_lints;
+        self
+    }
+
+    /// Cache the compiled test harness (and compile-time mutants) in the directory, keyed by the generated code and the
+    /// compiler configuration, so that compiling identical code again only copies the cached artifacts.
+    pub fn build_cache_dir(mut self, build_cache_dir: Option<PathBuf>) -> Self {
+        self.config.opts.build_cache_dir = build_cache_dir;
+        self
+    }
+
+    pub fn verify_opts(mut self, verify_opts: VerifyOptions) -> Self {
+        self.config.opts.verify_opts = verify_opts;
+        self
+    }
+
+    pub fn sanitize_macro_expns(mut self, sanitize_macro_expns: bool) -> Self {
+        self.config.opts.sanitize_macro_expns = sanitize_macro_expns;
+        self
+    }
+
+    /// Revert the expansion of non-local macros in the generated code to their original invocations. This only
+    /// applies if macro expansions are not sanitized (see [`Self::sanitize_macro_expns`]).
+    pub fn revert_macros(mut self, revert_macros: bool) -> Self {
+        self.config.opts.revert_macros = revert_macros;
+        self
+    }
+
+    pub fn build(self) -> Config<'op, 'm> {
+        self.config
+    }
+}
@@ -23,53 +23,214 @@ extern crate smallvec;
 
 pub mod config;
 pub mod passes;
+pub mod test_utils;
+pub mod timings;
 
-use std::time::Instant;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::time::{Duration, Instant};
 
+use mutest_runtime::log;
 use rustc_interface::interface::Result as CompilerResult;
+use rustc_span::fatal_error::FatalError;
 
-use crate::config::Config;
+use crate::config::{Config, TimingsFormat};
+use crate::timings::TimingsReport;
 
+/// Fetch the sysroot, looking from most to least specific to this invocation:
+/// * runtime environment
+///   * `SYSROOT`
+///   * `RUSTUP_HOME` and `RUSTUP_TOOLCHAIN`
+///   * `MULTIRUST_HOME` and MULTIRUST_TOOLCHAIN
+/// * sysroot from rustc in the path
+/// * compile-time environment
+///   * `SYSROOT`
+///   * `RUSTUP_HOME` and `RUSTUP_TOOLCHAIN`
+///   * `MULTIRUST_HOME` and `MULTIRUST_TOOLCHAIN`
+pub fn fetch_sysroot() -> Option<PathBuf> {
+    fn toolchain_path(home: Option<String>, toolchain: Option<String>) -> Option<PathBuf> {
+        match (home, toolchain) {
+            (Some(home), Some(toolchain)) => {
+                let mut path = PathBuf::from(home);
+                path.push("toolchains");
+                path.push(toolchain);
+                Some(path)
+            }
+            _ => None,
+        }
+    }
+
+    env::var("SYSROOT").ok().map(PathBuf::from)
+        .or_else(|| toolchain_path(env::var("RUSTUP_HOME").ok(), env::var("RUSTUP_TOOLCHAIN").ok()))
+        .or_else(|| toolchain_path(env::var("MULTIRUST_HOME").ok(), env::var("MULTIRUST_TOOLCHAIN").ok()))
+        .or_else(|| {
+            Command::new("rustc")
+                .args(&["--print", "sysroot"])
+                .output().ok()
+                .and_then(|out| String::from_utf8(out.stdout).ok())
+                .map(|s| PathBuf::from(s.trim()))
+        })
+        .or_else(|| option_env!("SYSROOT").map(PathBuf::from))
+        .or_else(|| toolchain_path(option_env!("RUSTUP_HOME").map(ToOwned::to_owned), option_env!("RUSTUP_TOOLCHAIN").map(ToOwned::to_owned)))
+        .or_else(|| toolchain_path(option_env!("MULTIRUST_HOME").map(ToOwned::to_owned), option_env!("MULTIRUST_TOOLCHAIN").map(ToOwned::to_owned)))
+}
+
+fn emit_code(emit_dir: &Path, analysis_pass: &passes::analysis::AnalysisPassResult) -> usize {
+    fs::create_dir_all(emit_dir).expect(&format!("cannot create directory `{}`", emit_dir.display()));
+
+    let write_code = |file_name: &str, code: &str| {
+        let path = emit_dir.join(file_name);
+        fs::write(&path, code).expect(&format!("cannot write `{}`", path.display()));
+    };
+
+    write_code("harness.rs", &analysis_pass.generated_crate_code);
+    for (mutant_id, mutant_crate_code) in &analysis_pass.generated_mutant_crate_codes {
+        write_code(&format!("mutant_{}.rs", mutant_id.index()), mutant_crate_code);
+    }
+
+    1 + analysis_pass.generated_mutant_crate_codes.len()
+}
+
+/// Breakdown of the phases of the analysis pass, followed by the pass run after it, if any.
+fn timings_report(analysis_pass: &passes::analysis::AnalysisPassResult, next_pass: Option<(&'static str, Duration, Option<u64>)>) -> TimingsReport {
+    let peak_rss = &analysis_pass.peak_rss;
+
+    let mut report = TimingsReport::default();
+    report.phase("analysis", analysis_pass.duration, [
+        peak_rss.rustc_analysis,
+        peak_rss.target_analysis,
+        peak_rss.sanitize_macro_expns,
+        peak_rss.mutation_analysis,
+        peak_rss.mutation_batching,
+        peak_rss.codegen,
+    ].into_iter().flatten().max());
+    report.phase("analysis.rustc", analysis_pass.rustc_analysis_duration, peak_rss.rustc_analysis);
+    report.phase("analysis.targets", analysis_pass.target_analysis_duration, peak_rss.target_analysis);
+    report.phase("analysis.hygiene", analysis_pass.sanitize_macro_expns_duration, peak_rss.sanitize_macro_expns);
+    report.phase("analysis.mutations", analysis_pass.mutation_analysis_duration, peak_rss.mutation_analysis);
+    report.phase("analysis.batching", analysis_pass.mutation_batching_duration, peak_rss.mutation_batching);
+    report.phase("analysis.codegen", analysis_pass.codegen_duration, peak_rss.codegen);
+    if let Some((name, duration, peak_rss)) = next_pass {
+        report.phase(name, duration, peak_rss);
+    }
+
+    report
+}
+
+fn print_timings_json(total: Duration, report: &TimingsReport) {
+    println!("{}", serde_json::to_string_pretty(&report.to_json(total)).expect("cannot serialize timings"));
+}
+
+/// Run the analysis pass and, unless only printing was requested, the compilation pass of the generated test harness.
+///
+/// See [`config::ConfigBuilder`] for constructing the configuration without going through the rustc wrapper.
 pub fn run(mut config: Config) -> CompilerResult<()> {
+    if let Err(e) = log::init(config.opts.verbosity, config.opts.log_file.as_deref()) {
+        let log_file = config.opts.log_file.as_deref().expect("log file error without log file");
+        log::error!("cannot open log file `{}`: {e}", log_file.display());
+        process::exit(rustc_driver::EXIT_FAILURE);
+    }
+
     let t_start = Instant::now();
 
     let Some(analysis_pass) = passes::analysis::run(&mut config)? else { return Ok(()) };
+    if let config::Mode::Collect = config.opts.mode { return Ok(()); }
 
-    if let Some(_) = config.opts.print_opts.code.take() {
+    if let Some(code_opts) = config.opts.print_opts.code.take() {
         if config.opts.print_opts.print_headers { println!("\n@@@ code @@@\n"); }
-        println!("{}", analysis_pass.generated_crate_code);
+        match &code_opts.emit_dir {
+            Some(emit_dir) => {
+                let emitted_files_count = emit_code(emit_dir, &analysis_pass);
+                log::info!("wrote {emitted_files_count} files to `{}`", emit_dir.display());
+            }
+            None => println!("{}", analysis_pass.generated_crate_code),
+        }
         if config.opts.print_opts.print_headers { println!(); }
         if let config::Mode::Print = config.opts.mode && config.opts.print_opts.is_empty() {
-            if config.opts.report_timings {
-                println!("finished in {total:.2?} (targets {targets:.2?}; mutations {mutations:.2?}; batching {batching:.2?}; codegen {codegen:.2?})",
-                    total = analysis_pass.duration,
-                    targets = analysis_pass.target_analysis_duration,
-                    mutations = analysis_pass.mutation_analysis_duration,
-                    batching = analysis_pass.mutation_batching_duration,
-                    codegen = analysis_pass.codegen_duration,
-                );
+            match config.opts.report_timings {
+                Some(TimingsFormat::Human) => {
+                    log::info!("finished in {total:.2?} (targets {targets:.2?}; mutations {mutations:.2?}; batching {batching:.2?}; codegen {codegen:.2?})",
+                        total = analysis_pass.duration,
+                        targets = analysis_pass.target_analysis_duration,
+                        mutations = analysis_pass.mutation_analysis_duration,
+                        batching = analysis_pass.mutation_batching_duration,
+                        codegen = analysis_pass.codegen_duration,
+                    );
+                }
+                Some(TimingsFormat::Json) => print_timings_json(analysis_pass.duration, &timings_report(&analysis_pass, None)),
+                None => {}
             }
             return Ok(());
         }
     }
 
-    let compilation_pass = passes::compilation::run(&config, &analysis_pass)?;
+    if let config::Mode::SelfCheck = config.opts.mode {
+        let self_check_pass = passes::self_check::run(&config, &analysis_pass);
 
-    if config.opts.report_timings {
-        println!("finished in {total:.2?}",
-            total = t_start.elapsed(),
-        );
-        println!("analysis took {analysis:.2?} (targets {targets:.2?}; mutations {mutations:.2?}; batching {batching:.2?}; hygiene {hygiene:.2?}; codegen {codegen:.2?})",
-            analysis = analysis_pass.duration,
-            targets = analysis_pass.target_analysis_duration,
-            mutations = analysis_pass.mutation_analysis_duration,
-            batching = analysis_pass.mutation_batching_duration,
-            hygiene = analysis_pass.sanitize_macro_expns_duration,
-            codegen = analysis_pass.codegen_duration,
-        );
-        println!("compilation took {compilation:.2?}",
-            compilation = compilation_pass.duration,
+        for ill_typed_mutation in &self_check_pass.ill_typed_mutations {
+            println!("ill-typed mutation {id}: {descr}",
+                id = ill_typed_mutation.mutation_id.index(),
+                descr = ill_typed_mutation.descr,
+            );
+        }
+        if !self_check_pass.ill_typed_mutations.is_empty() { println!(); }
+
+        println!("self-check: {checked} mutations checked; {ill_typed} ill-typed",
+            checked = self_check_pass.checked_mutations_count,
+            ill_typed = self_check_pass.ill_typed_mutations.len(),
         );
+
+        match config.opts.report_timings {
+            Some(TimingsFormat::Human) => {
+                log::info!("finished in {total:.2?}",
+                    total = t_start.elapsed(),
+                );
+                log::info!("analysis took {analysis:.2?}; self-check took {self_check:.2?}",
+                    analysis = analysis_pass.duration,
+                    self_check = self_check_pass.duration,
+                );
+            }
+            Some(TimingsFormat::Json) => {
+                let report = timings_report(&analysis_pass, Some(("self-check", self_check_pass.duration, self_check_pass.peak_rss)));
+                print_timings_json(t_start.elapsed(), &report);
+            }
+            None => {}
+        }
+
+        if !self_check_pass.ill_typed_mutations.is_empty() {
+            log::error!("found {} ill-typed mutations", self_check_pass.ill_typed_mutations.len());
+            FatalError.raise();
+        }
+
+        return Ok(());
+    }
+
+    let compilation_pass = passes::compilation::run(&config, &analysis_pass)?;
+
+    match config.opts.report_timings {
+        Some(TimingsFormat::Human) => {
+            log::info!("finished in {total:.2?}",
+                total = t_start.elapsed(),
+            );
+            log::info!("analysis took {analysis:.2?} (targets {targets:.2?}; mutations {mutations:.2?}; batching {batching:.2?}; hygiene {hygiene:.2?}; codegen {codegen:.2?})",
+                analysis = analysis_pass.duration,
+                targets = analysis_pass.target_analysis_duration,
+                mutations = analysis_pass.mutation_analysis_duration,
+                batching = analysis_pass.mutation_batching_duration,
+                hygiene = analysis_pass.sanitize_macro_expns_duration,
+                codegen = analysis_pass.codegen_duration,
+            );
+            log::info!("compilation took {compilation:.2?}",
+                compilation = compilation_pass.duration,
+            );
+        }
+        Some(TimingsFormat::Json) => {
+            let report = timings_report(&analysis_pass, Some(("compilation", compilation_pass.duration, compilation_pass.peak_rss)));
+            print_timings_json(t_start.elapsed(), &report);
+        }
+        None => {}
     }
 
     Ok(())
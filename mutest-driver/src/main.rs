@@ -9,12 +9,15 @@ extern crate rustc_session;
 extern crate rustc_span;
 
 use std::env;
+use std::fs;
 use std::path::{Path, PathBuf};
-use std::process::{self, Command};
+use std::process;
 
-use mutest_driver::config::{self, Config};
+use mutest_driver::config::{self, ConfigBuilder};
+use mutest_emit::analysis::call_graph::UnsafeTaintPolicy;
 use mutest_emit::analysis::hir::Unsafety;
-use mutest_emit::codegen::mutation::{Operators, UnsafeTargeting};
+use mutest_emit::codegen::integration_tests::IntegrationTest;
+use mutest_emit::codegen::mutation::{BatchLocality, MutationPriority, Operators, TargetCapPolicy, UnsafeTargeting};
 use rustc_hash::FxHashSet;
 use rustc_interface::Config as CompilerConfig;
 use rustc_session::EarlyDiagCtxt;
@@ -37,42 +40,36 @@ impl rustc_driver::Callbacks for RustcCallbacks {
     }
 }
 
-/// Fetch the sysroot, looking from most to least specific to this invocation:
-/// * runtime environment
-///   * `SYSROOT`
-///   * `RUSTUP_HOME` and `RUSTUP_TOOLCHAIN`
-///   * `MULTIRUST_HOME` and MULTIRUST_TOOLCHAIN
-/// * sysroot from rustc in the path
-/// * compile-time environment
-///   * `SYSROOT`
-///   * `RUSTUP_HOME` and `RUSTUP_TOOLCHAIN`
-///   * `MULTIRUST_HOME` and `MULTIRUST_TOOLCHAIN`
-fn fetch_sysroot() -> Option<PathBuf> {
-    fn toolchain_path(home: Option<String>, toolchain: Option<String>) -> Option<PathBuf> {
-        match (home, toolchain) {
-            (Some(home), Some(toolchain)) => {
-                let mut path = PathBuf::from(home);
-                path.push("toolchains");
-                path.push(toolchain);
-                Some(path)
-            }
-            _ => None,
-        }
-    }
-
-    env::var("SYSROOT").ok().map(PathBuf::from)
-        .or_else(|| toolchain_path(env::var("RUSTUP_HOME").ok(), env::var("RUSTUP_TOOLCHAIN").ok()))
-        .or_else(|| toolchain_path(env::var("MULTIRUST_HOME").ok(), env::var("MULTIRUST_TOOLCHAIN").ok()))
-        .or_else(|| {
-            Command::new("rustc")
-                .args(&["--print", "sysroot"])
-                .output().ok()
-                .and_then(|out| String::from_utf8(out.stdout).ok())
-                .map(|s| PathBuf::from(s.trim()))
+/// Discover the integration tests of the package being compiled, following Cargo's target auto-discovery conventions
+/// (i.e. `tests/*.rs` and `tests/*/main.rs`).
+///
+/// Integration tests are only discovered when compiling a library, since only libraries can be used by integration
+/// tests.
+fn discover_integration_tests() -> Vec<IntegrationTest> {
+    // Cargo sets `CARGO_BIN_NAME` when compiling binaries, and `CARGO_TARGET_TMPDIR` when compiling integration tests
+    // and benchmarks.
+    if env::var_os("CARGO_BIN_NAME").is_some() || env::var_os("CARGO_TARGET_TMPDIR").is_some() { return vec![]; }
+
+    let Some(manifest_dir) = env::var_os("CARGO_MANIFEST_DIR") else { return vec![]; };
+    let Ok(entries) = fs::read_dir(Path::new(&manifest_dir).join("tests")) else { return vec![]; };
+
+    let mut integration_tests = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let (name, path) = match () {
+                _ if path.is_file() && path.extension() == Some("rs".as_ref()) => (path.file_stem()?.to_str()?.to_owned(), path),
+                _ if path.is_dir() && path.join("main.rs").is_file() => (path.file_name()?.to_str()?.to_owned(), path.join("main.rs")),
+                _ => return None,
+            };
+            Some(IntegrationTest { name: name.replace('-', "_"), path })
         })
-        .or_else(|| option_env!("SYSROOT").map(PathBuf::from))
-        .or_else(|| toolchain_path(option_env!("RUSTUP_HOME").map(ToOwned::to_owned), option_env!("RUSTUP_TOOLCHAIN").map(ToOwned::to_owned)))
-        .or_else(|| toolchain_path(option_env!("MULTIRUST_HOME").map(ToOwned::to_owned), option_env!("MULTIRUST_TOOLCHAIN").map(ToOwned::to_owned)))
+        .collect::<Vec<_>>();
+
+    // Ensure that integration tests are injected in a deterministic order, otherwise mutation IDs will not match between
+    // repeated invocations.
+    integration_tests.sort_unstable_by(|a, b| Ord::cmp(&a.name, &b.name));
+
+    integration_tests
 }
 
 pub fn main() {
@@ -84,7 +81,7 @@ pub fn main() {
     let sysroot_arg = args.iter().find(|arg| arg.starts_with("--sysroot="))
         .or_else(|| args.iter().position(|arg| arg == "--sysroot").and_then(|i| args.get(i + 1)));
     let sysroot = sysroot_arg.map(PathBuf::from)
-        .or_else(|| fetch_sysroot())
+        .or_else(|| mutest_driver::fetch_sysroot())
         .map(|path| path.to_string_lossy().to_string())
         .expect("specify --sysroot argument or SYSROOT environment variable, or use rustup or multirust");
 
@@ -101,14 +98,24 @@ pub fn main() {
         }));
     }
 
-    let primary_package = env::var("CARGO_PRIMARY_PACKAGE").is_ok();
-    let test_target = args.iter().any(|arg| arg.starts_with("--test"));
-    let normal_rustc = args.iter().any(|arg| arg.starts_with("--print"));
-
     let mutest_args = (!rustc_wrapper)
         .then_some(args.iter().skip(1).map(ToOwned::to_owned).collect::<Vec<_>>().join(" "))
         .or_else(|| env::var("MUTEST_ARGS").ok());
 
+    let primary_package = env::var("CARGO_PRIMARY_PACKAGE").is_ok();
+    let mut test_target = args.iter().any(|arg| arg.starts_with("--test"));
+    let normal_rustc = args.iter().any(|arg| arg.starts_with("--print"));
+
+    // Test targets with custom test harnesses (i.e. `harness = false`) are compiled by Cargo without `--test`.
+    // If requested, we compile such targets in test-mode instead, replacing their custom test harness with our own, and
+    // using the functions marked with `#[mutest::test]` as tests.
+    // NOTE: Cargo sets `CARGO_TARGET_TMPDIR` only when compiling integration tests and benchmarks.
+    let custom_test_harnesses = mutest_args.as_deref().is_some_and(|mutest_args| mutest_args.split(" ").any(|arg| arg == "--custom-test-harnesses"));
+    if !normal_rustc && primary_package && !test_target && custom_test_harnesses && env::var_os("CARGO_TARGET_TMPDIR").is_some() {
+        args.push("--test".to_owned());
+        test_target = true;
+    }
+
     if normal_rustc || !primary_package || !test_target {
         process::exit(rustc_driver::catch_with_exit_code(|| {
             rustc_driver::RunCompiler::new(&args, &mut RustcCallbacks { mutest_args }).run()
@@ -129,28 +136,61 @@ pub fn main() {
             .expect("specify MUTEST_SEARCH_PATH environment variable");
 
         let mode = match mutest_arg_matches.subcommand() {
-            Some(("print", _)) => config::Mode::Print,
+            Some(("print" | "print-call-graph" | "print-ops", _)) => config::Mode::Print,
             Some(("build", _)) => config::Mode::Build,
+            Some(("self-check", _)) => config::Mode::SelfCheck,
             _ => unreachable!(),
         };
 
         let verbosity = mutest_arg_matches.get_count("verbose");
-        let report_timings = mutest_arg_matches.get_flag("timings");
+        let log_file = mutest_arg_matches.get_one::<PathBuf>("log-file").cloned();
+        let report_timings = {
+            use mutest_driver_cli::timings_format as opts;
+
+            match mutest_arg_matches.get_one::<String>("timings").map(String::as_str) {
+                None => None,
+                Some(opts::HUMAN) => Some(config::TimingsFormat::Human),
+                Some(opts::JSON) => Some(config::TimingsFormat::Json),
+                _ => unreachable!(),
+            }
+        };
+
+        // Options which are not given explicitly default to the defaults of the run profile.
+        let run_profile = mutest_driver_cli::RunProfile::from_name(mutest_arg_matches.get_one::<String>("run-profile").unwrap());
+
+        let mutation_operator_names = {
+            let mut op_names = run_profile.mutation_operators(&mutest_arg_matches);
+            op_names.sort_unstable();
+            op_names.dedup();
+            op_names
+        };
 
         let print_opts = {
             use mutest_driver_cli::print as opts;
 
             let mut print_names = mutest_arg_matches.get_many::<String>("print").map(|print| print.map(String::as_str).collect::<FxHashSet<_>>()).unwrap_or_default();
             if print_names.contains("all") { print_names = FxHashSet::from_iter(opts::ALL.into_iter().map(|s| *s)); }
+            if let Some(("print-call-graph", _)) = mutest_arg_matches.subcommand() { print_names.insert(opts::CALL_GRAPH); }
+            if let Some(("print-ops", _)) = mutest_arg_matches.subcommand() { print_names.insert(opts::OPS); }
+            // Writing the generated code into a directory implies printing it.
+            if mutest_arg_matches.contains_id("emit-code-dir") { print_names.insert(opts::CODE); }
+
+            let explain_target = mutest_arg_matches.get_one::<String>("explain-target").cloned();
+            let explain_mutation = mutest_arg_matches.get_one::<u32>("explain").copied();
 
             let mut print_opts = config::PrintOptions {
-                print_headers: print_names.len() > 1,
+                print_headers: print_names.len() + explain_target.is_some() as usize + explain_mutation.is_some() as usize > 1,
                 tests: None,
                 mutation_targets: None,
                 call_graph: None,
                 conflict_graph: None,
+                conflict_graph_stats: None,
+                operators: None,
                 mutants: None,
+                patches: None,
                 code: None,
+                explain_target,
+                explain_mutation,
             };
 
             let graph_format = {
@@ -159,6 +199,7 @@ pub fn main() {
                 match mutest_arg_matches.get_one::<String>("graph-format").map(String::as_str) {
                     Some(opts::SIMPLE) => config::GraphFormat::Simple,
                     Some(opts::GRAPHVIZ) => config::GraphFormat::Graphviz,
+                    Some(opts::JSON) => config::GraphFormat::Json,
                     _ => unreachable!(),
                 }
             };
@@ -166,7 +207,19 @@ pub fn main() {
             for print_name in print_names {
                 match print_name {
                     opts::TESTS => print_opts.tests = Some(()),
-                    opts::TARGETS => print_opts.mutation_targets = Some(()),
+                    opts::TARGETS => {
+                        let explain_skipped = mutest_arg_matches.get_flag("explain-skipped");
+                        let format = {
+                            use mutest_driver_cli::targets_format as opts;
+                            match mutest_arg_matches.get_one::<String>("targets-format").map(String::as_str) {
+                                Some(opts::HUMAN) => config::TargetsFormat::Human,
+                                Some(opts::JSON) => config::TargetsFormat::Json,
+                                _ => unreachable!(),
+                            }
+                        };
+                        let with_tests = mutest_arg_matches.get_flag("with-tests");
+                        print_opts.mutation_targets = Some(config::TargetsOptions { explain_skipped, format, with_tests });
+                    }
                     opts::CALL_GRAPH => {
                         let non_local_call_view = {
                             use mutest_driver_cli::call_graph_non_local_call_view as opts;
@@ -183,8 +236,18 @@ pub fn main() {
                         let exclude_unsafe = mutest_arg_matches.get_flag("graph-exclude-unsafe");
                         print_opts.conflict_graph = Some(config::ConflictGraphOptions { compatibility_graph, exclude_unsafe, format: graph_format });
                     }
+                    opts::CONFLICT_GRAPH_STATS => print_opts.conflict_graph_stats = Some(()),
+                    opts::OPS => {
+                        let op_names = mutation_operator_names.iter().map(|&op_name| op_name.to_owned()).collect();
+                        print_opts.operators = Some(config::OperatorsOptions { op_names });
+                    }
                     opts::MUTANTS => print_opts.mutants = Some(()),
-                    opts::CODE => print_opts.code = Some(()),
+                    opts::PATCHES => print_opts.patches = Some(()),
+                    opts::CODE => {
+                        let emit_dir = mutest_arg_matches.get_one::<PathBuf>("emit-code-dir").cloned();
+                        let split_per_mutant = mutest_arg_matches.get_flag("emit-code-per-mutant");
+                        print_opts.code = Some(config::CodeOptions { emit_dir, split_per_mutant });
+                    }
                     _ => unreachable!("invalid print information name: `{print_name}`"),
                 }
             }
@@ -200,14 +263,22 @@ pub fn main() {
             _ => UnsafeTargeting::None,
         };
 
+        let unsafe_taint_policy = {
+            use mutest_driver_cli::unsafe_taint as opts;
+
+            match mutest_arg_matches.get_one::<String>("unsafe-taint").map(String::as_str) {
+                None | Some(opts::TRANSITIVE) => UnsafeTaintPolicy::Transitive,
+                Some(opts::LOCAL) => UnsafeTaintPolicy::Local,
+                Some(opts::DIRECT) => UnsafeTaintPolicy::Direct,
+                _ => unreachable!(),
+            }
+        };
+
         let mutation_operators = {
             use mutest_driver_cli::mutation_operators as opts;
 
-            let mut op_names = mutest_arg_matches.get_many::<String>("mutation-operators").unwrap().map(String::as_str).collect::<FxHashSet<_>>();
-            if op_names.contains("all") { op_names = FxHashSet::from_iter(opts::ALL.into_iter().map(|s| *s)); }
-
-            op_names.into_iter()
-                .map(|op_name| {
+            mutation_operator_names.iter()
+                .map(|&op_name| {
                     macro const_op_ref($m:expr) { { const OP: Operators<'_, '_> = &[&$m]; OP[0] } }
 
                     match op_name {
@@ -217,26 +288,47 @@ pub fn main() {
                         opts::BIT_OP_SHIFT_DIR_SWAP => const_op_ref!(mutest_operators::BitOpShiftDirSwap),
                         opts::BIT_OP_XOR_AND_SWAP => const_op_ref!(mutest_operators::BitOpXorAndSwap),
                         opts::BOOL_EXPR_NEGATE => const_op_ref!(mutest_operators::BoolExprNegate),
+                        opts::BOOL_RETURN_FLIP => const_op_ref!(mutest_operators::BoolReturnFlip),
+                        opts::CALL_ARG_DEFAULT_REPLACE => const_op_ref!(mutest_operators::CallArgDefaultReplace),
                         opts::CALL_DELETE => const_op_ref!(mutest_operators::CallDelete { limit_scope_to_local_callees: false }),
                         opts::CALL_VALUE_DEFAULT_SHADOW => const_op_ref!(mutest_operators::CallValueDefaultShadow { limit_scope_to_local_callees: false }),
+                        opts::COMBINATOR_NOOP => const_op_ref!(mutest_operators::CombinatorNoop),
                         opts::CONTINUE_BREAK_SWAP => const_op_ref!(mutest_operators::ContinueBreakSwap),
                         opts::EQ_OP_INVERT => const_op_ref!(mutest_operators::EqOpInvert),
                         opts::LOGICAL_OP_AND_OR_SWAP => const_op_ref!(mutest_operators::LogicalOpAndOrSwap),
+                        opts::MATCH_ARM_BODY_REPLACE => const_op_ref!(mutest_operators::MatchArmBodyReplace),
+                        opts::MATCH_ARM_REORDER => const_op_ref!(mutest_operators::MatchArmReorder),
                         opts::MATH_OP_ADD_MUL_SWAP => const_op_ref!(mutest_operators::OpAddMulSwap),
                         opts::MATH_OP_ADD_SUB_SWAP => const_op_ref!(mutest_operators::OpAddSubSwap),
                         opts::MATH_OP_DIV_REM_SWAP => const_op_ref!(mutest_operators::OpDivRemSwap),
                         opts::MATH_OP_MUL_DIV_SWAP => const_op_ref!(mutest_operators::OpMulDivSwap),
+                        opts::METHOD_CALL_SWAP => const_op_ref!(mutest_operators::MethodCallSwap { method_pairs: mutest_operators::METHOD_CALL_SWAP_DEFAULT_PAIRS }),
+                        opts::OPTION_RESULT_COMBINATOR_SWAP => const_op_ref!(mutest_operators::OptionResultCombinatorSwap),
                         opts::RANGE_LIMIT_SWAP => const_op_ref!(mutest_operators::RangeLimitSwap),
+                        opts::REFUTABLE_LET_INVERT => const_op_ref!(mutest_operators::RefutableLetInvert),
                         opts::RELATIONAL_OP_EQ_SWAP => const_op_ref!(mutest_operators::RelationalOpEqSwap),
                         opts::RELATIONAL_OP_INVERT => const_op_ref!(mutest_operators::RelationalOpInvert),
+                        opts::STRING_LIT_REPLACE => const_op_ref!(mutest_operators::StringLitReplace),
+                        opts::STRUCT_FIELD_REPLACE => const_op_ref!(mutest_operators::StructFieldReplace),
                         _ => unreachable!("invalid mutation operator name: `{op_name}`"),
                     }
                 })
                 .collect::<Vec<_>>()
         };
 
+        let min_mutation_priority = {
+            use mutest_driver_cli::mutation_priority as opts;
+
+            match run_profile.min_priority(&mutest_arg_matches) {
+                opts::HIGH => MutationPriority::High,
+                opts::MEDIUM => MutationPriority::Medium,
+                opts::LOW => MutationPriority::Low,
+                _ => unreachable!(),
+            }
+        };
+
         let mut call_graph_depth = mutest_arg_matches.get_one::<usize>("call-graph-depth").copied();
-        let mutation_depth = *mutest_arg_matches.get_one::<usize>("depth").unwrap();
+        let mutation_depth = run_profile.depth(&mutest_arg_matches);
 
         if let Some(call_graph_depth_value) = call_graph_depth && call_graph_depth_value < mutation_depth {
             let mut diagnostic = early_dcx.early_struct_warn("explicit call graph depth argument ignored as mutation depth exceeds it");
@@ -247,15 +339,50 @@ pub fn main() {
             call_graph_depth = None;
         }
 
+        let include_doctests = mutest_arg_matches.get_flag("doctests");
+
+        let integration_tests = match mutest_arg_matches.get_flag("integration-tests") {
+            true => discover_integration_tests(),
+            false => vec![],
+        };
+
+        let test_filter = config::TestFilter {
+            filters: mutest_arg_matches.get_many::<String>("test-filter").map(|filters| filters.cloned().collect()).unwrap_or_default(),
+            skip: mutest_arg_matches.get_many::<String>("skip-test").map(|skip| skip.cloned().collect()).unwrap_or_default(),
+        };
+
+        let target_filter = config::TargetFilter {
+            mutate_only: mutest_arg_matches.get_many::<String>("mutate-only").map(|patterns| patterns.cloned().collect()).unwrap_or_default(),
+            skip: mutest_arg_matches.get_many::<String>("skip-target").map(|patterns| patterns.cloned().collect()).unwrap_or_default(),
+        };
+
+        let mutate_const_fns = mutest_arg_matches.get_flag("mutate-const-fns");
+        let mutate_macro_expansions = mutest_arg_matches.get_flag("mutate-macro-expansions");
+        let max_mutations_per_target = run_profile.max_mutations_per_target(&mutest_arg_matches);
+        let target_cap_policy = {
+            use mutest_driver_cli::target_cap_policy as opts;
+
+            match mutest_arg_matches.get_one::<String>("target-cap-policy").map(String::as_str) {
+                None | Some(opts::SPACED) => TargetCapPolicy::Spaced,
+                Some(opts::DIVERSE) => TargetCapPolicy::Diverse,
+                Some(opts::FIRST) => TargetCapPolicy::First,
+                Some(opts::RANDOM) => TargetCapPolicy::Random,
+                _ => unreachable!(),
+            }
+        };
+        let mutations_summary_dir = mutest_arg_matches.get_one::<PathBuf>("mutations-summary-dir").cloned();
+        let batching_state_dir = mutest_arg_matches.get_one::<PathBuf>("batching-state-dir").cloned();
+        let build_cache_dir = mutest_arg_matches.get_one::<PathBuf>("build-cache-dir").cloned();
+
         let mutation_batching_algorithm = {
             use mutest_driver_cli::mutant_batch_algorithm as opts;
 
-            match mutest_arg_matches.get_one::<String>("mutant-batch-algorithm").map(String::as_str) {
-                None | Some(opts::NONE) => config::MutationBatchingAlgorithm::None,
+            match run_profile.mutant_batch_algorithm(&mutest_arg_matches) {
+                opts::NONE => config::MutationBatchingAlgorithm::None,
 
-                Some(opts::RANDOM) => config::MutationBatchingAlgorithm::Random,
+                opts::RANDOM => config::MutationBatchingAlgorithm::Random,
 
-                Some(opts::GREEDY) => {
+                opts::GREEDY => {
                     let ordering_heuristic = {
                         use mutest_driver_cli::mutant_batch_greedy_ordering_heuristic as opts;
 
@@ -264,6 +391,7 @@ pub fn main() {
                             Some(opts::RANDOM) => Some(config::GreedyMutationBatchingOrderingHeuristic::Random),
                             Some(opts::CONFLICTS) => Some(config::GreedyMutationBatchingOrderingHeuristic::ConflictsAsc),
                             Some(opts::REVERSE_CONFLICTS) => Some(config::GreedyMutationBatchingOrderingHeuristic::ConflictsDesc),
+                            Some(opts::TARGET_DIVERSITY) => Some(config::GreedyMutationBatchingOrderingHeuristic::TargetDiversity),
                             _ => unreachable!(),
                         }
                     };
@@ -273,12 +401,18 @@ pub fn main() {
                     config::MutationBatchingAlgorithm::Greedy { ordering_heuristic, epsilon }
                 }
 
-                Some(opts::SIMULATED_ANNEALING) => config::MutationBatchingAlgorithm::SimulatedAnnealing,
+                opts::SIMULATED_ANNEALING => config::MutationBatchingAlgorithm::SimulatedAnnealing,
 
                 _ => unreachable!(),
             }
         };
 
+        if let Some(config::CodeOptions { split_per_mutant: true, .. }) = print_opts.code
+            && !matches!(mutation_batching_algorithm, config::MutationBatchingAlgorithm::None)
+        {
+            early_dcx.early_fatal("splitting the generated code per mutant is only supported without mutation batching");
+        }
+
         let mutation_batching_randomness = {
             use rand_seeder::Seeder;
 
@@ -288,7 +422,36 @@ pub fn main() {
             config::MutationBatchingRandomness { seed }
         };
 
-        let mutant_max_mutations_count = *mutest_arg_matches.get_one::<usize>("mutant-batch-size").unwrap();
+        let batch_locality = {
+            use mutest_driver_cli::batch_locality as opts;
+
+            match mutest_arg_matches.get_one::<String>("batch-locality").map(String::as_str) {
+                None | Some(opts::NONE) => BatchLocality::None,
+                Some(opts::FILE) => BatchLocality::File,
+                Some(opts::MODULE) => BatchLocality::Module,
+                _ => unreachable!(),
+            }
+        };
+
+        let mutant_max_mutations_count = run_profile.mutant_batch_size(&mutest_arg_matches);
+        let mutant_max_cost = mutest_arg_matches.get_one::<f64>("mutant-batch-max-cost").copied();
+        let test_timings_path = mutest_arg_matches.get_one::<PathBuf>("test-timings-file").cloned();
+        let report_mutation_batching = mutest_arg_matches.get_flag("batching-report");
+
+        let subst_dispatch = match mutest_arg_matches.get_flag("cfg-mutants") {
+            true => config::SubstDispatch::Cfg,
+            false => config::SubstDispatch::Runtime,
+        };
+
+        let lint_cap = match mutest_arg_matches.get_one::<String>("cap-lints").map(String::as_str) {
+            Some("none") => None,
+            Some("allow") => Some(config::LintLevel::Allow),
+            Some("warn") => Some(config::LintLevel::Warn),
+            Some("deny") => Some(config::LintLevel::Deny),
+            Some("forbid") => Some(config::LintLevel::Forbid),
+            lint_cap => unreachable!("invalid lint cap: `{lint_cap:?}`"),
+        };
+        let allowed_lints = mutest_arg_matches.get_many::<String>("allow").map(|lints| lints.cloned().collect::<Vec<_>>()).unwrap_or_default();
 
         let verify_opts = {
             use mutest_driver_cli::verify as opts;
@@ -311,28 +474,45 @@ pub fn main() {
         };
 
         let sanitize_macro_expns = !mutest_arg_matches.get_flag("Zno-sanitize-macro-expns");
-
-        let config = Config {
-            compiler_config,
-            invocation_fingerprint: mutest_args,
-            mutest_search_path,
-            opts: config::Options {
-                mode,
-                verbosity,
-                report_timings,
-                print_opts,
-                unsafe_targeting,
-                operators: &mutation_operators,
-                call_graph_depth,
-                mutation_depth,
-                mutation_batching_algorithm,
-                mutation_batching_randomness,
-                mutant_max_mutations_count,
-
-                verify_opts,
-                sanitize_macro_expns,
-            },
-        };
+        let revert_macros = !mutest_arg_matches.get_flag("no-revert-macros");
+
+        let config = ConfigBuilder::new(compiler_config, mutest_search_path, &mutation_operators)
+            .invocation_fingerprint(mutest_args)
+            .mode(mode)
+            .verbosity(verbosity)
+            .log_file(log_file)
+            .report_timings(report_timings)
+            .print_opts(print_opts)
+            .unsafe_targeting(unsafe_targeting)
+            .unsafe_taint_policy(unsafe_taint_policy)
+            .min_mutation_priority(min_mutation_priority)
+            .include_doctests(include_doctests)
+            .integration_tests(integration_tests)
+            .test_filter(test_filter)
+            .target_filter(target_filter)
+            .call_graph_depth(call_graph_depth)
+            .mutation_depth(mutation_depth)
+            .mutate_const_fns(mutate_const_fns)
+            .mutate_macro_expansions(mutate_macro_expansions)
+            .max_mutations_per_target(max_mutations_per_target)
+            .target_cap_policy(target_cap_policy)
+            .mutations_summary_dir(mutations_summary_dir)
+            .mutation_batching_algorithm(mutation_batching_algorithm)
+            .mutation_batching_randomness(mutation_batching_randomness)
+            .mutant_max_mutations_count(mutant_max_mutations_count)
+            .batch_locality(batch_locality)
+            .mutant_max_cost(mutant_max_cost)
+            .test_timings_path(test_timings_path)
+            .report_mutation_batching(report_mutation_batching)
+            .batching_state_dir(batching_state_dir)
+            .subst_dispatch(subst_dispatch)
+            .lint_cap(lint_cap)
+            .allowed_lints(allowed_lints)
+            .build_cache_dir(build_cache_dir)
+            .verify_opts(verify_opts)
+            .sanitize_macro_expns(sanitize_macro_expns)
+            .revert_macros(revert_macros)
+            .build();
 
         mutest_driver::run(config)?;
         Ok(())
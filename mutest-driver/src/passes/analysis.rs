@@ -1,29 +1,62 @@
+use std::fs;
 use std::iter;
+use std::mem;
+use std::path::Path;
+use std::slice;
 use std::time::{Duration, Instant};
 
-use mutest_emit::analysis::call_graph::{CallGraph, Callee, Target, Unsafety};
+use mutest_emit::analysis::call_graph::{CallGraph, Callee, NonMutableFnReason, Target, UnsafeSource, Unsafety};
 use mutest_emit::analysis::hir;
 use mutest_emit::analysis::tests::Test;
-use mutest_emit::codegen::mutation::{Mut, MutId, Mutant, MutationConflictGraph, UnsafeTargeting};
+use mutest_emit::codegen::ast;
+use mutest_emit::codegen::mutation::{Mut, MutId, MutStableId, Mutant, MutantCapacity, MutantId, MutationConflictGraph, MutationLocalities, MutationRegions, TargetCapPolicy, TestCostModel, UnsafeTargeting};
+use mutest_emit::codegen::patch::NodeSpans;
+use mutest_emit::codegen::substitution::SubstDispatch;
+use mutest_runtime::log;
+use mutest_runtime::test_timings::TestTimings;
 use rustc_hash::{FxHashMap, FxHashSet};
 use rustc_interface::run_compiler;
 use rustc_interface::interface::Result as CompilerResult;
 use rustc_middle::ty::TyCtxt;
+use rustc_span::{DUMMY_SP, FileName, FileNameDisplayPreference};
 use rustc_span::edition::Edition;
+use rustc_span::hygiene::ExpnKind;
 use rustc_span::fatal_error::FatalError;
 use smallvec::{SmallVec, smallvec};
 
 use crate::config::{self, Config};
 use crate::passes::{Flow, base_compiler_config};
+use crate::timings;
+
+/// Peak resident set size of the process during each phase of the analysis pass, see [`timings::peak_rss`].
+#[derive(Clone, Copy, Default)]
+pub struct AnalysisPeakRss {
+    pub rustc_analysis: Option<u64>,
+    pub target_analysis: Option<u64>,
+    pub sanitize_macro_expns: Option<u64>,
+    pub mutation_analysis: Option<u64>,
+    pub mutation_batching: Option<u64>,
+    pub codegen: Option<u64>,
+}
 
 pub struct AnalysisPassResult {
     pub duration: Duration,
+    /// Duration of the parsing, expansion, and analysis of the crate by rustc, preceding the target analysis.
+    pub rustc_analysis_duration: Duration,
     pub target_analysis_duration: Duration,
     pub sanitize_macro_expns_duration: Duration,
     pub mutation_analysis_duration: Duration,
     pub mutation_batching_duration: Duration,
     pub codegen_duration: Duration,
+    pub peak_rss: AnalysisPeakRss,
     pub generated_crate_code: String,
+    pub generated_mutant_crate_codes: Vec<(MutantId, String)>,
+    /// Mutations of each mutant, which must be built separately from the generated crate code, with the cfg flags
+    /// of its mutations set. Only used with `SubstDispatch::Cfg`.
+    pub cfg_mutants: Vec<(MutantId, Vec<MutId>)>,
+    /// Stable ID and descriptor of each mutation, in ID order, used to report ill-typed mutations, and to compare the
+    /// mutations against golden lists. Only collected in `Mode::SelfCheck` and `Mode::Collect`.
+    pub mutation_descrs: Vec<(MutId, MutStableId, String)>,
 }
 
 fn print_tests(tests: &[Test]) {
@@ -37,9 +70,12 @@ fn print_tests(tests: &[Test]) {
     let tests_count = tests_in_print_order.len();
 
     for (test_path_str, test) in tests_in_print_order {
-        let mut marker = "";
+        let mut marker = String::new();
         if test.ignore {
-            marker = " [ignored]";
+            marker = match test.ignore_message {
+                Some(ignore_message) => format!(" [ignored, {ignore_message}]"),
+                None => " [ignored]".to_owned(),
+            };
             ignored_tests_count += 1;
         }
 
@@ -55,7 +91,7 @@ fn print_tests(tests: &[Test]) {
     );
 }
 
-fn print_targets<'tcx, 'trg>(tcx: TyCtxt<'tcx>, targets: impl Iterator<Item = &'trg Target<'trg>>, unsafe_targeting: UnsafeTargeting) {
+fn print_targets<'tcx, 'trg>(tcx: TyCtxt<'tcx>, targets: impl Iterator<Item = &'trg Target<'trg>>, unsafe_targeting: UnsafeTargeting, format: config::TargetsFormat, with_tests: bool) {
     let mut unsafe_targets_count = 0;
     let mut tainted_targets_count = 0;
 
@@ -67,27 +103,26 @@ fn print_targets<'tcx, 'trg>(tcx: TyCtxt<'tcx>, targets: impl Iterator<Item = &'
 
     let targets_count = targets_in_print_order.len();
 
+    let mut targets_json = vec![];
+
     for (target_span, target) in targets_in_print_order {
         let mut unsafe_marker = "";
-        match (target.unsafety.is_unsafe(unsafe_targeting), target.unsafety) {
+        let mut unsafety = "safe";
+        match (target.is_unsafe(unsafe_targeting), target.unsafety) {
             (true, Unsafety::Tainted(_)) => {
                 unsafe_targets_count += 1;
                 tainted_targets_count += 1;
                 unsafe_marker = "[tainted] ";
+                unsafety = "tainted";
             }
             (true, _) => {
                 unsafe_targets_count += 1;
                 unsafe_marker = "[unsafe] ";
+                unsafety = "unsafe";
             }
             (false, _) => {}
         };
 
-        println!("tests -({distance})-> {unsafe_marker}{def_path} at {span:#?}",
-            distance = target.distance,
-            def_path = tcx.def_path_str(target.def_id.to_def_id()),
-            span = target_span,
-        );
-
         // Entry points are printed in order of distance first, within that by lexical order of their definition path.
         let mut entry_points_in_print_order = target.reachable_from.iter()
             .map(|(&test, entry_point)| (test.path_str(), test, entry_point))
@@ -96,26 +131,160 @@ fn print_targets<'tcx, 'trg>(tcx: TyCtxt<'tcx>, targets: impl Iterator<Item = &'
             Ord::cmp(&entry_point_a.distance, &entry_point_b.distance).then(Ord::cmp(test_a_path_str, test_b_path_str))
         });
 
-        for (test_path_str, test, entry_point) in entry_points_in_print_order {
-            println!("  ({distance}) {tainted_marker}{test}",
-                distance = entry_point.distance,
-                tainted_marker = match target.is_tainted(test, unsafe_targeting) {
-                    true => "[tainted] ",
-                    false => "",
-                },
-                test = test_path_str,
+        match format {
+            config::TargetsFormat::Human => {
+                println!("{tests_count} {tests} -({distance})-> {unsafe_marker}{def_path} at {span:#?}",
+                    tests_count = entry_points_in_print_order.len(),
+                    tests = match entry_points_in_print_order.len() {
+                        1 => "test",
+                        _ => "tests",
+                    },
+                    distance = target.distance,
+                    def_path = tcx.def_path_str(target.def_id.to_def_id()),
+                    span = target_span,
+                );
+
+                if let Some(note) = target.note {
+                    println!("  note: {note}");
+                }
+
+                for (test_path_str, test, entry_point) in entry_points_in_print_order {
+                    println!("  ({distance}) {tainted_marker}{test}",
+                        distance = entry_point.distance,
+                        tainted_marker = match target.is_tainted(test, unsafe_targeting) {
+                            true => "[tainted] ",
+                            false => "",
+                        },
+                        test = test_path_str,
+                    );
+                }
+
+                println!();
+            }
+            config::TargetsFormat::Json => {
+                let mut target_json = serde_json::json!({
+                    "def_path": tcx.def_path_str(target.def_id.to_def_id()),
+                    "span": format!("{target_span:#?}"),
+                    "unsafety": unsafety,
+                    "distance": target.distance,
+                    "reaching_tests_count": entry_points_in_print_order.len(),
+                    "note": target.note.map(|note| note.to_string()),
+                });
+                if with_tests {
+                    target_json["tests"] = entry_points_in_print_order.iter()
+                        .map(|(test_path_str, test, entry_point)| serde_json::json!({
+                            "def_path": test_path_str,
+                            "distance": entry_point.distance,
+                            "tainted": target.is_tainted(test, unsafe_targeting),
+                        }))
+                        .collect::<Vec<_>>()
+                        .into();
+                }
+                targets_json.push(target_json);
+            }
+        }
+    }
+
+    match format {
+        config::TargetsFormat::Human => {
+            println!("targets: {total} total; {safe} safe; {unsafe} unsafe ({tainted} tainted)",
+                total = targets_count,
+                safe = targets_count - unsafe_targets_count,
+                r#unsafe = unsafe_targets_count,
+                tainted = tainted_targets_count,
             );
         }
+        config::TargetsFormat::Json => {
+            let targets_json = serde_json::json!({
+                "targets": targets_json,
+                "total": targets_count,
+                "safe": targets_count - unsafe_targets_count,
+                "unsafe": unsafe_targets_count,
+                "tainted": tainted_targets_count,
+            });
+            println!("{}", serde_json::to_string_pretty(&targets_json).expect("cannot serialize targets"));
+        }
+    }
+}
+
+/// Reason for a function of the crate not being targeted for mutation.
+enum SkipReason {
+    NonMutable(NonMutableFnReason),
+    Filtered,
+    Unreachable,
+    BeyondMutationDepth(usize),
+    Unsafe(Unsafety),
+}
+
+impl SkipReason {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::NonMutable(reason) => reason.code(),
+            Self::Filtered => "filtered",
+            Self::Unreachable => "unreachable",
+            Self::BeyondMutationDepth(_) => "beyond_depth",
+            Self::Unsafe(_) => "unsafe",
+        }
+    }
+}
+
+fn print_skipped_fns<'tcx, 'trg>(
+    tcx: TyCtxt<'tcx>,
+    fns_with_mutability: impl Iterator<Item = (hir::LocalDefId, Result<(), NonMutableFnReason>)>,
+    reachable_fns: &[Target<'trg>],
+    target_filter: &config::TargetFilter,
+    call_graph_depth: usize,
+    mutation_depth: usize,
+    unsafe_targeting: UnsafeTargeting,
+) {
+    let skipped_fns = fns_with_mutability
+        // Functions generated by the compiler (e.g. the `main` function of the test harness) are not listed.
+        .filter(|&(local_def_id, _)| !matches!(tcx.def_span(local_def_id).ctxt().outer_expn_data().kind, ExpnKind::AstPass(_)))
+        .filter_map(|(local_def_id, mutability)| {
+            if let Err(reason) = mutability { return Some((local_def_id, SkipReason::NonMutable(reason))); }
+            if !target_filter.matches(&tcx.def_path_str(local_def_id.to_def_id())) { return Some((local_def_id, SkipReason::Filtered)); }
+
+            let Some(target) = reachable_fns.iter().find(|target| target.def_id == local_def_id) else {
+                return Some((local_def_id, SkipReason::Unreachable));
+            };
+            if target.distance >= mutation_depth { return Some((local_def_id, SkipReason::BeyondMutationDepth(target.distance))); }
+            if target.is_excluded(unsafe_targeting) { return Some((local_def_id, SkipReason::Unsafe(target.unsafety))); }
 
+            None
+        });
+
+    // Skipped functions are printed in source span order.
+    let mut skipped_fns_in_print_order = skipped_fns
+        .map(|(local_def_id, reason)| (tcx.def_span(local_def_id), local_def_id, reason))
+        .collect::<Vec<_>>();
+    skipped_fns_in_print_order.sort_unstable_by_key(|(span, _, _)| *span);
+
+    let skipped_fns_count = skipped_fns_in_print_order.len();
+
+    for (span, local_def_id, reason) in skipped_fns_in_print_order {
+        println!("[{code}] {def_path} at {span:#?}",
+            code = reason.code(),
+            def_path = tcx.def_path_str(local_def_id.to_def_id()),
+        );
+        match reason {
+            SkipReason::NonMutable(reason) => println!("  {}", reason.descr()),
+            SkipReason::Filtered => println!("  excluded by the target filter (`--mutate-only`, `--skip-target`)"),
+            SkipReason::Unreachable => println!("  not reached from any test within call graph depth {call_graph_depth}"),
+            SkipReason::BeyondMutationDepth(distance) => println!("  reached from tests at distance {distance}, beyond mutation depth {mutation_depth}"),
+            SkipReason::Unsafe(unsafety) => println!("  {unsafety}, which is excluded by the unsafe targeting policy",
+                unsafety = match unsafety {
+                    Unsafety::None => "safe",
+                    Unsafety::Unsafe(UnsafeSource::Unsafe) => "unsafe",
+                    Unsafety::Unsafe(UnsafeSource::EnclosingUnsafe) => "contains unsafe block",
+                    Unsafety::Tainted(UnsafeSource::Unsafe) => "tainted (called from unsafe function or block)",
+                    Unsafety::Tainted(UnsafeSource::EnclosingUnsafe) => "tainted (called from function containing unsafe block)",
+                },
+            ),
+        }
         println!();
     }
 
-    println!("targets: {total} total; {safe} safe; {unsafe} unsafe ({tainted} tainted)",
-        total = targets_count,
-        safe = targets_count - unsafe_targets_count,
-        r#unsafe = unsafe_targets_count,
-        tainted = tainted_targets_count,
-    );
+    println!("skipped: {skipped_fns_count} functions");
 }
 
 fn print_call_graph<'tcx, 'trg>(tcx: TyCtxt<'tcx>, tests: &[Test], call_graph: &CallGraph<'tcx>, targets: &[Target<'trg>], format: config::GraphFormat, non_local_call_view: config::CallGraphNonLocalCallView) {
@@ -325,6 +494,189 @@ fn print_call_graph<'tcx, 'trg>(tcx: TyCtxt<'tcx>, tests: &[Test], call_graph: &
 
             println!("}}");
         }
+        config::GraphFormat::Json => {
+            let callee_json = |callee: &Callee<'tcx>| serde_json::json!({
+                "def_path": callee.display_str(tcx),
+                "span": format!("{:#?}", tcx.def_span(callee.def_id)),
+                "local": callee.def_id.is_local(),
+                "target": targets.iter().any(|target| target.def_id.to_def_id() == callee.def_id),
+            });
+
+            let mut tests_in_print_order = tests.iter()
+                .map(|test| (test.path_str(), test))
+                .collect::<Vec<_>>();
+            tests_in_print_order.sort_unstable_by(|(test_a_path_str, _), (test_b_path_str, _)| Ord::cmp(test_a_path_str, test_b_path_str));
+
+            let tests_json = tests_in_print_order.iter()
+                .map(|(test_path_str, test)| serde_json::json!({
+                    "def_path": test_path_str,
+                    "ignore": test.ignore,
+                    "ignore_message": test.ignore_message.map(|ignore_message| ignore_message.to_string()),
+                }))
+                .collect::<Vec<_>>();
+
+            // Calls are printed in order of distance first, within that by their caller and callee definition paths.
+            let mut root_calls_in_print_order = call_graph.root_calls.iter()
+                .map(|(root_def_id, callee)| (tcx.def_path_str(root_def_id.to_def_id()), callee.display_str(tcx), callee))
+                .collect::<Vec<_>>();
+            root_calls_in_print_order.sort_unstable_by(|(caller_a, callee_a, _), (caller_b, callee_b, _)| Ord::cmp(caller_a, caller_b).then(Ord::cmp(callee_a, callee_b)));
+
+            let mut calls_json = root_calls_in_print_order.into_iter()
+                .map(|(caller_def_path, _, callee)| serde_json::json!({
+                    "distance": 0,
+                    "caller": { "def_path": caller_def_path },
+                    "callee": callee_json(callee),
+                }))
+                .collect::<Vec<_>>();
+
+            for (distance, calls) in iter::zip(1.., &call_graph.nested_calls) {
+                let mut calls_in_print_order = calls.iter()
+                    .map(|(caller, callee)| (caller.display_str(tcx), callee.display_str(tcx), caller, callee))
+                    .collect::<Vec<_>>();
+                calls_in_print_order.sort_unstable_by(|(caller_a, callee_a, ..), (caller_b, callee_b, ..)| Ord::cmp(caller_a, caller_b).then(Ord::cmp(callee_a, callee_b)));
+
+                calls_json.extend(calls_in_print_order.into_iter().map(|(_, _, caller, callee)| serde_json::json!({
+                    "distance": distance,
+                    "caller": callee_json(caller),
+                    "callee": callee_json(callee),
+                })));
+            }
+
+            let call_graph_json = serde_json::json!({
+                "tests": tests_json,
+                "calls": calls_json,
+            });
+            println!("{}", serde_json::to_string_pretty(&call_graph_json).expect("cannot serialize call graph"));
+        }
+    }
+}
+
+/// Find the shortest call path from the test to any instance of the function, by walking the call graph breadth-first.
+///
+/// The call graph records calls per distance, so the calls of the next distance are only followed from callees found at
+/// the current distance. Calls are walked in lexical order, so that the path is the same between invocations.
+fn shortest_call_path<'tcx>(tcx: TyCtxt<'tcx>, call_graph: &CallGraph<'tcx>, test: &Test, def_id: hir::DefId) -> Option<Vec<Callee<'tcx>>> {
+    let mut callers: FxHashMap<Callee<'tcx>, Option<Callee<'tcx>>> = Default::default();
+
+    let mut callees = call_graph.root_calls.iter()
+        .filter(|(root_def_id, _)| *root_def_id == test.def_id)
+        .map(|(_, callee)| *callee)
+        .collect::<Vec<_>>();
+    callees.sort_unstable_by_key(|callee| callee.display_str(tcx));
+    callers.extend(callees.iter().map(|callee| (*callee, None)));
+
+    for distance in 0.. {
+        if let Some(callee) = callees.iter().find(|callee| callee.def_id == def_id) {
+            let mut call_path = vec![*callee];
+            while let Some(Some(caller)) = callers.get(&call_path[call_path.len() - 1]) {
+                call_path.push(*caller);
+            }
+            call_path.reverse();
+            return Some(call_path);
+        }
+
+        let calls = call_graph.nested_calls.get(distance)?;
+        let current_callees = callees.drain(..).collect::<FxHashSet<_>>();
+
+        let mut calls_in_walk_order = calls.iter()
+            .filter(|(caller, _)| current_callees.contains(caller))
+            .map(|(caller, callee)| (caller.display_str(tcx), callee.display_str(tcx), caller, callee))
+            .collect::<Vec<_>>();
+        calls_in_walk_order.sort_unstable_by(|(caller_a, callee_a, ..), (caller_b, callee_b, ..)| Ord::cmp(callee_a, callee_b).then(Ord::cmp(caller_a, caller_b)));
+
+        for (_, _, caller, callee) in calls_in_walk_order {
+            if callers.contains_key(callee) { continue; }
+            callers.insert(*callee, Some(*caller));
+            callees.push(*callee);
+        }
+    }
+
+    None
+}
+
+fn print_target_explanation<'tcx, 'trg>(
+    tcx: TyCtxt<'tcx>,
+    call_graph: &CallGraph<'tcx>,
+    targets: &[Target<'trg>],
+    mutable_fns: impl Iterator<Item = hir::LocalDefId>,
+    target_path: &str,
+    target_filter: &config::TargetFilter,
+    call_graph_depth: usize,
+    mutation_depth: usize,
+    unsafe_targeting: UnsafeTargeting,
+) {
+    let mut fns = mutable_fns
+        .filter(|&local_def_id| tcx.def_path_str(local_def_id.to_def_id()) == target_path)
+        .map(|local_def_id| (tcx.def_span(local_def_id), local_def_id))
+        .collect::<Vec<_>>();
+    fns.sort_unstable_by_key(|(span, _)| *span);
+
+    if fns.is_empty() {
+        println!("cannot find function `{target_path}` among functions which can be mutated");
+        return;
+    }
+
+    for (i, (span, local_def_id)) in fns.into_iter().enumerate() {
+        if i >= 1 { println!(); }
+
+        println!("{target_path} at {span:#?}");
+
+        let Some(target) = targets.iter().find(|target| target.def_id == local_def_id) else {
+            println!("  not reached from any test within call graph depth {call_graph_depth}");
+            continue;
+        };
+
+        println!("  reached from {count} {descr}, at distance {distance}",
+            count = target.reachable_from.len(),
+            descr = match target.reachable_from.len() {
+                1 => "test",
+                _ => "tests",
+            },
+            distance = target.distance,
+        );
+        match (target.distance < mutation_depth, target_filter.matches(target_path)) {
+            (true, true) => println!("  targeted for mutation within mutation depth {mutation_depth}"),
+            (true, false) => println!("  not targeted for mutation, as it is excluded by the target filter"),
+            (false, _) => println!("  not targeted for mutation, as it is not reached within mutation depth {mutation_depth}"),
+        }
+        println!("  unsafety: {unsafety}{unsafe_marker}",
+            unsafety = match target.unsafety {
+                Unsafety::None => "safe",
+                Unsafety::Unsafe(UnsafeSource::Unsafe) => "unsafe",
+                Unsafety::Unsafe(UnsafeSource::EnclosingUnsafe) => "contains unsafe block",
+                Unsafety::Tainted(UnsafeSource::Unsafe) => "tainted (called from unsafe function or block)",
+                Unsafety::Tainted(UnsafeSource::EnclosingUnsafe) => "tainted (called from function containing unsafe block)",
+            },
+            unsafe_marker = match target.is_unsafe(unsafe_targeting) {
+                true => " [unsafe]",
+                false => "",
+            },
+        );
+
+        // Entry points are printed in order of distance first, within that by lexical order of their definition path.
+        let mut entry_points_in_print_order = target.reachable_from.iter()
+            .map(|(&test, entry_point)| (test.path_str(), test, entry_point))
+            .collect::<Vec<_>>();
+        entry_points_in_print_order.sort_unstable_by(|(test_a_path_str, _, entry_point_a), (test_b_path_str, _, entry_point_b)| {
+            Ord::cmp(&entry_point_a.distance, &entry_point_b.distance).then(Ord::cmp(test_a_path_str, test_b_path_str))
+        });
+
+        for (test_path_str, test, entry_point) in entry_points_in_print_order {
+            println!();
+            println!("  ({distance}) {tainted_marker}{test}",
+                distance = entry_point.distance,
+                tainted_marker = match target.is_tainted(test, unsafe_targeting) {
+                    true => "[tainted] ",
+                    false => "",
+                },
+                test = test_path_str,
+            );
+
+            let Some(call_path) = shortest_call_path(tcx, call_graph, test, local_def_id.to_def_id()) else { continue; };
+            for callee in call_path {
+                println!("    -> {} at {:#?}", callee.display_str(tcx), tcx.def_span(callee.def_id));
+            }
+        }
     }
 }
 
@@ -365,9 +717,342 @@ where
 
             println!("}}");
         }
+        config::GraphFormat::Json => {
+            let mutations_json = mutations_iter.into_iter()
+                .map(|m| serde_json::json!({
+                    "id": m.id.index(),
+                    "stable_id": m.stable_id.to_string(),
+                    "unsafe": mutation_conflict_graph.is_unsafe(m.id),
+                }))
+                .collect::<Vec<_>>();
+
+            let edges_json = edge_iter.into_iter()
+                .map(|(a, b)| [a.index(), b.index()])
+                .collect::<Vec<_>>();
+
+            let mutation_graph_json = serde_json::json!({
+                "mutations": mutations_json,
+                "edges": edges_json,
+            });
+            println!("{}", serde_json::to_string_pretty(&mutation_graph_json).expect("cannot serialize mutation graph"));
+        }
+    }
+}
+
+fn count_mutations_by_op<'trg, 'm>(mutations: &[Mut<'trg, 'm>]) -> FxHashMap<String, usize> {
+    let mut mutations_counts: FxHashMap<String, usize> = Default::default();
+    // Merged duplicate mutations are attributed to each of their operators.
+    for mutation in mutations {
+        for op_name in mutation.op_names() {
+            *mutations_counts.entry(op_name.to_owned()).or_default() += 1;
+        }
+    }
+    mutations_counts
+}
+
+fn print_operators<'trg, 'm>(
+    op_names: &[String],
+    generated_mutations_counts: &FxHashMap<String, usize>,
+    mutations: &[Mut<'trg, 'm>],
+    mutation_conflict_graph: &MutationConflictGraph<'m>,
+) {
+    let kept_mutations_counts = count_mutations_by_op(mutations);
+
+    let conflicting_mutations = mutation_conflict_graph.iter_conflicts().flat_map(|(a, b)| [a, b]).collect::<FxHashSet<_>>();
+    let mut conflicting_mutations_counts: FxHashMap<&str, usize> = Default::default();
+    for mutation in mutations {
+        if !conflicting_mutations.contains(&mutation.id) { continue; }
+        for op_name in mutation.op_names() {
+            *conflicting_mutations_counts.entry(op_name).or_default() += 1;
+        }
+    }
+
+    let name_w = op_names.iter().map(|op_name| op_name.len()).max().unwrap_or(0);
+
+    let mut applicable_ops_count = 0;
+    for op_name in op_names {
+        let generated = generated_mutations_counts.get(op_name).copied().unwrap_or(0);
+        let kept = kept_mutations_counts.get(op_name).copied().unwrap_or(0);
+        let conflicting = conflicting_mutations_counts.get(op_name.as_str()).copied().unwrap_or(0);
+        if generated >= 1 { applicable_ops_count += 1; }
+
+        println!("{op_name:<name_w$}  {generated} generated; {dropped} dropped by sampling; {kept} kept ({conflicting} conflicting)",
+            dropped = generated - kept,
+        );
+    }
+
+    println!("\noperators: {total} registered; {applicable_ops_count} applicable; {unapplicable} never applicable",
+        total = op_names.len(),
+        unapplicable = op_names.len() - applicable_ops_count,
+    );
+}
+
+fn print_mutation_conflict_graph_stats<'trg, 'm>(mutation_conflict_graph: &MutationConflictGraph<'m>, mutations: &[Mut<'trg, 'm>]) {
+    let mutations_count = mutations.len();
+    let unsafe_mutations_count = mutations.iter().filter(|m| mutation_conflict_graph.is_unsafe(m.id)).count();
+
+    let mut degrees = mutations.iter().map(|m| (m.id, 0_usize)).collect::<FxHashMap<_, _>>();
+    let mut conflicts_count = 0_usize;
+    for (a, b) in mutation_conflict_graph.iter_conflicts() {
+        conflicts_count += 1;
+        *degrees.entry(a).or_default() += 1;
+        *degrees.entry(b).or_default() += 1;
+    }
+    let conflicts_excluding_unsafe_count = mutation_conflict_graph.iter_conflicts_excluding_unsafe().count();
+
+    let pairs_count = mutations_count * mutations_count.saturating_sub(1) / 2;
+
+    println!("mutations: {mutations_count} total; {unsafe_mutations_count} unsafe (isolated into their own mutants)");
+    println!("conflicts: {conflicts_count} total ({conflicts_excluding_unsafe_count} excluding unsafe mutations); {compatibilities_count} compatibilities",
+        compatibilities_count = pairs_count - conflicts_count,
+    );
+    println!("density: {density}",
+        density = match pairs_count {
+            0 => "none".to_owned(),
+            _ => format!("{:.2}%", conflicts_count as f64 / pairs_count as f64 * 100_f64),
+        },
+    );
+
+    let mut degrees = degrees.into_values().collect::<Vec<_>>();
+    degrees.sort_unstable();
+    if let (Some(min), Some(max)) = (degrees.first(), degrees.last()) {
+        println!("degree: {min} min; {mean:.2} mean; {median} median; {max} max",
+            mean = degrees.iter().sum::<usize>() as f64 / degrees.len() as f64,
+            median = degrees[degrees.len() / 2],
+        );
+    }
+
+    // Degrees are grouped into exponentially growing buckets (i.e. 0, 1, 2-3, 4-7, ...).
+    let mut degree_buckets = Vec::<usize>::new();
+    for &degree in &degrees {
+        let bucket = match degree {
+            0 => 0,
+            _ => degree.ilog2() as usize + 1,
+        };
+        if degree_buckets.len() <= bucket { degree_buckets.resize(bucket + 1, 0); }
+        degree_buckets[bucket] += 1;
+    }
+
+    if !degree_buckets.is_empty() {
+        println!("\ndegree distribution:");
+        for (bucket, count) in degree_buckets.into_iter().enumerate() {
+            let degree_range = match bucket {
+                0 => "0".to_owned(),
+                1 => "1".to_owned(),
+                _ => format!("{}-{}", 1_usize << (bucket - 1), (1_usize << bucket) - 1),
+            };
+            println!("  {degree_range:>11}: {count} {descr}",
+                descr = match count {
+                    1 => "mutation",
+                    _ => "mutations",
+                },
+            );
+        }
+    }
+}
+
+/// Write a summary of the mutations of the crate into a JSON file in the directory, named after the crate.
+/// Mutations are identified by their operator, location, and description, so that summaries of the same crate under
+/// different configurations (e.g. enabled features) can be compared.
+fn write_mutations_summary(tcx: TyCtxt, mutations_summary_dir: &Path, mutations: &[Mut]) {
+    fs::create_dir_all(mutations_summary_dir).expect(&format!("cannot create directory `{}`", mutations_summary_dir.display()));
+
+    let mutations_summary_json = serde_json::json!({
+        "crate": tcx.crate_name(hir::LOCAL_CRATE).as_str(),
+        "mutations": mutations.iter().map(|mutation| serde_json::json!({
+            "stable_id": mutation.stable_id.to_string(),
+            "op_name": mutation.op_name(),
+            "merged_op_names": mutation.merged_op_names,
+            "display_name": mutation.display_name(),
+            "display_location": mutation.display_location(tcx.sess),
+        })).collect::<Vec<_>>(),
+    });
+
+    let path = mutations_summary_dir.join(format!("{crate_name}-{stable_crate_id:016x}.json",
+        crate_name = tcx.crate_name(hir::LOCAL_CRATE),
+        stable_crate_id = tcx.stable_crate_id(hir::LOCAL_CRATE).as_u64(),
+    ));
+    fs::write(&path, serde_json::to_string(&mutations_summary_json).expect("cannot serialize mutations summary"))
+        .expect(&format!("cannot write `{}`", path.display()));
+}
+
+fn mutant_batches_path(tcx: TyCtxt, batching_state_dir: &Path) -> std::path::PathBuf {
+    batching_state_dir.join(format!("{crate_name}-{stable_crate_id:016x}.json",
+        crate_name = tcx.crate_name(hir::LOCAL_CRATE),
+        stable_crate_id = tcx.stable_crate_id(hir::LOCAL_CRATE).as_u64(),
+    ))
+}
+
+/// Read the mutant batching of the previous run of the crate from the directory, listing the stable ids of the
+/// mutations of each mutant. Returns no mutants if the crate has not been batched before.
+fn read_mutant_batches(tcx: TyCtxt, batching_state_dir: &Path) -> Vec<(u32, Vec<MutStableId>)> {
+    let path = mutant_batches_path(tcx, batching_state_dir);
+    let Ok(mutant_batches_json) = fs::read_to_string(&path) else { return vec![]; };
+
+    let Ok(mutant_batches_json) = serde_json::from_str::<serde_json::Value>(&mutant_batches_json) else {
+        log::warn!("ignoring malformed mutant batching `{}`", path.display());
+        return vec![];
+    };
+
+    mutant_batches_json["mutants"].as_array().into_iter().flatten()
+        .filter_map(|mutant| {
+            let id = mutant["id"].as_u64()? as u32;
+            let stable_ids = mutant["mutations"].as_array()?.iter()
+                .filter_map(|stable_id| MutStableId::parse(stable_id.as_str()?))
+                .collect::<Vec<_>>();
+            Some((id, stable_ids))
+        })
+        .collect()
+}
+
+/// Write the mutant batching of the crate into the directory, to be reused by the next run.
+fn write_mutant_batches(tcx: TyCtxt, batching_state_dir: &Path, mutants: &[Mutant]) {
+    fs::create_dir_all(batching_state_dir).expect(&format!("cannot create directory `{}`", batching_state_dir.display()));
+
+    let mutant_batches_json = serde_json::json!({
+        "crate": tcx.crate_name(hir::LOCAL_CRATE).as_str(),
+        "mutants": mutants.iter().map(|mutant| serde_json::json!({
+            "id": mutant.id.index(),
+            "mutations": mutant.mutations.iter().map(|mutation| mutation.stable_id.to_string()).collect::<Vec<_>>(),
+        })).collect::<Vec<_>>(),
+    });
+
+    let path = mutant_batches_path(tcx, batching_state_dir);
+    fs::write(&path, serde_json::to_string(&mutant_batches_json).expect("cannot serialize mutant batching"))
+        .expect(&format!("cannot write `{}`", path.display()));
+}
+
+/// Build the cost model of the tests, using the execution times recorded by the test harness in a previous run, if
+/// available. Tests without a recorded execution time are estimated to take the average time of the other tests.
+/// Without test timings, each test has a cost of 1.
+fn mk_test_cost_model(tests: &[Test], test_timings_path: Option<&Path>) -> TestCostModel {
+    let unit_test_cost_model = || TestCostModel { test_costs: Default::default(), default_test_cost: 1_f64 };
+
+    let Some(test_timings_path) = test_timings_path else { return unit_test_cost_model(); };
+    // Test timings are only available after the test harness was run once.
+    if !test_timings_path.exists() { return unit_test_cost_model(); }
+    let test_timings = match TestTimings::read(test_timings_path) {
+        Ok(test_timings) => test_timings,
+        Err(e) => {
+            log::warn!("ignoring malformed test timings `{}`: {e}", test_timings_path.display());
+            return unit_test_cost_model();
+        }
+    };
+
+    let test_costs = tests.iter()
+        .filter_map(|test| Some((test.def_id, test_timings.exec_times.get(&test.path_str())?.as_secs_f64())))
+        .collect::<FxHashMap<_, _>>();
+    let default_test_cost = match test_costs.len() {
+        0 => 0_f64,
+        n => test_costs.values().sum::<f64>() / n as f64,
+    };
+
+    TestCostModel { test_costs, default_test_cost }
+}
+
+fn batch_mutations<'trg, 'm>(
+    mutations: Vec<Mut<'trg, 'm>>,
+    mutation_conflict_graph: &MutationConflictGraph<'m>,
+    mutation_batching_algorithm: &config::MutationBatchingAlgorithm,
+    mutation_batching_randomness: &config::MutationBatchingRandomness,
+    mutant_capacity: MutantCapacity,
+    mutation_localities: &MutationLocalities,
+) -> Vec<Mutant<'trg, 'm>> {
+    match *mutation_batching_algorithm {
+        config::MutationBatchingAlgorithm::None
+        => mutest_emit::codegen::mutation::batch_mutations_dummy(mutations),
+
+        config::MutationBatchingAlgorithm::Random => {
+            let mut rng = mutation_batching_randomness.rng();
+            mutest_emit::codegen::mutation::batch_mutations_random(mutations, mutation_conflict_graph, mutant_capacity, &mut rng)
+        }
+
+        config::MutationBatchingAlgorithm::Greedy { ordering_heuristic, epsilon } => {
+            let mut rng = mutation_batching_randomness.rng();
+            if let Some(v) = epsilon {
+                if v < 0_f64 || v > 1_f64 { panic!("epsilon must be a valid probability"); }
+            }
+            mutest_emit::codegen::mutation::batch_mutations_greedy(
+                mutations,
+                mutation_conflict_graph,
+                ordering_heuristic,
+                epsilon,
+                Some(&mut rng),
+                mutant_capacity,
+                mutation_localities,
+            )
+        }
+
+        config::MutationBatchingAlgorithm::SimulatedAnnealing => {
+            let mut mutants = mutest_emit::codegen::mutation::batch_mutations_dummy(mutations);
+
+            let mut rng = mutation_batching_randomness.rng();
+            mutest_emit::codegen::mutation::optimize_batches_simulated_annealing(&mut mutants, mutation_conflict_graph, mutant_capacity, mutation_localities, 5000, &mut rng);
+
+            mutants
+        }
     }
 }
 
+/// Estimated cost of evaluating the mutants, in the number of test runs required, assuming that each mutant is
+/// evaluated against every test that reaches any of its mutations.
+fn estimate_mutants_evaluation_cost(mutants: &[Mutant]) -> usize {
+    mutants.iter()
+        .map(|mutant| {
+            mutant.mutations.iter()
+                .flat_map(|mutation| mutation.target.reachable_from.keys().map(|test| test.def_id))
+                .collect::<FxHashSet<_>>()
+                .len()
+        })
+        .sum()
+}
+
+fn report_mutation_batching<'trg, 'm>(
+    mutations: &mut Vec<Mut<'trg, 'm>>,
+    mutation_conflict_graph: &MutationConflictGraph<'m>,
+    mutation_batching_randomness: &config::MutationBatchingRandomness,
+    mutant_capacity: MutantCapacity,
+    mutation_localities: &MutationLocalities,
+) {
+    use config::{GreedyMutationBatchingOrderingHeuristic as Heuristic, MutationBatchingAlgorithm as Algorithm};
+
+    let mutation_batching_algorithms = [
+        ("none", Algorithm::None),
+        ("random", Algorithm::Random),
+        ("greedy (no ordering)", Algorithm::Greedy { ordering_heuristic: None, epsilon: None }),
+        ("greedy (random ordering)", Algorithm::Greedy { ordering_heuristic: Some(Heuristic::Random), epsilon: None }),
+        ("greedy (conflicts)", Algorithm::Greedy { ordering_heuristic: Some(Heuristic::ConflictsAsc), epsilon: None }),
+        ("greedy (reverse conflicts)", Algorithm::Greedy { ordering_heuristic: Some(Heuristic::ConflictsDesc), epsilon: None }),
+        ("greedy (target diversity)", Algorithm::Greedy { ordering_heuristic: Some(Heuristic::TargetDiversity), epsilon: None }),
+        ("simulated annealing", Algorithm::SimulatedAnnealing),
+    ];
+
+    let name_w = mutation_batching_algorithms.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+
+    log::info!("mutation batching report (batch size {max_mutations_count}{max_cost}):",
+        max_mutations_count = mutant_capacity.max_mutations_count,
+        max_cost = match mutant_capacity.max_cost {
+            Some((max_cost, _)) => format!("; max cost {max_cost}"),
+            None => String::new(),
+        },
+    );
+    for (name, mutation_batching_algorithm) in &mutation_batching_algorithms {
+        let t_start = Instant::now();
+        let mutants = batch_mutations(mem::take(mutations), mutation_conflict_graph, mutation_batching_algorithm, mutation_batching_randomness, mutant_capacity, mutation_localities);
+        let duration = t_start.elapsed();
+
+        log::info!("  {name:>name_w$}: {mutants_count} mutants; {cost} estimated test runs (batched in {duration:.2?})",
+            mutants_count = mutants.len(),
+            cost = estimate_mutants_evaluation_cost(&mutants),
+        );
+
+        // Recover the mutations from the mutants for the next algorithm, in their original order.
+        mutations.extend(mutants.into_iter().flat_map(|mutant| mutant.mutations));
+        mutations.sort_by_key(|mutation| mutation.id.index());
+    }
+    log::info!("");
+}
+
 fn print_mutants<'tcx>(tcx: TyCtxt<'tcx>, mutants: &[Mutant], unsafe_targeting: UnsafeTargeting, verbosity: u8) {
     let mut total_mutations_count = 0;
     let mut unsafe_mutations_count = 0;
@@ -411,12 +1096,7 @@ fn print_mutants<'tcx>(tcx: TyCtxt<'tcx>, mutants: &[Mutant], unsafe_targeting:
             if verbosity >= 1 {
                 print!("{}: ", mutation.id.index());
             }
-            println!("{unsafe_marker}[{op_name}] {display_name} in {def_path} at {display_location}",
-                op_name = mutation.op_name(),
-                display_name = mutation.display_name(),
-                def_path = tcx.def_path_str(mutation.target.def_id.to_def_id()),
-                display_location = mutation.display_location(tcx.sess),
-            );
+            println!("{unsafe_marker}{descriptor}", descriptor = mutation.descriptor(tcx));
 
             // Entry points are printed in order of distance first, within that by lexical order of their definition path.
             let mut entry_points_in_print_order = mutation.target.reachable_from.iter()
@@ -498,6 +1178,179 @@ fn print_mutants<'tcx>(tcx: TyCtxt<'tcx>, mutants: &[Mutant], unsafe_targeting:
     );
 }
 
+fn print_mutation_explanation<'tcx>(tcx: TyCtxt<'tcx>, mutants: &[Mutant], mutation_id: u32, unsafe_targeting: UnsafeTargeting) {
+    let Some((mutant, mutation)) = mutants.iter()
+        .flat_map(|mutant| mutant.mutations.iter().map(move |mutation| (mutant, mutation)))
+        .find(|(_, mutation)| mutation.id.index() == mutation_id)
+    else {
+        println!("cannot find mutation {mutation_id}");
+        return;
+    };
+
+    println!("mutation {mutation_id}: {descriptor}", descriptor = mutation.descriptor(tcx));
+    println!("  operator: {}", mutation.op_names_descr());
+    println!("  target: {def_path} at {def_span:#?}",
+        def_path = tcx.def_path_str(mutation.target.def_id.to_def_id()),
+        def_span = tcx.def_span(mutation.target.def_id),
+    );
+    println!("  span: {:#?}", mutation.span);
+
+    match tcx.sess.source_map().span_to_snippet(mutation.display_span()) {
+        Ok(snippet) if snippet.lines().count() <= 1 => println!("  original: `{snippet}`"),
+        Ok(snippet) => println!("  original:\n```\n{snippet}\n```"),
+        Err(_) => println!("  original: <unavailable>"),
+    }
+    for subst_descr in mutation.substs_descrs() {
+        println!("  {subst_descr}");
+    }
+
+    println!("  safety: {safety}",
+        safety = match (mutation.is_in_unsafe_block, mutation.target.unsafety) {
+            (true, _) => "unsafe (in unsafe block)",
+            (false, Unsafety::None) => "safe",
+            (false, Unsafety::Unsafe(UnsafeSource::Unsafe)) => "unsafe (in unsafe function)",
+            (false, Unsafety::Unsafe(UnsafeSource::EnclosingUnsafe)) => "unsafe (in function containing unsafe block)",
+            (false, Unsafety::Tainted(UnsafeSource::Unsafe)) => "tainted (called from unsafe function or block)",
+            (false, Unsafety::Tainted(UnsafeSource::EnclosingUnsafe)) => "tainted (called from function containing unsafe block)",
+        },
+    );
+    if mutation.is_unsafe(unsafe_targeting) {
+        println!("  treated as unsafe under the current unsafe targeting");
+    }
+
+    if let Some(note) = mutation.target.note {
+        println!("  note: {note}");
+    }
+
+    let mut batched_mutation_ids = mutant.mutations.iter()
+        .map(|m| m.id.index())
+        .filter(|&id| id != mutation_id)
+        .collect::<Vec<_>>();
+    batched_mutation_ids.sort_unstable();
+    match &batched_mutation_ids[..] {
+        [] => println!("  mutant: {mutant_id}, unbatched", mutant_id = mutant.id.index()),
+        _ => println!("  mutant: {mutant_id}, batched with mutations {mutation_ids}",
+            mutant_id = mutant.id.index(),
+            mutation_ids = batched_mutation_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", "),
+        ),
+    }
+
+    println!("  reached from {count} {descr}:",
+        count = mutation.target.reachable_from.len(),
+        descr = match mutation.target.reachable_from.len() {
+            1 => "test",
+            _ => "tests",
+        },
+    );
+
+    // Entry points are printed in order of distance first, within that by lexical order of their definition path.
+    let mut entry_points_in_print_order = mutation.target.reachable_from.iter()
+        .map(|(&test, entry_point)| (test.path_str(), test, entry_point))
+        .collect::<Vec<_>>();
+    entry_points_in_print_order.sort_unstable_by(|(test_a_path_str, _, entry_point_a), (test_b_path_str, _, entry_point_b)| {
+        Ord::cmp(&entry_point_a.distance, &entry_point_b.distance).then(Ord::cmp(test_a_path_str, test_b_path_str))
+    });
+
+    for (test_path_str, test, entry_point) in entry_points_in_print_order {
+        println!("    ({distance}) {tainted_marker}{test}",
+            distance = entry_point.distance,
+            tainted_marker = match mutation.target.is_tainted(test, unsafe_targeting) {
+                true => "[tainted] ",
+                false => "",
+            },
+            test = test_path_str,
+        );
+    }
+}
+
+fn print_patches<'tcx>(tcx: TyCtxt<'tcx>, mutants: &[Mutant], node_spans: &NodeSpans) {
+    let mut unavailable_patches_count = 0;
+
+    // Mutations are printed in assigned ID order.
+    let mut mutations_in_print_order = mutants.iter().flat_map(|mutant| &mutant.mutations).collect::<Vec<_>>();
+    mutations_in_print_order.sort_unstable_by_key(|mutation| mutation.id.index());
+
+    let mutations_count = mutations_in_print_order.len();
+
+    for mutation in mutations_in_print_order {
+        println!("{id}: [{op_names}] {display_name} in {def_path} at {display_location}",
+            id = mutation.id.index(),
+            op_names = mutation.op_names_descr(),
+            display_name = mutation.display_name(),
+            def_path = tcx.def_path_str(mutation.target.def_id.to_def_id()),
+            display_location = mutation.display_location(tcx.sess),
+        );
+
+        match mutest_emit::codegen::patch::render_mutation_patch(tcx.sess.source_map(), node_spans, mutation) {
+            Ok(patch) => print!("{patch}"),
+            Err(error) => {
+                unavailable_patches_count += 1;
+                println!("patch unavailable: {}", error.desc());
+            }
+        }
+
+        println!();
+    }
+
+    println!("patches: {total} total; {unavailable} unavailable",
+        total = mutations_count,
+        unavailable = unavailable_patches_count,
+    );
+}
+
+fn generate_crate_code<'tcx>(tcx: TyCtxt<'tcx>, mutants: &[Mutant], tests: &[Test], mut generated_crate_ast: ast::Crate, original_crate_ast: Option<&ast::Crate>, source_name: FileName, subst_dispatch: SubstDispatch, unsafe_targeting: UnsafeTargeting) -> String {
+    let sess = tcx.sess;
+
+    let subst_locs = mutest_emit::codegen::substitution::write_substitutions(tcx, mutants, subst_dispatch, &mut generated_crate_ast);
+
+    // HACK: See below.
+    mutest_emit::codegen::expansion::insert_generated_code_crate_refs(tcx, &mut generated_crate_ast);
+    mutest_emit::codegen::expansion::insert_generated_code_prelude_attrs(tcx, &mut generated_crate_ast);
+
+    mutest_emit::codegen::entry_point::clean_entry_points(sess, &mut generated_crate_ast);
+    mutest_emit::codegen::entry_point::generate_dummy_main(tcx, &mut generated_crate_ast);
+
+    if let Some(original_crate_ast) = original_crate_ast {
+        mutest_emit::codegen::expansion::revert_non_local_macro_expansions(&mut generated_crate_ast, original_crate_ast);
+    }
+
+    mutest_emit::codegen::substitution::resolve_syntax_ambiguities(tcx, &mut generated_crate_ast);
+
+    mutest_emit::codegen::harness::generate_harness(tcx, mutants, &subst_locs, subst_dispatch, tests, &mut generated_crate_ast, unsafe_targeting);
+
+    let generated_file = source_name.display(FileNameDisplayPreference::Remapped).to_string();
+
+    // HACK: The generated code is currently based on the expanded AST and contains references to the internals
+    //       of macro expansions. These are patched over using a static attribute prelude (here) and a static
+    //       set of crate references (above).
+    let span_markers = mutest_emit::codegen::span_map::SpanMarkers::default();
+    let marked_code = format!("{prelude}\n{code}",
+        prelude = mutest_emit::codegen::expansion::GENERATED_CODE_PRELUDE,
+        code = rustc_ast_pretty::pprust::print_crate(
+            sess.source_map(),
+            &generated_crate_ast,
+            source_name,
+            "".to_owned(),
+            &span_markers,
+            true,
+            Edition::Edition2021,
+            &sess.psess.attr_id_generator,
+        ),
+    );
+    let (mut code, span_map) = span_markers.strip_markers(sess.source_map(), &marked_code);
+
+    // The span map is inserted at the end of the `mutest_generated` module, which is the last item of the crate, so
+    // that the locations of the preceding code are unaffected.
+    let span_map_item = mutest_emit::codegen::harness::mk_span_map_static(DUMMY_SP, &generated_file, &span_map);
+    let Some(mutest_generated_mod_end) = code.rfind('}') else { unreachable!("generated code has no `mutest_generated` module") };
+    code.insert_str(mutest_generated_mod_end, &format!("{}\n", rustc_ast_pretty::pprust::item_to_string(&span_map_item)));
+
+    code
+}
+
+/// Analyze the crate and generate the code of the test harness.
+///
+/// Returns `None` if the pass was stopped early, after printing the requested information in [`config::Mode::Print`].
 pub fn run(config: &mut Config) -> CompilerResult<Option<AnalysisPassResult>> {
     let mut compiler_config = base_compiler_config(config);
 
@@ -514,8 +1367,9 @@ pub fn run(config: &mut Config) -> CompilerResult<Option<AnalysisPassResult>> {
 
     let sess_opts = mutest_emit::session::Options {
         verbosity: opts.verbosity,
-        report_timings: opts.report_timings,
+        report_timings: opts.report_timings.is_some(),
         sanitize_macro_expns: opts.sanitize_macro_expns,
+        mutate_macro_expansions: opts.mutate_macro_expansions,
     };
 
     let analysis_pass = run_compiler(compiler_config, |compiler| -> CompilerResult<Option<AnalysisPassResult>> {
@@ -523,6 +1377,9 @@ pub fn run(config: &mut Config) -> CompilerResult<Option<AnalysisPassResult>> {
             let sess = &compiler.sess;
 
             let t_start = Instant::now();
+            timings::reset_peak_rss();
+            let mut peak_rss = AnalysisPeakRss::default();
+            let mut rustc_analysis_duration = Duration::ZERO;
             let mut target_analysis_duration = Duration::ZERO;
             let mut sanitize_macro_expns_duration = Duration::ZERO;
             let mut mutation_analysis_duration = Duration::ZERO;
@@ -536,6 +1393,10 @@ pub fn run(config: &mut Config) -> CompilerResult<Option<AnalysisPassResult>> {
                 let mut crate_ast_steal = queries.parse()?;
                 let crate_ast = crate_ast_steal.get_mut();
                 mutest_emit::codegen::tool_attr::register(sess, crate_ast);
+                if opts.include_doctests {
+                    mutest_emit::codegen::doctests::inject_doctests(sess, crate_ast);
+                }
+                mutest_emit::codegen::integration_tests::inject_integration_tests(sess, crate_ast, &opts.integration_tests);
                 crate_ast.clone()
             };
 
@@ -552,14 +1413,20 @@ pub fn run(config: &mut Config) -> CompilerResult<Option<AnalysisPassResult>> {
                     (generated_crate_ast, def_res)
                 };
 
-                let tests = mutest_emit::analysis::tests::collect_tests(&generated_crate_ast, &def_res);
+                let all_tests = mutest_emit::analysis::tests::collect_tests(&generated_crate_ast, &def_res);
+                // Only tests matching the test filter are entry points of the analysis, but all tests are excluded from
+                // mutation, and all test cases are cleaned up in the generated code.
+                let tests = all_tests.iter()
+                    .filter(|test| opts.test_filter.matches(&test.path_str()))
+                    .cloned()
+                    .collect::<Vec<_>>();
 
                 if let Some(_) = opts.print_opts.tests.take() {
                     if opts.print_opts.print_headers { println!("\n@@@ tests @@@\n"); }
                     print_tests(&tests);
                     if let config::Mode::Print = opts.mode && opts.print_opts.is_empty() {
-                        if opts.report_timings {
-                            println!("\nfinished in {total:.2?}",
+                        if let Some(config::TimingsFormat::Human) = opts.report_timings {
+                            log::info!("\nfinished in {total:.2?}",
                                 total = t_start.elapsed(),
                             );
                         }
@@ -572,7 +1439,12 @@ pub fn run(config: &mut Config) -> CompilerResult<Option<AnalysisPassResult>> {
 
                 let crate_res = mutest_emit::analysis::res::CrateResolutions::from_post_analysis_tcx(tcx);
 
-                let all_mutable_fns_count = mutest_emit::analysis::call_graph::all_mutable_fns(tcx, &tests).count();
+                let mutable_const_fns = match opts.mutate_const_fns {
+                    true => mutest_emit::analysis::call_graph::runtime_only_const_fns(tcx),
+                    false => Default::default(),
+                };
+
+                let all_mutable_fns_count = mutest_emit::analysis::call_graph::all_mutable_fns(tcx, &all_tests, &mutable_const_fns).count();
 
                 let call_graph_depth = match opts.call_graph_depth {
                     Some(call_graph_depth) => {
@@ -584,11 +1456,15 @@ pub fn run(config: &mut Config) -> CompilerResult<Option<AnalysisPassResult>> {
                     None => opts.mutation_depth,
                 };
 
+                rustc_analysis_duration = t_start.elapsed();
+                peak_rss.rustc_analysis = timings::peak_rss();
+
                 let t_target_analysis_start = Instant::now();
+                timings::reset_peak_rss();
 
-                let (call_graph, mut reachable_fns) = mutest_emit::analysis::call_graph::reachable_fns(tcx, &def_res, &generated_crate_ast, &tests, call_graph_depth);
-                if opts.verbosity >= 1 {
-                    println!("reached {reached_pct:.2}% of functions from tests ({reached} out of {total} functions)",
+                let (call_graph, mut reachable_fns) = mutest_emit::analysis::call_graph::reachable_fns(tcx, &def_res, &generated_crate_ast, &tests, call_graph_depth, &mutable_const_fns, opts.unsafe_taint_policy);
+                if log::enabled(log::Level::Debug) {
+                    log::debug!("reached {reached_pct:.2}% of functions from tests ({reached} out of {total} functions)",
                         reached_pct = reachable_fns.len() as f64 / all_mutable_fns_count as f64 * 100_f64,
                         reached = reachable_fns.len(),
                         total = all_mutable_fns_count,
@@ -596,7 +1472,7 @@ pub fn run(config: &mut Config) -> CompilerResult<Option<AnalysisPassResult>> {
 
                     if call_graph.virtual_calls_count >= 1 || call_graph.dynamic_calls_count >= 1 {
                         let total_calls_count = call_graph.total_calls_count();
-                        println!("could not resolve {unresolved_pct:.2}% of function calls ({virtual} virtual, {dynamic} dynamic, {foreign} foreign out of {total} function calls)",
+                        log::debug!("could not resolve {unresolved_pct:.2}% of function calls ({virtual} virtual, {dynamic} dynamic, {foreign} foreign out of {total} function calls)",
                             unresolved_pct = (call_graph.virtual_calls_count + call_graph.dynamic_calls_count + call_graph.foreign_calls_count) as f64 / total_calls_count as f64 * 100_f64,
                             virtual = call_graph.virtual_calls_count,
                             dynamic = call_graph.dynamic_calls_count,
@@ -604,6 +1480,12 @@ pub fn run(config: &mut Config) -> CompilerResult<Option<AnalysisPassResult>> {
                             total = total_calls_count,
                         );
                     }
+
+                    if call_graph.approximated_calls_count >= 1 {
+                        log::debug!("approximated {approximated} function calls from HIR, for functions without available MIR",
+                            approximated = call_graph.approximated_calls_count,
+                        );
+                    }
                 }
 
                 // HACK: Ensure that targets are in a deterministic, stable order, otherwise
@@ -614,8 +1496,8 @@ pub fn run(config: &mut Config) -> CompilerResult<Option<AnalysisPassResult>> {
                     if opts.print_opts.print_headers { println!("\n@@@ call graph @@@\n"); }
                     print_call_graph(tcx, &tests, &call_graph, &reachable_fns, format, non_local_call_view);
                     if let config::Mode::Print = opts.mode && opts.print_opts.is_empty() {
-                        if opts.report_timings {
-                            println!("\nfinished in {total:.2?} (targets {targets:.2?})",
+                        if let Some(config::TimingsFormat::Human) = opts.report_timings {
+                            log::info!("\nfinished in {total:.2?} (targets {targets:.2?})",
                                 total = t_start.elapsed(),
                                 targets = t_target_analysis_start.elapsed(),
                             );
@@ -625,16 +1507,40 @@ pub fn run(config: &mut Config) -> CompilerResult<Option<AnalysisPassResult>> {
                     if opts.verbosity >= 1 { println!(); }
                 }
 
-                let targets = reachable_fns.iter().filter(|f| f.distance < opts.mutation_depth);
+                if let Some(explain_target) = opts.print_opts.explain_target.take() {
+                    if opts.print_opts.print_headers { println!("\n@@@ target explanation @@@\n"); }
+                    let mutable_fns = mutest_emit::analysis::call_graph::all_mutable_fns(tcx, &all_tests, &mutable_const_fns);
+                    print_target_explanation(tcx, &call_graph, &reachable_fns, mutable_fns, &explain_target, &opts.target_filter, call_graph_depth, opts.mutation_depth, opts.unsafe_targeting);
+                    if let config::Mode::Print = opts.mode && opts.print_opts.is_empty() {
+                        if let Some(config::TimingsFormat::Human) = opts.report_timings {
+                            log::info!("\nfinished in {total:.2?} (targets {targets:.2?})",
+                                total = t_start.elapsed(),
+                                targets = t_target_analysis_start.elapsed(),
+                            );
+                        }
+                        return Flow::Break;
+                    }
+                    if opts.verbosity >= 1 { println!(); }
+                }
+
+                let targets = reachable_fns.iter()
+                    .filter(|f| f.distance < opts.mutation_depth)
+                    .filter(|f| opts.target_filter.matches(&tcx.def_path_str(f.def_id.to_def_id())));
 
                 target_analysis_duration = t_target_analysis_start.elapsed();
+                peak_rss.target_analysis = timings::peak_rss();
 
-                if let Some(_) = opts.print_opts.mutation_targets.take() {
+                if let Some(config::TargetsOptions { explain_skipped, format, with_tests }) = opts.print_opts.mutation_targets.take() {
                     if opts.print_opts.print_headers { println!("\n@@@ targets @@@\n"); }
-                    print_targets(tcx, targets.clone(), opts.unsafe_targeting);
+                    print_targets(tcx, targets.clone(), opts.unsafe_targeting, format, with_tests);
+                    if explain_skipped {
+                        println!();
+                        let fns_with_mutability = mutest_emit::analysis::call_graph::all_fns_with_mutability(tcx, &all_tests, &mutable_const_fns);
+                        print_skipped_fns(tcx, fns_with_mutability, &reachable_fns, &opts.target_filter, call_graph_depth, opts.mutation_depth, opts.unsafe_targeting);
+                    }
                     if let config::Mode::Print = opts.mode && opts.print_opts.is_empty() {
-                        if opts.report_timings {
-                            println!("\nfinished in {total:.2?} (targets {targets:.2?})",
+                        if let Some(config::TimingsFormat::Human) = opts.report_timings {
+                            log::info!("\nfinished in {total:.2?} (targets {targets:.2?})",
                                 total = t_start.elapsed(),
                                 targets = target_analysis_duration,
                             );
@@ -644,33 +1550,70 @@ pub fn run(config: &mut Config) -> CompilerResult<Option<AnalysisPassResult>> {
                     if opts.verbosity >= 1 { println!(); }
                 }
 
-                mutest_emit::codegen::expansion::clean_up_test_cases(sess, &tests, &mut generated_crate_ast);
+                mutest_emit::codegen::expansion::clean_up_test_cases(sess, &all_tests, &mut generated_crate_ast);
 
-                let body_res = mutest_emit::analysis::ast_lowering::resolve_bodies(tcx, &def_res, &generated_crate_ast);
+                // Items raising internal errors are skipped, rather than aborting the entire analysis.
+                let mut internal_errors = mutest_emit::analysis::diagnostic::InternalErrors::default();
+
+                let body_res = mutest_emit::analysis::ast_lowering::resolve_bodies(tcx, &def_res, &generated_crate_ast, &mut internal_errors);
                 if opts.verify_opts.ast_lowering {
                     mutest_emit::analysis::ast_lowering::validate_body_resolutions(&body_res, &def_res, &generated_crate_ast);
                 }
 
                 if opts.sanitize_macro_expns {
                     let t_sanitize_macro_expns_start = Instant::now();
-                    mutest_emit::codegen::hygiene::sanitize_macro_expansions(tcx, &crate_res, &def_res, &body_res, &mut generated_crate_ast);
+                    timings::reset_peak_rss();
+                    mutest_emit::codegen::hygiene::sanitize_macro_expansions(tcx, &crate_res, &def_res, &body_res, &mut generated_crate_ast, &mut internal_errors);
                     sanitize_macro_expns_duration = t_sanitize_macro_expns_start.elapsed();
+                    peak_rss.sanitize_macro_expns = timings::peak_rss();
                 }
 
                 let t_mutation_analysis_start = Instant::now();
-                let mutations = mutest_emit::codegen::mutation::apply_mutation_operators(tcx, &crate_res, &def_res, &body_res, &generated_crate_ast, targets, &opts.operators, opts.unsafe_targeting, &sess_opts);
-                if opts.verbosity >= 1 {
+                timings::reset_peak_rss();
+                let mut node_ancestry = mutest_emit::codegen::substitution::NodeAncestry::default();
+                // Mutations of generic functions are checked against each of their instantiations reached from tests.
+                let instantiations = call_graph.instantiations();
+                let mut mutations = mutest_emit::codegen::mutation::apply_mutation_operators(tcx, &crate_res, &def_res, &body_res, &generated_crate_ast, targets, &instantiations, &opts.operators, opts.unsafe_targeting, opts.min_mutation_priority, &sess_opts, &mut node_ancestry, &mut internal_errors);
+                internal_errors.report_summary(tcx);
+                let merged_mutations_count = mutest_emit::codegen::mutation::dedup_mutations(&mut mutations);
+                if merged_mutations_count > 0 {
+                    log::debug!("merged {merged_mutations_count} duplicate mutations");
+                }
+                // Mutations may be dropped by sampling, so the number of generated mutations is recorded beforehand.
+                let generated_mutations_counts = opts.print_opts.operators.as_ref().map(|_| count_mutations_by_op(&mutations));
+                if log::enabled(log::Level::Debug) {
                     let mutated_fns = mutations.iter().map(|m| m.target.def_id).collect::<FxHashSet<_>>();
                     let mutated_fns_count = mutated_fns.len();
 
-                    println!("generated {mutations} mutations in {mutated_pct:.2}% of functions ({mutated} out of {total} functions)",
+                    log::debug!("generated {mutations} mutations in {mutated_pct:.2}% of functions ({mutated} out of {total} functions)",
                         mutations = mutations.len(),
                         mutated_pct = mutated_fns_count as f64 / all_mutable_fns_count as f64 * 100_f64,
                         mutated = mutated_fns_count,
                         total = all_mutable_fns_count,
                     );
                 }
+                if let Some(max_mutations_per_target) = opts.max_mutations_per_target {
+                    let mutation_regions = match opts.target_cap_policy {
+                        TargetCapPolicy::Diverse => MutationRegions::new(tcx, &mutations),
+                        _ => MutationRegions::default(),
+                    };
+                    let mut rng = opts.mutation_batching_randomness.rng();
+                    let trimmed_mutations_counts = mutest_emit::codegen::mutation::limit_mutations_per_target(&mut mutations, max_mutations_per_target, opts.target_cap_policy, &mutation_regions, &mut rng);
+                    if log::enabled(log::Level::Debug) {
+                        let mut trimmed_targets_in_print_order = trimmed_mutations_counts.into_iter()
+                            .map(|(def_id, trimmed_mutations_count)| (tcx.def_span(def_id), def_id, trimmed_mutations_count))
+                            .collect::<Vec<_>>();
+                        trimmed_targets_in_print_order.sort_unstable_by_key(|(span, _, _)| *span);
+
+                        for (_, def_id, trimmed_mutations_count) in trimmed_targets_in_print_order {
+                            log::debug!("trimmed {trimmed_mutations_count} mutations in {def_path}, keeping {max_mutations_per_target} mutations",
+                                def_path = tcx.def_path_str(def_id),
+                            );
+                        }
+                    }
+                }
                 mutation_analysis_duration = t_mutation_analysis_start.elapsed();
+                peak_rss.mutation_analysis = timings::peak_rss();
 
                 if let Err(errors) = mutest_emit::codegen::mutation::validate_mutations(&mutations) {
                     for error in &errors {
@@ -690,15 +1633,20 @@ pub fn run(config: &mut Config) -> CompilerResult<Option<AnalysisPassResult>> {
                         }
                     }
 
-                    println!("found {} mutation errors", errors.len());
+                    log::error!("found {} mutation errors", errors.len());
                     FatalError.raise();
                 }
 
+                if let Some(mutations_summary_dir) = &opts.mutations_summary_dir {
+                    write_mutations_summary(tcx, mutations_summary_dir, &mutations);
+                }
+
                 let t_mutation_batching_start = Instant::now();
+                timings::reset_peak_rss();
 
-                let mutation_conflict_graph = mutest_emit::codegen::mutation::generate_mutation_conflict_graph(&mutations, opts.unsafe_targeting);
-                if opts.verbosity >= 1 {
-                    println!("found {conflicts} conflicts ({conflicts_excluding_unsafe} excluding unsafe mutations), {compatibilities} compatibilities",
+                let mutation_conflict_graph = mutest_emit::codegen::mutation::generate_mutation_conflict_graph(&mutations, &node_ancestry, opts.unsafe_targeting);
+                if log::enabled(log::Level::Debug) {
+                    log::debug!("found {conflicts} conflicts ({conflicts_excluding_unsafe} excluding unsafe mutations), {compatibilities} compatibilities",
                         conflicts = mutation_conflict_graph.iter_conflicts().count(),
                         conflicts_excluding_unsafe = mutation_conflict_graph.iter_conflicts_excluding_unsafe().count(),
                         compatibilities = mutation_conflict_graph.iter_compatibilities().count(),
@@ -716,8 +1664,8 @@ pub fn run(config: &mut Config) -> CompilerResult<Option<AnalysisPassResult>> {
                         (true, true) => print_mutation_graph(&mutation_conflict_graph, mutations_excluding_unsafe, mutation_conflict_graph.iter_compatibilities(), format),
                     }
                     if let config::Mode::Print = opts.mode && opts.print_opts.is_empty() {
-                        if opts.report_timings {
-                            println!("\nfinished in {total:.2?} (targets {targets:.2?}; mutations {mutations:.2?}; conflicts {conflicts:.2?})",
+                        if let Some(config::TimingsFormat::Human) = opts.report_timings {
+                            log::info!("\nfinished in {total:.2?} (targets {targets:.2?}; mutations {mutations:.2?}; conflicts {conflicts:.2?})",
                                 total = t_start.elapsed(),
                                 targets = target_analysis_duration,
                                 mutations = mutation_analysis_duration,
@@ -728,41 +1676,71 @@ pub fn run(config: &mut Config) -> CompilerResult<Option<AnalysisPassResult>> {
                     }
                 }
 
-                let mutants = match opts.mutation_batching_algorithm {
-                    config::MutationBatchingAlgorithm::None
-                    => mutest_emit::codegen::mutation::batch_mutations_dummy(mutations),
-
-                    config::MutationBatchingAlgorithm::Random => {
-                        let mut rng = opts.mutation_batching_randomness.rng();
-                        mutest_emit::codegen::mutation::batch_mutations_random(mutations, &mutation_conflict_graph, opts.mutant_max_mutations_count, &mut rng)
+                if let Some(_) = opts.print_opts.conflict_graph_stats.take() {
+                    let mutation_conflict_resolution_duration = t_mutation_batching_start.elapsed();
+                    if opts.print_opts.print_headers { println!("\n@@@ conflict graph statistics @@@\n"); }
+                    print_mutation_conflict_graph_stats(&mutation_conflict_graph, &mutations);
+                    if let config::Mode::Print = opts.mode && opts.print_opts.is_empty() {
+                        if let Some(config::TimingsFormat::Human) = opts.report_timings {
+                            log::info!("\nfinished in {total:.2?} (targets {targets:.2?}; mutations {mutations:.2?}; conflicts {conflicts:.2?})",
+                                total = t_start.elapsed(),
+                                targets = target_analysis_duration,
+                                mutations = mutation_analysis_duration,
+                                conflicts = mutation_conflict_resolution_duration,
+                            );
+                        }
+                        return Flow::Break;
                     }
+                }
 
-                    config::MutationBatchingAlgorithm::Greedy { ordering_heuristic, epsilon } => {
-                        let mut rng = opts.mutation_batching_randomness.rng();
-                        if let Some(v) = epsilon {
-                            if v < 0_f64 || v > 1_f64 { panic!("epsilon must be a valid probability"); }
+                if let Some(config::OperatorsOptions { op_names }) = opts.print_opts.operators.take() {
+                    let mutation_conflict_resolution_duration = t_mutation_batching_start.elapsed();
+                    if opts.print_opts.print_headers { println!("\n@@@ operators @@@\n"); }
+                    let Some(generated_mutations_counts) = &generated_mutations_counts else { unreachable!() };
+                    print_operators(&op_names, generated_mutations_counts, &mutations, &mutation_conflict_graph);
+                    if let config::Mode::Print = opts.mode && opts.print_opts.is_empty() {
+                        if let Some(config::TimingsFormat::Human) = opts.report_timings {
+                            log::info!("\nfinished in {total:.2?} (targets {targets:.2?}; mutations {mutations:.2?}; conflicts {conflicts:.2?})",
+                                total = t_start.elapsed(),
+                                targets = target_analysis_duration,
+                                mutations = mutation_analysis_duration,
+                                conflicts = mutation_conflict_resolution_duration,
+                            );
                         }
-                        mutest_emit::codegen::mutation::batch_mutations_greedy(
-                            mutations,
-                            &mutation_conflict_graph,
-                            ordering_heuristic,
-                            epsilon,
-                            Some(&mut rng),
-                            opts.mutant_max_mutations_count,
-                        )
+                        return Flow::Break;
                     }
+                }
 
-                    config::MutationBatchingAlgorithm::SimulatedAnnealing => {
-                        let mut mutants = mutest_emit::codegen::mutation::batch_mutations_dummy(mutations);
+                let test_cost_model = opts.mutant_max_cost.map(|_| mk_test_cost_model(&tests, opts.test_timings_path.as_deref()));
+                let mutant_capacity = MutantCapacity {
+                    max_mutations_count: opts.mutant_max_mutations_count,
+                    max_cost: Option::zip(opts.mutant_max_cost, test_cost_model.as_ref()),
+                };
+
+                let mutation_localities = MutationLocalities::new(tcx, &mutations, opts.batch_locality);
+
+                if opts.report_mutation_batching {
+                    report_mutation_batching(&mut mutations, &mutation_conflict_graph, &opts.mutation_batching_randomness, mutant_capacity, &mutation_localities);
+                }
 
-                        let mut rng = opts.mutation_batching_randomness.rng();
-                        mutest_emit::codegen::mutation::optimize_batches_simulated_annealing(&mut mutants, &mutation_conflict_graph, opts.mutant_max_mutations_count, 5000, &mut rng);
+                let mutants = match &opts.batching_state_dir {
+                    // Only the mutations which are not part of the previous batching are batched again.
+                    Some(batching_state_dir) => {
+                        let previous_mutant_batches = read_mutant_batches(tcx, batching_state_dir);
+                        let (reused_mutants, remaining_mutations) = mutest_emit::codegen::mutation::reuse_mutant_batches(mutations, &previous_mutant_batches, &mutation_conflict_graph, mutant_capacity);
+                        log::debug!("reused batching of {reused_mutants} mutants, batching {remaining_mutations} remaining mutations",
+                            reused_mutants = reused_mutants.len(),
+                            remaining_mutations = remaining_mutations.len(),
+                        );
 
-                        mutants
+                        let new_mutants = batch_mutations(remaining_mutations, &mutation_conflict_graph, &opts.mutation_batching_algorithm, &opts.mutation_batching_randomness, mutant_capacity, &mutation_localities);
+                        mutest_emit::codegen::mutation::merge_mutant_batches(reused_mutants, new_mutants)
                     }
+                    None => batch_mutations(mutations, &mutation_conflict_graph, &opts.mutation_batching_algorithm, &opts.mutation_batching_randomness, mutant_capacity, &mutation_localities),
                 };
 
                 mutation_batching_duration = t_mutation_batching_start.elapsed();
+                peak_rss.mutation_batching = timings::peak_rss();
 
                 if let Err(errors) = mutest_emit::codegen::mutation::validate_mutation_batches(&mutants, &mutation_conflict_graph) {
                     for error in &errors {
@@ -778,16 +1756,20 @@ pub fn run(config: &mut Config) -> CompilerResult<Option<AnalysisPassResult>> {
                         }
                     }
 
-                    println!("found {} mutation batching errors", errors.len());
+                    log::error!("found {} mutation batching errors", errors.len());
                     FatalError.raise();
                 }
 
+                if let Some(batching_state_dir) = &opts.batching_state_dir {
+                    write_mutant_batches(tcx, batching_state_dir, &mutants);
+                }
+
                 if let Some(_) = opts.print_opts.mutants.take() {
                     if opts.print_opts.print_headers { println!("\n@@@ mutants @@@\n"); }
                     print_mutants(tcx, &mutants, opts.unsafe_targeting, opts.verbosity);
                     if let config::Mode::Print = opts.mode && opts.print_opts.is_empty() {
-                        if opts.report_timings {
-                            println!("\nfinished in {total:.2?} (targets {targets:.2?}; mutations {mutations:.2?}; batching {batching:.2?})",
+                        if let Some(config::TimingsFormat::Human) = opts.report_timings {
+                            log::info!("\nfinished in {total:.2?} (targets {targets:.2?}; mutations {mutations:.2?}; batching {batching:.2?})",
                                 total = t_start.elapsed(),
                                 targets = target_analysis_duration,
                                 mutations = mutation_analysis_duration,
@@ -798,55 +1780,108 @@ pub fn run(config: &mut Config) -> CompilerResult<Option<AnalysisPassResult>> {
                     }
                 }
 
-                let t_codegen_start = Instant::now();
+                if let Some(explain_mutation) = opts.print_opts.explain_mutation.take() {
+                    if opts.print_opts.print_headers { println!("\n@@@ mutation explanation @@@\n"); }
+                    print_mutation_explanation(tcx, &mutants, explain_mutation, opts.unsafe_targeting);
+                    if let config::Mode::Print = opts.mode && opts.print_opts.is_empty() {
+                        if let Some(config::TimingsFormat::Human) = opts.report_timings {
+                            log::info!("\nfinished in {total:.2?} (targets {targets:.2?}; mutations {mutations:.2?}; batching {batching:.2?})",
+                                total = t_start.elapsed(),
+                                targets = target_analysis_duration,
+                                mutations = mutation_analysis_duration,
+                                batching = mutation_batching_duration,
+                            );
+                        }
+                        return Flow::Break;
+                    }
+                }
 
-                let subst_locs = mutest_emit::codegen::substitution::write_substitutions(tcx, &mutants, &mut generated_crate_ast);
+                if let Some(_) = opts.print_opts.patches.take() {
+                    if opts.print_opts.print_headers { println!("\n@@@ patches @@@\n"); }
+                    let node_spans = mutest_emit::codegen::patch::collect_node_spans(&generated_crate_ast);
+                    print_patches(tcx, &mutants, &node_spans);
+                    if let config::Mode::Print = opts.mode && opts.print_opts.is_empty() {
+                        if let Some(config::TimingsFormat::Human) = opts.report_timings {
+                            log::info!("\nfinished in {total:.2?} (targets {targets:.2?}; mutations {mutations:.2?}; batching {batching:.2?})",
+                                total = t_start.elapsed(),
+                                targets = target_analysis_duration,
+                                mutations = mutation_analysis_duration,
+                                batching = mutation_batching_duration,
+                            );
+                        }
+                        return Flow::Break;
+                    }
+                }
 
-                // HACK: See below.
-                mutest_emit::codegen::expansion::insert_generated_code_crate_refs(tcx, &mut generated_crate_ast);
-                mutest_emit::codegen::expansion::insert_generated_code_prelude_attrs(tcx, &mut generated_crate_ast);
+                let t_codegen_start = Instant::now();
+                timings::reset_peak_rss();
 
-                mutest_emit::codegen::entry_point::clean_entry_points(sess, &mut generated_crate_ast);
-                mutest_emit::codegen::entry_point::generate_dummy_main(tcx, &mut generated_crate_ast);
+                mutest_emit::codegen::substitution::remove_const_qualifiers(&def_res, &mutable_const_fns, &mut generated_crate_ast);
 
-                if !opts.sanitize_macro_expns {
+                let revert_macros = !opts.sanitize_macro_expns && opts.revert_macros;
+                if revert_macros {
                     mutest_emit::codegen::expansion::load_modules(sess, &mut crate_ast);
-                    mutest_emit::codegen::expansion::revert_non_local_macro_expansions(&mut generated_crate_ast, &crate_ast);
                 }
+                let original_crate_ast = revert_macros.then_some(&crate_ast);
 
-                mutest_emit::codegen::substitution::resolve_syntax_ambiguities(tcx, &mut generated_crate_ast);
+                // The self-check enables the substitutions of each mutation separately, which requires compile-time
+                // substitutions.
+                let subst_dispatch = match opts.mode {
+                    config::Mode::SelfCheck => SubstDispatch::Cfg,
+                    _ => opts.subst_dispatch,
+                };
 
-                mutest_emit::codegen::harness::generate_harness(tcx, &mutants, &subst_locs, &mut generated_crate_ast, opts.unsafe_targeting);
+                let generated_mutant_crate_codes = match &opts.print_opts.code {
+                    Some(config::CodeOptions { split_per_mutant: true, .. }) => {
+                        mutants.iter()
+                            .map(|mutant| {
+                                let mutant_crate_code = generate_crate_code(tcx, slice::from_ref(mutant), &tests, generated_crate_ast.clone(), original_crate_ast, source_name.clone(), subst_dispatch, opts.unsafe_targeting);
+                                (mutant.id, mutant_crate_code)
+                            })
+                            .collect::<Vec<_>>()
+                    }
+                    _ => vec![],
+                };
 
-                codegen_duration = t_codegen_start.elapsed();
+                let generated_crate_code = generate_crate_code(tcx, &mutants, &tests, generated_crate_ast, original_crate_ast, source_name, subst_dispatch, opts.unsafe_targeting);
 
-                // HACK: The generated code is currently based on the expanded AST and contains references to the internals
-                //       of macro expansions. These are patched over using a static attribute prelude (here) and a static
-                //       set of crate references (above).
-                struct NoAnn;
-                impl rustc_ast_pretty::pprust::state::PpAnn for NoAnn {}
-                let generated_crate_code = format!("{prelude}\n{code}",
-                    prelude = mutest_emit::codegen::expansion::GENERATED_CODE_PRELUDE,
-                    code = rustc_ast_pretty::pprust::print_crate(
-                        sess.source_map(),
-                        &generated_crate_ast,
-                        source_name,
-                        "".to_owned(),
-                        &NoAnn,
-                        true,
-                        Edition::Edition2021,
-                        &sess.psess.attr_id_generator,
-                    ),
-                );
+                let cfg_mutants = match subst_dispatch {
+                    SubstDispatch::Runtime => vec![],
+                    SubstDispatch::Cfg => {
+                        mutants.iter()
+                            .map(|mutant| (mutant.id, mutant.mutations.iter().map(|mutation| mutation.id).collect()))
+                            .collect()
+                    }
+                };
+
+                let mutation_descrs = match opts.mode {
+                    config::Mode::SelfCheck | config::Mode::Collect => {
+                        let mut mutations = mutants.iter().flat_map(|mutant| &mutant.mutations).collect::<Vec<_>>();
+                        mutations.sort_unstable_by_key(|mutation| mutation.id.index());
+
+                        mutations.into_iter()
+                            .map(|mutation| (mutation.id, mutation.stable_id, mutation.descriptor(tcx)))
+                            .collect()
+                    }
+                    _ => vec![],
+                };
+
+                codegen_duration = t_codegen_start.elapsed();
+                peak_rss.codegen = timings::peak_rss();
 
                 Flow::Continue(AnalysisPassResult {
                     duration: t_start.elapsed(),
+                    rustc_analysis_duration,
                     target_analysis_duration,
                     sanitize_macro_expns_duration,
                     mutation_analysis_duration,
                     mutation_batching_duration,
                     codegen_duration,
+                    peak_rss,
                     generated_crate_code,
+                    generated_mutant_crate_codes,
+                    cfg_mutants,
+                    mutation_descrs,
                 })
             })
         });
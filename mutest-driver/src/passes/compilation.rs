@@ -1,39 +1,200 @@
 use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use mutest_emit::codegen::mutation::{MutantId, MutId};
+use mutest_runtime::log;
 use rustc_feature::UnstableFeatures;
+use rustc_hash::FxHasher;
+use rustc_interface::Config as CompilerConfig;
 use rustc_interface::interface::Result as CompilerResult;
 use rustc_interface::run_compiler;
 use rustc_lint_defs::Level as LintLevel;
 use rustc_session::EarlyDiagCtxt;
-use rustc_session::config::{ExternEntry, ExternLocation, Externs, Input, OutputFilenames};
+use rustc_session::config::{CrateType, ExternEntry, ExternLocation, Externs, Input, OutFileName, OutputFilenames, OutputType};
 use rustc_session::filesearch;
+use rustc_session::output::out_filename;
 use rustc_session::search_paths::SearchPath;
 use rustc_session::utils::CanonicalizedPath;
+use rustc_span::def_id::LOCAL_CRATE;
 
 use crate::config::Config;
 use crate::passes::base_compiler_config;
 use crate::passes::analysis::AnalysisPassResult;
+use crate::timings;
 
 pub struct CompilationPassResult {
     pub duration: Duration,
-    pub outputs: Arc<OutputFilenames>,
+    /// Peak resident set size of the process during the pass, see [`timings::peak_rss`].
+    pub peak_rss: Option<u64>,
+    /// Output filenames of the compilation. `None` if the artifacts were restored from the build cache.
+    pub outputs: Option<Arc<OutputFilenames>>,
 }
 
+const BUILD_CACHE_MANIFEST_FILE_NAME: &str = "manifest";
+
+/// Entry of the build cache, holding copies of the artifacts of a compilation, keyed by all inputs of the compilation.
+///
+/// The artifacts are stored in the directory of the entry, named after their index in the manifest, which lists the
+/// original path of each artifact, one per line. The manifest is written last, so that incomplete entries are never
+/// restored.
+struct BuildCacheEntry {
+    dir: PathBuf,
+}
+
+impl BuildCacheEntry {
+    fn new(build_cache_dir: &Path, key: u64) -> Self {
+        Self { dir: build_cache_dir.join(format!("{key:016x}")) }
+    }
+
+    /// Copy the cached artifacts to their original paths. Returns `false` if the entry is not (fully) available.
+    fn restore(&self) -> bool {
+        let Ok(manifest) = fs::read_to_string(self.dir.join(BUILD_CACHE_MANIFEST_FILE_NAME)) else { return false; };
+
+        for (i, artifact_path) in manifest.lines().enumerate() {
+            if let Err(e) = fs::copy(self.dir.join(i.to_string()), artifact_path) {
+                log::warn!("cannot restore `{artifact_path}` from build cache: {e}");
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn store(&self, artifact_paths: &[PathBuf]) {
+        let store_artifacts = || -> std::io::Result<()> {
+            if self.dir.exists() { fs::remove_dir_all(&self.dir)?; }
+            fs::create_dir_all(&self.dir)?;
+
+            let mut manifest = String::new();
+            for (i, artifact_path) in artifact_paths.iter().enumerate() {
+                fs::copy(artifact_path, self.dir.join(i.to_string()))?;
+                manifest.push_str(&format!("{}\n", artifact_path.display()));
+            }
+            fs::write(self.dir.join(BUILD_CACHE_MANIFEST_FILE_NAME), manifest)
+        };
+
+        if let Err(e) = store_artifacts() {
+            log::warn!("cannot write build cache entry `{}`: {e}", self.dir.display());
+        }
+    }
+}
+
+/// Extensions of library files, which may be loaded from search paths as dependencies of the crate.
+const LIBRARY_EXTENSIONS: &[&str] = &["rlib", "rmeta", "so", "dylib", "dll", "a", "lib"];
+
+/// Hash the path, modification time and size of the file, so that rebuilding the file changes the hash, even though
+/// its path remains unchanged.
+fn hash_file_metadata(path: &Path, hasher: &mut impl Hasher) {
+    path.hash(hasher);
+    let metadata = fs::metadata(path).ok();
+    metadata.as_ref().and_then(|metadata| metadata.modified().ok()).hash(hasher);
+    metadata.map(|metadata| metadata.len()).hash(hasher);
+}
+
+/// Hash the metadata of the dependencies of the crate: the files given with `--extern`, and the library files in the
+/// `-L` search path directories, which transitive dependencies are loaded from. Other files in the search paths (e.g.
+/// test executables, including the test harness itself) are ignored.
+fn hash_dependencies(extern_paths: &[PathBuf], search_dirs: &[PathBuf], hasher: &mut impl Hasher) {
+    for extern_path in extern_paths {
+        hash_file_metadata(extern_path, hasher);
+    }
+
+    for search_dir in search_dirs {
+        let Ok(entries) = fs::read_dir(search_dir) else { continue; };
+        let mut library_paths = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().and_then(|extension| extension.to_str()).is_some_and(|extension| LIBRARY_EXTENSIONS.contains(&extension)))
+            .collect::<Vec<_>>();
+        library_paths.sort_unstable();
+
+        search_dir.hash(hasher);
+        for library_path in library_paths {
+            hash_file_metadata(&library_path, hasher);
+        }
+    }
+}
+
+/// Key of the compilation in the build cache, covering the generated crate code (and thus the mutations), the compiler
+/// configuration, and the builds of the dependencies of the crate, including the `mutest_runtime` crate linked into the
+/// test harness.
+fn build_cache_key(compiler_config: &CompilerConfig) -> u64 {
+    let mut hasher = FxHasher::default();
+
+    let Input::Str { input: code, .. } = &compiler_config.input else { unreachable!("generated crate code is not a string input") };
+    code.hash(&mut hasher);
+    compiler_config.crate_cfg.hash(&mut hasher);
+    compiler_config.crate_check_cfg.hash(&mut hasher);
+    compiler_config.expanded_args.hash(&mut hasher);
+    format!("{:?}", compiler_config.output_file).hash(&mut hasher);
+    compiler_config.opts.cg.extra_filename.hash(&mut hasher);
+    format!("{:?}", compiler_config.opts.lint_cap).hash(&mut hasher);
+    format!("{:?}", compiler_config.opts.lint_opts).hash(&mut hasher);
+
+    // Rebuilding a dependency (e.g. `mutest-runtime`) must invalidate the cache, even though the generated crate code
+    // and the paths of the dependencies remain unchanged.
+    let extern_paths = compiler_config.opts.externs.iter()
+        .flat_map(|(_, entry)| match &entry.location {
+            ExternLocation::ExactPaths(paths) => paths.iter().map(|path| path.original().to_owned()).collect::<Vec<_>>(),
+            ExternLocation::FoundInLibrarySearchDirectories => vec![],
+        })
+        .collect::<Vec<_>>();
+    let search_dirs = compiler_config.opts.search_paths.iter().map(|search_path| search_path.dir.clone()).collect::<Vec<_>>();
+    hash_dependencies(&extern_paths, &search_dirs, &mut hasher);
+
+    hasher.finish()
+}
+
+/// Compile the test harness code generated by the analysis pass.
+///
+/// With compile-time mutants (see `SubstDispatch::Cfg`), each mutant is additionally built into a separate executable
+/// next to the test harness, named after it with a `-mutant-<ID>` suffix.
 pub fn run(config: &Config, analysis_pass: &AnalysisPassResult) -> CompilerResult<CompilationPassResult> {
+    let t_start = Instant::now();
+    timings::reset_peak_rss();
+
+    let compilation_pass = compile(config, analysis_pass, None)?;
+
+    for (mutant_id, mutation_ids) in &analysis_pass.cfg_mutants {
+        compile(config, analysis_pass, Some((*mutant_id, mutation_ids)))?;
+    }
+
+    Ok(CompilationPassResult {
+        duration: t_start.elapsed(),
+        peak_rss: timings::peak_rss(),
+        outputs: compilation_pass.outputs,
+    })
+}
+
+/// Compiler configuration for the test harness code generated by the analysis pass, with the substitutions of the
+/// given mutations enabled. Substitutions can only be enabled individually with `SubstDispatch::Cfg`.
+pub(crate) fn harness_compiler_config(config: &Config, analysis_pass: &AnalysisPassResult, cfg_mutation_ids: &[MutId]) -> CompilerConfig {
     let mut compiler_config = base_compiler_config(config);
     compiler_config.input = Input::Str {
         name: compiler_config.input.source_name(),
         input: analysis_pass.generated_crate_code.to_owned(),
     };
 
+    if !cfg_mutation_ids.is_empty() {
+        compiler_config.crate_check_cfg.push("cfg(mutest_mutation, values(any()))".to_owned());
+        for &mutation_id in cfg_mutation_ids {
+            compiler_config.crate_cfg.push(mutest_emit::codegen::substitution::mutation_cfg_flag(mutation_id));
+        }
+    }
+
     // The generated crate code relies on the rustc test harness using a custom test runner.
     compiler_config.opts.test = true;
     // The generated crate code uses many unstable and internal features, most of which are emitted by rustc itself.
     compiler_config.opts.unstable_features = UnstableFeatures::Allow;
-    // Disable lints on generated crate code.
-    compiler_config.opts.lint_cap = Some(LintLevel::Allow);
+    // Lints of the crate must not fail the build of the generated crate code (e.g. with `#![deny(warnings)]`), so they
+    // are capped, unless explicitly configured otherwise. The generated code itself does not trigger lints.
+    compiler_config.opts.lint_cap = config.opts.lint_cap;
+    for allowed_lint in &config.opts.allowed_lints {
+        compiler_config.opts.lint_opts.push((allowed_lint.clone(), LintLevel::Allow));
+    }
 
     // The generated crate code relies on the `mutest_runtime` crate (and its dependencies), which must be loaded.
     let early_dcx = EarlyDiagCtxt::new(compiler_config.opts.error_format);
@@ -78,36 +239,142 @@ pub fn run(config: &Config, analysis_pass: &AnalysisPassResult) -> CompilerResul
     }
     compiler_config.opts.externs = Externs::new(externs);
 
-    let compilation_pass = run_compiler(compiler_config, |compiler| -> CompilerResult<CompilationPassResult> {
+    compiler_config
+}
+
+fn compile(config: &Config, analysis_pass: &AnalysisPassResult, cfg_mutant: Option<(MutantId, &[MutId])>) -> CompilerResult<CompilationPassResult> {
+    // Enable the substitutions of the mutations of the mutant.
+    let mut compiler_config = harness_compiler_config(config, analysis_pass, cfg_mutant.map_or(&[], |(_, mutation_ids)| mutation_ids));
+
+    if let Some((mutant_id, _)) = cfg_mutant {
+        let mutant_suffix = format!("-mutant-{}", mutant_id.index());
+        match &mut compiler_config.output_file {
+            Some(OutFileName::Real(output_file)) => {
+                let mut file_name = output_file.file_stem().unwrap_or_default().to_owned();
+                file_name.push(&mutant_suffix);
+                if let Some(extension) = output_file.extension() {
+                    file_name.push(".");
+                    file_name.push(extension);
+                }
+                output_file.set_file_name(file_name);
+            }
+            _ => compiler_config.opts.cg.extra_filename.push_str(&mutant_suffix),
+        }
+
+        // The incremental compilation cache of the crate is reserved for the test harness.
+        compiler_config.opts.incremental = None;
+    }
+
+    let build_cache_entry = config.opts.build_cache_dir.as_deref().map(|build_cache_dir| {
+        BuildCacheEntry::new(build_cache_dir, build_cache_key(&compiler_config))
+    });
+    if let Some(build_cache_entry) = &build_cache_entry && build_cache_entry.restore() {
+        log::debug!("reused cached build `{}`", build_cache_entry.dir.display());
+        return Ok(CompilationPassResult { duration: Duration::ZERO, peak_rss: None, outputs: None });
+    }
+
+    let (compilation_pass, artifact_paths) = run_compiler(compiler_config, |compiler| -> CompilerResult<(CompilationPassResult, Vec<PathBuf>)> {
         let t_start = Instant::now();
 
         let sess = &compiler.sess;
         let codegen_backend = &*compiler.codegen_backend;
 
-        let (linker, outputs) = compiler.enter(|queries| {
+        let (linker, outputs, artifact_paths) = compiler.enter(|queries| {
             queries.parse()?;
 
-            let outputs = queries.global_ctxt()?.enter(|tcx| {
+            let (outputs, artifact_paths) = queries.global_ctxt()?.enter(|tcx| {
                 let _ = tcx.resolver_for_lowering();
 
                 let outputs = tcx.output_filenames(());
 
+                // The linked test harness executable, and any other requested outputs (e.g. dep-info for Cargo).
+                let mut artifact_paths = vec![];
+                if let OutFileName::Real(path) = out_filename(tcx.sess, CrateType::Executable, outputs, tcx.crate_name(LOCAL_CRATE)) {
+                    artifact_paths.push(path);
+                }
+                for (&output_type, _) in tcx.sess.opts.output_types.iter() {
+                    if let OutputType::Exe = output_type { continue; }
+                    if let OutFileName::Real(path) = outputs.path(output_type) { artifact_paths.push(path); }
+                }
+
                 tcx.analysis(())?;
 
-                Ok(outputs.clone())
+                Ok((outputs.clone(), artifact_paths))
             })?;
 
             let linker = queries.codegen_and_build_linker()?;
-            Ok((linker, outputs))
+            Ok((linker, outputs, artifact_paths))
         })?;
 
         linker.link(sess, codegen_backend)?;
 
-        Ok(CompilationPassResult {
-            duration: t_start.elapsed(),
-            outputs,
-        })
+        Ok((CompilationPassResult { duration: t_start.elapsed(), peak_rss: None, outputs: Some(outputs) }, artifact_paths))
     })?;
 
+    if let Some(build_cache_entry) = &build_cache_entry {
+        let artifact_paths = artifact_paths.into_iter().filter(|path| path.exists()).collect::<Vec<_>>();
+        build_cache_entry.store(&artifact_paths);
+    }
+
     Ok(compilation_pass)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::{self, File};
+    use std::hash::Hasher;
+    use std::path::Path;
+    use std::process;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use rustc_hash::FxHasher;
+
+    use super::hash_dependencies;
+
+    #[test]
+    fn test_metadata_version_matches_runtime() {
+        assert_eq!(mutest_emit::codegen::harness::METADATA_VERSION, mutest_runtime::METADATA_VERSION);
+    }
+
+    #[test]
+    fn test_rebuilt_dependencies_change_build_cache_key() {
+        let deps_dir = env::temp_dir().join(format!("mutest-build-cache-deps-{}", process::id()));
+        fs::create_dir_all(&deps_dir).unwrap();
+
+        let extern_path = deps_dir.join("libdep-0123.rlib");
+        let transitive_dep_path = deps_dir.join("libtransitive-4567.rlib");
+        let test_harness_path = deps_dir.join("krate-89ab");
+
+        let build = |path: &Path, contents: &str, mtime_secs: u64| {
+            fs::write(path, contents).unwrap();
+            File::options().write(true).open(path).unwrap().set_modified(UNIX_EPOCH + Duration::from_secs(mtime_secs)).unwrap();
+        };
+        let key = || {
+            let mut hasher = FxHasher::default();
+            hash_dependencies(&[extern_path.clone()], &[deps_dir.clone()], &mut hasher);
+            hasher.finish()
+        };
+
+        build(&extern_path, "dep v1", 1_000);
+        build(&transitive_dep_path, "transitive v1", 1_000);
+        build(&test_harness_path, "harness v1", 1_000);
+        let initial_key = key();
+        assert_eq!(key(), initial_key);
+
+        // Other files in the search paths, e.g. the test harness restored from the cache, do not affect the key.
+        build(&test_harness_path, "harness v2", 2_000);
+        assert_eq!(key(), initial_key);
+
+        // A dependency rebuilt with an output of the same size, but at a later time.
+        build(&extern_path, "dep v2", 2_000);
+        let rebuilt_extern_key = key();
+        assert_ne!(rebuilt_extern_key, initial_key);
+
+        // A transitive dependency, only found in the search paths, rebuilt with a different output at the same time.
+        build(&transitive_dep_path, "transitive v2 with more code", 1_000);
+        assert_ne!(key(), rebuilt_extern_key);
+
+        fs::remove_dir_all(&deps_dir).unwrap();
+    }
+}
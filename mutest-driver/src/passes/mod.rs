@@ -115,6 +115,9 @@ impl rustc_driver::Callbacks for RustcConfigCallbacks {
     }
 }
 
+/// Parse rustc command-line arguments into a compiler configuration, without compiling the crate.
+///
+/// Must be called within the context of [`rustc_driver::catch_with_exit_code`] or equivalent.
 pub fn parse_compiler_args(args: &[String]) -> CompilerResult<Option<CompilerConfig>> {
     let mut callbacks = RustcConfigCallbacks { config: None };
     rustc_driver::RunCompiler::new(args, &mut callbacks).run()?;
@@ -160,3 +163,4 @@ pub fn base_compiler_config(config: &Config) -> CompilerConfig {
 
 pub mod analysis;
 pub mod compilation;
+pub mod self_check;
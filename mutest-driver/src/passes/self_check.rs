@@ -0,0 +1,80 @@
+use std::time::{Duration, Instant};
+
+use mutest_emit::codegen::mutation::MutId;
+use mutest_runtime::log;
+use rustc_interface::interface::Result as CompilerResult;
+use rustc_interface::run_compiler;
+use rustc_session::config::{OutputType, OutputTypes};
+use rustc_span::fatal_error::FatalError;
+
+use crate::config::Config;
+use crate::passes::analysis::AnalysisPassResult;
+use crate::passes::compilation::harness_compiler_config;
+use crate::timings;
+
+pub struct IllTypedMutation {
+    pub mutation_id: MutId,
+    pub descr: String,
+}
+
+pub struct SelfCheckPassResult {
+    pub duration: Duration,
+    /// Peak resident set size of the process during the pass, see [`timings::peak_rss`].
+    pub peak_rss: Option<u64>,
+    pub checked_mutations_count: usize,
+    pub ill_typed_mutations: Vec<IllTypedMutation>,
+}
+
+/// Type-check the generated test harness code with only the substitutions of the given mutations enabled, without
+/// producing any artifacts. Returns `false` if the code fails to type-check.
+fn check(config: &Config, analysis_pass: &AnalysisPassResult, mutation_ids: &[MutId]) -> bool {
+    let mut compiler_config = harness_compiler_config(config, analysis_pass, mutation_ids);
+    // No artifacts are produced, and the incremental compilation cache of the crate is reserved for the test harness.
+    compiler_config.opts.output_types = OutputTypes::new(&[(OutputType::Metadata, None)]);
+    compiler_config.opts.incremental = None;
+
+    let result = rustc_driver::catch_fatal_errors(|| {
+        run_compiler(compiler_config, |compiler| -> CompilerResult<()> {
+            compiler.enter(|queries| {
+                queries.parse()?;
+                queries.global_ctxt()?.enter(|tcx| tcx.analysis(()))
+            })
+        })
+    });
+
+    matches!(result, Ok(Ok(())))
+}
+
+/// Verify that each mutation is well-typed on its own, by type-checking the generated test harness code with only
+/// the substitutions of that mutation enabled.
+///
+/// Ill-typed mutations are caused by bugs in mutation operators. Rather than failing the build of the complete
+/// meta-mutant, they are reported individually, with their operator, so that they can be triaged.
+pub fn run(config: &Config, analysis_pass: &AnalysisPassResult) -> SelfCheckPassResult {
+    let t_start = Instant::now();
+    timings::reset_peak_rss();
+
+    // Without any mutations enabled, the generated code is the original code of the crate, which must type-check.
+    // Otherwise, every mutation would be reported as ill-typed.
+    if !check(config, analysis_pass, &[]) {
+        log::error!("generated test harness fails to type-check without any mutations enabled");
+        FatalError.raise();
+    }
+
+    let mut ill_typed_mutations = vec![];
+
+    for (mutation_id, _, descr) in &analysis_pass.mutation_descrs {
+        log::debug!("checking mutation {}", mutation_id.index());
+
+        if !check(config, analysis_pass, &[*mutation_id]) {
+            ill_typed_mutations.push(IllTypedMutation { mutation_id: *mutation_id, descr: descr.clone() });
+        }
+    }
+
+    SelfCheckPassResult {
+        duration: t_start.elapsed(),
+        peak_rss: timings::peak_rss(),
+        checked_mutations_count: analysis_pass.mutation_descrs.len(),
+        ill_typed_mutations,
+    }
+}
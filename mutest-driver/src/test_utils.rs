@@ -0,0 +1,222 @@
+//! Utilities for testing mutation operators, for the tests of mutest-rs, and for authors of external operators.
+//!
+//! Unlike the UI tests, which only check the end-to-end output of the tool, these collect the mutations produced by
+//! the operators for a source snippet by running the analysis pass in-process, so that they can be compared against a
+//! golden list of mutations:
+//!
+//! ```ignore
+//! let mutations = mutest_driver::test_utils::collect_mutations(source, &[&mutest_operators::RelationalOpInvert]);
+//! mutest_driver::test_utils::assert_golden(Path::new("tests/golden/relational_op_invert.txt"), &mutations);
+//! ```
+//!
+//! Golden lists are written instead of being compared against if the `MUTEST_BLESS` environment variable is set.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use mutest_emit::codegen::mutation::{MutStableId, Operators};
+use rustc_interface::interface::Result as CompilerResult;
+use rustc_session::config::Input;
+use rustc_span::{FileName, RealFileName};
+
+use crate::config::{ConfigBuilder, Mode};
+use crate::passes;
+use crate::passes::analysis::AnalysisPassResult;
+
+/// Name of the source file of snippets, as it appears in the locations of their mutations.
+pub const SNIPPET_FILE_NAME: &str = "lib.rs";
+
+/// Collect the descriptors of the mutations produced by the operators for the source snippet, in the order of their
+/// IDs (e.g. `[relational_op_invert] invert relational operator `>` for `<=` in f at lib.rs:2:5: 2:10`).
+///
+/// The snippet is compiled as a library crate in test mode. Like in regular runs, only functions reached from the tests
+/// of the snippet are mutated. Panics if the snippet fails to compile.
+pub fn collect_mutations<'op, 'm>(source: &str, operators: Operators<'op, 'm>) -> Vec<String> {
+    analyze_snippet(source, operators).mutation_descrs.into_iter().map(|(_, _, descr)| descr).collect()
+}
+
+/// Collect the stable IDs of the mutations produced by the operators for the source snippet, together with their
+/// descriptors, in the order of their IDs. See [`collect_mutations`].
+pub fn collect_mutations_with_stable_ids<'op, 'm>(source: &str, operators: Operators<'op, 'm>) -> Vec<(MutStableId, String)> {
+    analyze_snippet(source, operators).mutation_descrs.into_iter().map(|(_, stable_id, descr)| (stable_id, descr)).collect()
+}
+
+fn analyze_snippet<'op, 'm>(source: &str, operators: Operators<'op, 'm>) -> AnalysisPassResult {
+    static SNIPPETS_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    let sysroot = crate::fetch_sysroot().expect("specify SYSROOT environment variable, or use rustup or multirust");
+
+    // The compiler configuration is parsed from command-line arguments, which require an input file. The input is
+    // replaced with the snippet itself afterwards, so that the locations of mutations do not depend on the file.
+    let snippet_dir = env::temp_dir().join(format!("mutest-snippet-{}-{}", process::id(), SNIPPETS_COUNT.fetch_add(1, Ordering::Relaxed)));
+    fs::create_dir_all(&snippet_dir).expect(&format!("cannot create directory `{}`", snippet_dir.display()));
+    let snippet_path = snippet_dir.join(SNIPPET_FILE_NAME);
+    fs::write(&snippet_path, source).expect(&format!("cannot write `{}`", snippet_path.display()));
+
+    let args = [
+        "rustc".to_owned(),
+        snippet_path.display().to_string(),
+        "--crate-type=lib".to_owned(),
+        "--edition=2021".to_owned(),
+        "--out-dir".to_owned(), snippet_dir.display().to_string(),
+        "--sysroot".to_owned(), sysroot.display().to_string(),
+    ];
+
+    let result = rustc_driver::catch_fatal_errors(|| -> CompilerResult<_> {
+        let Some(mut compiler_config) = passes::parse_compiler_args(&args)? else { return Ok(None); };
+        compiler_config.input = Input::Str {
+            name: FileName::Real(RealFileName::LocalPath(PathBuf::from(SNIPPET_FILE_NAME))),
+            input: source.to_owned(),
+        };
+
+        // The generated test harness is not compiled, so the search path of `mutest-runtime` is not used.
+        let mut config = ConfigBuilder::new(compiler_config, PathBuf::new(), operators)
+            .mode(Mode::Collect)
+            .build();
+
+        passes::analysis::run(&mut config)
+    });
+
+    let _ = fs::remove_dir_all(&snippet_dir);
+
+    let Ok(Ok(Some(analysis_pass))) = result else { panic!("source snippet failed to compile"); };
+    analysis_pass
+}
+
+/// Golden list of the mutations, with one mutation descriptor per line.
+pub fn golden_mutation_list(mutations: &[String]) -> String {
+    let mut golden_mutation_list = String::new();
+    for mutation in mutations {
+        writeln!(golden_mutation_list, "{mutation}").unwrap();
+    }
+    golden_mutation_list
+}
+
+/// Compare the mutations against the golden list at the path, or write the golden list instead if the `MUTEST_BLESS`
+/// environment variable is set. Panics if the mutations do not match the golden list.
+pub fn assert_golden(golden_path: &Path, mutations: &[String]) {
+    let actual = golden_mutation_list(mutations);
+
+    if env::var_os("MUTEST_BLESS").is_some() {
+        fs::write(golden_path, &actual).expect(&format!("cannot write `{}`", golden_path.display()));
+        return;
+    }
+
+    let Ok(expected) = fs::read_to_string(golden_path) else {
+        panic!("cannot read golden mutation list `{}`, set `MUTEST_BLESS=1` to create it", golden_path.display());
+    };
+
+    if actual != expected {
+        panic!("mutations do not match golden mutation list `{}`, set `MUTEST_BLESS=1` to update it\n\nexpected:\n{expected}\nactual:\n{actual}",
+            golden_path.display(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect_mutations, collect_mutations_with_stable_ids};
+
+    #[test]
+    fn test_collect_mutations_of_snippet() {
+        let source = "\
+pub fn is_positive(x: i32) -> bool {
+    x > 0
+}
+
+#[test]
+fn test() {
+    assert!(is_positive(1));
+}
+";
+
+        let mutations = collect_mutations(source, &[&mutest_operators::RelationalOpInvert]);
+
+        assert_eq!(mutations, [
+            "[relational_op_invert] invert relational operator `>` for `<=` in is_positive at lib.rs:2:5: 2:10",
+        ]);
+    }
+
+    #[test]
+    fn test_stable_ids_unaffected_by_unrelated_changes() {
+        let source = "\
+pub fn is_positive(x: i32) -> bool {
+    x > 0
+}
+
+pub fn is_negative(x: i32) -> bool {
+    x < 0
+}
+
+#[test]
+fn test() {
+    assert!(is_positive(1));
+    assert!(is_negative(-1));
+}
+";
+        // The same functions, reordered, with unrelated items added before and between them.
+        let source_with_unrelated_changes = "\
+pub const LIMIT: i32 = 100;
+
+pub fn is_negative(x: i32) -> bool {
+    x < 0
+}
+
+pub fn unrelated(x: i32) -> i32 {
+    x * 2
+}
+
+pub fn is_positive(x: i32) -> bool {
+    x > 0
+}
+
+#[test]
+fn test() {
+    assert!(is_positive(1));
+    assert!(is_negative(-1));
+}
+";
+        // The same functions, with the mutated expression of one of them changed.
+        let source_with_changed_mutation = "\
+pub fn is_positive(x: i32) -> bool {
+    x > 1
+}
+
+pub fn is_negative(x: i32) -> bool {
+    x < 0
+}
+
+#[test]
+fn test() {
+    assert!(is_positive(2));
+    assert!(is_negative(-1));
+}
+";
+
+        let stable_ids_by_descr = |source: &str| {
+            let mut stable_ids = collect_mutations_with_stable_ids(source, &[&mutest_operators::RelationalOpInvert]).into_iter()
+                // Strip the location of the mutation from the descriptor, which changes with unrelated code.
+                .map(|(stable_id, descr)| (descr.split(" at ").next().unwrap().to_owned(), stable_id))
+                .collect::<Vec<_>>();
+            stable_ids.sort_unstable();
+            stable_ids
+        };
+
+        let stable_ids = stable_ids_by_descr(source);
+        assert_eq!(stable_ids.len(), 2);
+        assert_ne!(stable_ids[0].1, stable_ids[1].1);
+
+        assert_eq!(stable_ids_by_descr(source_with_unrelated_changes), stable_ids);
+
+        let changed_stable_ids = stable_ids_by_descr(source_with_changed_mutation);
+        let [(is_negative_descr, is_negative_stable_id), (is_positive_descr, is_positive_stable_id)] = &stable_ids[..] else { unreachable!(); };
+        assert_eq!(changed_stable_ids.len(), 2);
+        assert_eq!(&changed_stable_ids[0], &(is_negative_descr.clone(), *is_negative_stable_id));
+        assert_eq!(changed_stable_ids[1].0, *is_positive_descr);
+        assert_ne!(changed_stable_ids[1].1, *is_positive_stable_id);
+    }
+}
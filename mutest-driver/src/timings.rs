@@ -0,0 +1,74 @@
+//! Duration and peak memory usage of the phases of the driver, reported with `--timings`.
+//!
+//! The peak memory usage of a phase is measured as the peak resident set size (RSS) of the process, which is reset at
+//! the start of each phase. Memory statistics are only available on Linux, where they are read from `/proc/self`.
+
+use std::fs;
+use std::time::Duration;
+
+/// Peak resident set size of the process in bytes, since the process started or since the last call to
+/// [`reset_peak_rss`], whichever is later. Only available on Linux.
+pub fn peak_rss() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let vm_hwm = status.lines().find_map(|line| line.strip_prefix("VmHWM:"))?;
+    let kilobytes = vm_hwm.trim().strip_suffix("kB")?.trim().parse::<u64>().ok()?;
+    Some(kilobytes * 1024)
+}
+
+/// Reset the peak resident set size of the process to its current resident set size, so that the peak of the
+/// following phase can be measured. Only has an effect on Linux.
+pub fn reset_peak_rss() {
+    // Writing `5` to `clear_refs` resets the peak RSS of the process (see `proc(5)`).
+    let _ = fs::write("/proc/self/clear_refs", "5");
+}
+
+/// Breakdown of a run of the driver into its phases, in the order they were run.
+///
+/// Phases are named by their path (e.g. `analysis.mutations`), with the phases of a pass nested under the pass.
+#[derive(Default)]
+pub struct TimingsReport {
+    phases: Vec<(&'static str, Duration, Option<u64>)>,
+}
+
+impl TimingsReport {
+    pub fn phase(&mut self, name: &'static str, duration: Duration, peak_rss: Option<u64>) {
+        self.phases.push((name, duration, peak_rss));
+    }
+
+    pub fn to_json(&self, total: Duration) -> serde_json::Value {
+        serde_json::json!({
+            "total": total.as_secs_f64(),
+            "peak_rss": self.phases.iter().filter_map(|&(_, _, peak_rss)| peak_rss).max(),
+            "phases": self.phases.iter()
+                .map(|&(name, duration, peak_rss)| serde_json::json!({
+                    "name": name,
+                    "duration": duration.as_secs_f64(),
+                    "peak_rss": peak_rss,
+                }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::TimingsReport;
+
+    #[test]
+    fn test_timings_report_to_json() {
+        let mut report = TimingsReport::default();
+        report.phase("analysis", Duration::from_millis(1500), Some(2048));
+        report.phase("compilation", Duration::from_millis(500), None);
+
+        assert_eq!(report.to_json(Duration::from_secs(2)), serde_json::json!({
+            "total": 2.0,
+            "peak_rss": 2048,
+            "phases": [
+                { "name": "analysis", "duration": 1.5, "peak_rss": 2048 },
+                { "name": "compilation", "duration": 0.5, "peak_rss": null },
+            ],
+        }));
+    }
+}
@@ -4,9 +4,9 @@ use std::iter;
 use itertools::Itertools;
 use rustc_data_structures::sync::HashMapExt;
 use rustc_hash::FxHashMap;
-use rustc_middle::span_bug;
 use rustc_middle::ty::ResolverAstLowering;
 
+use crate::analysis::diagnostic::{self, DiagMutestCodeExt, InternalErrors, internal_error};
 use crate::analysis::hir;
 use crate::analysis::ty::TyCtxt;
 use crate::analysis::res;
@@ -17,6 +17,7 @@ pub struct DefResolutions {
     pub node_id_to_def_id: ast::node_id::NodeMap<hir::LocalDefId>,
     pub partial_res_map: ast::node_id::NodeMap<hir::PartialRes>,
     pub import_res_map: ast::node_id::NodeMap<hir::PerNS<Option<hir::Res<ast::NodeId>>>>,
+    pub lifetimes_res_map: ast::node_id::NodeMap<hir::LifetimeRes>,
 }
 
 impl DefResolutions {
@@ -25,6 +26,7 @@ impl DefResolutions {
             node_id_to_def_id: resolver.node_id_to_def_id.clone(),
             partial_res_map: resolver.partial_res_map.clone(),
             import_res_map: resolver.import_res_map.clone(),
+            lifetimes_res_map: resolver.lifetimes_res_map.clone(),
         }
     }
 
@@ -35,6 +37,10 @@ impl DefResolutions {
     pub fn import_res(&self, node_id: ast::NodeId) -> Option<hir::PerNS<Option<hir::Res<ast::NodeId>>>> {
         self.import_res_map.get(&node_id).copied()
     }
+
+    pub fn lifetime_res(&self, node_id: ast::NodeId) -> Option<hir::LifetimeRes> {
+        self.lifetimes_res_map.get(&node_id).copied()
+    }
 }
 
 pub mod visit {
@@ -687,6 +693,7 @@ pub mod visit {
 
             (ast_kind, hir_kind) => {
                 let mut diagnostic = visitor.tcx().dcx().struct_warn("unrecognized AST-HIR node pair");
+                diagnostic.mutest_code(diagnostic::codes::UNRECOGNIZED_AST_HIR_NODE_PAIR);
                 diagnostic.span_note(stmt_ast.span, format!("AST node: {}", ast_kind.descr()));
                 diagnostic.span_note(stmt_hir.span, format!("HIR node: {}", hir_kind.descr()));
                 diagnostic.emit();
@@ -1082,6 +1089,7 @@ pub mod visit {
 
             (ast_kind, hir_kind) => {
                 let mut diagnostic = visitor.tcx().dcx().struct_warn("unrecognized AST-HIR node pair");
+                diagnostic.mutest_code(diagnostic::codes::UNRECOGNIZED_AST_HIR_NODE_PAIR);
                 diagnostic.span_note(expr_ast.span, format!("AST node: {}", ast_kind.descr()));
                 diagnostic.span_note(expr_hir.span, format!("HIR node: {}", hir_kind.descr()));
                 diagnostic.emit();
@@ -1198,6 +1206,7 @@ pub mod visit {
 
             (ast_kind, hir_kind) => {
                 let mut diagnostic = visitor.tcx().dcx().struct_warn("unrecognized AST-HIR node pair");
+                diagnostic.mutest_code(diagnostic::codes::UNRECOGNIZED_AST_HIR_NODE_PAIR);
                 diagnostic.span_note(pat_ast.span, format!("AST node: {}", ast_kind.descr()));
                 diagnostic.span_note(pat_hir.span, format!("HIR node: {}", hir_kind.descr()));
                 diagnostic.emit();
@@ -1322,6 +1331,7 @@ pub mod visit {
 
             (ast_kind, hir_kind) => {
                 let mut diagnostic = visitor.tcx().dcx().struct_warn("unrecognized AST-HIR node pair");
+                diagnostic.mutest_code(diagnostic::codes::UNRECOGNIZED_AST_HIR_NODE_PAIR);
                 diagnostic.span_note(ty_ast.span, format!("AST node: {}", ast_kind.descr()));
                 diagnostic.span_note(ty_hir.span, format!("HIR node: {}", hir_kind.descr()));
                 diagnostic.emit();
@@ -1455,6 +1465,7 @@ pub mod visit {
             }
             _ => {
                 let mut diagnostic = visitor.tcx().dcx().struct_warn("unrecognized AST-HIR node pair");
+                diagnostic.mutest_code(diagnostic::codes::UNRECOGNIZED_AST_HIR_NODE_PAIR);
                 diagnostic.span_note(generic_arg_ast.span(), format!("AST node: {}", match generic_arg_ast {
                     ast::GenericArg::Lifetime(_) => "lifetime",
                     ast::GenericArg::Type(_) => "type",
@@ -1924,30 +1935,47 @@ pub fn resolve_fn_body<'tcx>(tcx: TyCtxt<'tcx>, def_res: &DefResolutions, fn_ast
     collector.finalize()
 }
 
-struct BodyMetaVisitor<T> {
+struct BodyMetaVisitor<'e, T> {
     visitor: T,
+    /// Items skipped due to internal errors while matching them to the HIR.
+    internal_errors: &'e mut InternalErrors,
+}
+
+impl<'ast, 'hir, 'e, T: visit::AstHirVisitor<'ast, 'hir>> BodyMetaVisitor<'e, T> {
+    /// Visit the item with its corresponding HIR node, returning whether the visit completed without internal errors.
+    fn visit_with_hir_node<I: visit::VisitWithHirNode>(&mut self, item: &'ast I, def_id: hir::LocalDefId) -> bool {
+        let tcx = self.visitor.tcx();
+        let node_hir = tcx.hir_node_by_def_id(def_id);
+
+        let visitor = &mut self.visitor;
+        match diagnostic::catch_internal_error(tcx, def_id, "matching the syntax tree of", || visit::VisitWithHirNode::visit(item, visitor, node_hir)) {
+            Ok(_) => true,
+            Err(error) => {
+                self.internal_errors.push(error);
+                false
+            }
+        }
+    }
 }
 
-impl<'ast, 'hir, T: visit::AstHirVisitor<'ast, 'hir>> ast::visit::Visitor<'ast> for BodyMetaVisitor<T> {
+impl<'ast, 'hir, 'e, T: visit::AstHirVisitor<'ast, 'hir>> ast::visit::Visitor<'ast> for BodyMetaVisitor<'e, T> {
     fn visit_item(&mut self, item: &'ast ast::Item) {
         let Some(&def_id) = self.visitor.def_res().node_id_to_def_id.get(&item.id) else { return; };
-        let node_hir = self.visitor.tcx().hir_node_by_def_id(def_id);
-        visit::VisitWithHirNode::visit(item, &mut self.visitor, node_hir);
+        if !self.visit_with_hir_node(item, def_id) { return; }
 
         ast::visit::walk_item(self, item);
     }
 
     fn visit_assoc_item(&mut self, assoc_item: &'ast ast::AssocItem, _assoc_ctxt: ast::visit::AssocCtxt) {
         let Some(&def_id) = self.visitor.def_res().node_id_to_def_id.get(&assoc_item.id) else { return; };
-        let node_hir = self.visitor.tcx().hir_node_by_def_id(def_id);
-        visit::VisitWithHirNode::visit(assoc_item, &mut self.visitor, node_hir);
+        if !self.visit_with_hir_node(assoc_item, def_id) { return; }
 
         ast::visit::walk_item(self, assoc_item);
     }
 }
 
-pub fn resolve_bodies<'tcx>(tcx: TyCtxt<'tcx>, def_res: &DefResolutions, krate_ast: &ast::Crate) -> BodyResolutions<'tcx> {
-    let mut body_visitor = BodyMetaVisitor { visitor: BodyResolutionsCollector::new(tcx, def_res) };
+pub fn resolve_bodies<'tcx>(tcx: TyCtxt<'tcx>, def_res: &DefResolutions, krate_ast: &ast::Crate, internal_errors: &mut InternalErrors) -> BodyResolutions<'tcx> {
+    let mut body_visitor = BodyMetaVisitor { visitor: BodyResolutionsCollector::new(tcx, def_res), internal_errors };
     ast::visit::Visitor::visit_crate(&mut body_visitor, krate_ast);
     body_visitor.visitor.finalize()
 }
@@ -1965,6 +1993,7 @@ impl<'tcx, 'op> BodyResValidator<'tcx, 'op> {
 
         let Some(_hir_id) = self.body_res.hir_id(node_id) else {
             let mut diagnostic = tcx.dcx().struct_warn(format!("invalid AST-HIR mapping for {node_descr}"));
+            diagnostic.mutest_code(diagnostic::codes::INVALID_AST_HIR_MAPPING);
             diagnostic.span(span);
             diagnostic.span_label(span, "no matching HIR node found");
             diagnostic.emit();
@@ -2667,7 +2696,7 @@ pub fn find_def_in_ast<'ast, 'tcx>(tcx: TyCtxt<'tcx>, def_res: &DefResolutions,
     if let Some(def_item) = finder.result {
         let found_def_id = def_res.node_id_to_def_id.get(&def_item.node_id()).copied();
         if found_def_id != Some(def_id) {
-            span_bug!(def_item.span(), "found mismatched syntax item for {}", tcx.def_path_str(def_id));
+            internal_error!(def_item.span(), "found mismatched syntax item for {}", tcx.def_path_str(def_id));
         }
     }
 
@@ -5,14 +5,15 @@ use rustc_middle::mir;
 use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
 
 use crate::analysis::ast_lowering;
+use crate::analysis::diagnostic::{self, DiagMutestCodeExt};
 use crate::analysis::hir;
 use crate::analysis::res;
 use crate::analysis::tests::{self, Test};
-use crate::analysis::ty::{self, TyCtxt};
+use crate::analysis::ty::{self, TyCtxt, TypeVisitableExt};
 use crate::codegen::ast;
 use crate::codegen::ast::visit::Visitor;
 use crate::codegen::mutation::{UnsafeTargeting};
-use crate::codegen::symbols::{DUMMY_SP, Span, sym};
+use crate::codegen::symbols::{DUMMY_SP, Span, Symbol, sym};
 use crate::codegen::tool_attr;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -34,6 +35,12 @@ impl Unsafety {
         !matches!(self, Self::None)
     }
 
+    /// Returns whether targets of this unsafety are excluded from mutation under the unsafe targeting policy.
+    pub fn is_excluded(&self, unsafe_targeting: UnsafeTargeting) -> bool {
+        (!unsafe_targeting.any() && self.any())
+            || (!unsafe_targeting.inside_unsafe() && matches!(self, Self::Unsafe(UnsafeSource::Unsafe) | Self::Tainted(UnsafeSource::Unsafe)))
+    }
+
     pub fn is_unsafe(&self, unsafe_targeting: UnsafeTargeting) -> bool {
         matches!((unsafe_targeting, self),
             | (_, Unsafety::Unsafe(UnsafeSource::Unsafe) | Unsafety::Tainted(UnsafeSource::Unsafe))
@@ -43,6 +50,19 @@ impl Unsafety {
     }
 }
 
+/// Policy of how the unsafety of call paths taints the safe functions called along them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum UnsafeTaintPolicy {
+    /// Every function called from an unsafe context, directly or transitively, is tainted, including through unsafe
+    /// contexts of other crates (e.g. trait implementations called from `unsafe` blocks of the standard library).
+    Transitive,
+    /// Like `Transitive`, but only unsafe contexts of local functions taint their callees.
+    Local,
+    /// Only functions called directly from unsafe contexts of local functions are tainted. Taint does not propagate to
+    /// their callees, e.g. to `Drop` implementations called through `ptr::drop_in_place`.
+    Direct,
+}
+
 struct BodyUnsafetyChecker {
     unsafety: Option<Unsafety>,
 }
@@ -117,47 +137,177 @@ pub struct Target<'tst> {
     pub unsafety: Unsafety,
     pub reachable_from: FxHashMap<&'tst Test, EntryPointAssociation>,
     pub distance: usize,
+    /// Local override of the unsafe targeting policy, set with `#[mutest::unsafe_policy]`.
+    pub unsafe_policy: Option<UnsafeTargeting>,
+    /// Note attached to the mutations of the target, set with `#[mutest::note]`.
+    pub note: Option<Symbol>,
 }
 
 impl<'tst> Target<'tst> {
+    /// The unsafe targeting policy applying to the target, which is the global policy, unless it is overridden locally.
+    pub fn unsafe_targeting(&self, unsafe_targeting: UnsafeTargeting) -> UnsafeTargeting {
+        self.unsafe_policy.unwrap_or(unsafe_targeting)
+    }
+
+    pub fn is_unsafe(&self, unsafe_targeting: UnsafeTargeting) -> bool {
+        self.unsafety.is_unsafe(self.unsafe_targeting(unsafe_targeting))
+    }
+
+    pub fn is_excluded(&self, unsafe_targeting: UnsafeTargeting) -> bool {
+        self.unsafety.is_excluded(self.unsafe_targeting(unsafe_targeting))
+    }
+
     pub fn is_tainted(&self, entry_point: &Test, unsafe_targeting: UnsafeTargeting) -> bool {
         self.reachable_from.get(entry_point).is_some_and(|entry_point| {
             let unsafety = entry_point.unsafe_call_path.map(Unsafety::Tainted).unwrap_or(Unsafety::None);
-            unsafety.is_unsafe(unsafe_targeting)
+            unsafety.is_unsafe(self.unsafe_targeting(unsafe_targeting))
         })
     }
 }
 
-/// All functions we can introduce mutations in.
-/// Does not include closures, as they are (currently) considered part of their containing function, rather than
-/// standalone functions. This might change in the future.
-pub fn all_mutable_fns<'tcx, 'tst>(tcx: TyCtxt<'tcx>, tests: &'tst [Test]) -> impl Iterator<Item = hir::LocalDefId> + 'tcx {
+struct FnRefCollector<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    typeck: &'tcx ty::TypeckResults<'tcx>,
+    fn_refs: Vec<hir::DefId>,
+}
+
+impl<'tcx> hir::intravisit::Visitor<'tcx> for FnRefCollector<'tcx> {
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr<'tcx>) {
+        // NOTE: Both calls and references to function items (e.g. as function pointers) are collected.
+        if let Some(expr_ty) = self.typeck.node_type_opt(expr.hir_id) && let ty::FnDef(def_id, _) = *expr_ty.kind() {
+            self.fn_refs.push(def_id);
+        }
+        if let Some((def_id, _)) = res::callee(self.typeck, expr) {
+            self.fn_refs.push(def_id);
+        }
+
+        if let hir::ExprKind::Closure(closure) = expr.kind {
+            self.visit_expr(self.tcx.hir().body(closure.body).value);
+        }
+
+        hir::intravisit::walk_expr(self, expr);
+    }
+}
+
+/// Local `const fn`s which may be evaluated at compile-time, i.e. which are (transitively) referenced from a `const`
+/// context, such as `const` and `static` items, array lengths, or const generic arguments.
+pub fn const_eval_reachable_fns<'tcx>(tcx: TyCtxt<'tcx>) -> FxHashSet<hir::LocalDefId> {
+    let mut reachable_fns: FxHashSet<hir::LocalDefId> = Default::default();
+
+    let mut body_owners_to_visit = tcx.hir().body_owners()
+        .filter(|&body_owner| matches!(tcx.hir().body_const_context(body_owner), Some(hir::ConstContext::Const { .. } | hir::ConstContext::Static(_))))
+        .collect::<Vec<_>>();
+
+    while let Some(body_owner) = body_owners_to_visit.pop() {
+        let Some(body_id) = tcx.hir_node_by_def_id(body_owner).body_id() else { continue; };
+        let body = tcx.hir().body(body_id);
+
+        let mut collector = FnRefCollector { tcx, typeck: tcx.typeck(body_owner), fn_refs: vec![] };
+        hir::intravisit::Visitor::visit_body(&mut collector, body);
+
+        for def_id in collector.fn_refs {
+            let Some(local_def_id) = def_id.as_local() else { continue; };
+            if !tcx.is_const_fn(def_id) { continue; }
+
+            if reachable_fns.insert(local_def_id) {
+                body_owners_to_visit.push(local_def_id);
+            }
+        }
+    }
+
+    reachable_fns
+}
+
+/// Local `const fn`s which are never evaluated at compile-time.
+///
+/// These can be mutated like any other function, once their `const` qualifier is removed.
+pub fn runtime_only_const_fns<'tcx>(tcx: TyCtxt<'tcx>) -> FxHashSet<hir::LocalDefId> {
+    let const_eval_reachable_fns = const_eval_reachable_fns(tcx);
+
+    tcx.hir_crate_items(()).definitions()
+        .filter(|&local_def_id| matches!(tcx.def_kind(local_def_id), hir::DefKind::Fn | hir::DefKind::AssocFn))
+        .filter(|&local_def_id| tcx.is_const_fn(local_def_id.to_def_id()))
+        .filter(|local_def_id| !const_eval_reachable_fns.contains(local_def_id))
+        .collect()
+}
+
+/// Reason for a function of the crate not being mutable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NonMutableFnReason {
+    /// `fn main() {}`
+    EntryPoint,
+    /// `const fn`, unless it is never evaluated at compile-time, and mutating const fns was requested.
+    ConstFn,
+    /// `fn;`, i.e. functions without a body, such as required trait methods.
+    NoBody,
+    /// `#[test]` functions, or inner functions.
+    Test,
+    /// `#[cfg(test)]` functions, or functions in `#[cfg(test)]` module.
+    CfgTest,
+    /// `#[mutest::skip]` functions.
+    SkipAttr,
+}
+
+impl NonMutableFnReason {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::EntryPoint => "entry_point",
+            Self::ConstFn => "const_fn",
+            Self::NoBody => "no_body",
+            Self::Test => "test",
+            Self::CfgTest => "cfg_test",
+            Self::SkipAttr => "mutest_skip",
+        }
+    }
+
+    pub fn descr(&self) -> &'static str {
+        match self {
+            Self::EntryPoint => "entry point of the program",
+            Self::ConstFn => "`const fn`, which may be evaluated at compile-time",
+            Self::NoBody => "function without a body",
+            Self::Test => "test function, or function nested in a test function",
+            Self::CfgTest => "marked with `#[cfg(test)]`, or in a `#[cfg(test)]` module",
+            Self::SkipAttr => "marked with `#[mutest::skip]`",
+        }
+    }
+}
+
+/// All functions of the crate, along with the reason they cannot be mutated, if any.
+pub fn all_fns_with_mutability<'tcx, 'tst>(tcx: TyCtxt<'tcx>, tests: &'tst [Test], mutable_const_fns: &FxHashSet<hir::LocalDefId>) -> impl Iterator<Item = (hir::LocalDefId, Result<(), NonMutableFnReason>)> + 'tcx {
     let entry_fn = tcx.entry_fn(());
     let test_def_ids = tests.iter().map(|test| test.def_id).collect::<FxHashSet<_>>();
+    let mutable_const_fns = mutable_const_fns.clone();
 
     tcx.hir_crate_items(()).definitions()
-        .filter(move |&local_def_id| {
+        .filter(move |&local_def_id| matches!(tcx.def_kind(local_def_id), hir::DefKind::Fn | hir::DefKind::AssocFn))
+        .map(move |local_def_id| {
             let def_id = local_def_id.to_def_id();
             let hir_id = tcx.local_def_id_to_hir_id(local_def_id);
 
             // TODO: Ignore #[coverage(off)] functions
-            matches!(tcx.def_kind(def_id), hir::DefKind::Fn | hir::DefKind::AssocFn)
-                // fn main() {}
-                && !entry_fn.map(|(entry_def_id, _)| def_id == entry_def_id).unwrap_or(false)
-                // const fn
-                && !tcx.is_const_fn(def_id)
-                // fn;
-                && !tcx.hir_node_by_def_id(local_def_id).body_id().is_none()
-                // #[test] functions, or inner functions
-                && !test_def_ids.contains(&local_def_id)
-                && !res::parent_iter(tcx, def_id).any(|parent_id| parent_id.as_local().is_some_and(|local_parent_id| test_def_ids.contains(&local_parent_id)))
-                // #[cfg(test)] functions, or functions in #[cfg(test)] module
-                && !tests::is_marked_or_in_cfg_test(tcx, hir_id)
-                // #[mutest::skip] functions
-                && !tool_attr::skip(tcx.hir().attrs(hir_id))
+            let reason = 'reason: {
+                if entry_fn.is_some_and(|(entry_def_id, _)| def_id == entry_def_id) { break 'reason Some(NonMutableFnReason::EntryPoint); }
+                if tcx.is_const_fn(def_id) && !mutable_const_fns.contains(&local_def_id) { break 'reason Some(NonMutableFnReason::ConstFn); }
+                if tcx.hir_node_by_def_id(local_def_id).body_id().is_none() { break 'reason Some(NonMutableFnReason::NoBody); }
+                if test_def_ids.contains(&local_def_id) { break 'reason Some(NonMutableFnReason::Test); }
+                if res::parent_iter(tcx, def_id).any(|parent_id| parent_id.as_local().is_some_and(|local_parent_id| test_def_ids.contains(&local_parent_id))) { break 'reason Some(NonMutableFnReason::Test); }
+                if tests::is_marked_or_in_cfg_test(tcx, hir_id) { break 'reason Some(NonMutableFnReason::CfgTest); }
+                if tool_attr::skip(tcx.hir().attrs(hir_id)) { break 'reason Some(NonMutableFnReason::SkipAttr); }
+                None
+            };
+
+            (local_def_id, reason.map_or(Ok(()), Err))
         })
 }
 
+/// All functions we can introduce mutations in.
+/// Does not include closures, as they are (currently) considered part of their containing function, rather than
+/// standalone functions. This might change in the future.
+pub fn all_mutable_fns<'tcx, 'tst>(tcx: TyCtxt<'tcx>, tests: &'tst [Test], mutable_const_fns: &FxHashSet<hir::LocalDefId>) -> impl Iterator<Item = hir::LocalDefId> + 'tcx {
+    all_fns_with_mutability(tcx, tests, mutable_const_fns)
+        .filter_map(|(local_def_id, mutability)| mutability.is_ok().then_some(local_def_id))
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum CallKind<'tcx> {
     Def(hir::DefId, ty::GenericArgsRef<'tcx>),
@@ -241,6 +391,27 @@ pub fn mir_callees<'tcx>(tcx: TyCtxt<'tcx>, body_mir: &'tcx mir::Body<'tcx>, gen
         .collect::<FxHashSet<_>>()
 }
 
+/// Calls made by the body of a local function, collected from its HIR, rather than its MIR. Used for local functions
+/// without available MIR. Unlike [`mir_callees`], this does not include the calls made in nested closures, and the calls
+/// made through calls to closures, so the resulting call graph edges are approximate.
+pub fn hir_callees<'tcx>(tcx: TyCtxt<'tcx>, local_def_id: hir::LocalDefId, generic_args: ty::GenericArgsRef<'tcx>) -> FxHashSet<Call<'tcx>> {
+    let Some(body_id) = tcx.hir_node_by_def_id(local_def_id).body_id() else { return Default::default(); };
+    let param_env = ty::ParamEnv::reveal_all();
+
+    res::collect_callees(tcx, tcx.hir().body(body_id)).into_iter()
+        .map(|call| {
+            let kind = match call.kind {
+                CallKind::Def(def_id, call_generic_args) => {
+                    let call_generic_args = tcx.instantiate_and_normalize_erasing_regions(generic_args, param_env, ty::EarlyBinder::bind(call_generic_args));
+                    CallKind::Def(def_id, call_generic_args)
+                }
+                kind @ CallKind::Ptr(_) => kind,
+            };
+            Call { kind, ..call }
+        })
+        .collect()
+}
+
 pub fn drop_glue_callees<'tcx>(tcx: TyCtxt<'tcx>, body_mir: &'tcx mir::Body<'tcx>, generic_args: ty::GenericArgsRef<'tcx>) -> impl Iterator<Item = Call<'tcx>> {
     let instance = ty::Instance { def: body_mir.source.instance, args: generic_args };
     let param_env = ty::ParamEnv::reveal_all();
@@ -283,6 +454,8 @@ pub struct CallGraph<'tcx> {
     pub virtual_calls_count: usize,
     pub dynamic_calls_count: usize,
     pub foreign_calls_count: usize,
+    /// Number of calls collected from the HIR of local functions without available MIR, which may be incomplete.
+    pub approximated_calls_count: usize,
     pub root_calls: FxHashSet<(hir::LocalDefId, Callee<'tcx>)>,
     pub nested_calls: Vec<FxHashSet<(Callee<'tcx>, Callee<'tcx>)>>,
 }
@@ -296,8 +469,28 @@ impl<'tcx> CallGraph<'tcx> {
 
         total_calls_count
     }
+
+    /// Generic arguments of the monomorphic instantiations of each local generic function, observed in the call graph.
+    pub fn instantiations(&self) -> Instantiations<'tcx> {
+        let callees = Iterator::chain(
+            self.root_calls.iter().map(|(_, callee)| callee),
+            self.nested_calls.iter().flatten().map(|(_, callee)| callee),
+        );
+
+        let mut instantiations: Instantiations<'tcx> = Default::default();
+        for callee in callees {
+            let Some(local_def_id) = callee.def_id.as_local() else { continue; };
+            if callee.generic_args.is_empty() || callee.generic_args.has_non_region_param() { continue; }
+
+            let generic_args = instantiations.entry(local_def_id).or_default();
+            if !generic_args.contains(&callee.generic_args) { generic_args.push(callee.generic_args); }
+        }
+        instantiations
+    }
 }
 
+pub type Instantiations<'tcx> = FxHashMap<hir::LocalDefId, Vec<ty::GenericArgsRef<'tcx>>>;
+
 pub fn instantiate_generic_args<'tcx, T>(tcx: TyCtxt<'tcx>, foldable: T, generic_args: ty::GenericArgsRef<'tcx>) -> T
 where
     T: ty::TypeFoldable<TyCtxt<'tcx>>,
@@ -311,11 +504,14 @@ pub fn reachable_fns<'ast, 'tcx, 'tst>(
     krate: &'ast ast::Crate,
     tests: &'tst [Test],
     depth: usize,
+    mutable_const_fns: &FxHashSet<hir::LocalDefId>,
+    unsafe_taint_policy: UnsafeTaintPolicy,
 ) -> (CallGraph<'tcx>, Vec<Target<'tst>>) {
     let mut call_graph = CallGraph {
         virtual_calls_count: 0,
         dynamic_calls_count: 0,
         foreign_calls_count: 0,
+        approximated_calls_count: 0,
         root_calls: Default::default(),
         nested_calls: iter::repeat_with(|| Default::default()).take(depth - 1).collect(),
     };
@@ -338,6 +534,8 @@ pub fn reachable_fns<'ast, 'tcx, 'tst>(
     ///     }
     /// }
     /// ```
+    ///
+    /// How far unsafety taints along call paths is determined by the [`UnsafeTaintPolicy`].
     type CallPaths<'tst> = FxHashMap<&'tst Test, Option<UnsafeSource>>;
 
     let test_def_ids = tests.iter().map(|test| test.def_id).collect::<FxHashSet<_>>();
@@ -367,6 +565,7 @@ pub fn reachable_fns<'ast, 'tcx, 'tst>(
                         call_graph.virtual_calls_count += 1;
 
                         let mut diagnostic = tcx.dcx().struct_warn("encountered virtual call during call graph construction");
+                        diagnostic.mutest_code(diagnostic::codes::VIRTUAL_CALL);
                         diagnostic.span(call.span);
                         diagnostic.span_label(call.span, format!("call to {}", tcx.def_path_str_with_args(def_id, instance.args)));
                         diagnostic.note(format!("in {}", tcx.def_path_str(test.def_id)));
@@ -386,6 +585,7 @@ pub fn reachable_fns<'ast, 'tcx, 'tst>(
                             call_graph.foreign_calls_count += 1;
 
                             let mut diagnostic = tcx.dcx().struct_warn("encountered foreign call during call graph construction");
+                            diagnostic.mutest_code(diagnostic::codes::FOREIGN_CALL);
                             diagnostic.span(call.span);
                             diagnostic.span_label(call.span, format!("call to {}", tcx.def_path_str_with_args(instance.def_id(), instance.args)));
                             diagnostic.note(format!("in {}", tcx.def_path_str(test.def_id)));
@@ -400,6 +600,7 @@ pub fn reachable_fns<'ast, 'tcx, 'tst>(
                     call_graph.dynamic_calls_count += 1;
 
                     let mut diagnostic = tcx.dcx().struct_warn("encountered dynamic call during call graph construction");
+                    diagnostic.mutest_code(diagnostic::codes::DYNAMIC_CALL);
                     diagnostic.span(call.span);
                     diagnostic.span_label(call.span, format!("call to {fn_sig}"));
                     diagnostic.note(format!("in {}", tcx.def_path_str(test.def_id)));
@@ -422,8 +623,10 @@ pub fn reachable_fns<'ast, 'tcx, 'tst>(
         let mut newly_found_callees: FxHashMap<Callee<'tcx>, CallPaths<'tst>> = Default::default();
 
         for (caller, call_paths) in previously_found_callees.drain() {
-            // `const` functions, like other `const` scopes, cannot be mutated.
-            if tcx.is_const_fn(caller.def_id) { continue; }
+            // `const` functions, like other `const` scopes, cannot be mutated,
+            // unless they are never evaluated at compile-time, and are explicitly allowed to be mutated.
+            let is_mutable_const_fn = caller.def_id.as_local().is_some_and(|local_def_id| mutable_const_fns.contains(&local_def_id));
+            if tcx.is_const_fn(caller.def_id) && !is_mutable_const_fn { continue; }
 
             if let Some(local_def_id) = caller.def_id.as_local() {
                 if !tcx.hir_node_by_def_id(local_def_id).body_id().is_some() { continue; }
@@ -446,6 +649,8 @@ pub fn reachable_fns<'ast, 'tcx, 'tst>(
                             unsafety: check_item_unsafety(caller_def_item),
                             reachable_from: Default::default(),
                             distance,
+                            unsafe_policy: tool_attr::unsafe_policy(tcx, local_def_id),
+                            note: tool_attr::note(tcx, local_def_id),
                         }
                     });
 
@@ -469,11 +674,31 @@ pub fn reachable_fns<'ast, 'tcx, 'tst>(
             // NOTE: This is not performed on the last depth iteration; calls made by
             //       callees at the end of the call graph are ignored.
             if distance < (depth - 1) {
-                if !tcx.is_mir_available(caller.def_id) { continue; }
-                let body_mir = tcx.instance_mir(ty::InstanceDef::Item(caller.def_id));
+                let callees = match tcx.is_mir_available(caller.def_id) {
+                    true => {
+                        let body_mir = tcx.instance_mir(ty::InstanceDef::Item(caller.def_id));
 
-                let mut callees = mir_callees(tcx, &body_mir, caller.generic_args);
-                callees.extend(drop_glue_callees(tcx, &body_mir, caller.generic_args));
+                        let mut callees = mir_callees(tcx, &body_mir, caller.generic_args);
+                        callees.extend(drop_glue_callees(tcx, &body_mir, caller.generic_args));
+                        callees
+                    }
+                    false => {
+                        // Local functions without available MIR would otherwise silently lose their call edges, so we
+                        // fall back to collecting their calls from HIR, and report the approximation.
+                        let Some(local_def_id) = caller.def_id.as_local() else { continue; };
+                        let callees = hir_callees(tcx, local_def_id, caller.generic_args);
+                        call_graph.approximated_calls_count += callees.len();
+
+                        let mut diagnostic = tcx.dcx().struct_warn("approximated calls from HIR during call graph construction");
+                        diagnostic.mutest_code(diagnostic::codes::APPROXIMATED_CALLS);
+                        diagnostic.span(tcx.def_span(caller.def_id));
+                        diagnostic.note(format!("MIR is not available for {}", tcx.def_path_str_with_args(caller.def_id, caller.generic_args)));
+                        diagnostic.note("calls made through closures are not included in the call graph");
+                        diagnostic.emit();
+
+                        callees
+                    }
+                };
 
                 for call in callees {
                     // NOTE: We are post type-checking, querying monomorphic obligations.
@@ -493,6 +718,7 @@ pub fn reachable_fns<'ast, 'tcx, 'tst>(
                                 call_graph.virtual_calls_count += 1;
 
                                 let mut diagnostic = tcx.dcx().struct_warn("encountered virtual call during call graph construction");
+                                diagnostic.mutest_code(diagnostic::codes::VIRTUAL_CALL);
                                 diagnostic.span(call.span);
                                 diagnostic.span_label(call.span, format!("call to {}", tcx.def_path_str_with_args(def_id, instance.args)));
                                 diagnostic.note(format!("in {}", tcx.def_path_str_with_args(caller.def_id, caller.generic_args)));
@@ -512,6 +738,7 @@ pub fn reachable_fns<'ast, 'tcx, 'tst>(
                                     call_graph.foreign_calls_count += 1;
 
                                     let mut diagnostic = tcx.dcx().struct_warn("encountered foreign call during call graph construction");
+                                    diagnostic.mutest_code(diagnostic::codes::FOREIGN_CALL);
                                     diagnostic.span(call.span);
                                     diagnostic.span_label(call.span, format!("call to {}", tcx.def_path_str_with_args(instance.def_id(), instance.args)));
                                     diagnostic.note(format!("in {}", tcx.def_path_str_with_args(caller.def_id, caller.generic_args)));
@@ -526,6 +753,7 @@ pub fn reachable_fns<'ast, 'tcx, 'tst>(
                             call_graph.dynamic_calls_count += 1;
 
                             let mut diagnostic = tcx.dcx().struct_warn("encountered dynamic call during call graph construction");
+                            diagnostic.mutest_code(diagnostic::codes::DYNAMIC_CALL);
                             diagnostic.span(call.span);
                             diagnostic.span_label(call.span, format!("call to {fn_sig}"));
                             diagnostic.note(format!("in {}", tcx.def_path_str_with_args(caller.def_id, caller.generic_args)));
@@ -539,13 +767,23 @@ pub fn reachable_fns<'ast, 'tcx, 'tst>(
 
                     let new_call_paths = newly_found_callees.entry(callee).or_insert_with(Default::default);
 
+                    let is_unsafe_call = match unsafe_taint_policy {
+                        UnsafeTaintPolicy::Transitive => call.unsafety == hir::Unsafety::Unsafe,
+                        UnsafeTaintPolicy::Local | UnsafeTaintPolicy::Direct => call.unsafety == hir::Unsafety::Unsafe && caller.def_id.is_local(),
+                    };
+
                     for (&test, &unsafety) in &call_paths {
-                        let unsafe_source = match call.unsafety {
-                            hir::Unsafety::Normal => unsafety,
-                            hir::Unsafety::Unsafe => Some(UnsafeSource::Unsafe),
+                        let inherited_unsafety = match unsafe_taint_policy {
+                            UnsafeTaintPolicy::Transitive | UnsafeTaintPolicy::Local => unsafety,
+                            UnsafeTaintPolicy::Direct => None,
+                        };
+
+                        let unsafe_source = match is_unsafe_call {
+                            false => inherited_unsafety,
+                            true => Some(UnsafeSource::Unsafe),
                         };
 
-                        let new_unsafety = new_call_paths.entry(test).or_insert(unsafety);
+                        let new_unsafety = new_call_paths.entry(test).or_insert(inherited_unsafety);
                         *new_unsafety = new_unsafety.or(unsafe_source);
                     }
                 }
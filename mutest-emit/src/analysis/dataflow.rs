@@ -0,0 +1,142 @@
+//! Dataflow facts of function bodies, computed from their MIR.
+//!
+//! These facts allow mutation operators to avoid generating mutations which are no-ops or always equivalent, e.g.
+//! replacing the value of an expression which is never read. Computing the MIR of a body is expensive, so facts are
+//! only computed for a body once they are first requested.
+//!
+//! All facts are conservative: if the MIR of the body does not have a counterpart for the given node (e.g. because
+//! it was optimized away, or because it is part of a closure body), the fact is unknown, and `None` is returned.
+
+use std::cell::OnceCell;
+
+use rustc_index::IndexVec;
+use rustc_middle::mir;
+use rustc_middle::mir::visit::{MutatingUseContext, PlaceContext, Visitor};
+
+use crate::analysis::hir;
+use crate::analysis::ty::TyCtxt;
+use crate::codegen::symbols::Span;
+
+#[derive(Default)]
+struct LocalUses {
+    reads: Vec<Span>,
+    writes: Vec<Span>,
+}
+
+struct LocalUsesCollector<'tcx, 'a> {
+    body: &'tcx mir::Body<'tcx>,
+    local_uses: &'a mut IndexVec<mir::Local, LocalUses>,
+}
+
+impl<'tcx, 'a> Visitor<'tcx> for LocalUsesCollector<'tcx, 'a> {
+    fn visit_local(&mut self, local: mir::Local, context: PlaceContext, location: mir::Location) {
+        let span = self.body.source_info(location).span;
+        let uses = &mut self.local_uses[local];
+
+        match context {
+            PlaceContext::NonUse(_) => {}
+            PlaceContext::NonMutatingUse(_) => uses.reads.push(span),
+            // Mutable borrows and raw pointers may be used both for reading and writing the local.
+            PlaceContext::MutatingUse(MutatingUseContext::Borrow | MutatingUseContext::AddressOf) => {
+                uses.reads.push(span);
+                uses.writes.push(span);
+            }
+            // Projections (e.g. field accesses) of the local are reported as uses of the local.
+            PlaceContext::MutatingUse(MutatingUseContext::Projection) => uses.reads.push(span),
+            PlaceContext::MutatingUse(_) => uses.writes.push(span),
+        }
+    }
+}
+
+/// Dataflow facts of a function body.
+pub struct BodyFacts<'tcx> {
+    body: &'tcx mir::Body<'tcx>,
+    local_uses: IndexVec<mir::Local, LocalUses>,
+}
+
+impl<'tcx> BodyFacts<'tcx> {
+    pub fn compute(tcx: TyCtxt<'tcx>, def_id: hir::LocalDefId) -> Option<Self> {
+        if !tcx.is_mir_available(def_id) { return None; }
+        let body = tcx.optimized_mir(def_id);
+
+        let mut local_uses = IndexVec::from_fn_n(|_| LocalUses::default(), body.local_decls.len());
+        LocalUsesCollector { body, local_uses: &mut local_uses }.visit_body(body);
+
+        Some(Self { body, local_uses })
+    }
+
+    /// Returns the MIR local corresponding to the local binding pattern.
+    fn binding_local(&self, binding: &hir::Pat<'_>) -> Option<mir::Local> {
+        let hir::PatKind::Binding(_, _, ident, _) = binding.kind else { return None; };
+
+        self.body.var_debug_info.iter().find_map(|var_debug_info| {
+            if var_debug_info.name != ident.name || var_debug_info.source_info.span != ident.span { return None; }
+            let mir::VarDebugInfoContents::Place(place) = &var_debug_info.value else { return None; };
+            place.as_local()
+        })
+    }
+
+    /// Returns whether the value of the local binding is ever read.
+    pub fn is_local_read(&self, binding: &hir::Pat<'_>) -> Option<bool> {
+        let local = self.binding_local(binding)?;
+        Some(!self.local_uses[local].reads.is_empty())
+    }
+
+    /// Returns whether the value of the local binding is read after the given span.
+    pub fn is_local_read_after(&self, binding: &hir::Pat<'_>, span: Span) -> Option<bool> {
+        let local = self.binding_local(binding)?;
+        Some(self.local_uses[local].reads.iter().any(|read| read.lo() >= span.hi()))
+    }
+
+    /// Returns whether the local binding is mutated after the given span.
+    pub fn is_local_mutated_after(&self, binding: &hir::Pat<'_>, span: Span) -> Option<bool> {
+        let local = self.binding_local(binding)?;
+        Some(self.local_uses[local].writes.iter().any(|write| write.lo() >= span.hi()))
+    }
+
+    /// Returns whether the value of the expression is ever read.
+    pub fn is_expr_value_read(&self, expr: &hir::Expr<'_>) -> Option<bool> {
+        let mut assigned_locals = self.body.basic_blocks.iter()
+            .flat_map(|basic_block| {
+                let assigned_by_statements = basic_block.statements.iter()
+                    .filter(|statement| statement.source_info.span == expr.span)
+                    .filter_map(|statement| {
+                        let mir::StatementKind::Assign(assign) = &statement.kind else { return None; };
+                        let (place, _) = &**assign;
+                        Some(*place)
+                    });
+
+                // The values of calls are assigned by the call terminator.
+                let assigned_by_terminator = basic_block.terminator.iter()
+                    .filter(|terminator| terminator.source_info.span == expr.span)
+                    .filter_map(|terminator| {
+                        let mir::TerminatorKind::Call { destination, .. } = &terminator.kind else { return None; };
+                        Some(*destination)
+                    });
+
+                assigned_by_statements.chain(assigned_by_terminator)
+            })
+            .filter_map(|place| place.as_local())
+            .peekable();
+
+        assigned_locals.peek()?;
+        Some(assigned_locals.any(|local| !self.local_uses[local].reads.is_empty()))
+    }
+}
+
+/// Dataflow facts of a function body, computed when first requested.
+pub struct LazyBodyFacts<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    def_id: hir::LocalDefId,
+    facts: OnceCell<Option<BodyFacts<'tcx>>>,
+}
+
+impl<'tcx> LazyBodyFacts<'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>, def_id: hir::LocalDefId) -> Self {
+        Self { tcx, def_id, facts: OnceCell::new() }
+    }
+
+    pub fn get(&self) -> Option<&BodyFacts<'tcx>> {
+        self.facts.get_or_init(|| BodyFacts::compute(self.tcx, self.def_id)).as_ref()
+    }
+}
@@ -1,13 +1,21 @@
+use std::any::Any;
+use std::cell::Cell;
 use std::io::{self, Write};
+use std::panic;
 use std::sync::{Arc, Mutex};
 
 use rustc_data_structures::sync::Lrc;
 use rustc_error_messages::{FluentBundle, LazyFallbackBundle};
 use rustc_errors::{Diag, EmissionGuarantee, TerminalUrl};
 use rustc_errors::emitter::{DynEmitter, HumanEmitter};
+use rustc_hash::FxHashSet;
 use rustc_session::Session;
 use rustc_span::source_map::SourceMap;
 
+use crate::analysis::hir;
+use crate::analysis::ty::TyCtxt;
+use crate::codegen::symbols::Span;
+
 pub fn escape_literal(s: &str) -> String {
     let mut escaped = String::with_capacity(s.len());
     let mut chrs = s.chars().peekable();
@@ -28,6 +36,38 @@ pub fn escape_literal(s: &str) -> String {
     escaped
 }
 
+/// Reason codes of the diagnostics emitted by mutest-rs during analysis.
+///
+/// Reason codes are attached to diagnostics as `mutest::<code>`, and are reported as the `code` of the diagnostic
+/// when using `--error-format=json`, so that tools wrapping mutest-rs can identify the diagnostics.
+pub mod codes {
+    pub const UNMATCHED_AST_NODE: &str = "unmatched_ast_node";
+    pub const UNRECOGNIZED_AST_HIR_NODE_PAIR: &str = "unrecognized_ast_hir_node_pair";
+    pub const INVALID_AST_HIR_MAPPING: &str = "invalid_ast_hir_mapping";
+    pub const VIRTUAL_CALL: &str = "virtual_call";
+    pub const FOREIGN_CALL: &str = "foreign_call";
+    pub const DYNAMIC_CALL: &str = "dynamic_call";
+    pub const APPROXIMATED_CALLS: &str = "approximated_calls";
+    pub const INTERNAL_ERROR: &str = "internal_error";
+    pub const SKIPPED_ITEMS: &str = "skipped_items";
+    pub const UNSANITIZED_PATHS: &str = "unsanitized_paths";
+    pub const UNREPRESENTABLE_GENERIC_ARG: &str = "unrepresentable_generic_arg";
+    pub const SKIPPED_DOCTEST: &str = "skipped_doctest";
+}
+
+pub trait DiagMutestCodeExt {
+    /// Attach a mutest-rs reason code (see [`codes`]) to the diagnostic.
+    fn mutest_code(&mut self, code: &str) -> &mut Self;
+}
+
+impl<'a, G: EmissionGuarantee> DiagMutestCodeExt for Diag<'a, G> {
+    fn mutest_code(&mut self, code: &str) -> &mut Self {
+        // NOTE: Diagnostic codes are limited to rustc's error codes, but lint names are reported as codes in
+        //       JSON diagnostics, so the reason code is attached as the name of a tool lint instead.
+        self.is_lint(format!("mutest::{code}"), false)
+    }
+}
+
 pub trait SessionRcSourceMap {
     fn rc_source_map(&self) -> Lrc<SourceMap>;
 }
@@ -134,3 +174,187 @@ pub fn output<G: EmissionGuarantee>(diagnostic: Diag<G>, source_map: Lrc<SourceM
 pub fn emit_str<G: EmissionGuarantee>(diagnostic: Diag<G>, source_map: Lrc<SourceMap>) -> String {
     output(diagnostic, source_map)
 }
+
+thread_local! {
+    /// The number of enclosing [`catch_internal_error`] scopes on the current thread.
+    static INTERNAL_ERROR_CATCH_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Panic payload of internal errors raised with [`internal_error!`].
+pub struct InternalErrorPanic {
+    pub span: Span,
+    pub message: String,
+    pub notes: Vec<String>,
+}
+
+/// Raise an internal error, similarly to `span_bug!`.
+///
+/// Within a [`catch_internal_error`] scope, the error is caught and reported as a diagnostic, and the offending item
+/// is skipped. Outside of such a scope, this is equivalent to `span_bug!`.
+pub macro internal_error($span:expr, $($arg:tt)+) {
+    $crate::analysis::diagnostic::raise_internal_error($span, format!($($arg)+), vec![])
+}
+
+#[track_caller]
+pub fn raise_internal_error(span: Span, message: String, notes: Vec<String>) -> ! {
+    if INTERNAL_ERROR_CATCH_DEPTH.get() == 0 {
+        let notes = notes.iter().map(|note| format!("\nnote: {note}")).collect::<String>();
+        rustc_middle::util::bug::span_bug_fmt(span, format_args!("{message}{notes}"));
+    }
+
+    panic::panic_any(InternalErrorPanic { span, message, notes });
+}
+
+pub struct InternalError {
+    pub def_id: hir::LocalDefId,
+    pub span: Span,
+    pub message: String,
+    pub notes: Vec<String>,
+    /// Source location at which the error was raised, if known.
+    pub location: Option<String>,
+}
+
+impl InternalError {
+    fn from_panic_payload(def_id: hir::LocalDefId, def_span: Span, payload: Box<dyn Any + Send>, location: Option<String>) -> Self {
+        let payload = match payload.downcast::<InternalErrorPanic>() {
+            Ok(internal_error) => {
+                let InternalErrorPanic { span, message, notes } = *internal_error;
+                return Self { def_id, span, message, notes, location };
+            }
+            Err(payload) => payload,
+        };
+
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => match payload.downcast::<&'static str>() {
+                Ok(message) => (*message).to_owned(),
+                // NOTE: Compiler bugs (e.g. `span_bug!` in queries) are reported by the compiler before unwinding.
+                Err(_) => "internal compiler error".to_owned(),
+            },
+        };
+
+        Self { def_id, span: def_span, message, notes: vec![], location }
+    }
+}
+
+/// Run `f` for the item `def_id`, catching internal errors and panics raised during it.
+///
+/// Caught errors are reported as a warning naming the offending item, so that the caller can skip the item and
+/// continue with the rest of the crate. `action` describes the operation in progress (e.g. "sanitizing").
+pub fn catch_internal_error<'tcx, R>(tcx: TyCtxt<'tcx>, def_id: hir::LocalDefId, action: &str, f: impl FnOnce() -> R) -> Result<R, InternalError> {
+    // NOTE: We replace the panic hook to suppress the ICE message of the compiler, recording only the location of the
+    //       panic for the diagnostic.
+    let panic_location = Arc::new(Mutex::new(None::<String>));
+    let builtin_panic_hook = panic::take_hook();
+    panic::set_hook(Box::new({
+        let panic_location = panic_location.clone();
+        move |panic_info| {
+            *panic_location.lock().unwrap() = panic_info.location().map(|location| location.to_string());
+        }
+    }));
+
+    INTERNAL_ERROR_CATCH_DEPTH.set(INTERNAL_ERROR_CATCH_DEPTH.get() + 1);
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(f));
+    INTERNAL_ERROR_CATCH_DEPTH.set(INTERNAL_ERROR_CATCH_DEPTH.get() - 1);
+
+    panic::set_hook(builtin_panic_hook);
+
+    let payload = match result {
+        Ok(result) => return Ok(result),
+        Err(payload) => payload,
+    };
+
+    let location = panic_location.lock().unwrap().take();
+    let error = InternalError::from_panic_payload(def_id, tcx.def_span(def_id), payload, location);
+
+    let mut diagnostic = tcx.dcx().struct_span_warn(error.span, format!("internal error while {action} `{def_path}`: {message}",
+        def_path = tcx.def_path_str(def_id),
+        message = error.message,
+    ));
+    diagnostic.mutest_code(codes::INTERNAL_ERROR);
+    diagnostic.span_note(tcx.def_span(def_id), "this item is skipped, and will not be mutated");
+    for note in &error.notes {
+        diagnostic.note(note.clone());
+    }
+    if let Some(location) = &error.location {
+        diagnostic.note(format!("raised at {location}"));
+    }
+    diagnostic.emit();
+
+    Err(error)
+}
+
+/// A path which could not be resolved during the sanitization of macro expansions, and was kept as-is instead.
+pub struct UnsanitizedPath {
+    pub span: Span,
+    pub path: String,
+}
+
+/// Items skipped due to internal errors, caught with [`catch_internal_error`], and paths left unsanitized due to
+/// missing resolutions.
+#[derive(Default)]
+pub struct InternalErrors {
+    pub errors: Vec<InternalError>,
+    skipped_def_ids: FxHashSet<hir::LocalDefId>,
+    pub unsanitized_paths: Vec<UnsanitizedPath>,
+}
+
+impl InternalErrors {
+    pub fn push(&mut self, error: InternalError) {
+        self.skipped_def_ids.insert(error.def_id);
+        self.errors.push(error);
+    }
+
+    pub fn push_unsanitized_path(&mut self, span: Span, path: String) {
+        self.unsanitized_paths.push(UnsanitizedPath { span, path });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Whether the definition, or any of its parents, was skipped due to an internal error.
+    pub fn is_skipped(&self, tcx: TyCtxt<'_>, def_id: hir::LocalDefId) -> bool {
+        if self.skipped_def_ids.is_empty() { return false; }
+
+        let mut current_def_id = Some(def_id);
+        while let Some(def_id) = current_def_id {
+            if self.skipped_def_ids.contains(&def_id) { return true; }
+            current_def_id = tcx.opt_local_parent(def_id);
+        }
+        false
+    }
+
+    pub fn report_summary(&self, tcx: TyCtxt<'_>) {
+        self.report_unsanitized_paths_summary(tcx);
+
+        if self.errors.is_empty() { return; }
+
+        let mut diagnostic = tcx.dcx().struct_warn(format!("skipped {n} {items} due to internal errors",
+            n = self.errors.len(),
+            items = match self.errors.len() { 1 => "item", _ => "items" },
+        ));
+        diagnostic.mutest_code(codes::SKIPPED_ITEMS);
+        for error in &self.errors {
+            diagnostic.note(format!("skipped `{}`", tcx.def_path_str(error.def_id)));
+        }
+        diagnostic.help("please report this as a bug, along with the source of the skipped items");
+        diagnostic.emit();
+    }
+
+    fn report_unsanitized_paths_summary(&self, tcx: TyCtxt<'_>) {
+        if self.unsanitized_paths.is_empty() { return; }
+
+        let mut diagnostic = tcx.dcx().struct_warn(format!("left {n} {paths} unsanitized, as they could not be resolved",
+            n = self.unsanitized_paths.len(),
+            paths = match self.unsanitized_paths.len() { 1 => "path", _ => "paths" },
+        ));
+        diagnostic.mutest_code(codes::UNSANITIZED_PATHS);
+        for unsanitized_path in &self.unsanitized_paths {
+            diagnostic.span_note(unsanitized_path.span, format!("path `{}` is kept as written", unsanitized_path.path));
+        }
+        diagnostic.note("unsanitized paths may fail to resolve in the generated code if they refer to definitions from macro expansions");
+        diagnostic.help("please report this as a bug, along with the source of the unsanitized paths");
+        diagnostic.emit();
+    }
+}
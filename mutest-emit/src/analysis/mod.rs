@@ -1,5 +1,6 @@
 pub mod ast_lowering;
 pub mod call_graph;
+pub mod dataflow;
 pub mod diagnostic;
 pub mod hir;
 pub mod res;
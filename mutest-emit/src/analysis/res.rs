@@ -734,5 +734,8 @@ pub mod traits {
 pub mod fns {
     super::interned! {
         fn default (::core::default::Default::default),
+
+        fn iter_filter (::core::iter::Iterator::filter),
+        fn iter_map (::core::iter::Iterator::map),
     }
 }
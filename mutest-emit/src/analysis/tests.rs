@@ -9,14 +9,21 @@ use crate::analysis::hir;
 use crate::codegen::ast;
 use crate::codegen::ast::P;
 use crate::codegen::ast::visit::Visitor;
-use crate::codegen::symbols::{Ident, sym};
+use crate::codegen::symbols::{Ident, Symbol, kw, sym};
+use crate::codegen::tool_attr;
 
+#[derive(Clone)]
 pub struct Test {
     pub path: Vec<Ident>,
-    pub descriptor: P<ast::Item>,
+    /// The test descriptor generated by the built-in test harness, if the test was declared using `#[test]`.
+    pub descriptor: Option<P<ast::Item>>,
     pub item: P<ast::Item>,
     pub def_id: hir::LocalDefId,
     pub ignore: bool,
+    /// The reason given for ignoring the test, if any (i.e. `#[ignore = "reason"]`).
+    pub ignore_message: Option<Symbol>,
+    /// Whether the test is a property-based test, which is run with a reduced number of cases against mutants.
+    pub property_test: bool,
 }
 
 impl Test {
@@ -57,6 +64,47 @@ fn is_test_case(item: &ast::Item) -> bool {
     item.attrs.iter().any(|attr| attr.has_name(sym::rustc_test_marker))
 }
 
+/// Returns whether the test is marked with `#[ignore]`, and the reason given for ignoring it, if any.
+fn ignore_attr(attrs: &[ast::Attribute]) -> (bool, Option<Symbol>) {
+    match attrs.iter().find(|attr| attr.has_name(sym::ignore)) {
+        Some(attr) => (true, attr.value_str()),
+        None => (false, None),
+    }
+}
+
+struct PropertyTestingPathFinder {
+    found: bool,
+}
+
+impl<'ast> ast::visit::Visitor<'ast> for PropertyTestingPathFinder {
+    fn visit_path(&mut self, path: &'ast ast::Path, _id: ast::NodeId) {
+        let crate_name = path.segments.iter()
+            .map(|segment| segment.ident)
+            .find(|ident| ident.name != kw::PathRoot)
+            .map(|ident| match ident.name {
+                // Paths in the expansion of macros of property testing libraries (e.g. `proptest!`).
+                kw::DollarCrate => ident.span.ctxt().dollar_crate_name(),
+                name => name,
+            });
+
+        if let Some(crate_name) = crate_name && (crate_name == *sym::proptest || crate_name == *sym::quickcheck) {
+            self.found = true;
+        }
+
+        ast::visit::walk_path(self, path);
+    }
+}
+
+/// Returns whether the test is a property-based test, i.e. it is marked with `#[mutest::property_test]`, or it uses
+/// `proptest` or `quickcheck`, whose test macros and attributes expand to references to the library.
+fn is_property_test(test_item: &ast::Item) -> bool {
+    if tool_attr::property_test(&test_item.attrs) { return true; }
+
+    let mut finder = PropertyTestingPathFinder { found: false };
+    finder.visit_item(test_item);
+    finder.found
+}
+
 fn extract_expanded_tests(def_res: &ast_lowering::DefResolutions, path: &[Ident], items: &[P<ast::Item>]) -> Vec<Test> {
     let mut tests = vec![];
 
@@ -71,20 +119,74 @@ fn extract_expanded_tests(def_res: &ast_lowering::DefResolutions, path: &[Ident]
 
         let Some(def_id) = def_res.node_id_to_def_id.get(&test_item.id).copied() else { unreachable!(); };
 
-        let ignore = test_item.attrs.iter().any(|attr| attr.has_name(sym::ignore));
+        let (ignore, ignore_message) = ignore_attr(&test_item.attrs);
 
         tests.push(Test {
             path: path.iter().copied().chain(iter::once(test_case.ident)).collect(),
-            descriptor: test_case.to_owned(),
+            descriptor: Some(test_case.to_owned()),
             item: test_item.to_owned(),
             def_id,
             ignore,
+            ignore_message,
+            property_test: is_property_test(test_item),
         });
     }
 
     tests
 }
 
+/// Returns `true` if the item is a function which can be used as a test, i.e. it takes no arguments and has no generic
+/// parameters.
+fn is_test_fn(item: &ast::Item) -> bool {
+    let ast::ItemKind::Fn(fn_item) = &item.kind else { return false; };
+    fn_item.sig.decl.inputs.is_empty() && fn_item.generics.params.is_empty()
+}
+
+/// Extract functions marked with `#[mutest::test]`, which are used as tests by crates with custom test harnesses
+/// (i.e. `harness = false`), that do not declare their tests using `#[test]`.
+fn extract_marked_tests(def_res: &ast_lowering::DefResolutions, path: &[Ident], items: &[P<ast::Item>]) -> Vec<Test> {
+    let mut tests = vec![];
+
+    let mut item_iterator = items.iter();
+    while let Some(item) = item_iterator.next() {
+        // Tests declared using `#[test]` are already extracted by `extract_expanded_tests`.
+        if is_test_case(item) {
+            item_iterator.next();
+            continue;
+        }
+
+        if !tool_attr::test(&item.attrs) || !is_test_fn(item) { continue; }
+
+        let Some(def_id) = def_res.node_id_to_def_id.get(&item.id).copied() else { unreachable!(); };
+
+        let (ignore, ignore_message) = ignore_attr(&item.attrs);
+
+        tests.push(Test {
+            path: path.iter().copied().chain(iter::once(item.ident)).collect(),
+            descriptor: None,
+            item: item.to_owned(),
+            def_id,
+            ignore,
+            ignore_message,
+            property_test: is_property_test(item),
+        });
+    }
+
+    tests
+}
+
+type ExtractTestsFn = fn(&ast_lowering::DefResolutions, &[Ident], &[P<ast::Item>]) -> Vec<Test>;
+
+/// Test entry point discovery mechanisms, each extracting the tests declared in a list of items.
+const TEST_EXTRACTORS: &[ExtractTestsFn] = &[
+    extract_expanded_tests,
+    extract_marked_tests,
+];
+
+fn extract_tests(def_res: &ast_lowering::DefResolutions, path: &[Ident], items: &[P<ast::Item>]) -> Vec<Test> {
+    TEST_EXTRACTORS.iter().flat_map(|extract_tests| extract_tests(def_res, path, items)).collect()
+}
+
 struct TestCollector<'op> {
     current_path: Vec<Ident>,
     tests: Vec<Test>,
@@ -93,7 +195,7 @@ struct TestCollector<'op> {
 
 impl<'ast, 'op> ast::visit::Visitor<'ast> for TestCollector<'op> {
     fn visit_crate(&mut self, c: &'ast ast::Crate) {
-        let mut tests = extract_expanded_tests(self.def_res, &self.current_path, &c.items);
+        let mut tests = extract_tests(self.def_res, &self.current_path, &c.items);
         self.tests.append(&mut tests);
 
         ast::visit::walk_crate(self, c);
@@ -103,7 +205,7 @@ impl<'ast, 'op> ast::visit::Visitor<'ast> for TestCollector<'op> {
         if let ast::ItemKind::Mod(.., ast::ModKind::Loaded(ref items, ..)) = i.kind {
             self.current_path.push(i.ident);
 
-            let mut tests = extract_expanded_tests(self.def_res, &self.current_path, &items);
+            let mut tests = extract_tests(self.def_res, &self.current_path, &items);
             self.tests.append(&mut tests);
 
             ast::visit::walk_item(self, i);
@@ -120,10 +222,20 @@ pub fn collect_tests(krate: &ast::Crate, def_res: &ast_lowering::DefResolutions)
     collector.tests
 }
 
+/// Whether the item is marked with `#[cfg(test)]`, or is nested in an item marked with it (e.g. a `#[cfg(test)]` module).
+/// Only the item and its enclosing items are considered, so that items next to `#[cfg(test)]` items (e.g. a `mod tests`
+/// in the same file) are not excluded.
+///
+/// Items marked with `#[mutest::mutate_cfg_test]`, or nested in an item marked with it, are opted back in, and are not
+/// considered to be in `#[cfg(test)]`.
 pub fn is_marked_or_in_cfg_test<'tcx>(tcx: TyCtxt<'tcx>, id: hir::HirId) -> bool {
-    iter::once(id).chain(tcx.hir().parent_id_iter(id)).any(|parent_id| {
-        tcx.hir().attrs(parent_id).iter().any(|attr| {
-            ast::inspect::is_list_attr_with_ident(attr, None, sym::cfg, sym::test)
-        })
-    })
+    let enclosing_item_ids = tcx.hir().parent_owner_iter(id).map(|(owner_id, _)| tcx.local_def_id_to_hir_id(owner_id.def_id));
+
+    for item_id in iter::once(id).chain(enclosing_item_ids) {
+        let attrs = tcx.hir().attrs(item_id);
+        if tool_attr::mutate_cfg_test(attrs) { return false; }
+        if attrs.iter().any(|attr| ast::inspect::is_list_attr_with_ident(attr, None, sym::cfg, sym::test)) { return true; }
+    }
+
+    false
 }
@@ -4,6 +4,7 @@ use rustc_infer::infer::TyCtxtInferExt;
 use rustc_middle::ty;
 use rustc_trait_selection::infer::InferCtxtExt;
 
+use crate::analysis::diagnostic::{self, DiagMutestCodeExt};
 use crate::analysis::hir;
 use crate::codegen::symbols::{DUMMY_SP, Ident, Span, Symbol};
 
@@ -19,6 +20,23 @@ pub fn impls_trait<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>, trait_def_id: hir::Def
     impls_trait_with_env(tcx, ty::ParamEnv::empty(), ty, trait_def_id, args)
 }
 
+/// Returns whether the predicate holds for the value in each of the given instantiations of its generic context.
+/// The predicate is given the value instantiated with the generic arguments of the instantiation, along with the
+/// parameter environment of the monomorphic instantiation. Instantiations in which the value cannot be normalized fail.
+pub fn holds_for_instantiations<'tcx, T>(tcx: TyCtxt<'tcx>, instantiations: &[ty::GenericArgsRef<'tcx>], value: T, mut predicate: impl FnMut(T, ty::ParamEnv<'tcx>) -> bool) -> bool
+where
+    T: ty::TypeFoldable<TyCtxt<'tcx>> + Clone,
+{
+    let param_env = ty::ParamEnv::reveal_all();
+
+    instantiations.iter().all(|&generic_args| {
+        match tcx.try_instantiate_and_normalize_erasing_regions(generic_args, param_env, ty::EarlyBinder::bind(value.clone())) {
+            Ok(value) => predicate(value, param_env),
+            Err(_) => false,
+        }
+    })
+}
+
 pub fn impl_assoc_ty<'tcx>(tcx: TyCtxt<'tcx>, param_env: ty::ParamEnv<'tcx>, caller_def_id: hir::LocalDefId, ty: Ty<'tcx>, trait_def_id: hir::DefId, args: Vec<ty::GenericArg<'tcx>>, assoc_ty: Symbol) -> Option<Ty<'tcx>> {
     tcx.associated_items(trait_def_id)
         .find_by_name_and_kind(tcx, Ident::new(assoc_ty, DUMMY_SP), ty::AssocKind::Type, trait_def_id)
@@ -282,6 +300,7 @@ pub mod print {
                                 Ok(ty_ast) => ty_ast,
                                 Err(error) => {
                                     let mut diagnostic = self.tcx.dcx().struct_span_warn(sp, format!("replacing unrepresentable generic type argument `{ty}` with `_`"));
+                                    diagnostic.mutest_code(diagnostic::codes::UNREPRESENTABLE_GENERIC_ARG);
                                     diagnostic.span_label(sp, error);
                                     diagnostic.emit();
 
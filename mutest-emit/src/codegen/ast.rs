@@ -162,6 +162,8 @@ impl<'ast> DefItem<'ast> {
 }
 
 pub mod mk {
+    use std::iter;
+
     use rustc_ast as ast;
     use rustc_ast::ptr::P;
     use rustc_span::{Span, Symbol, sym};
@@ -662,6 +664,10 @@ pub mod mk {
         self::expr_lit(sp, ast::token::LitKind::Integer, Symbol::intern(&i.to_string()), Some(sym::u32))
     }
 
+    pub fn expr_u64(sp: Span, i: u64) -> P<ast::Expr> {
+        self::expr_lit(sp, ast::token::LitKind::Integer, Symbol::intern(&i.to_string()), Some(sym::u64))
+    }
+
     pub fn expr_str(sp: Span, str: &str) -> P<ast::Expr> {
         self::expr_lit(sp, ast::token::LitKind::Str, Symbol::intern(str), None)
     }
@@ -880,6 +886,19 @@ pub mod mk {
         ast::attr::mk_attr(g, ast::AttrStyle::Outer, ast::Path::from_ident(ident), args, sp)
     }
 
+    /// `#[allow($lint, ...)]`
+    pub fn attr_outer_allow(g: &ast::attr::AttrIdGenerator, sp: Span, lints: &[Symbol]) -> ast::Attribute {
+        let token_trees = lints.iter().enumerate()
+            .flat_map(|(i, &lint)| {
+                let comma_token = (i != 0).then(|| self::tt_token_alone(sp, ast::TokenKind::Comma));
+                let lint_token = self::tt_token_alone(sp, ast::TokenKind::Ident(lint, ast::token::IdentIsRaw::No));
+                comma_token.into_iter().chain(iter::once(lint_token))
+            })
+            .collect::<Vec<_>>();
+
+        self::attr_outer(g, sp, Ident::new(sym::allow, sp), self::attr_args_delimited(sp, ast::token::Delimiter::Parenthesis, self::token_stream(token_trees)))
+    }
+
     pub fn attr_args_delimited(sp: Span, delimiter: ast::token::Delimiter, tokens: ast::tokenstream::TokenStream) -> ast::AttrArgs {
         ast::AttrArgs::Delimited(ast::DelimArgs {
             dspan: ast::tokenstream::DelimSpan::from_single(sp),
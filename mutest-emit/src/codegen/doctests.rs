@@ -0,0 +1,249 @@
+use std::borrow::Cow;
+
+use rustc_hash::FxHashSet;
+use rustc_session::Session;
+use thin_vec::ThinVec;
+
+use crate::analysis::diagnostic::{self, DiagMutestCodeExt};
+use crate::codegen::ast::{self, P};
+use crate::codegen::ast::visit::Visitor;
+use crate::codegen::expansion;
+use crate::codegen::symbols::{FileName, Symbol, kw, sym};
+
+/// Name of the module into which the extracted documentation tests are injected.
+pub const DOCTESTS_MOD_NAME: &str = "__mutest_doctests";
+
+struct CodeBlockInfo {
+    should_panic: bool,
+}
+
+/// Parse the info string of a fenced code block, following the code block attributes recognized by rustdoc.
+///
+/// Returns `None` if the code block is not Rust code which would be run as a documentation test.
+fn parse_code_block_info(info: &str) -> Option<CodeBlockInfo> {
+    let mut code_block_info = CodeBlockInfo { should_panic: false };
+    let mut seen_rust = false;
+    let mut seen_other = false;
+
+    for token in info.split(|c: char| c == ',' || c.is_whitespace()).filter(|token| !token.is_empty()) {
+        match token {
+            "rust" => seen_rust = true,
+            "should_panic" => code_block_info.should_panic = true,
+            "ignore" | "no_run" | "compile_fail" | "test_harness" => return None,
+            _ if token.starts_with("ignore-") => return None,
+            _ if token.starts_with("edition") => {}
+            _ => seen_other = true,
+        }
+    }
+
+    // Code blocks with unknown attributes are considered to be in another language, unless explicitly marked as Rust.
+    if seen_other && !seen_rust { return None; }
+
+    Some(code_block_info)
+}
+
+/// Reveal lines hidden from the rendered documentation (i.e. lines starting with `# `).
+fn unhide_line(line: &str) -> Cow<'_, str> {
+    let trimmed = line.trim();
+    if trimmed.starts_with("##") {
+        Cow::Owned(line.replacen("##", "#", 1))
+    } else if let Some(rest) = trimmed.strip_prefix("# ") {
+        Cow::Borrowed(rest)
+    } else if trimmed == "#" {
+        Cow::Borrowed("")
+    } else {
+        Cow::Borrowed(line)
+    }
+}
+
+struct Doctest {
+    name: String,
+    cfg_attrs: Vec<String>,
+    line: usize,
+    code: String,
+    should_panic: bool,
+}
+
+struct DoctestCollector<'a> {
+    sess: &'a Session,
+    crate_name: Symbol,
+    current_path: Vec<Symbol>,
+    current_cfg_attrs: Vec<String>,
+    doctests: Vec<Doctest>,
+}
+
+impl<'a> DoctestCollector<'a> {
+    fn collect_doctests(&mut self, attrs: &[ast::Attribute]) {
+        let source_map = self.sess.source_map();
+
+        // Lines of the documentation, paired with their line number in the source file.
+        let mut doc_lines = vec![];
+        for attr in attrs {
+            let Some(doc) = attr.doc_str() else { continue; };
+            let attr_line = source_map.lookup_char_pos(attr.span.lo()).line;
+            doc_lines.extend(doc.as_str().lines().enumerate().map(|(i, line)| (attr_line + i, line.to_owned())));
+        }
+        if doc_lines.is_empty() { return; }
+
+        let indent = doc_lines.iter()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(_, line)| line.len() - line.trim_start().len())
+            .min().unwrap_or(0);
+
+        let name_prefix = match &self.current_path[..] {
+            [] => "crate".to_owned(),
+            path => path.iter().map(|segment| segment.as_str()).intersperse("_").collect::<String>(),
+        };
+
+        let mut doc_lines = doc_lines.iter().map(|(line_no, line)| (*line_no, line.get(indent..).unwrap_or("")));
+        while let Some((line_no, line)) = doc_lines.next() {
+            let Some(info) = line.trim_start().strip_prefix("```") else { continue; };
+
+            let code_lines = doc_lines.by_ref()
+                .map(|(_, line)| line)
+                .take_while(|line| !line.trim_start().starts_with("```"))
+                .collect::<Vec<_>>();
+
+            let Some(CodeBlockInfo { should_panic }) = parse_code_block_info(info) else { continue; };
+
+            let extern_crate_line = format!("extern crate {};", self.crate_name);
+            let code = code_lines.iter()
+                .map(|line| unhide_line(line))
+                // Crate-level attributes cannot be applied to the crate under test, and the crate under test is
+                // referred to through the crate-level `extern crate self` item instead.
+                .filter(|line| !line.trim_start().starts_with("#![") && line.trim() != extern_crate_line)
+                .intersperse(Cow::Borrowed("\n"))
+                .collect::<String>();
+
+            self.doctests.push(Doctest {
+                name: format!("{name_prefix}_{line_no}"),
+                cfg_attrs: self.current_cfg_attrs.clone(),
+                line: line_no,
+                code,
+                should_panic,
+            });
+        }
+    }
+
+    fn with_item<F: FnOnce(&mut Self)>(&mut self, name: Symbol, attrs: &[ast::Attribute], f: F) {
+        let cfg_attrs = attrs.iter().filter(|attr| attr.has_name(sym::cfg)).map(ast::print::attribute_to_string).collect::<Vec<_>>();
+        let cfg_attrs_count = cfg_attrs.len();
+        self.current_cfg_attrs.extend(cfg_attrs);
+        // Items without a name (e.g. impl blocks) do not contribute to the path of their documentation tests.
+        if name != kw::Empty { self.current_path.push(name); }
+
+        self.collect_doctests(attrs);
+        f(self);
+
+        if name != kw::Empty { self.current_path.pop(); }
+        self.current_cfg_attrs.truncate(self.current_cfg_attrs.len() - cfg_attrs_count);
+    }
+}
+
+impl<'ast, 'a> ast::visit::Visitor<'ast> for DoctestCollector<'a> {
+    fn visit_crate(&mut self, krate: &'ast ast::Crate) {
+        self.collect_doctests(&krate.attrs);
+        ast::visit::walk_crate(self, krate);
+    }
+
+    fn visit_item(&mut self, item: &'ast ast::Item) {
+        self.with_item(item.ident.name, &item.attrs, |this| ast::visit::walk_item(this, item));
+    }
+
+    fn visit_assoc_item(&mut self, item: &'ast ast::AssocItem, ctxt: ast::visit::AssocCtxt) {
+        self.with_item(item.ident.name, &item.attrs, |this| ast::visit::walk_assoc_item(this, item, ctxt));
+    }
+
+    fn visit_block(&mut self, _block: &'ast ast::Block) {
+        // Items nested in function bodies are not documented by rustdoc.
+    }
+}
+
+fn parse_item(sess: &Session, file_name: String, source: String) -> Option<P<ast::Item>> {
+    let mut parser = match rustc_parse::maybe_new_parser_from_source_str(&sess.psess, FileName::Custom(file_name), source) {
+        Ok(parser) => parser,
+        Err(diagnostics) => {
+            diagnostics.into_iter().for_each(|diagnostic| diagnostic.cancel());
+            return None;
+        }
+    };
+
+    match parser.parse_item(rustc_parse::parser::ForceCollect::No) {
+        Ok(item) => item,
+        Err(diagnostic) => {
+            diagnostic.cancel();
+            None
+        }
+    }
+}
+
+fn doctest_item(sess: &Session, doctest: &Doctest) -> Option<P<ast::Item>> {
+    let Doctest { name, cfg_attrs, line, code, should_panic } = doctest;
+
+    // Like rustdoc, the code is wrapped in a function, unless it already defines its own `main` function.
+    let main_call = match code.contains("fn main(") {
+        true => "main()",
+        false => "",
+    };
+
+    let source = match should_panic {
+        // Tests marked with `#[should_panic]` must return `()`.
+        true => format!("{cfg_attrs}\n#[test]\n#[should_panic]\nfn {name}() {{\n{code}\n{main_call};\n}}", cfg_attrs = cfg_attrs.join("\n")),
+        false => format!("{cfg_attrs}\n#[test]\nfn {name}() -> impl ::std::process::Termination {{\n{code}\n{main_call}\n}}", cfg_attrs = cfg_attrs.join("\n")),
+    };
+
+    parse_item(sess, format!("doctest {name} (line {line})"), source)
+}
+
+/// Extract the documentation tests from the documentation of the crate's items, and inject them into the crate as
+/// regular tests, so that they are used as entry points like any other test.
+///
+/// The documentation tests are injected into a `#[cfg(test)]` module at the root of the crate, alongside an
+/// `extern crate self` item which allows the documentation tests to refer to the crate by its name, like they would as
+/// separate crates compiled by rustdoc. Documentation tests which cannot be parsed are skipped.
+pub fn inject_doctests(sess: &Session, krate: &mut ast::Crate) {
+    let crate_name = rustc_session::output::find_crate_name(sess, &krate.attrs);
+
+    // The documentation of out-of-line modules is only available once the module files are loaded.
+    let mut loaded_krate = krate.clone();
+    expansion::load_modules(sess, &mut loaded_krate);
+
+    let mut collector = DoctestCollector {
+        sess,
+        crate_name,
+        current_path: vec![],
+        current_cfg_attrs: vec![],
+        doctests: vec![],
+    };
+    collector.visit_crate(&loaded_krate);
+
+    let mut doctest_names = FxHashSet::<String>::default();
+    let mut doctest_items = ThinVec::<P<ast::Item>>::new();
+    for mut doctest in collector.doctests {
+        // Disambiguate documentation tests of items with the same name and line number in different files.
+        let name = doctest.name.clone();
+        let mut i = 1;
+        while doctest_names.contains(&doctest.name) {
+            doctest.name = format!("{name}_{i}");
+            i += 1;
+        }
+        doctest_names.insert(doctest.name.clone());
+
+        let Some(doctest_item) = doctest_item(sess, &doctest) else {
+            let mut diagnostic = sess.dcx().struct_warn(format!("skipping documentation test `{name}`: cannot parse code", name = doctest.name));
+            diagnostic.mutest_code(diagnostic::codes::SKIPPED_DOCTEST);
+            diagnostic.emit();
+            continue;
+        };
+        doctest_items.push(doctest_item);
+    }
+
+    if doctest_items.is_empty() { return; }
+
+    expansion::insert_extern_crate_self_alias(krate, crate_name);
+
+    let Some(mut doctests_mod) = parse_item(sess, "doctests".to_owned(), format!("#[cfg(test)]\n#[allow(unused)]\nmod {DOCTESTS_MOD_NAME} {{}}")) else { unreachable!() };
+    let ast::ItemKind::Mod(_, ast::ModKind::Loaded(items, ..)) = &mut doctests_mod.kind else { unreachable!() };
+    *items = doctest_items;
+    krate.items.push(doctests_mod);
+}
@@ -15,7 +15,7 @@ use crate::analysis::hir;
 use crate::analysis::tests::Test;
 use crate::codegen::ast::{self, P};
 use crate::codegen::ast::mut_visit::MutVisitor;
-use crate::codegen::symbols::{DUMMY_SP, ExpnKind, FileName, Ident, MacroKind, Span, Symbol, sym};
+use crate::codegen::symbols::{DUMMY_SP, ExpnKind, FileName, Ident, MacroKind, Span, Symbol, kw, sym};
 use crate::codegen::symbols::hygiene::AstPass;
 
 pub trait TcxExpansionExt {
@@ -91,13 +91,23 @@ pub fn insert_generated_code_prelude_attrs<'tcx>(tcx: TyCtxt<'tcx>, krate: &mut
         #![feature(coverage_attribute)]
         #![feature(rt)]
         #![feature(rustc_private)]
+        #![feature(stmt_expr_attributes)]
         #![feature(stdarch_internal)]
         #![feature(structural_match)]
         #![feature(thread_local)]
     }
 }
 
+/// Insert `extern crate` items at the root of the crate for the sysroot crates that the generated code refers to
+/// through absolute paths (e.g. `::core::option::Option`), unless the names are already declared.
+///
+/// The crates are chosen based on the crate attributes: `core` and `alloc` are declared for both regular and
+/// `#![no_std]` crates, and nothing is declared for `#![no_core]` crates. Declaring the crates at the root makes absolute
+/// paths resolve regardless of the edition, and of `#![no_implicit_prelude]`, which removes the implicit preludes of the
+/// crate, but not its root items.
 pub fn insert_generated_code_crate_refs<'tcx>(tcx: TyCtxt<'tcx>, krate: &mut ast::Crate) {
+    if krate.attrs.iter().any(|attr| ast::inspect::is_word_attr(attr, None, sym::no_core)) { return; }
+
     let expn_id = tcx.expansion_for_ast_pass(
         AstPass::StdImports,
         DUMMY_SP,
@@ -105,12 +115,29 @@ pub fn insert_generated_code_crate_refs<'tcx>(tcx: TyCtxt<'tcx>, krate: &mut ast
     );
     let def_site = DUMMY_SP.with_def_site_ctxt(expn_id.to_expn_id());
 
+    // extern crate core;
     // extern crate alloc;
-    if !krate.items.iter().any(|item| ast::inspect::is_extern_crate_decl(item, sym::alloc)) {
-        krate.items.push(ast::mk::item_extern_crate(def_site, sym::alloc, None));
+    for crate_name in [sym::core, sym::alloc] {
+        // NOTE: Any root item with the same name would conflict with the declaration,
+        //       including extern crate declarations of the same crate (e.g. `extern crate core;` in `#![no_std]` crates).
+        if krate.items.iter().any(|item| item.ident.name == crate_name) { continue; }
+        krate.items.push(ast::mk::item_extern_crate(def_site, crate_name, None));
     }
 }
 
+/// Insert an `extern crate self as <crate_name>;` item at the root of the crate, unless it already exists.
+///
+/// This allows code written from the perspective of a dependent crate (e.g. documentation tests) to refer to the crate
+/// by its name.
+pub fn insert_extern_crate_self_alias(krate: &mut ast::Crate, crate_name: Symbol) {
+    let alias_exists = krate.items.iter().any(|item| {
+        matches!(item.kind, ast::ItemKind::ExternCrate(Some(orig_name)) if orig_name == kw::SelfLower) && item.ident.name == crate_name
+    });
+    if alias_exists { return; }
+
+    krate.items.insert(0, ast::mk::item_extern_crate(DUMMY_SP, kw::SelfLower, Some(Ident::new(crate_name, DUMMY_SP))));
+}
+
 #[derive(Copy, Clone)]
 enum DirOwnership {
     Owned {
@@ -384,6 +411,26 @@ fn remove_macro_attrs_from_item(item: &mut ast::Item) {
     }
 }
 
+/// Returns whether the original item is annotated with attribute macros which are consumed by the expansion of the item,
+/// meaning that the expanded item may have been rewritten entirely by the macros.
+fn has_consumed_macro_attrs(original_item: &ast::Item, expanded_item: &ast::Item) -> bool {
+    // Built-in attributes which are consumed during expansion, but are otherwise handled.
+    const EXPANSION_ATTRS: &[Symbol] = &[
+        sym::cfg,
+        sym::cfg_attr,
+        sym::derive,
+        sym::test,
+        sym::bench,
+        sym::test_case,
+        sym::global_allocator,
+    ];
+
+    original_item.attrs.iter()
+        .filter(|attr| matches!(attr.kind, ast::AttrKind::Normal(_)))
+        .filter(|attr| !EXPANSION_ATTRS.iter().any(|&expansion_attr| attr.has_name(expansion_attr)))
+        .any(|attr| !expanded_item.attrs.iter().any(|expanded_attr| expanded_attr.path() == attr.path()))
+}
+
 struct MacroExpansionReverter<'ast> {
     original_crate: &'ast ast::Crate,
     current_scope_in_original: &'ast [P<ast::Item>],
@@ -426,6 +473,14 @@ impl<'ast> ast::mut_visit::MutVisitor for MacroExpansionReverter<'ast> {
                     return smallvec![item];
                 };
 
+                // Items rewritten by attribute macros are kept in their expanded form, as copying the original
+                // definition would discard the rewrite, and copying the original attributes would expand the macros
+                // once again.
+                if has_consumed_macro_attrs(original_item, &item) {
+                    remove_macro_attrs_from_item(&mut item);
+                    return smallvec![item];
+                }
+
                 match &item.kind {
                     | ast::ItemKind::Struct(_, _)
                     | ast::ItemKind::Enum(_, _)
@@ -543,7 +598,7 @@ impl<'tcx, 'tst> ast::mut_visit::MutVisitor for TestCaseCleaner<'tcx, 'tst> {
             }
         }
 
-        if let Some(_test) = self.tests.iter().find(|&test| test.descriptor.id == item.id) {
+        if let Some(_test) = self.tests.iter().find(|&test| test.descriptor.as_ref().is_some_and(|descriptor| descriptor.id == item.id)) {
             return smallvec![];
         }
 
@@ -553,6 +608,8 @@ impl<'tcx, 'tst> ast::mut_visit::MutVisitor for TestCaseCleaner<'tcx, 'tst> {
             // #[test]
             let test_attr = ast::mk::attr_outer(g, item.span, Ident::new(sym::test, item.span), ast::AttrArgs::Empty);
 
+            // Any other attributes of the test (e.g. `#[ignore = "reason"]`, `#[should_panic(expected = "...")]`) are
+            // retained, so that the test descriptor regenerated by the rustc test harness is identical to the original.
             item.attrs = item.attrs.into_iter()
                 .filter(|attr| !attr.has_name(sym::rustc_test_marker))
                 .filter(|attr| !attr.has_name(sym::test))
@@ -1,25 +1,67 @@
 use std::iter;
 
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use rustc_middle::ty::TyCtxt;
 use rustc_session::Session;
 use thin_vec::{ThinVec, thin_vec};
 
 use crate::analysis::call_graph::Unsafety;
 use crate::analysis::diagnostic;
+use crate::analysis::tests::Test;
 use crate::codegen::ast;
 use crate::codegen::ast::P;
 use crate::codegen::ast::mut_visit::MutVisitor;
+use crate::codegen::ast::visit::Visitor;
 use crate::codegen::expansion::TcxExpansionExt;
 use crate::codegen::mutation::{Mut, Mutant, SubstLoc, UnsafeTargeting};
-use crate::codegen::symbols::{DUMMY_SP, Ident, Span, Symbol, path, sym};
+use crate::codegen::span_map::SpanMap;
+use crate::codegen::substitution::SubstDispatch;
+use crate::codegen::symbols::{DUMMY_SP, Ident, Span, Symbol, kw, path, sym};
 use crate::codegen::symbols::hygiene::AstPass;
+use crate::codegen::tool_attr;
+
+/// Version of the schema of the generated metadata, checked against `mutest_runtime::METADATA_VERSION` when the
+/// meta-mutant is compiled, so that mixing incompatible versions of the driver and the runtime is reported clearly.
+pub const METADATA_VERSION: u32 = 2;
+
+/// Interned strings and tests of the generated metadata, referenced by index from the metadata of mutations.
+/// See `mutest_runtime::MetadataTables`.
+#[derive(Default)]
+pub struct MetadataTables {
+    strs: Vec<String>,
+    str_idxs: FxHashMap<String, u32>,
+    test_paths: Vec<String>,
+    test_idxs: FxHashMap<String, u32>,
+}
+
+impl MetadataTables {
+    pub fn intern_str(&mut self, str: String) -> u32 {
+        if let Some(&str_idx) = self.str_idxs.get(&str) { return str_idx; }
+
+        let str_idx = self.strs.len() as u32;
+        self.strs.push(str.clone());
+        self.str_idxs.insert(str, str_idx);
+        str_idx
+    }
+
+    pub fn intern_test(&mut self, test_path: String) -> u32 {
+        if let Some(&test_idx) = self.test_idxs.get(&test_path) { return test_idx; }
 
-pub fn bake_mutation(mutation: &Mut, sp: Span, sess: &Session, unsafe_targeting: UnsafeTargeting) -> P<ast::Expr> {
+        let test_idx = self.test_paths.len() as u32;
+        self.test_paths.push(test_path.clone());
+        self.test_idxs.insert(test_path, test_idx);
+        test_idx
+    }
+}
+
+pub fn bake_mutation<'tcx>(tcx: TyCtxt<'tcx>, mutation: &Mut, sp: Span, unsafe_targeting: UnsafeTargeting, tables: &mut MetadataTables) -> P<ast::Expr> {
     ast::mk::expr_struct(sp, ast::mk::path_local(path::MutationMeta(sp)), thin_vec![
         ast::mk::expr_struct_field(sp, Ident::new(*sym::id, sp), {
             ast::mk::expr_u32(sp, mutation.id.index())
         }),
+        ast::mk::expr_struct_field(sp, Ident::new(*sym::stable_id, sp), {
+            ast::mk::expr_u64(sp, mutation.stable_id.as_u64())
+        }),
 
         ast::mk::expr_struct_field(sp, Ident::new(sym::safety, sp), {
             match (mutation.is_unsafe(unsafe_targeting), mutation.target.unsafety) {
@@ -29,49 +71,130 @@ pub fn bake_mutation(mutation: &Mut, sp: Span, sess: &Session, unsafe_targeting:
             }
         }),
 
+        ast::mk::expr_struct_field(sp, Ident::new(*sym::tables, sp), {
+            ast::mk::expr_ref(sp, ast::mk::expr_path(path::METADATA_TABLES(sp)))
+        }),
+
         ast::mk::expr_struct_field(sp, Ident::new(*sym::op_name, sp), {
-            ast::mk::expr_str(sp, &mutation.op_name())
+            ast::mk::expr_u32(sp, tables.intern_str(mutation.op_name().to_owned()))
+        }),
+        ast::mk::expr_struct_field(sp, Ident::new(*sym::merged_op_names, sp), {
+            let elements = mutation.merged_op_names.iter()
+                .map(|op_name| ast::mk::expr_u32(sp, tables.intern_str(op_name.clone())))
+                .collect::<ThinVec<_>>();
+            ast::mk::expr_slice(sp, elements)
         }),
         ast::mk::expr_struct_field(sp, Ident::new(*sym::display_name, sp), {
-            ast::mk::expr_str(sp, &mutation.display_name())
+            ast::mk::expr_u32(sp, tables.intern_str(mutation.display_name()))
         }),
         ast::mk::expr_struct_field(sp, Ident::new(*sym::display_location, sp), {
-            ast::mk::expr_str(sp, &diagnostic::escape_literal(&mutation.display_location(sess)))
+            ast::mk::expr_u32(sp, tables.intern_str(diagnostic::escape_literal(&mutation.display_location(tcx.sess))))
+        }),
+        ast::mk::expr_struct_field(sp, Ident::new(*sym::target_path, sp), {
+            ast::mk::expr_u32(sp, tables.intern_str(tcx.def_path_str(mutation.target.def_id)))
         }),
 
         ast::mk::expr_struct_field(sp, Ident::new(*sym::reachable_from, sp), {
-            let args_token_trees = mutation.target.reachable_from.iter()
-                .flat_map(|(&test, entry_point)| {
-                    let key_lit = ast::TokenKind::lit(ast::token::LitKind::Str, Symbol::intern(&test.path_str()), None);
-                    let key_token = ast::mk::tt_token_alone(sp, key_lit);
-
-                    let arrow_token = ast::mk::tt_token_alone(sp, ast::TokenKind::FatArrow);
-
-                    let value_lit = ast::TokenKind::lit(ast::token::LitKind::Integer, Symbol::intern(&entry_point.distance.to_string()), None);
-                    let value_token = ast::mk::tt_token_alone(sp, value_lit);
-
-                    let comma_token = ast::mk::tt_token_alone(sp, ast::TokenKind::Comma);
-
-                    [key_token, arrow_token, value_token, comma_token]
-                })
+            // &[($test_idx, $distance), ...], sorted by test index.
+            let mut reachable_from = mutation.target.reachable_from.iter()
+                .map(|(&test, entry_point)| (tables.intern_test(test.path_str()), entry_point.distance))
                 .collect::<Vec<_>>();
+            reachable_from.sort_unstable_by_key(|&(test_idx, _)| test_idx);
 
-            ast::mk::expr(sp, ast::ExprKind::MacCall(P(ast::MacCall {
-                path: ast::mk::path_local(path::static_map(sp)),
-                args: P(ast::DelimArgs {
-                    dspan: ast::tokenstream::DelimSpan::from_single(sp),
-                    delim: ast::token::Delimiter::Brace,
-                    tokens: ast::mk::token_stream(args_token_trees),
-                })
-            })))
+            let elements = reachable_from.into_iter()
+                .map(|(test_idx, distance)| ast::mk::expr_tuple(sp, thin_vec![
+                    ast::mk::expr_u32(sp, test_idx),
+                    ast::mk::expr_u32(sp, distance as u32),
+                ]))
+                .collect::<ThinVec<_>>();
+            ast::mk::expr_slice(sp, elements)
+        }),
+
+        ast::mk::expr_struct_field(sp, Ident::new(*sym::substs_descr, sp), {
+            ast::mk::expr_u32(sp, tables.intern_str(diagnostic::escape_literal(&mutation.substs_descrs().join("\n"))))
         }),
 
         ast::mk::expr_struct_field(sp, Ident::new(*sym::undetected_diagnostic, sp), {
-            ast::mk::expr_str(sp, &diagnostic::escape_literal(&mutation.undetected_diagnostic(sess)))
+            ast::mk::expr_u32(sp, tables.intern_str(diagnostic::escape_literal(&mutation.undetected_diagnostic(tcx.sess))))
         }),
     ])
 }
 
+fn mk_metadata_tables_static(sp: Span, tables: &MetadataTables) -> P<ast::Item> {
+    let strs_expr = ast::mk::expr_slice(sp, tables.strs.iter().map(|str| ast::mk::expr_str(sp, str)).collect());
+
+    let test_idxs_expr = {
+        let args_token_trees = tables.test_paths.iter().enumerate()
+            .flat_map(|(test_idx, test_path)| {
+                let key_lit = ast::TokenKind::lit(ast::token::LitKind::Str, Symbol::intern(test_path), None);
+                let key_token = ast::mk::tt_token_alone(sp, key_lit);
+
+                let arrow_token = ast::mk::tt_token_alone(sp, ast::TokenKind::FatArrow);
+
+                let value_lit = ast::TokenKind::lit(ast::token::LitKind::Integer, Symbol::intern(&test_idx.to_string()), None);
+                let value_token = ast::mk::tt_token_alone(sp, value_lit);
+
+                let comma_token = ast::mk::tt_token_alone(sp, ast::TokenKind::Comma);
+
+                [key_token, arrow_token, value_token, comma_token]
+            })
+            .collect::<Vec<_>>();
+
+        ast::mk::expr(sp, ast::ExprKind::MacCall(P(ast::MacCall {
+            path: ast::mk::path_local(path::static_map(sp)),
+            args: P(ast::DelimArgs {
+                dspan: ast::tokenstream::DelimSpan::from_single(sp),
+                delim: ast::token::Delimiter::Brace,
+                tokens: ast::mk::token_stream(args_token_trees),
+            })
+        })))
+    };
+
+    // pub(crate) static METADATA_TABLES: MetadataTables = MetadataTables { ... };
+    let vis = ast::mk::vis_pub_crate(sp);
+    let mutbl = ast::Mutability::Not;
+    let ident = Ident::new(*sym::METADATA_TABLES, sp);
+    let ty = ast::mk::ty_path(None, ast::mk::path_local(path::MetadataTables(sp)));
+    let expr = ast::mk::expr_struct(sp, ast::mk::path_local(path::MetadataTables(sp)), thin_vec![
+        ast::mk::expr_struct_field(sp, Ident::new(*sym::strs, sp), strs_expr),
+        ast::mk::expr_struct_field(sp, Ident::new(*sym::test_idxs, sp), test_idxs_expr),
+        ast::mk::expr_struct_field(sp, Ident::new(*sym::span_map, sp), {
+            ast::mk::expr_ref(sp, ast::mk::expr_path(ast::mk::path_local(path::SPAN_MAP(sp))))
+        }),
+    ]);
+    ast::mk::item_static(sp, vis, mutbl, ident, ty, expr)
+}
+
+/// Build the static holding the span map of the generated code, which is referenced by the metadata tables.
+///
+/// The span map can only be built once the generated code is printed, so this item is inserted into the
+/// `mutest_generated` module after printing. See [`crate::codegen::span_map`].
+pub fn mk_span_map_static(sp: Span, generated_file: &str, span_map: &SpanMap) -> P<ast::Item> {
+    let files_expr = ast::mk::expr_slice(sp, span_map.files.iter().map(|file| ast::mk::expr_str(sp, file)).collect());
+
+    let entries_expr = ast::mk::expr_slice(sp, span_map.entries.iter().map(|entry| {
+        ast::mk::expr_tuple(sp, thin_vec![
+            ast::mk::expr_u32(sp, entry.generated_line),
+            ast::mk::expr_u32(sp, entry.generated_column),
+            ast::mk::expr_u32(sp, entry.file_idx),
+            ast::mk::expr_u32(sp, entry.line),
+            ast::mk::expr_u32(sp, entry.column),
+        ])
+    }).collect());
+
+    // pub(crate) static SPAN_MAP: SpanMap = SpanMap { ... };
+    let vis = ast::mk::vis_pub_crate(sp);
+    let mutbl = ast::Mutability::Not;
+    let ident = Ident::new(*sym::SPAN_MAP, sp);
+    let ty = ast::mk::ty_path(None, ast::mk::path_local(path::SpanMap(sp)));
+    let expr = ast::mk::expr_struct(sp, ast::mk::path_local(path::SpanMap(sp)), thin_vec![
+        ast::mk::expr_struct_field(sp, Ident::new(*sym::generated_file, sp), ast::mk::expr_str(sp, generated_file)),
+        ast::mk::expr_struct_field(sp, Ident::new(*sym::files, sp), files_expr),
+        ast::mk::expr_struct_field(sp, Ident::new(*sym::entries, sp), entries_expr),
+    ]);
+    ast::mk::item_static(sp, vis, mutbl, ident, ty, expr)
+}
+
 pub fn bake_mutant(mutant: &Mutant, sp: Span, _sess: &Session, mutations_expr: P<ast::Expr>, subst_map_expr: P<ast::Expr>) -> P<ast::Expr> {
     ast::mk::expr_struct(sp, ast::mk::path_local(path::MutantMeta(sp)), thin_vec![
         ast::mk::expr_struct_field(sp, Ident::new(*sym::id, sp), {
@@ -102,17 +225,18 @@ fn mk_subst_map_ty_alias(sp: Span, subst_locs: &[SubstLoc]) -> P<ast::Item> {
     })))
 }
 
-fn mk_mutations_mod(sp: Span, sess: &Session, mutations: &[&Mut], unsafe_targeting: UnsafeTargeting) -> P<ast::Item> {
-    let g = &sess.psess.attr_id_generator;
+fn mk_mutations_mod<'tcx>(sp: Span, tcx: TyCtxt<'tcx>, mutations: &[&Mut], unsafe_targeting: UnsafeTargeting, tables: &mut MetadataTables) -> P<ast::Item> {
+    let g = &tcx.sess.psess.attr_id_generator;
 
     let items = iter::once(ast::mk::item_extern_crate(sp, *sym::mutest_runtime, None))
         .chain(mutations.iter().map(|mutation| {
-            // pub const $mut_id: MutationMeta = MutationMeta { ... };
+            // pub static $mut_id: MutationMeta = MutationMeta { ... };
             let vis = ast::mk::vis_pub(sp);
+            let mutbl = ast::Mutability::Not;
             let ident = Ident::new(mutation.id.into_symbol(), sp);
             let ty = ast::mk::ty_path(None, ast::mk::path_local(path::MutationMeta(sp)));
-            let expr = bake_mutation(mutation, sp, sess, unsafe_targeting);
-            ast::mk::item_const(sp, vis, ident, ty, expr)
+            let expr = bake_mutation(tcx, mutation, sp, unsafe_targeting, tables);
+            ast::mk::item_static(sp, vis, mutbl, ident, ty, expr)
         }))
         .collect::<ThinVec<_>>();
 
@@ -184,8 +308,9 @@ fn mk_mutants_slice_const(sp: Span, sess: &Session, mutants: &[Mutant], subst_lo
         })
         .collect::<ThinVec<_>>();
 
-    // const MUTANTS: &[&mutest_runtime::MutantMeta<SubstMap>] = &[ ... ];
+    // static MUTANTS: &[&mutest_runtime::MutantMeta<SubstMap>] = &[ ... ];
     let vis = ast::mk::vis_default(sp);
+    let mutbl = ast::Mutability::Not;
     let ident = Ident::new(*sym::MUTANTS, sp);
     let mutant_meta_ty = ast::mk::ty_path(None, ast::mk::pathx_args(sp,
         ast::mk::path_local(path::MutantMeta(sp)),
@@ -194,7 +319,7 @@ fn mk_mutants_slice_const(sp: Span, sess: &Session, mutants: &[Mutant], subst_lo
     ));
     let ty = ast::mk::ty_ref(sp, ast::mk::ty_slice(sp, ast::mk::ty_ref(sp, mutant_meta_ty, None)), None);
     let expr = ast::mk::expr_slice(sp, elements);
-    ast::mk::item_const(sp, vis, ident, ty, expr)
+    ast::mk::item_static(sp, vis, mutbl, ident, ty, expr)
 }
 
 fn mk_active_mutant_handle_static(sp: Span) -> P<ast::Item> {
@@ -211,16 +336,120 @@ fn mk_active_mutant_handle_static(sp: Span) -> P<ast::Item> {
     ast::mk::item_static(sp, vis, mutbl, ident, ty, expr)
 }
 
-fn mk_harness_fn(sp: Span) -> P<ast::Item> {
+struct ResetHookCollector<'tcx> {
+    sess: &'tcx Session,
+    current_path: Vec<Ident>,
+    /// Whether the current module is accessible from the generated harness.
+    current_mod_accessible: bool,
+    reset_hooks: Vec<Vec<Ident>>,
+}
+
+impl<'tcx> ResetHookCollector<'tcx> {
+    /// The generated harness is a child module of the crate root, which can only access private items at the crate
+    /// root. Nested items must be visible to the crate, along with their enclosing modules.
+    fn is_accessible(&self, item: &ast::Item) -> bool {
+        self.current_path.is_empty() || (self.current_mod_accessible && !matches!(item.vis.kind, ast::VisibilityKind::Inherited))
+    }
+}
+
+impl<'ast, 'tcx> ast::visit::Visitor<'ast> for ResetHookCollector<'tcx> {
+    fn visit_item(&mut self, i: &'ast ast::Item) {
+        match &i.kind {
+            ast::ItemKind::Mod(.., ast::ModKind::Loaded(..)) => {
+                let parent_mod_accessible = self.current_mod_accessible;
+                self.current_mod_accessible = self.is_accessible(i);
+                self.current_path.push(i.ident);
+
+                ast::visit::walk_item(self, i);
+
+                self.current_path.pop();
+                self.current_mod_accessible = parent_mod_accessible;
+            }
+
+            ast::ItemKind::Fn(fn_item) if tool_attr::reset_hook(&i.attrs) => {
+                if !fn_item.sig.decl.inputs.is_empty() || !fn_item.generics.params.is_empty() {
+                    let mut diagnostic = self.sess.dcx().struct_err("invalid reset hook");
+                    diagnostic.span(i.span);
+                    diagnostic.note("reset hooks are called without arguments");
+                    diagnostic.emit();
+                    return;
+                }
+
+                if !self.is_accessible(i) {
+                    let mut diagnostic = self.sess.dcx().struct_err("reset hook is not accessible from the crate root");
+                    diagnostic.span(i.span);
+                    diagnostic.help("make the function, and its enclosing modules, visible to the crate (e.g. `pub(crate)`)");
+                    diagnostic.emit();
+                    return;
+                }
+
+                self.reset_hooks.push(self.current_path.iter().copied().chain(iter::once(i.ident)).collect());
+            }
+
+            _ => {}
+        }
+    }
+}
+
+/// Collect the paths of the functions marked with `#[mutest::reset_hook]` in the modules of the crate, which are
+/// registered with the runtime by the generated harness, see `mutest_runtime::register_reset_hook`.
+fn collect_reset_hooks(sess: &Session, krate: &ast::Crate) -> Vec<Vec<Ident>> {
+    let mut collector = ResetHookCollector { sess, current_path: vec![], current_mod_accessible: true, reset_hooks: vec![] };
+    collector.visit_crate(krate);
+    collector.reset_hooks
+}
+
+/// Statically assert that the metadata schema version expected by the runtime matches the version of the generated
+/// metadata. On a mismatch, the array lengths differ, and the harness fails to compile, before any of the metadata is
+/// used.
+fn mk_metadata_version_assertion(sp: Span) -> P<ast::Item> {
+    // const _: [(); $METADATA_VERSION] = [(); mutest_runtime::METADATA_VERSION as usize];
+    let unit_ty = ast::mk::ty_tuple(sp, thin_vec![]);
+    let expected_length = ast::mk::anon_const(sp, ast::mk::expr_usize(sp, METADATA_VERSION as usize).into_inner().kind);
+    let ty = ast::mk::ty_array(sp, unit_ty, expected_length);
+
+    let runtime_version = ast::mk::expr_path(ast::mk::path_local(path::METADATA_VERSION(sp)));
+    let runtime_length = ast::mk::anon_const(sp, ast::mk::expr_cast(sp, runtime_version, ast::mk::ty_ident(sp, None, Ident::new(sym::usize, sp))).into_inner().kind);
+    let expr = ast::mk::expr(sp, ast::ExprKind::Repeat(ast::mk::expr_tuple(sp, thin_vec![]), runtime_length));
+
+    ast::mk::item_const(sp, ast::mk::vis_default(sp), Ident::new(kw::Underscore, sp), ty, expr)
+}
+
+fn mk_harness_fn(sp: Span, subst_dispatch: SubstDispatch, reset_hooks: &[Vec<Ident>], property_tests: &[&Test]) -> P<ast::Item> {
+    // mutest_runtime::register_reset_hook(crate::$reset_hook_path);
+    let calls_register_reset_hook = reset_hooks.iter().map(|reset_hook_path| {
+        let reset_hook_path = ast::mk::path(sp, false, iter::once(Ident::new(kw::Crate, sp)).chain(reset_hook_path.iter().map(|ident| Ident::new(ident.name, sp))).collect());
+        ast::mk::stmt_expr(ast::mk::expr_call(sp,
+            ast::mk::expr_path(ast::mk::path_local(path::register_reset_hook(sp))),
+            thin_vec![ast::mk::expr_path(reset_hook_path)],
+        ))
+    });
+
+    // mutest_runtime::register_property_tests(&[$property_test_names]);
+    let call_register_property_tests = (!property_tests.is_empty()).then(|| {
+        ast::mk::stmt_expr(ast::mk::expr_call(sp,
+            ast::mk::expr_path(ast::mk::path_local(path::register_property_tests(sp))),
+            thin_vec![ast::mk::expr_slice(sp, property_tests.iter().map(|test| ast::mk::expr_str(sp, &test.path_str())).collect())],
+        ))
+    });
+
     // mutest_runtime::mutest_main_static(...);
-    let test_runner = ast::mk::expr_path(ast::mk::path_local(path::mutest_main_static(sp)));
+    // mutest_runtime::mutest_main_static_cfg(...);
+    let test_runner = ast::mk::expr_path(ast::mk::path_local(match subst_dispatch {
+        SubstDispatch::Runtime => path::mutest_main_static(sp),
+        SubstDispatch::Cfg => path::mutest_main_static_cfg(sp),
+    }));
     let call_test_main = ast::mk::stmt_expr(ast::mk::expr_call(sp, test_runner, thin_vec![
         ast::mk::expr_ident(sp, Ident::new(*sym::tests, sp)),
         ast::mk::expr_path(path::MUTANTS(sp)),
         ast::mk::expr_ref(sp, ast::mk::expr_path(path::ACTIVE_MUTANT_HANDLE(sp))),
     ]));
 
-    let body = ast::mk::block(sp, thin_vec![call_test_main]);
+    let body = ast::mk::block(sp, calls_register_reset_hook
+        .chain(call_register_property_tests)
+        .chain(iter::once(call_test_main))
+        .collect()
+    );
 
     // pub(crate) fn harness(tests: &[&test::TestDescAndFn]) { ... }
     let vis = ast::mk::vis_pub_crate(sp);
@@ -234,10 +463,13 @@ fn mk_harness_fn(sp: Span) -> P<ast::Item> {
 }
 
 struct HarnessGenerator<'tcx, 'trg, 'm> {
+    tcx: TyCtxt<'tcx>,
     sess: &'tcx Session,
     unsafe_targeting: UnsafeTargeting,
     mutants: &'m [Mutant<'trg, 'm>],
     subst_locs: &'m [SubstLoc],
+    subst_dispatch: SubstDispatch,
+    tests: &'m [Test],
     def_site: Span,
 }
 
@@ -251,6 +483,9 @@ impl<'tcx, 'trg, 'm> ast::mut_visit::MutVisitor for HarnessGenerator<'tcx, 'trg,
 
         let mutations = FxHashSet::from_iter(self.mutants.iter().flat_map(|m| &m.mutations)).into_iter().collect::<Vec<_>>();
 
+        let reset_hooks = collect_reset_hooks(self.sess, c);
+        let property_tests = self.tests.iter().filter(|test| test.property_test).collect::<Vec<_>>();
+
         // #![feature(test)]
         let feature_test_attr = ast::mk::attr_inner(g, def,
             Ident::new(sym::feature, def),
@@ -282,26 +517,43 @@ impl<'tcx, 'trg, 'm> ast::mut_visit::MutVisitor for HarnessGenerator<'tcx, 'trg,
         // extern crate mutest_runtime;
         let extern_crate_mutest_runtime = ast::mk::item_extern_crate(def, *sym::mutest_runtime, None);
 
+        let mut tables = MetadataTables::default();
+        let mutations_mod = mk_mutations_mod(def, self.tcx, &mutations, self.unsafe_targeting, &mut tables);
+
+        // #[allow(dead_code, unused, unreachable_pub, non_camel_case_types, non_snake_case, non_upper_case_globals)]
+        let allow_generated_code_lints_attr = ast::mk::attr_outer_allow(g, def, &[
+            sym::dead_code,
+            sym::unused,
+            *sym::unreachable_pub,
+            *sym::non_camel_case_types,
+            *sym::non_snake_case,
+            *sym::non_upper_case_globals,
+        ]);
+
         // pub(crate) mod mutest_generated { ... }
-        let mutest_generated_mod = ast::mk::item_mod(def,
+        let mut mutest_generated_mod = ast::mk::item_mod(def,
             ast::mk::vis_pub_crate(def),
             Ident::new(*sym::mutest_generated, def),
             thin_vec![
                 extern_crate_test,
                 extern_crate_mutest_runtime,
+                mk_metadata_version_assertion(def),
                 mk_subst_map_ty_alias(def, &self.subst_locs),
-                mk_mutations_mod(def, self.sess, &mutations, self.unsafe_targeting),
+                mutations_mod,
+                mk_metadata_tables_static(def, &tables),
                 mk_mutants_slice_const(def, self.sess, self.mutants, &self.subst_locs),
                 mk_active_mutant_handle_static(def),
-                mk_harness_fn(def),
+                mk_harness_fn(def, self.subst_dispatch, &reset_hooks, &property_tests),
             ],
         );
 
+        mutest_generated_mod.attrs.push(allow_generated_code_lints_attr);
+
         c.items.push(mutest_generated_mod);
     }
 }
 
-pub fn generate_harness<'tcx>(tcx: TyCtxt<'tcx>, mutants: &[Mutant], subst_locs: &[SubstLoc], krate: &mut ast::Crate, unsafe_targeting: UnsafeTargeting) {
+pub fn generate_harness<'tcx>(tcx: TyCtxt<'tcx>, mutants: &[Mutant], subst_locs: &[SubstLoc], subst_dispatch: SubstDispatch, tests: &[Test], krate: &mut ast::Crate, unsafe_targeting: UnsafeTargeting) {
     let expn_id = tcx.expansion_for_ast_pass(
         AstPass::TestHarness,
         DUMMY_SP,
@@ -309,6 +561,6 @@ pub fn generate_harness<'tcx>(tcx: TyCtxt<'tcx>, mutants: &[Mutant], subst_locs:
     );
     let def_site = DUMMY_SP.with_def_site_ctxt(expn_id.to_expn_id());
 
-    let mut generator = HarnessGenerator { sess: tcx.sess, unsafe_targeting, mutants, subst_locs, def_site };
+    let mut generator = HarnessGenerator { tcx, sess: tcx.sess, unsafe_targeting, mutants, subst_locs, subst_dispatch, tests, def_site };
     generator.visit_crate(krate);
 }
@@ -9,13 +9,13 @@ use rustc_hash::FxHashSet;
 use rustc_hir_analysis::collect::ItemCtxt;
 use rustc_infer::infer::TyCtxtInferExt;
 use rustc_metadata::creader::{CStore, LoadedMacro};
-use rustc_middle::span_bug;
 use rustc_span::edition::Edition;
 use rustc_trait_selection::traits::{ImplSource, Obligation, ObligationCause, SelectionContext};
 use smallvec::{SmallVec, smallvec};
 use thin_vec::{ThinVec, thin_vec};
 
 use crate::analysis::ast_lowering;
+use crate::analysis::diagnostic::{self, InternalErrors, internal_error};
 use crate::analysis::hir::{self, LOCAL_CRATE, NodeExt};
 use crate::analysis::res;
 use crate::analysis::ty::{self, Ty, TyCtxt};
@@ -79,7 +79,7 @@ fn sanitize_ident_if_from_expansion(ident: &mut Ident, ident_res_kind: IdentResK
     };
 
     if bare_ident.starts_with("__rustc_expn_") {
-        span_bug!(ident.span, "encountered ident starting with `__rustc_expn`: the ident might have been sanitized twice");
+        internal_error!(ident.span, "encountered ident starting with `__rustc_expn`: the ident might have been sanitized twice");
     }
 
     ident.name = Symbol::intern(&format!("{prefix}__rustc_expn_{expn_crate_id}_{expn_local_id}_{bare_ident}",
@@ -154,6 +154,9 @@ struct MacroExpansionSanitizer<'tcx, 'op> {
     /// We do not want to sanitize some idents (mostly temporarily) in the AST.
     /// During the visit we keep track of these so that they can be exluded from sanitization.
     protected_idents: FxHashSet<Ident>,
+
+    /// Items skipped due to internal errors during sanitization.
+    internal_errors: &'op mut InternalErrors,
 }
 
 impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
@@ -323,7 +326,7 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
         *path = ast::Path { span: path.span, segments, tokens: None };
 
         if path.segments.is_empty() {
-            span_bug!(path.span, "path was sanitized into an empty path");
+            internal_error!(path.span, "path was sanitized into an empty path");
         }
 
         qself
@@ -397,12 +400,12 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
 
         // Ensure that the def is in the current scope, otherwise it really is not visible from here.
         let Some(current_scope) = self.current_scope else {
-            span_bug!(span, "{} is not accessible in this crate", self.tcx.def_path_str(def_id));
+            internal_error!(span, "{} is not accessible in this crate", self.tcx.def_path_str(def_id));
         };
         match res::locally_visible_def_path(self.tcx, def_id, current_scope) {
             Ok(visible_path) => { return visible_path; }
             Err(adjusted_scope) => {
-                span_bug!(span, "{def} is not defined in the scope {scope} and is not otherwise accessible here",
+                internal_error!(span, "{def} is not defined in the scope {scope} and is not otherwise accessible here",
                     def = self.tcx.def_path_str(def_id),
                     scope = self.tcx.def_path_str(adjusted_scope),
                 );
@@ -416,16 +419,15 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
         S: Into<String>,
         F: FnOnce() -> S,
     {
-        let mut diagnostic = self.tcx.dcx().struct_span_bug(span, msg().into());
-        diagnostic.span_label(span, "no corresponding HIR node found");
-        diagnostic.note(format!("body resolutions from {scope}",
-            scope = match self.current_scope {
-                Some(scope) =>  self.tcx.def_path_debug_str(scope),
-                None => "<unknown scope>".to_owned(),
-            },
-        ));
-        diagnostic.note(format!("expected at {}", std::panic::Location::caller()));
-        diagnostic.emit();
+        diagnostic::raise_internal_error(span, format!("{}: no corresponding HIR node found", msg().into()), vec![
+            format!("body resolutions from {scope}",
+                scope = match self.current_scope {
+                    Some(scope) =>  self.tcx.def_path_debug_str(scope),
+                    None => "<unknown scope>".to_owned(),
+                },
+            ),
+            format!("expected at {}", std::panic::Location::caller()),
+        ]);
     }
 
     #[must_use]
@@ -479,6 +481,11 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
                     | hir::DefKind::ConstParam
                     => {
                         let [param_segment] = &mut path.segments[..] else { unreachable!() };
+                        // Generic params may be introduced at the call-site, but used in a macro expansion
+                        // (e.g. in impls generated by derive-like macros), in which case the use-site ident carries
+                        // a different syntax context than the definition, so we copy it from the definition.
+                        let Some(param_ident_span) = self.tcx.def_ident_span(def_id) else { unreachable!() };
+                        copy_def_span_ctxt(&mut param_segment.ident, param_ident_span);
                         sanitize_ident_if_from_expansion(&mut param_segment.ident, IdentResKind::Def);
 
                         None
@@ -558,7 +565,7 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
         let def_path_handling = ty::print::DefPathHandling::PreferVisible(ty::print::ScopedItemPaths::Trimmed);
         let opaque_ty_handling = ty::print::OpaqueTyHandling::Infer;
         let Some(ty_ast) = ty::ast_repr(self.tcx, self.crate_res, self.def_res, self.current_scope, span, ty, def_path_handling, opaque_ty_handling, true) else {
-            span_bug!(span, "cannot construct AST representation of type `{ty:?}`");
+            internal_error!(span, "cannot construct AST representation of type `{ty:?}`");
         };
 
         ty_ast
@@ -646,7 +653,7 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
             // If the path can be resolved without type-checking, then it will be handled like in `visit_path`.
             (None, Some(res)) => {
                 let None = self.sanitize_path(path, res, None) else {
-                    span_bug!(path.span, "produced unexpected type-relative path for path with simple resolution");
+                    internal_error!(path.span, "produced unexpected type-relative path for path with simple resolution");
                 };
                 res
             }
@@ -657,12 +664,12 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
                     self.bug_unmatched_ast_node(path.span, || format!("unable to resolve path `{}` for sanitization", ast::print::qpath_to_string(qself.as_deref(), path)));
                 };
 
-                let Some(qpath_hir) = self.tcx.hir_node(node_hir_id).qpath() else { span_bug!(path.span, "no corresponding qualified path in HIR") };
+                let Some(qpath_hir) = self.tcx.hir_node(node_hir_id).qpath() else { internal_error!(path.span, "no corresponding qualified path in HIR") };
 
                 match qpath_hir {
                     // NOTE: This corresponds to the already handled case where
                     //       a non-qualified path has a concrete AST resolution.
-                    hir::QPath::Resolved(None, _) => span_bug!(path.span, "encountered unexpected resolved, non-qualified HIR path"),
+                    hir::QPath::Resolved(None, _) => internal_error!(path.span, "encountered unexpected resolved, non-qualified HIR path"),
 
                     | hir::QPath::Resolved(Some(qself_ty_hir), _)
                     | hir::QPath::TypeRelative(qself_ty_hir, _) => {
@@ -676,20 +683,28 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
                             }
                         };
                         if let hir::Res::Err = qres {
-                            match self.tcx.hir_node(node_hir_id) {
+                            let projection_def_id = match self.tcx.hir_node(node_hir_id) {
                                 hir::Node::Ty(ty_hir) => {
                                     let icx = ItemCtxt::new(self.tcx, node_hir_id.owner.def_id);
                                     let ty = icx.lower_ty(ty_hir);
-                                    let ty::TyKind::Alias(ty::AliasTyKind::Projection, alias_ty) = ty.kind() else { unreachable!() };
-
-                                    let trait_item_def_id = alias_ty.def_id;
-                                    let trait_item_def_kind = self.tcx.def_kind(alias_ty.def_id);
-
-                                    qres = hir::Res::Def(trait_item_def_kind, trait_item_def_id);
+                                    match ty.kind() {
+                                        ty::TyKind::Alias(ty::AliasTyKind::Projection, alias_ty) => Some(alias_ty.def_id),
+                                        _ => None,
+                                    }
                                 }
+                                _ => None,
+                            };
 
-                                _ => span_bug!(path.span, "path `{}` cannot be resolved", ast::print::qpath_to_string(qself.as_deref(), path)),
-                            }
+                            let Some(trait_item_def_id) = projection_def_id else {
+                                // NOTE: Without a resolution (e.g. if no type-check results are available for the path),
+                                //       the original path is kept as-is. This is correct as long as the path does not
+                                //       refer to definitions only visible from within a macro expansion.
+                                self.internal_errors.push_unsanitized_path(path.span, ast::print::qpath_to_string(qself.as_deref(), path));
+                                return hir::Res::Err;
+                            };
+                            let trait_item_def_kind = self.tcx.def_kind(trait_item_def_id);
+
+                            qres = hir::Res::Def(trait_item_def_kind, trait_item_def_id);
                         }
 
                         let qself_ty = self.lookup_hir_node_ty(qself_ty_hir);
@@ -785,7 +800,7 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
                                             let infcx = self.tcx.infer_ctxt().build();
                                             let mut selcx = SelectionContext::new(&infcx);
                                             let Ok(Some(ImplSource::UserDefined(data))) = selcx.select(&Obligation::new(self.tcx, ObligationCause::dummy(), param_env, assoc_item_trait_predicate)) else {
-                                                span_bug!(path.span, "cannot resolve impl for `{qself_ty}` of the trait of the associated item {}", self.tcx.def_path_str(trait_item_def_id))
+                                                internal_error!(path.span, "cannot resolve impl for `{qself_ty}` of the trait of the associated item {}", self.tcx.def_path_str(trait_item_def_id))
                                             };
                                             let assoc_item_impl_def_id = data.impl_def_id;
 
@@ -823,13 +838,13 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
                                                     trait_predicate.trait_ref.def_id == assoc_item_trait_def_id
                                                         && trait_predicate.self_ty() == trait_ref.skip_binder().self_ty()
                                                 })
-                                            else { span_bug!(path.span, "cannot find trait predicate related to the trait of associated item {}", self.tcx.def_path_str(trait_item_def_id)) };
+                                            else { internal_error!(path.span, "cannot find trait predicate related to the trait of associated item {}", self.tcx.def_path_str(trait_item_def_id)) };
 
                                             let param_env = self.tcx.param_env(impl_def_id);
                                             let infcx = self.tcx.infer_ctxt().build();
                                             let mut selcx = SelectionContext::new(&infcx);
                                             let Ok(Some(ImplSource::UserDefined(data))) = selcx.select(&Obligation::new(self.tcx, ObligationCause::dummy(), param_env, assoc_item_trait_predicate)) else {
-                                                span_bug!(path.span, "cannot resolve impl for `Self` of the trait of the associated item {}", self.tcx.def_path_str(trait_item_def_id))
+                                                internal_error!(path.span, "cannot resolve impl for `Self` of the trait of the associated item {}", self.tcx.def_path_str(trait_item_def_id))
                                             };
                                             let assoc_item_impl_def_id = data.impl_def_id;
 
@@ -838,7 +853,7 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
                                             return hir::Res::Def(hir::DefKind::AssocTy, impl_item_def_id);
                                         }
 
-                                        _ => span_bug!(path.span, "unhandled associated item root resolution: {parent_path_segment_res:?}"),
+                                        _ => internal_error!(path.span, "unhandled associated item root resolution: {parent_path_segment_res:?}"),
                                     }
                                 }
                             }
@@ -865,7 +880,7 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
                             // then the resolved path no longer needs a qualified self.
                             _ => {
                                 let None = self.sanitize_path(path, qres.expect_non_local(), None) else {
-                                    span_bug!(path.span, "produced unexpected type-relative path for non-assoc path")
+                                    internal_error!(path.span, "produced unexpected type-relative path for non-assoc path")
                                 };
                                 *qself = None;
                             }
@@ -874,7 +889,7 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
                         qres.expect_non_local()
                     }
 
-                    hir::QPath::LangItem(_, _) => span_bug!(path.span, "encountered #[lang] item path"),
+                    hir::QPath::LangItem(_, _) => internal_error!(path.span, "encountered #[lang] item path"),
                 }
             }
         }
@@ -894,7 +909,7 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
 
             let visible_def_path = self.expect_visible_def_path(def_path_request, path.span, ignore_reexport);
             let None = self.overwrite_path_with_def_path(path, &visible_def_path) else {
-                span_bug!(path.span, "produced type-relative path in context which disallows qualified paths");
+                internal_error!(path.span, "produced type-relative path in context which disallows qualified paths");
             };
         };
 
@@ -932,7 +947,7 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
                 return;
             }
 
-            _ => span_bug!(path.span, "import path has non-def last segment"),
+            _ => internal_error!(path.span, "import path has non-def last segment"),
         }
 
         let (mod_path_segments, item_path_segment, item_res, enum_variant) = match &mut path.segments[..] {
@@ -942,9 +957,9 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
 
             [mod_path_segments @ .., item_path_segment] => (mod_path_segments, item_path_segment, res, None),
 
-            _ => span_bug!(path.span, "empty import path"),
+            _ => internal_error!(path.span, "empty import path"),
         };
-        let hir::Res::Def(_, item_def_id) = item_res else { span_bug!(path.span, "import path has non-def item segment") };
+        let hir::Res::Def(_, item_def_id) = item_res else { internal_error!(path.span, "import path has non-def item segment") };
 
         let mod_scope = self.tcx.parent_module(scope).to_def_id();
         let overlay_mod_scope = match self.macros_2_0_top_level_relative_path_res_hack {
@@ -954,9 +969,9 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
 
         let (parent_mod_def_id, referenced_mod_child) = match mod_path_segments {
             [.., parent_mod_path_segment] if let Some(parent_mod_res) = self.def_res.node_res(parent_mod_path_segment.id) => {
-                let hir::Res::Def(hir::DefKind::Mod, parent_mod_def_id) = parent_mod_res else { span_bug!(path.span, "import path has non-mod prefix segment") };
+                let hir::Res::Def(hir::DefKind::Mod, parent_mod_def_id) = parent_mod_res else { internal_error!(path.span, "import path has non-mod prefix segment") };
                 let Some(referenced_mod_child) = res::lookup_mod_child(self.tcx, parent_mod_def_id, item_res.expect_non_local(), item_path_segment.ident.name) else {
-                    span_bug!(path.span, "cannot resolve item {} in module {}", self.tcx.def_path_str(item_def_id), self.tcx.def_path_str(parent_mod_def_id))
+                    internal_error!(path.span, "cannot resolve item {} in module {}", self.tcx.def_path_str(item_def_id), self.tcx.def_path_str(parent_mod_def_id))
                 };
                 (parent_mod_def_id, referenced_mod_child)
             }
@@ -965,7 +980,7 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
             [dollar_crate_segment]  if dollar_crate_segment.ident.name == kw::DollarCrate => {
                 let crate_num = dollar_crate_segment.ident.span.ctxt().outer_expn_data().macro_def_id.unwrap().krate;
                 let Some(referenced_mod_child) = res::lookup_mod_child(self.tcx, crate_num.as_def_id(), item_res.expect_non_local(), item_path_segment.ident.name) else {
-                    span_bug!(path.span, "cannot resolve item {} in module {}", self.tcx.def_path_str(item_def_id), self.tcx.def_path_str(crate_num.as_def_id()))
+                    internal_error!(path.span, "cannot resolve item {} in module {}", self.tcx.def_path_str(item_def_id), self.tcx.def_path_str(crate_num.as_def_id()))
                 };
                 (crate_num.as_def_id(), referenced_mod_child)
             }
@@ -980,7 +995,7 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
                     Some((scope, referenced_mod_child))
                 }) else {
                     let searched_mods = crate_scopes.into_iter().map(|parent_mod_def_id| self.tcx.def_path_str(parent_mod_def_id));
-                    span_bug!(path.span, "cannot resolve item {} in modules {}", self.tcx.def_path_str(item_def_id), searched_mods.intersperse(", ".to_owned()).collect::<String>())
+                    internal_error!(path.span, "cannot resolve item {} in modules {}", self.tcx.def_path_str(item_def_id), searched_mods.intersperse(", ".to_owned()).collect::<String>())
                 };
 
                 (parent_mod_def_id, referenced_mod_child)
@@ -993,7 +1008,7 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
                     parent_mod_def_id = self.tcx.parent_module_from_def_id(parent_mod_def_id.expect_local()).to_def_id();
                 }
                 let Some(referenced_mod_child) = res::lookup_mod_child(self.tcx, parent_mod_def_id, item_res.expect_non_local(), item_path_segment.ident.name) else {
-                    span_bug!(path.span, "cannot resolve item {} in module {}", self.tcx.def_path_str(item_def_id), self.tcx.def_path_str(parent_mod_def_id))
+                    internal_error!(path.span, "cannot resolve item {} in module {}", self.tcx.def_path_str(item_def_id), self.tcx.def_path_str(parent_mod_def_id))
                 };
                 (parent_mod_def_id, referenced_mod_child)
             }
@@ -1007,13 +1022,13 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
                     Some((scope, referenced_mod_child))
                 }) else {
                     let searched_mods = mod_scopes.into_iter().flatten().map(|parent_mod_def_id| self.tcx.def_path_str(parent_mod_def_id));
-                    span_bug!(path.span, "cannot resolve item {} in modules {}", self.tcx.def_path_str(item_def_id), searched_mods.intersperse(", ".to_owned()).collect::<String>())
+                    internal_error!(path.span, "cannot resolve item {} in modules {}", self.tcx.def_path_str(item_def_id), searched_mods.intersperse(", ".to_owned()).collect::<String>())
                 };
 
                 (parent_mod_def_id, referenced_mod_child)
             }
 
-            _ => span_bug!(path.span, "unhandled import path root with missing parent mod resolutions"),
+            _ => internal_error!(path.span, "unhandled import path root with missing parent mod resolutions"),
         };
 
         let mod_child_path_segments_count = match enum_variant {
@@ -1027,10 +1042,10 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
             item_path_segment.ident = def_ident.with_span_pos(item_path_segment.ident.span);
 
             if let Some((enum_variant_path_segment, enum_variant_res)) = enum_variant {
-                let hir::Res::Def(_, enum_variant_def_id) = enum_variant_res else { span_bug!(path.span, "import path has non-def enum child segment") };
+                let hir::Res::Def(_, enum_variant_def_id) = enum_variant_res else { internal_error!(path.span, "import path has non-def enum child segment") };
 
                 let Some(referenced_enum_child) = res::lookup_mod_child(self.tcx, item_def_id, enum_variant_res.expect_non_local(), enum_variant_path_segment.ident.name) else {
-                    span_bug!(path.span, "cannot resolve item {} in enum {}", self.tcx.def_path_str(enum_variant_def_id), self.tcx.def_path_str(item_def_id))
+                    internal_error!(path.span, "cannot resolve item {} in enum {}", self.tcx.def_path_str(enum_variant_def_id), self.tcx.def_path_str(item_def_id))
                 };
 
                 let mut def_ident = referenced_enum_child.ident;
@@ -1090,14 +1105,14 @@ impl<'tcx, 'op> MacroExpansionSanitizer<'tcx, 'op> {
                     let Some(res) = def_res.node_res(node_id).or_else(|| {
                         let [.., last_prefix_segment] = &use_tree.prefix.segments[..] else { return None; };
                         def_res.node_res(last_prefix_segment.id)
-                    }) else { span_bug!(path_segments.last().unwrap().ident.span, "import path cannot be resolved") };
+                    }) else { internal_error!(path_segments.last().unwrap().ident.span, "import path cannot be resolved") };
 
                     imports.push(Import { res, path_segments, node_id, use_kind: UseKind::Glob });
                 }
 
                 ast::UseTreeKind::Simple(rename) => {
                     let Some(import_res) = def_res.import_res(node_id) else {
-                        span_bug!(path_segments.last().unwrap().ident.span, "import path cannot be resolved")
+                        internal_error!(path_segments.last().unwrap().ident.span, "import path cannot be resolved")
                     };
 
                     let import_res_with_ns = [
@@ -1206,6 +1221,7 @@ pub fn sanitize_path<'tcx>(tcx: TyCtxt<'tcx>, crate_res: &res::CrateResolutions<
         current_typeck_ctx: None,
         macros_2_0_top_level_relative_path_res_hack: Macros2_0TopLevelRelativePathResHack::NotInMacros2_0Scope,
         protected_idents: Default::default(),
+        internal_errors: &mut InternalErrors::default(),
     };
 
     if descend_into_args {
@@ -1219,10 +1235,26 @@ pub fn sanitize_path<'tcx>(tcx: TyCtxt<'tcx>, crate_res: &res::CrateResolutions<
     }
 
     let None = sanitizer.adjust_path_from_expansion(path, res, None) else {
-        span_bug!(path.span, "produced type-relative path in context which disallows qualified paths");
+        internal_error!(path.span, "produced type-relative path in context which disallows qualified paths");
     };
 }
 
+trait ItemKindExt {
+    fn is_mod(&self) -> bool;
+}
+
+impl ItemKindExt for ast::ItemKind {
+    fn is_mod(&self) -> bool {
+        matches!(self, ast::ItemKind::Mod(..))
+    }
+}
+
+impl ItemKindExt for ast::AssocItemKind {
+    fn is_mod(&self) -> bool {
+        false
+    }
+}
+
 macro def_flat_map_item_fns(
     $(fn $ident:ident: $item_kind:ident |$self:ident, $item:ident| {
         $(check $check:block)?
@@ -1268,7 +1300,26 @@ macro def_flat_map_item_fns(
                 }
             }
 
-            let item = ast::mut_visit::noop_flat_map_item($item, $self);
+            // Skip items which raise internal errors, retaining the original, unsanitized item.
+            // NOTE: Modules are not caught as a whole, since their items are caught individually.
+            let item = match $item.kind.is_mod() {
+                true => ast::mut_visit::noop_flat_map_item($item, $self),
+                false => {
+                    let original_item = $item.clone();
+                    let previous_macros_2_0_top_level_relative_path_res_hack = $self.macros_2_0_top_level_relative_path_res_hack;
+                    let previous_protected_idents = $self.protected_idents.clone();
+
+                    match diagnostic::catch_internal_error($self.tcx, def_id, "sanitizing macro expansions in", || ast::mut_visit::noop_flat_map_item($item, $self)) {
+                        Ok(item) => item,
+                        Err(error) => {
+                            $self.internal_errors.push(error);
+                            $self.macros_2_0_top_level_relative_path_res_hack = previous_macros_2_0_top_level_relative_path_res_hack;
+                            $self.protected_idents = previous_protected_idents;
+                            smallvec![original_item]
+                        }
+                    }
+                }
+            };
 
             // Restore previous context.
             $self.current_typeck_ctx = previous_typeck_ctx;
@@ -1371,27 +1422,30 @@ impl<'tcx, 'op> ast::mut_visit::MutVisitor for MacroExpansionSanitizer<'tcx, 'op
                 let struct_expr = struct_expr.ast_deref_mut();
                 let res = self.sanitize_qualified_path(&mut struct_expr.qself, &mut struct_expr.path, expr_id);
 
-                let variant_def = match res {
-                    | hir::Res::SelfTyAlias { alias_to: alias_def_id, .. }
-                    | hir::Res::Def(hir::DefKind::TyAlias, alias_def_id)
-                    | hir::Res::Def(hir::DefKind::AssocTy, alias_def_id) => {
-                        let self_ty = self.tcx.type_of(alias_def_id).instantiate_identity();
-                        let ty::TyKind::Adt(adt_def, _) = self_ty.kind() else { unreachable!() };
-                        adt_def.variant_of_res(res.expect_non_local())
-                    }
-                    // Expect a struct, union, or enum variant, and get the corresponding ADT variant.
-                    _ => self.tcx.expect_variant_res(res.expect_non_local()),
-                };
-
-                for field in &mut struct_expr.fields {
-                    let Some(field_def) = variant_def.fields.iter().find(|field_def| self.tcx.hygienic_eq(field.ident, field_def.ident(self.tcx), variant_def.def_id)) else {
-                        span_bug!(field.span, "field `{}` does not match any field of {}", field.ident, self.tcx.def_path_str(variant_def.def_id));
+                // NOTE: The fields of unresolved paths are kept as-is, like the path itself.
+                if !matches!(res, hir::Res::Err) {
+                    let variant_def = match res {
+                        | hir::Res::SelfTyAlias { alias_to: alias_def_id, .. }
+                        | hir::Res::Def(hir::DefKind::TyAlias, alias_def_id)
+                        | hir::Res::Def(hir::DefKind::AssocTy, alias_def_id) => {
+                            let self_ty = self.tcx.type_of(alias_def_id).instantiate_identity();
+                            let ty::TyKind::Adt(adt_def, _) = self_ty.kind() else { unreachable!() };
+                            adt_def.variant_of_res(res.expect_non_local())
+                        }
+                        // Expect a struct, union, or enum variant, and get the corresponding ADT variant.
+                        _ => self.tcx.expect_variant_res(res.expect_non_local()),
                     };
-                    // HACK: Copy ident syntax context from definition for correct sanitization later.
-                    copy_def_span_ctxt(&mut field.ident, field_def.ident(self.tcx).span);
-                    // NOTE: We have to disable shorthand syntax to ensure that
-                    //       the correct field ident appears in printed code.
-                    field.is_shorthand = false;
+
+                    for field in &mut struct_expr.fields {
+                        let Some(field_def) = variant_def.fields.iter().find(|field_def| self.tcx.hygienic_eq(field.ident, field_def.ident(self.tcx), variant_def.def_id)) else {
+                            internal_error!(field.span, "field `{}` does not match any field of {}", field.ident, self.tcx.def_path_str(variant_def.def_id));
+                        };
+                        // HACK: Copy ident syntax context from definition for correct sanitization later.
+                        copy_def_span_ctxt(&mut field.ident, field_def.ident(self.tcx).span);
+                        // NOTE: We have to disable shorthand syntax to ensure that
+                        //       the correct field ident appears in printed code.
+                        field.is_shorthand = false;
+                    }
                 }
 
                 struct_expr.fields.flat_map_in_place(|field| self.flat_map_expr_field(field));
@@ -1442,7 +1496,7 @@ impl<'tcx, 'op> ast::mut_visit::MutVisitor for MacroExpansionSanitizer<'tcx, 'op
                 let Some(typeck) = self.typeck_for(expr_hir.hir_id.owner) else { unreachable!() };
 
                 let Some(call_def_id) = typeck.type_dependent_def_id(expr_hir.hir_id) else {
-                    span_bug!(expr_span, "cannot resolve type dependent def of method call")
+                    internal_error!(expr_span, "cannot resolve type dependent def of method call")
                 };
 
                 // HACK: Copy ident syntax context from definition for correct sanitization later.
@@ -1476,27 +1530,30 @@ impl<'tcx, 'op> ast::mut_visit::MutVisitor for MacroExpansionSanitizer<'tcx, 'op
             ast::PatKind::Struct(qself, path, fields, _) => {
                 let res = self.sanitize_qualified_path(qself, path, pat_id);
 
-                let variant_def = match res {
-                    | hir::Res::SelfTyAlias { alias_to: alias_def_id, .. }
-                    | hir::Res::Def(hir::DefKind::TyAlias, alias_def_id)
-                    | hir::Res::Def(hir::DefKind::AssocTy, alias_def_id) => {
-                        let self_ty = self.tcx.type_of(alias_def_id).instantiate_identity();
-                        let ty::TyKind::Adt(adt_def, _) = self_ty.kind() else { unreachable!() };
-                        adt_def.variant_of_res(res.expect_non_local())
-                    }
-                    // Expect a struct, union, or enum variant, and get the corresponding ADT variant.
-                    _ => self.tcx.expect_variant_res(res.expect_non_local()),
-                };
-
-                for field in &mut *fields {
-                    let Some(field_def) = variant_def.fields.iter().find(|field_def| self.tcx.hygienic_eq(field.ident, field_def.ident(self.tcx), variant_def.def_id)) else {
-                        span_bug!(field.span, "field `{}` does not match any field of {}", field.ident, self.tcx.def_path_str(variant_def.def_id));
+                // NOTE: The fields of unresolved paths are kept as-is, like the path itself.
+                if !matches!(res, hir::Res::Err) {
+                    let variant_def = match res {
+                        | hir::Res::SelfTyAlias { alias_to: alias_def_id, .. }
+                        | hir::Res::Def(hir::DefKind::TyAlias, alias_def_id)
+                        | hir::Res::Def(hir::DefKind::AssocTy, alias_def_id) => {
+                            let self_ty = self.tcx.type_of(alias_def_id).instantiate_identity();
+                            let ty::TyKind::Adt(adt_def, _) = self_ty.kind() else { unreachable!() };
+                            adt_def.variant_of_res(res.expect_non_local())
+                        }
+                        // Expect a struct, union, or enum variant, and get the corresponding ADT variant.
+                        _ => self.tcx.expect_variant_res(res.expect_non_local()),
                     };
-                    // HACK: Copy ident syntax context from definition for correct sanitization later.
-                    copy_def_span_ctxt(&mut field.ident, field_def.ident(self.tcx).span);
-                    // NOTE: We have to disable shorthand syntax to ensure that
-                    //       the correct field ident appears in printed code.
-                    field.is_shorthand = false;
+
+                    for field in &mut *fields {
+                        let Some(field_def) = variant_def.fields.iter().find(|field_def| self.tcx.hygienic_eq(field.ident, field_def.ident(self.tcx), variant_def.def_id)) else {
+                            internal_error!(field.span, "field `{}` does not match any field of {}", field.ident, self.tcx.def_path_str(variant_def.def_id));
+                        };
+                        // HACK: Copy ident syntax context from definition for correct sanitization later.
+                        copy_def_span_ctxt(&mut field.ident, field_def.ident(self.tcx).span);
+                        // NOTE: We have to disable shorthand syntax to ensure that
+                        //       the correct field ident appears in printed code.
+                        field.is_shorthand = false;
+                    }
                 }
 
                 fields.flat_map_in_place(|field| self.flat_map_pat_field(field));
@@ -1543,13 +1600,23 @@ impl<'tcx, 'op> ast::mut_visit::MutVisitor for MacroExpansionSanitizer<'tcx, 'op
     fn visit_path(&mut self, path: &mut ast::Path) {
         let Some(last_segment) = path.segments.last() else { unreachable!(); };
         let Some(res) = self.def_res.node_res(last_segment.id) else {
-            span_bug!(path.span, "path `{}` cannot be resolved by generic path handler", ast::print::path_to_string(path));
+            internal_error!(path.span, "path `{}` cannot be resolved by generic path handler", ast::print::path_to_string(path));
         };
         let None = self.sanitize_path(path, res, None) else {
-            span_bug!(path.span, "produced type-relative path in context which disallows qualified paths");
+            internal_error!(path.span, "produced type-relative path in context which disallows qualified paths");
         };
     }
 
+    fn visit_lifetime(&mut self, lifetime: &mut ast::Lifetime) {
+        // Copy the syntax context of the lifetime param definition, like for other generic params in paths.
+        if let Some(hir::LifetimeRes::Param { param, .. }) = self.def_res.lifetime_res(lifetime.id) {
+            let Some(param_ident_span) = self.tcx.def_ident_span(param) else { unreachable!() };
+            copy_def_span_ctxt(&mut lifetime.ident, param_ident_span);
+        }
+
+        ast::mut_visit::noop_visit_lifetime(lifetime, self);
+    }
+
     fn visit_label(&mut self, label: &mut ast::Label) {
         sanitize_standalone_ident_if_from_expansion(&mut label.ident, IdentResKind::Label);
     }
@@ -1563,10 +1630,10 @@ impl<'tcx, 'op> ast::mut_visit::MutVisitor for MacroExpansionSanitizer<'tcx, 'op
         match &mut vis.kind {
             ast::VisibilityKind::Restricted { path, id, .. } => {
                 let Some(res) = self.def_res.node_res(*id) else {
-                    span_bug!(vis.span, "restricted visibility path `{}` cannot be resolved", ast::print::path_to_string(path));
+                    internal_error!(vis.span, "restricted visibility path `{}` cannot be resolved", ast::print::path_to_string(path));
                 };
                 let None = self.adjust_path_from_expansion(path, res, None) else {
-                    span_bug!(path.span, "produced type-relative path in context which disallows qualified paths");
+                    internal_error!(path.span, "produced type-relative path in context which disallows qualified paths");
                 };
             }
             _ => ast::mut_visit::noop_visit_vis(vis, self),
@@ -1596,7 +1663,7 @@ fn register_builtin_macros(syntax_extensions: &mut Vec<SyntaxExtension>) {
     ]);
 }
 
-pub fn sanitize_macro_expansions<'tcx>(tcx: TyCtxt<'tcx>, crate_res: &res::CrateResolutions<'tcx>, def_res: &ast_lowering::DefResolutions, body_res: &ast_lowering::BodyResolutions<'tcx>, krate: &mut ast::Crate) {
+pub fn sanitize_macro_expansions<'tcx>(tcx: TyCtxt<'tcx>, crate_res: &res::CrateResolutions<'tcx>, def_res: &ast_lowering::DefResolutions, body_res: &ast_lowering::BodyResolutions<'tcx>, krate: &mut ast::Crate, internal_errors: &mut InternalErrors) {
     let mut syntax_extensions = vec![];
     register_builtin_macros(&mut syntax_extensions);
 
@@ -1660,6 +1727,7 @@ pub fn sanitize_macro_expansions<'tcx>(tcx: TyCtxt<'tcx>, crate_res: &res::Crate
         current_typeck_ctx: None,
         macros_2_0_top_level_relative_path_res_hack: Macros2_0TopLevelRelativePathResHack::NotInMacros2_0Scope,
         protected_idents: Default::default(),
+        internal_errors,
     };
     sanitizer.visit_crate(krate);
 
@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use rustc_session::Session;
+use thin_vec::ThinVec;
+
+use crate::codegen::ast;
+use crate::codegen::expansion;
+use crate::codegen::symbols::{DUMMY_SP, Ident, Symbol, sym};
+
+/// Name of the module into which the integration tests are injected.
+pub const INTEGRATION_TESTS_MOD_NAME: &str = "__mutest_integration_tests";
+
+#[derive(Clone, Debug)]
+pub struct IntegrationTest {
+    /// Name of the integration test target, used as the name of its module.
+    pub name: String,
+    /// Path to the crate root of the integration test target.
+    pub path: PathBuf,
+}
+
+/// Inject the integration tests of a library into the library's unit tests, so that the tests of the integration tests
+/// are used as entry points like any other test.
+///
+/// Each integration test is injected as a module which is loaded from the crate root of the integration test target
+/// using a `#[path]` attribute, so that its own modules are resolved relative to the integration test, as they would be
+/// by Cargo. An `extern crate self` item is also inserted, which allows the integration tests to refer to the library
+/// by its name, like they would as separate crates.
+pub fn inject_integration_tests(sess: &Session, krate: &mut ast::Crate, integration_tests: &[IntegrationTest]) {
+    if integration_tests.is_empty() { return; }
+
+    let g = &sess.psess.attr_id_generator;
+
+    let crate_name = rustc_session::output::find_crate_name(sess, &krate.attrs);
+    expansion::insert_extern_crate_self_alias(krate, crate_name);
+
+    let integration_test_mods = integration_tests.iter()
+        .map(|integration_test| {
+            let path = Symbol::intern(&integration_test.path.to_string_lossy());
+            // #[path = "..."]
+            let path_attr = ast::attr::mk_attr_name_value_str(g, ast::AttrStyle::Outer, sym::path, path, DUMMY_SP);
+
+            let mod_kind = ast::ItemKind::Mod(ast::Unsafe::No, ast::ModKind::Unloaded);
+            ast::mk::item(DUMMY_SP, ThinVec::from([path_attr]), ast::mk::vis_default(DUMMY_SP), Ident::from_str(&integration_test.name), mod_kind)
+        })
+        .collect::<ThinVec<_>>();
+
+    let mut integration_tests_mod = ast::mk::item_mod(DUMMY_SP, ast::mk::vis_default(DUMMY_SP), Ident::from_str(INTEGRATION_TESTS_MOD_NAME), integration_test_mods);
+    // #[cfg(test)]
+    integration_tests_mod.attrs.push(ast::attr::mk_attr_nested_word(g, ast::AttrStyle::Outer, sym::cfg, sym::test, DUMMY_SP));
+    // #[allow(unused)]
+    integration_tests_mod.attrs.push(ast::attr::mk_attr_nested_word(g, ast::AttrStyle::Outer, sym::allow, sym::unused, DUMMY_SP));
+
+    krate.items.push(integration_tests_mod);
+}
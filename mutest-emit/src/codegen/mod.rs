@@ -1,9 +1,14 @@
 pub mod ast;
+pub mod doctests;
 pub mod entry_point;
 pub mod expansion;
 pub mod harness;
 pub mod hygiene;
+pub mod integration_tests;
 pub mod mutation;
+pub mod patch;
+pub mod span_map;
 pub mod substitution;
+pub mod suppression;
 pub mod symbols;
 pub mod tool_attr;
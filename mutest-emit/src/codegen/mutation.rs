@@ -1,22 +1,26 @@
+use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::iter;
 use std::marker::PhantomData;
 
-use rustc_hash::{FxHashSet, FxHashMap};
+use rustc_hash::{FxHashSet, FxHashMap, FxHasher};
 use rustc_session::Session;
 use rustc_span::source_map::SourceMap;
 use smallvec::{SmallVec, smallvec};
 
 use crate::analysis::ast_lowering;
-use crate::analysis::call_graph::{Target, UnsafeSource, Unsafety};
-use crate::analysis::diagnostic::{self, SessionRcSourceMap};
+use crate::analysis::call_graph::{Instantiations, Target, UnsafeSource, Unsafety};
+use crate::analysis::dataflow::LazyBodyFacts;
+use crate::analysis::diagnostic::{self, DiagMutestCodeExt, InternalErrors, SessionRcSourceMap};
 use crate::analysis::hir;
 use crate::analysis::res;
-use crate::analysis::ty::TyCtxt;
+use crate::analysis::ty::{self, Ty, TyCtxt};
 use crate::codegen::ast::{self, P};
 use crate::codegen::ast::visit::Visitor;
 use crate::codegen::expansion::TcxExpansionExt;
-use crate::codegen::substitution::conflicting_substs;
-use crate::codegen::symbols::{DUMMY_SP, Ident, Span, Symbol, sym};
+use crate::codegen::substitution::{NodeAncestry, conflicting_substs};
+use crate::codegen::suppression;
+use crate::codegen::symbols::{DUMMY_SP, ExpnKind, Ident, MacroKind, Span, Symbol, path, sym};
 use crate::codegen::symbols::hygiene::AstPass;
 use crate::codegen::tool_attr;
 use crate::session::Options;
@@ -57,10 +61,75 @@ pub struct MutCtxt<'tcx, 'ast, 'op> {
     pub body_res: &'op ast_lowering::BodyResolutions<'tcx>,
     pub def_site: Span,
     pub item_hir: &'op hir::FnItem<'tcx>,
+    /// Dataflow facts of the body of the item, computed when first requested.
+    pub body_facts: &'op LazyBodyFacts<'tcx>,
+    /// Generic arguments of the monomorphic instantiations of the item observed in the call graph, if it is generic.
+    /// Type-level checks of mutations must hold for each of these, not just in the generic context of the item.
+    pub instantiations: &'op [ty::GenericArgsRef<'tcx>],
     pub location: MutLoc<'ast, 'op>,
 }
 
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+/// Helpers for common type-level queries of mutation operators.
+impl<'tcx, 'ast, 'op> MutCtxt<'tcx, 'ast, 'op> {
+    pub fn param_env(&self) -> ty::ParamEnv<'tcx> {
+        self.tcx.param_env(self.item_hir.owner_id.def_id)
+    }
+
+    /// Returns the type-checking results of the body of the item, if it has a body.
+    pub fn typeck(&self) -> Option<&'tcx ty::TypeckResults<'tcx>> {
+        let body_hir = self.item_hir.body?;
+        Some(self.tcx.typeck_body(body_hir.id()))
+    }
+
+    /// Returns the type of the expression, without any adjustments (e.g. auto-deref) applied.
+    pub fn expr_ty(&self, expr: &ast::Expr) -> Option<Ty<'tcx>> {
+        let expr_hir = self.body_res.hir_expr(expr)?;
+        Some(self.typeck()?.expr_ty(expr_hir))
+    }
+
+    /// Returns the type of the pattern.
+    pub fn pat_ty(&self, pat: &ast::Pat) -> Option<Ty<'tcx>> {
+        let pat_hir = self.body_res.hir_pat(pat)?;
+        Some(self.typeck()?.pat_ty(pat_hir))
+    }
+
+    /// Returns whether the type implements the trait with the given generic arguments, in the context of the item,
+    /// and in each of its observed instantiations.
+    pub fn implements(&self, ty: Ty<'tcx>, trait_def_id: hir::DefId, args: Vec<ty::GenericArg<'tcx>>) -> bool {
+        ty::impls_trait_with_env(self.tcx, self.param_env(), ty, trait_def_id, args.clone())
+            && self.holds_for_instantiations((ty, args), |(ty, args), param_env| {
+                ty::impls_trait_with_env(self.tcx, param_env, ty, trait_def_id, args)
+            })
+    }
+
+    /// Returns whether the predicate holds for the value in each observed instantiation of the item
+    /// (see [`ty::holds_for_instantiations`]).
+    pub fn holds_for_instantiations<T>(&self, value: T, predicate: impl FnMut(T, ty::ParamEnv<'tcx>) -> bool) -> bool
+    where
+        T: ty::TypeFoldable<TyCtxt<'tcx>> + Clone,
+    {
+        ty::holds_for_instantiations(self.tcx, self.instantiations, value, predicate)
+    }
+
+    /// Returns the AST representation of the type, with paths as visible from the item.
+    pub fn ty_ast(&self, ty: Ty<'tcx>) -> Option<P<ast::Ty>> {
+        let scope = self.item_hir.owner_id.def_id.to_def_id();
+        let def_path_handling = ty::print::DefPathHandling::PreferVisible(ty::print::ScopedItemPaths::Trimmed);
+        let opaque_ty_handling = ty::print::OpaqueTyHandling::Infer;
+        ty::ast_repr(self.tcx, self.crate_res, self.def_res, Some(scope), self.def_site, ty, def_path_handling, opaque_ty_handling, self.opts.sanitize_macro_expns)
+    }
+
+    /// Returns a `Default::default()` expression, if the type implements `Default`.
+    ///
+    /// The type of the expression is inferred, so the expression must be placed where the type can be inferred
+    /// to be the given type.
+    pub fn make_default_expr(&self, ty: Ty<'tcx>) -> Option<P<ast::Expr>> {
+        if !self.implements(ty, res::traits::Default(self.tcx), vec![]) { return None; }
+        Some(ast::mk::expr_call_path(self.def_site, path::default(self.def_site), thin_vec::thin_vec![]))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum SubstLoc {
     InsertBefore(ast::NodeId),
     InsertAfter(ast::NodeId),
@@ -68,6 +137,14 @@ pub enum SubstLoc {
 }
 
 impl SubstLoc {
+    pub fn node_id(&self) -> ast::NodeId {
+        match *self {
+            Self::InsertBefore(node_id) => node_id,
+            Self::InsertAfter(node_id) => node_id,
+            Self::Replace(node_id) => node_id,
+        }
+    }
+
     pub fn is_dummy(&self) -> bool {
         match *self {
             Self::InsertBefore(node_id) => node_id == ast::DUMMY_NODE_ID,
@@ -115,9 +192,23 @@ impl SubstDef {
     }
 }
 
+/// Priority tier of a mutation, indicating the value of evaluating the mutation.
+///
+/// High priority mutations are the ones most likely to reveal weaknesses in the test suite, and are suitable for quick
+/// runs. Low priority mutations are more likely to be equivalent or redundant, and are only worth evaluating in full
+/// runs.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MutationPriority {
+    Low,
+    Medium,
+    High,
+}
+
 pub trait Mutation {
     fn op_name(&self) -> &str;
 
+    fn priority(&self) -> MutationPriority;
+
     fn display_name(&self) -> String;
 
     fn span_label(&self) -> String {
@@ -191,13 +282,68 @@ impl MutId {
     }
 }
 
+/// Identifier of a mutation which is stable across runs, unlike the sequentially assigned [`MutId`].
+///
+/// It is a hash of the def path of the target, the span of the mutation relative to the containing function, the
+/// mutation operator, and the substitutions of the mutation. As such, it is only invalidated by changes to the
+/// mutation itself, or to the containing function before the mutation.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MutStableId(u64);
+
+impl MutStableId {
+    pub fn new<'tcx>(tcx: TyCtxt<'tcx>, def_id: hir::LocalDefId, fn_span: Span, span: Span, mutation: &dyn Mutation, substs: &[SubstDef]) -> Self {
+        let mut hasher = FxHasher::default();
+
+        tcx.crate_name(hir::LOCAL_CRATE).as_str().hash(&mut hasher);
+        tcx.def_path(def_id.to_def_id()).to_string_no_crate_verbose().hash(&mut hasher);
+
+        // Spans are hashed relative to the containing function, so that unrelated changes elsewhere in the file do not
+        // affect the id.
+        span.lo().0.wrapping_sub(fn_span.lo().0).hash(&mut hasher);
+        span.hi().0.wrapping_sub(fn_span.lo().0).hash(&mut hasher);
+
+        mutation.op_name().hash(&mut hasher);
+        mutation.display_name().hash(&mut hasher);
+
+        for subst in substs {
+            let location_kind = match subst.location {
+                SubstLoc::InsertBefore(_) => "insert_before",
+                SubstLoc::InsertAfter(_) => "insert_after",
+                SubstLoc::Replace(_) => "replace",
+            };
+            location_kind.hash(&mut hasher);
+            subst.substitute.to_source_string().hash(&mut hasher);
+        }
+
+        Self(hasher.finish())
+    }
+
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+
+    /// Parse a stable id from its string representation (see the [`Display`](fmt::Display) implementation).
+    pub fn parse(s: &str) -> Option<Self> {
+        u64::from_str_radix(s, 16).ok().map(Self)
+    }
+}
+
+impl fmt::Display for MutStableId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
 pub struct Mut<'trg, 'm> {
     pub id: MutId,
+    pub stable_id: MutStableId,
     pub target: &'trg Target<'trg>,
     pub span: Span,
     pub is_in_unsafe_block: bool,
     pub mutation: BoxedMutation<'m>,
     pub substs: SmallVec<[SubstDef; 1]>,
+    /// Operators of the duplicate mutations merged into this mutation, see [`dedup_mutations`].
+    pub merged_op_names: Vec<String>,
 }
 
 impl<'trg, 'm> Mut<'trg, 'm> {
@@ -205,12 +351,67 @@ impl<'trg, 'm> Mut<'trg, 'm> {
         self.mutation.op_name()
     }
 
+    /// The operator of the mutation, followed by the operators of the duplicate mutations merged into it.
+    pub fn op_names(&self) -> impl Iterator<Item = &str> {
+        iter::once(self.op_name()).chain(self.merged_op_names.iter().map(String::as_str))
+    }
+
+    /// The operators of the mutation, as reported (e.g. `call_delete, call_value_default_shadow`).
+    pub fn op_names_descr(&self) -> String {
+        self.op_names().collect::<Vec<_>>().join(", ")
+    }
+
     pub fn display_name(&self) -> String {
         self.mutation.display_name()
     }
 
+    pub fn priority(&self) -> MutationPriority {
+        self.mutation.priority()
+    }
+
+    /// The span reported for the mutation. Mutations in the expansion of local macros are reported at the
+    /// outermost macro invocation, rather than at the macro definition.
+    pub fn display_span(&self) -> Span {
+        match is_in_local_macro_expansion(self.span) {
+            true => self.span.source_callsite(),
+            false => self.span,
+        }
+    }
+
     pub fn display_location(&self, sess: &Session) -> String {
-        sess.source_map().span_to_embeddable_string(self.span)
+        sess.source_map().span_to_embeddable_string(self.display_span())
+    }
+
+    /// Single-line textual description of the mutation, which is stable across runs, unlike its [`MutId`], e.g.
+    /// `[math_op_add_sub_swap] swap addition for subtraction in foo::bar at src/foo.rs:12:5: 12:10`.
+    pub fn descriptor(&self, tcx: TyCtxt) -> String {
+        format!("[{op_names}] {display_name} in {def_path} at {display_location}",
+            op_names = self.op_names_descr(),
+            display_name = self.display_name(),
+            def_path = tcx.def_path_str(self.target.def_id.to_def_id()),
+            display_location = self.display_location(tcx.sess),
+        )
+    }
+
+    /// Descriptions of the substitutions of the mutation (e.g. ``expression replaced with: `a - b` ``).
+    pub fn substs_descrs(&self) -> Vec<String> {
+        self.substs.iter()
+            .map(|subst| {
+                let action = match &subst.location {
+                    SubstLoc::InsertBefore(_) | SubstLoc::InsertAfter(_) => "inserted",
+                    SubstLoc::Replace(_) => "replaced with",
+                };
+                let node_kind = subst.substitute.descr();
+                let new_node = subst.substitute.to_source_string();
+                format!("{node_kind} {action}: {new_node}",
+                    new_node = match new_node.lines().count() {
+                        0 => "<empty>".to_owned(),
+                        1 => format!("`{new_node}`"),
+                        _ => format!("\n```\n{new_node}\n```"),
+                    },
+                )
+            })
+            .collect()
     }
 
     pub fn undetected_diagnostic(&self, sess: &Session) -> String {
@@ -219,27 +420,20 @@ impl<'trg, 'm> Mut<'trg, 'm> {
         ));
         diagnostic.span_label(self.span, self.mutation.span_label());
 
-        for subst in &self.substs {
-            let action = match &subst.location {
-                SubstLoc::InsertBefore(_) | SubstLoc::InsertAfter(_) => "inserted",
-                SubstLoc::Replace(_) => "replaced with",
-            };
-            let node_kind = subst.substitute.descr();
-            let new_node = subst.substitute.to_source_string();
-            diagnostic.note(format!("{node_kind} {action}: {new_node}",
-                new_node = match new_node.lines().count() {
-                    0 => "<empty>".to_owned(),
-                    1 => format!("`{new_node}`"),
-                    _ => format!("\n```\n{new_node}\n```"),
-                },
-            ));
+        for subst_descr in self.substs_descrs() {
+            diagnostic.note(subst_descr);
+        }
+
+        // Notes set with `#[mutest::note]`, e.g. to route surviving mutations to their owners.
+        if let Some(note) = self.target.note {
+            diagnostic.note(note.to_string());
         }
 
         diagnostic::emit_str(diagnostic, sess.rc_source_map())
     }
 
     pub fn is_unsafe(&self, unsafe_targeting: UnsafeTargeting) -> bool {
-        self.is_in_unsafe_block || self.target.unsafety.is_unsafe(unsafe_targeting)
+        self.is_in_unsafe_block || self.target.is_unsafe(unsafe_targeting)
     }
 }
 
@@ -293,11 +487,15 @@ struct MutationCollector<'tcx, 'ast, 'op, 'trg, 'm> {
     def_site: Span,
     unsafe_targeting: UnsafeTargeting,
     target: Option<&'trg Target<'trg>>,
-    current_fn: Option<(ast::FnItem<'ast>, hir::FnItem<'tcx>)>,
-    current_closure: Option<hir::BodyId>,
+    instantiations: &'op Instantiations<'tcx>,
+    current_fn: Option<(ast::FnItem<'ast>, hir::FnItem<'tcx>, LazyBodyFacts<'tcx>)>,
+    current_coroutine: Option<hir::BodyId>,
+    current_node: Option<ast::NodeId>,
     is_in_unsafe_block: bool,
+    min_priority: MutationPriority,
     next_mut_index: u32,
     mutations: Vec<Mut<'trg, 'm>>,
+    node_ancestry: &'op mut NodeAncestry,
 }
 
 /// Macro used during mutation collection to apply every mutation operator using the given mutation
@@ -311,13 +509,29 @@ macro register_mutations($self:ident, $($mcx:tt)+) {
 
         for operator in $self.operators {
             for (mutation, substs) in operator.try_apply_boxed(&mcx) {
+                if mutation.priority() < $self.min_priority { continue; }
+
+                let target = $self.target.expect("attempted to collect mutations without a target");
+                let span = mcx.location.span();
+
+                // Mutations suppressed by `// mutest: ...` comments, which refer to the lines of the reported spans.
+                let display_span = match is_in_local_macro_expansion(span) {
+                    true => span.source_callsite(),
+                    false => span,
+                };
+                if suppression::is_suppressed($self.tcx.sess.source_map(), display_span, mutation.op_name()) { continue; }
+
+                let fn_span = mcx.location.containing_fn().map(|fn_item| fn_item.span).unwrap_or(span);
+
                 $self.mutations.push(Mut {
                     id: MutId($self.next_mut_index),
-                    target: $self.target.expect("attempted to collect mutations without a target"),
-                    span: mcx.location.span(),
+                    stable_id: MutStableId::new($self.tcx, target.def_id, fn_span, span, &*mutation, &substs),
+                    target,
+                    span,
                     is_in_unsafe_block: $self.is_in_unsafe_block,
                     mutation,
                     substs,
+                    merged_op_names: vec![],
                 });
 
                 $self.next_mut_index += 1;
@@ -332,15 +546,44 @@ fn is_local_span(source_map: &SourceMap, sp: Span) -> bool {
     local_begin.sf.src.is_some() && local_end.sf.src.is_some()
 }
 
+/// Returns whether the span originates from the expansion of a local bang macro (e.g. `macro_rules`), rather than from
+/// the arguments of the macro invocation.
+pub fn is_in_local_macro_expansion(sp: Span) -> bool {
+    sp.macro_backtrace().any(|expn| {
+        matches!(expn.kind, ExpnKind::Macro(MacroKind::Bang, _)) && expn.macro_def_id.is_some_and(|def_id| def_id.is_local())
+    })
+}
+
 fn report_unmatched_ast_node<'tcx>(tcx: TyCtxt<'tcx>, node_kind: &str, def_id: hir::LocalDefId, span: Span) {
     let mut diagnostic = tcx.dcx().struct_warn(format!("unmatched {node_kind} in {def_path}",
         def_path = tcx.def_path_debug_str(def_id.to_def_id()),
     ));
+    diagnostic.mutest_code(diagnostic::codes::UNMATCHED_AST_NODE);
     diagnostic.span(span);
     diagnostic.span_label(span, "no matching HIR node found");
     diagnostic.emit();
 }
 
+impl<'tcx, 'ast, 'op, 'trg, 'm> MutationCollector<'tcx, 'ast, 'op, 'trg, 'm> {
+    /// Record the node as a child of the currently visited node, and make it the currently visited node.
+    /// Returns the previously visited node, which must be restored once the node's children have been visited.
+    fn enter_node(&mut self, node_id: ast::NodeId) -> Option<ast::NodeId> {
+        if let Some(parent_id) = self.current_node { self.node_ancestry.insert(node_id, parent_id); }
+        self.current_node.replace(node_id)
+    }
+
+    fn is_mutable_span(&self, sp: Span) -> bool {
+        if !is_local_span(self.tcx.sess.source_map(), sp) { return false; }
+        // Code produced by local macros is shared by every invocation of the macro, so it is only mutated on request.
+        if !self.opts.mutate_macro_expansions && is_in_local_macro_expansion(sp) { return false; }
+        true
+    }
+
+    fn instantiations_of(&self, def_id: hir::LocalDefId) -> &'op [ty::GenericArgsRef<'tcx>] {
+        self.instantiations.get(&def_id).map(Vec::as_slice).unwrap_or(&[])
+    }
+}
+
 impl<'tcx, 'ast, 'op, 'trg, 'm> ast::visit::Visitor<'ast> for MutationCollector<'tcx, 'ast, 'op, 'trg, 'm> {
     fn visit_fn(&mut self, kind: ast::visit::FnKind<'ast>, span: Span, id: ast::NodeId) {
         let ast::visit::FnKind::Fn(ctx, ident, sig, vis, generics, body) = kind else { return; };
@@ -348,6 +591,7 @@ impl<'tcx, 'ast, 'op, 'trg, 'm> ast::visit::Visitor<'ast> for MutationCollector<
 
         let Some(fn_def_id) = self.def_res.node_id_to_def_id.get(&fn_ast.id).copied() else { unreachable!() };
         let Some(fn_hir) = hir::FnItem::from_node(self.tcx, self.tcx.hir_node_by_def_id(fn_def_id)) else { unreachable!() };
+        let body_facts = LazyBodyFacts::new(self.tcx, fn_def_id);
 
         register_mutations!(self, MutCtxt {
             opts: self.opts,
@@ -357,16 +601,19 @@ impl<'tcx, 'ast, 'op, 'trg, 'm> ast::visit::Visitor<'ast> for MutationCollector<
             body_res: self.body_res,
             def_site: self.def_site,
             item_hir: &fn_hir,
+            body_facts: &body_facts,
+            instantiations: self.instantiations_of(fn_def_id),
             location: MutLoc::Fn(&fn_ast),
         });
 
-        self.current_fn = Some((fn_ast, fn_hir));
+        self.current_fn = Some((fn_ast, fn_hir, body_facts));
+        self.current_node = None;
         ast::visit::walk_fn(self, kind);
         self.current_fn = None;
     }
 
     fn visit_param(&mut self, param: &'ast ast::Param) {
-        let Some((fn_ast, fn_hir)) = &self.current_fn else { return; };
+        let Some((fn_ast, fn_hir, body_facts)) = &self.current_fn else { return; };
         let Some(param_hir) = self.body_res.hir_param(param) else {
             if self.opts.verbosity >= 1 {
                 report_unmatched_ast_node(self.tcx, "parameter", fn_hir.owner_id.def_id, param.span);
@@ -374,12 +621,12 @@ impl<'tcx, 'ast, 'op, 'trg, 'm> ast::visit::Visitor<'ast> for MutationCollector<
             return;
         };
 
-        if !is_local_span(self.tcx.sess.source_map(), param.span) { return; };
+        if !self.is_mutable_span(param.span) { return; };
         if tool_attr::ignore(self.tcx.hir().attrs(param_hir.hir_id)) { return; }
 
-        // FIXME: Nested function bodies are currently not represented in `MutLoc`, so we skip them for now to
-        //        avoid generating leaking, malformed mutations.
-        if let Some(_) = self.current_closure { return; }
+        // FIXME: Coroutine bodies (e.g. `async` blocks) are currently not represented in `MutLoc`, so we skip them for
+        //        now to avoid generating leaking, malformed mutations.
+        if let Some(_) = self.current_coroutine { return; }
 
         register_mutations!(self, MutCtxt {
             opts: self.opts,
@@ -389,6 +636,8 @@ impl<'tcx, 'ast, 'op, 'trg, 'm> ast::visit::Visitor<'ast> for MutationCollector<
             body_res: self.body_res,
             def_site: self.def_site,
             item_hir: fn_hir,
+            body_facts,
+            instantiations: self.instantiations_of(fn_hir.owner_id.def_id),
             location: MutLoc::FnParam(param, fn_ast),
         });
 
@@ -396,7 +645,7 @@ impl<'tcx, 'ast, 'op, 'trg, 'm> ast::visit::Visitor<'ast> for MutationCollector<
     }
 
     fn visit_block(&mut self, block: &'ast ast::Block) {
-        let Some((_fn_ast, fn_hir)) = &self.current_fn else { return; };
+        let Some((_fn_ast, fn_hir, _body_facts)) = &self.current_fn else { return; };
         let Some(block_hir) = self.body_res.hir_block(block) else {
             if self.opts.verbosity >= 1 {
                 report_unmatched_ast_node(self.tcx, "block", fn_hir.owner_id.def_id, block.span);
@@ -404,7 +653,7 @@ impl<'tcx, 'ast, 'op, 'trg, 'm> ast::visit::Visitor<'ast> for MutationCollector<
             return;
         };
 
-        if !is_local_span(self.tcx.sess.source_map(), block.span) { return; };
+        if !self.is_mutable_span(block.span) { return; };
         if tool_attr::ignore(self.tcx.hir().attrs(block_hir.hir_id)) { return; }
         if !self.unsafe_targeting.inside_unsafe() && let ast::BlockCheckMode::Unsafe(_) = block.rules { return; }
 
@@ -425,7 +674,7 @@ impl<'tcx, 'ast, 'op, 'trg, 'm> ast::visit::Visitor<'ast> for MutationCollector<
             _ => {}
         }
 
-        let Some((fn_ast, fn_hir)) = &self.current_fn else { return; };
+        let Some((fn_ast, fn_hir, body_facts)) = &self.current_fn else { return; };
         let Some(stmt_hir) = self.body_res.hir_stmt(stmt) else {
             if self.opts.verbosity >= 1 {
                 report_unmatched_ast_node(self.tcx, "statement", fn_hir.owner_id.def_id, stmt.span);
@@ -433,12 +682,12 @@ impl<'tcx, 'ast, 'op, 'trg, 'm> ast::visit::Visitor<'ast> for MutationCollector<
             return;
         };
 
-        if !is_local_span(self.tcx.sess.source_map(), stmt.span) { return; };
+        if !self.is_mutable_span(stmt.span) { return; };
         if tool_attr::ignore(self.tcx.hir().attrs(stmt_hir.hir_id)) { return; }
 
-        // FIXME: Nested function bodies are currently not represented in `MutLoc`, so we skip them for now to
-        //        avoid generating leaking, malformed mutations.
-        if let Some(_) = self.current_closure { return; }
+        // FIXME: Coroutine bodies (e.g. `async` blocks) are currently not represented in `MutLoc`, so we skip them for
+        //        now to avoid generating leaking, malformed mutations.
+        if let Some(_) = self.current_coroutine { return; }
 
         register_mutations!(self, MutCtxt {
             opts: self.opts,
@@ -448,10 +697,14 @@ impl<'tcx, 'ast, 'op, 'trg, 'm> ast::visit::Visitor<'ast> for MutationCollector<
             body_res: self.body_res,
             def_site: self.def_site,
             item_hir: fn_hir,
+            body_facts,
+            instantiations: self.instantiations_of(fn_hir.owner_id.def_id),
             location: MutLoc::FnBodyStmt(stmt, fn_ast),
         });
 
+        let parent_node = self.enter_node(stmt.id);
         ast::visit::walk_stmt(self, stmt);
+        self.current_node = parent_node;
     }
 
     fn visit_expr(&mut self, expr: &'ast ast::Expr) {
@@ -461,7 +714,7 @@ impl<'tcx, 'ast, 'op, 'trg, 'm> ast::visit::Visitor<'ast> for MutationCollector<
             _ => {}
         }
 
-        let Some((fn_ast, fn_hir)) = &self.current_fn else { return; };
+        let Some((fn_ast, fn_hir, body_facts)) = &self.current_fn else { return; };
         let Some(expr_hir) = self.body_res.hir_expr(expr) else {
             if self.opts.verbosity >= 1 {
                 report_unmatched_ast_node(self.tcx, "expression", fn_hir.owner_id.def_id, expr.span);
@@ -469,12 +722,12 @@ impl<'tcx, 'ast, 'op, 'trg, 'm> ast::visit::Visitor<'ast> for MutationCollector<
             return;
         };
 
-        if !is_local_span(self.tcx.sess.source_map(), expr.span) { return; };
+        if !self.is_mutable_span(expr.span) { return; };
         if tool_attr::ignore(self.tcx.hir().attrs(expr_hir.hir_id)) { return; }
 
-        // FIXME: Nested function bodies are currently not represented in `MutLoc`, so we skip them for now to
-        //        avoid generating leaking, malformed mutations.
-        if let Some(_) = self.current_closure { return; }
+        // FIXME: Coroutine bodies (e.g. `async` blocks) are currently not represented in `MutLoc`, so we skip them for
+        //        now to avoid generating leaking, malformed mutations.
+        if let Some(_) = self.current_coroutine { return; }
 
         // Ignore block expressions with only a single nested node, visit the nested node instead.
         if let ast::ExprKind::Block(block_ast, _) = &expr.kind && block_ast.stmts.len() == 1 {
@@ -489,11 +742,21 @@ impl<'tcx, 'ast, 'op, 'trg, 'm> ast::visit::Visitor<'ast> for MutationCollector<
             body_res: self.body_res,
             def_site: self.def_site,
             item_hir: fn_hir,
+            body_facts,
+            instantiations: self.instantiations_of(fn_hir.owner_id.def_id),
             location: MutLoc::FnBodyExpr(expr, fn_ast),
         });
 
-        let current_closure = self.current_closure;
-        if let hir::ExprKind::Closure(&hir::Closure { body, .. }) = expr_hir.kind { self.current_closure = Some(body); }
+        let parent_node = self.enter_node(expr.id);
+
+        let current_coroutine = self.current_coroutine;
+        let is_coroutine = match expr_hir.kind {
+            hir::ExprKind::Closure(&hir::Closure { body, kind: hir::ClosureKind::Coroutine(_) | hir::ClosureKind::CoroutineClosure(_), .. }) => {
+                self.current_coroutine = Some(body);
+                true
+            }
+            _ => false,
+        };
 
         match &expr.kind {
             // The left-hand side of assignment expressions only supports a strict subset of expressions, not including
@@ -509,6 +772,11 @@ impl<'tcx, 'ast, 'op, 'trg, 'm> ast::visit::Visitor<'ast> for MutationCollector<
                     if let Some(body) = &arm.body { self.visit_expr(body); }
                 }
             }
+            // The bodies of closures are mutated as part of the enclosing function. The parameters of closures are not
+            // mutated, since `MutLoc::FnParam` only represents the parameters of the enclosing function.
+            ast::ExprKind::Closure(closure) if !is_coroutine => {
+                self.visit_expr(&closure.body);
+            }
             // The `else` branch of an `if` conditional must be either another `if` conditional or a block, so we do
             // not mutate `else` blocks directly, instead visiting its contents.
             ast::ExprKind::If(_, _, _) => {
@@ -531,7 +799,8 @@ impl<'tcx, 'ast, 'op, 'trg, 'm> ast::visit::Visitor<'ast> for MutationCollector<
             _ => ast::visit::walk_expr(self, expr),
         }
 
-        if let hir::ExprKind::Closure(_) = expr_hir.kind { self.current_closure = current_closure; }
+        if is_coroutine { self.current_coroutine = current_coroutine; }
+        self.current_node = parent_node;
     }
 
     fn visit_attribute(&mut self, _attr: &'ast ast::Attribute) {}
@@ -554,9 +823,13 @@ pub fn apply_mutation_operators<'ast, 'tcx, 'trg, 'm>(
     body_res: &ast_lowering::BodyResolutions<'tcx>,
     krate: &'ast ast::Crate,
     targets: impl Iterator<Item = &'trg Target<'trg>>,
+    instantiations: &Instantiations<'tcx>,
     ops: Operators<'_, 'm>,
     unsafe_targeting: UnsafeTargeting,
+    min_priority: MutationPriority,
     opts: &Options,
+    node_ancestry: &mut NodeAncestry,
+    internal_errors: &mut InternalErrors,
 ) -> Vec<Mut<'trg, 'm>> {
     let expn_id = tcx.expansion_for_ast_pass(
         AstPass::TestHarness,
@@ -575,32 +848,250 @@ pub fn apply_mutation_operators<'ast, 'tcx, 'trg, 'm>(
         def_site,
         unsafe_targeting,
         target: None,
+        instantiations,
         current_fn: None,
-        current_closure: None,
+        current_coroutine: None,
+        current_node: None,
         is_in_unsafe_block: false,
+        min_priority,
         next_mut_index: 1,
         mutations: vec![],
+        node_ancestry,
     };
 
     for target in targets {
-        if !unsafe_targeting.any() && target.unsafety.any() { continue; }
-        if !unsafe_targeting.inside_unsafe() && let Unsafety::Unsafe(UnsafeSource::Unsafe) | Unsafety::Tainted(UnsafeSource::Unsafe) = target.unsafety { continue; }
+        if target.is_excluded(unsafe_targeting) { continue; }
+        // Skip items which previously raised internal errors.
+        if internal_errors.is_skipped(tcx, target.def_id) { continue; }
 
         collector.target = Some(target);
+        collector.unsafe_targeting = target.unsafe_targeting(unsafe_targeting);
         collector.is_in_unsafe_block = target.unsafety == Unsafety::Unsafe(UnsafeSource::Unsafe);
 
-        let Some(target_item) = ast_lowering::find_def_in_ast(tcx, def_res, target.def_id, krate) else { continue; };
+        let previous_mutations_count = collector.mutations.len();
+        let previous_next_mut_index = collector.next_mut_index;
+
+        let result = diagnostic::catch_internal_error(tcx, target.def_id, "applying mutation operators to", || {
+            let Some(target_item) = ast_lowering::find_def_in_ast(tcx, def_res, target.def_id, krate) else { return; };
+
+            match target_item {
+                ast::DefItem::Item(item) => collector.visit_item(item),
+                ast::DefItem::ForeignItem(item) => collector.visit_foreign_item(item),
+                ast::DefItem::AssocItem(item, ctx) => collector.visit_assoc_item(item, ctx),
+            }
+        });
 
-        match target_item {
-            ast::DefItem::Item(item) => collector.visit_item(item),
-            ast::DefItem::ForeignItem(item) => collector.visit_foreign_item(item),
-            ast::DefItem::AssocItem(item, ctx) => collector.visit_assoc_item(item, ctx),
+        if let Err(error) = result {
+            // Discard the partial mutations of the target, and reset the state of the interrupted visit.
+            collector.mutations.truncate(previous_mutations_count);
+            collector.next_mut_index = previous_next_mut_index;
+            collector.current_fn = None;
+            collector.current_coroutine = None;
+            collector.current_node = None;
+
+            internal_errors.push(error);
         }
     }
 
     collector.mutations
 }
 
+/// Policy by which the mutations of targets with more mutations than the per-target maximum are kept, see
+/// [`limit_mutations_per_target`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TargetCapPolicy {
+    /// Keep evenly spaced mutations in the order in which they were generated, covering the entire body of the target.
+    Spaced,
+    /// Keep a diverse subset of the mutations, preferring one mutation of each operator in each basic region of the
+    /// target (see [`MutationRegions`]), over more mutations of the same operator in the same region.
+    Diverse,
+    /// Keep the first mutations in the order in which they were generated.
+    First,
+    /// Keep randomly chosen mutations.
+    Random,
+}
+
+/// Basic region of each mutation, i.e. the innermost block of the body of its target enclosing it, identified by the
+/// index of the block in the body. Mutations outside of any block (e.g. in the signature of the target) have no region.
+#[derive(Default)]
+pub struct MutationRegions {
+    regions: FxHashMap<MutId, u32>,
+}
+
+impl MutationRegions {
+    pub fn new<'tcx>(tcx: TyCtxt<'tcx>, mutations: &[Mut]) -> Self {
+        struct BlockSpanCollector {
+            block_spans: Vec<Span>,
+        }
+
+        impl<'tcx> hir::intravisit::Visitor<'tcx> for BlockSpanCollector {
+            fn visit_block(&mut self, block: &'tcx hir::Block<'tcx>) {
+                self.block_spans.push(block.span);
+                hir::intravisit::walk_block(self, block);
+            }
+        }
+
+        let mut target_block_spans: FxHashMap<hir::LocalDefId, Vec<Span>> = Default::default();
+        let mut regions: FxHashMap<MutId, u32> = Default::default();
+
+        for mutation in mutations {
+            let block_spans = target_block_spans.entry(mutation.target.def_id).or_insert_with(|| {
+                let Some(body_id) = tcx.hir_node_by_def_id(mutation.target.def_id).body_id() else { return vec![]; };
+                let mut collector = BlockSpanCollector { block_spans: vec![] };
+                hir::intravisit::Visitor::visit_body(&mut collector, tcx.hir().body(body_id));
+                collector.block_spans
+            });
+
+            let innermost_block = block_spans.iter().enumerate()
+                .filter(|(_, block_span)| block_span.contains(mutation.span))
+                .min_by_key(|(_, block_span)| block_span.hi() - block_span.lo());
+            if let Some((block_idx, _)) = innermost_block {
+                regions.insert(mutation.id, block_idx as u32);
+            }
+        }
+
+        Self { regions }
+    }
+
+    pub fn region(&self, mutation_id: MutId) -> Option<u32> {
+        self.regions.get(&mutation_id).copied()
+    }
+}
+
+/// Indices of the mutations of a target to keep, in the order in which they were generated. The mutations are ranked
+/// by how many mutations of the same operator in the same region precede them, then by how many other regions the
+/// operator was already found in, so that each operator is first kept once in as many regions as possible.
+fn diverse_mutation_indices(mutations: &[&Mut], max_mutations: usize, mutation_regions: &MutationRegions) -> Vec<usize> {
+    let mut op_regions: FxHashMap<&str, Vec<Option<u32>>> = Default::default();
+    let mut op_region_mutations_counts: FxHashMap<(&str, Option<u32>), usize> = Default::default();
+
+    let mut ranked_mutation_indices = mutations.iter().enumerate()
+        .map(|(i, mutation)| {
+            let region = mutation_regions.region(mutation.id);
+
+            let regions = op_regions.entry(mutation.op_name()).or_default();
+            let region_ordinal = match regions.iter().position(|r| *r == region) {
+                Some(region_ordinal) => region_ordinal,
+                None => { regions.push(region); regions.len() - 1 }
+            };
+
+            let op_region_mutations_count = op_region_mutations_counts.entry((mutation.op_name(), region)).or_default();
+            let rank = (*op_region_mutations_count, region_ordinal, i);
+            *op_region_mutations_count += 1;
+
+            rank
+        })
+        .collect::<Vec<_>>();
+
+    ranked_mutation_indices.sort_unstable();
+    let mut kept_mutation_indices = ranked_mutation_indices.into_iter().take(max_mutations).map(|(_, _, i)| i).collect::<Vec<_>>();
+    kept_mutation_indices.sort_unstable();
+    kept_mutation_indices
+}
+
+/// Limit the number of mutations of each target to the specified maximum, so that targets with many mutable
+/// expressions do not dominate the mutations. The kept mutations are chosen according to the policy. Mutation regions
+/// are only used by [`TargetCapPolicy::Diverse`], and the random number generator by [`TargetCapPolicy::Random`].
+///
+/// The mutations are renumbered to keep their ids contiguous. Returns the number of trimmed mutations of each target.
+pub fn limit_mutations_per_target<'trg, 'm>(
+    mutations: &mut Vec<Mut<'trg, 'm>>,
+    max_mutations_per_target: usize,
+    policy: TargetCapPolicy,
+    mutation_regions: &MutationRegions,
+    rng: &mut impl rand::Rng,
+) -> FxHashMap<hir::LocalDefId, usize> {
+    let mut target_mutation_indices: FxHashMap<hir::LocalDefId, Vec<usize>> = Default::default();
+    for (i, mutation) in mutations.iter().enumerate() {
+        target_mutation_indices.entry(mutation.target.def_id).or_default().push(i);
+    }
+
+    // Targets are processed in the order of their first mutation, so that random choices are reproducible.
+    let mut target_mutation_indices = target_mutation_indices.into_values().collect::<Vec<_>>();
+    target_mutation_indices.sort_unstable_by_key(|mutation_indices| mutation_indices[0]);
+
+    let mut trimmed_mutations_counts: FxHashMap<hir::LocalDefId, usize> = Default::default();
+    let mut kept_mutation_indices: FxHashSet<usize> = Default::default();
+
+    for mutation_indices in target_mutation_indices {
+        let total = mutation_indices.len();
+        if total <= max_mutations_per_target {
+            kept_mutation_indices.extend(mutation_indices);
+            continue;
+        }
+
+        let kept_positions = match policy {
+            TargetCapPolicy::Spaced => (0..max_mutations_per_target).map(|kept| kept * total / max_mutations_per_target).collect::<Vec<_>>(),
+            TargetCapPolicy::First => (0..max_mutations_per_target).collect(),
+            TargetCapPolicy::Random => rand::seq::index::sample(rng, total, max_mutations_per_target).into_vec(),
+            TargetCapPolicy::Diverse => {
+                let target_mutations = mutation_indices.iter().map(|&i| &mutations[i]).collect::<Vec<_>>();
+                diverse_mutation_indices(&target_mutations, max_mutations_per_target, mutation_regions)
+            }
+        };
+
+        trimmed_mutations_counts.insert(mutations[mutation_indices[0]].target.def_id, total - max_mutations_per_target);
+        kept_mutation_indices.extend(kept_positions.into_iter().map(|position| mutation_indices[position]));
+    }
+
+    let mut i = 0;
+    mutations.retain(|_| {
+        let keep = kept_mutation_indices.contains(&i);
+        i += 1;
+        keep
+    });
+
+    for (i, mutation) in mutations.iter_mut().enumerate() {
+        mutation.id = MutId(i as u32 + 1);
+    }
+
+    trimmed_mutations_counts
+}
+
+/// Merge mutations which make byte-identical substitutions at the same locations, which happens if multiple operators
+/// produce the same replacement. The first of the duplicate mutations is kept, and the operators of the others are
+/// recorded in [`Mut::merged_op_names`], so that the mutation is only evaluated, and counted towards the mutation
+/// score, once.
+///
+/// Returns the number of merged mutations. Mutation ids are reassigned to stay sequential.
+pub fn dedup_mutations<'trg, 'm>(mutations: &mut Vec<Mut<'trg, 'm>>) -> usize {
+    let mut first_mutation_idxs: FxHashMap<Vec<(SubstLoc, String)>, usize> = Default::default();
+    let mut merged_mutation_idxs: FxHashSet<usize> = Default::default();
+
+    for idx in 0..mutations.len() {
+        let mut substs_key = mutations[idx].substs.iter()
+            .map(|subst| (subst.location, subst.substitute.to_source_string()))
+            .collect::<Vec<_>>();
+        substs_key.sort_unstable();
+
+        let Some(&first_idx) = first_mutation_idxs.get(&substs_key) else {
+            first_mutation_idxs.insert(substs_key, idx);
+            continue;
+        };
+
+        let merged_op_names = iter::once(mutations[idx].op_name().to_owned()).chain(mutations[idx].merged_op_names.drain(..)).collect::<Vec<_>>();
+        let first_mutation = &mut mutations[first_idx];
+        for op_name in merged_op_names {
+            if first_mutation.op_names().any(|first_op_name| first_op_name == op_name) { continue; }
+            first_mutation.merged_op_names.push(op_name);
+        }
+        merged_mutation_idxs.insert(idx);
+    }
+
+    let mut idx = 0;
+    mutations.retain(|_| {
+        let keep = !merged_mutation_idxs.contains(&idx);
+        idx += 1;
+        keep
+    });
+
+    for (i, mutation) in mutations.iter_mut().enumerate() {
+        mutation.id = MutId(i as u32 + 1);
+    }
+
+    merged_mutation_idxs.len()
+}
+
 pub enum MutationError<'trg, 'm> {
     DummySubsts(&'m Mut<'trg, 'm>, Vec<&'m SubstDef>),
 }
@@ -716,7 +1207,7 @@ impl<'m, 'op> Iterator for MutationConflictGraphCompatibilityIter<'m, 'op> {
     }
 }
 
-pub fn generate_mutation_conflict_graph<'trg, 'm>(mutations: &[Mut<'trg, 'm>], unsafe_targeting: UnsafeTargeting) -> MutationConflictGraph<'m> {
+pub fn generate_mutation_conflict_graph<'trg, 'm>(mutations: &[Mut<'trg, 'm>], node_ancestry: &NodeAncestry, unsafe_targeting: UnsafeTargeting) -> MutationConflictGraph<'m> {
     let mut unsafes: FxHashSet<MutId> = Default::default();
     let mut conflicts: FxHashSet<(MutId, MutId)> = Default::default();
 
@@ -731,10 +1222,13 @@ pub fn generate_mutation_conflict_graph<'trg, 'm>(mutations: &[Mut<'trg, 'm>], u
                 // Unsafe mutations cannot be batched with any other mutation.
                 || mutation.is_unsafe(unsafe_targeting)
                 || other.is_unsafe(unsafe_targeting)
+                // Mutants only contain mutations of the same priority, so that they can be evaluated by priority.
+                || mutation.priority() != other.priority()
                 // To discern results related to the various mutations of a mutant, they have to have distinct entry points.
                 || conflicting_targets(&mutation.target, &other.target)
-                // The substitutions that make up each mutation cannot conflict with each other.
-                || mutation.substs.iter().any(|s| other.substs.iter().any(|s_other| conflicting_substs(s, s_other)));
+                // The substitutions that make up each mutation cannot conflict with each other, including substitutions
+                // nested within nodes replaced by the other mutation.
+                || mutation.substs.iter().any(|s| other.substs.iter().any(|s_other| conflicting_substs(node_ancestry, s, s_other)));
 
             if is_conflicting {
                 conflicts.insert((mutation.id, other.id));
@@ -783,14 +1277,107 @@ pub fn batch_mutations_dummy<'trg, 'm>(mutations: Vec<Mut<'trg, 'm>>) -> Vec<Mut
     mutants
 }
 
+/// Estimated cost of evaluating tests, used to limit the size of mutants by the cost of the tests they are evaluated
+/// against, rather than just by the number of their mutations.
+pub struct TestCostModel {
+    /// Estimated cost of each test (e.g. its profiled execution time in seconds).
+    pub test_costs: FxHashMap<hir::LocalDefId, f64>,
+    /// Estimated cost of tests without a known cost.
+    pub default_test_cost: f64,
+}
+
+impl TestCostModel {
+    pub fn test_cost(&self, test_def_id: hir::LocalDefId) -> f64 {
+        self.test_costs.get(&test_def_id).copied().unwrap_or(self.default_test_cost)
+    }
+
+    /// Estimated cost of evaluating the mutations together, which is the total cost of the union of tests reaching any
+    /// of the mutations.
+    pub fn mutations_cost<'a, 'trg: 'a, 'm: 'a>(&self, mutations: impl Iterator<Item = &'a Mut<'trg, 'm>>) -> f64 {
+        mutations
+            .flat_map(|mutation| mutation.target.reachable_from.keys().map(|test| test.def_id))
+            .collect::<FxHashSet<_>>()
+            .into_iter()
+            .map(|test_def_id| self.test_cost(test_def_id))
+            .sum()
+    }
+}
+
+/// Limits on the size of the mutants produced by mutation batching.
+#[derive(Clone, Copy)]
+pub struct MutantCapacity<'c> {
+    /// Maximum number of mutations in a mutant.
+    pub max_mutations_count: usize,
+    /// Maximum estimated cost of evaluating a mutant, according to the test cost model.
+    /// Mutations exceeding the cost on their own are placed in their own mutant.
+    pub max_cost: Option<(f64, &'c TestCostModel)>,
+}
+
+impl<'c> MutantCapacity<'c> {
+    pub fn max_mutations_count(max_mutations_count: usize) -> Self {
+        Self { max_mutations_count, max_cost: None }
+    }
+}
+
+/// Locality by which mutation batching prefers to group mutations into the same mutant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BatchLocality {
+    None,
+    /// Prefer batching mutations of the same source file.
+    File,
+    /// Prefer batching mutations of the same module.
+    Module,
+}
+
+/// Locality of each mutation (e.g. the index of its source file), see [`BatchLocality`].
+#[derive(Default)]
+pub struct MutationLocalities {
+    localities: FxHashMap<MutId, u32>,
+}
+
+impl MutationLocalities {
+    pub fn new<'tcx>(tcx: TyCtxt<'tcx>, mutations: &[Mut], batch_locality: BatchLocality) -> Self {
+        let localities = match batch_locality {
+            BatchLocality::None => Default::default(),
+            BatchLocality::File => {
+                mutations.iter()
+                    .map(|mutation| (mutation.id, tcx.sess.source_map().lookup_source_file_idx(mutation.display_span().lo()) as u32))
+                    .collect()
+            }
+            BatchLocality::Module => {
+                mutations.iter()
+                    .map(|mutation| (mutation.id, tcx.parent_module_from_def_id(mutation.target.def_id).to_local_def_id().local_def_index.as_u32()))
+                    .collect()
+            }
+        };
+
+        Self { localities }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.localities.is_empty()
+    }
+
+    /// Number of localities the mutations span beyond the first one.
+    fn locality_splits<'a, 'trg: 'a, 'm: 'a>(&self, mutations: impl Iterator<Item = &'a Mut<'trg, 'm>>) -> usize {
+        if self.is_empty() { return 0; }
+
+        let localities = mutations.filter_map(|mutation| self.localities.get(&mutation.id)).collect::<FxHashSet<_>>();
+        localities.len().saturating_sub(1)
+    }
+}
+
 fn compatible_mutant<'trg, 'm>(
     mutation: &Mut<'trg, 'm>,
     mutant: &Mutant<'trg, 'm>,
     mutation_conflict_graph: &MutationConflictGraph<'m>,
-    mutant_max_mutations_count: usize,
+    mutant_capacity: MutantCapacity,
 ) -> bool {
     // Ensure the mutant has not already reached capacity.
-    if mutant.mutations.len() >= mutant_max_mutations_count { return false; }
+    if mutant.mutations.len() >= mutant_capacity.max_mutations_count { return false; }
+    if let Some((max_cost, test_cost_model)) = mutant_capacity.max_cost {
+        if test_cost_model.mutations_cost(mutant.mutations.iter().chain(iter::once(mutation))) > max_cost { return false; }
+    }
 
     // The mutation must not conflict with any other mutation already in the mutant.
     if mutant.mutations.iter().any(|m| mutation_conflict_graph.conflicting_mutations(m.id, mutation.id)) { return false; }
@@ -804,7 +1391,7 @@ fn choose_random_mutant<'trg, 'm, 'a>(
     mutation: &Mut<'trg, 'm>,
     mutants: &'a mut [Mutant<'trg, 'm>],
     mutation_conflict_graph: &MutationConflictGraph<'m>,
-    mutant_max_mutations_count: usize,
+    mutant_capacity: MutantCapacity,
     rng: &mut impl rand::Rng,
 ) -> Option<&'a mut Mutant<'trg, 'm>> {
     use rand::prelude::*;
@@ -815,16 +1402,20 @@ fn choose_random_mutant<'trg, 'm, 'a>(
     if mutation_conflict_graph.is_unsafe(mutation.id) { return None; }
 
     // Filter out mutants so that we only consider compatible mutants.
-    let possible_mutants = mutants.iter_mut().filter(|mutant| compatible_mutant(mutation, mutant, mutation_conflict_graph, mutant_max_mutations_count));
+    let possible_mutants = mutants.iter_mut().filter(|mutant| compatible_mutant(mutation, mutant, mutation_conflict_graph, mutant_capacity));
     // Choose random compatible mutant, if available.
     possible_mutants.choose_stable(rng)
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub enum GreedyMutationBatchingOrderingHeuristic {
     ConflictsAsc,
     ConflictsDesc,
     Random,
+    /// Interleave the mutations of different targets, taking one mutation from each target in turn.
+    /// Mutations of the same target are likely to conflict, so spreading them out lets the first mutants be packed
+    /// with mutually compatible mutations.
+    TargetDiversity,
 }
 
 pub fn batch_mutations_greedy<'trg, 'm>(
@@ -833,7 +1424,8 @@ pub fn batch_mutations_greedy<'trg, 'm>(
     ordering_heuristic: Option<GreedyMutationBatchingOrderingHeuristic>,
     epsilon: Option<f64>,
     mut rng: Option<&mut impl rand::Rng>,
-    mutant_max_mutations_count: usize,
+    mutant_capacity: MutantCapacity,
+    mutation_localities: &MutationLocalities,
 ) -> Vec<Mutant<'trg, 'm>> {
     use GreedyMutationBatchingOrderingHeuristic::*;
     match ordering_heuristic {
@@ -864,6 +1456,22 @@ pub fn batch_mutations_greedy<'trg, 'm>(
             mutations.shuffle(rng);
         }
 
+        Some(TargetDiversity) => {
+            // Rank each mutation by its position among the mutations of its target. A stable sort by this rank
+            // interleaves the targets, while preserving the relative order of mutations.
+            let mut target_mutations_counts: FxHashMap<hir::LocalDefId, usize> = Default::default();
+            let mutation_target_ranks = mutations.iter()
+                .map(|mutation| {
+                    let target_mutations_count = target_mutations_counts.entry(mutation.target.def_id).or_insert(0);
+                    let rank = *target_mutations_count;
+                    *target_mutations_count += 1;
+                    (mutation.id, rank)
+                })
+                .collect::<FxHashMap<_, _>>();
+
+            mutations.sort_by_key(|mutation| mutation_target_ranks[&mutation.id]);
+        }
+
         None => {}
     }
 
@@ -883,12 +1491,18 @@ pub fn batch_mutations_greedy<'trg, 'm>(
                 // mutation. If the random choice is true, then instead of making a greedy choice, a random compatible
                 // mutant is picked the same way as in random batching.
                 if rng.gen_bool(epsilon) {
-                    break 'mutant_candidate choose_random_mutant(&mutation, &mut mutants, mutation_conflict_graph, mutant_max_mutations_count, rng);
+                    break 'mutant_candidate choose_random_mutant(&mutation, &mut mutants, mutation_conflict_graph, mutant_capacity, rng);
                 }
             }
 
-            // Pick the first mutant the current mutation is compatible with.
-            mutants.iter_mut().find(|mutant| compatible_mutant(&mutation, mutant, mutation_conflict_graph, mutant_max_mutations_count))
+            let mut compatible_mutants = mutants.iter_mut().filter(|mutant| compatible_mutant(&mutation, mutant, mutation_conflict_graph, mutant_capacity));
+
+            // Pick the first mutant the current mutation is compatible with, preferring mutants whose mutations are
+            // of the same locality, if any.
+            match mutation_localities.is_empty() {
+                true => compatible_mutants.next(),
+                false => compatible_mutants.min_by_key(|mutant| mutation_localities.locality_splits(mutant.mutations.iter().chain(iter::once(&mutation)))),
+            }
         };
 
         match mutant_candidate {
@@ -903,17 +1517,70 @@ pub fn batch_mutations_greedy<'trg, 'm>(
     mutants
 }
 
+/// Reuse the mutant batching of a previous run for the mutations which still exist, identified by their stable ids,
+/// preserving the ids of their mutants. Mutations are only kept in their previous mutant if they remain compatible with
+/// the rest of its mutations.
+///
+/// Returns the reused mutants, and the remaining mutations, which have to be batched separately. The resulting mutants
+/// can be combined with the reused ones using [`merge_mutant_batches`].
+pub fn reuse_mutant_batches<'trg, 'm>(
+    mutations: Vec<Mut<'trg, 'm>>,
+    previous_mutant_batches: &[(u32, Vec<MutStableId>)],
+    mutation_conflict_graph: &MutationConflictGraph<'m>,
+    mutant_capacity: MutantCapacity,
+) -> (Vec<Mutant<'trg, 'm>>, Vec<Mut<'trg, 'm>>) {
+    let mut mutation_idxs_by_stable_id: FxHashMap<MutStableId, usize> = Default::default();
+    for (idx, mutation) in mutations.iter().enumerate() {
+        mutation_idxs_by_stable_id.entry(mutation.stable_id).or_insert(idx);
+    }
+
+    let mut mutations = mutations.into_iter().map(Some).collect::<Vec<_>>();
+    let mut reused_mutants: Vec<Mutant<'trg, 'm>> = vec![];
+
+    for (mutant_index, stable_ids) in previous_mutant_batches {
+        let mut mutant = Mutant { id: MutantId(*mutant_index), mutations: vec![] };
+
+        for stable_id in stable_ids {
+            let Some(&idx) = mutation_idxs_by_stable_id.get(stable_id) else { continue; };
+            let Some(mutation) = &mutations[idx] else { continue; };
+            if !compatible_mutant(mutation, &mutant, mutation_conflict_graph, mutant_capacity) { continue; }
+
+            let Some(mutation) = mutations[idx].take() else { unreachable!() };
+            mutant.mutations.push(mutation);
+        }
+
+        if !mutant.mutations.is_empty() { reused_mutants.push(mutant); }
+    }
+
+    (reused_mutants, mutations.into_iter().flatten().collect())
+}
+
+/// Combine reused mutants (see [`reuse_mutant_batches`]) with newly batched mutants, renumbering the new mutants to
+/// follow the ids of the reused mutants.
+pub fn merge_mutant_batches<'trg, 'm>(mut reused_mutants: Vec<Mutant<'trg, 'm>>, new_mutants: Vec<Mutant<'trg, 'm>>) -> Vec<Mutant<'trg, 'm>> {
+    let mut next_mutant_index = reused_mutants.iter().map(|mutant| mutant.id.0).max().unwrap_or(0) + 1;
+
+    reused_mutants.sort_by_key(|mutant| mutant.id.0);
+    for mut mutant in new_mutants {
+        mutant.id = MutantId(next_mutant_index);
+        next_mutant_index += 1;
+        reused_mutants.push(mutant);
+    }
+
+    reused_mutants
+}
+
 pub fn batch_mutations_random<'trg, 'm>(
     mutations: Vec<Mut<'trg, 'm>>,
     mutation_conflict_graph: &MutationConflictGraph<'m>,
-    mutant_max_mutations_count: usize,
+    mutant_capacity: MutantCapacity,
     rng: &mut impl rand::Rng,
 ) -> Vec<Mutant<'trg, 'm>> {
     let mut mutants: Vec<Mutant<'trg, 'm>> = vec![];
     let mut next_mutant_index = 1;
 
     for mutation in mutations {
-        let mutant_candidate = choose_random_mutant(&mutation, &mut mutants, mutation_conflict_graph, mutant_max_mutations_count, rng);
+        let mutant_candidate = choose_random_mutant(&mutation, &mut mutants, mutation_conflict_graph, mutant_capacity, rng);
 
         match mutant_candidate {
             Some(mutant) => mutant.mutations.push(mutation),
@@ -930,10 +1597,14 @@ pub fn batch_mutations_random<'trg, 'm>(
 pub fn optimize_batches_simulated_annealing<'trg, 'm>(
     mutants: &mut Vec<Mutant<'trg, 'm>>,
     mutation_conflict_graph: &MutationConflictGraph<'m>,
-    mutant_max_mutations_count: usize,
+    mutant_capacity: MutantCapacity,
+    mutation_localities: &MutationLocalities,
     max_iterations: usize,
     rng: &mut impl rand::Rng,
 ) {
+    /// Energy of a mutant spanning an additional locality, relative to the energy of an additional mutant.
+    const LOCALITY_SPLIT_ENERGY: f64 = 0.5;
+
     #[derive(Clone, Copy)]
     enum StateChange {
         MoveMutation { mutation_id: MutId, old_mutant_id: MutantId, new_mutant_id: MutantId },
@@ -958,7 +1629,7 @@ pub fn optimize_batches_simulated_annealing<'trg, 'm>(
     fn random_neighbour_state<'trg, 'm>(
         mutants: &mut Vec<Mutant<'trg, 'm>>,
         mutation_conflict_graph: &MutationConflictGraph<'m>,
-        mutant_max_mutations_count: usize,
+        mutant_capacity: MutantCapacity,
         rng: &mut impl rand::Rng,
     ) -> StateChange {
         use rand::prelude::*;
@@ -969,7 +1640,7 @@ pub fn optimize_batches_simulated_annealing<'trg, 'm>(
 
             let compatible_mutants = mutants.iter()
                 .filter(|m| m.id != random_mutant.id)
-                .filter(|m| compatible_mutant(random_mutation, m, mutation_conflict_graph, mutant_max_mutations_count));
+                .filter(|m| compatible_mutant(random_mutation, m, mutation_conflict_graph, mutant_capacity));
             let Some(new_random_mutant) = compatible_mutants.choose_stable(rng) else { continue; };
 
             break (random_mutant, random_mutation, new_random_mutant);
@@ -982,19 +1653,33 @@ pub fn optimize_batches_simulated_annealing<'trg, 'm>(
         max_iterations as f64 * (1_f64 - iterations_expended)
     }
 
-    fn energy<'trg, 'm>(mutants: &[Mutant<'trg, 'm>], state_change: Option<StateChange>) -> f64 {
+    fn energy<'trg, 'm>(mutants: &[Mutant<'trg, 'm>], state_change: Option<StateChange>, mutation_localities: &MutationLocalities) -> f64 {
         let mut mutants_count = mutants.len();
+        let mut locality_splits = match mutation_localities.is_empty() {
+            true => 0,
+            false => mutants.iter().map(|m| mutation_localities.locality_splits(m.mutations.iter())).sum::<usize>(),
+        };
 
         if let Some(state_change) = state_change {
             match state_change {
-                StateChange::MoveMutation { mutation_id: _, old_mutant_id, new_mutant_id: _ } => {
+                StateChange::MoveMutation { mutation_id, old_mutant_id, new_mutant_id } => {
                     let Some(old_mutant) = mutants.iter().find(|m| m.id == old_mutant_id) else { unreachable!(); };
                     if old_mutant.mutations.len() <= 1 { mutants_count -= 1; }
+
+                    if !mutation_localities.is_empty() {
+                        let Some(new_mutant) = mutants.iter().find(|m| m.id == new_mutant_id) else { unreachable!(); };
+                        let Some(mutation) = old_mutant.mutations.iter().find(|m| m.id == mutation_id) else { unreachable!(); };
+
+                        locality_splits -= mutation_localities.locality_splits(old_mutant.mutations.iter());
+                        locality_splits -= mutation_localities.locality_splits(new_mutant.mutations.iter());
+                        locality_splits += mutation_localities.locality_splits(old_mutant.mutations.iter().filter(|m| m.id != mutation_id));
+                        locality_splits += mutation_localities.locality_splits(new_mutant.mutations.iter().chain(iter::once(mutation)));
+                    }
                 }
             }
         }
 
-        mutants_count as f64
+        mutants_count as f64 + LOCALITY_SPLIT_ENERGY * locality_splits as f64
     }
 
     fn acceptance_probability(curr_energy: f64, next_energy: f64, temp: f64) -> f64 {
@@ -1005,12 +1690,275 @@ pub fn optimize_batches_simulated_annealing<'trg, 'm>(
     for i in 0..max_iterations {
         let temp = temperature(1_f64 - ((i + 1) as f64 / max_iterations as f64), max_iterations);
 
-        let curr_energy = energy(mutants, None);
+        let curr_energy = energy(mutants, None, mutation_localities);
 
-        let state_change = random_neighbour_state(mutants, mutation_conflict_graph, mutant_max_mutations_count, rng);
-        let next_energy = energy(mutants, Some(state_change));
+        let state_change = random_neighbour_state(mutants, mutation_conflict_graph, mutant_capacity, rng);
+        let next_energy = energy(mutants, Some(state_change), mutation_localities);
         if acceptance_probability(curr_energy, next_energy, temp) >= rng.gen_range(0_f64..1_f64) {
             state_change.apply(mutants);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DummyMutation;
+
+    impl Mutation for DummyMutation {
+        fn op_name(&self) -> &str { "dummy" }
+        fn priority(&self) -> MutationPriority { MutationPriority::High }
+        fn display_name(&self) -> String { "dummy mutation".to_owned() }
+    }
+
+    struct OtherDummyMutation;
+
+    impl Mutation for OtherDummyMutation {
+        fn op_name(&self) -> &str { "other_dummy" }
+        fn priority(&self) -> MutationPriority { MutationPriority::High }
+        fn display_name(&self) -> String { "other dummy mutation".to_owned() }
+    }
+
+    fn mk_targets(count: usize) -> Vec<Target<'static>> {
+        (0..count)
+            .map(|i| Target {
+                def_id: hir::LocalDefId { local_def_index: hir::DefIndex::from_usize(i) },
+                unsafety: Unsafety::None,
+                reachable_from: Default::default(),
+                distance: 0,
+                unsafe_policy: None,
+                note: None,
+            })
+            .collect()
+    }
+
+    /// Mutations with ids starting at 1, each in the target of the corresponding index.
+    fn mk_mutations<'trg>(targets: &'trg [Target<'trg>], mutation_target_idxs: &[usize]) -> Vec<Mut<'trg, 'static>> {
+        iter::zip(1.., mutation_target_idxs)
+            .map(|(i, &target_idx)| Mut {
+                id: MutId(i),
+                stable_id: MutStableId(i as u64),
+                target: &targets[target_idx],
+                span: DUMMY_SP,
+                is_in_unsafe_block: false,
+                mutation: Box::new(DummyMutation),
+                substs: smallvec![],
+                merged_op_names: vec![],
+            })
+            .collect()
+    }
+
+    fn mk_conflict_graph(n_mutations: u32, conflicts: impl IntoIterator<Item = (u32, u32)>) -> MutationConflictGraph<'static> {
+        MutationConflictGraph {
+            n_mutations,
+            unsafes: Default::default(),
+            conflicts: conflicts.into_iter().map(|(a, b)| (MutId(a), MutId(b))).collect(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Number of mutants produced by greedy batching with each deterministic ordering heuristic.
+    fn greedy_mutants_counts(
+        targets: &[Target<'_>],
+        mutation_target_idxs: &[usize],
+        mutation_conflict_graph: &MutationConflictGraph<'static>,
+        mutant_capacity: MutantCapacity,
+    ) -> [(Option<GreedyMutationBatchingOrderingHeuristic>, usize); 4] {
+        use GreedyMutationBatchingOrderingHeuristic::*;
+
+        [None, Some(ConflictsAsc), Some(ConflictsDesc), Some(TargetDiversity)].map(|ordering_heuristic| {
+            let mutations = mk_mutations(targets, mutation_target_idxs);
+            let mutants = batch_mutations_greedy(mutations, mutation_conflict_graph, ordering_heuristic, None, None::<&mut rand::rngs::StdRng>, mutant_capacity, &MutationLocalities::default());
+            (ordering_heuristic, mutants.len())
+        })
+    }
+
+    #[test]
+    fn test_target_diversity_conflicting_targets() {
+        // 4 targets with 3 mutations each, where all mutations of a target conflict with each other.
+        let targets = mk_targets(4);
+        let mutation_target_idxs = [0, 0, 0, 1, 1, 1, 2, 2, 2, 3, 3, 3];
+        let conflicts = (0..4).flat_map(|t| [(3 * t + 1, 3 * t + 2), (3 * t + 1, 3 * t + 3), (3 * t + 2, 3 * t + 3)]);
+        let mutation_conflict_graph = mk_conflict_graph(12, conflicts);
+
+        let mutants_counts = greedy_mutants_counts(&targets, &mutation_target_idxs, &mutation_conflict_graph, MutantCapacity::max_mutations_count(2));
+        let [.., (_, target_diversity_mutants_count)] = mutants_counts;
+
+        // 12 mutations in mutants of at most 2 mutations.
+        assert_eq!(target_diversity_mutants_count, 6);
+        for (ordering_heuristic, mutants_count) in mutants_counts {
+            assert!(target_diversity_mutants_count <= mutants_count, "{ordering_heuristic:?} produced fewer mutants");
+        }
+    }
+
+    #[test]
+    fn test_target_diversity_crown_conflicts() {
+        // Each of the 4 targets has mutations u_i and v_i, with u_i conflicting with every v_j, where i != j.
+        // Batching the mutations target by target forces a new mutant for each target, whereas interleaving the
+        // targets batches all u_i, and all v_i together.
+        let targets = mk_targets(4);
+        let mutation_target_idxs = [0, 0, 1, 1, 2, 2, 3, 3];
+        let u = |i: u32| 2 * i + 1;
+        let v = |i: u32| 2 * i + 2;
+        let conflicts = (0..4).flat_map(|i| (0..4).filter(move |&j| j != i).map(move |j| (u(i), v(j))));
+        let mutation_conflict_graph = mk_conflict_graph(8, conflicts);
+
+        let mutants_counts = greedy_mutants_counts(&targets, &mutation_target_idxs, &mutation_conflict_graph, MutantCapacity::max_mutations_count(usize::MAX));
+        let [(_, no_ordering_mutants_count), .., (_, target_diversity_mutants_count)] = mutants_counts;
+
+        assert_eq!(no_ordering_mutants_count, 4);
+        assert_eq!(target_diversity_mutants_count, 2);
+        for (ordering_heuristic, mutants_count) in mutants_counts {
+            assert!(target_diversity_mutants_count <= mutants_count, "{ordering_heuristic:?} produced fewer mutants");
+        }
+    }
+
+    #[test]
+    fn test_batch_locality_groups_mutations_of_same_locality() {
+        // Mutations 1 and 4 are in one file, mutations 2 and 3 in another. Mutations 1 and 2 conflict, so mutations 3
+        // and 4 are both compatible with either mutant.
+        let targets = mk_targets(1);
+        let mutation_target_idxs = [0, 0, 0, 0];
+        let mutation_conflict_graph = mk_conflict_graph(4, [(1, 2)]);
+        let mutation_localities = MutationLocalities {
+            localities: [(MutId(1), 0), (MutId(2), 1), (MutId(3), 1), (MutId(4), 0)].into_iter().collect(),
+        };
+
+        let mutants_mutation_ids = |mutation_localities: &MutationLocalities| {
+            let mutations = mk_mutations(&targets, &mutation_target_idxs);
+            let mutants = batch_mutations_greedy(mutations, &mutation_conflict_graph, None, None, None::<&mut rand::rngs::StdRng>, MutantCapacity::max_mutations_count(2), mutation_localities);
+            mutants.iter().map(|mutant| mutant.mutations.iter().map(|mutation| mutation.id.index()).collect::<Vec<_>>()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(mutants_mutation_ids(&MutationLocalities::default()), [vec![1, 3], vec![2, 4]]);
+        assert_eq!(mutants_mutation_ids(&mutation_localities), [vec![1, 4], vec![2, 3]]);
+    }
+
+    /// Ids of the mutants, with the stable ids of their mutations.
+    fn mutants_stable_ids(mutants: &[Mutant]) -> Vec<(u32, Vec<u64>)> {
+        mutants.iter()
+            .map(|mutant| (mutant.id.0, mutant.mutations.iter().map(|mutation| mutation.stable_id.0).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn test_reuse_mutant_batches_drops_removed_mutations() {
+        // Mutations 7, 8, and 9 no longer exist, and mutation 3 is new.
+        let targets = mk_targets(1);
+        let mutations = mk_mutations(&targets, &[0, 0, 0]);
+        let mutation_conflict_graph = mk_conflict_graph(3, []);
+        let previous_mutant_batches = [(1, vec![MutStableId(1), MutStableId(7)]), (2, vec![MutStableId(8), MutStableId(2)]), (4, vec![MutStableId(9)])];
+
+        let (reused_mutants, remaining_mutations) = reuse_mutant_batches(mutations, &previous_mutant_batches, &mutation_conflict_graph, MutantCapacity::max_mutations_count(usize::MAX));
+
+        assert_eq!(mutants_stable_ids(&reused_mutants), [(1, vec![1]), (2, vec![2])]);
+        assert_eq!(remaining_mutations.iter().map(|mutation| mutation.stable_id.0).collect::<Vec<_>>(), [3]);
+    }
+
+    #[test]
+    fn test_reuse_mutant_batches_splits_out_conflicting_mutations() {
+        // Mutations 1 and 2 were batched together, but now conflict. Mutation 6 no longer fits into its mutant, since
+        // the capacity of mutants was reduced.
+        let targets = mk_targets(1);
+        let mutations = mk_mutations(&targets, &[0, 0, 0, 0, 0, 0]);
+        let mutation_conflict_graph = mk_conflict_graph(6, [(1, 2)]);
+        let previous_mutant_batches = [
+            (1, vec![MutStableId(1), MutStableId(2), MutStableId(3)]),
+            (2, vec![MutStableId(4), MutStableId(5), MutStableId(6)]),
+        ];
+
+        let (reused_mutants, remaining_mutations) = reuse_mutant_batches(mutations, &previous_mutant_batches, &mutation_conflict_graph, MutantCapacity::max_mutations_count(2));
+
+        assert_eq!(mutants_stable_ids(&reused_mutants), [(1, vec![1, 3]), (2, vec![4, 5])]);
+        assert_eq!(remaining_mutations.iter().map(|mutation| mutation.stable_id.0).collect::<Vec<_>>(), [2, 6]);
+    }
+
+    #[test]
+    fn test_merge_mutant_batches_renumbers_new_mutants() {
+        let targets = mk_targets(1);
+        let mutations = mk_mutations(&targets, &[0, 0, 0, 0]);
+        let mutation_conflict_graph = mk_conflict_graph(4, []);
+        let previous_mutant_batches = [(3, vec![MutStableId(1)]), (1, vec![MutStableId(2)])];
+
+        let (reused_mutants, remaining_mutations) = reuse_mutant_batches(mutations, &previous_mutant_batches, &mutation_conflict_graph, MutantCapacity::max_mutations_count(1));
+        let new_mutants = batch_mutations_dummy(remaining_mutations);
+        assert_eq!(mutants_stable_ids(&new_mutants), [(1, vec![3]), (2, vec![4])]);
+
+        // Reused mutants keep their ids, and new mutants are numbered after the last reused mutant.
+        let mutants = merge_mutant_batches(reused_mutants, new_mutants);
+        assert_eq!(mutants_stable_ids(&mutants), [(1, vec![2]), (3, vec![1]), (4, vec![3]), (5, vec![4])]);
+
+        // Without any reused mutants, new mutants are numbered from the start.
+        let mutations = mk_mutations(&targets, &[0, 0]);
+        let mutants = merge_mutant_batches(vec![], batch_mutations_dummy(mutations));
+        assert_eq!(mutants_stable_ids(&mutants), [(1, vec![1]), (2, vec![2])]);
+    }
+
+    /// Stable ids of the mutations kept by limiting the mutations per target with the policy.
+    fn kept_mutation_stable_ids(mutations: &mut Vec<Mut>, max_mutations_per_target: usize, policy: TargetCapPolicy, mutation_regions: &MutationRegions) -> Vec<u64> {
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(0);
+        limit_mutations_per_target(mutations, max_mutations_per_target, policy, mutation_regions, &mut rng);
+        mutations.iter().map(|mutation| mutation.stable_id.0).collect()
+    }
+
+    #[test]
+    fn test_target_cap_policies() {
+        let targets = mk_targets(2);
+        let mutation_target_idxs = [0, 0, 0, 0, 1];
+
+        let mut mutations = mk_mutations(&targets, &mutation_target_idxs);
+        assert_eq!(kept_mutation_stable_ids(&mut mutations, 2, TargetCapPolicy::Spaced, &MutationRegions::default()), [1, 3, 5]);
+        assert_eq!(mutations.iter().map(|mutation| mutation.id.index()).collect::<Vec<_>>(), [1, 2, 3]);
+
+        let mut mutations = mk_mutations(&targets, &mutation_target_idxs);
+        assert_eq!(kept_mutation_stable_ids(&mut mutations, 2, TargetCapPolicy::First, &MutationRegions::default()), [1, 2, 5]);
+
+        let mut mutations = mk_mutations(&targets, &mutation_target_idxs);
+        let kept_stable_ids = kept_mutation_stable_ids(&mut mutations, 2, TargetCapPolicy::Random, &MutationRegions::default());
+        assert_eq!(kept_stable_ids.len(), 3);
+        assert!(kept_stable_ids.contains(&5));
+    }
+
+    #[test]
+    fn test_diverse_target_cap_policy_prefers_distinct_operators_and_regions() {
+        // Mutations 1 and 2 are of the same operator in the same region, mutations 3 and 4 of another operator in
+        // different regions.
+        let targets = mk_targets(1);
+        let mutation_regions = MutationRegions {
+            regions: [(MutId(1), 0), (MutId(2), 0), (MutId(3), 0), (MutId(4), 1)].into_iter().collect(),
+        };
+        let mk_diverse_mutations = || {
+            let mut mutations = mk_mutations(&targets, &[0, 0, 0, 0]);
+            mutations[2].mutation = Box::new(OtherDummyMutation);
+            mutations[3].mutation = Box::new(OtherDummyMutation);
+            mutations
+        };
+
+        assert_eq!(kept_mutation_stable_ids(&mut mk_diverse_mutations(), 2, TargetCapPolicy::Diverse, &mutation_regions), [1, 3]);
+        assert_eq!(kept_mutation_stable_ids(&mut mk_diverse_mutations(), 3, TargetCapPolicy::Diverse, &mutation_regions), [1, 3, 4]);
+    }
+
+    #[test]
+    fn test_dedup_mutations_merges_identical_substitutions() {
+        rustc_span::create_default_session_globals_then(|| {
+            let subst = |value| SubstDef::new(SubstLoc::Replace(ast::NodeId::from_u32(1)), Subst::AstExpr(ast::mk::expr_u32(DUMMY_SP, value).into_inner()));
+
+            let targets = mk_targets(1);
+            let mut mutations = mk_mutations(&targets, &[0, 0, 0]);
+            mutations[0].substs = smallvec![subst(1)];
+            mutations[1].substs = smallvec![subst(2)];
+            mutations[2].substs = smallvec![subst(1)];
+            mutations[2].mutation = Box::new(OtherDummyMutation);
+
+            assert_eq!(dedup_mutations(&mut mutations), 1);
+
+            assert_eq!(mutations.iter().map(|mutation| mutation.id.index()).collect::<Vec<_>>(), [1, 2]);
+            assert_eq!(mutations[0].op_names().collect::<Vec<_>>(), ["dummy", "other_dummy"]);
+            assert_eq!(mutations[1].op_names().collect::<Vec<_>>(), ["dummy"]);
+            assert_eq!(mutations[1].substs[0].substitute.to_source_string(), "2");
+        });
+    }
+}
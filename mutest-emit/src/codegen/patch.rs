@@ -0,0 +1,265 @@
+use std::collections::BTreeMap;
+use std::iter;
+use std::ops::Range;
+
+use rustc_hash::FxHashMap;
+use rustc_span::source_map::SourceMap;
+
+use crate::codegen::ast;
+use crate::codegen::ast::visit::Visitor;
+use crate::codegen::mutation::{Mut, SubstLoc};
+use crate::codegen::symbols::Span;
+
+/// Number of unchanged lines printed around the changed lines of each hunk.
+const CONTEXT_LINES: usize = 3;
+
+pub struct NodeSpans(FxHashMap<ast::NodeId, Span>);
+
+struct NodeSpanCollector {
+    spans: FxHashMap<ast::NodeId, Span>,
+}
+
+impl<'ast> ast::visit::Visitor<'ast> for NodeSpanCollector {
+    fn visit_stmt(&mut self, stmt: &'ast ast::Stmt) {
+        self.spans.insert(stmt.id, stmt.span);
+        ast::visit::walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'ast ast::Expr) {
+        self.spans.insert(expr.id, expr.span);
+        ast::visit::walk_expr(self, expr);
+    }
+}
+
+/// Collect the original source spans of all substitutable nodes (statements and expressions) in the crate.
+///
+/// This must be called before any substitutions are written to the crate.
+pub fn collect_node_spans(krate: &ast::Crate) -> NodeSpans {
+    let mut collector = NodeSpanCollector { spans: Default::default() };
+    collector.visit_crate(krate);
+    NodeSpans(collector.spans)
+}
+
+#[derive(Debug)]
+pub enum PatchError {
+    UnknownNode,
+    MacroExpansion,
+    UnavailableSource,
+    OverlappingSubstitutions,
+}
+
+impl PatchError {
+    pub fn desc(&self) -> &str {
+        match self {
+            Self::UnknownNode => "substitution does not correspond to a node in the original source",
+            Self::MacroExpansion => "substitution is within a macro expansion",
+            Self::UnavailableSource => "source code of the file is unavailable",
+            Self::OverlappingSubstitutions => "substitutions overlap in the original source",
+        }
+    }
+}
+
+struct Edit {
+    lo: usize,
+    hi: usize,
+    text: String,
+}
+
+fn line_indent(src: &str, pos: usize) -> &str {
+    let line_start = src[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line = &src[line_start..];
+    &line[..(line.len() - line.trim_start_matches([' ', '\t']).len())]
+}
+
+fn indent_subsequent_lines(text: &str, indent: &str) -> String {
+    text.lines().collect::<Vec<_>>().join(&format!("\n{indent}"))
+}
+
+fn render_file_diff(file_name: &str, src: &str, mut edits: Vec<Edit>) -> Result<String, PatchError> {
+    edits.sort_by_key(|edit| (edit.lo, edit.hi));
+    if edits.windows(2).any(|window| window[1].lo < window[0].hi) {
+        return Err(PatchError::OverlappingSubstitutions);
+    }
+
+    let line_starts = iter::once(0).chain(src.match_indices('\n').map(|(i, _)| i + 1)).collect::<Vec<_>>();
+    let lines_count = src.lines().count();
+    let line_of = |pos: usize| line_starts.partition_point(|&line_start| line_start <= pos) - 1;
+    let line_end = |line: usize| line_starts.get(line + 1).copied().unwrap_or(src.len());
+
+    // Edits are grouped into separate hunks, unless their changed lines are close enough for their context lines to
+    // overlap or touch.
+    let mut hunks: Vec<(usize, usize, Range<usize>)> = vec![];
+    for (i, edit) in edits.iter().enumerate() {
+        let first_line = line_of(edit.lo);
+        let last_line = line_of(edit.hi).max(first_line);
+
+        match hunks.last_mut() {
+            Some((_, hunk_last_line, hunk_edits)) if first_line <= *hunk_last_line + 2 * CONTEXT_LINES + 1 => {
+                *hunk_last_line = (*hunk_last_line).max(last_line);
+                hunk_edits.end = i + 1;
+            }
+            _ => hunks.push((first_line, last_line, i..(i + 1))),
+        }
+    }
+
+    if hunks.is_empty() { return Ok(String::new()); }
+
+    let mut diff = String::new();
+    diff.push_str(&format!("--- a/{file_name}\n"));
+    diff.push_str(&format!("+++ b/{file_name}\n"));
+
+    // Difference in the number of lines of the new file compared to the old file, introduced by previous hunks.
+    let mut lines_delta = 0_isize;
+    for (first_line, last_line, hunk_edits) in hunks {
+        let context_first_line = first_line.saturating_sub(CONTEXT_LINES);
+        let context_last_line = (last_line + CONTEXT_LINES).min(lines_count.saturating_sub(1)).max(last_line);
+
+        let region_lo = line_starts[first_line];
+        let region_hi = line_end(last_line);
+
+        let old_region = &src[region_lo..region_hi];
+        let mut new_region = String::with_capacity(old_region.len());
+        let mut cursor = region_lo;
+        for edit in &edits[hunk_edits] {
+            new_region.push_str(&src[cursor..edit.lo]);
+            new_region.push_str(&edit.text);
+            cursor = edit.hi;
+        }
+        new_region.push_str(&src[cursor..region_hi]);
+
+        let context_before = src[line_starts[context_first_line]..region_lo].lines().collect::<Vec<_>>();
+        let context_after = src[region_hi..line_end(context_last_line)].lines().collect::<Vec<_>>();
+        let old_lines = old_region.lines().collect::<Vec<_>>();
+        let new_lines = new_region.lines().collect::<Vec<_>>();
+
+        diff.push_str(&format!("@@ -{old_start},{old_count} +{new_start},{new_count} @@\n",
+            old_start = context_first_line + 1,
+            old_count = context_before.len() + old_lines.len() + context_after.len(),
+            new_start = (context_first_line + 1).saturating_add_signed(lines_delta),
+            new_count = context_before.len() + new_lines.len() + context_after.len(),
+        ));
+        for line in context_before { diff.push_str(&format!(" {line}\n")); }
+        for line in &old_lines { diff.push_str(&format!("-{line}\n")); }
+        for line in &new_lines { diff.push_str(&format!("+{line}\n")); }
+        for line in context_after { diff.push_str(&format!(" {line}\n")); }
+
+        lines_delta += new_lines.len() as isize - old_lines.len() as isize;
+    }
+
+    Ok(diff)
+}
+
+/// Render the substitutions of the mutation as a unified diff against the original source files.
+pub fn render_mutation_patch(source_map: &SourceMap, node_spans: &NodeSpans, mutation: &Mut) -> Result<String, PatchError> {
+    let mut edits_by_file: BTreeMap<String, (_, Vec<Edit>)> = Default::default();
+
+    for subst in &mutation.substs {
+        let node_id = match subst.location {
+            SubstLoc::InsertBefore(node_id) | SubstLoc::InsertAfter(node_id) | SubstLoc::Replace(node_id) => node_id,
+        };
+        let Some(&span) = node_spans.0.get(&node_id) else { return Err(PatchError::UnknownNode); };
+        if span.from_expansion() { return Err(PatchError::MacroExpansion); }
+
+        let source_file = source_map.lookup_source_file(span.lo());
+        let Some(src) = source_file.src.clone() else { return Err(PatchError::UnavailableSource); };
+
+        let lo = (span.lo().0 - source_file.start_pos.0) as usize;
+        let hi = (span.hi().0 - source_file.start_pos.0) as usize;
+        let indent = line_indent(&src, lo).to_owned();
+
+        let subst_text = indent_subsequent_lines(&subst.substitute.to_source_string(), &indent);
+        let edit = match subst.location {
+            SubstLoc::Replace(_) => Edit { lo, hi, text: subst_text },
+            SubstLoc::InsertBefore(_) => Edit { lo, hi: lo, text: format!("{subst_text}\n{indent}") },
+            SubstLoc::InsertAfter(_) => Edit { lo: hi, hi, text: format!("\n{indent}{subst_text}") },
+        };
+
+        let file_name = source_map.span_to_filename(span).prefer_local().to_string();
+        edits_by_file.entry(file_name).or_insert_with(|| (src, vec![])).1.push(edit);
+    }
+
+    edits_by_file.into_iter()
+        .map(|(file_name, (src, edits))| render_file_diff(&file_name, &src, edits))
+        .collect::<Result<String, _>>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Edit, PatchError, render_file_diff};
+
+    const SRC: &str = "\
+fn a() -> u32 {
+    1 + 2
+}
+
+fn b() {}
+
+fn c() {}
+
+fn d() {}
+
+fn e() -> u32 {
+    3 * 4
+}
+";
+
+    fn edit(src: &str, old: &str, text: &str) -> Edit {
+        let lo = src.find(old).unwrap();
+        Edit { lo, hi: lo + old.len(), text: text.to_owned() }
+    }
+
+    #[test]
+    fn test_render_file_diff_nearby_edits_in_single_hunk() {
+        let edits = vec![edit(SRC, "1 + 2", "1 - 2"), edit(SRC, "fn b() {}", "fn b() { loop {} }")];
+
+        assert_eq!(render_file_diff("lib.rs", SRC, edits).unwrap(), concat!(
+            "--- a/lib.rs\n",
+            "+++ b/lib.rs\n",
+            "@@ -1,8 +1,8 @@\n",
+            " fn a() -> u32 {\n",
+            "-    1 + 2\n",
+            "-}\n",
+            "-\n",
+            "-fn b() {}\n",
+            "+    1 - 2\n",
+            "+}\n",
+            "+\n",
+            "+fn b() { loop {} }\n",
+            " \n",
+            " fn c() {}\n",
+            " \n",
+        ));
+    }
+
+    #[test]
+    fn test_render_file_diff_distant_edits_in_separate_hunks() {
+        let edits = vec![edit(SRC, "3 * 4", "3 / 4"), edit(SRC, "1 + 2", "1 - 2\n    + 0")];
+
+        assert_eq!(render_file_diff("lib.rs", SRC, edits).unwrap(), concat!(
+            "--- a/lib.rs\n",
+            "+++ b/lib.rs\n",
+            "@@ -1,5 +1,6 @@\n",
+            " fn a() -> u32 {\n",
+            "-    1 + 2\n",
+            "+    1 - 2\n",
+            "+    + 0\n",
+            " }\n",
+            " \n",
+            " fn b() {}\n",
+            "@@ -9,5 +10,5 @@\n",
+            " fn d() {}\n",
+            " \n",
+            " fn e() -> u32 {\n",
+            "-    3 * 4\n",
+            "+    3 / 4\n",
+            " }\n",
+        ));
+    }
+
+    #[test]
+    fn test_render_file_diff_rejects_overlapping_edits() {
+        let edits = vec![edit(SRC, "1 + 2", "1 - 2"), edit(SRC, "2\n}", "3\n}")];
+
+        assert!(matches!(render_file_diff("lib.rs", SRC, edits), Err(PatchError::OverlappingSubstitutions)));
+    }
+}
@@ -0,0 +1,119 @@
+//! Mapping of locations in the generated crate code back to the original source of the crate.
+//!
+//! The generated crate code is printed from the AST, so its locations do not correspond to the original source.
+//! While printing, markers referencing the original spans are inserted before items, blocks, and expressions, which are
+//! then stripped from the printed code to produce a table of generated locations and their original counterparts.
+
+use std::cell::RefCell;
+
+use rustc_ast_pretty::pprust::state::{AnnNode, PpAnn, State};
+use rustc_hash::FxHashMap;
+use rustc_span::FileNameDisplayPreference;
+use rustc_span::source_map::SourceMap;
+
+use crate::codegen::symbols::Span;
+
+const MARKER_PREFIX: &str = "/*@mutest-span:";
+const MARKER_SUFFIX: &str = "@*/";
+
+/// Pretty-printer annotation inserting span markers into the printed code.
+/// The markers must be removed from the printed code using [`SpanMarkers::strip_markers`].
+#[derive(Default)]
+pub struct SpanMarkers {
+    spans: RefCell<Vec<Span>>,
+}
+
+impl PpAnn for SpanMarkers {
+    fn pre(&self, state: &mut State<'_>, node: AnnNode<'_>) {
+        let span = match node {
+            AnnNode::Item(item) => item.span,
+            AnnNode::Block(block) => block.span,
+            AnnNode::Expr(expr) => expr.span,
+            _ => return,
+        };
+
+        // Code produced by macros is mapped to the outermost macro invocation. Code generated by mutest-rs has no
+        // corresponding source, and is mapped to the closest original code preceding it.
+        let span = span.source_callsite();
+        if span.is_dummy() { return; }
+
+        let mut spans = self.spans.borrow_mut();
+        state.word(format!("{MARKER_PREFIX}{}{MARKER_SUFFIX}", spans.len()));
+        spans.push(span);
+    }
+}
+
+/// Original location of a location in the generated code. Lines and columns are 1-based.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SpanMapEntry {
+    pub generated_line: u32,
+    pub generated_column: u32,
+    pub file_idx: u32,
+    pub line: u32,
+    pub column: u32,
+}
+
+pub struct SpanMap {
+    /// Files of the original source, referenced by index from the entries.
+    pub files: Vec<String>,
+    /// Entries, sorted by their location in the generated code.
+    pub entries: Vec<SpanMapEntry>,
+}
+
+impl SpanMarkers {
+    /// Remove the markers from the printed code, and map each marked location in the resulting code to the original
+    /// location of the marker's span.
+    pub fn strip_markers(self, source_map: &SourceMap, code: &str) -> (String, SpanMap) {
+        let spans = self.spans.into_inner();
+
+        let mut stripped_code = String::with_capacity(code.len());
+        let mut span_map = SpanMap { files: vec![], entries: vec![] };
+        let mut file_idxs: FxHashMap<String, u32> = Default::default();
+
+        let mut generated_line = 1;
+        let mut generated_column = 1;
+
+        let mut rest = code;
+        while let Some(marker_start) = rest.find(MARKER_PREFIX) {
+            let (before_marker, marker) = rest.split_at(marker_start);
+            for c in before_marker.chars() {
+                match c {
+                    '\n' => { generated_line += 1; generated_column = 1; }
+                    _ => generated_column += 1,
+                }
+            }
+            stripped_code.push_str(before_marker);
+
+            let marker = &marker[MARKER_PREFIX.len()..];
+            let Some((span_idx, after_marker)) = marker.split_once(MARKER_SUFFIX) else { unreachable!("unterminated span marker") };
+            let Ok(span_idx) = span_idx.parse::<usize>() else { unreachable!("invalid span marker") };
+            rest = after_marker;
+
+            let loc = source_map.lookup_char_pos(spans[span_idx].lo());
+            let file_name = loc.file.name.display(FileNameDisplayPreference::Remapped).to_string();
+            let file_idx = *file_idxs.entry(file_name).or_insert_with_key(|file_name| {
+                span_map.files.push(file_name.clone());
+                (span_map.files.len() - 1) as u32
+            });
+
+            let entry = SpanMapEntry {
+                generated_line,
+                generated_column,
+                file_idx,
+                line: loc.line as u32,
+                column: loc.col.0 as u32 + 1,
+            };
+
+            // Nested nodes often start at the same location, in which case the outermost node is kept. Consecutive
+            // entries with the same original location are redundant.
+            match span_map.entries.last() {
+                Some(last) if (last.generated_line, last.generated_column) == (entry.generated_line, entry.generated_column) => {}
+                Some(last) if (last.file_idx, last.line, last.column) == (entry.file_idx, entry.line, entry.column) => {}
+                _ => span_map.entries.push(entry),
+            }
+        }
+        stripped_code.push_str(rest);
+
+        (stripped_code, span_map)
+    }
+}
@@ -1,8 +1,11 @@
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use rustc_middle::ty::TyCtxt;
 use rustc_session::Session;
+use smallvec::SmallVec;
 use thin_vec::{ThinVec, thin_vec};
 
+use crate::analysis::ast_lowering;
+use crate::analysis::hir;
 use crate::codegen::ast;
 use crate::codegen::ast::P;
 use crate::codegen::ast::mut_visit::MutVisitor;
@@ -11,7 +14,32 @@ use crate::codegen::symbols::{DUMMY_SP, Ident, Span, Symbol, path, sym};
 use crate::codegen::symbols::hygiene::AstPass;
 use crate::codegen::mutation::{Mutant, MutId, Subst, SubstDef, SubstLoc};
 
-pub fn conflicting_substs(a: &SubstDef, b: &SubstDef) -> bool {
+/// Index of the closest enclosing mutable node of each statement and expression visited during mutation collection.
+#[derive(Default)]
+pub struct NodeAncestry {
+    parents: FxHashMap<ast::NodeId, ast::NodeId>,
+}
+
+impl NodeAncestry {
+    pub fn insert(&mut self, node_id: ast::NodeId, parent_id: ast::NodeId) {
+        self.parents.insert(node_id, parent_id);
+    }
+
+    pub fn is_ancestor(&self, ancestor_id: ast::NodeId, node_id: ast::NodeId) -> bool {
+        let mut node_id = node_id;
+        while let Some(&parent_id) = self.parents.get(&node_id) {
+            if parent_id == ancestor_id { return true; }
+            node_id = parent_id;
+        }
+        false
+    }
+}
+
+pub fn conflicting_substs(node_ancestry: &NodeAncestry, a: &SubstDef, b: &SubstDef) -> bool {
+    // Replacing a node also replaces the substitutions nested within it, so these can never be active together.
+    if let SubstLoc::Replace(node_id) = a.location && node_ancestry.is_ancestor(node_id, b.location.node_id()) { return true; }
+    if let SubstLoc::Replace(node_id) = b.location && node_ancestry.is_ancestor(node_id, a.location.node_id()) { return true; }
+
     match (&a.substitute, &b.substitute) {
         (Subst::AstLocal(..), Subst::AstLocal(..)) => false,
         _ => a.location == b.location,
@@ -41,7 +69,66 @@ impl SubstLocId {
     }
 }
 
-fn mk_subst_match_expr(sp: Span, _subst_loc: SubstLoc, subst_loc_idx: usize, default: Option<P<ast::Expr>>, substs: Vec<(MutId, P<ast::Expr>)>) -> P<ast::Expr> {
+/// Mechanism by which the substitutions of mutations are enabled in the generated code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubstDispatch {
+    /// Substitutions are looked up in the active mutant handle at runtime, producing a single meta-mutant.
+    Runtime,
+    /// Substitutions are guarded by `mutest_mutation` cfg flags (e.g. `#[cfg(mutest_mutation = "1")]`), producing
+    /// separate builds for each mutant, with the cfg flags of its mutations set.
+    Cfg,
+}
+
+pub fn mutation_cfg_flag(mut_id: MutId) -> String {
+    format!("{}=\"{}\"", *sym::mutest_mutation, mut_id.index())
+}
+
+fn mk_mutation_cfg_attr(g: &ast::attr::AttrIdGenerator, sp: Span, mut_id: MutId) -> ast::Attribute {
+    // #[cfg(mutest_mutation = "$mut_id")]
+    ast::mk::attr_outer(g, sp,
+        Ident::new(sym::cfg, sp),
+        ast::mk::attr_args_delimited(sp, ast::token::Delimiter::Parenthesis, ast::mk::token_stream(vec![
+            ast::mk::tt_token_alone(sp, ast::TokenKind::Ident(*sym::mutest_mutation, ast::token::IdentIsRaw::No)),
+            ast::mk::tt_token_alone(sp, ast::TokenKind::Eq),
+            ast::mk::tt_token_alone(sp, ast::TokenKind::lit(ast::token::LitKind::Str, Symbol::intern(&mut_id.index().to_string()), None)),
+        ])),
+    )
+}
+
+/// Lints triggered by the structure of substitution matches, rather than by the code of the user, which must not fail
+/// the build of crates denying warnings. For example, the default arm of compile-time mutants is unreachable.
+fn mk_subst_match_allow_attr(g: &ast::attr::AttrIdGenerator, sp: Span) -> ast::Attribute {
+    // #[allow(unreachable_code, unreachable_patterns)]
+    ast::mk::attr_outer_allow(g, sp, &[*sym::unreachable_code, *sym::unreachable_patterns])
+}
+
+fn mk_subst_cfg_match_expr(g: &ast::attr::AttrIdGenerator, sp: Span, default: Option<P<ast::Expr>>, substs: Vec<(MutId, P<ast::Expr>)>) -> P<ast::Expr> {
+    let mut arms = substs.into_iter()
+        .map(|(mut_id, subst)| {
+            // #[cfg(mutest_mutation = "$mut_id")] _ => $subst,
+            let mut arm = ast::mk::arm(sp, ast::mk::pat_wild(sp), None, Some(subst));
+            arm.attrs.push(mk_mutation_cfg_attr(g, sp, mut_id));
+            arm
+        })
+        .collect::<ThinVec<_>>();
+
+    // _ => $default
+    arms.push(ast::mk::arm(sp, ast::mk::pat_wild(sp), None, match default {
+        Some(expr) => Some(expr),
+        None => Some(ast::mk::expr_noop(sp)),
+    }));
+
+    // match () { ... }
+    let mut match_expr = ast::mk::expr_match(sp, ast::mk::expr_tuple(sp, ThinVec::new()), arms);
+    match_expr.attrs.push(mk_subst_match_allow_attr(g, sp));
+    ast::mk::expr_paren(sp, match_expr)
+}
+
+fn mk_subst_match_expr(g: &ast::attr::AttrIdGenerator, sp: Span, dispatch: SubstDispatch, _subst_loc: SubstLoc, subst_loc_idx: usize, default: Option<P<ast::Expr>>, substs: Vec<(MutId, P<ast::Expr>)>) -> P<ast::Expr> {
+    if let SubstDispatch::Cfg = dispatch {
+        return mk_subst_cfg_match_expr(g, sp, default, substs);
+    }
+
     let mut arms = substs.into_iter()
         .map(|(mut_id, subst)| {
             // Some(subst) if subst.mutation.id == crate::mutest_generated::mutations::$mut_id.id => $subst,
@@ -69,10 +156,12 @@ fn mk_subst_match_expr(sp: Span, _subst_loc: SubstLoc, subst_loc_idx: usize, def
     ]));
 
     // match unsafe { crate::mutest_generated::ACTIVE_MUTANT_HANDLE.subst_at_unchecked($subst_loc_idx) } { ... }
-    ast::mk::expr_paren(sp, ast::mk::expr_match(sp, subst_lookup_expr, arms))
+    let mut match_expr = ast::mk::expr_match(sp, subst_lookup_expr, arms);
+    match_expr.attrs.push(mk_subst_match_allow_attr(g, sp));
+    ast::mk::expr_paren(sp, match_expr)
 }
 
-pub fn expand_subst_match_expr(sp: Span, subst_loc: SubstLoc, subst_loc_idx: usize, original: Option<P<ast::Expr>>, substs: Vec<(MutId, &Subst)>) -> P<ast::Expr> {
+pub fn expand_subst_match_expr(g: &ast::attr::AttrIdGenerator, sp: Span, dispatch: SubstDispatch, subst_loc: SubstLoc, subst_loc_idx: usize, original: Option<P<ast::Expr>>, substs: Vec<(MutId, &Subst)>) -> P<ast::Expr> {
     let subst_exprs = substs.into_iter()
         .map(|(mut_id, subst)| {
             let subst_expr = match subst {
@@ -85,10 +174,10 @@ pub fn expand_subst_match_expr(sp: Span, subst_loc: SubstLoc, subst_loc_idx: usi
         })
         .collect::<Vec<_>>();
 
-    mk_subst_match_expr(sp, subst_loc, subst_loc_idx, original, subst_exprs)
+    mk_subst_match_expr(g, sp, dispatch, subst_loc, subst_loc_idx, original, subst_exprs)
 }
 
-pub fn expand_subst_match_stmt(sp: Span, subst_loc: SubstLoc, subst_loc_idx: usize, original: Option<ast::Stmt>, substs: Vec<(MutId, &Subst)>) -> Vec<ast::Stmt> {
+pub fn expand_subst_match_stmt(g: &ast::attr::AttrIdGenerator, sp: Span, dispatch: SubstDispatch, subst_loc: SubstLoc, subst_loc_idx: usize, original: Option<ast::Stmt>, substs: Vec<(MutId, &Subst)>) -> Vec<ast::Stmt> {
     let mut binding_substs: Vec<(MutId, (Ident, ast::Mutability, Option<P<ast::Ty>>, P<ast::Expr>, Option<P<ast::Expr>>))> = vec![];
     let mut non_binding_substs: Vec<(MutId, &Subst)> = vec![];
 
@@ -108,7 +197,7 @@ pub fn expand_subst_match_stmt(sp: Span, subst_loc: SubstLoc, subst_loc_idx: usi
         // assigning the value of the previous binding with the same identifier to the new binding
         // (and copying all of the properties of the original binding): `let $ident = $ident`.
         let default_expr = default_expr.unwrap_or_else(|| ast::mk::expr_ident(sp, ident));
-        let subst_match_expr = mk_subst_match_expr(sp, subst_loc, subst_loc_idx, Some(default_expr), vec![(mut_id, expr)]);
+        let subst_match_expr = mk_subst_match_expr(g, sp, dispatch, subst_loc, subst_loc_idx, Some(default_expr), vec![(mut_id, expr)]);
 
         let mutbl = matches!(mutbl, ast::Mutability::Mut);
         stmts.push(ast::mk::stmt_let(sp, mutbl, ident, ty, subst_match_expr));
@@ -116,7 +205,7 @@ pub fn expand_subst_match_stmt(sp: Span, subst_loc: SubstLoc, subst_loc_idx: usi
 
     if !non_binding_substs.is_empty() {
         let original_expr = original.map(|v| ast::mk::expr_block(ast::mk::block(sp, thin_vec![v])));
-        stmts.push(ast::mk::stmt_expr(expand_subst_match_expr(sp, subst_loc, subst_loc_idx, original_expr, non_binding_substs)));
+        stmts.push(ast::mk::stmt_expr(expand_subst_match_expr(g, sp, dispatch, subst_loc, subst_loc_idx, original_expr, non_binding_substs)));
     }
 
     stmts
@@ -124,6 +213,7 @@ pub fn expand_subst_match_stmt(sp: Span, subst_loc: SubstLoc, subst_loc_idx: usi
 
 struct SubstWriter<'tcx, 'op> {
     sess: &'tcx Session,
+    dispatch: SubstDispatch,
     substitutions: FxHashMap<SubstLoc, Vec<(MutId, &'op Subst)>>,
     def_site: Span,
     indexed_subst_locs: Vec<SubstLoc>,
@@ -158,7 +248,7 @@ impl<'tcx, 'op> ast::mut_visit::MutVisitor for SubstWriter<'tcx, 'op> {
                 let subst_loc_idx = self.indexed_subst_locs.len();
                 self.indexed_subst_locs.push(insert_before_loc);
 
-                let replacement_stmts = expand_subst_match_stmt(self.def_site, insert_before_loc, subst_loc_idx, None, insertions_before);
+                let replacement_stmts = expand_subst_match_stmt(&self.sess.psess.attr_id_generator, self.def_site, self.dispatch, insert_before_loc, subst_loc_idx, None, insertions_before);
                 let replacement_stmts_count = replacement_stmts.len();
 
                 block.stmts.splice(i..i, replacement_stmts);
@@ -171,7 +261,7 @@ impl<'tcx, 'op> ast::mut_visit::MutVisitor for SubstWriter<'tcx, 'op> {
                 let subst_loc_idx = self.indexed_subst_locs.len();
                 self.indexed_subst_locs.push(replacement_loc);
 
-                let replacement_stmts = expand_subst_match_stmt(self.def_site, insert_before_loc, subst_loc_idx, None, replacements);
+                let replacement_stmts = expand_subst_match_stmt(&self.sess.psess.attr_id_generator, self.def_site, self.dispatch, insert_before_loc, subst_loc_idx, None, replacements);
                 let replacement_stmts_count = replacement_stmts.len();
 
                 block.stmts.splice(i..i, replacement_stmts);
@@ -184,7 +274,7 @@ impl<'tcx, 'op> ast::mut_visit::MutVisitor for SubstWriter<'tcx, 'op> {
                 let subst_loc_idx = self.indexed_subst_locs.len();
                 self.indexed_subst_locs.push(insert_after_loc);
 
-                let replacement_stmts = expand_subst_match_stmt(self.def_site, insert_after_loc, subst_loc_idx, None, insertions_after);
+                let replacement_stmts = expand_subst_match_stmt(&self.sess.psess.attr_id_generator, self.def_site, self.dispatch, insert_after_loc, subst_loc_idx, None, insertions_after);
                 let replacement_stmts_count = replacement_stmts.len();
                 i += replacement_stmts_count;
 
@@ -225,7 +315,7 @@ impl<'tcx, 'op> ast::mut_visit::MutVisitor for SubstWriter<'tcx, 'op> {
             let subst_loc_idx = self.indexed_subst_locs.len();
             self.indexed_subst_locs.push(replacement_loc);
 
-            *expr = expand_subst_match_expr(expr.span, replacement_loc, subst_loc_idx, Some(expr.clone()), replacements);
+            *expr = expand_subst_match_expr(&self.sess.psess.attr_id_generator, expr.span, self.dispatch, replacement_loc, subst_loc_idx, Some(expr.clone()), replacements);
         }
 
         if let Some(_insertions_after) = self.substitutions.remove(&SubstLoc::InsertAfter(expr_id)) {
@@ -234,7 +324,7 @@ impl<'tcx, 'op> ast::mut_visit::MutVisitor for SubstWriter<'tcx, 'op> {
     }
 }
 
-pub fn write_substitutions<'tcx>(tcx: TyCtxt<'tcx>, mutants: &[Mutant], krate: &mut ast::Crate) -> Vec<SubstLoc> {
+pub fn write_substitutions<'tcx>(tcx: TyCtxt<'tcx>, mutants: &[Mutant], dispatch: SubstDispatch, krate: &mut ast::Crate) -> Vec<SubstLoc> {
     let mut substitutions: FxHashMap<SubstLoc, Vec<(MutId, &Subst)>> = Default::default();
     for mutant in mutants {
         for mutation in &mutant.mutations {
@@ -260,6 +350,7 @@ pub fn write_substitutions<'tcx>(tcx: TyCtxt<'tcx>, mutants: &[Mutant], krate: &
     // TODO: Warn if any substitutions have not been written to the AST. (e.g. they were defined for nodes which are not substitutable)
     let mut subst_writer = SubstWriter {
         sess: tcx.sess,
+        dispatch,
         substitutions,
         def_site,
         indexed_subst_locs: Vec::with_capacity(n_subst_locs),
@@ -269,6 +360,48 @@ pub fn write_substitutions<'tcx>(tcx: TyCtxt<'tcx>, mutants: &[Mutant], krate: &
     subst_writer.indexed_subst_locs
 }
 
+struct ConstQualifierRemover<'op> {
+    def_res: &'op ast_lowering::DefResolutions,
+    fn_def_ids: &'op FxHashSet<hir::LocalDefId>,
+}
+
+impl<'op> ConstQualifierRemover<'op> {
+    fn remove_const_qualifier(&self, id: ast::NodeId, sig: &mut ast::FnSig) {
+        let Some(def_id) = self.def_res.node_id_to_def_id.get(&id) else { return; };
+        if !self.fn_def_ids.contains(def_id) { return; }
+
+        sig.header.constness = ast::Const::No;
+    }
+}
+
+impl<'op> ast::mut_visit::MutVisitor for ConstQualifierRemover<'op> {
+    fn flat_map_item(&mut self, mut item: P<ast::Item>) -> SmallVec<[P<ast::Item>; 1]> {
+        let id = item.id;
+        if let ast::ItemKind::Fn(fn_item) = &mut item.kind { self.remove_const_qualifier(id, &mut fn_item.sig); }
+
+        ast::mut_visit::noop_flat_map_item(item, self)
+    }
+
+    fn flat_map_impl_item(&mut self, mut item: P<ast::AssocItem>) -> SmallVec<[P<ast::AssocItem>; 1]> {
+        let id = item.id;
+        if let ast::AssocItemKind::Fn(fn_item) = &mut item.kind { self.remove_const_qualifier(id, &mut fn_item.sig); }
+
+        ast::mut_visit::noop_flat_map_item(item, self)
+    }
+}
+
+/// Remove the `const` qualifier of the specified functions.
+///
+/// Substitutions look up the active mutant at runtime, which cannot be done in `const` contexts.
+/// This must only be used on functions which are never evaluated at compile-time,
+/// see [`crate::analysis::call_graph::runtime_only_const_fns`].
+pub fn remove_const_qualifiers(def_res: &ast_lowering::DefResolutions, fn_def_ids: &FxHashSet<hir::LocalDefId>, krate: &mut ast::Crate) {
+    if fn_def_ids.is_empty() { return; }
+
+    let mut const_qualifier_remover = ConstQualifierRemover { def_res, fn_def_ids };
+    const_qualifier_remover.visit_crate(krate);
+}
+
 struct SyntaxAmbiguityResolver<'tcx> {
     _sess: &'tcx Session,
     _def_site: Span,
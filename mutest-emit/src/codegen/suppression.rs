@@ -0,0 +1,92 @@
+//! Suppression of mutations using comments in the source code, as a lighter-weight alternative to
+//! `#[mutest::ignore]`, which also works in positions where attributes are not allowed (e.g. in the arguments of macro
+//! invocations).
+//!
+//! * `// mutest: ignore-next-line` suppresses all mutations on the next line.
+//! * `// mutest: ignore(math_op_add_sub_swap, ...)` suppresses the mutations of the listed operators on the next line,
+//!   or, if it follows code on the same line, on its own line.
+//!
+//! Mutations are matched by the line on which they start.
+
+use rustc_span::source_map::SourceMap;
+
+use crate::codegen::symbols::Span;
+
+const SUPPRESSION_COMMENT_PREFIX: &str = "// mutest:";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Suppression<'a> {
+    NextLine,
+    Ops(Vec<&'a str>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct SuppressionComment<'a> {
+    suppression: Suppression<'a>,
+    /// Whether the comment follows code on the same line.
+    is_trailing: bool,
+}
+
+fn parse_suppression_comment(line: &str) -> Option<SuppressionComment<'_>> {
+    let comment_start = line.find(SUPPRESSION_COMMENT_PREFIX)?;
+    let is_trailing = !line[..comment_start].trim().is_empty();
+    let directive = line[(comment_start + SUPPRESSION_COMMENT_PREFIX.len())..].trim();
+
+    let suppression = match directive {
+        "ignore-next-line" => Suppression::NextLine,
+        _ => {
+            let op_names = directive.strip_prefix("ignore(")?.strip_suffix(")")?;
+            Suppression::Ops(op_names.split(',').map(str::trim).filter(|op_name| !op_name.is_empty()).collect())
+        }
+    };
+
+    Some(SuppressionComment { suppression, is_trailing })
+}
+
+/// Returns whether the mutation of the operator, starting at the span, is suppressed by a comment.
+pub fn is_suppressed(source_map: &SourceMap, span: Span, op_name: &str) -> bool {
+    let Ok(loc) = source_map.lookup_line(span.lo()) else { return false; };
+
+    // Trailing comments on the same line.
+    if let Some(line) = loc.sf.get_line(loc.line)
+        && let Some(comment) = parse_suppression_comment(&line)
+        && comment.is_trailing
+        && let Suppression::Ops(op_names) = &comment.suppression
+        && op_names.contains(&op_name)
+    {
+        return true;
+    }
+
+    // Comments on the previous line.
+    if let Some(prev_line_idx) = loc.line.checked_sub(1)
+        && let Some(line) = loc.sf.get_line(prev_line_idx)
+        && let Some(comment) = parse_suppression_comment(&line)
+    {
+        match &comment.suppression {
+            Suppression::NextLine => return true,
+            Suppression::Ops(op_names) if !comment.is_trailing && op_names.contains(&op_name) => return true,
+            Suppression::Ops(_) => {}
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Suppression, SuppressionComment, parse_suppression_comment};
+
+    #[test]
+    fn test_parse_suppression_comment() {
+        assert_eq!(parse_suppression_comment("    // mutest: ignore-next-line"), Some(SuppressionComment {
+            suppression: Suppression::NextLine,
+            is_trailing: false,
+        }));
+        assert_eq!(parse_suppression_comment("    a + b // mutest: ignore(math_op_add_sub_swap, math_op_add_mul_swap)"), Some(SuppressionComment {
+            suppression: Suppression::Ops(vec!["math_op_add_sub_swap", "math_op_add_mul_swap"]),
+            is_trailing: true,
+        }));
+        assert_eq!(parse_suppression_comment("    // mutest: ignore"), None);
+        assert_eq!(parse_suppression_comment("    // ignore-next-line"), None);
+    }
+}
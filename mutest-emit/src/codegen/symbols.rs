@@ -21,36 +21,64 @@ pub mod sym {
         as_ref,
         borrow,
         default,
+        non_camel_case_types,
+        non_snake_case,
         non_upper_case_globals,
         println,
+        unreachable_code,
+        unreachable_patterns,
+        unreachable_pub,
         unused_parens,
 
         ACTIVE_MUTANT_HANDLE,
         ActiveMutantHandle,
         display_location,
         display_name,
+        entries,
+        files,
+        generated_file,
         harness,
         id,
+        merged_op_names,
+        METADATA_TABLES,
+        MetadataTables,
         Mutant,
         mutant,
         MutantMeta,
         MUTANTS,
+        mutate_cfg_test,
         mutation,
         mutations,
         MutationMeta,
         mutest,
         mutest_generated,
         mutest_main_static,
+        mutest_main_static_cfg,
+        mutest_mutation,
         mutest_runtime,
         op_name,
+        property_test,
+        proptest,
+        quickcheck,
         reachable_from,
+        reset_hook,
+        SPAN_MAP,
+        span_map,
+        SpanMap,
+        stable_id,
+        strs,
         substitutions,
+        substs_descr,
         SubstMap,
         SubstMeta,
         subst_at,
         subst_at_unchecked,
+        tables,
+        target_path,
+        test_idxs,
         tests,
         undetected_diagnostic,
+        unsafe_policy,
     }
 }
 
@@ -100,6 +128,9 @@ pub mod path {
         ActiveMutantHandle (::mutest_runtime::ActiveMutantHandle),
         active_mutant_handle_init_empty (::mutest_runtime::ActiveMutantHandle::empty),
         harness (crate::mutest_generated::harness),
+        METADATA_TABLES (crate::mutest_generated::METADATA_TABLES),
+        METADATA_VERSION (::mutest_runtime::METADATA_VERSION),
+        MetadataTables (::mutest_runtime::MetadataTables),
         MutantMeta (::mutest_runtime::MutantMeta),
         MUTANTS (crate::mutest_generated::MUTANTS),
         mutations (crate::mutest_generated::mutations),
@@ -107,7 +138,12 @@ pub mod path {
         MutationSafetySafe (::mutest_runtime::MutationSafety::Safe),
         MutationSafetyTainted (::mutest_runtime::MutationSafety::Tainted),
         MutationSafetyUnsafe (::mutest_runtime::MutationSafety::Unsafe),
+        register_property_tests (::mutest_runtime::register_property_tests),
+        register_reset_hook (::mutest_runtime::register_reset_hook),
+        SPAN_MAP (crate::mutest_generated::SPAN_MAP),
+        SpanMap (::mutest_runtime::SpanMap),
         mutest_main_static (::mutest_runtime::mutest_main_static),
+        mutest_main_static_cfg (::mutest_runtime::mutest_main_static_cfg),
         static_map (::mutest_runtime::static_map),
         SubstMap (crate::mutest_generated::SubstMap),
         SubstMapTrait (::mutest_runtime::SubstMap),
@@ -1,7 +1,13 @@
+use std::iter;
+
 use rustc_session::Session;
 
+use crate::analysis::hir;
+use crate::analysis::res;
+use crate::analysis::ty::TyCtxt;
 use crate::codegen::ast;
-use crate::codegen::symbols::{DUMMY_SP, Ident, sym};
+use crate::codegen::mutation::UnsafeTargeting;
+use crate::codegen::symbols::{DUMMY_SP, Ident, Symbol, sym};
 
 pub fn register(sess: &Session, krate: &mut ast::Crate) {
     let g = &sess.psess.attr_id_generator;
@@ -38,3 +44,83 @@ where
 {
     attrs.into_iter().any(|attr| ast::inspect::is_word_attr(attr, Some(*sym::mutest), sym::skip))
 }
+
+pub fn mutate_cfg_test<'tcx, I>(attrs: I) -> bool
+where
+    I: IntoIterator<Item = &'tcx ast::Attribute>,
+{
+    attrs.into_iter().any(|attr| ast::inspect::is_word_attr(attr, Some(*sym::mutest), *sym::mutate_cfg_test))
+}
+
+pub fn test<'tcx, I>(attrs: I) -> bool
+where
+    I: IntoIterator<Item = &'tcx ast::Attribute>,
+{
+    attrs.into_iter().any(|attr| ast::inspect::is_word_attr(attr, Some(*sym::mutest), sym::test))
+}
+
+pub fn property_test<'tcx, I>(attrs: I) -> bool
+where
+    I: IntoIterator<Item = &'tcx ast::Attribute>,
+{
+    attrs.into_iter().any(|attr| ast::inspect::is_word_attr(attr, Some(*sym::mutest), *sym::property_test))
+}
+
+pub fn reset_hook<'tcx, I>(attrs: I) -> bool
+where
+    I: IntoIterator<Item = &'tcx ast::Attribute>,
+{
+    attrs.into_iter().any(|attr| ast::inspect::is_word_attr(attr, Some(*sym::mutest), *sym::reset_hook))
+}
+
+/// Unsafe targeting policy set with `#[mutest::unsafe_policy = "..."]` on the item, or on its closest parent item (e.g.
+/// an enclosing module) with one. The policies are named after the corresponding command-line flags.
+pub fn unsafe_policy<'tcx>(tcx: TyCtxt<'tcx>, def_id: hir::LocalDefId) -> Option<UnsafeTargeting> {
+    let def_ids = iter::once(def_id).chain(res::parent_iter(tcx, def_id.to_def_id()).filter_map(|parent_id| parent_id.as_local()));
+
+    for def_id in def_ids {
+        let attrs = tcx.hir().attrs(tcx.local_def_id_to_hir_id(def_id));
+        let Some(attr) = attrs.iter().find(|attr| ast::inspect::match_attr_name(attr, Some(*sym::mutest), *sym::unsafe_policy)) else { continue; };
+
+        let unsafe_targeting = match attr.value_str().as_ref().map(|value| value.as_str()) {
+            Some("safe") => UnsafeTargeting::None,
+            Some("cautious") => UnsafeTargeting::OnlyEnclosing(hir::Unsafety::Unsafe),
+            Some("risky") => UnsafeTargeting::OnlyEnclosing(hir::Unsafety::Normal),
+            Some("unsafe") => UnsafeTargeting::All,
+            _ => {
+                let mut diagnostic = tcx.dcx().struct_err("invalid unsafe policy");
+                diagnostic.span(attr.span);
+                diagnostic.help("use one of `#[mutest::unsafe_policy = \"safe\"]`, `\"cautious\"`, `\"risky\"`, or `\"unsafe\"`");
+                diagnostic.emit();
+                continue;
+            }
+        };
+
+        return Some(unsafe_targeting);
+    }
+
+    None
+}
+
+/// Note set with `#[mutest::note = "..."]` on the item, or on its closest parent item (e.g. an enclosing module) with
+/// one. The note is attached to all mutations of the item.
+pub fn note<'tcx>(tcx: TyCtxt<'tcx>, def_id: hir::LocalDefId) -> Option<Symbol> {
+    let def_ids = iter::once(def_id).chain(res::parent_iter(tcx, def_id.to_def_id()).filter_map(|parent_id| parent_id.as_local()));
+
+    for def_id in def_ids {
+        let attrs = tcx.hir().attrs(tcx.local_def_id_to_hir_id(def_id));
+        let Some(attr) = attrs.iter().find(|attr| ast::inspect::match_attr_name(attr, Some(*sym::mutest), sym::note)) else { continue; };
+
+        let Some(note) = attr.value_str() else {
+            let mut diagnostic = tcx.dcx().struct_err("invalid note");
+            diagnostic.span(attr.span);
+            diagnostic.help("use `#[mutest::note = \"...\"]`");
+            diagnostic.emit();
+            continue;
+        };
+
+        return Some(note);
+    }
+
+    None
+}
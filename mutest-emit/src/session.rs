@@ -3,4 +3,6 @@ pub struct Options {
     pub report_timings: bool,
 
     pub sanitize_macro_expns: bool,
+    /// Mutate code produced by the expansion of local bang macros (e.g. `macro_rules`), not just their arguments.
+    pub mutate_macro_expansions: bool,
 }
@@ -1,10 +1,7 @@
 use mutest_emit::{Mutation, Operator};
-use mutest_emit::analysis::res;
-use mutest_emit::analysis::ty;
 use mutest_emit::codegen::ast;
-use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, Mutations, Subst, SubstDef, SubstLoc};
-use mutest_emit::codegen::symbols::{Ident, path};
-use mutest_emit::thin_vec::thin_vec;
+use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, MutationPriority, Mutations, Subst, SubstDef, SubstLoc};
+use mutest_emit::codegen::symbols::Ident;
 use mutest_emit::smallvec::{SmallVec, smallvec};
 
 fn find_ident_pats<'ast>(pat: &'ast ast::Pat) -> Vec<&'ast ast::Pat> {
@@ -64,6 +61,8 @@ pub struct ArgDefaultShadowMutation {
 impl Mutation for ArgDefaultShadowMutation {
     fn op_name(&self) -> &str { ARG_DEFAULT_SHADOW }
 
+    fn priority(&self) -> MutationPriority { MutationPriority::High }
+
     fn display_name(&self) -> String {
         format!("ignore `{param}` argument by shadowing it with `Default::default()`",
             param = self.param_ident
@@ -85,7 +84,7 @@ impl<'a> Operator<'a> for ArgDefaultShadow {
     type Mutation = ArgDefaultShadowMutation;
 
     fn try_apply(&self, mcx: &MutCtxt) -> Mutations<Self::Mutation> {
-        let MutCtxt { opts, tcx, crate_res, def_res, def_site: def, item_hir: f_hir, body_res, location } = *mcx;
+        let MutCtxt { opts: _, tcx: _, crate_res: _, def_res: _, def_site: _, item_hir: _, body_res: _, body_facts: _, instantiations: _, location } = *mcx;
 
         let MutLoc::FnParam(param, f) = location else { return Mutations::none(); };
 
@@ -97,11 +96,6 @@ impl<'a> Operator<'a> for ArgDefaultShadow {
         let Some(body) = &f.body else { return Mutations::none(); };
         let Some(first_valid_stmt) = body.stmts.iter().filter(|stmt| stmt.id != ast::DUMMY_NODE_ID).next() else { return Mutations::none(); };
 
-        let param_env = tcx.param_env(f_hir.owner_id.def_id);
-
-        let Some(body_hir) = f_hir.body else { return Mutations::none(); };
-        let typeck = tcx.typeck_body(body_hir.id());
-
         let mut mutations = SmallVec::with_capacity(ident_pats.len());
         for ident_pat in ident_pats {
             let ast::PatKind::Ident(
@@ -110,9 +104,9 @@ impl<'a> Operator<'a> for ArgDefaultShadow {
                 _,
             ) = ident_pat.kind else { unreachable!() };
 
-            let Some(ident_pat_hir) = body_res.hir_pat(ident_pat) else { continue; };
-            let param_ty = typeck.pat_ty(ident_pat_hir);
-            if !ty::impls_trait_with_env(tcx, param_env, param_ty, res::traits::Default(tcx), vec![]) { continue; }
+            let Some(param_ty) = mcx.pat_ty(ident_pat) else { continue; };
+            // Default::default();
+            let Some(default) = mcx.make_default_expr(param_ty) else { continue; };
 
             // Short-circuit in the common case where the parameter pattern is an ident in and of itself.
             // This case is simpler, since we can simply copy the type ascription from the parameter directly.
@@ -121,15 +115,9 @@ impl<'a> Operator<'a> for ArgDefaultShadow {
             let Some(param_ty_ast) = (if let ast::PatKind::Ident(_, top_param_ident, _) = param.pat.kind && param_ident == top_param_ident {
                 Some(param.ty.clone())
             } else {
-                let scope = f_hir.owner_id.def_id.to_def_id();
-                let def_path_handling = ty::print::DefPathHandling::PreferVisible(ty::print::ScopedItemPaths::Trimmed);
-                let opaque_ty_handling = ty::print::OpaqueTyHandling::Infer;
-                ty::ast_repr(tcx, crate_res, def_res, Some(scope), def, param_ty, def_path_handling, opaque_ty_handling, opts.sanitize_macro_expns)
+                mcx.ty_ast(param_ty)
             }) else { continue; };
 
-            // Default::default();
-            let default = ast::mk::expr_call_path(def, path::default(def), thin_vec![]);
-
             let mutation = Self::Mutation { param_ident };
 
             mutations.push((mutation, smallvec![
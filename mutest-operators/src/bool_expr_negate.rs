@@ -1,6 +1,6 @@
 use mutest_emit::{Mutation, Operator};
 use mutest_emit::codegen::ast::{self, P};
-use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, Mutations, Subst, SubstDef, SubstLoc};
+use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, MutationPriority, Mutations, Subst, SubstDef, SubstLoc};
 use mutest_emit::codegen::symbols::{Ident, Symbol, sym};
 use mutest_emit::smallvec::smallvec;
 use mutest_emit::thin_vec::thin_vec;
@@ -14,6 +14,8 @@ pub struct BoolExprNegateMutation {
 impl Mutation for BoolExprNegateMutation {
     fn op_name(&self) -> &str { BOOL_EXPR_NEGATE }
 
+    fn priority(&self) -> MutationPriority { MutationPriority::High }
+
     fn display_name(&self) -> String {
         format!("{operation} boolean expression",
             operation = match self.was_negated {
@@ -31,7 +33,7 @@ impl<'a> Operator<'a> for BoolExprNegate {
     type Mutation = BoolExprNegateMutation;
 
     fn try_apply(&self, mcx: &MutCtxt) -> Mutations<Self::Mutation> {
-        let MutCtxt { opts: _, tcx, crate_res: _, def_res: _, def_site: def, item_hir: f_hir, body_res, location } = *mcx;
+        let MutCtxt { opts: _, tcx, crate_res: _, def_res: _, def_site: def, item_hir: f_hir, body_res, body_facts: _, instantiations: _, location } = *mcx;
 
         let MutLoc::FnBodyExpr(expr, _f) = location else { return Mutations::none(); };
 
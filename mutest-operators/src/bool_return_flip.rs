@@ -0,0 +1,95 @@
+use mutest_emit::{Mutation, Operator};
+use mutest_emit::codegen::ast::{self, P};
+use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, MutationPriority, Mutations, Subst, SubstDef, SubstLoc};
+use mutest_emit::codegen::symbols::{Ident, Symbol, sym};
+use mutest_emit::smallvec::smallvec;
+use mutest_emit::thin_vec::thin_vec;
+
+pub const BOOL_RETURN_FLIP: &str = "bool_return_flip";
+
+pub struct BoolReturnFlipMutation {
+    pub is_tail_expr: bool,
+}
+
+impl Mutation for BoolReturnFlipMutation {
+    fn op_name(&self) -> &str { BOOL_RETURN_FLIP }
+
+    fn priority(&self) -> MutationPriority { MutationPriority::High }
+
+    fn display_name(&self) -> String {
+        format!("negate boolean return value{of_tail_expr}",
+            of_tail_expr = match self.is_tail_expr {
+                false => "",
+                true => " of tail expression",
+            },
+        )
+    }
+
+    fn span_label(&self) -> String {
+        "negate return value".to_owned()
+    }
+}
+
+/// Negate the returned value of functions returning `bool`, at each return point of the function.
+pub struct BoolReturnFlip;
+
+impl<'a> Operator<'a> for BoolReturnFlip {
+    type Mutation = BoolReturnFlipMutation;
+
+    fn try_apply(&self, mcx: &MutCtxt) -> Mutations<Self::Mutation> {
+        let MutCtxt { opts: _, tcx, crate_res: _, def_res: _, def_site: def, item_hir: f_hir, body_res, body_facts: _, instantiations: _, location } = *mcx;
+
+        let MutLoc::FnBodyExpr(expr, f) = location else { return Mutations::none(); };
+
+        let fn_sig = tcx.fn_sig(f_hir.owner_id).instantiate_identity().skip_binder();
+        if fn_sig.output() != tcx.types.bool { return Mutations::none(); }
+
+        let is_tail_expr = f.body
+            .and_then(|body| body.stmts.last())
+            .is_some_and(|stmt| matches!(&stmt.kind, ast::StmtKind::Expr(tail_expr) if tail_expr.id == expr.id));
+
+        let (return_value, is_tail_expr) = match &expr.kind {
+            ast::ExprKind::Ret(Some(return_value)) => (&**return_value, false),
+            ast::ExprKind::Ret(None) => { return Mutations::none(); }
+            _ if is_tail_expr => (expr, true),
+            _ => { return Mutations::none(); }
+        };
+
+        let Some(typeck) = mcx.typeck() else { return Mutations::none(); };
+        let Some(expr_hir) = body_res.hir_expr(expr) else { unreachable!() };
+        // Return expressions within closures return from the closure, rather than the function.
+        if tcx.hir().enclosing_body_owner(expr_hir.hir_id) != f_hir.owner_id.def_id { return Mutations::none(); }
+
+        let Some(return_value_hir) = body_res.hir_expr(return_value) else { return Mutations::none(); };
+        // Diverging tail expressions (e.g. `panic!()`) do not return a value.
+        if typeck.expr_ty(return_value_hir) != tcx.types.bool { return Mutations::none(); }
+
+        let unambiguous_return_value = match &return_value.kind {
+            // NOTE: Calls to generic functions with generic return types (e.g. `Default::default`)
+            //       may not be inferrable once the call is wrapped in a negation, so we ascribe the `bool` type to the
+            //       return value of the call expression, like in `bool_expr_negate`.
+            ast::ExprKind::Call(_, _) | ast::ExprKind::MethodCall(_) => {
+                let return_ty_ast = ast::mk::ty_ident(def, None, Ident::new(sym::bool, def));
+
+                // { let v: bool = $expr; v }
+                let v = Ident::new(Symbol::intern("v"), def);
+                ast::mk::expr_block(ast::mk::block(def, thin_vec![
+                    ast::mk::stmt_let(def, false, v, Some(return_ty_ast), P(return_value.clone())),
+                    ast::mk::stmt_expr(ast::mk::expr_ident(def, v)),
+                ]))
+            }
+            _ => P(return_value.clone()),
+        };
+
+        let negated_return_value = ast::mk::expr_unary(def, ast::UnOp::Not, unambiguous_return_value);
+
+        let mutation = Self::Mutation { is_tail_expr };
+
+        Mutations::new_one(mutation, smallvec![
+            SubstDef::new(
+                SubstLoc::Replace(return_value.id),
+                Subst::AstExpr(negated_return_value.into_inner()),
+            ),
+        ])
+    }
+}
@@ -0,0 +1,111 @@
+use mutest_emit::{Mutation, Operator};
+use mutest_emit::analysis::hir;
+use mutest_emit::analysis::res;
+use mutest_emit::codegen::ast;
+use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, MutationPriority, Mutations, Subst, SubstDef, SubstLoc};
+use mutest_emit::codegen::symbols::{Ident, Symbol};
+use mutest_emit::thin_vec::thin_vec;
+use mutest_emit::smallvec::{SmallVec, smallvec};
+
+pub const CALL_ARG_DEFAULT_REPLACE: &str = "call_arg_default_replace";
+
+pub struct CallArgDefaultReplaceMutation {
+    pub callee_path: String,
+    pub arg_index: usize,
+}
+
+impl Mutation for CallArgDefaultReplaceMutation {
+    fn op_name(&self) -> &str { CALL_ARG_DEFAULT_REPLACE }
+
+    fn priority(&self) -> MutationPriority { MutationPriority::Medium }
+
+    fn display_name(&self) -> String {
+        format!("replace argument #{arg} of call to `{callee}` with `Default::default()`",
+            arg = self.arg_index + 1,
+            callee = self.callee_path,
+        )
+    }
+
+    fn span_label(&self) -> String {
+        "replace argument with `Default::default()`".to_owned()
+    }
+}
+
+/// Replace individual arguments of function calls with `Default::default()` to test whether the
+/// values passed by callers are meaningfully tested, modeling callers passing wrong or empty values.
+pub struct CallArgDefaultReplace;
+
+impl<'a> Operator<'a> for CallArgDefaultReplace {
+    type Mutation = CallArgDefaultReplaceMutation;
+
+    fn try_apply(&self, mcx: &MutCtxt) -> Mutations<Self::Mutation> {
+        let MutCtxt { opts: _, tcx, crate_res: _, def_res: _, def_site: def, item_hir: f_hir, body_res, body_facts: _, instantiations: _, location } = *mcx;
+
+        let MutLoc::FnBodyExpr(expr, _f) = location else { return Mutations::none(); };
+
+        let args = match &expr.kind {
+            ast::ExprKind::Call(_, args) => args,
+            ast::ExprKind::MethodCall(call) => &call.args,
+            _ => { return Mutations::none(); }
+        };
+        if args.is_empty() { return Mutations::none(); }
+
+        let Some(typeck) = mcx.typeck() else { return Mutations::none(); };
+        let Some(expr_hir) = body_res.hir_expr(expr) else { unreachable!() };
+        let Some((callee, _)) = res::callee(typeck, expr_hir) else { return Mutations::none(); };
+
+        let mut mutations = SmallVec::with_capacity(args.len());
+        for (arg_index, arg) in args.iter().enumerate() {
+            // Arguments originating from macro expansions are shared with other code.
+            if arg.span.ctxt() != expr.span.ctxt() { continue; }
+
+            let Some(arg_hir) = body_res.hir_expr(arg) else { continue; };
+            // The argument is replaced with a value of the type it was coerced to, which is the type of the parameter.
+            let arg_ty = typeck.expr_ty_adjusted(arg_hir);
+            if arg_ty.is_unit() || arg_ty.is_never() { continue; }
+
+            // Replacing an argument which is already a default value would be an equivalent mutation.
+            if let hir::ExprKind::Call(..) = arg_hir.kind
+                && let Some((arg_callee, _)) = res::callee(typeck, arg_hir)
+                && arg_callee == res::fns::default(tcx)
+            {
+                continue;
+            }
+
+            // Avoid replacing the argument with `Default::default` within the type's `Default::default`
+            // implementation, which would result in infinite recursion.
+            if let Some(impl_def_id) = tcx.impl_of_method(f_hir.owner_id.to_def_id())
+                && tcx.trait_id_of_impl(impl_def_id) == Some(res::traits::Default(tcx))
+                && tcx.type_of(impl_def_id).instantiate_identity() == arg_ty
+            {
+                continue;
+            }
+
+            let Some(default) = mcx.make_default_expr(arg_ty) else { continue; };
+            let Some(arg_ty_ast) = mcx.ty_ast(arg_ty) else { continue; };
+
+            // The parameter type of generic callees cannot be inferred from `Default::default()`, so we ascribe the
+            // original type to the default value using a let binding in a block expression.
+            // { let v: $ty = Default::default(); v }
+            let v = Ident::new(Symbol::intern("v"), def);
+            let default_arg = ast::mk::expr_block(ast::mk::block(def, thin_vec![
+                ast::mk::stmt_let(def, false, v, Some(arg_ty_ast), default),
+                ast::mk::stmt_expr(ast::mk::expr_ident(def, v)),
+            ]));
+
+            let mutation = Self::Mutation {
+                callee_path: tcx.def_path_str(callee),
+                arg_index,
+            };
+
+            mutations.push((mutation, smallvec![
+                SubstDef::new(
+                    SubstLoc::Replace(arg.id),
+                    Subst::AstExpr(default_arg.into_inner()),
+                ),
+            ]));
+        }
+
+        Mutations::new(mutations)
+    }
+}
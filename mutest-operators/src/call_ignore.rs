@@ -4,7 +4,7 @@ use mutest_emit::analysis::hir;
 use mutest_emit::analysis::res;
 use mutest_emit::analysis::ty::{self, Ty, TyCtxt};
 use mutest_emit::codegen::ast::{self, P};
-use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, Mutations, Subst, SubstDef, SubstLoc};
+use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, MutationPriority, Mutations, Subst, SubstDef, SubstLoc};
 use mutest_emit::codegen::symbols::{Ident, path, kw};
 use mutest_emit::thin_vec::thin_vec;
 use mutest_emit::smallvec::smallvec;
@@ -67,6 +67,8 @@ pub struct CallValueDefaultShadowMutation {
 impl Mutation for CallValueDefaultShadowMutation {
     fn op_name(&self) -> &str { CALL_VALUE_DEFAULT_SHADOW }
 
+    fn priority(&self) -> MutationPriority { MutationPriority::Medium }
+
     fn display_name(&self) -> String {
         format!("ignore return value of call to `{callee}` by shadowing it with `Default::default()`",
             callee = self.callee_path
@@ -89,7 +91,7 @@ impl<'a> Operator<'a> for CallValueDefaultShadow {
     type Mutation = CallValueDefaultShadowMutation;
 
     fn try_apply(&self, mcx: &MutCtxt) -> Mutations<Self::Mutation> {
-        let MutCtxt { opts, tcx, crate_res, def_res, def_site: def, item_hir: f_hir, body_res, location } = *mcx;
+        let MutCtxt { opts: _, tcx, crate_res: _, def_res: _, def_site: def, item_hir: f_hir, body_res, body_facts: _, instantiations: _, location } = *mcx;
 
         let MutLoc::FnBodyExpr(expr, _f) = location else { return Mutations::none(); };
         let Some(body_hir) = f_hir.body else { return Mutations::none(); };
@@ -104,13 +106,12 @@ impl<'a> Operator<'a> for CallValueDefaultShadow {
             expr_hir,
             self.limit_scope_to_local_callees,
         ) else { return Mutations::none(); };
+        // The type must also implement `Default` in each instantiation of generic functions.
+        if !mcx.implements(expr_ty, res::traits::Default(tcx), vec![]) { return Mutations::none(); }
 
         // A type annotation with the originally resolved type has to be added to the ignoring
         // `let _ = $expr` statement to guarantee the same callee resolution.
-        let scope = f_hir.owner_id.def_id.to_def_id();
-        let def_path_handling = ty::print::DefPathHandling::PreferVisible(ty::print::ScopedItemPaths::Trimmed);
-        let opaque_ty_handling = ty::print::OpaqueTyHandling::Infer;
-        let Some(expr_ty_ast) = ty::ast_repr(tcx, crate_res, def_res, Some(scope), def, expr_ty, def_path_handling, opaque_ty_handling, opts.sanitize_macro_expns) else { return Mutations::none(); };
+        let Some(expr_ty_ast) = mcx.ty_ast(expr_ty) else { return Mutations::none(); };
 
         // Default::default()
         let default = ast::mk::expr_call_path(def, path::default(def), thin_vec![]);
@@ -142,6 +143,8 @@ pub struct CallDeleteMutation {
 impl Mutation for CallDeleteMutation {
     fn op_name(&self) -> &str { CALL_DELETE }
 
+    fn priority(&self) -> MutationPriority { MutationPriority::High }
+
     fn display_name(&self) -> String {
         format!("delete call to `{callee}` and replace it with `Default::default()`",
             callee = self.callee_path
@@ -163,7 +166,7 @@ impl<'a> Operator<'a> for CallDelete {
     type Mutation = CallDeleteMutation;
 
     fn try_apply(&self, mcx: &MutCtxt) -> Mutations<Self::Mutation> {
-        let MutCtxt { opts: _, tcx, crate_res: _, def_res: _, def_site: def, item_hir: f_hir, body_res, location } = *mcx;
+        let MutCtxt { opts: _, tcx, crate_res: _, def_res: _, def_site: def, item_hir: f_hir, body_res, body_facts: _, instantiations: _, location } = *mcx;
 
         let MutLoc::FnBodyExpr(expr, _f) = location else { return Mutations::none(); };
         let Some(body_hir) = f_hir.body else { return Mutations::none(); };
@@ -171,13 +174,15 @@ impl<'a> Operator<'a> for CallDelete {
         let (ast::ExprKind::Call(..) | ast::ExprKind::MethodCall(..)) = expr.kind else { return Mutations::none(); };
 
         let Some(expr_hir) = body_res.hir_expr(expr) else { unreachable!() };
-        let Some((callee, _)) = non_default_call(
+        let Some((callee, expr_ty)) = non_default_call(
             tcx,
             f_hir.owner_id.to_def_id(),
             body_hir.id(),
             expr_hir,
             self.limit_scope_to_local_callees,
         ) else { return Mutations::none(); };
+        // The type must also implement `Default` in each instantiation of generic functions.
+        if !mcx.implements(expr_ty, res::traits::Default(tcx), vec![]) { return Mutations::none(); }
 
         // Default::default()
         let default = ast::mk::expr_call_path(def, path::default(def), thin_vec![]);
@@ -0,0 +1,140 @@
+use mutest_emit::{Mutation, Operator};
+use mutest_emit::analysis::hir;
+use mutest_emit::analysis::res;
+use mutest_emit::codegen::ast;
+use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, MutationPriority, Mutations, Subst, SubstDef, SubstLoc};
+use mutest_emit::codegen::symbols::Symbol;
+use mutest_emit::smallvec::smallvec;
+
+/// Methods of the standard library collections which modify the collection in-place, and return nothing.
+const IN_PLACE_METHODS: &[&str] = &[
+    "dedup",
+    "dedup_by",
+    "dedup_by_key",
+    "reverse",
+    "sort",
+    "sort_by",
+    "sort_by_cached_key",
+    "sort_by_key",
+    "sort_unstable",
+    "sort_unstable_by",
+    "sort_unstable_by_key",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CombinatorNoopKind {
+    /// Replace the predicate of the combinator with `true`.
+    TruePredicate,
+    /// Replace the mapping closure of the combinator with the identity function.
+    IdentityMapping,
+    /// Delete the in-place operation.
+    DeleteInPlace,
+}
+
+pub const COMBINATOR_NOOP: &str = "combinator_noop";
+
+pub struct CombinatorNoopMutation {
+    pub kind: CombinatorNoopKind,
+    pub method_name: Symbol,
+}
+
+impl Mutation for CombinatorNoopMutation {
+    fn op_name(&self) -> &str { COMBINATOR_NOOP }
+
+    fn priority(&self) -> MutationPriority { MutationPriority::Low }
+
+    fn display_name(&self) -> String {
+        match self.kind {
+            CombinatorNoopKind::TruePredicate => format!("replace predicate of `{method}` with `true`", method = self.method_name),
+            CombinatorNoopKind::IdentityMapping => format!("replace mapping of `{method}` with identity", method = self.method_name),
+            CombinatorNoopKind::DeleteInPlace => format!("delete in-place `{method}` call", method = self.method_name),
+        }
+    }
+
+    fn span_label(&self) -> String {
+        match self.kind {
+            CombinatorNoopKind::TruePredicate => "replace predicate with `true`".to_owned(),
+            CombinatorNoopKind::IdentityMapping => "replace mapping with identity".to_owned(),
+            CombinatorNoopKind::DeleteInPlace => "delete in-place call".to_owned(),
+        }
+    }
+}
+
+/// Replace iterator and collection combinators with no-op equivalents.
+///
+/// The predicates of `Iterator::filter` calls are replaced with `true`, the closures of `Iterator::map` calls are
+/// replaced with the identity function (if the item type is unchanged), and in-place collection operations
+/// (e.g. `sort`, `reverse`, `dedup`) are deleted.
+///
+/// Since the substitutions must retain the type of the original expression, only the bodies of closure arguments
+/// are replaced, rather than the combinator calls themselves.
+pub struct CombinatorNoop;
+
+impl<'a> Operator<'a> for CombinatorNoop {
+    type Mutation = CombinatorNoopMutation;
+
+    fn try_apply(&self, mcx: &MutCtxt) -> Mutations<Self::Mutation> {
+        let MutCtxt { opts: _, tcx, crate_res: _, def_res: _, def_site: def, item_hir: f_hir, body_res, body_facts: _, instantiations: _, location } = *mcx;
+
+        let MutLoc::FnBodyExpr(expr, _f) = location else { return Mutations::none(); };
+
+        let ast::ExprKind::MethodCall(call) = &expr.kind else { return Mutations::none(); };
+
+        let Some(body_hir) = f_hir.body else { return Mutations::none(); };
+        let typeck = tcx.typeck_body(body_hir.id());
+
+        let Some(expr_hir) = body_res.hir_expr(expr) else { unreachable!() };
+        let hir::ExprKind::MethodCall(_, _, args_hir, _) = expr_hir.kind else { return Mutations::none(); };
+        let Some(callee) = typeck.type_dependent_def_id(expr_hir.hir_id) else { return Mutations::none(); };
+
+        let method_name = call.seg.ident.name;
+
+        if callee == res::fns::iter_filter(tcx) || callee == res::fns::iter_map(tcx) {
+            let [closure_arg] = &call.args[..] else { return Mutations::none(); };
+            let ast::ExprKind::Closure(closure) = &closure_arg.kind else { return Mutations::none(); };
+            if closure.coroutine_kind.is_some() { return Mutations::none(); }
+
+            let (kind, replacement_body) = match () {
+                _ if callee == res::fns::iter_filter(tcx) => {
+                    (CombinatorNoopKind::TruePredicate, ast::mk::expr_bool(def, true))
+                }
+                _ => {
+                    let [param] = &closure.fn_decl.inputs[..] else { return Mutations::none(); };
+                    let ast::PatKind::Ident(_, param_ident, None) = param.pat.kind else { return Mutations::none(); };
+
+                    // The identity mapping is only valid if the closure returns the same type it takes.
+                    let [closure_arg_hir] = args_hir else { unreachable!() };
+                    let hir::ExprKind::Closure(closure_hir) = closure_arg_hir.kind else { unreachable!() };
+                    let closure_body_hir = tcx.hir().body(closure_hir.body);
+                    let [param_hir] = closure_body_hir.params else { unreachable!() };
+                    if typeck.pat_ty(param_hir.pat) != typeck.expr_ty(closure_body_hir.value) { return Mutations::none(); }
+
+                    (CombinatorNoopKind::IdentityMapping, ast::mk::expr_ident(def, param_ident))
+                }
+            };
+
+            let mutation = Self::Mutation { kind, method_name };
+
+            return Mutations::new_one(mutation, smallvec![
+                SubstDef::new(
+                    SubstLoc::Replace(closure.body.id),
+                    Subst::AstExpr(replacement_body.into_inner()),
+                ),
+            ]);
+        }
+
+        if callee.is_local() { return Mutations::none(); }
+        if !matches!(tcx.crate_name(callee.krate).as_str(), "core" | "alloc") { return Mutations::none(); }
+        if !IN_PLACE_METHODS.contains(&tcx.item_name(callee).as_str()) { return Mutations::none(); }
+        if typeck.expr_ty(expr_hir) != tcx.types.unit { return Mutations::none(); }
+
+        let mutation = Self::Mutation { kind: CombinatorNoopKind::DeleteInPlace, method_name };
+
+        Mutations::new_one(mutation, smallvec![
+            SubstDef::new(
+                SubstLoc::Replace(expr.id),
+                Subst::AstExpr(ast::mk::expr_noop(def).into_inner()),
+            ),
+        ])
+    }
+}
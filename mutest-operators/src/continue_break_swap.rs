@@ -1,7 +1,7 @@
 use mutest_emit::{Mutation, Operator};
 use mutest_emit::analysis::hir;
 use mutest_emit::codegen::ast;
-use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, Mutations, Subst, SubstDef, SubstLoc};
+use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, MutationPriority, Mutations, Subst, SubstDef, SubstLoc};
 use mutest_emit::smallvec::smallvec;
 
 pub const CONTINUE_BREAK_SWAP: &str = "continue_break_swap";
@@ -14,6 +14,8 @@ pub struct ContinueBreakSwapMutation {
 impl Mutation for ContinueBreakSwapMutation {
     fn op_name(&self) -> &str { CONTINUE_BREAK_SWAP }
 
+    fn priority(&self) -> MutationPriority { MutationPriority::Medium }
+
     fn display_name(&self) -> String {
         let display_expr = |expr: &ast::ExprKind| match expr {
             ast::ExprKind::Break(Some(label), _) => format!("break with label `{}`", label.ident),
@@ -51,7 +53,7 @@ impl<'a> Operator<'a> for ContinueBreakSwap {
     type Mutation = ContinueBreakSwapMutation;
 
     fn try_apply(&self, mcx: &MutCtxt) -> Mutations<Self::Mutation> {
-        let MutCtxt { opts: _, tcx, crate_res: _, def_res: _, def_site: def, item_hir: f_hir, body_res, location } = *mcx;
+        let MutCtxt { opts: _, tcx, crate_res: _, def_res: _, def_site: def, item_hir: f_hir, body_res, body_facts: _, instantiations: _, location } = *mcx;
 
         let MutLoc::FnBodyExpr(expr, _) = location else { return Mutations::none(); };
 
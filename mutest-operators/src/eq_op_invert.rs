@@ -1,6 +1,6 @@
 use mutest_emit::{Mutation, Operator};
 use mutest_emit::codegen::ast;
-use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, Mutations, Subst, SubstDef, SubstLoc};
+use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, MutationPriority, Mutations, Subst, SubstDef, SubstLoc};
 use mutest_emit::smallvec::smallvec;
 
 pub const EQ_OP_INVERT: &str = "eq_op_invert";
@@ -13,6 +13,8 @@ pub struct EqOpInvertMutation {
 impl Mutation for EqOpInvertMutation {
     fn op_name(&self) -> &str { EQ_OP_INVERT }
 
+    fn priority(&self) -> MutationPriority { MutationPriority::High }
+
     fn display_name(&self) -> String {
         format!("invert equality operator `{original_bin_op}` to `{replacement_bin_op}`",
             original_bin_op = self.original_bin_op.as_str(),
@@ -34,7 +36,7 @@ impl<'a> Operator<'a> for EqOpInvert {
     type Mutation = EqOpInvertMutation;
 
     fn try_apply(&self, mcx: &MutCtxt) -> Mutations<Self::Mutation> {
-        let MutCtxt { opts: _, tcx: _, crate_res: _, def_res: _, def_site: def, item_hir: _, body_res: _, location } = *mcx;
+        let MutCtxt { opts: _, tcx: _, crate_res: _, def_res: _, def_site: def, item_hir: _, body_res: _, body_facts: _, instantiations: _, location } = *mcx;
 
         let MutLoc::FnBodyExpr(expr, _) = location else { return Mutations::none(); };
 
@@ -9,27 +9,57 @@ pub use arg_default_shadow::*;
 mod bool_expr_negate;
 pub use bool_expr_negate::*;
 
+mod bool_return_flip;
+pub use bool_return_flip::*;
+
+mod call_arg_default_replace;
+pub use call_arg_default_replace::*;
+
 mod call_ignore;
 pub use call_ignore::*;
 
+mod combinator_noop;
+pub use combinator_noop::*;
+
 mod continue_break_swap;
 pub use continue_break_swap::*;
 
 mod eq_op_invert;
 pub use eq_op_invert::*;
 
+mod match_arm_body_replace;
+pub use match_arm_body_replace::*;
+
+mod match_arm_reorder;
+pub use match_arm_reorder::*;
+
+mod method_call_swap;
+pub use method_call_swap::*;
+
 mod op_swap;
 pub use op_swap::*;
 
+mod option_result_combinator_swap;
+pub use option_result_combinator_swap::*;
+
 mod range_limit_swap;
 pub use range_limit_swap::*;
 
+mod refutable_let_invert;
+pub use refutable_let_invert::*;
+
 mod relational_op_eq_swap;
 pub use relational_op_eq_swap::*;
 
 mod relational_op_invert;
 pub use relational_op_invert::*;
 
+mod string_lit_replace;
+pub use string_lit_replace::*;
+
+mod struct_field_replace;
+pub use struct_field_replace::*;
+
 pub const ALL: &[&str] = &[
     ARG_DEFAULT_SHADOW,
     BIT_OP_OR_AND_SWAP,
@@ -37,16 +67,26 @@ pub const ALL: &[&str] = &[
     BIT_OP_SHIFT_DIR_SWAP,
     BIT_OP_XOR_AND_SWAP,
     BOOL_EXPR_NEGATE,
+    BOOL_RETURN_FLIP,
+    CALL_ARG_DEFAULT_REPLACE,
     CALL_DELETE,
     CALL_VALUE_DEFAULT_SHADOW,
+    COMBINATOR_NOOP,
     CONTINUE_BREAK_SWAP,
     EQ_OP_INVERT,
     LOGICAL_OP_AND_OR_SWAP,
+    MATCH_ARM_BODY_REPLACE,
+    MATCH_ARM_REORDER,
     MATH_OP_ADD_MUL_SWAP,
     MATH_OP_ADD_SUB_SWAP,
     MATH_OP_DIV_REM_SWAP,
     MATH_OP_MUL_DIV_SWAP,
+    METHOD_CALL_SWAP,
+    OPTION_RESULT_COMBINATOR_SWAP,
     RANGE_LIMIT_SWAP,
+    REFUTABLE_LET_INVERT,
     RELATIONAL_OP_EQ_SWAP,
     RELATIONAL_OP_INVERT,
+    STRING_LIT_REPLACE,
+    STRUCT_FIELD_REPLACE,
 ];
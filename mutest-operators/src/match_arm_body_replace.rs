@@ -0,0 +1,143 @@
+use mutest_emit::{Mutation, Operator};
+use mutest_emit::analysis::hir;
+use mutest_emit::analysis::ty::TyCtxt;
+use mutest_emit::codegen::ast;
+use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, MutationPriority, Mutations, Subst, SubstDef, SubstLoc};
+use mutest_emit::smallvec::{SmallVec, smallvec};
+
+struct LocalRefFinder<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    locals: Vec<hir::HirId>,
+    found: bool,
+}
+
+impl<'tcx> hir::intravisit::Visitor<'tcx> for LocalRefFinder<'tcx> {
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr<'tcx>) {
+        match expr.kind {
+            hir::ExprKind::Path(hir::QPath::Resolved(None, path)) => {
+                if let hir::Res::Local(hir_id) = path.res && self.locals.contains(&hir_id) {
+                    self.found = true;
+                }
+            }
+            hir::ExprKind::Closure(closure) => {
+                self.visit_expr(self.tcx.hir().body(closure.body).value);
+            }
+            _ => {}
+        }
+
+        hir::intravisit::walk_expr(self, expr);
+    }
+}
+
+/// Returns `true` if the expression references any of the bindings introduced by the pattern.
+pub(crate) fn refers_to_pat_bindings<'tcx>(tcx: TyCtxt<'tcx>, pat: &'tcx hir::Pat<'tcx>, expr: &'tcx hir::Expr<'tcx>) -> bool {
+    let mut locals = vec![];
+    pat.each_binding(|_, hir_id, _, _| locals.push(hir_id));
+    if locals.is_empty() { return false; }
+
+    let mut finder = LocalRefFinder { tcx, locals, found: false };
+    hir::intravisit::Visitor::visit_expr(&mut finder, expr);
+    finder.found
+}
+
+fn is_empty_expr(expr: &ast::Expr) -> bool {
+    match &expr.kind {
+        ast::ExprKind::Block(block, None) => block.stmts.is_empty(),
+        ast::ExprKind::Tup(exprs) => exprs.is_empty(),
+        _ => false,
+    }
+}
+
+pub const MATCH_ARM_BODY_REPLACE: &str = "match_arm_body_replace";
+
+pub struct MatchArmBodyReplaceMutation {
+    pub arm_pat: String,
+    pub replacement_arm_pat: Option<String>,
+}
+
+impl Mutation for MatchArmBodyReplaceMutation {
+    fn op_name(&self) -> &str { MATCH_ARM_BODY_REPLACE }
+
+    fn priority(&self) -> MutationPriority { MutationPriority::Medium }
+
+    fn display_name(&self) -> String {
+        match &self.replacement_arm_pat {
+            Some(replacement_arm_pat) => format!("replace body of match arm `{arm_pat}` with body of arm `{replacement_arm_pat}`", arm_pat = self.arm_pat),
+            None => format!("delete body of match arm `{arm_pat}`", arm_pat = self.arm_pat),
+        }
+    }
+
+    fn span_label(&self) -> String {
+        match &self.replacement_arm_pat {
+            Some(replacement_arm_pat) => format!("replace arm body with body of arm `{replacement_arm_pat}`"),
+            None => "delete arm body".to_owned(),
+        }
+    }
+}
+
+/// Replace the bodies of match arms.
+///
+/// If the match expression evaluates to unit, the body of each arm is replaced with an empty block.
+/// Otherwise, the body of each arm is replaced with the body of its neighboring arm (the next arm, or the previous arm
+/// for the last arm), as long as the neighboring body does not refer to bindings of its own pattern.
+pub struct MatchArmBodyReplace;
+
+impl<'a> Operator<'a> for MatchArmBodyReplace {
+    type Mutation = MatchArmBodyReplaceMutation;
+
+    fn try_apply(&self, mcx: &MutCtxt) -> Mutations<Self::Mutation> {
+        let MutCtxt { opts: _, tcx, crate_res: _, def_res: _, def_site: def, item_hir: f_hir, body_res, body_facts: _, instantiations: _, location } = *mcx;
+
+        let MutLoc::FnBodyExpr(expr, _f) = location else { return Mutations::none(); };
+
+        let ast::ExprKind::Match(_, arms, _) = &expr.kind else { return Mutations::none(); };
+
+        let Some(body_hir) = f_hir.body else { return Mutations::none(); };
+        let typeck = tcx.typeck_body(body_hir.id());
+
+        let Some(expr_hir) = body_res.hir_expr(expr) else { unreachable!() };
+        let hir::ExprKind::Match(_, arms_hir, hir::MatchSource::Normal) = expr_hir.kind else { return Mutations::none(); };
+        if arms_hir.len() != arms.len() { return Mutations::none(); }
+        let match_ty = typeck.expr_ty(expr_hir);
+
+        let mut mutations: SmallVec<[_; 1]> = smallvec![];
+
+        for (i, arm) in arms.iter().enumerate() {
+            let Some(arm_body) = &arm.body else { continue; };
+
+            let (replacement_arm_pat, replacement_body) = match () {
+                _ if match_ty == tcx.types.unit => {
+                    if is_empty_expr(arm_body) { continue; }
+                    (None, ast::mk::expr_noop(def))
+                }
+                _ => {
+                    let neighbor_idx = if i + 1 < arms.len() { i + 1 } else if i > 0 { i - 1 } else { continue; };
+                    let (neighbor_arm, neighbor_arm_hir) = (&arms[neighbor_idx], &arms_hir[neighbor_idx]);
+                    let Some(neighbor_body) = &neighbor_arm.body else { continue; };
+
+                    // Identical bodies would only yield an equivalent mutation.
+                    if ast::print::expr_to_string(arm_body) == ast::print::expr_to_string(neighbor_body) { continue; }
+                    // The bindings of the neighboring arm's pattern are not in scope of this arm.
+                    if refers_to_pat_bindings(tcx, neighbor_arm_hir.pat, neighbor_arm_hir.body) { continue; }
+                    if typeck.expr_ty_adjusted(neighbor_arm_hir.body) != match_ty { continue; }
+
+                    (Some(ast::print::pat_to_string(&neighbor_arm.pat)), neighbor_body.clone())
+                }
+            };
+
+            let mutation = Self::Mutation {
+                arm_pat: ast::print::pat_to_string(&arm.pat),
+                replacement_arm_pat,
+            };
+
+            mutations.push((mutation, smallvec![
+                SubstDef::new(
+                    SubstLoc::Replace(arm_body.id),
+                    Subst::AstExpr(replacement_body.into_inner()),
+                ),
+            ]));
+        }
+
+        Mutations::new(mutations)
+    }
+}
@@ -0,0 +1,179 @@
+use mutest_emit::{Mutation, Operator};
+use mutest_emit::analysis::hir;
+use mutest_emit::analysis::res;
+use mutest_emit::analysis::ty::{self, TyCtxt};
+use mutest_emit::codegen::ast;
+use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, MutationPriority, Mutations, Subst, SubstDef, SubstLoc};
+use mutest_emit::smallvec::{SmallVec, smallvec};
+
+use crate::match_arm_body_replace::refers_to_pat_bindings;
+
+/// Returns the enum variant matched by the pattern path, if it resolves to one.
+fn pat_variant<'tcx>(tcx: TyCtxt<'tcx>, typeck: &'tcx ty::TypeckResults<'tcx>, pat: &'tcx hir::Pat<'tcx>, qpath: &'tcx hir::QPath<'tcx>) -> Option<hir::DefId> {
+    match res::qpath_res(typeck, qpath, pat.hir_id) {
+        hir::Res::Def(hir::DefKind::Variant, def_id) => Some(def_id),
+        hir::Res::Def(hir::DefKind::Ctor(hir::CtorOf::Variant, _), ctor_def_id) => Some(tcx.parent(ctor_def_id)),
+        _ => None,
+    }
+}
+
+/// Returns `false` if the two patterns are known to never match the same value.
+///
+/// This is a conservative approximation: patterns are only considered disjoint if they match different enum variants
+/// or different literals in the same position. Anything else (ranges, constants, slices, etc.) is assumed to overlap.
+fn pats_may_overlap<'tcx>(tcx: TyCtxt<'tcx>, typeck: &'tcx ty::TypeckResults<'tcx>, a: &'tcx hir::Pat<'tcx>, b: &'tcx hir::Pat<'tcx>) -> bool {
+    let all_may_overlap = |pats_a: &'tcx [hir::Pat<'tcx>], pats_b: &'tcx [hir::Pat<'tcx>]| {
+        pats_a.len() != pats_b.len() || pats_a.iter().zip(pats_b).all(|(a, b)| pats_may_overlap(tcx, typeck, a, b))
+    };
+
+    match (&a.kind, &b.kind) {
+        (hir::PatKind::Wild, _) | (_, hir::PatKind::Wild) => true,
+        (hir::PatKind::Binding(_, _, _, None), _) | (_, hir::PatKind::Binding(_, _, _, None)) => true,
+        (hir::PatKind::Binding(_, _, _, Some(a)), _) => pats_may_overlap(tcx, typeck, a, b),
+        (_, hir::PatKind::Binding(_, _, _, Some(b))) => pats_may_overlap(tcx, typeck, a, b),
+
+        (hir::PatKind::Or(pats_a), _) => pats_a.iter().any(|a| pats_may_overlap(tcx, typeck, a, b)),
+        (_, hir::PatKind::Or(pats_b)) => pats_b.iter().any(|b| pats_may_overlap(tcx, typeck, a, b)),
+
+        (hir::PatKind::Box(a), hir::PatKind::Box(b)) => pats_may_overlap(tcx, typeck, a, b),
+        (hir::PatKind::Deref(a), hir::PatKind::Deref(b)) => pats_may_overlap(tcx, typeck, a, b),
+        (hir::PatKind::Ref(a, _), hir::PatKind::Ref(b, _)) => pats_may_overlap(tcx, typeck, a, b),
+
+        (hir::PatKind::Lit(expr_a), hir::PatKind::Lit(expr_b)) => {
+            match (&expr_a.kind, &expr_b.kind) {
+                (hir::ExprKind::Lit(lit_a), hir::ExprKind::Lit(lit_b)) => lit_a.node == lit_b.node,
+                _ => true,
+            }
+        }
+
+        (hir::PatKind::Tuple(pats_a, dotdot_a), hir::PatKind::Tuple(pats_b, dotdot_b)) => {
+            if dotdot_a.as_opt_usize().is_some() || dotdot_b.as_opt_usize().is_some() { return true; }
+            all_may_overlap(pats_a, pats_b)
+        }
+
+        (
+            hir::PatKind::Path(qpath_a) | hir::PatKind::TupleStruct(qpath_a, _, _) | hir::PatKind::Struct(qpath_a, _, _),
+            hir::PatKind::Path(qpath_b) | hir::PatKind::TupleStruct(qpath_b, _, _) | hir::PatKind::Struct(qpath_b, _, _),
+        ) => {
+            if let Some(variant_a) = pat_variant(tcx, typeck, a, qpath_a)
+                && let Some(variant_b) = pat_variant(tcx, typeck, b, qpath_b)
+                && variant_a != variant_b
+            {
+                return false;
+            }
+
+            match (&a.kind, &b.kind) {
+                (hir::PatKind::TupleStruct(_, pats_a, dotdot_a), hir::PatKind::TupleStruct(_, pats_b, dotdot_b)) => {
+                    if dotdot_a.as_opt_usize().is_some() || dotdot_b.as_opt_usize().is_some() { return true; }
+                    all_may_overlap(pats_a, pats_b)
+                }
+                (hir::PatKind::Struct(_, fields_a, _), hir::PatKind::Struct(_, fields_b, _)) => {
+                    // Fields missing from either pattern match any value.
+                    fields_a.iter().all(|field_a| {
+                        match fields_b.iter().find(|field_b| field_b.ident.name == field_a.ident.name) {
+                            Some(field_b) => pats_may_overlap(tcx, typeck, field_a.pat, field_b.pat),
+                            None => true,
+                        }
+                    })
+                }
+                _ => true,
+            }
+        }
+
+        _ => true,
+    }
+}
+
+fn arm_pat_to_string(arm: &ast::Arm) -> String {
+    match &arm.guard {
+        Some(guard) => format!("{pat} if {guard}", pat = ast::print::pat_to_string(&arm.pat), guard = ast::print::expr_to_string(guard)),
+        None => ast::print::pat_to_string(&arm.pat),
+    }
+}
+
+pub const MATCH_ARM_REORDER: &str = "match_arm_reorder";
+
+pub struct MatchArmReorderMutation {
+    pub arm_pat: String,
+    pub next_arm_pat: String,
+}
+
+impl Mutation for MatchArmReorderMutation {
+    fn op_name(&self) -> &str { MATCH_ARM_REORDER }
+
+    fn priority(&self) -> MutationPriority { MutationPriority::Medium }
+
+    fn display_name(&self) -> String {
+        format!("swap order of match arms `{arm_pat}` and `{next_arm_pat}`",
+            arm_pat = self.arm_pat,
+            next_arm_pat = self.next_arm_pat,
+        )
+    }
+
+    fn span_label(&self) -> String {
+        format!("swap order with next match arm `{next_arm_pat}`", next_arm_pat = self.next_arm_pat)
+    }
+}
+
+/// Swap the order of adjacent match arms whose patterns may match the same value, modeling arm precedence faults
+/// (e.g. a more general arm, or an arm with a guard, being placed before a more specific arm).
+///
+/// Arms whose patterns are known to be disjoint are not swapped, since their order does not affect which arm is taken.
+pub struct MatchArmReorder;
+
+impl<'a> Operator<'a> for MatchArmReorder {
+    type Mutation = MatchArmReorderMutation;
+
+    fn try_apply(&self, mcx: &MutCtxt) -> Mutations<Self::Mutation> {
+        let MutCtxt { opts: _, tcx, crate_res: _, def_res: _, def_site: _, item_hir: _, body_res, body_facts: _, instantiations: _, location } = *mcx;
+
+        let MutLoc::FnBodyExpr(expr, _f) = location else { return Mutations::none(); };
+
+        let ast::ExprKind::Match(scrutinee, arms, kind) = &expr.kind else { return Mutations::none(); };
+        if arms.len() < 2 { return Mutations::none(); }
+
+        let Some(typeck) = mcx.typeck() else { return Mutations::none(); };
+
+        let Some(expr_hir) = body_res.hir_expr(expr) else { unreachable!() };
+        let hir::ExprKind::Match(_, arms_hir, hir::MatchSource::Normal) = expr_hir.kind else { return Mutations::none(); };
+        if arms_hir.len() != arms.len() { return Mutations::none(); }
+
+        let mut mutations: SmallVec<[_; 1]> = smallvec![];
+
+        for (i, (arm_pair, arm_pair_hir)) in arms.windows(2).zip(arms_hir.windows(2)).enumerate() {
+            let [arm, next_arm] = arm_pair else { unreachable!() };
+            let [arm_hir, next_arm_hir] = arm_pair_hir else { unreachable!() };
+
+            if arm.span.ctxt() != expr.span.ctxt() || next_arm.span.ctxt() != expr.span.ctxt() { continue; }
+
+            if !pats_may_overlap(tcx, typeck, arm_hir.pat, next_arm_hir.pat) { continue; }
+
+            // Swapping arms with identical bodies which do not depend on their patterns would be an equivalent mutation.
+            if let (Some(body), Some(next_body)) = (&arm.body, &next_arm.body)
+                && ast::print::expr_to_string(body) == ast::print::expr_to_string(next_body)
+                && !refers_to_pat_bindings(tcx, arm_hir.pat, arm_hir.body)
+                && !refers_to_pat_bindings(tcx, next_arm_hir.pat, next_arm_hir.body)
+            {
+                continue;
+            }
+
+            let mut reordered_arms = arms.clone();
+            reordered_arms.swap(i, i + 1);
+            let reordered_expr = ast::mk::expr(expr.span, ast::ExprKind::Match(scrutinee.clone(), reordered_arms, *kind));
+
+            let mutation = Self::Mutation {
+                arm_pat: arm_pat_to_string(arm),
+                next_arm_pat: arm_pat_to_string(next_arm),
+            };
+
+            mutations.push((mutation, smallvec![
+                SubstDef::new(
+                    SubstLoc::Replace(expr.id),
+                    Subst::AstExpr(reordered_expr.into_inner()),
+                ),
+            ]));
+        }
+
+        Mutations::new(mutations)
+    }
+}
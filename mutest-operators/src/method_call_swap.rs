@@ -0,0 +1,121 @@
+use mutest_emit::{Mutation, Operator};
+use mutest_emit::analysis::hir;
+use mutest_emit::analysis::ty::{self, TyCtxt};
+use mutest_emit::codegen::ast;
+use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, MutationPriority, Mutations, Subst, SubstDef, SubstLoc};
+use mutest_emit::codegen::symbols::{Ident, Symbol};
+use mutest_emit::smallvec::smallvec;
+
+/// Default table of swappable method pairs, used by the `method_call_swap` mutation operator.
+///
+/// Each pair is swapped in both directions. Only methods with matching signatures, defined in the same `impl` block or
+/// trait are swapped, so methods like `push` and `insert` are intentionally not included.
+pub const METHOD_CALL_SWAP_DEFAULT_PAIRS: &[(&str, &str)] = &[
+    ("min", "max"),
+    ("floor", "ceil"),
+    ("saturating_add", "wrapping_add"),
+    ("saturating_sub", "wrapping_sub"),
+    ("saturating_mul", "wrapping_mul"),
+    ("saturating_pow", "wrapping_pow"),
+    ("first", "last"),
+    ("starts_with", "ends_with"),
+    ("trim_start", "trim_end"),
+    ("to_lowercase", "to_uppercase"),
+    ("any", "all"),
+];
+
+fn swappable_method<'tcx>(tcx: TyCtxt<'tcx>, callee: hir::DefId, replacement_name: Symbol) -> Option<hir::DefId> {
+    let assoc_item = tcx.opt_associated_item(callee)?;
+    let container_def_id = assoc_item.container_id(tcx);
+
+    let replacement = tcx.associated_items(container_def_id)
+        .filter_by_name_unhygienic(replacement_name)
+        .find(|item| item.kind == ty::AssocKind::Fn)?;
+
+    // The replacement method must be a drop-in replacement for the original method.
+    if tcx.generics_of(callee).count() != tcx.generics_of(replacement.def_id).count() { return None; }
+    let callee_sig = tcx.anonymize_bound_vars(tcx.fn_sig(callee).instantiate_identity());
+    let replacement_sig = tcx.anonymize_bound_vars(tcx.fn_sig(replacement.def_id).instantiate_identity());
+    if callee_sig != replacement_sig { return None; }
+
+    Some(replacement.def_id)
+}
+
+pub const METHOD_CALL_SWAP: &str = "method_call_swap";
+
+pub struct MethodCallSwapMutation {
+    pub original_method: Symbol,
+    pub replacement_method: Symbol,
+}
+
+impl Mutation for MethodCallSwapMutation {
+    fn op_name(&self) -> &str { METHOD_CALL_SWAP }
+
+    fn priority(&self) -> MutationPriority { MutationPriority::Medium }
+
+    fn display_name(&self) -> String {
+        format!("swap method call to `{original_method}` for `{replacement_method}`",
+            original_method = self.original_method,
+            replacement_method = self.replacement_method,
+        )
+    }
+
+    fn span_label(&self) -> String {
+        format!("swap method call for `{replacement_method}`",
+            replacement_method = self.replacement_method,
+        )
+    }
+}
+
+/// Swap calls to methods with their counterpart from a table of known method pairs (e.g. `min` for `max`).
+pub struct MethodCallSwap {
+    pub method_pairs: &'static [(&'static str, &'static str)],
+}
+
+impl<'a> Operator<'a> for MethodCallSwap {
+    type Mutation = MethodCallSwapMutation;
+
+    fn try_apply(&self, mcx: &MutCtxt) -> Mutations<Self::Mutation> {
+        let MutCtxt { opts: _, tcx, crate_res: _, def_res: _, def_site: def, item_hir: f_hir, body_res, body_facts: _, instantiations: _, location } = *mcx;
+
+        let MutLoc::FnBodyExpr(expr, _f) = location else { return Mutations::none(); };
+
+        let ast::ExprKind::MethodCall(call) = &expr.kind else { return Mutations::none(); };
+
+        let method_name = call.seg.ident.name;
+        let Some(replacement_name) = self.method_pairs.iter()
+            .find_map(|&(a, b)| match method_name.as_str() {
+                name if name == a => Some(b),
+                name if name == b => Some(a),
+                _ => None,
+            })
+            .map(Symbol::intern)
+        else { return Mutations::none(); };
+
+        let Some(body_hir) = f_hir.body else { return Mutations::none(); };
+        let typeck = tcx.typeck_body(body_hir.id());
+
+        let Some(expr_hir) = body_res.hir_expr(expr) else { unreachable!() };
+        let Some(callee) = typeck.type_dependent_def_id(expr_hir.hir_id) else { return Mutations::none(); };
+        let Some(_) = swappable_method(tcx, callee, replacement_name) else { return Mutations::none(); };
+
+        let replacement_seg = ast::PathSegment {
+            ident: Ident::new(replacement_name, call.seg.ident.span),
+            id: ast::DUMMY_NODE_ID,
+            args: call.seg.args.clone(),
+        };
+        let swapped_expr = ast::mk::expr_method_call(def, call.receiver.clone(), replacement_seg, call.args.clone());
+
+        let mutation = Self::Mutation {
+            original_method: method_name,
+            replacement_method: replacement_name,
+        };
+
+        Mutations::new_one(mutation, smallvec![
+            SubstDef::new(
+                SubstLoc::Replace(expr.id),
+                Subst::AstExpr(swapped_expr.into_inner()),
+            ),
+        ])
+    }
+}
@@ -2,10 +2,11 @@ use mutest_emit::{Mutation, Operator};
 use mutest_emit::analysis::hir;
 use mutest_emit::analysis::res;
 use mutest_emit::analysis::ty::{self, Ty, TyCtxt};
-use mutest_emit::codegen::ast;
-use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, Mutations, Subst, SubstDef, SubstLoc};
-use mutest_emit::codegen::symbols::sym;
+use mutest_emit::codegen::ast::{self, P};
+use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, MutationPriority, Mutations, Subst, SubstDef, SubstLoc};
+use mutest_emit::codegen::symbols::{Ident, Span, Symbol, sym};
 use mutest_emit::smallvec::smallvec;
+use mutest_emit::thin_vec::thin_vec;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum OpKind {
@@ -22,6 +23,155 @@ impl OpKind {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpForm {
+    /// Operator syntax (e.g. `a + b`).
+    Operator,
+    /// Explicit call to the method of the operator trait (e.g. `a.add(b)`, or `Add::add(a, b)`).
+    MethodCall { original_method: Symbol, replacement_method: Symbol },
+}
+
+impl OpForm {
+    /// Description of the form, followed by the displayed original and replacement operators.
+    fn display_ops(&self, original_bin_op: ast::BinOpKind, replacement_bin_op: ast::BinOpKind) -> (&'static str, String, String) {
+        match *self {
+            Self::Operator => ("", original_bin_op.as_str().to_owned(), replacement_bin_op.as_str().to_owned()),
+            Self::MethodCall { original_method, replacement_method } => (" method", original_method.to_string(), replacement_method.to_string()),
+        }
+    }
+}
+
+/// Overloadable binary operators, with their operator traits, and their assignment operator traits.
+const OP_TRAITS: &[(ast::BinOpKind, fn(TyCtxt<'_>) -> hir::DefId, fn(TyCtxt<'_>) -> hir::DefId)] = &[
+    (ast::BinOpKind::Add, res::traits::Add, res::traits::AddAssign),
+    (ast::BinOpKind::Sub, res::traits::Sub, res::traits::SubAssign),
+    (ast::BinOpKind::Mul, res::traits::Mul, res::traits::MulAssign),
+    (ast::BinOpKind::Div, res::traits::Div, res::traits::DivAssign),
+    (ast::BinOpKind::Rem, res::traits::Rem, res::traits::RemAssign),
+    (ast::BinOpKind::BitAnd, res::traits::BitAnd, res::traits::BitAndAssign),
+    (ast::BinOpKind::BitOr, res::traits::BitOr, res::traits::BitOrAssign),
+    (ast::BinOpKind::BitXor, res::traits::BitXor, res::traits::BitXorAssign),
+    (ast::BinOpKind::Shl, res::traits::Shl, res::traits::ShlAssign),
+    (ast::BinOpKind::Shr, res::traits::Shr, res::traits::ShrAssign),
+];
+
+/// The operator corresponding to the method of an operator trait, if the function is one.
+fn op_of_trait_method<'tcx>(tcx: TyCtxt<'tcx>, def_id: hir::DefId) -> Option<(ast::BinOpKind, OpKind)> {
+    let trait_def_id = tcx.trait_of_item(def_id)?;
+    OP_TRAITS.iter().find_map(|&(bin_op, op_trait, assign_op_trait)| {
+        if op_trait(tcx) == trait_def_id { return Some((bin_op, OpKind::Standalone)); }
+        if assign_op_trait(tcx) == trait_def_id { return Some((bin_op, OpKind::Assign)); }
+        None
+    })
+}
+
+fn op_trait_method<'tcx>(tcx: TyCtxt<'tcx>, op_trait: hir::DefId) -> Option<Symbol> {
+    tcx.associated_items(op_trait).in_definition_order()
+        .find(|assoc_item| assoc_item.kind == ty::AssocKind::Fn)
+        .map(|assoc_item| assoc_item.name)
+}
+
+/// `::core::ops::$Trait::$method`
+fn op_trait_method_path<'tcx>(tcx: TyCtxt<'tcx>, sp: Span, op_trait: hir::DefId) -> Option<ast::Path> {
+    let method = op_trait_method(tcx, op_trait)?;
+    Some(ast::mk::path_global(sp, vec![
+        Ident::new(sym::core, sp),
+        Ident::new(Symbol::intern("ops"), sp),
+        Ident::new(tcx.item_name(op_trait), sp),
+        Ident::new(method, sp),
+    ]))
+}
+
+struct Operands<'tcx> {
+    lhs: P<ast::Expr>,
+    lhs_ty: Ty<'tcx>,
+    rhs: P<ast::Expr>,
+    rhs_ty: Ty<'tcx>,
+}
+
+/// Operands of an explicit call to the method of an operator trait, as passed to the method with function call syntax.
+/// The types of the operands are the `Self` and `Rhs` types of the operator trait.
+fn op_method_call_operands<'tcx>(tcx: TyCtxt<'tcx>, typeck: &'tcx ty::TypeckResults<'tcx>, sp: Span, expr: &ast::Expr, expr_hir: &'tcx hir::Expr<'tcx>) -> Option<(ast::BinOpKind, OpKind, Operands<'tcx>)> {
+    let (callee, generic_args) = res::callee(typeck, expr_hir)?;
+    let (bin_op, op_kind) = op_of_trait_method(tcx, callee)?;
+    let (self_ty, rhs_ty) = (generic_args.type_at(0), generic_args.type_at(1));
+
+    let (lhs, rhs) = match (&expr.kind, expr_hir.kind) {
+        // Add::add($lhs, $rhs), AddAssign::add_assign($lhs, $rhs)
+        (ast::ExprKind::Call(_, args), _) => {
+            let [lhs, rhs] = &args[..] else { return None; };
+            (lhs.clone(), rhs.clone())
+        }
+        // $lhs.add($rhs), $lhs.add_assign($rhs)
+        (ast::ExprKind::MethodCall(call), hir::ExprKind::MethodCall(_, receiver_hir, _, _)) => {
+            let [rhs] = &call.args[..] else { return None; };
+
+            // The autoref of the receiver of assignment operator methods is made explicit.
+            // Receivers with other adjustments (e.g. autoderef) are not supported.
+            let receiver_ty = typeck.expr_ty(receiver_hir);
+            let lhs = match op_kind {
+                OpKind::Standalone if receiver_ty == self_ty => call.receiver.clone(),
+                OpKind::Assign if receiver_ty == self_ty => {
+                    ast::mk::expr_mut_ref(sp, ast::mk::expr_paren(sp, call.receiver.clone()))
+                }
+                OpKind::Assign if matches!(*receiver_ty.kind(), ty::Ref(_, inner_ty, ast::Mutability::Mut) if inner_ty == self_ty) => {
+                    ast::mk::expr_mut_ref(sp, ast::mk::expr_deref(sp, ast::mk::expr_paren(sp, call.receiver.clone())))
+                }
+                _ => return None,
+            };
+
+            (lhs, rhs.clone())
+        }
+        _ => return None,
+    };
+
+    Some((bin_op, op_kind, Operands { lhs, lhs_ty: self_ty, rhs, rhs_ty }))
+}
+
+/// Adjustment of the operands of the replacement operator, for when the replacement operator is not implemented for
+/// the original operand types, but is for references to them, or for the types they reference.
+///
+/// NOTE: Unlike method calls, rustc's lookup of overloaded operators does not autoref or autoderef the operands
+///       (with the exception of comparison operators), so the adjustment has to be explicit in the replacement.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OperandAdjustment {
+    /// `$lhs op $rhs`.
+    None,
+    /// `&$lhs op &$rhs`, or `$lhs op= &$rhs` for assignment operators.
+    Ref,
+    /// `*$lhs op *$rhs`, or `$lhs op= *$rhs` for assignment operators, for references to `Copy` types.
+    Deref,
+}
+
+impl OperandAdjustment {
+    const ALL: [Self; 3] = [Self::None, Self::Ref, Self::Deref];
+
+    fn adjust_operand_tys<'tcx>(&self, tcx: TyCtxt<'tcx>, param_env: ty::ParamEnv<'tcx>, lhs_ty: Ty<'tcx>, rhs_ty: Ty<'tcx>, op_kind: OpKind) -> Option<(Ty<'tcx>, Ty<'tcx>)> {
+        let adjust_ty = |ty: Ty<'tcx>| match self {
+            Self::None => Some(ty),
+            Self::Ref => Some(Ty::new_imm_ref(tcx, tcx.lifetimes.re_erased, ty)),
+            Self::Deref => match *ty.kind() {
+                ty::Ref(_, inner_ty, _) if inner_ty.is_copy_modulo_regions(tcx, param_env) => Some(inner_ty),
+                _ => None,
+            },
+        };
+
+        match op_kind {
+            OpKind::Standalone => Some((adjust_ty(lhs_ty)?, adjust_ty(rhs_ty)?)),
+            // The left-hand side of assignment operators is a place, which is always mutably borrowed.
+            OpKind::Assign => Some((lhs_ty, adjust_ty(rhs_ty)?)),
+        }
+    }
+
+    fn adjust_operand(&self, sp: Span, operand: P<ast::Expr>) -> P<ast::Expr> {
+        match self {
+            Self::None => operand,
+            Self::Ref => ast::mk::expr_ref(sp, ast::mk::expr_paren(sp, operand)),
+            Self::Deref => ast::mk::expr_deref(sp, ast::mk::expr_paren(sp, operand)),
+        }
+    }
+}
+
 fn impls_matching_op<'tcx>(tcx: TyCtxt<'tcx>, param_env: ty::ParamEnv<'tcx>, caller_def_id: hir::LocalDefId, lhs_ty: Ty<'tcx>, rhs_ty: Ty<'tcx>, expr_ty: Ty<'tcx>, op_trait: hir::DefId, op_kind: OpKind) -> bool {
     if !ty::impls_trait_with_env(tcx, param_env, lhs_ty, op_trait, vec![rhs_ty.into()]) { return false; }
 
@@ -34,9 +184,15 @@ fn impls_matching_op<'tcx>(tcx: TyCtxt<'tcx>, param_env: ty::ParamEnv<'tcx>, cal
     }
 }
 
+/// Trait of the replacement operator, if it is overloadable.
+macro op_trait_def_id {
+    ($tcx:ident) => { None },
+    ($tcx:ident, $op_trait:ident) => { Some(res::traits::$op_trait($tcx)) },
+}
+
 macro define_op_swap_operator(
     $(#[$meta:meta])*
-    $vis:vis $operator:ident, $mutation:ident as $op_name_ident:ident = $op_name:literal $([$bin_op_group:expr])? {
+    $vis:vis $operator:ident, $mutation:ident as $op_name_ident:ident = $op_name:literal $([$bin_op_group:expr])?, $priority:ident {
         $($bin_op_from:pat $(if impl $bin_op_to_trait:ident, $bin_assign_op_to_trait:ident)? => $bin_op_to:expr),+ $(,)?
     }
 ) {
@@ -44,6 +200,7 @@ macro define_op_swap_operator(
 
     $vis struct $mutation {
         pub op_kind: OpKind,
+        pub op_form: OpForm,
         pub original_bin_op: ast::BinOpKind,
         pub replacement_bin_op: ast::BinOpKind,
     }
@@ -51,18 +208,19 @@ macro define_op_swap_operator(
     impl Mutation for $mutation {
         fn op_name(&self) -> &str { $op_name }
 
+        fn priority(&self) -> MutationPriority { MutationPriority::$priority }
+
         fn display_name(&self) -> String {
-            format!(concat!("swap ", $($bin_op_group, " ",)? "{op_kind} `{original_bin_op}` for `{replacement_bin_op}`"),
+            let (op_form, original_op, replacement_op) = self.op_form.display_ops(self.original_bin_op, self.replacement_bin_op);
+            format!(concat!("swap ", $($bin_op_group, " ",)? "{op_kind}{op_form} `{original_op}` for `{replacement_op}`"),
                 op_kind = self.op_kind.desc(),
-                original_bin_op = self.original_bin_op.as_str(),
-                replacement_bin_op = self.replacement_bin_op.as_str(),
             )
         }
 
         fn span_label(&self) -> String {
-            format!(concat!("swap ", $($bin_op_group, " ",)? "{op_kind} for `{replacement_bin_op}`"),
+            let (op_form, _, replacement_op) = self.op_form.display_ops(self.original_bin_op, self.replacement_bin_op);
+            format!(concat!("swap ", $($bin_op_group, " ",)? "{op_kind}{op_form} for `{replacement_op}`"),
                 op_kind = self.op_kind.desc(),
-                replacement_bin_op = self.replacement_bin_op.as_str(),
             )
         }
     }
@@ -74,15 +232,11 @@ macro define_op_swap_operator(
         type Mutation = $mutation;
 
         fn try_apply(&self, mcx: &MutCtxt) -> Mutations<Self::Mutation> {
-            let MutCtxt { opts: _, tcx, crate_res: _, def_res: _, def_site: def, item_hir: f_hir, body_res, location } = *mcx;
+            let MutCtxt { opts: _, tcx, crate_res: _, def_res: _, def_site: def, item_hir: f_hir, body_res, body_facts: _, instantiations: _, location } = *mcx;
 
             let MutLoc::FnBodyExpr(expr, _f) = location else { return Mutations::none(); };
 
-            let (bin_op, op_kind) = match &expr.kind {
-                ast::ExprKind::Binary(bin_op, _, _) => (bin_op.node, OpKind::Standalone),
-                ast::ExprKind::AssignOp(bin_op, _, _) => (bin_op.node, OpKind::Assign),
-                _ => { return Mutations::none(); }
-            };
+            let (ast::ExprKind::Binary(..) | ast::ExprKind::AssignOp(..) | ast::ExprKind::MethodCall(..) | ast::ExprKind::Call(..)) = expr.kind else { return Mutations::none(); };
 
             let param_env = tcx.param_env(f_hir.owner_id.def_id);
 
@@ -91,34 +245,77 @@ macro define_op_swap_operator(
 
             let Some(expr_hir) = body_res.hir_expr(expr) else { unreachable!() };
             let expr_ty = typeck.expr_ty(expr_hir);
-            let (lhs_ty, rhs_ty) = match expr_hir.kind {
-                | hir::ExprKind::Binary(_, lhs, rhs)
-                | hir::ExprKind::AssignOp(_, lhs, rhs) => {
-                    (typeck.expr_ty(lhs), typeck.expr_ty(rhs))
+            let (bin_op, op_kind, is_method_call, Operands { lhs, lhs_ty, rhs, rhs_ty }) = match (&expr.kind, expr_hir.kind) {
+                | (ast::ExprKind::Binary(bin_op, lhs, rhs), hir::ExprKind::Binary(_, lhs_hir, rhs_hir))
+                | (ast::ExprKind::AssignOp(bin_op, lhs, rhs), hir::ExprKind::AssignOp(_, lhs_hir, rhs_hir)) => {
+                    let op_kind = match expr.kind {
+                        ast::ExprKind::AssignOp(..) => OpKind::Assign,
+                        _ => OpKind::Standalone,
+                    };
+                    let operands = Operands { lhs: lhs.clone(), lhs_ty: typeck.expr_ty(lhs_hir), rhs: rhs.clone(), rhs_ty: typeck.expr_ty(rhs_hir) };
+                    (bin_op.node, op_kind, false, operands)
+                }
+                (ast::ExprKind::MethodCall(..) | ast::ExprKind::Call(..), _) => {
+                    let Some((bin_op, op_kind, operands)) = op_method_call_operands(tcx, typeck, def, expr, expr_hir) else { return Mutations::none(); };
+                    (bin_op, op_kind, true, operands)
                 }
                 _ => unreachable!(),
             };
 
-            let caller_def_id = f_hir.owner_id.def_id;
-            #[allow(unused_variables)]
-            let expr_impls_matching_op = |op_trait| impls_matching_op(tcx, param_env, caller_def_id, lhs_ty, rhs_ty, expr_ty, op_trait, op_kind);
-
-            let mapped_bin_op = match (bin_op, op_kind) {
+            let (mapped_bin_op, op_trait) = match (bin_op, op_kind) {
                 $(
-                    ($bin_op_from, OpKind::Standalone) $(if expr_impls_matching_op(res::traits::$bin_op_to_trait(tcx)))? => $bin_op_to,
-                    ($bin_op_from, OpKind::Assign) $(if expr_impls_matching_op(res::traits::$bin_assign_op_to_trait(tcx)))? => $bin_op_to,
+                    ($bin_op_from, OpKind::Standalone) => ($bin_op_to, op_trait_def_id!(tcx $(, $bin_op_to_trait)?)),
+                    ($bin_op_from, OpKind::Assign) => ($bin_op_to, op_trait_def_id!(tcx $(, $bin_assign_op_to_trait)?)),
                 )+
                 _ => { return Mutations::none(); }
             };
 
-            let mapped_bin_expr = match &expr.kind {
-                ast::ExprKind::Binary(_, lhs, rhs) => ast::mk::expr_binary(def, mapped_bin_op, lhs.clone(), rhs.clone()),
-                ast::ExprKind::AssignOp(_, lhs, rhs) => ast::mk::expr_assign_op(def, mapped_bin_op, lhs.clone(), rhs.clone()),
-                _ => unreachable!(),
+            let caller_def_id = f_hir.owner_id.def_id;
+            let operand_adjustment = match op_trait {
+                Some(op_trait) => {
+                    let operand_adjustment = OperandAdjustment::ALL.into_iter().find(|operand_adjustment| {
+                        let Some((lhs_ty, rhs_ty)) = operand_adjustment.adjust_operand_tys(tcx, param_env, lhs_ty, rhs_ty, op_kind) else { return false; };
+
+                        impls_matching_op(tcx, param_env, caller_def_id, lhs_ty, rhs_ty, expr_ty, op_trait, op_kind)
+                            // The operator must also be implemented in each instantiation of generic functions.
+                            && mcx.holds_for_instantiations((lhs_ty, rhs_ty, expr_ty), |(lhs_ty, rhs_ty, expr_ty), param_env| {
+                                impls_matching_op(tcx, param_env, caller_def_id, lhs_ty, rhs_ty, expr_ty, op_trait, op_kind)
+                            })
+                    });
+                    let Some(operand_adjustment) = operand_adjustment else { return Mutations::none(); };
+                    operand_adjustment
+                }
+                None => OperandAdjustment::None,
+            };
+
+            let lhs = match op_kind {
+                OpKind::Standalone => operand_adjustment.adjust_operand(def, lhs),
+                OpKind::Assign => lhs,
+            };
+            let rhs = operand_adjustment.adjust_operand(def, rhs);
+
+            let (mapped_bin_expr, op_form) = match (is_method_call, op_trait) {
+                (false, _) => {
+                    let mapped_bin_expr = match op_kind {
+                        OpKind::Standalone => ast::mk::expr_binary(def, mapped_bin_op, lhs, rhs),
+                        OpKind::Assign => ast::mk::expr_assign_op(def, mapped_bin_op, lhs, rhs),
+                    };
+                    (mapped_bin_expr, OpForm::Operator)
+                }
+                // The method of the replacement operator trait is called with function call syntax, since the trait may
+                // not be in scope.
+                (true, Some(op_trait)) => {
+                    let Some((callee, _)) = res::callee(typeck, expr_hir) else { unreachable!() };
+                    let (Some(replacement_method), Some(op_trait_method_path)) = (op_trait_method(tcx, op_trait), op_trait_method_path(tcx, def, op_trait)) else { return Mutations::none(); };
+                    let op_form = OpForm::MethodCall { original_method: tcx.item_name(callee), replacement_method };
+                    (ast::mk::expr_call_path(def, op_trait_method_path, thin_vec![lhs, rhs]), op_form)
+                }
+                (true, None) => unreachable!(),
             };
 
             let mutation = Self::Mutation {
                 op_kind,
+                op_form,
                 original_bin_op: bin_op,
                 replacement_bin_op: mapped_bin_op,
             };
@@ -135,7 +332,7 @@ macro define_op_swap_operator(
 
 define_op_swap_operator! {
     /// Swap addition for subtraction and vice versa.
-    pub OpAddSubSwap, OpAddSubSwapMutation as MATH_OP_ADD_SUB_SWAP = "math_op_add_sub_swap" {
+    pub OpAddSubSwap, OpAddSubSwapMutation as MATH_OP_ADD_SUB_SWAP = "math_op_add_sub_swap", High {
         ast::BinOpKind::Add if impl Sub, SubAssign => ast::BinOpKind::Sub,
         ast::BinOpKind::Sub if impl Add, AddAssign => ast::BinOpKind::Add,
     }
@@ -143,7 +340,7 @@ define_op_swap_operator! {
 
 define_op_swap_operator! {
     /// Swap addition for multiplication and vice versa.
-    pub OpAddMulSwap, OpAddMulSwapMutation as MATH_OP_ADD_MUL_SWAP = "math_op_add_mul_swap" {
+    pub OpAddMulSwap, OpAddMulSwapMutation as MATH_OP_ADD_MUL_SWAP = "math_op_add_mul_swap", Medium {
         ast::BinOpKind::Add if impl Mul, MulAssign => ast::BinOpKind::Mul,
         ast::BinOpKind::Mul if impl Add, AddAssign => ast::BinOpKind::Add,
     }
@@ -151,7 +348,7 @@ define_op_swap_operator! {
 
 define_op_swap_operator! {
     /// Swap multiplication for division and vice versa.
-    pub OpMulDivSwap, OpMulDivSwapMutation as MATH_OP_MUL_DIV_SWAP = "math_op_mul_div_swap" {
+    pub OpMulDivSwap, OpMulDivSwapMutation as MATH_OP_MUL_DIV_SWAP = "math_op_mul_div_swap", Medium {
         ast::BinOpKind::Mul if impl Div, DivAssign => ast::BinOpKind::Div,
         ast::BinOpKind::Div if impl Mul, MulAssign => ast::BinOpKind::Mul,
     }
@@ -159,7 +356,7 @@ define_op_swap_operator! {
 
 define_op_swap_operator! {
     /// Swap division for modulus and vice versa.
-    pub OpDivRemSwap, OpDivRemSwapMutation as MATH_OP_DIV_REM_SWAP = "math_op_div_rem_swap" {
+    pub OpDivRemSwap, OpDivRemSwapMutation as MATH_OP_DIV_REM_SWAP = "math_op_div_rem_swap", Medium {
         ast::BinOpKind::Div if impl Rem, RemAssign => ast::BinOpKind::Rem,
         ast::BinOpKind::Rem if impl Div, DivAssign => ast::BinOpKind::Div,
     }
@@ -167,7 +364,7 @@ define_op_swap_operator! {
 
 define_op_swap_operator! {
     /// Swap bitwise OR for bitwise XOR and vice versa.
-    pub BitOpOrXorSwap, BitOpOrXorSwapMutation as BIT_OP_OR_XOR_SWAP = "bit_op_or_xor_swap" ["bitwise"] {
+    pub BitOpOrXorSwap, BitOpOrXorSwapMutation as BIT_OP_OR_XOR_SWAP = "bit_op_or_xor_swap" ["bitwise"], Low {
         ast::BinOpKind::BitOr if impl BitXor, BitXorAssign => ast::BinOpKind::BitXor,
         ast::BinOpKind::BitXor if impl BitOr, BitOrAssign => ast::BinOpKind::BitOr,
     }
@@ -175,7 +372,7 @@ define_op_swap_operator! {
 
 define_op_swap_operator! {
     /// Swap bitwise OR for bitwise AND and vice versa.
-    pub BitOpOrAndSwap, BitOpOrAndSwapMutation as BIT_OP_OR_AND_SWAP = "bit_op_or_and_swap" ["bitwise"] {
+    pub BitOpOrAndSwap, BitOpOrAndSwapMutation as BIT_OP_OR_AND_SWAP = "bit_op_or_and_swap" ["bitwise"], Low {
         ast::BinOpKind::BitOr if impl BitAnd, BitAndAssign => ast::BinOpKind::BitAnd,
         ast::BinOpKind::BitAnd if impl BitOr, BitOrAssign => ast::BinOpKind::BitOr,
     }
@@ -183,7 +380,7 @@ define_op_swap_operator! {
 
 define_op_swap_operator! {
     /// Swap bitwise XOR for bitwise AND and vice versa.
-    pub BitOpXorAndSwap, BitOpXorAndSwapMutation as BIT_OP_XOR_AND_SWAP = "bit_op_xor_and_swap" ["bitwise"] {
+    pub BitOpXorAndSwap, BitOpXorAndSwapMutation as BIT_OP_XOR_AND_SWAP = "bit_op_xor_and_swap" ["bitwise"], Low {
         ast::BinOpKind::BitXor if impl BitAnd, BitAndAssign => ast::BinOpKind::BitAnd,
         ast::BinOpKind::BitAnd if impl BitXor, BitXorAssign => ast::BinOpKind::BitXor,
     }
@@ -191,7 +388,7 @@ define_op_swap_operator! {
 
 define_op_swap_operator! {
     /// Swap the direction of bitwise shift operators.
-    pub BitOpShiftDirSwap, BitOpShiftDirSwapMutation as BIT_OP_SHIFT_DIR_SWAP = "bit_op_shift_dir_swap" ["bitwise"] {
+    pub BitOpShiftDirSwap, BitOpShiftDirSwapMutation as BIT_OP_SHIFT_DIR_SWAP = "bit_op_shift_dir_swap" ["bitwise"], Low {
         ast::BinOpKind::Shl if impl Shr, ShrAssign => ast::BinOpKind::Shr,
         ast::BinOpKind::Shr if impl Shl, ShlAssign => ast::BinOpKind::Shl,
     }
@@ -199,7 +396,7 @@ define_op_swap_operator! {
 
 define_op_swap_operator! {
     /// Swap logical && for logical || and vice versa.
-    pub LogicalOpAndOrSwap, LogicalOpAndOrSwapMutation as LOGICAL_OP_AND_OR_SWAP = "logical_op_and_or_swap" ["logical"] {
+    pub LogicalOpAndOrSwap, LogicalOpAndOrSwapMutation as LOGICAL_OP_AND_OR_SWAP = "logical_op_and_or_swap" ["logical"], High {
         ast::BinOpKind::And => ast::BinOpKind::Or,
         ast::BinOpKind::Or => ast::BinOpKind::And,
     }
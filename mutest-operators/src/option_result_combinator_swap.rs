@@ -0,0 +1,165 @@
+use mutest_emit::{Mutation, Operator};
+use mutest_emit::analysis::hir;
+use mutest_emit::analysis::ty::{self, TyCtxt};
+use mutest_emit::codegen::ast;
+use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, MutationPriority, Mutations, Subst, SubstDef, SubstLoc};
+use mutest_emit::codegen::symbols::{Ident, Symbol, kw, sym};
+use mutest_emit::smallvec::smallvec;
+use mutest_emit::thin_vec::thin_vec;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CombinatorReceiver {
+    Option,
+    Result,
+}
+
+/// Pairs of eagerly and lazily evaluated combinators of `Option` and `Result`, with the receivers they are swapped for.
+const COMBINATOR_PAIRS: &[(&str, &str, &[CombinatorReceiver])] = &[
+    ("unwrap_or", "unwrap_or_else", &[CombinatorReceiver::Option, CombinatorReceiver::Result]),
+    ("ok_or", "ok_or_else", &[CombinatorReceiver::Option]),
+];
+
+fn combinator_receiver<'tcx>(tcx: TyCtxt<'tcx>, callee: hir::DefId) -> Option<CombinatorReceiver> {
+    let impl_def_id = tcx.impl_of_method(callee)?;
+    if tcx.trait_id_of_impl(impl_def_id).is_some() { return None; }
+
+    let ty::TyKind::Adt(adt_def, _) = tcx.type_of(impl_def_id).instantiate_identity().kind() else { return None; };
+    match () {
+        _ if tcx.is_diagnostic_item(sym::Option, adt_def.did()) => Some(CombinatorReceiver::Option),
+        _ if tcx.is_diagnostic_item(sym::Result, adt_def.did()) => Some(CombinatorReceiver::Result),
+        _ => None,
+    }
+}
+
+/// Returns whether evaluating the expression cannot have side-effects, in which case evaluating it eagerly or lazily is
+/// equivalent.
+///
+/// NOTE: This is a conservative, syntactic check. Calls (other than to constructors), operators, and field accesses are
+///       assumed to have side-effects, since they may panic, or may be overloaded.
+fn is_side_effect_free<'tcx>(expr: &hir::Expr<'tcx>) -> bool {
+    match expr.kind {
+        hir::ExprKind::Lit(_) | hir::ExprKind::Path(_) => true,
+        hir::ExprKind::AddrOf(_, _, expr) | hir::ExprKind::DropTemps(expr) => is_side_effect_free(expr),
+        hir::ExprKind::Tup(exprs) | hir::ExprKind::Array(exprs) => exprs.iter().all(is_side_effect_free),
+        hir::ExprKind::Call(callee, args) => {
+            let hir::ExprKind::Path(hir::QPath::Resolved(_, path)) = callee.kind else { return false; };
+            let hir::Res::Def(hir::DefKind::Ctor(..), _) = path.res else { return false; };
+            args.iter().all(is_side_effect_free)
+        }
+        _ => false,
+    }
+}
+
+pub const OPTION_RESULT_COMBINATOR_SWAP: &str = "option_result_combinator_swap";
+
+pub struct OptionResultCombinatorSwapMutation {
+    pub original_method: Symbol,
+    pub replacement_method: Symbol,
+}
+
+impl Mutation for OptionResultCombinatorSwapMutation {
+    fn op_name(&self) -> &str { OPTION_RESULT_COMBINATOR_SWAP }
+
+    fn priority(&self) -> MutationPriority { MutationPriority::Low }
+
+    fn display_name(&self) -> String {
+        format!("swap combinator `{original_method}` for `{replacement_method}`",
+            original_method = self.original_method,
+            replacement_method = self.replacement_method,
+        )
+    }
+
+    fn span_label(&self) -> String {
+        format!("swap combinator for `{replacement_method}`",
+            replacement_method = self.replacement_method,
+        )
+    }
+}
+
+/// Swap eagerly evaluated combinators of `Option` and `Result` for their lazily evaluated counterparts and vice versa
+/// (e.g. `unwrap_or` for `unwrap_or_else`), to test whether the side-effects of computing fallback values are
+/// meaningfully tested.
+///
+/// The argument of the original call is converted into the argument of its counterpart: values are wrapped in a
+/// closure, and closures are called eagerly. Closures taking arguments (e.g. the error of `Result::unwrap_or_else`)
+/// cannot be called eagerly, so these are not swapped.
+///
+/// Arguments (and bodies of closure arguments) which cannot have side-effects (e.g. literals and paths) are not swapped,
+/// since evaluating them eagerly or lazily is equivalent.
+///
+/// NOTE: `and_then` and `map` are not swapped, since the resulting expressions never have the same type.
+pub struct OptionResultCombinatorSwap;
+
+impl<'a> Operator<'a> for OptionResultCombinatorSwap {
+    type Mutation = OptionResultCombinatorSwapMutation;
+
+    fn try_apply(&self, mcx: &MutCtxt) -> Mutations<Self::Mutation> {
+        let MutCtxt { opts: _, tcx, crate_res: _, def_res: _, def_site: def, item_hir: _, body_res, body_facts: _, instantiations: _, location } = *mcx;
+
+        let MutLoc::FnBodyExpr(expr, _f) = location else { return Mutations::none(); };
+
+        let ast::ExprKind::MethodCall(call) = &expr.kind else { return Mutations::none(); };
+        let [arg] = &call.args[..] else { return Mutations::none(); };
+
+        let method_name = call.seg.ident.name;
+        let Some((replacement_name, is_original_lazy, receivers)) = COMBINATOR_PAIRS.iter()
+            .find_map(|&(eager, lazy, receivers)| match method_name.as_str() {
+                name if name == eager => Some((lazy, false, receivers)),
+                name if name == lazy => Some((eager, true, receivers)),
+                _ => None,
+            })
+        else { return Mutations::none(); };
+        let replacement_name = Symbol::intern(replacement_name);
+
+        let Some(typeck) = mcx.typeck() else { return Mutations::none(); };
+        let Some(expr_hir) = body_res.hir_expr(expr) else { unreachable!() };
+        let Some(callee) = typeck.type_dependent_def_id(expr_hir.hir_id) else { return Mutations::none(); };
+        let Some(receiver) = combinator_receiver(tcx, callee) else { return Mutations::none(); };
+        if !receivers.contains(&receiver) { return Mutations::none(); }
+
+        let Some(arg_hir) = body_res.hir_expr(arg) else { return Mutations::none(); };
+        let evaluated_arg_hir = match (is_original_lazy, arg_hir.kind) {
+            (true, hir::ExprKind::Closure(closure)) => Some(tcx.hir().body(closure.body).value),
+            (true, _) => None,
+            (false, _) => Some(arg_hir),
+        };
+        if let Some(evaluated_arg_hir) = evaluated_arg_hir && is_side_effect_free(evaluated_arg_hir) { return Mutations::none(); }
+
+        let replacement_arg = match is_original_lazy {
+            // unwrap_or($v) -> unwrap_or_else(|| $v)
+            // Result::unwrap_or($v) -> Result::unwrap_or_else(|_| $v)
+            false => {
+                let closure_params = match receiver {
+                    CombinatorReceiver::Option => vec![],
+                    CombinatorReceiver::Result => vec![Ident::new(kw::Underscore, def)],
+                };
+                ast::mk::expr_closure(def, closure_params, arg.clone())
+            }
+            // unwrap_or_else($f) -> unwrap_or(($f)())
+            // NOTE: Closure bodies are not inlined into the argument, since they may contain `return` expressions.
+            true => {
+                if receiver == CombinatorReceiver::Result { return Mutations::none(); }
+                ast::mk::expr_call(def, ast::mk::expr_paren(def, arg.clone()), thin_vec![])
+            }
+        };
+
+        let replacement_seg = ast::PathSegment {
+            ident: Ident::new(replacement_name, call.seg.ident.span),
+            id: ast::DUMMY_NODE_ID,
+            args: call.seg.args.clone(),
+        };
+        let swapped_expr = ast::mk::expr_method_call(def, call.receiver.clone(), replacement_seg, thin_vec![replacement_arg]);
+
+        let mutation = Self::Mutation {
+            original_method: method_name,
+            replacement_method: replacement_name,
+        };
+
+        Mutations::new_one(mutation, smallvec![
+            SubstDef::new(
+                SubstLoc::Replace(expr.id),
+                Subst::AstExpr(swapped_expr.into_inner()),
+            ),
+        ])
+    }
+}
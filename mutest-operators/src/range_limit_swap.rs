@@ -1,7 +1,7 @@
 use mutest_emit::{Mutation, Operator};
 use mutest_emit::analysis::ty;
 use mutest_emit::codegen::ast;
-use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, Mutations, Subst, SubstDef, SubstLoc};
+use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, MutationPriority, Mutations, Subst, SubstDef, SubstLoc};
 use mutest_emit::smallvec::smallvec;
 
 pub const RANGE_LIMIT_SWAP: &str = "range_limit_swap";
@@ -13,6 +13,8 @@ pub struct RangeLimitSwapMutation {
 impl Mutation for RangeLimitSwapMutation {
     fn op_name(&self) -> &str { RANGE_LIMIT_SWAP }
 
+    fn priority(&self) -> MutationPriority { MutationPriority::Medium }
+
     fn display_name(&self) -> String {
         format!("{operation} range expression",
             operation = match self.replacement_limits {
@@ -30,7 +32,7 @@ impl<'a> Operator<'a> for RangeLimitSwap {
     type Mutation = RangeLimitSwapMutation;
 
     fn try_apply(&self, mcx: &MutCtxt) -> Mutations<Self::Mutation> {
-        let MutCtxt { opts: _, tcx, crate_res: _, def_res: _, def_site: def, item_hir: f_hir, body_res, location } = *mcx;
+        let MutCtxt { opts: _, tcx, crate_res: _, def_res: _, def_site: def, item_hir: f_hir, body_res, body_facts: _, instantiations: _, location } = *mcx;
 
         let MutLoc::FnBodyExpr(expr, _f) = location else { return Mutations::none(); };
 
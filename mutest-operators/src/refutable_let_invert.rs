@@ -0,0 +1,218 @@
+use mutest_emit::{Mutation, Operator};
+use mutest_emit::analysis::ast_lowering;
+use mutest_emit::analysis::hir;
+use mutest_emit::codegen::ast::{self, P};
+use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, MutationPriority, Mutations, Subst, SubstDef, SubstLoc};
+use mutest_emit::codegen::symbols::{Ident, Symbol};
+use mutest_emit::smallvec::smallvec;
+use mutest_emit::thin_vec::{ThinVec, thin_vec};
+
+use crate::match_arm_body_replace::refers_to_pat_bindings;
+
+/// Construct a value matching the pattern, using `Default::default()` for the bindings and wildcards of the pattern.
+///
+/// Returns `None` if no such value can be constructed, e.g. if the pattern binds by reference, relies on default
+/// binding modes, contains rest or range patterns, or if the type of a binding does not implement `Default`.
+fn pat_default_value(mcx: &MutCtxt, pat: &ast::Pat) -> Option<P<ast::Expr>> {
+    let def = mcx.def_site;
+
+    let typeck = mcx.typeck()?;
+    let pat_hir = mcx.body_res.hir_pat(pat)?;
+    if typeck.pat_adjustments().get(pat_hir.hir_id).is_some_and(|adjustments| !adjustments.is_empty()) { return None; }
+
+    let typed_default = || {
+        let ty = mcx.pat_ty(pat)?;
+        let default = mcx.make_default_expr(ty)?;
+        let ty_ast = mcx.ty_ast(ty)?;
+
+        // { let v: $ty = Default::default(); v }
+        let v = Ident::new(Symbol::intern("v"), def);
+        Some(ast::mk::expr_block(ast::mk::block(def, thin_vec![
+            ast::mk::stmt_let(def, false, v, Some(ty_ast), default),
+            ast::mk::stmt_expr(ast::mk::expr_ident(def, v)),
+        ])))
+    };
+
+    match &pat.kind {
+        ast::PatKind::Ident(binding_mode, ident, sub_pat) => {
+            match mcx.def_res.node_res(pat.id) {
+                Some(hir::Res::Local(_)) => {
+                    if let ast::BindingMode(ast::ByRef::Yes(_), _) = binding_mode { return None; }
+                    match sub_pat {
+                        Some(sub_pat) => pat_default_value(mcx, sub_pat),
+                        None => typed_default(),
+                    }
+                }
+                // Unit structs, unit variants, and constants.
+                _ => Some(ast::mk::expr_ident(pat.span, *ident)),
+            }
+        }
+        ast::PatKind::Wild => typed_default(),
+        ast::PatKind::Lit(expr) => Some(expr.clone()),
+        ast::PatKind::Path(qself, path) => Some(ast::mk::expr(pat.span, ast::ExprKind::Path(qself.clone(), path.clone()))),
+        ast::PatKind::TupleStruct(qself, path, pats) => {
+            let args = pats.iter().map(|pat| pat_default_value(mcx, pat)).collect::<Option<ThinVec<_>>>()?;
+            let callee = ast::mk::expr(pat.span, ast::ExprKind::Path(qself.clone(), path.clone()));
+            Some(ast::mk::expr_call(pat.span, callee, args))
+        }
+        ast::PatKind::Tuple(pats) => {
+            let exprs = pats.iter().map(|pat| pat_default_value(mcx, pat)).collect::<Option<ThinVec<_>>>()?;
+            Some(ast::mk::expr_tuple(pat.span, exprs))
+        }
+        ast::PatKind::Struct(qself, path, field_pats, rest) => {
+            if !matches!(rest, ast::PatFieldsRest::None) { return None; }
+
+            let fields = field_pats.iter()
+                .map(|field_pat| Some(ast::mk::expr_struct_field(field_pat.span, field_pat.ident, pat_default_value(mcx, &field_pat.pat)?)))
+                .collect::<Option<ThinVec<_>>>()?;
+
+            Some(ast::mk::expr(pat.span, ast::ExprKind::Struct(P(ast::StructExpr {
+                qself: qself.clone(),
+                path: path.clone(),
+                fields,
+                rest: ast::StructRest::None,
+            }))))
+        }
+        ast::PatKind::Paren(pat) => pat_default_value(mcx, pat),
+        ast::PatKind::Or(pats) => pat_default_value(mcx, pats.first()?),
+        _ => None,
+    }
+}
+
+/// Replace the bindings of the pattern with wildcards, so that it can be matched without moving out of the scrutinee.
+struct PatBindingEraser<'op> {
+    def_res: &'op ast_lowering::DefResolutions,
+}
+
+impl<'op> ast::mut_visit::MutVisitor for PatBindingEraser<'op> {
+    fn visit_pat(&mut self, pat: &mut P<ast::Pat>) {
+        ast::mut_visit::noop_visit_pat(pat, self);
+
+        if let ast::PatKind::Ident(_, _, sub_pat) = &mut pat.kind
+            && let Some(hir::Res::Local(_)) = self.def_res.node_res(pat.id)
+        {
+            pat.kind = match sub_pat.take() {
+                Some(sub_pat) => sub_pat.into_inner().kind,
+                None => ast::PatKind::Wild,
+            };
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RefutableLetInvertKind {
+    /// Swap the then and else blocks of an `if let` expression.
+    IfLetBranchSwap,
+    /// Bind a default value matching the pattern of a `let .. else` statement, instead of diverging.
+    LetElseDefault,
+}
+
+pub const REFUTABLE_LET_INVERT: &str = "refutable_let_invert";
+
+pub struct RefutableLetInvertMutation {
+    pub kind: RefutableLetInvertKind,
+    pub pat: String,
+}
+
+impl Mutation for RefutableLetInvertMutation {
+    fn op_name(&self) -> &str { REFUTABLE_LET_INVERT }
+
+    fn priority(&self) -> MutationPriority { MutationPriority::Medium }
+
+    fn display_name(&self) -> String {
+        match self.kind {
+            RefutableLetInvertKind::IfLetBranchSwap => format!("swap then and else blocks of `if let {pat}`", pat = self.pat),
+            RefutableLetInvertKind::LetElseDefault => format!("bind default value to `let {pat}` instead of diverging", pat = self.pat),
+        }
+    }
+
+    fn span_label(&self) -> String {
+        match self.kind {
+            RefutableLetInvertKind::IfLetBranchSwap => "swap then and else blocks".to_owned(),
+            RefutableLetInvertKind::LetElseDefault => "bind default value instead of diverging".to_owned(),
+        }
+    }
+}
+
+/// Invert the outcome of refutable patterns in `if let` expressions and `let .. else` statements.
+///
+/// The then and else blocks of `if let` expressions are swapped, if the then block does not use the bindings of the
+/// pattern. `let .. else` statements bind a default value matching the pattern whenever the pattern does not match,
+/// instead of diverging, if such a value can be constructed.
+pub struct RefutableLetInvert;
+
+impl<'a> Operator<'a> for RefutableLetInvert {
+    type Mutation = RefutableLetInvertMutation;
+
+    fn try_apply(&self, mcx: &MutCtxt) -> Mutations<Self::Mutation> {
+        let MutCtxt { opts: _, tcx, crate_res: _, def_res, def_site: def, item_hir: _, body_res, body_facts: _, instantiations: _, location } = *mcx;
+
+        match location {
+            MutLoc::FnBodyExpr(expr, _f) => {
+                let ast::ExprKind::If(cond, then, Some(els)) = &expr.kind else { return Mutations::none(); };
+                let ast::ExprKind::Let(pat, _, _, _) = &cond.kind else { return Mutations::none(); };
+                // `else if` chains are not swapped.
+                let ast::ExprKind::Block(els_block, None) = &els.kind else { return Mutations::none(); };
+
+                if expr.span.from_expansion() { return Mutations::none(); }
+
+                let Some(expr_hir) = body_res.hir_expr(expr) else { unreachable!() };
+                let hir::ExprKind::If(cond_hir, then_hir, Some(_)) = expr_hir.kind else { return Mutations::none(); };
+                let hir::ExprKind::Let(let_expr_hir) = cond_hir.kind else { return Mutations::none(); };
+                // The then block is moved out of the scope of the bindings of the pattern.
+                if refers_to_pat_bindings(tcx, let_expr_hir.pat, then_hir) { return Mutations::none(); }
+
+                let swapped_expr = ast::mk::expr(expr.span, ast::ExprKind::If(cond.clone(), els_block.clone(), Some(ast::mk::expr_block(then.clone()))));
+
+                let mutation = Self::Mutation {
+                    kind: RefutableLetInvertKind::IfLetBranchSwap,
+                    pat: ast::print::pat_to_string(pat),
+                };
+
+                Mutations::new_one(mutation, smallvec![
+                    SubstDef::new(
+                        SubstLoc::Replace(expr.id),
+                        Subst::AstExpr(swapped_expr.into_inner()),
+                    ),
+                ])
+            }
+
+            MutLoc::FnBodyStmt(stmt, _f) => {
+                let ast::StmtKind::Let(local) = &stmt.kind else { return Mutations::none(); };
+                let ast::LocalKind::InitElse(init, _) = &local.kind else { return Mutations::none(); };
+
+                if stmt.span.from_expansion() { return Mutations::none(); }
+
+                let Some(default_value) = pat_default_value(mcx, &local.pat) else { return Mutations::none(); };
+
+                // The scrutinee is matched against the pattern with its bindings erased, so that it is not moved
+                // out of before being bound by the original pattern.
+                // match $init { v @ $pat_without_bindings => v, _ => $default_value }
+                let mut pat_without_bindings = local.pat.clone();
+                ast::mut_visit::MutVisitor::visit_pat(&mut PatBindingEraser { def_res }, &mut pat_without_bindings);
+
+                let v = Ident::new(Symbol::intern("v"), def);
+                // NOTE: The match expression is parenthesized, as the initializer of `let .. else` statements must not
+                //       end with a closing brace.
+                let defaulted_init = ast::mk::expr_paren(def, ast::mk::expr_match(def, init.clone(), thin_vec![
+                    ast::mk::arm(def, ast::mk::pat(def, ast::PatKind::Ident(ast::BindingMode::NONE, v, Some(pat_without_bindings))), None, Some(ast::mk::expr_ident(def, v))),
+                    ast::mk::arm(def, ast::mk::pat_wild(def), None, Some(default_value)),
+                ]));
+
+                let mutation = Self::Mutation {
+                    kind: RefutableLetInvertKind::LetElseDefault,
+                    pat: ast::print::pat_to_string(&local.pat),
+                };
+
+                Mutations::new_one(mutation, smallvec![
+                    SubstDef::new(
+                        SubstLoc::Replace(init.id),
+                        Subst::AstExpr(defaulted_init.into_inner()),
+                    ),
+                ])
+            }
+
+            _ => Mutations::none(),
+        }
+    }
+}
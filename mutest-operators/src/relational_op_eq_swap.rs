@@ -1,6 +1,6 @@
 use mutest_emit::{Mutation, Operator};
 use mutest_emit::codegen::ast;
-use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, Mutations, Subst, SubstDef, SubstLoc};
+use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, MutationPriority, Mutations, Subst, SubstDef, SubstLoc};
 use mutest_emit::smallvec::smallvec;
 
 pub const RELATIONAL_OP_EQ_SWAP: &str = "relational_op_eq_swap";
@@ -13,6 +13,8 @@ pub struct RelationalOpEqSwapMutation {
 impl Mutation for RelationalOpEqSwapMutation {
     fn op_name(&self) -> &str { RELATIONAL_OP_EQ_SWAP }
 
+    fn priority(&self) -> MutationPriority { MutationPriority::High }
+
     fn display_name(&self) -> String {
         format!("{operation} relational operator `{original_bin_op}`",
             operation = match self.replacement_bin_op {
@@ -42,7 +44,7 @@ impl<'a> Operator<'a> for RelationalOpEqSwap {
     type Mutation = RelationalOpEqSwapMutation;
 
     fn try_apply(&self, mcx: &MutCtxt) -> Mutations<Self::Mutation> {
-        let MutCtxt { opts: _, tcx: _, crate_res: _, def_res: _, def_site: def, item_hir: _, body_res: _, location } = *mcx;
+        let MutCtxt { opts: _, tcx: _, crate_res: _, def_res: _, def_site: def, item_hir: _, body_res: _, body_facts: _, instantiations: _, location } = *mcx;
 
         let MutLoc::FnBodyExpr(expr, _) = location else { return Mutations::none(); };
 
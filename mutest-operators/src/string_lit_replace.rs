@@ -0,0 +1,91 @@
+use mutest_emit::{Mutation, Operator};
+use mutest_emit::analysis::hir;
+use mutest_emit::codegen::ast;
+use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, MutationPriority, Mutations, Subst, SubstDef, SubstLoc};
+use mutest_emit::smallvec::{SmallVec, smallvec};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StringLitReplacementKind {
+    /// Replace the string literal with the empty string.
+    Empty,
+    /// Replace the string literal with its characters in reverse order.
+    Scrambled,
+}
+
+pub const STRING_LIT_REPLACE: &str = "string_lit_replace";
+
+pub struct StringLitReplaceMutation {
+    pub kind: StringLitReplacementKind,
+    pub replacement: String,
+}
+
+impl Mutation for StringLitReplaceMutation {
+    fn op_name(&self) -> &str { STRING_LIT_REPLACE }
+
+    fn priority(&self) -> MutationPriority { MutationPriority::Low }
+
+    fn display_name(&self) -> String {
+        match self.kind {
+            StringLitReplacementKind::Empty => "replace string literal with empty string".to_owned(),
+            StringLitReplacementKind::Scrambled => format!("replace string literal with scrambled string {replacement:?}", replacement = self.replacement),
+        }
+    }
+
+    fn span_label(&self) -> String {
+        match self.kind {
+            StringLitReplacementKind::Empty => "replace with empty string".to_owned(),
+            StringLitReplacementKind::Scrambled => format!("replace with {replacement:?}", replacement = self.replacement),
+        }
+    }
+}
+
+/// Replace string literals with the empty string, and with a scrambled variant of the string.
+///
+/// String literals in patterns, and in macro expansions (including the format strings of formatting macros) are
+/// not mutated.
+pub struct StringLitReplace;
+
+impl<'a> Operator<'a> for StringLitReplace {
+    type Mutation = StringLitReplaceMutation;
+
+    fn try_apply(&self, mcx: &MutCtxt) -> Mutations<Self::Mutation> {
+        let MutCtxt { opts: _, tcx, crate_res: _, def_res: _, def_site: def, item_hir: _, body_res, body_facts: _, instantiations: _, location } = *mcx;
+
+        let MutLoc::FnBodyExpr(expr, _f) = location else { return Mutations::none(); };
+
+        let ast::ExprKind::Lit(token_lit) = &expr.kind else { return Mutations::none(); };
+        let Ok(ast::LitKind::Str(value, _)) = ast::LitKind::from_token_lit(*token_lit) else { return Mutations::none(); };
+
+        if expr.span.from_expansion() { return Mutations::none(); }
+
+        let Some(expr_hir) = body_res.hir_expr(expr) else { unreachable!() };
+        if let hir::Node::Pat(_) = tcx.parent_hir_node(expr_hir.hir_id) { return Mutations::none(); }
+
+        let value = value.as_str();
+        let mut mutations: SmallVec<[_; 1]> = smallvec![];
+
+        if !value.is_empty() {
+            let mutation = Self::Mutation { kind: StringLitReplacementKind::Empty, replacement: String::new() };
+            mutations.push((mutation, smallvec![
+                SubstDef::new(
+                    SubstLoc::Replace(expr.id),
+                    Subst::AstExpr(ast::mk::expr_str(def, "").into_inner()),
+                ),
+            ]));
+        }
+
+        let scrambled = value.chars().rev().collect::<String>();
+        if scrambled != value {
+            let scrambled_expr = ast::mk::expr_str(def, &scrambled.escape_debug().to_string());
+            let mutation = Self::Mutation { kind: StringLitReplacementKind::Scrambled, replacement: scrambled };
+            mutations.push((mutation, smallvec![
+                SubstDef::new(
+                    SubstLoc::Replace(expr.id),
+                    Subst::AstExpr(scrambled_expr.into_inner()),
+                ),
+            ]));
+        }
+
+        Mutations::new(mutations)
+    }
+}
@@ -0,0 +1,171 @@
+use mutest_emit::{Mutation, Operator};
+use mutest_emit::analysis::hir;
+use mutest_emit::analysis::res;
+use mutest_emit::codegen::ast::{self, P};
+use mutest_emit::codegen::mutation::{MutCtxt, MutLoc, MutationPriority, Mutations, Subst, SubstDef, SubstLoc};
+use mutest_emit::codegen::symbols::{Ident, Symbol};
+use mutest_emit::thin_vec::{ThinVec, thin_vec};
+use mutest_emit::smallvec::{SmallVec, smallvec};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StructFieldReplacementKind {
+    /// Replace the value of the field with `Default::default()`.
+    Default { field: Ident },
+    /// Swap the values of two fields of the same type.
+    Swap { field_a: Ident, field_b: Ident },
+}
+
+pub const STRUCT_FIELD_REPLACE: &str = "struct_field_replace";
+
+pub struct StructFieldReplaceMutation {
+    pub struct_path: String,
+    pub kind: StructFieldReplacementKind,
+}
+
+impl Mutation for StructFieldReplaceMutation {
+    fn op_name(&self) -> &str { STRUCT_FIELD_REPLACE }
+
+    fn priority(&self) -> MutationPriority { MutationPriority::Medium }
+
+    fn display_name(&self) -> String {
+        match &self.kind {
+            StructFieldReplacementKind::Default { field } => {
+                format!("replace value of field `{field}` of `{struct_path}` with `Default::default()`",
+                    struct_path = self.struct_path,
+                )
+            }
+            StructFieldReplacementKind::Swap { field_a, field_b } => {
+                format!("swap values of fields `{field_a}` and `{field_b}` of `{struct_path}`",
+                    struct_path = self.struct_path,
+                )
+            }
+        }
+    }
+
+    fn span_label(&self) -> String {
+        match &self.kind {
+            StructFieldReplacementKind::Default { field } => format!("replace value of field `{field}` with `Default::default()`"),
+            StructFieldReplacementKind::Swap { field_a, field_b } => format!("swap values of fields `{field_a}` and `{field_b}`"),
+        }
+    }
+}
+
+/// Replace the values of individual fields in struct literal expressions with `Default::default()`, and swap the
+/// values of fields of the same type, modeling fields being left uninitialized or mixed up.
+///
+/// Fields whose values originate from macro expansions are not mutated.
+pub struct StructFieldReplace;
+
+impl<'a> Operator<'a> for StructFieldReplace {
+    type Mutation = StructFieldReplaceMutation;
+
+    fn try_apply(&self, mcx: &MutCtxt) -> Mutations<Self::Mutation> {
+        let MutCtxt { opts: _, tcx, crate_res: _, def_res: _, def_site: def, item_hir: f_hir, body_res, body_facts: _, instantiations: _, location } = *mcx;
+
+        let MutLoc::FnBodyExpr(expr, _f) = location else { return Mutations::none(); };
+
+        let ast::ExprKind::Struct(struct_expr) = &expr.kind else { return Mutations::none(); };
+        if struct_expr.fields.is_empty() { return Mutations::none(); }
+
+        let Some(typeck) = mcx.typeck() else { return Mutations::none(); };
+        let struct_path = ast::print::path_to_string(&struct_expr.path);
+
+        // Fields are typed by the type their values are coerced to, which is the type of the field.
+        let fields = struct_expr.fields.iter()
+            .filter(|field| field.expr.span.ctxt() == expr.span.ctxt())
+            .filter_map(|field| {
+                let field_expr_hir = body_res.hir_expr(&field.expr)?;
+                Some((field, field_expr_hir, typeck.expr_ty_adjusted(field_expr_hir)))
+            })
+            .collect::<Vec<_>>();
+
+        // The struct literal is replaced as a whole, so that shorthand fields can also be given new values.
+        let replace_fields = |replacements: &[(Ident, P<ast::Expr>)]| {
+            let fields = struct_expr.fields.iter()
+                .map(|field| {
+                    match replacements.iter().find(|(ident, _)| *ident == field.ident) {
+                        Some((_, replacement)) => ast::mk::expr_struct_field(field.span, field.ident, replacement.clone()),
+                        None => field.clone(),
+                    }
+                })
+                .collect::<ThinVec<_>>();
+
+            ast::mk::expr(expr.span, ast::ExprKind::Struct(P(ast::StructExpr {
+                qself: struct_expr.qself.clone(),
+                path: struct_expr.path.clone(),
+                fields,
+                rest: struct_expr.rest.clone(),
+            })))
+        };
+
+        let mut mutations: SmallVec<[_; 1]> = smallvec![];
+
+        for &(field, field_expr_hir, field_ty) in &fields {
+            if field_ty.is_unit() || field_ty.is_never() { continue; }
+
+            // Replacing a value which is already a default value would be an equivalent mutation.
+            if let hir::ExprKind::Call(..) = field_expr_hir.kind
+                && let Some((callee, _)) = res::callee(typeck, field_expr_hir)
+                && callee == res::fns::default(tcx)
+            {
+                continue;
+            }
+
+            // Avoid replacing the value with `Default::default` within the type's `Default::default` implementation,
+            // which would result in infinite recursion.
+            if let Some(impl_def_id) = tcx.impl_of_method(f_hir.owner_id.to_def_id())
+                && tcx.trait_id_of_impl(impl_def_id) == Some(res::traits::Default(tcx))
+                && tcx.type_of(impl_def_id).instantiate_identity() == field_ty
+            {
+                continue;
+            }
+
+            let Some(default) = mcx.make_default_expr(field_ty) else { continue; };
+            let Some(field_ty_ast) = mcx.ty_ast(field_ty) else { continue; };
+
+            // The types of generic fields cannot be inferred from `Default::default()`, so we ascribe the original type
+            // to the default value using a let binding in a block expression.
+            // { let v: $ty = Default::default(); v }
+            let v = Ident::new(Symbol::intern("v"), def);
+            let default_value = ast::mk::expr_block(ast::mk::block(def, thin_vec![
+                ast::mk::stmt_let(def, false, v, Some(field_ty_ast), default),
+                ast::mk::stmt_expr(ast::mk::expr_ident(def, v)),
+            ]));
+
+            let mutation = Self::Mutation {
+                struct_path: struct_path.clone(),
+                kind: StructFieldReplacementKind::Default { field: field.ident },
+            };
+
+            mutations.push((mutation, smallvec![
+                SubstDef::new(
+                    SubstLoc::Replace(expr.id),
+                    Subst::AstExpr(replace_fields(&[(field.ident, default_value)]).into_inner()),
+                ),
+            ]));
+        }
+
+        for (i, &(field_a, _, field_a_ty)) in fields.iter().enumerate() {
+            for &(field_b, _, field_b_ty) in &fields[(i + 1)..] {
+                if field_a_ty != field_b_ty { continue; }
+
+                // Swapping identical values would be an equivalent mutation.
+                if ast::print::expr_to_string(&field_a.expr) == ast::print::expr_to_string(&field_b.expr) { continue; }
+
+                let mutation = Self::Mutation {
+                    struct_path: struct_path.clone(),
+                    kind: StructFieldReplacementKind::Swap { field_a: field_a.ident, field_b: field_b.ident },
+                };
+
+                mutations.push((mutation, smallvec![
+                    SubstDef::new(
+                        SubstLoc::Replace(expr.id),
+                        Subst::AstExpr(replace_fields(&[(field_a.ident, field_b.expr.clone()), (field_b.ident, field_a.expr.clone())]).into_inner()),
+                    ),
+                ]));
+            }
+        }
+
+        Mutations::new(mutations)
+    }
+}
@@ -0,0 +1,210 @@
+//! Baselines of accepted surviving mutations, used to incrementally adopt mutation testing.
+//!
+//! Baselines are stored in `mutest-baseline.toml` files, using the following subset of TOML:
+//!
+//! ```toml
+//! [[mutation]]
+//! id = "9f3c1a0b27d4e865"
+//!
+//! [[mutation]]
+//! file = "src/lib.rs"
+//! line = 42
+//! op = "math_op_add_sub_swap"
+//! ```
+//!
+//! Entries with an `id` match the mutation with the same stable id. Entries without an `id` match any mutation of the
+//! operator `op` on the line `line` of the file `file`, which is more resilient to changes of the surrounding code.
+
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::metadata::MutationMeta;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BaselineEntry {
+    StableId(u64),
+    Location { file: String, line: u32, op_name: String },
+}
+
+impl BaselineEntry {
+    pub fn matches(&self, mutation: &MutationMeta) -> bool {
+        match self {
+            Self::StableId(stable_id) => mutation.stable_id == *stable_id,
+            Self::Location { file, line, op_name } => {
+                mutation.op_names().any(|mutation_op_name| mutation_op_name == op_name)
+                    && split_display_location(mutation.display_location()).is_some_and(|(f, l)| f == file && l == *line)
+            }
+        }
+    }
+}
+
+/// Returns the file and starting line of the display location of a mutation (e.g. `src/lib.rs:42:5: 42:10`).
+pub(crate) fn split_display_location(display_location: &str) -> Option<(&str, u32)> {
+    let (lo, _hi) = display_location.rsplit_once(": ")?;
+    let mut parts = lo.rsplitn(3, ':');
+    let _col = parts.next()?;
+    let line = parts.next()?.parse::<u32>().ok()?;
+    let file = parts.next()?;
+    Some((file, line))
+}
+
+#[derive(Debug, Default)]
+pub struct Baseline {
+    pub entries: Vec<BaselineEntry>,
+}
+
+impl Baseline {
+    pub fn contains(&self, mutation: &MutationMeta) -> bool {
+        self.entries.iter().any(|entry| entry.matches(mutation))
+    }
+
+    pub fn parse(src: &str) -> Result<Self, String> {
+        #[derive(Default)]
+        struct RawEntry {
+            id: Option<String>,
+            file: Option<String>,
+            line: Option<u32>,
+            op: Option<String>,
+        }
+
+        fn finish_entry(raw_entry: RawEntry) -> Result<BaselineEntry, String> {
+            match raw_entry {
+                RawEntry { id: Some(id), .. } => {
+                    let stable_id = u64::from_str_radix(&id, 16).map_err(|_| format!("invalid mutation id `{id}`"))?;
+                    Ok(BaselineEntry::StableId(stable_id))
+                }
+                RawEntry { id: None, file: Some(file), line: Some(line), op: Some(op_name) } => {
+                    Ok(BaselineEntry::Location { file, line, op_name })
+                }
+                _ => Err("mutation must have either an `id`, or a `file`, `line`, and `op`".to_owned()),
+            }
+        }
+
+        let mut entries = vec![];
+        let mut current_entry: Option<RawEntry> = None;
+
+        for (i, line) in src.lines().enumerate() {
+            let line_no = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue; }
+
+            if line == "[[mutation]]" {
+                if let Some(raw_entry) = current_entry.take() {
+                    entries.push(finish_entry(raw_entry).map_err(|e| format!("line {line_no}: {e}"))?);
+                }
+                current_entry = Some(Default::default());
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("line {line_no}: expected `[[mutation]]` or `key = value`"));
+            };
+            let Some(raw_entry) = &mut current_entry else {
+                return Err(format!("line {line_no}: key outside of `[[mutation]]` table"));
+            };
+
+            let value = value.trim();
+            match key.trim() {
+                "id" => raw_entry.id = Some(parse_str(value).ok_or_else(|| format!("line {line_no}: `id` must be a string"))?),
+                "file" => raw_entry.file = Some(parse_str(value).ok_or_else(|| format!("line {line_no}: `file` must be a string"))?),
+                "op" => raw_entry.op = Some(parse_str(value).ok_or_else(|| format!("line {line_no}: `op` must be a string"))?),
+                "line" => raw_entry.line = Some(value.parse::<u32>().map_err(|_| format!("line {line_no}: `line` must be a positive integer"))?),
+                key => return Err(format!("line {line_no}: unknown key `{key}`")),
+            }
+        }
+
+        if let Some(raw_entry) = current_entry.take() {
+            entries.push(finish_entry(raw_entry).map_err(|e| format!("end of file: {e}"))?);
+        }
+
+        Ok(Self { entries })
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let src = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::parse(&src)
+    }
+
+    /// Write a baseline accepting the given mutations, identified by their stable ids.
+    pub fn write(path: &Path, mutations: &[&MutationMeta]) -> io::Result<()> {
+        let mut out = String::new();
+        writeln!(out, "# Surviving mutations accepted by `cargo mutest run --baseline`.").unwrap();
+        writeln!(out, "# Regenerate with `cargo mutest run --update-baseline`.").unwrap();
+
+        for mutation in mutations {
+            writeln!(out).unwrap();
+            writeln!(out, "# [{op_name}] {display_name} at {display_location}",
+                op_name = mutation.op_name(),
+                display_name = mutation.display_name().replace('\n', " "),
+                display_location = mutation.display_location(),
+            ).unwrap();
+            writeln!(out, "[[mutation]]").unwrap();
+            writeln!(out, "id = \"{}\"", mutation.stable_id_str()).unwrap();
+        }
+
+        fs::write(path, out)
+    }
+}
+
+fn parse_str(value: &str) -> Option<String> {
+    let inner = value.strip_prefix('"')?.strip_suffix('"')?;
+
+    let mut str = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                '\\' => str.push('\\'),
+                '"' => str.push('"'),
+                _ => return None,
+            },
+            '"' => return None,
+            c => str.push(c),
+        }
+    }
+
+    Some(str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Baseline, BaselineEntry, split_display_location};
+
+    #[test]
+    fn test_parse_entries() {
+        let baseline = Baseline::parse(r#"
+            # comment
+            [[mutation]]
+            id = "00000000000000ff"
+
+            [[mutation]]
+            file = "src/dir \"a\"/lib.rs"
+            line = 42
+            op = "eq_op_invert"
+        "#).unwrap();
+
+        assert_eq!(baseline.entries, vec![
+            BaselineEntry::StableId(0xff),
+            BaselineEntry::Location { file: "src/dir \"a\"/lib.rs".to_owned(), line: 42, op_name: "eq_op_invert".to_owned() },
+        ]);
+    }
+
+    #[test]
+    fn test_parse_incomplete_entry() {
+        assert!(Baseline::parse("[[mutation]]\nfile = \"src/lib.rs\"\nline = 1\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_key_outside_table() {
+        assert!(Baseline::parse("id = \"ff\"\n").is_err());
+    }
+
+    #[test]
+    fn test_split_display_location() {
+        assert_eq!(split_display_location("src/lib.rs:42:5: 42:10"), Some(("src/lib.rs", 42)));
+        assert_eq!(split_display_location("C:\\src\\lib.rs:7:1: 9:2"), Some(("C:\\src\\lib.rs", 7)));
+        assert_eq!(split_display_location("<unknown>"), None);
+    }
+}
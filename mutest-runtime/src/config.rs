@@ -1,5 +1,11 @@
+use std::path::PathBuf;
 use std::time::Duration;
 
+use crate::baseline::Baseline;
+use crate::mutant_selection::MutantSelection;
+use crate::test_filter::TestFilter;
+use crate::test_runner::ChildProcessLimits;
+
 pub enum Mode {
     Evaluate,
     Flakes { iterations_count: usize },
@@ -8,6 +14,8 @@ pub enum Mode {
 pub struct PrintOptions {
     pub detection_matrix: Option<()>,
     pub subsumption_matrix: Option<()>,
+    pub target_scores: Option<()>,
+    pub file_scores: Option<()>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -23,13 +31,98 @@ pub enum TestTimeout {
     Explicit(Duration),
 }
 
+/// Format of the diagnostics of undetected mutations.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Compiler-style diagnostics, with annotated source code snippets.
+    Human,
+    /// GitHub Actions workflow commands, which annotate the mutated lines in the diffs of pull requests.
+    Github,
+}
+
+/// Mutation results which fail the run, i.e. make the process exit with an error code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FailOn {
+    /// Fail if any mutation is undetected.
+    Undetected,
+    /// Fail if any mutation is undetected, or is only detected by timing out.
+    TimedOut,
+    /// Never fail because of the mutation results.
+    Never,
+}
+
+/// Action taken when the tests of a mutant leak resources, see `leak_check`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LeakPolicy {
+    /// Evaluate all subsequent mutants in isolated child processes.
+    Isolate,
+    /// Report the leak, and the subsequent mutations whose results may be affected by it.
+    Report,
+}
+
 pub struct Options {
     pub mode: Mode,
     pub verbosity: u8,
     pub report_timings: bool,
     pub print_opts: PrintOptions,
+    /// Filter of the tests which are profiled, and against which mutations are evaluated.
+    pub test_filter: TestFilter,
+    /// Subset of the mutants and mutations which are evaluated.
+    pub mutant_selection: MutantSelection,
+    pub output_format: OutputFormat,
     pub exhaustive: bool,
+    /// Show the live status of the evaluation in a terminal UI, and explore the results interactively once finished.
+    /// Only used in `Mode::Evaluate`.
+    pub tui: bool,
+    /// Maximum number of tests to evaluate for each mutation, after which the mutation is no longer evaluated, even if it
+    /// has not been detected.
+    pub max_tests_per_mutation: Option<usize>,
     pub test_ordering: TestOrdering,
     pub test_timeout: TestTimeout,
+    /// Slack of automatically deduced test timeouts over the profiled execution time of each test, relative to the
+    /// execution time. The slack is at least one second.
+    pub test_timeout_slack: f64,
     pub use_thread_pool: bool,
+    pub child_process_limits: ChildProcessLimits,
+    /// Check for resources leaked by the tests of each mutant before evaluating the next mutant.
+    pub leak_policy: Option<LeakPolicy>,
+    /// Number of cases generated by property tests, which are run without shrinking where supported. Defaults to
+    /// `property_tests::DEFAULT_PROPERTY_TEST_CASES` if the crate has any property tests.
+    pub property_test_cases: Option<usize>,
+    pub bench_slowdown_threshold: Option<f64>,
+    /// Number of times a detecting test is re-run against the unmutated program, to check if the test is flaky.
+    /// Only used in `Mode::Evaluate`.
+    pub flaky_retries_count: Option<usize>,
+    /// Factor over its profiled execution time by which a test must be slowed down by an undetected mutation for the
+    /// mutation to be reported as possibly only affecting performance. Only used in `Mode::Evaluate`.
+    pub perf_regression_factor: Option<f64>,
+    pub fail_on: FailOn,
+    /// Minimum mutation score, as a percentage, below which the run fails. Only used in `Mode::Evaluate`.
+    pub fail_under: Option<f64>,
+    /// Accepted surviving mutations, which do not fail the run. Only used in `Mode::Evaluate`.
+    pub baseline: Option<Baseline>,
+    /// Path to write the surviving mutations of the run to, as the new baseline. Only used in `Mode::Evaluate`.
+    pub update_baseline_path: Option<PathBuf>,
+    /// Path of the history file to append a summary of the run to. Only used in `Mode::Evaluate`.
+    pub history_path: Option<PathBuf>,
+    /// Path to write the undetected mutations of the run to, as a SARIF log. Only used in `Mode::Evaluate`.
+    pub sarif_path: Option<PathBuf>,
+    /// Path to save the results of the run to, for regenerating reports with `cargo mutest report`. Only used in
+    /// `Mode::Evaluate`.
+    pub results_path: Option<PathBuf>,
+    /// Path to write the execution times of the profiled tests to, for estimating the cost of mutants in subsequent
+    /// runs.
+    pub test_timings_path: Option<PathBuf>,
+    /// Number of previous runs in which a test must have consistently produced the same result against a mutation
+    /// for the test to be skipped, with its known result, when evaluating the mutation again (see `verdict_cache`).
+    pub flakes_min_confidence: Option<usize>,
+    /// Path of the verdict cache to resume from and to write the results of test runs to, so that they can be skipped
+    /// in subsequent runs. Only used with `flakes_min_confidence`.
+    pub verdict_cache_path: Option<PathBuf>,
+    /// Path to write the flakiness matrix to, as JSON, or as CSV if the path has a `.csv` extension. Only used in
+    /// `Mode::Flakes`.
+    pub flakiness_report_path: Option<PathBuf>,
+    /// Fail the run if any test produced different results against a mutation across iterations. Only used in
+    /// `Mode::Flakes`.
+    pub fail_on_flaky_tests: bool,
 }
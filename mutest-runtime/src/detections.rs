@@ -1,7 +1,9 @@
+use std::fmt::Write;
 use std::iter;
 
 use crate::data_structures::TestArray;
 use crate::harness::{MutationTestResult, MutationTestResults};
+use crate::log;
 use crate::test_runner;
 
 pub struct MutationDetectionMatrix {
@@ -48,69 +50,128 @@ impl MutationDetectionMatrix {
     }
 }
 
+/// Symbol of the test result in the mutation detection matrix, colored by the verdict.
+/// Tests which were not run against the mutation have no result.
+fn mutation_test_result_symbol(mutation_test_result: Option<MutationTestResult>) -> &'static str {
+    match mutation_test_result {
+        None => "\x1b[2m.\x1b[0m",
+        Some(MutationTestResult::Undetected) => "\x1b[1;31m-\x1b[0m",
+        Some(MutationTestResult::Detected) => "\x1b[32mD\x1b[0m",
+        Some(MutationTestResult::Crashed) => "\x1b[35mC\x1b[0m",
+        Some(MutationTestResult::TimedOut) => "\x1b[33mT\x1b[0m",
+        Some(MutationTestResult::FlakyDetected) => "\x1b[36mF\x1b[0m",
+    }
+}
+
+/// Split the test name into the path of its module, and its name within the module.
+fn split_test_module(test_name: &str) -> (&str, &str) {
+    match test_name.rsplit_once("::") {
+        Some((module_path, name)) => (module_path, name),
+        None => ("", test_name),
+    }
+}
+
 pub fn print_mutation_detection_matrix(mutation_detection_matrix: &MutationDetectionMatrix, tests: &[test_runner::Test], warn_non_exhaustive: bool) {
-    // Tests are printed in name order.
+    // Tests are printed in name order, grouped by their module. Tests of the crate root are printed first.
     let mut test_names = tests.iter().map(|test| test.desc.name.clone()).collect::<Vec<_>>();
-    test_names.sort_unstable_by(|test_name_a, test_name_b| Ord::cmp(test_name_a.as_slice(), test_name_b.as_slice()));
+    test_names.sort_unstable_by(|test_name_a, test_name_b| {
+        let (module_path_a, name_a) = split_test_module(test_name_a.as_slice());
+        let (module_path_b, name_b) = split_test_module(test_name_b.as_slice());
+        Ord::cmp(&(module_path_a, name_a), &(module_path_b, name_b))
+    });
+
+    // Tests within modules are indented under the heading of their module.
+    let test_label_w = test_names.iter()
+        .map(|test_name| {
+            let (module_path, name) = split_test_module(test_name.as_slice());
+            let indent_w = if module_path.is_empty() { 0 } else { 2 };
+            indent_w + "test ".len() + name.len()
+        })
+        .max().unwrap_or(0)
+        .max("total".len());
+    let w = test_label_w + 1;
 
-    let test_name_w = test_names.iter().map(|test_name| test_name.as_slice().len()).max().unwrap_or(0);
+    let mut line = String::new();
 
     // Print mutation ID numbers in 10's for matrix heading, like so `1        10        20...`.
-    print!("{:w$}", "", w = test_name_w + "test ".len() + 1);
+    write!(line, "{:w$}", "").unwrap();
     for mutation_idx in mutation_detection_matrix.iter_mutation_ids() {
         if mutation_idx == 1 {
-            print!("{mutation_idx:<9}");
+            write!(line, "{mutation_idx:<9}").unwrap();
         } else if mutation_idx % 10 == 0 {
-            print!("{mutation_idx:<10}");
+            write!(line, "{mutation_idx:<10}").unwrap();
         }
     }
-    println!();
+    log::info!("{line}");
+    line.clear();
 
     // Print mutation ID numbers' last digits for matrix heading, like so `12345678901234567890123...`.
-    print!("{:w$}", "", w = test_name_w + "test ".len() + 1);
+    write!(line, "{:w$}", "").unwrap();
     let mut mutation_id_chunks = mutation_detection_matrix.iter_mutation_ids().array_chunks::<10>();
     while let Some(_) = mutation_id_chunks.next() {
-        print!("1234567890");
+        line.push_str("1234567890");
     }
     if let Some(last_mutation_id_chunk) = mutation_id_chunks.into_remainder() {
-        print!("{}", &"1234567890"[..last_mutation_id_chunk.count()]);
+        line.push_str(&"1234567890"[..last_mutation_id_chunk.count()]);
     }
-    println!();
+    log::info!("{line}");
+    line.clear();
 
     // Print matrix row for overall mutation detection.
-    print!("{:w$}", "total", w = test_name_w + "test ".len() + 1);
+    write!(line, "{:w$}", "total").unwrap();
     for (_mutation_id, mutation_test_result) in mutation_detection_matrix.iter_detections() {
-        match mutation_test_result {
-            MutationTestResult::Undetected => print!("-"),
-            MutationTestResult::Detected => print!("D"),
-            MutationTestResult::Crashed => print!("C"),
-            MutationTestResult::TimedOut => print!("T"),
-        }
+        line.push_str(mutation_test_result_symbol(Some(mutation_test_result)));
     }
-    println!();
-
-    // Print one matrix row for each test for test-mutation detections.
-    for test_name in test_names {
-        print!("test {:test_name_w$} ", test_name.as_slice());
-        for (_mutation_id, mutation_test_result) in mutation_detection_matrix.iter_test_detections(&test_name) {
-            match mutation_test_result {
-                None => print!("."),
-                Some(MutationTestResult::Undetected) => print!("-"),
-                Some(MutationTestResult::Detected) => print!("D"),
-                Some(MutationTestResult::Crashed) => print!("C"),
-                Some(MutationTestResult::TimedOut) => print!("T"),
-            }
+    log::info!("{line}");
+    line.clear();
+
+    // Print one matrix row for each test for test-mutation detections, with a heading for each module.
+    let mut current_module_path = "";
+    for test_name in &test_names {
+        let (module_path, name) = split_test_module(test_name.as_slice());
+        if module_path != current_module_path {
+            log::info!("\x1b[1m{module_path}\x1b[0m");
+            current_module_path = module_path;
         }
-        println!();
+
+        let indent = if module_path.is_empty() { "" } else { "  " };
+        write!(line, "{:w$}", format!("{indent}test {name}")).unwrap();
+        for (_mutation_id, mutation_test_result) in mutation_detection_matrix.iter_test_detections(test_name) {
+            line.push_str(mutation_test_result_symbol(mutation_test_result));
+        }
+        log::info!("{line}");
+        line.clear();
     }
-    println!();
+    log::info!("");
 
     // Print legend of symbols used in the matrix.
-    println!("legend: .: not ran; -: undetected; D: detected; C: crashed; T: timed out");
-    println!();
+    log::info!("legend: {not_ran}: not ran; {undetected}: undetected; {detected}: detected; {crashed}: crashed; {timed_out}: timed out; {flaky}: flaky detection (discarded)",
+        not_ran = mutation_test_result_symbol(None),
+        undetected = mutation_test_result_symbol(Some(MutationTestResult::Undetected)),
+        detected = mutation_test_result_symbol(Some(MutationTestResult::Detected)),
+        crashed = mutation_test_result_symbol(Some(MutationTestResult::Crashed)),
+        timed_out = mutation_test_result_symbol(Some(MutationTestResult::TimedOut)),
+        flaky = mutation_test_result_symbol(Some(MutationTestResult::FlakyDetected)),
+    );
+    log::info!("");
 
     if warn_non_exhaustive {
-        println!("warning: mutation detection matrix is incomplete as not all tests were evaluated, rerun with `--exhaustive`");
-        println!();
+        log::warn!("mutation detection matrix is incomplete as not all tests were evaluated, rerun with `--exhaustive`");
+        log::info!("");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_test_module;
+
+    #[test]
+    fn test_split_test_in_module() {
+        assert_eq!(split_test_module("parser::tests::test_empty"), ("parser::tests", "test_empty"));
+    }
+
+    #[test]
+    fn test_split_test_in_crate_root() {
+        assert_eq!(split_test_module("test_empty"), ("", "test_empty"));
     }
 }
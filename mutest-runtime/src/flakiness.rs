@@ -1,7 +1,14 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
 
 use crate::detections::MutationDetectionMatrix;
 use crate::harness::MutationTestResult;
+use crate::log;
+use crate::metadata::MutationMeta;
+use crate::sarif::escape_json_str;
 use crate::test_runner;
 
 pub fn compute_flakiness<I>(mutation_test_results: I) -> bool
@@ -69,6 +76,113 @@ impl MutationFlakinessMatrix {
             (mutation_id, mutation_test_flakiness)
         })
     }
+
+    /// Names of the tests which are flaky against any of the mutations, in name order.
+    pub fn flaky_test_names(&self) -> BTreeSet<&str> {
+        self.inner.iter()
+            .flat_map(|mutation_flakiness| mutation_flakiness.flakiness_per_test.iter())
+            .filter(|(_, mutation_test_flakiness)| matches!(mutation_test_flakiness, Some(true)))
+            .map(|(test_name, _)| test_name.as_slice())
+            .collect()
+    }
+
+    /// Names of the tests which were run against the mutation, split into flaky and not flaky tests, in name order.
+    fn ran_test_names(&self, mutation_idx: usize) -> (BTreeSet<&str>, BTreeSet<&str>) {
+        let mut flaky_test_names = BTreeSet::new();
+        let mut not_flaky_test_names = BTreeSet::new();
+        for (test_name, mutation_test_flakiness) in &self.inner[mutation_idx].flakiness_per_test {
+            match mutation_test_flakiness {
+                None => {}
+                Some(false) => { not_flaky_test_names.insert(test_name.as_slice()); }
+                Some(true) => { flaky_test_names.insert(test_name.as_slice()); }
+            }
+        }
+        (flaky_test_names, not_flaky_test_names)
+    }
+}
+
+fn mutation_by_id<'m>(mutations: &[&'m MutationMeta], mutation_id: u32) -> &'m MutationMeta {
+    mutations.iter().find(|mutation| mutation.id == mutation_id).expect("mutation of flakiness matrix not found")
+}
+
+fn json_str_list<'a>(strs: impl IntoIterator<Item = &'a str>) -> String {
+    let strs = strs.into_iter().map(escape_json_str).collect::<Vec<_>>();
+    format!("[{}]", strs.join(", "))
+}
+
+/// Serialize the flakiness matrix as JSON, with the overall and per-test flakiness of each mutation.
+pub fn to_json(mutation_flakiness_matrix: &MutationFlakinessMatrix, mutations: &[&MutationMeta], iterations_count: usize) -> String {
+    let mut out = String::new();
+    writeln!(out, "{{").unwrap();
+    writeln!(out, "  \"iterations_count\": {iterations_count},").unwrap();
+    writeln!(out, "  \"flaky_tests\": {},", json_str_list(mutation_flakiness_matrix.flaky_test_names())).unwrap();
+    writeln!(out, "  \"mutations\": [").unwrap();
+    for (mutation_id, detection_flakiness) in mutation_flakiness_matrix.iter_detection_flakes() {
+        let mutation = mutation_by_id(mutations, mutation_id);
+        let (flaky_test_names, not_flaky_test_names) = mutation_flakiness_matrix.ran_test_names(mutation_id as usize - 1);
+
+        writeln!(out, "    {{").unwrap();
+        writeln!(out, "      \"id\": {mutation_id},").unwrap();
+        writeln!(out, "      \"stable_id\": {},", escape_json_str(&mutation.stable_id_str())).unwrap();
+        writeln!(out, "      \"op_name\": {},", escape_json_str(mutation.op_name())).unwrap();
+        writeln!(out, "      \"display_name\": {},", escape_json_str(mutation.display_name())).unwrap();
+        writeln!(out, "      \"display_location\": {},", escape_json_str(mutation.display_location())).unwrap();
+        writeln!(out, "      \"flaky\": {detection_flakiness},").unwrap();
+        writeln!(out, "      \"flaky_tests\": {},", json_str_list(flaky_test_names)).unwrap();
+        writeln!(out, "      \"not_flaky_tests\": {}", json_str_list(not_flaky_test_names)).unwrap();
+        writeln!(out, "    }}{}", if (mutation_id as usize) < mutation_flakiness_matrix.inner.len() { "," } else { "" }).unwrap();
+    }
+    writeln!(out, "  ]").unwrap();
+    writeln!(out, "}}").unwrap();
+
+    out
+}
+
+pub(crate) fn escape_csv_field(field: &str) -> String {
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_owned(),
+    }
+}
+
+/// Serialize the flakiness matrix as CSV, with one row for each test run against each mutation. Rows with an empty
+/// test name hold the overall flakiness of the mutation.
+pub fn to_csv(mutation_flakiness_matrix: &MutationFlakinessMatrix, mutations: &[&MutationMeta]) -> String {
+    let mut out = String::new();
+    writeln!(out, "mutation_id,stable_id,op_name,display_location,test,flaky").unwrap();
+    for (mutation_id, detection_flakiness) in mutation_flakiness_matrix.iter_detection_flakes() {
+        let mutation = mutation_by_id(mutations, mutation_id);
+        let (flaky_test_names, not_flaky_test_names) = mutation_flakiness_matrix.ran_test_names(mutation_id as usize - 1);
+
+        let mut write_row = |test_name: &str, flaky: bool| {
+            writeln!(out, "{mutation_id},{stable_id},{op_name},{display_location},{test_name},{flaky}",
+                stable_id = mutation.stable_id_str(),
+                op_name = escape_csv_field(mutation.op_name()),
+                display_location = escape_csv_field(mutation.display_location()),
+                test_name = escape_csv_field(test_name),
+            ).unwrap();
+        };
+
+        write_row("", detection_flakiness);
+        let mut test_names = flaky_test_names.into_iter().map(|test_name| (test_name, true))
+            .chain(not_flaky_test_names.into_iter().map(|test_name| (test_name, false)))
+            .collect::<Vec<_>>();
+        test_names.sort_unstable();
+        for (test_name, flaky) in test_names {
+            write_row(test_name, flaky);
+        }
+    }
+
+    out
+}
+
+/// Write the flakiness matrix to the path, as CSV if the path has a `.csv` extension, and as JSON otherwise.
+pub fn write_report(path: &Path, mutation_flakiness_matrix: &MutationFlakinessMatrix, mutations: &[&MutationMeta], iterations_count: usize) -> io::Result<()> {
+    let report = match path.extension().is_some_and(|extension| extension == "csv") {
+        true => to_csv(mutation_flakiness_matrix, mutations),
+        false => to_json(mutation_flakiness_matrix, mutations, iterations_count),
+    };
+    fs::write(path, report)
 }
 
 pub fn print_mutation_flakiness_matrix(mutation_flakiness_matrix: &MutationFlakinessMatrix, tests: &[test_runner::Test]) {
@@ -78,55 +192,61 @@ pub fn print_mutation_flakiness_matrix(mutation_flakiness_matrix: &MutationFlaki
 
     let test_name_w = test_names.iter().map(|test_name| test_name.as_slice().len()).max().unwrap_or(0);
 
+    let mut line = String::new();
+
     // Print mutation ID numbers in 10's for matrix heading, like so `1        10        20...`.
-    print!("{:w$}", "", w = test_name_w + "test ".len() + 1);
+    write!(line, "{:w$}", "", w = test_name_w + "test ".len() + 1).unwrap();
     for mutation_idx in mutation_flakiness_matrix.iter_mutation_ids() {
         if mutation_idx == 1 {
-            print!("{mutation_idx:<9}");
+            write!(line, "{mutation_idx:<9}").unwrap();
         } else if mutation_idx % 10 == 0 {
-            print!("{mutation_idx:<10}");
+            write!(line, "{mutation_idx:<10}").unwrap();
         }
     }
-    println!();
+    log::info!("{line}");
+    line.clear();
 
     // Print mutation ID numbers' last digits for matrix heading, like so `12345678901234567890123...`.
-    print!("{:w$}", "", w = test_name_w + "test ".len() + 1);
+    write!(line, "{:w$}", "", w = test_name_w + "test ".len() + 1).unwrap();
     let mut mutation_id_chunks = mutation_flakiness_matrix.iter_mutation_ids().array_chunks::<10>();
     while let Some(_) = mutation_id_chunks.next() {
-        print!("1234567890");
+        line.push_str("1234567890");
     }
     if let Some(last_mutation_id_chunk) = mutation_id_chunks.into_remainder() {
-        print!("{}", &"1234567890"[..last_mutation_id_chunk.count()]);
+        line.push_str(&"1234567890"[..last_mutation_id_chunk.count()]);
     }
-    println!();
+    log::info!("{line}");
+    line.clear();
 
     // Print matrix row for overall mutation flakiness.
-    print!("{:w$}", "total", w = test_name_w + "test ".len() + 1);
+    write!(line, "{:w$}", "total", w = test_name_w + "test ".len() + 1).unwrap();
     for (_mutation_id, detection_flakiness) in mutation_flakiness_matrix.iter_detection_flakes() {
         match detection_flakiness {
-            false => print!("-"),
-            true => print!("F"),
+            false => line.push('-'),
+            true => line.push('F'),
         }
     }
-    println!();
+    log::info!("{line}");
+    line.clear();
 
     // Print one matrix row for each test for test-mutation flakiness.
     for test_name in test_names {
-        print!("test {:test_name_w$} ", test_name.as_slice());
+        write!(line, "test {:test_name_w$} ", test_name.as_slice()).unwrap();
         for (_mutation_id, mutation_test_flakiness) in mutation_flakiness_matrix.iter_test_flakes(&test_name) {
             match mutation_test_flakiness {
-                None => print!("."),
-                Some(false) => print!("-"),
-                Some(true) => print!("F"),
+                None => line.push('.'),
+                Some(false) => line.push('-'),
+                Some(true) => line.push('F'),
             }
         }
-        println!();
+        log::info!("{line}");
+        line.clear();
     }
-    println!();
+    log::info!("");
 
     // Print legend of symbols used in the matrix.
-    println!("legend: .: not ran; -: not flaky; F: flaky");
-    println!();
+    log::info!("legend: .: not ran; -: not flaky; F: flaky");
+    log::info!("");
 }
 
 pub fn print_mutation_flakiness_epilogue(mutation_flakiness_matrix: &MutationFlakinessMatrix, tests: &[test_runner::Test]) {
@@ -150,7 +270,7 @@ pub fn print_mutation_flakiness_epilogue(mutation_flakiness_matrix: &MutationFla
         }
     }
 
-    println!("flakiness: {score}. {flaky} flaky; {not_flaky} not flaky; {not_ran} not ran; {total} total",
+    log::info!("flakiness: {score}. {flaky} flaky; {not_flaky} not flaky; {not_ran} not ran; {total} total",
         score = match total_test_mutation_pairs_count {
             0 => "none".to_owned(),
             _ => format!("{:.2}%", flaky_test_mutation_pairs_count as f64 / total_test_mutation_pairs_count as f64 * 100_f64),
@@ -164,9 +284,11 @@ pub fn print_mutation_flakiness_epilogue(mutation_flakiness_matrix: &MutationFla
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use crate::harness::MutationTestResult;
 
-    use super::compute_flakiness;
+    use super::{MutationFlakiness, MutationFlakinessMatrix, compute_flakiness, escape_csv_field};
 
     #[test]
     fn test_no_tests_not_flaky() {
@@ -212,4 +334,30 @@ mod tests {
             false,
         );
     }
+
+    #[test]
+    fn test_flaky_test_names() {
+        let mutation_flakiness = |flakiness_per_test: &[(&'static str, Option<bool>)]| MutationFlakiness {
+            detection_flakiness: flakiness_per_test.iter().any(|&(_, flakiness)| flakiness == Some(true)),
+            flakiness_per_test: flakiness_per_test.iter()
+                .map(|&(test_name, flakiness)| (test::TestName::StaticTestName(test_name), flakiness))
+                .collect::<HashMap<_, _>>(),
+        };
+
+        let mutation_flakiness_matrix = MutationFlakinessMatrix {
+            inner: vec![
+                mutation_flakiness(&[("tests::b", Some(true)), ("tests::c", Some(false))]),
+                mutation_flakiness(&[("tests::a", Some(true)), ("tests::c", None)]),
+                mutation_flakiness(&[("tests::b", Some(true))]),
+            ],
+        };
+
+        assert_eq!(mutation_flakiness_matrix.flaky_test_names().into_iter().collect::<Vec<_>>(), ["tests::a", "tests::b"]);
+    }
+
+    #[test]
+    fn test_escape_csv_field() {
+        assert_eq!(escape_csv_field("tests::a"), "tests::a");
+        assert_eq!(escape_csv_field("src/lib.rs:1:5: 1:10, \"x\""), "\"src/lib.rs:1:5: 1:10, \"\"x\"\"\"");
+    }
 }
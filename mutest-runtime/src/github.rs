@@ -0,0 +1,43 @@
+//! Output of mutation analysis results as GitHub Actions workflow commands, which are displayed as annotations of the
+//! affected lines in the diffs of pull requests.
+//!
+//! See https://docs.github.com/en/actions/reference/workflow-commands-for-github-actions#setting-a-warning-message.
+
+use crate::baseline::split_display_location;
+use crate::metadata::MutationMeta;
+
+/// Escape data of a workflow command (i.e. its message).
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Escape a property value of a workflow command.
+fn escape_property(property: &str) -> String {
+    escape_data(property).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// Returns a warning annotation of the location of an undetected mutation.
+pub fn undetected_mutation_annotation(mutation: &MutationMeta) -> String {
+    let location_properties = match split_display_location(mutation.display_location()) {
+        Some((file, line)) => format!(" file={file},line={line}", file = escape_property(file)),
+        None => String::new(),
+    };
+
+    format!("::warning{location_properties}::{message}",
+        message = escape_data(&format!("[{op_name}] mutation survived: {display_name}",
+            op_name = mutation.op_name(),
+            display_name = mutation.display_name(),
+        )),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_data, escape_property};
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape_data("100% of a\nb"), "100%25 of a%0Ab");
+        assert_eq!(escape_property("C:\\src\\a,b.rs"), "C%3A\\src\\a%2Cb.rs");
+    }
+}
@@ -0,0 +1,283 @@
+#[cfg(not(feature = "atomic-handle"))]
+use core::cell::Cell;
+#[cfg(feature = "atomic-handle")]
+use core::sync::atomic::{self, AtomicPtr, AtomicUsize};
+
+#[cfg(feature = "atomic-handle")]
+use alloc::boxed::Box;
+
+use crate::metadata::{SubstLocIdx, SubstMap, SubstMeta};
+
+/// Marker for the unlikely branch of a substitution lookup.
+///
+/// Substitution lookups are performed at every substitution location of the meta-mutant, including when no mutant is
+/// active (e.g. in the unmutated test baseline). Calling a `#[cold]` function in the branch with an active mutant hints
+/// the optimizer to lay out the code for the common case of no active mutant.
+#[cold]
+#[inline(always)]
+fn active_mutant() {}
+
+/// Handle storing the currently active substitution map of a program.
+///
+/// With the `atomic-handle` feature, the handle is replaced by an implementation which can be replaced while other
+/// threads are reading from it, see below.
+///
+/// An instance of this handle is automatically created and referenced in
+/// meta-mutant programs generated by mutest-rs.
+///
+/// # Safety
+///
+/// All valid uses of this handle must be stored in a `static` (or any pinned memory location).
+///
+/// Reading from this handle remains valid even as the substitution map stored in the handle
+/// is changed or swapped out, as it represents a static memory location.
+/// For example, it is considered valid for a read from the handle to return substitution metadata
+/// for new substitution maps if the handle is simultaneously modified from another thread.
+#[cfg(not(feature = "atomic-handle"))]
+pub struct ActiveMutantHandle<S: SubstMap>(Cell<Option<S>>);
+
+#[cfg(not(feature = "atomic-handle"))]
+impl<S: SubstMap> ActiveMutantHandle<S> {
+    pub const fn empty() -> Self {
+        Self(Cell::new(None))
+    }
+
+    pub const fn with(v: S) -> Self {
+        Self(Cell::new(Some(v)))
+    }
+
+    #[inline(always)]
+    pub fn subst_at(self: &'static Self, subst_loc_idx: SubstLocIdx) -> Option<SubstMeta> {
+        // SAFETY: We are acquiring a reference to the static memory location backing the handle
+        //         and the value is allowed to change before the substitution metadata is read.
+        let subst_map_ref = (unsafe { &*self.0.as_ptr() }).as_ref();
+
+        match subst_map_ref {
+            None => None,
+            Some(subst_map) => {
+                active_mutant();
+                subst_map.subst_at(subst_loc_idx)
+            }
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The substitution location index must be valid for the active substitution map.
+    /// In debug builds, the index is checked regardless.
+    #[inline(always)]
+    pub unsafe fn subst_at_unchecked(self: &'static Self, subst_loc_idx: SubstLocIdx) -> Option<SubstMeta> {
+        // SAFETY: We are acquiring a reference to the static memory location backing the handle
+        //         and the value is allowed to change before the substitution metadata is read.
+        let subst_map_ref = (unsafe { &*self.0.as_ptr() }).as_ref();
+
+        match subst_map_ref {
+            None => None,
+            Some(subst_map) => {
+                active_mutant();
+                match cfg!(debug_assertions) {
+                    true => subst_map.subst_at(subst_loc_idx),
+                    false => subst_map.subst_at_unchecked(subst_loc_idx),
+                }
+            }
+        }
+    }
+
+    /// Replace the active substitution map of the program, e.g. to activate a mutant from a custom test harness.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that no other thread is reading from the handle.
+    pub unsafe fn replace(&self, v: Option<S>) {
+        self.0.replace(v);
+    }
+}
+
+// SAFETY: While access to the handle data is not synchronized, the handle can only be mutated using
+//         unsafe functions, see above.
+#[cfg(not(feature = "atomic-handle"))]
+unsafe impl<S: SubstMap> Sync for ActiveMutantHandle<S> {}
+
+/// Number of spins to wait for readers of a replaced substitution map to finish before the map is leaked instead of
+/// being freed.
+#[cfg(feature = "atomic-handle")]
+const RETIRE_SPINS_LIMIT: usize = 1 << 20;
+
+/// Handle storing the currently active substitution map of a program, which can be replaced while other threads are
+/// reading from it (e.g. background threads spawned by tests of previous mutants, which are still running).
+///
+/// Substitution maps are replaced by atomically swapping a pointer to a heap-allocated map. A replaced map is only
+/// freed once no thread is reading from the handle anymore, which is tracked by a counter of active readers. If
+/// readers keep the handle busy for too long, the replaced map is leaked instead.
+///
+/// Every substitution lookup updates the counter of active readers, so lookups are slower than with the default,
+/// unsynchronized handle.
+///
+/// # Safety
+///
+/// All valid uses of this handle must be stored in a `static` (or any pinned memory location).
+#[cfg(feature = "atomic-handle")]
+pub struct ActiveMutantHandle<S: SubstMap> {
+    /// Substitution map the handle was created with, used until the handle is first replaced.
+    initial: Option<S>,
+    /// Substitution map the handle was last replaced with, or null if the handle was never replaced.
+    replaced: AtomicPtr<Option<S>>,
+    readers_count: AtomicUsize,
+}
+
+#[cfg(feature = "atomic-handle")]
+impl<S: SubstMap> ActiveMutantHandle<S> {
+    pub const fn empty() -> Self {
+        Self { initial: None, replaced: AtomicPtr::new(core::ptr::null_mut()), readers_count: AtomicUsize::new(0) }
+    }
+
+    pub const fn with(v: S) -> Self {
+        Self { initial: Some(v), replaced: AtomicPtr::new(core::ptr::null_mut()), readers_count: AtomicUsize::new(0) }
+    }
+
+    /// Read the active substitution map, which is not freed while it is being read.
+    #[inline(always)]
+    fn read<R>(&self, f: impl FnOnce(&S) -> Option<R>) -> Option<R> {
+        self.readers_count.fetch_add(1, atomic::Ordering::SeqCst);
+        // The reader is unregistered even if the read panics, which would otherwise keep replaced maps from being freed.
+        let _reader = ReaderGuard(&self.readers_count);
+
+        let replaced = self.replaced.load(atomic::Ordering::SeqCst);
+        // SAFETY: Replaced substitution maps are only freed once there are no active readers, see `replace`.
+        let subst_map_ref = match replaced.is_null() {
+            true => self.initial.as_ref(),
+            false => (unsafe { &*replaced }).as_ref(),
+        };
+
+        match subst_map_ref {
+            None => None,
+            Some(subst_map) => {
+                active_mutant();
+                f(subst_map)
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn subst_at(self: &'static Self, subst_loc_idx: SubstLocIdx) -> Option<SubstMeta> {
+        self.read(|subst_map| subst_map.subst_at(subst_loc_idx))
+    }
+
+    /// # Safety
+    ///
+    /// The substitution location index must be valid for the active substitution map.
+    /// In debug builds, the index is checked regardless.
+    #[inline(always)]
+    pub unsafe fn subst_at_unchecked(self: &'static Self, subst_loc_idx: SubstLocIdx) -> Option<SubstMeta> {
+        self.read(|subst_map| {
+            match cfg!(debug_assertions) {
+                true => subst_map.subst_at(subst_loc_idx),
+                false => unsafe { subst_map.subst_at_unchecked(subst_loc_idx) },
+            }
+        })
+    }
+
+    /// Replace the active substitution map of the program, e.g. to activate a mutant from a custom test harness.
+    ///
+    /// Unlike with the default handle, other threads may be reading from the handle, and may observe either the
+    /// previous or the new substitution map until the call returns.
+    pub fn replace(&self, v: Option<S>) {
+        let previous = self.replaced.swap(Box::into_raw(Box::new(v)), atomic::Ordering::SeqCst);
+        if previous.is_null() { return; }
+
+        // Readers which loaded the previous substitution map registered themselves before loading it, so once there
+        // are no active readers, no one can be reading from the previous map anymore.
+        for _ in 0..RETIRE_SPINS_LIMIT {
+            if self.readers_count.load(atomic::Ordering::SeqCst) == 0 {
+                // SAFETY: The previous substitution map was allocated by a previous call, and is no longer read.
+                drop(unsafe { Box::from_raw(previous) });
+                return;
+            }
+            core::hint::spin_loop();
+        }
+
+        // The previous substitution map may still be read, so it is leaked rather than freed.
+    }
+}
+
+/// Unregisters an active reader of the handle when dropped.
+#[cfg(feature = "atomic-handle")]
+struct ReaderGuard<'a>(&'a AtomicUsize);
+
+#[cfg(feature = "atomic-handle")]
+impl Drop for ReaderGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, atomic::Ordering::Release);
+    }
+}
+
+#[cfg(feature = "atomic-handle")]
+impl<S: SubstMap> Drop for ActiveMutantHandle<S> {
+    fn drop(&mut self) {
+        let replaced = *self.replaced.get_mut();
+        if !replaced.is_null() {
+            // SAFETY: The handle is no longer read, as it is being dropped.
+            drop(unsafe { Box::from_raw(replaced) });
+        }
+    }
+}
+
+// SAFETY: Replacing the active substitution map is synchronized with readers, see above.
+#[cfg(feature = "atomic-handle")]
+unsafe impl<S: SubstMap> Sync for ActiveMutantHandle<S> {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{self, AtomicUsize};
+
+    use super::ActiveMutantHandle;
+    use crate::metadata::{SubstLocIdx, SubstMap, SubstMeta};
+
+    /// Substitution map without any substitutions, which counts how many times it was dropped.
+    #[derive(Clone)]
+    struct CountedSubstMap(&'static AtomicUsize);
+
+    impl Drop for CountedSubstMap {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, atomic::Ordering::SeqCst);
+        }
+    }
+
+    impl SubstMap for CountedSubstMap {
+        fn subst_at(&self, _subst_loc_idx: SubstLocIdx) -> Option<SubstMeta> { None }
+        unsafe fn subst_at_unchecked(&self, _subst_loc_idx: SubstLocIdx) -> Option<SubstMeta> { None }
+        fn retain_mutation(&mut self, _mutation_id: u32) {}
+    }
+
+    #[test]
+    fn test_replace() {
+        static DROPPED_COUNT: AtomicUsize = AtomicUsize::new(0);
+        static HANDLE: ActiveMutantHandle<CountedSubstMap> = ActiveMutantHandle::empty();
+        assert!(HANDLE.subst_at(0).is_none());
+
+        #[cfg_attr(feature = "atomic-handle", allow(unused_unsafe))]
+        // SAFETY: No other thread is reading from the handle.
+        unsafe { HANDLE.replace(Some(CountedSubstMap(&DROPPED_COUNT))); }
+        assert!(HANDLE.subst_at(0).is_none());
+        assert_eq!(DROPPED_COUNT.load(atomic::Ordering::SeqCst), 0);
+    }
+
+    #[cfg(feature = "atomic-handle")]
+    #[test]
+    fn test_replace_after_panicking_reader() {
+        static DROPPED_COUNT: AtomicUsize = AtomicUsize::new(0);
+        static HANDLE: ActiveMutantHandle<CountedSubstMap> = ActiveMutantHandle::empty();
+
+        HANDLE.replace(Some(CountedSubstMap(&DROPPED_COUNT)));
+
+        let read_result = std::panic::catch_unwind(|| {
+            HANDLE.read(|_| -> Option<()> { panic!("reader panicked") })
+        });
+        assert!(read_result.is_err());
+        assert_eq!(HANDLE.readers_count.load(atomic::Ordering::SeqCst), 0);
+
+        // The panicking reader is no longer registered, so the replaced map is freed rather than leaked.
+        HANDLE.replace(None);
+        assert_eq!(DROPPED_COUNT.load(atomic::Ordering::SeqCst), 1);
+    }
+}
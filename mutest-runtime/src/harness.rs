@@ -1,20 +1,40 @@
-use std::cell::Cell;
+// With the `atomic-handle` feature, replacing the active mutant handle is safe, but the default handle is not.
+#![cfg_attr(feature = "atomic-handle", allow(unused_unsafe))]
+
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::Infallible;
 use std::env;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
 use std::process;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::sync::atomic::{self, AtomicBool};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use crate::MutationSafety;
+use crate::{ActiveMutantHandle, MutationSafety};
+use crate::baseline::{self, Baseline};
 use crate::config::{self, Options};
 use crate::detections::{MutationDetectionMatrix, print_mutation_detection_matrix};
-use crate::flakiness::{MutationFlakinessMatrix, print_mutation_flakiness_epilogue, print_mutation_flakiness_matrix};
-use crate::metadata::{MutantMeta, MutationMeta, SubstLocIdx, SubstMap, SubstMeta};
+use crate::flakiness::{self, MutationFlakinessMatrix, print_mutation_flakiness_epilogue, print_mutation_flakiness_matrix};
+use crate::github;
+use crate::history::{History, HistoryRecord, OpCounts};
+use crate::leak_check::{self, LeakCheck};
+use crate::log;
+use crate::metadata::{MutantMeta, MutationMeta, SubstMap};
+use crate::mutant_selection::MutantSelection;
+use crate::property_tests;
+use crate::reset_hooks;
+use crate::results::{MutationRecord, SavedResults};
+use crate::sarif;
 use crate::subsumption::{MutationSubsumptionMatrix, print_mutation_subsumption_matrix};
+use crate::test_filter::TestFilter;
 use crate::test_runner;
+use crate::test_scheduler::TestScheduler;
+use crate::test_timings::TestTimings;
 use crate::thread_pool::ThreadPool;
+use crate::tui::Tui;
+use crate::verdict_cache::{self, VerdictCache};
 
 mod test {
     #![allow(unused_imports)]
@@ -23,63 +43,6 @@ mod test {
     pub use ::test::test::*;
 }
 
-/// Handle storing the currently active substitution map of a program.
-///
-/// An instance of this handle is automatically created and referenced in
-/// meta-mutant programs generated by mutest-rs.
-///
-/// # Safety
-///
-/// All valid uses of this handle must be stored in a `static` (or any pinned memory location).
-///
-/// Reading from this handle remains valid even as the substitution map stored in the handle
-/// is changed or swapped out, as it represents a static memory location.
-/// For example, it is considered valid for a read from the handle to return substitution metadata
-/// for new substitution maps if the handle is simultaneously modified from another thread.
-pub struct ActiveMutantHandle<S: SubstMap>(Cell<Option<S>>);
-
-impl<S: SubstMap> ActiveMutantHandle<S> {
-    pub const fn empty() -> Self {
-        Self(Cell::new(None))
-    }
-
-    pub const fn with(v: S) -> Self {
-        Self(Cell::new(Some(v)))
-    }
-
-    #[inline]
-    pub fn subst_at(self: &'static Self, subst_loc_idx: SubstLocIdx) -> Option<SubstMeta> {
-        // SAFETY: We are acquiring a reference to the static memory location backing the handle
-        //         and the value is allowed to change before the substitution metadata is read.
-        let subst_map_ref = (unsafe { &*self.0.as_ptr() }).as_ref();
-
-        subst_map_ref.and_then(|subst| subst.subst_at(subst_loc_idx))
-    }
-
-    /// # Safety
-    ///
-    /// The substitution location index must be valid for the active substitution map.
-    #[inline]
-    pub unsafe fn subst_at_unchecked(self: &'static Self, subst_loc_idx: SubstLocIdx) -> Option<SubstMeta> {
-        // SAFETY: We are acquiring a reference to the static memory location backing the handle
-        //         and the value is allowed to change before the substitution metadata is read.
-        let subst_map_ref = (unsafe { &*self.0.as_ptr() }).as_ref();
-
-        subst_map_ref.and_then(|subst| subst.subst_at_unchecked(subst_loc_idx))
-    }
-
-    /// # Safety
-    ///
-    /// The caller must ensure that no other thread is reading from the handle.
-    pub(crate) unsafe fn replace(&self, v: Option<S>) {
-        self.0.replace(v);
-    }
-}
-
-// SAFETY: While access to the handle data is not synchronized, the handle can only be mutated using
-//         unsafe, crate-private functions, see above.
-unsafe impl<S: SubstMap> Sync for ActiveMutantHandle<S> {}
-
 const ERROR_EXIT_CODE: i32 = 101;
 
 fn make_owned_test_fn(test_fn: &test::TestFn) -> test::TestFn {
@@ -104,11 +67,21 @@ fn clone_tests(tests: &[test_runner::Test]) -> Vec<test_runner::Test> {
                 desc: test.desc.clone(),
                 test_fn: make_owned_test_fn(&test.test_fn),
                 timeout: test.timeout,
+                bench_slowdown_limit: test.bench_slowdown_limit,
             }
         })
         .collect()
 }
 
+/// Displays an ignored test's status like libtest, including the reason given for ignoring it, if any
+/// (e.g. `ignored, requires network access`).
+fn display_ignored(desc: &test::TestDesc) -> String {
+    match desc.ignore_message {
+        Some(ignore_message) => format!("ignored, {ignore_message}"),
+        None => "ignored".to_owned(),
+    }
+}
+
 struct ProfiledTest {
     pub test: test::TestDescAndFn,
     pub result: test_runner::TestResult,
@@ -122,6 +95,7 @@ fn profile_tests(tests: Vec<test::TestDescAndFn>) -> Result<Vec<ProfiledTest>, I
                 desc: test.desc.clone(),
                 test_fn: make_owned_test_fn(&test.testfn),
                 timeout: None,
+                bench_slowdown_limit: None,
             }
         })
         .collect::<Vec<_>>();
@@ -164,13 +138,13 @@ fn sort_profiled_tests_by_exec_time(profiled_tests: &mut Vec<ProfiledTest>) {
     });
 }
 
-fn prioritize_tests_by_distance(tests: &mut Vec<test_runner::Test>, mutations: &'static [&'static MutationMeta]) {
+fn prioritize_tests_by_distance(tests: &mut Vec<test_runner::Test>, mutations: &[&'static MutationMeta]) {
     tests.sort_by(|a, b| {
-        let distance_a = mutations.iter().filter_map(|&m| m.reachable_from.get(a.desc.name.as_slice())).reduce(Ord::min);
-        let distance_b = mutations.iter().filter_map(|&m| m.reachable_from.get(b.desc.name.as_slice())).reduce(Ord::min);
+        let distance_a = mutations.iter().filter_map(|&m| m.distance_from(a.desc.name.as_slice())).reduce(Ord::min);
+        let distance_b = mutations.iter().filter_map(|&m| m.distance_from(b.desc.name.as_slice())).reduce(Ord::min);
 
         match (distance_a, distance_b) {
-            (Some(distance_a), Some(distance_b)) => Ord::cmp(distance_a, distance_b),
+            (Some(distance_a), Some(distance_b)) => Ord::cmp(&distance_a, &distance_b),
             (Some(_), None) => Ordering::Less,
             (None, Some(_)) => Ordering::Greater,
             (None, None) => Ordering::Equal,
@@ -178,21 +152,11 @@ fn prioritize_tests_by_distance(tests: &mut Vec<test_runner::Test>, mutations: &
     });
 }
 
-fn maximize_mutation_parallelism(tests: &mut Vec<test_runner::Test>, mutations: &'static [&'static MutationMeta]) {
-    let mut parallelized_tests = Vec::<test_runner::Test>::with_capacity(tests.len());
-
-    while !tests.is_empty() {
-        for mutation in mutations {
-            if let Some(test) = tests.iter()
-                .position(|t| mutation.reachable_from.contains_key(t.desc.name.as_slice()))
-                .map(|i| tests.remove(i))
-            {
-                parallelized_tests.push(test);
-            }
-        }
-    }
-
-    *tests = parallelized_tests;
+/// Returns the ID of the mutation of the mutant which the test is evaluated for.
+/// The mutations batched into a mutant are reachable from disjoint sets of tests.
+fn mutation_of_test(mutations: &[&'static MutationMeta], test_name: &str) -> u32 {
+    mutations.iter().find(|m| m.is_reachable_from(test_name)).map(|m| m.id)
+        .expect("only tests which reach mutations should have been run: no mutation is reachable from this test")
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
@@ -202,40 +166,168 @@ pub enum MutationTestResult {
     Detected,
     TimedOut,
     Crashed,
+    /// The test failed, but it also failed when re-run against the unmutated program, so the detection is discarded.
+    /// Only recorded for individual tests, never as the result of the mutation.
+    FlakyDetected,
 }
 
 #[derive(Default)]
 pub struct MutationTestResults {
     pub result: MutationTestResult,
+    /// Whether the evaluation of the mutation was stopped early, because the maximum number of tests was evaluated.
+    pub partially_evaluated: bool,
     pub results_per_test: HashMap<test::TestName, Option<MutationTestResult>>,
+    /// Execution time of each test run against the mutation, if it was measured.
+    pub exec_times_per_test: HashMap<test::TestName, Duration>,
 }
 
-fn run_tests<S: SubstMap>(mut tests: Vec<test_runner::Test>, mutant: &MutantMeta<S>, exhaustive: bool, thread_pool: Option<ThreadPool>) -> Result<HashMap<u32, MutationTestResults>, Infallible> {
-    let mut results = HashMap::<u32, MutationTestResults>::with_capacity(mutant.mutations.len());
+/// Re-run the test against the unmutated program in isolated child processes, returning `true` if the test failed in
+/// any of the retries, i.e. the test is flaky regardless of mutations.
+fn is_flaky_without_mutations(test: &test_runner::Test, retries_count: usize, child_process_limits: test_runner::ChildProcessLimits) -> Result<bool, Infallible> {
+    // Benchmarks are not evaluated in child processes, see `test_runner::run_test`.
+    if let test::TestFn::StaticBenchFn(_) = test.test_fn { return Ok(false); }
+
+    for _ in 0..retries_count {
+        let test_to_run = test_runner::Test {
+            desc: test.desc.clone(),
+            test_fn: make_owned_test_fn(&test.test_fn),
+            timeout: test.timeout,
+            bench_slowdown_limit: None,
+        };
 
-    for &mutation in mutant.mutations {
+        let mut failed = false;
+
+        let on_test_event = |event, _remaining_tests: &mut Vec<(test::TestId, test_runner::Test)>| -> Result<_, Infallible> {
+            match event {
+                test_runner::TestEvent::Result(test) => {
+                    failed = !matches!(test.result, test_runner::TestResult::Ignored | test_runner::TestResult::Ok);
+                }
+                _ => {}
+            }
+
+            Ok(test_runner::Flow::Continue)
+        };
+
+        // Without a mutant ID, the isolated worker runs the test without any mutations applied.
+        let test_run_strategy = test_runner::TestRunStrategy::InIsolatedChildProcess(Arc::new(|_cmd| {}), child_process_limits);
+
+        test_runner::run_tests(vec![test_to_run], on_test_event, test_run_strategy, false)?;
+
+        if failed { return Ok(true); }
+    }
+
+    Ok(false)
+}
+
+/// Tests which were re-run against the unmutated program, and whether they were found to be flaky.
+type FlakyTests = HashMap<test::TestName, bool>;
+
+fn run_tests<S: SubstMap>(mut tests: Vec<test_runner::Test>, mutant: &MutantMeta<S>, mutations: &[&'static MutationMeta], exhaustive: bool, max_tests_per_mutation: Option<usize>, flaky_retries_count: Option<usize>, flaky_tests: &mut FlakyTests, known_verdicts: Option<(&VerdictCache, usize)>, isolate: bool, retained_mutation_id: Option<u32>, child_process_limits: test_runner::ChildProcessLimits, thread_pool: Option<ThreadPool>, lingering_tests: &mut Vec<test_runner::RunningTest>) -> Result<HashMap<u32, MutationTestResults>, Infallible> {
+    let mut results = HashMap::<u32, MutationTestResults>::with_capacity(mutations.len());
+
+    for &mutation in mutations {
         results.insert(mutation.id, MutationTestResults {
             result: MutationTestResult::Undetected,
-            results_per_test: HashMap::with_capacity(mutation.reachable_from.len()),
+            partially_evaluated: false,
+            results_per_test: HashMap::with_capacity(mutation.reachable_from_count()),
+            exec_times_per_test: HashMap::with_capacity(mutation.reachable_from_count()),
         });
     }
 
-    tests.retain(|test| mutant.mutations.iter().any(|m| m.reachable_from.contains_key(test.desc.name.as_slice())));
-    maximize_mutation_parallelism(&mut tests, mutant.mutations);
+    let mut evaluated_tests_counts = HashMap::<u32, usize>::with_capacity(mutations.len());
+
+    tests.retain(|test| mutations.iter().any(|m| m.is_reachable_from(test.desc.name.as_slice())));
+
+    // Tests which consistently produced the same result against their mutation in enough previous runs are not run
+    // again, and their known result is used instead.
+    if let Some((verdict_cache, min_confidence)) = known_verdicts {
+        tests.retain(|test| {
+            let mutation_id = mutation_of_test(mutations, test.desc.name.as_slice());
+            let mutation = mutations.iter().find(|m| m.id == mutation_id).expect("mutation of test not found in mutant");
+
+            let Some(known_result) = verdict_cache.known_result(mutation.stable_id, test.desc.name.as_slice(), min_confidence) else { return true; };
+
+            let mutation_results = results.get_mut(&mutation.id).expect("mutation result slot not allocated");
+            mutation_results.results_per_test.insert(test.desc.name.clone(), Some(known_result));
+            match known_result {
+                MutationTestResult::Detected => mutation_results.result = MutationTestResult::Detected,
+                MutationTestResult::Crashed | MutationTestResult::TimedOut if mutation_results.result != MutationTestResult::Detected => {
+                    mutation_results.result = known_result;
+                }
+                _ => {}
+            }
+
+            false
+        });
+
+        // By default, mutations which are already known to be detected are not evaluated any further.
+        if !exhaustive {
+            tests.retain(|test| {
+                let mutation_id = mutation_of_test(mutations, test.desc.name.as_slice());
+                matches!(results[&mutation_id].result, MutationTestResult::Undetected)
+            });
+        }
+    }
+
+    // Tests are scheduled grouped by the mutation they are evaluated for, and are rebalanced as tests start and
+    // complete, see `test_scheduler`.
+    let mut scheduler = TestScheduler::<u32>::new();
+    scheduler.schedule(&mut tests, |test| mutation_of_test(mutations, test.desc.name.as_slice()));
+
+    // Detecting tests are re-run against the unmutated program, for which we need to retain the tests.
+    let tests_to_retry = flaky_retries_count.map(|_| clone_tests(&tests)).unwrap_or_default();
 
     let total_tests_count = tests.len();
     let mut completed_tests_count = 0;
 
+    let mut running_tests = Vec::<test::TestName>::new();
+    // Running tests which were cancelled, because their mutation was detected by another test in the meantime.
+    let mut cancelled_tests = HashSet::<test::TestName>::new();
+
     let on_test_event = |event, remaining_tests: &mut Vec<(test::TestId, test_runner::Test)>| -> Result<_, Infallible> {
         match event {
+            test_runner::TestEvent::Wait(desc) => {
+                running_tests.push(desc.name.clone());
+                scheduler.start(mutation_of_test(mutations, desc.name.as_slice()));
+                scheduler.schedule_queue(remaining_tests, |(_, test)| mutation_of_test(mutations, test.desc.name.as_slice()));
+            }
             test_runner::TestEvent::Result(test) => {
+                running_tests.retain(|test_name| *test_name != test.desc.name);
+
+                // The results of cancelled tests are redundant, and are discarded.
+                if cancelled_tests.remove(&test.desc.name) {
+                    return Ok(test_runner::Flow::Continue);
+                }
+
                 completed_tests_count += 1;
 
-                let mutation = mutant.mutations.iter().find(|m| m.reachable_from.contains_key(test.desc.name.as_slice()))
-                    .expect("only tests which reach mutations should have been run: no mutation is reachable from this test");
+                let mutation_id = mutation_of_test(mutations, test.desc.name.as_slice());
+                scheduler.complete(mutation_id);
+                scheduler.schedule_queue(remaining_tests, |(_, test)| mutation_of_test(mutations, test.desc.name.as_slice()));
+
+                let mutation = mutations.iter().find(|m| m.id == mutation_id).expect("mutation of test not found in mutant");
 
                 let mutation_results = results.get_mut(&mutation.id).expect("mutation result slot not allocated");
 
+                if let Some(exec_time) = test.exec_time {
+                    mutation_results.exec_times_per_test.insert(test.desc.name.clone(), exec_time);
+                }
+
+                let evaluated_tests_count = evaluated_tests_counts.entry(mutation.id).or_default();
+                if !matches!(test.result, test_runner::TestResult::Ignored) {
+                    *evaluated_tests_count += 1;
+                }
+
+                // Tests for a mutation are only run until the maximum number of tests have been evaluated for it,
+                // regardless of whether the mutation has been detected.
+                if let Some(max_tests_per_mutation) = max_tests_per_mutation && *evaluated_tests_count >= max_tests_per_mutation {
+                    let remaining_tests_count = remaining_tests.len();
+                    remaining_tests.retain(|(_, test)| !mutation.is_reachable_from(test.desc.name.as_slice()));
+                    if remaining_tests.len() < remaining_tests_count {
+                        mutation_results.partially_evaluated = true;
+                    }
+                }
+
                 match test.result {
                     | test_runner::TestResult::Ignored
                     | test_runner::TestResult::Ok => {
@@ -245,6 +337,25 @@ fn run_tests<S: SubstMap>(mut tests: Vec<test_runner::Test>, mutant: &MutantMeta
 
                     | test_runner::TestResult::Failed
                     | test_runner::TestResult::FailedMsg(_) => {
+                        if let Some(flaky_retries_count) = flaky_retries_count {
+                            let flaky = match flaky_tests.get(&test.desc.name) {
+                                Some(&flaky) => flaky,
+                                None => {
+                                    let test_to_retry = tests_to_retry.iter().find(|t| t.desc.name == test.desc.name)
+                                        .expect("completed test not found amongst tests to retry");
+                                    let flaky = is_flaky_without_mutations(test_to_retry, flaky_retries_count, child_process_limits)?;
+                                    flaky_tests.insert(test.desc.name.clone(), flaky);
+                                    flaky
+                                }
+                            };
+
+                            // Detections by tests which also fail without mutations are discarded.
+                            if flaky {
+                                mutation_results.results_per_test.insert(test.desc.name.clone(), Some(MutationTestResult::FlakyDetected));
+                                return Ok(test_runner::Flow::Continue);
+                            }
+                        }
+
                         mutation_results.results_per_test.insert(test.desc.name.clone(), Some(MutationTestResult::Detected));
                         mutation_results.result = MutationTestResult::Detected;
                     }
@@ -270,12 +381,23 @@ fn run_tests<S: SubstMap>(mut tests: Vec<test_runner::Test>, mutant: &MutantMeta
                 // test evaluation is stopped early if all mutations are detected.
                 if !exhaustive {
                     // Remove any remaining tests from the queue that are for the just detected mutation.
-                    remaining_tests.retain(|(_, test)| !mutation.reachable_from.contains_key(test.desc.name.as_slice()));
+                    remaining_tests.retain(|(_, test)| !mutation.is_reachable_from(test.desc.name.as_slice()));
 
-                    // If all mutations have been detected, stop test evaluation early.
-                    if results.iter().all(|(_, mutation_results)| !matches!(mutation_results.result, MutationTestResult::Undetected)) {
+                    // If all mutations have been detected (or are no longer evaluated), stop test evaluation early.
+                    if results.iter().all(|(_, mutation_results)| !matches!(mutation_results.result, MutationTestResult::Undetected) || mutation_results.partially_evaluated) {
                         return Ok(test_runner::Flow::Stop);
                     }
+
+                    // Cancel any still running tests for the just detected mutation, rather than waiting for their
+                    // results. Tests run in-process are still waited for before the next mutant is activated.
+                    let tests_to_cancel = running_tests.extract_if(|test_name| mutation.is_reachable_from(test_name.as_slice())).collect::<Vec<_>>();
+                    if !tests_to_cancel.is_empty() {
+                        for test_name in &tests_to_cancel {
+                            scheduler.complete(mutation.id);
+                            cancelled_tests.insert(test_name.clone());
+                        }
+                        return Ok(test_runner::Flow::CancelRunning(tests_to_cancel));
+                    }
                 }
             }
             _ => {}
@@ -284,19 +406,26 @@ fn run_tests<S: SubstMap>(mut tests: Vec<test_runner::Test>, mutant: &MutantMeta
         Ok(test_runner::Flow::Continue)
     };
 
-    let test_run_strategy = match mutant.is_unsafe() {
+    // Memory limits can only be applied to tests run in isolated child processes.
+    // Compile-time mutants can only be evaluated in their own executables, which are run as isolated child processes.
+    // Once the tests of a previous mutant leaked resources, mutants may be isolated from them, see `leak_check`.
+    let test_run_strategy = match isolate || mutant.is_unsafe() || child_process_limits.max_memory.is_some() || CFG_MUTANTS.load(atomic::Ordering::Relaxed) {
         false => test_runner::TestRunStrategy::InProcess(thread_pool),
         true => test_runner::TestRunStrategy::InIsolatedChildProcess({
             let mutant_id = mutant.id;
             Arc::new(move |cmd| {
                 cmd.env(MUTEST_ISOLATED_WORKER_MUTANT_ID, mutant_id.to_string());
+                // Only the single mutation of a batched mutant is applied in isolated workers, like in the current
+                // process (see `SubstMap::retain_mutation`).
+                if let Some(retained_mutation_id) = retained_mutation_id { cmd.env(MUTEST_ISOLATED_WORKER_MUTATION_ID, retained_mutation_id.to_string()); }
             })
-        }),
+        }, child_process_limits),
     };
 
-    test_runner::run_tests(tests, on_test_event, test_run_strategy, false)?;
+    let (_, mut timed_out_tests) = test_runner::run_tests(tests, on_test_event, test_run_strategy, false)?;
+    lingering_tests.append(&mut timed_out_tests);
 
-    println!("ran {completed} out of {total} {descr}",
+    log::info!("ran {completed} out of {total} {descr}\n",
         completed = completed_tests_count,
         total = total_tests_count,
         descr = match total_tests_count {
@@ -304,7 +433,6 @@ fn run_tests<S: SubstMap>(mut tests: Vec<test_runner::Test>, mutant: &MutantMeta
             _ => "tests",
         },
     );
-    println!();
 
     Ok(results)
 }
@@ -317,6 +445,15 @@ pub struct MutationOpStats {
     pub crashed_mutations_count: usize,
 }
 
+impl MutationOpStats {
+    fn add(&mut self, other: &MutationOpStats) {
+        self.total_mutations_count += other.total_mutations_count;
+        self.undetected_mutations_count += other.undetected_mutations_count;
+        self.timed_out_mutations_count += other.timed_out_mutations_count;
+        self.crashed_mutations_count += other.crashed_mutations_count;
+    }
+}
+
 pub struct MutationAnalysisResults {
     pub all_test_runs_failed_successfully: bool,
     pub total_mutations_count: usize,
@@ -327,12 +464,70 @@ pub struct MutationAnalysisResults {
     pub timed_out_safe_mutations_count: usize,
     pub crashed_mutations_count: usize,
     pub crashed_safe_mutations_count: usize,
+    pub flaky_detections_count: usize,
+    pub partially_evaluated_undetected_mutations_count: usize,
+    /// Undetected mutations which are accepted by the baseline.
+    pub baselined_undetected_mutations_count: usize,
+    pub undetected_mutations: Vec<&'static MutationMeta>,
+    /// Undetected mutations which slowed down tests beyond the performance regression factor, with the slowed down
+    /// tests. These mutations may only affect the performance of the program, rather than its correctness.
+    pub perf_suspicious_mutations: Vec<(&'static MutationMeta, Vec<SlowedDownTest>)>,
+    /// Number of threads of timed out tests which were abandoned while still running, and are still running.
+    pub leaked_threads_count: usize,
+    /// Number of mutations evaluated in-process after the tests of a previous mutant leaked resources, whose results
+    /// may be affected by the leak. Only counted with `LeakPolicy::Report`.
+    pub possibly_contaminated_mutations_count: usize,
     pub mutation_detection_matrix: MutationDetectionMatrix,
     pub mutation_op_stats: HashMap<&'static str, MutationOpStats>,
+    /// Mutation statistics of each target function, keyed by the definition path of the function.
+    pub mutation_target_stats: HashMap<&'static str, MutationOpStats>,
+    /// Mutation statistics of each source file, keyed by the file component of the display location of mutations.
+    pub mutation_file_stats: HashMap<&'static str, MutationOpStats>,
     pub duration: Duration,
 }
 
-fn run_mutation_analysis<S: SubstMap>(opts: &Options, tests: &[test_runner::Test], mutants: &'static [&'static MutantMeta<S>], active_mutant_handle: &'static ActiveMutantHandle<S>, thread_pool: Option<ThreadPool>) -> MutationAnalysisResults {
+pub struct SlowedDownTest {
+    pub test_name: test::TestName,
+    pub exec_time: Duration,
+    pub profiled_exec_time: Duration,
+}
+
+/// Slowdowns below this are considered measurement noise, regardless of the performance regression factor, so that
+/// very short tests are not reported.
+const PERF_REGRESSION_MIN_SLOWDOWN: Duration = Duration::from_millis(10);
+
+/// Tests which took longer than their profiled execution time by more than the factor when run against a mutation,
+/// in name order.
+fn slowed_down_tests(exec_times_per_test: &HashMap<test::TestName, Duration>, profiled_exec_times: &HashMap<test::TestName, Duration>, perf_regression_factor: f64) -> Vec<SlowedDownTest> {
+    let mut slowed_down_tests = exec_times_per_test.iter()
+        .filter_map(|(test_name, &exec_time)| {
+            let &profiled_exec_time = profiled_exec_times.get(test_name)?;
+            if exec_time <= profiled_exec_time.mul_f64(perf_regression_factor) { return None; }
+            if exec_time - profiled_exec_time < PERF_REGRESSION_MIN_SLOWDOWN { return None; }
+            Some(SlowedDownTest { test_name: test_name.clone(), exec_time, profiled_exec_time })
+        })
+        .collect::<Vec<_>>();
+    slowed_down_tests.sort_unstable_by(|a, b| Ord::cmp(a.test_name.as_slice(), b.test_name.as_slice()));
+    slowed_down_tests
+}
+
+/// Activate the substitutions of a mutant, or no mutant, after resetting global state (see `reset_hooks`). Global state
+/// is reset without any mutant active, so that the reset hooks themselves are not mutated.
+///
+/// # Safety
+///
+/// See `ActiveMutantHandle::replace`.
+unsafe fn reset_and_activate_mutant<S: SubstMap>(active_mutant_handle: &ActiveMutantHandle<S>, substitutions: Option<S>) {
+    unsafe {
+        if reset_hooks::has_reset_hooks() {
+            active_mutant_handle.replace(None);
+            reset_hooks::run_reset_hooks();
+        }
+        active_mutant_handle.replace(substitutions);
+    }
+}
+
+fn run_mutation_analysis<S: SubstMap>(opts: &Options, tests: &[test_runner::Test], profiled_exec_times: &HashMap<test::TestName, Duration>, mutants: &'static [&'static MutantMeta<S>], active_mutant_handle: &'static ActiveMutantHandle<S>, thread_pool: Option<ThreadPool>, mut tui: Option<&mut Tui>, mut verdict_cache: Option<&mut VerdictCache>) -> MutationAnalysisResults {
     let mut results = MutationAnalysisResults {
         all_test_runs_failed_successfully: true,
         total_mutations_count: 0,
@@ -343,78 +538,179 @@ fn run_mutation_analysis<S: SubstMap>(opts: &Options, tests: &[test_runner::Test
         timed_out_safe_mutations_count: 0,
         crashed_mutations_count: 0,
         crashed_safe_mutations_count: 0,
+        flaky_detections_count: 0,
+        partially_evaluated_undetected_mutations_count: 0,
+        baselined_undetected_mutations_count: 0,
+        undetected_mutations: vec![],
+        perf_suspicious_mutations: vec![],
+        leaked_threads_count: 0,
+        possibly_contaminated_mutations_count: 0,
         mutation_detection_matrix: MutationDetectionMatrix::new(mutants.iter().map(|mutant| mutant.mutations.len()).sum()),
         mutation_op_stats: Default::default(),
+        mutation_target_stats: Default::default(),
+        mutation_file_stats: Default::default(),
         duration: Duration::ZERO,
     };
 
+    let mut flaky_tests = FlakyTests::new();
+    let mut lingering_tests = Vec::<test_runner::RunningTest>::new();
+    let mut evaluated_mutations = Vec::<&'static MutationMeta>::new();
+
+    let mut leak_check = opts.leak_policy.map(|_| LeakCheck::new());
+    // Set once the tests of a mutant leaked resources.
+    let mut leaked = false;
+
     let t_start = Instant::now();
 
     for &mutant in mutants {
+        // Mutations which are not selected are not evaluated, and mutants without any selected mutations are skipped.
+        let mutations = mutant.mutations.iter().copied()
+            .filter(|mutation| opts.mutant_selection.includes(mutant.id, mutation.id))
+            .collect::<Vec<_>>();
+        if mutations.is_empty() { continue; }
+
         // SAFETY: Ideally, since the previous test runs all completed, no other thread is running, no one else is
         //         reading from the handle.
         //         As for lingering test cases from previous test runs, their behaviour will change accordingly, but we
         //         have already marked them as timed out and abandoned them by this point. The behaviour in such cases
         //         stays the same, regardless of whether the handle performs locking or not.
-        unsafe { active_mutant_handle.replace(Some(mutant.substitutions.clone())); }
+        //         With the `atomic-handle` feature, the handle may be replaced while other threads read from it.
+        unsafe { reset_and_activate_mutant(active_mutant_handle, Some(mutant.substitutions.clone())); }
 
-        if opts.verbosity >= 1 {
-            print!("{}: ", mutant.id);
-        }
-        println!("applying mutant with the following mutations:");
-        for mutation in mutant.mutations {
-            print!("- ");
-            if opts.verbosity >= 1 {
-                print!("{}: ", mutation.id);
-            }
-            println!("{unsafe_marker}[{op_name}] {display_name} at {display_location}",
+        let mutant_id_prefix = match opts.verbosity {
+            0 => String::new(),
+            _ => format!("{}: ", mutant.id),
+        };
+        log::info!("{mutant_id_prefix}applying mutant with the following mutations:");
+        for mutation in &mutations {
+            let mutation_id_prefix = match opts.verbosity {
+                0 => String::new(),
+                _ => format!("{}: ", mutation.id),
+            };
+            log::info!("- {mutation_id_prefix}{unsafe_marker}[{op_name}] {display_name} at {display_location}",
                 unsafe_marker = match mutation.safety {
                     MutationSafety::Safe => "",
                     MutationSafety::Tainted => "(tainted) ",
                     MutationSafety::Unsafe => "(unsafe) ",
                 },
-                op_name = mutation.op_name,
-                display_name = mutation.display_name,
-                display_location = mutation.display_location,
+                op_name = mutation.op_name(),
+                display_name = mutation.display_name(),
+                display_location = mutation.display_location(),
             );
         }
-        println!();
+        log::info!("");
 
         let mut tests = clone_tests(tests);
         if let config::TestOrdering::MutationDistance = opts.test_ordering {
-            prioritize_tests_by_distance(&mut tests, mutant.mutations);
+            prioritize_tests_by_distance(&mut tests, &mutations);
+        }
+
+        let flaky_retries_count = match opts.mode {
+            config::Mode::Evaluate => opts.flaky_retries_count,
+            config::Mode::Flakes { .. } => None,
+        };
+
+        let known_verdicts = verdict_cache.as_deref().zip(opts.flakes_min_confidence);
+
+        let isolate = leaked && opts.leak_policy == Some(config::LeakPolicy::Isolate);
+        if leaked && opts.leak_policy == Some(config::LeakPolicy::Report) {
+            results.possibly_contaminated_mutations_count += mutations.len();
         }
 
-        match run_tests(tests, mutant, opts.exhaustive, thread_pool.clone()) {
+        match run_tests(tests, mutant, &mutations, opts.exhaustive, opts.max_tests_per_mutation, flaky_retries_count, &mut flaky_tests, known_verdicts, isolate, None, opts.child_process_limits, thread_pool.clone(), &mut lingering_tests) {
             Ok(mut run_results) => {
-                for &mutation in mutant.mutations {
-                    let op_stats = results.mutation_op_stats.entry(mutation.op_name).or_default();
+                for &mutation in &mutations {
+                    // Merged duplicate mutations are attributed to each of their operators, see below.
+                    let mut op_stats = MutationOpStats::default();
+                    let target_stats = results.mutation_target_stats.entry(mutation.target_path()).or_default();
+                    let display_file = baseline::split_display_location(mutation.display_location()).map(|(file, _)| file).unwrap_or(mutation.display_location());
+                    let file_stats = results.mutation_file_stats.entry(display_file).or_default();
 
                     results.total_mutations_count += 1;
                     op_stats.total_mutations_count += 1;
+                    target_stats.total_mutations_count += 1;
+                    file_stats.total_mutations_count += 1;
                     if let MutationSafety::Safe = mutation.safety {
                         results.total_safe_mutations_count += 1;
                     }
 
                     let Some(mutation_result) = run_results.remove(&mutation.id) else { unreachable!() };
 
+                    // Only the results of tests which were actually run are recorded, not the known results of
+                    // skipped tests.
+                    if let Some(verdict_cache) = verdict_cache.as_deref_mut() {
+                        for (test_name, &test_result) in &mutation_result.results_per_test {
+                            let Some(test_result) = test_result else { continue; };
+                            if opts.flakes_min_confidence.is_some_and(|min_confidence| verdict_cache.known_result(mutation.stable_id, test_name.as_slice(), min_confidence).is_some()) { continue; }
+                            verdict_cache.record(mutation.stable_id, test_name.as_slice(), test_result);
+                        }
+                    }
+
+                    results.flaky_detections_count += mutation_result.results_per_test.values()
+                        .filter(|result| matches!(result, Some(MutationTestResult::FlakyDetected)))
+                        .count();
+
                     match mutation_result.result {
                         MutationTestResult::Undetected => {
-                            results.all_test_runs_failed_successfully = false;
+                            results.undetected_mutations.push(mutation);
 
                             results.undetected_mutations_count += 1;
                             op_stats.undetected_mutations_count += 1;
+                            target_stats.undetected_mutations_count += 1;
+                            file_stats.undetected_mutations_count += 1;
                             if let MutationSafety::Safe = mutation.safety {
                                 results.undetected_safe_mutations_count += 1;
                             }
+                            if mutation_result.partially_evaluated {
+                                results.partially_evaluated_undetected_mutations_count += 1;
+                            }
+
+                            // Accepted surviving mutations do not fail the run, and are not reported again.
+                            match opts.baseline.as_ref().is_some_and(|baseline| baseline.contains(mutation)) {
+                                true => results.baselined_undetected_mutations_count += 1,
+                                false => {
+                                    results.all_test_runs_failed_successfully = false;
+                                    match opts.output_format {
+                                        config::OutputFormat::Human => {
+                                            let diagnostic = mutation.undetected_diagnostic();
+                                            log::info!("{}", diagnostic.strip_suffix('\n').unwrap_or(diagnostic));
+                                        }
+                                        config::OutputFormat::Github => {
+                                            // Workflow commands are read from stdout by GitHub Actions, rather than being log messages.
+                                            println!("{}", github::undetected_mutation_annotation(mutation));
+                                        }
+                                    }
+                                }
+                            }
 
-                            print!("{}", mutation.undetected_diagnostic);
+                            // Surviving mutations which slow down tests may only affect performance, rather than
+                            // correctness, which the tests cannot detect.
+                            if let Some(perf_regression_factor) = opts.perf_regression_factor {
+                                let slowed_down_tests = slowed_down_tests(&mutation_result.exec_times_per_test, profiled_exec_times, perf_regression_factor);
+                                if !slowed_down_tests.is_empty() {
+                                    log::info!("note: mutation {stable_id} survived, but slowed down tests, and may only affect performance:",
+                                        stable_id = mutation.stable_id_str(),
+                                    );
+                                    for slowed_down_test in &slowed_down_tests {
+                                        log::info!("  test {test_name} took {exec_time:.2?}, {factor:.2}x its profiled {profiled_exec_time:.2?}",
+                                            test_name = slowed_down_test.test_name.as_slice(),
+                                            exec_time = slowed_down_test.exec_time,
+                                            factor = slowed_down_test.exec_time.as_secs_f64() / slowed_down_test.profiled_exec_time.as_secs_f64(),
+                                            profiled_exec_time = slowed_down_test.profiled_exec_time,
+                                        );
+                                    }
+                                    log::info!("");
+                                    results.perf_suspicious_mutations.push((mutation, slowed_down_tests));
+                                }
+                            }
                         }
 
                         MutationTestResult::Detected => {}
                         MutationTestResult::TimedOut => {
                             results.timed_out_mutations_count += 1;
                             op_stats.timed_out_mutations_count += 1;
+                            target_stats.timed_out_mutations_count += 1;
+                            file_stats.timed_out_mutations_count += 1;
                             if let MutationSafety::Safe = mutation.safety {
                                 results.timed_out_safe_mutations_count += 1;
                             }
@@ -423,24 +719,94 @@ fn run_mutation_analysis<S: SubstMap>(opts: &Options, tests: &[test_runner::Test
                         MutationTestResult::Crashed => {
                             results.crashed_mutations_count += 1;
                             op_stats.crashed_mutations_count += 1;
+                            target_stats.crashed_mutations_count += 1;
+                            file_stats.crashed_mutations_count += 1;
                             if let MutationSafety::Safe = mutation.safety {
                                 results.crashed_safe_mutations_count += 1;
                             }
                         }
+                        MutationTestResult::FlakyDetected => unreachable!(),
+                    }
+
+                    if let Some(tui) = &mut tui {
+                        tui.record(mutation.id, MutationTestResults {
+                            result: mutation_result.result,
+                            partially_evaluated: mutation_result.partially_evaluated,
+                            results_per_test: mutation_result.results_per_test.clone(),
+                            exec_times_per_test: mutation_result.exec_times_per_test.clone(),
+                        });
+                        evaluated_mutations.push(mutation);
                     }
 
                     results.mutation_detection_matrix.insert(mutation.id, mutation_result.result, mutation_result.results_per_test.into_iter());
+
+                    for op_name in mutation.op_names() {
+                        results.mutation_op_stats.entry(op_name).or_default().add(&op_stats);
+                    }
                 }
             }
             Err(_) => { process::exit(ERROR_EXIT_CODE); }
         }
+
+        if let Some(tui) = &tui {
+            tui.render_progress(&evaluated_mutations);
+        }
+
+        // Only keep track of lingering tests which are still running.
+        lingering_tests.retain(|test| test.join_handle.as_ref().is_some_and(|join_handle| !join_handle.is_finished()));
+
+        if let Some(leak_check) = &mut leak_check {
+            let cancelled_tests_count = lingering_tests.iter().filter(|test| test.cancelled).count();
+            let leaks = leak_check.check(lingering_tests.len(), cancelled_tests_count, leak_check::threads_count(), leak_check::open_fds_count());
+            if !leaks.is_empty() {
+                log::warn!("tests of mutant {mutant_id} leaked {leaks}, which may affect the results of subsequent mutants{note}",
+                    mutant_id = mutant.id,
+                    leaks = leaks.iter().map(ToString::to_string).collect::<Vec<_>>().join(" and "),
+                    note = match !leaked && opts.leak_policy == Some(config::LeakPolicy::Isolate) {
+                        true => "\nnote: subsequent mutants are evaluated in isolated child processes",
+                        false => "",
+                    },
+                );
+                log::info!("");
+                leaked = true;
+            }
+        }
     }
 
     results.duration = t_start.elapsed();
+    results.leaked_threads_count = lingering_tests.len();
 
     results
 }
 
+/// Prints the mutation score of each group of mutations (e.g. targets, or files), with the groups containing the most
+/// undetected mutations listed first, as these are the least tested.
+fn print_mutation_scores(heading: &str, stats: &HashMap<&'static str, MutationOpStats>) {
+    let mut keys = stats.keys().collect::<Vec<_>>();
+    keys.sort_unstable_by(|a, b| {
+        let a_stats = &stats[*a];
+        let b_stats = &stats[*b];
+        Ord::cmp(&b_stats.undetected_mutations_count, &a_stats.undetected_mutations_count).then_with(|| Ord::cmp(a, b))
+    });
+
+    let total_w = stats.values().map(|s| s.total_mutations_count.checked_ilog10().unwrap_or(0) as usize + 1).max().unwrap_or(0);
+    let detected_w = stats.values().map(|s| (s.total_mutations_count - s.undetected_mutations_count).checked_ilog10().unwrap_or(0) as usize + 1).max().unwrap_or(0);
+    let undetected_w = stats.values().map(|s| s.undetected_mutations_count.checked_ilog10().unwrap_or(0) as usize + 1).max().unwrap_or(0);
+
+    log::info!("{heading}:");
+    for key in keys {
+        let key_stats = stats[key];
+
+        log::info!("  {score:>7}. {undetected:>undetected_w$} undetected; {detected:>detected_w$} detected; {total:>total_w$} total: {key}",
+            score = format!("{:.2}%", (key_stats.total_mutations_count - key_stats.undetected_mutations_count) as f64 / key_stats.total_mutations_count as f64 * 100_f64),
+            undetected = key_stats.undetected_mutations_count,
+            detected = key_stats.total_mutations_count - key_stats.undetected_mutations_count,
+            total = key_stats.total_mutations_count,
+        );
+    }
+    log::info!("");
+}
+
 fn print_mutation_analysis_epilogue(results: &MutationAnalysisResults, verbosity: u8) {
     if verbosity >= 1 {
         let mut op_names = results.mutation_op_stats.keys().collect::<Vec<_>>();
@@ -455,7 +821,7 @@ fn print_mutation_analysis_epilogue(results: &MutationAnalysisResults, verbosity
         for op_name in op_names {
             let op_stats = results.mutation_op_stats.get(op_name).map(|s| *s).unwrap_or_default();
 
-            println!("{op_name:>op_name_w$}: {score:>7}. {detected:>detected_w$} detected ({timed_out:>timed_out_w$} timed out; {crashed:>crashed_w$} crashed); {undetected:>undetected_w$} undetected",
+            log::info!("{op_name:>op_name_w$}: {score:>7}. {detected:>detected_w$} detected ({timed_out:>timed_out_w$} timed out; {crashed:>crashed_w$} crashed); {undetected:>undetected_w$} undetected",
                 score = format!("{:.2}%",(op_stats.total_mutations_count - op_stats.undetected_mutations_count) as f64 / op_stats.total_mutations_count as f64 * 100_f64),
                 detected = op_stats.total_mutations_count - op_stats.undetected_mutations_count,
                 timed_out = op_stats.timed_out_mutations_count,
@@ -464,10 +830,10 @@ fn print_mutation_analysis_epilogue(results: &MutationAnalysisResults, verbosity
             );
         }
 
-        println!();
+        log::info!("");
     }
 
-    println!("mutations: {score}. {detected} detected ({timed_out} timed out; {crashed} crashed); {undetected} undetected; {total} total",
+    log::info!("mutations: {score}. {detected} detected ({timed_out} timed out; {crashed} crashed); {undetected} undetected; {total} total",
         score = match results.total_mutations_count {
             0 => "none".to_owned(),
             _ => format!("{:.2}%", (results.total_mutations_count - results.undetected_mutations_count) as f64 / results.total_mutations_count as f64 * 100_f64),
@@ -478,7 +844,7 @@ fn print_mutation_analysis_epilogue(results: &MutationAnalysisResults, verbosity
         undetected = results.undetected_mutations_count,
         total = results.total_mutations_count,
     );
-    println!("     safe: {score}. {detected} detected ({timed_out} timed out; {crashed} crashed); {undetected} undetected; {total} total",
+    log::info!("     safe: {score}. {detected} detected ({timed_out} timed out; {crashed} crashed); {undetected} undetected; {total} total",
         score = match results.total_safe_mutations_count {
             0 => "none".to_owned(),
             _ => format!("{:.2}%", (results.total_safe_mutations_count - results.undetected_safe_mutations_count) as f64 / results.total_safe_mutations_count as f64 * 100_f64),
@@ -489,7 +855,7 @@ fn print_mutation_analysis_epilogue(results: &MutationAnalysisResults, verbosity
         undetected = results.undetected_safe_mutations_count,
         total = results.total_safe_mutations_count,
     );
-    println!("   unsafe: {score}. {detected} detected ({timed_out} timed out; {crashed} crashed); {undetected} undetected; {total} total",
+    log::info!("   unsafe: {score}. {detected} detected ({timed_out} timed out; {crashed} crashed); {undetected} undetected; {total} total",
         score = match results.total_mutations_count - results.total_safe_mutations_count {
             0 => "none".to_owned(),
             _ => format!("{:.2}%", ((results.total_mutations_count - results.total_safe_mutations_count) - (results.undetected_mutations_count - results.undetected_safe_mutations_count)) as f64 / (results.total_mutations_count - results.total_safe_mutations_count) as f64 * 100_f64),
@@ -500,9 +866,181 @@ fn print_mutation_analysis_epilogue(results: &MutationAnalysisResults, verbosity
         undetected = results.undetected_mutations_count - results.undetected_safe_mutations_count,
         total = results.total_mutations_count - results.total_safe_mutations_count,
     );
+
+    if results.leaked_threads_count >= 1 {
+        log::warn!("\n{count} {descr} of timed out tests leaked, as the tests are still running",
+            count = results.leaked_threads_count,
+            descr = match results.leaked_threads_count {
+                1 => "thread",
+                _ => "threads",
+            },
+        );
+    }
+
+    if results.possibly_contaminated_mutations_count >= 1 {
+        log::warn!("\n{count} {descr} evaluated after tests of previous mutants leaked resources, and their results may be affected",
+            count = results.possibly_contaminated_mutations_count,
+            descr = match results.possibly_contaminated_mutations_count {
+                1 => "mutation was",
+                _ => "mutations were",
+            },
+        );
+    }
+
+    if results.partially_evaluated_undetected_mutations_count >= 1 {
+        log::warn!("\n{count} undetected {descr} not evaluated against all tests, as the maximum number of tests per mutation was reached",
+            count = results.partially_evaluated_undetected_mutations_count,
+            descr = match results.partially_evaluated_undetected_mutations_count {
+                1 => "mutation was",
+                _ => "mutations were",
+            },
+        );
+    }
+
+    if results.perf_suspicious_mutations.len() >= 1 {
+        log::warn!("\n{count} undetected {descr} tests beyond the performance regression factor, and may only affect performance",
+            count = results.perf_suspicious_mutations.len(),
+            descr = match results.perf_suspicious_mutations.len() {
+                1 => "mutation slowed down",
+                _ => "mutations slowed down",
+            },
+        );
+    }
+
+    if results.flaky_detections_count >= 1 {
+        log::warn!("\ndiscarded {count} {descr} by tests which also failed without mutations",
+            count = results.flaky_detections_count,
+            descr = match results.flaky_detections_count {
+                1 => "detection",
+                _ => "detections",
+            },
+        );
+    }
 }
 
-pub fn mutest_main<S: SubstMap>(args: &[&str], tests: Vec<test::TestDescAndFn>, mutants: &'static [&'static MutantMeta<S>], active_mutant_handle: &'static ActiveMutantHandle<S>) {
+fn print_baseline_epilogue(baseline: &Baseline, results: &MutationAnalysisResults) {
+    if results.baselined_undetected_mutations_count >= 1 {
+        log::info!("\nnote: {count} undetected {descr} accepted by the baseline",
+            count = results.baselined_undetected_mutations_count,
+            descr = match results.baselined_undetected_mutations_count {
+                1 => "mutation was",
+                _ => "mutations were",
+            },
+        );
+    }
+
+    // Entries which no longer match any surviving mutation were either detected, or the mutation no longer exists.
+    let stale_entries_count = baseline.entries.iter()
+        .filter(|entry| !results.undetected_mutations.iter().any(|mutation| entry.matches(mutation)))
+        .count();
+    if stale_entries_count >= 1 {
+        log::warn!("\n{stale_entries_count} baseline {descr} no surviving mutation, update the baseline with `--update-baseline`",
+            descr = match stale_entries_count {
+                1 => "entry matches",
+                _ => "entries match",
+            },
+        );
+    }
+}
+
+fn mk_history_record(results: &MutationAnalysisResults) -> HistoryRecord {
+    // The test harness is run by Cargo from the root directory of the package, within its checked out repository.
+    let commit = process::Command::new("git").args(["rev-parse", "--short", "HEAD"]).output().ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|commit| commit.trim().to_owned());
+
+    HistoryRecord {
+        timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+        version: env::var("CARGO_PKG_VERSION").ok(),
+        commit,
+        score: match results.total_mutations_count {
+            0 => None,
+            _ => Some((results.total_mutations_count - results.undetected_mutations_count) as f64 / results.total_mutations_count as f64 * 100_f64),
+        },
+        total: results.total_mutations_count,
+        undetected: results.undetected_mutations_count,
+        timed_out: results.timed_out_mutations_count,
+        crashed: results.crashed_mutations_count,
+        ops: results.mutation_op_stats.iter()
+            .map(|(&op_name, op_stats)| (op_name.to_owned(), OpCounts { total: op_stats.total_mutations_count, undetected: op_stats.undetected_mutations_count }))
+            .collect(),
+    }
+}
+
+fn mk_saved_results<S: SubstMap>(opts: &Options, tests: &[test_runner::Test], mutants: &'static [&'static MutantMeta<S>], results: &MutationAnalysisResults) -> SavedResults {
+    let mut mutations = mutants.iter()
+        .flat_map(|mutant| mutant.mutations.iter().copied().filter(|mutation| opts.mutant_selection.includes(mutant.id, mutation.id)))
+        .map(|mutation| {
+            let mutation_results = &results.mutation_detection_matrix.inner[mutation.id as usize - 1];
+
+            let mut results_per_test = mutation_results.results_per_test.iter()
+                .filter_map(|(test_name, result)| Some((test_name.as_slice().to_owned(), (*result)?)))
+                .collect::<Vec<_>>();
+            results_per_test.sort_unstable_by(|(test_name_a, _), (test_name_b, _)| Ord::cmp(test_name_a, test_name_b));
+
+            MutationRecord {
+                id: mutation.id,
+                stable_id: mutation.stable_id,
+                op_name: mutation.op_name().to_owned(),
+                display_name: mutation.display_name().to_owned(),
+                display_location: mutation.display_location().to_owned(),
+                target_path: mutation.target_path().to_owned(),
+                substs_descr: mutation.substs_descr().to_owned(),
+                safe: matches!(mutation.safety, MutationSafety::Safe),
+                result: mutation_results.result,
+                accepted: mutation_results.result == MutationTestResult::Undetected
+                    && opts.baseline.as_ref().is_some_and(|baseline| baseline.contains(mutation)),
+                results_per_test,
+            }
+        })
+        .collect::<Vec<_>>();
+    mutations.sort_unstable_by_key(|mutation| mutation.id);
+
+    let mut tests = tests.iter().map(|test| test.desc.name.as_slice().to_owned()).collect::<Vec<_>>();
+    tests.sort_unstable();
+
+    SavedResults { tests, mutations }
+}
+
+fn passes_fail_policy(results: &MutationAnalysisResults, fail_on: config::FailOn, fail_under: Option<f64>) -> bool {
+    let mut passes = match fail_on {
+        config::FailOn::Undetected => results.all_test_runs_failed_successfully,
+        config::FailOn::TimedOut => results.all_test_runs_failed_successfully && results.timed_out_mutations_count == 0,
+        config::FailOn::Never => true,
+    };
+
+    if let Some(fail_under) = fail_under && results.total_mutations_count >= 1 {
+        let score = (results.total_mutations_count - results.undetected_mutations_count) as f64 / results.total_mutations_count as f64 * 100_f64;
+        if score < fail_under {
+            log::error!("\nmutation score of {score:.2}% is below the threshold of {fail_under}%");
+            passes = false;
+        }
+    }
+
+    passes
+}
+
+fn parse_child_process_limits(args: &[&str]) -> test_runner::ChildProcessLimits {
+    test_runner::ChildProcessLimits {
+        max_concurrent_processes: args.iter().flat_map(|arg| arg.strip_prefix("--max-test-processes=")).next()
+            .map(|max_processes_arg| {
+                max_processes_arg.parse::<NonZeroUsize>().ok()
+                    .expect("maximum number of test processes must be a positive integer")
+            }),
+        max_memory: args.iter().flat_map(|arg| arg.strip_prefix("--test-memory-limit=")).next()
+            .map(|memory_limit_arg| {
+                if !cfg!(unix) { panic!("test memory limits are only supported on Unix platforms"); }
+                match memory_limit_arg.parse::<u64>() {
+                    // The memory limit is given in megabytes.
+                    Ok(memory_limit) if memory_limit >= 1 => memory_limit * 1024 * 1024,
+                    _ => panic!("test memory limit must be a positive integer"),
+                }
+            }),
+    }
+}
+
+pub fn mutest_main<S: SubstMap>(args: &[&str], mut tests: Vec<test::TestDescAndFn>, mutants: &'static [&'static MutantMeta<S>], active_mutant_handle: &'static ActiveMutantHandle<S>) {
     let mode = match () {
         _ if let Some(flakes_arg) = args.iter().flat_map(|arg| arg.strip_prefix("--flakes=")).next() => {
             let Some(iterations_count) = flakes_arg.parse::<usize>().ok() else {
@@ -514,6 +1052,29 @@ pub fn mutest_main<S: SubstMap>(args: &[&str], tests: Vec<test::TestDescAndFn>,
         _ => config::Mode::Evaluate,
     };
 
+    let baseline_path = args.iter().flat_map(|arg| arg.strip_prefix("--baseline=")).next().map(Path::new);
+    let update_baseline = args.contains(&"--update-baseline");
+    if update_baseline && baseline_path.is_none() {
+        panic!("updating the baseline requires a baseline path");
+    }
+
+    let mutant_selection = match MutantSelection::from_args(args) {
+        Ok(mutant_selection) => mutant_selection,
+        Err(e) => panic!("{e}"),
+    };
+    // The baseline records all surviving mutations, which would be lost for the mutations which are not evaluated.
+    if update_baseline && !mutant_selection.is_empty() {
+        panic!("updating the baseline requires evaluating all mutants");
+    }
+
+    let fail_under = args.iter().flat_map(|arg| arg.strip_prefix("--fail-under=")).next()
+        .map(|fail_under_arg| {
+            match fail_under_arg.parse::<f64>() {
+                Ok(fail_under) if (0_f64..=100_f64).contains(&fail_under) => fail_under,
+                _ => panic!("mutation score threshold must be a percentage between 0 and 100"),
+            }
+        });
+
     let opts = Options {
         mode,
         verbosity: args.iter().filter(|&arg| *arg == "-v").count() as u8,
@@ -521,16 +1082,133 @@ pub fn mutest_main<S: SubstMap>(args: &[&str], tests: Vec<test::TestDescAndFn>,
         print_opts: config::PrintOptions {
             detection_matrix: args.contains(&"--print=detection-matrix").then_some(()),
             subsumption_matrix: args.contains(&"--print=subsumption-matrix").then_some(()),
+            target_scores: args.contains(&"--print=target-scores").then_some(()),
+            file_scores: args.contains(&"--print=file-scores").then_some(()),
+        },
+        test_filter: TestFilter::from_args(args),
+        mutant_selection,
+        output_format: match args.iter().flat_map(|arg| arg.strip_prefix("--format=")).next() {
+            Some("human") | None => config::OutputFormat::Human,
+            Some("github") => config::OutputFormat::Github,
+            Some(_) => panic!("invalid output format: must be one of `human`, `github`"),
         },
         exhaustive: args.contains(&"--exhaustive"),
+        tui: args.contains(&"--tui"),
+        max_tests_per_mutation: args.iter().flat_map(|arg| arg.strip_prefix("--max-tests-per-mutation=")).next()
+            .map(|max_tests_arg| {
+                match max_tests_arg.parse::<usize>() {
+                    Ok(max_tests) if max_tests >= 1 => max_tests,
+                    _ => panic!("maximum number of tests per mutation must be a positive integer"),
+                }
+            }),
         test_timeout: config::TestTimeout::Auto,
+        test_timeout_slack: args.iter().flat_map(|arg| arg.strip_prefix("--test-timeout-slack=")).next()
+            .map(|slack_arg| {
+                match slack_arg.parse::<f64>() {
+                    Ok(slack) if slack >= 0_f64 => slack,
+                    _ => panic!("test timeout slack must be a non-negative number"),
+                }
+            })
+            .unwrap_or(0.1),
         test_ordering: config::TestOrdering::ExecTime,
         use_thread_pool: args.contains(&"--use-thread-pool"),
+        child_process_limits: parse_child_process_limits(args),
+        leak_policy: match args.iter().flat_map(|arg| arg.strip_prefix("--check-leaks=")).next() {
+            Some("isolate") => Some(config::LeakPolicy::Isolate),
+            Some("report") => Some(config::LeakPolicy::Report),
+            Some(_) => panic!("invalid leak policy: must be one of `isolate`, `report`"),
+            None => None,
+        },
+        property_test_cases: args.iter().flat_map(|arg| arg.strip_prefix("--property-test-cases=")).next()
+            .map(|cases_arg| {
+                match cases_arg.parse::<usize>() {
+                    Ok(cases) if cases >= 1 => cases,
+                    _ => panic!("property test cases count must be a positive integer"),
+                }
+            }),
+        bench_slowdown_threshold: args.iter().flat_map(|arg| arg.strip_prefix("--bench-slowdown-threshold=")).next()
+            .map(|threshold_arg| {
+                match threshold_arg.parse::<f64>() {
+                    Ok(threshold) if threshold >= 1_f64 => threshold,
+                    _ => panic!("benchmark slowdown threshold must be a number greater than or equal to 1"),
+                }
+            }),
+        perf_regression_factor: args.iter().flat_map(|arg| arg.strip_prefix("--perf-regression-factor=")).next()
+            .map(|factor_arg| {
+                match factor_arg.parse::<f64>() {
+                    Ok(factor) if factor > 1_f64 => factor,
+                    _ => panic!("performance regression factor must be a number greater than 1"),
+                }
+            }),
+        flaky_retries_count: args.iter().flat_map(|arg| arg.strip_prefix("--flaky-retries=")).next()
+            .map(|retries_arg| {
+                match retries_arg.parse::<usize>() {
+                    Ok(retries_count) if retries_count >= 1 => retries_count,
+                    _ => panic!("flaky test retries count must be a positive integer"),
+                }
+            }),
+        baseline: baseline_path.map(|baseline_path| {
+            // A missing baseline is only created when updating the baseline.
+            if update_baseline && !baseline_path.exists() { return Baseline::default(); }
+
+            match Baseline::read(baseline_path) {
+                Ok(baseline) => baseline,
+                Err(e) => panic!("cannot read baseline `{}`: {e}", baseline_path.display()),
+            }
+        }),
+        update_baseline_path: baseline_path.filter(|_| update_baseline).map(Path::to_owned),
+        history_path: args.iter().flat_map(|arg| arg.strip_prefix("--history-file=")).next().map(PathBuf::from),
+        sarif_path: args.iter().flat_map(|arg| arg.strip_prefix("--sarif=")).next().map(PathBuf::from),
+        results_path: args.iter().flat_map(|arg| arg.strip_prefix("--results-file=")).next().map(PathBuf::from),
+        test_timings_path: args.iter().flat_map(|arg| arg.strip_prefix("--test-timings-file=")).next().map(PathBuf::from),
+        flakes_min_confidence: args.iter().flat_map(|arg| arg.strip_prefix("--flakes-min-confidence=")).next()
+            .map(|min_confidence_arg| {
+                match min_confidence_arg.parse::<usize>() {
+                    Ok(min_confidence) if min_confidence >= 1 => min_confidence,
+                    _ => panic!("flakes minimum confidence must be a positive integer"),
+                }
+            }),
+        verdict_cache_path: args.iter().flat_map(|arg| arg.strip_prefix("--verdict-cache-file=")).next().map(PathBuf::from),
+        flakiness_report_path: args.iter().flat_map(|arg| arg.strip_prefix("--flakiness-report=")).next().map(PathBuf::from),
+        fail_on_flaky_tests: args.contains(&"--fail-on-flaky-tests"),
+        fail_on: match args.iter().flat_map(|arg| arg.strip_prefix("--fail-on=")).next() {
+            Some("undetected") => config::FailOn::Undetected,
+            Some("timed-out") => config::FailOn::TimedOut,
+            Some("never") => config::FailOn::Never,
+            Some(_) => panic!("invalid fail-on policy: must be one of `undetected`, `timed-out`, `never`"),
+            // A score threshold replaces the default policy of failing on any undetected mutation.
+            None if fail_under.is_some() => config::FailOn::Never,
+            None => config::FailOn::Undetected,
+        },
+        fail_under,
     };
 
+    tests.retain(|test| opts.test_filter.matches(test.desc.name.as_slice()));
+
+    // Benchmarks are only evaluated if a slowdown threshold is given.
+    if opts.bench_slowdown_threshold.is_none() {
+        tests.retain(|test| !matches!(test.testfn, test::TestFn::StaticBenchFn(_)));
+    }
+
+    // Memory limits force all tests to be evaluated in isolated child processes, in which benchmarks are ignored.
+    if opts.child_process_limits.max_memory.is_some() && tests.iter().any(|test| matches!(test.testfn, test::TestFn::StaticBenchFn(_))) {
+        log::warn!("benchmarks are ignored with a test memory limit, as they cannot be evaluated in separate processes");
+    }
+
+    // Property tests are configured before profiling, so that their timeouts are deduced from the reduced number of
+    // cases they run against mutants.
+    let property_test_names = property_tests::property_tests();
+    let property_tests_count = tests.iter().filter(|test| property_test_names.contains(&test.desc.name.as_slice())).count();
+    if let Some(property_test_cases) = opts.property_test_cases.or((property_tests_count >= 1).then_some(property_tests::DEFAULT_PROPERTY_TEST_CASES)) {
+        property_tests::configure(property_test_cases);
+        if property_tests_count >= 1 {
+            log::info!("running {property_tests_count} property tests with {property_test_cases} cases");
+        }
+    }
+
     let t_start = Instant::now();
 
-    println!("profiling reference test run");
+    log::info!("profiling reference test run");
     let t_test_profiling_start = Instant::now();
     let mut profiled_tests = match profile_tests(tests) {
         Ok(tests) => tests,
@@ -541,9 +1219,9 @@ pub fn mutest_main<S: SubstMap>(args: &[&str], tests: Vec<test::TestDescAndFn>,
     let failed_profiled_tests = profiled_tests.iter().filter(|test| !matches!(test.result, test_runner::TestResult::Ignored | test_runner::TestResult::Ok)).collect::<Vec<_>>();
     if !failed_profiled_tests.is_empty() {
         for failed_profiled_test in failed_profiled_tests {
-            println!("  test {} ... fail", failed_profiled_test.test.desc.name.as_slice());
+            log::error!("  test {} ... fail", failed_profiled_test.test.desc.name.as_slice());
         }
-        println!("not all tests passed, cannot continue");
+        log::error!("not all tests passed, cannot continue");
         process::exit(ERROR_EXIT_CODE);
     }
 
@@ -551,19 +1229,46 @@ pub fn mutest_main<S: SubstMap>(args: &[&str], tests: Vec<test::TestDescAndFn>,
 
     for profiled_test in &profiled_tests {
         match profiled_test.exec_time {
-            Some(exec_time) => println!("{} took {:?}", profiled_test.test.desc.name.as_slice(), exec_time),
-            None => println!("{} was not profiled", profiled_test.test.desc.name.as_slice()),
+            Some(exec_time) => log::info!("{} took {:?}", profiled_test.test.desc.name.as_slice(), exec_time),
+            None if let test_runner::TestResult::Ignored = profiled_test.result => {
+                log::info!("{} was {}", profiled_test.test.desc.name.as_slice(), display_ignored(&profiled_test.test.desc));
+            }
+            None => log::info!("{} was not profiled", profiled_test.test.desc.name.as_slice()),
         }
     }
-    println!();
+    log::info!("");
+
+    if let Some(test_timings_path) = &opts.test_timings_path {
+        let test_timings = TestTimings {
+            exec_times: profiled_tests.iter()
+                .filter_map(|profiled_test| Some((profiled_test.test.desc.name.as_slice().to_owned(), profiled_test.exec_time?)))
+                .collect(),
+        };
+        if let Err(e) = test_timings.write(test_timings_path) {
+            log::error!("cannot write test timings `{}`: {e}", test_timings_path.display());
+        }
+    }
+
+    let profiled_exec_times = profiled_tests.iter()
+        .filter_map(|profiled_test| Some((profiled_test.test.desc.name.clone(), profiled_test.exec_time?)))
+        .collect::<HashMap<_, _>>();
 
     let tests = profiled_tests.into_iter()
         .filter(|profiled_test| !matches!(profiled_test.result, test_runner::TestResult::Ignored))
         .map(|profiled_test| {
             let test::TestDescAndFn { desc, testfn: test_fn } = profiled_test.test;
 
-            let auto_test_timeout = profiled_test.exec_time
-                .map(|d| d + Ord::max(d.mul_f32(0.1), Duration::from_secs(1)));
+            let bench_slowdown_limit = match test_fn {
+                test::TestFn::StaticBenchFn(_) => {
+                    let bench_slowdown_threshold = opts.bench_slowdown_threshold.expect("benchmarks are only evaluated with a slowdown threshold");
+                    profiled_test.exec_time.map(|d| d.mul_f64(bench_slowdown_threshold))
+                }
+                _ => None,
+            };
+
+            // Benchmarks must be allowed to run until their slowdown limit, rather than timing out before it.
+            let auto_test_timeout = bench_slowdown_limit.or(profiled_test.exec_time)
+                .map(|d| d + Ord::max(d.mul_f64(opts.test_timeout_slack), Duration::from_secs(1)));
 
             let timeout = match opts.test_timeout {
                 config::TestTimeout::None => None,
@@ -571,7 +1276,7 @@ pub fn mutest_main<S: SubstMap>(args: &[&str], tests: Vec<test::TestDescAndFn>,
                 config::TestTimeout::Explicit(test_timeout) => {
                     if let Some(auto_test_timeout) = auto_test_timeout {
                         if test_timeout < auto_test_timeout {
-                            println!("warning: explicit test timeout is less than the recommended test timeout based on the profiled reference run\n");
+                            log::warn!("explicit test timeout is less than the recommended test timeout based on the profiled reference run\n");
                         }
                     }
 
@@ -579,7 +1284,7 @@ pub fn mutest_main<S: SubstMap>(args: &[&str], tests: Vec<test::TestDescAndFn>,
                 }
             };
 
-            test_runner::Test { desc, test_fn, timeout }
+            test_runner::Test { desc, test_fn, timeout, bench_slowdown_limit }
         })
         .collect::<Vec<_>>();
 
@@ -588,13 +1293,87 @@ pub fn mutest_main<S: SubstMap>(args: &[&str], tests: Vec<test::TestDescAndFn>,
         ThreadPool::new(concurrency, Some("test_thread_pool".to_owned()), None)
     });
     if let Some(thread_pool) = &thread_pool {
-        println!("using thread pool of size {} for running tests", thread_pool.max_thread_count());
-        println!();
+        log::info!("using thread pool of size {} for running tests\n", thread_pool.max_thread_count());
     }
 
+    // Verdicts are only recorded if they can be used to skip test runs.
+    let mut verdict_cache = opts.flakes_min_confidence.map(|_| {
+        let Some(verdict_cache_path) = &opts.verdict_cache_path else { return VerdictCache::default(); };
+
+        let build_fingerprint = match env::current_exe().and_then(|harness_path| verdict_cache::build_fingerprint(&harness_path)) {
+            Ok(build_fingerprint) => build_fingerprint,
+            Err(e) => {
+                log::error!("cannot fingerprint test harness for verdict cache: {e}");
+                process::exit(ERROR_EXIT_CODE);
+            }
+        };
+
+        // A missing verdict cache is created at the end of the run.
+        if !verdict_cache_path.exists() { return VerdictCache::new(build_fingerprint); }
+
+        match VerdictCache::read(verdict_cache_path) {
+            Ok(verdict_cache) if verdict_cache.is_valid_for_build(build_fingerprint) => verdict_cache,
+            Ok(_) => {
+                log::info!("discarding verdict cache `{}` recorded with a different build of the test harness\n", verdict_cache_path.display());
+                VerdictCache::new(build_fingerprint)
+            }
+            Err(e) => {
+                log::error!("cannot read verdict cache `{}`: {e}", verdict_cache_path.display());
+                process::exit(ERROR_EXIT_CODE);
+            }
+        }
+    });
+    let write_verdict_cache = |verdict_cache: &Option<VerdictCache>| {
+        let (Some(verdict_cache), Some(verdict_cache_path)) = (verdict_cache, &opts.verdict_cache_path) else { return; };
+        if let Err(e) = verdict_cache.write(verdict_cache_path) {
+            log::error!("cannot write verdict cache `{}`: {e}", verdict_cache_path.display());
+        }
+    };
+
     match opts.mode {
         config::Mode::Evaluate => {
-            let results = run_mutation_analysis(&opts, &tests, mutants, active_mutant_handle, thread_pool);
+            // Status messages would be drawn over the terminal UI, and are only appended to the log file.
+            if opts.tui { log::suppress_stdout(true); }
+            let mut tui = opts.tui.then(|| Tui::new(mutants.iter().flat_map(|mutant| {
+                mutant.mutations.iter().copied().filter(|mutation| opts.mutant_selection.includes(mutant.id, mutation.id))
+            })));
+            let results = run_mutation_analysis(&opts, &tests, &profiled_exec_times, mutants, active_mutant_handle, thread_pool.clone(), tui.as_mut(), verdict_cache.as_mut());
+            write_verdict_cache(&verdict_cache);
+            // Time spent in the terminal UI is not reported.
+            let total_duration = t_start.elapsed();
+
+            // The results are printed once the user quits the terminal UI, so that they remain on screen.
+            if let Some(tui) = &mut tui {
+                let rerun = |mutation: &'static MutationMeta| {
+                    let Some(mutant) = mutants.iter().find(|mutant| mutant.mutations.iter().any(|m| m.id == mutation.id)) else { unreachable!() };
+
+                    // Like with `--simulate`, mutations batched into a mutant with other mutations are rerun in a
+                    // singleton mutant of their own, derived from the substitutions of the batched mutant.
+                    let is_batched = mutant.mutations.len() > 1;
+                    // The substitutions of compile-time mutants are fixed in their executables, so batched mutations
+                    // cannot be rerun in isolation.
+                    if is_batched && CFG_MUTANTS.load(atomic::Ordering::Relaxed) {
+                        return Err("mutation is batched into a compile-time mutant with other mutations, disable mutation batching".to_owned());
+                    }
+                    let mut substitutions = mutant.substitutions.clone();
+                    if is_batched { substitutions.retain_mutation(mutation.id); }
+
+                    // SAFETY: All previous test runs completed, and lingering tests of previous test runs were abandoned.
+                    unsafe { reset_and_activate_mutant(active_mutant_handle, Some(substitutions)); }
+
+                    // Like with `--simulate`, all tests reaching the mutation are evaluated.
+                    let run_results = run_tests(clone_tests(&tests), mutant, &[mutation], true, None, None, &mut FlakyTests::new(), None, false, is_batched.then_some(mutation.id), opts.child_process_limits, thread_pool.clone(), &mut vec![]);
+
+                    // No mutant remains active between reruns, and global state is reset after the rerun mutant.
+                    // SAFETY: All test runs of the rerun completed.
+                    unsafe { reset_and_activate_mutant(active_mutant_handle, None); }
+
+                    let mut run_results = run_results.map_err(|e| match e {})?;
+                    Ok(run_results.remove(&mutation.id).expect("mutation result slot not allocated"))
+                };
+                tui.explore(&tests, rerun);
+            }
+            log::suppress_stdout(false);
 
             if let Some(()) = &opts.print_opts.detection_matrix {
                 print_mutation_detection_matrix(&results.mutation_detection_matrix, &tests, !opts.exhaustive);
@@ -605,17 +1384,75 @@ pub fn mutest_main<S: SubstMap>(args: &[&str], tests: Vec<test::TestDescAndFn>,
                 print_mutation_subsumption_matrix(&mutation_subsumption_matrix, mutants, !opts.exhaustive);
             }
 
+            if let Some(()) = &opts.print_opts.target_scores {
+                print_mutation_scores("mutation scores of targets", &results.mutation_target_stats);
+            }
+
+            if let Some(()) = &opts.print_opts.file_scores {
+                print_mutation_scores("mutation scores of files", &results.mutation_file_stats);
+            }
+
             print_mutation_analysis_epilogue(&results, opts.verbosity);
 
+            if let Some(baseline) = &opts.baseline && opts.update_baseline_path.is_none() {
+                print_baseline_epilogue(baseline, &results);
+            }
+
             if opts.report_timings {
-                println!("\nfinished in {total:.2?} (profiling {profiling:.2?}; tests {tests:.2?})",
-                    total = t_start.elapsed(),
+                log::info!("\nfinished in {total:.2?} (profiling {profiling:.2?}; tests {tests:.2?})",
+                    total = total_duration,
                     profiling = test_profiling_duration,
                     tests = results.duration,
                 );
             }
 
-            if !results.all_test_runs_failed_successfully {
+            if let Some(history_path) = &opts.history_path {
+                if let Err(e) = History::append(history_path, &mk_history_record(&results)) {
+                    log::error!("cannot append to history `{}`: {e}", history_path.display());
+                    process::exit(ERROR_EXIT_CODE);
+                }
+            }
+
+            if let Some(sarif_path) = &opts.sarif_path {
+                let mut undetected_mutations = results.undetected_mutations.clone();
+                undetected_mutations.sort_by_key(|mutation| mutation.id);
+
+                let is_accepted = |mutation: &MutationMeta| opts.baseline.as_ref().is_some_and(|baseline| baseline.contains(mutation));
+                if let Err(e) = sarif::write(sarif_path, &undetected_mutations, is_accepted) {
+                    log::error!("cannot write SARIF log `{}`: {e}", sarif_path.display());
+                    process::exit(ERROR_EXIT_CODE);
+                }
+            }
+
+            if let Some(results_path) = &opts.results_path {
+                if let Err(e) = mk_saved_results(&opts, &tests, mutants, &results).write(results_path) {
+                    log::error!("cannot write results `{}`: {e}", results_path.display());
+                    process::exit(ERROR_EXIT_CODE);
+                }
+            }
+
+            if let Some(update_baseline_path) = &opts.update_baseline_path {
+                let mut undetected_mutations = results.undetected_mutations.clone();
+                undetected_mutations.sort_by_key(|mutation| mutation.id);
+
+                if let Err(e) = Baseline::write(update_baseline_path, &undetected_mutations) {
+                    log::error!("cannot write baseline `{}`: {e}", update_baseline_path.display());
+                    process::exit(ERROR_EXIT_CODE);
+                }
+                log::info!("\nupdated baseline `{path}` with {count} accepted {descr}",
+                    path = update_baseline_path.display(),
+                    count = undetected_mutations.len(),
+                    descr = match undetected_mutations.len() {
+                        1 => "mutation",
+                        _ => "mutations",
+                    },
+                );
+
+                // All surviving mutations of the run are accepted by the updated baseline.
+                return;
+            }
+
+            if !passes_fail_policy(&results, opts.fail_on, opts.fail_under) {
                 process::exit(ERROR_EXIT_CODE);
             }
         }
@@ -626,10 +1463,11 @@ pub fn mutest_main<S: SubstMap>(args: &[&str], tests: Vec<test::TestDescAndFn>,
             let mut results = Vec::with_capacity(iterations_count);
 
             for iteration in 1..=iterations_count {
-                println!("running iteration {iteration} out of {iterations_count}");
-                println!();
+                log::info!("running iteration {iteration} out of {iterations_count}\n");
 
-                let iteration_results = run_mutation_analysis(&opts, &tests, mutants, active_mutant_handle, thread_pool.clone());
+                let iteration_results = run_mutation_analysis(&opts, &tests, &profiled_exec_times, mutants, active_mutant_handle, thread_pool.clone(), None, verdict_cache.as_mut());
+                // The verdict cache is written after each iteration, so that interrupted runs can be resumed.
+                write_verdict_cache(&verdict_cache);
 
                 if let Some(()) = &opts.print_opts.detection_matrix {
                     print_mutation_detection_matrix(&iteration_results.mutation_detection_matrix, &tests, !opts.exhaustive);
@@ -643,12 +1481,12 @@ pub fn mutest_main<S: SubstMap>(args: &[&str], tests: Vec<test::TestDescAndFn>,
                 print_mutation_analysis_epilogue(&iteration_results, opts.verbosity);
 
                 if opts.report_timings {
-                    println!("\nfinished in {tests:.2?}",
+                    log::info!("\nfinished in {tests:.2?}",
                         tests = iteration_results.duration,
                     );
                 }
 
-                println!();
+                log::info!("");
 
                 results.push(iteration_results);
             }
@@ -661,35 +1499,120 @@ pub fn mutest_main<S: SubstMap>(args: &[&str], tests: Vec<test::TestDescAndFn>,
 
             print_mutation_flakiness_epilogue(&mutation_flakiness_matrix, &tests);
 
-            println!("\nfinished in {total:.2?} (profiling {profiling:.2?}; iterations {iterations:.2?})",
+            if let Some(flakiness_report_path) = &opts.flakiness_report_path {
+                let mutations = mutants.iter().flat_map(|mutant| mutant.mutations.iter().copied()).collect::<Vec<_>>();
+                if let Err(e) = flakiness::write_report(flakiness_report_path, &mutation_flakiness_matrix, &mutations, iterations_count) {
+                    log::error!("cannot write flakiness report `{}`: {e}", flakiness_report_path.display());
+                }
+            }
+
+            log::info!("\nfinished in {total:.2?} (profiling {profiling:.2?}; iterations {iterations:.2?})",
                 total = t_start.elapsed(),
                 profiling = test_profiling_duration,
                 iterations = t_flaky_iterations_start.elapsed(),
             );
+
+            if opts.fail_on_flaky_tests {
+                let flaky_test_names = mutation_flakiness_matrix.flaky_test_names();
+                if !flaky_test_names.is_empty() {
+                    log::error!("\nthe following tests produced different results across iterations:");
+                    for flaky_test_name in flaky_test_names {
+                        log::error!("  {flaky_test_name}");
+                    }
+                    process::exit(ERROR_EXIT_CODE);
+                }
+            }
         }
     }
 }
 
 const MUTEST_ISOLATED_WORKER_MUTANT_ID: &str = "__MUTEST_ISOLATED_WORKER_MUTANT_ID";
+/// Id of the only mutation of the mutant to apply in the isolated worker, if the other mutations of the mutant are not
+/// applied (e.g. when simulating a mutation of a batched mutant).
+const MUTEST_ISOLATED_WORKER_MUTATION_ID: &str = "__MUTEST_ISOLATED_WORKER_MUTATION_ID";
+
+/// Set if the program was built with compile-time mutants, see [`mutest_main_static_cfg`].
+static CFG_MUTANTS: AtomicBool = AtomicBool::new(false);
+
+/// Path of the separately built executable of a compile-time mutant, next to the current executable.
+fn cfg_mutant_exe_path(mutant_id: u32) -> PathBuf {
+    let current_exe = env::current_exe().expect("cannot resolve test executable path");
+
+    let mut file_name = current_exe.file_stem().expect("test executable path has no file name").to_owned();
+    file_name.push(format!("-mutant-{mutant_id}"));
+    if let Some(extension) = current_exe.extension() {
+        file_name.push(".");
+        file_name.push(extension);
+    }
+
+    current_exe.with_file_name(file_name)
+}
+
+/// Run the test in the executable of the compile-time mutant, in place of the current process where supported.
+fn exec_cfg_mutant_worker<S: SubstMap>(mutant: &MutantMeta<S>, test: &test::TestDescAndFn) -> ! {
+    let mutant_id = mutant.id;
+
+    // The substitutions of the mutations are fixed in the executable of the mutant, so a single mutation of a batched
+    // mutant cannot be applied on its own (see `SubstMap::retain_mutation`). Callers must reject such runs up front.
+    if let Ok(mutation_id) = env::var(MUTEST_ISOLATED_WORKER_MUTATION_ID) && mutant.mutations.len() > 1 {
+        panic!("cannot apply only mutation {mutation_id} of compile-time mutant {mutant_id}, which is batched with other mutations");
+    }
+
+    let mut cmd = process::Command::new(cfg_mutant_exe_path(mutant_id));
+    cmd.env(test_runner::TEST_SUBPROCESS_INVOCATION, test.desc.name.as_slice());
+    // The mutations are already applied in the executable of the mutant.
+    cmd.env_remove(MUTEST_ISOLATED_WORKER_MUTANT_ID);
+    cmd.env_remove(MUTEST_ISOLATED_WORKER_MUTATION_ID);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+
+        let e = cmd.exec();
+        panic!("cannot run executable of mutant {mutant_id}: {e}");
+    }
+
+    #[cfg(not(unix))]
+    {
+        let exit_status = cmd.status().expect(&format!("cannot run executable of mutant {mutant_id}"));
+        process::exit(exit_status.code().unwrap_or(ERROR_EXIT_CODE));
+    }
+}
 
 fn mutest_isolated_worker<S: SubstMap>(test: test::TestDescAndFn, mutants: &'static [&'static MutantMeta<S>], active_mutant_handle: &'static ActiveMutantHandle<S>) -> ! {
-    let mutant_id = env::var(MUTEST_ISOLATED_WORKER_MUTANT_ID).unwrap()
-        .parse::<u32>().expect(&format!("{MUTEST_ISOLATED_WORKER_MUTANT_ID} must be a number"));
+    // Tests are run without any mutations applied if no mutant is specified (e.g. when retrying flaky tests).
+    if let Ok(mutant_id) = env::var(MUTEST_ISOLATED_WORKER_MUTANT_ID) {
+        let mutant_id = mutant_id.parse::<u32>().expect(&format!("{MUTEST_ISOLATED_WORKER_MUTANT_ID} must be a number"));
 
-    let Some(mutant) = mutants.iter().find(|m| m.id == mutant_id) else {
-        panic!("{MUTEST_ISOLATED_WORKER_MUTANT_ID} must be a valid id");
-    };
+        let Some(mutant) = mutants.iter().find(|m| m.id == mutant_id) else {
+            panic!("{MUTEST_ISOLATED_WORKER_MUTANT_ID} must be a valid id");
+        };
 
-    // SAFETY: No other thread is running yet, no one else is reading from the handle yet.
-    unsafe { active_mutant_handle.replace(Some(mutant.substitutions.clone())); }
+        if CFG_MUTANTS.load(atomic::Ordering::Relaxed) {
+            exec_cfg_mutant_worker(mutant, &test);
+        }
 
-    test_runner::run_test_in_spawned_subprocess(test);
+        let mut substitutions = mutant.substitutions.clone();
+        if let Ok(mutation_id) = env::var(MUTEST_ISOLATED_WORKER_MUTATION_ID) {
+            let mutation_id = mutation_id.parse::<u32>().expect(&format!("{MUTEST_ISOLATED_WORKER_MUTATION_ID} must be a number"));
+            substitutions.retain_mutation(mutation_id);
+        }
+
+        // SAFETY: No other thread is running yet, no one else is reading from the handle yet.
+        unsafe { active_mutant_handle.replace(Some(substitutions)); }
+    }
+
+    let span_map = mutants.iter().flat_map(|mutant| mutant.mutations).next().map(|mutation| mutation.tables.span_map);
+    test_runner::run_test_in_spawned_subprocess(test, span_map);
 }
 
-fn mutest_simulate_main<S: SubstMap>(args: &[&str], tests: Vec<test::TestDescAndFn>, mutant: &'static MutantMeta<S>, active_mutant_handle: &'static ActiveMutantHandle<S>) {
-    let _verbosity = args.iter().filter(|&arg| *arg == "-v").count() as u8;
+fn mutest_simulate_main<S: SubstMap>(args: &[&str], mut tests: Vec<test::TestDescAndFn>, mutant: &'static MutantMeta<S>, mutation: &'static MutationMeta, active_mutant_handle: &'static ActiveMutantHandle<S>) {
+    let test_filter = TestFilter::from_args(args);
+    tests.retain(|test| test_filter.matches(test.desc.name.as_slice()));
+
     let report_timings = args.contains(&"--timings");
     let use_thread_pool = args.contains(&"--use-thread-pool");
+    let child_process_limits = parse_child_process_limits(args);
 
     let t_start = Instant::now();
 
@@ -698,25 +1621,33 @@ fn mutest_simulate_main<S: SubstMap>(args: &[&str], tests: Vec<test::TestDescAnd
         ThreadPool::new(concurrency, Some("test_thread_pool".to_owned()), None)
     });
 
-    print!("running {} tests", tests.len());
-    if let Some(thread_pool) = &thread_pool {
-        print!(" using thread pool of size {}", thread_pool.max_thread_count());
+    match &thread_pool {
+        Some(thread_pool) => log::info!("running {} tests using thread pool of size {}", tests.len(), thread_pool.max_thread_count()),
+        None => log::info!("running {} tests", tests.len()),
     }
-    println!();
 
     let total_tests_count = tests.len();
     let mut failed_tests_count = 0;
     let mut ignored_tests_count = 0;
 
+    // Mutations batched into a mutant with other mutations are simulated in a singleton mutant of their own, derived
+    // from the substitutions of the batched mutant.
+    let is_batched = mutant.mutations.len() > 1;
+    let mut substitutions = mutant.substitutions.clone();
+    if is_batched { substitutions.retain_mutation(mutation.id); }
+
     // SAFETY: No other thread is running yet, no one else is reading from the handle yet.
-    unsafe { active_mutant_handle.replace(Some(mutant.substitutions.clone())); }
+    unsafe { active_mutant_handle.replace(Some(substitutions)); }
 
+    // Benchmarks are not evaluated when simulating mutations, as there is no reference run to compare them against.
     let tests_to_run = tests.iter()
+        .filter(|test| !matches!(test.testfn, test::TestFn::StaticBenchFn(_)))
         .map(|test| {
             test_runner::Test {
                 desc: test.desc.clone(),
                 test_fn: make_owned_test_fn(&test.testfn),
                 timeout: None,
+                bench_slowdown_limit: None,
             }
         })
         .collect::<Vec<_>>();
@@ -726,18 +1657,18 @@ fn mutest_simulate_main<S: SubstMap>(args: &[&str], tests: Vec<test::TestDescAnd
             test_runner::TestEvent::Result(test) => {
                 match test.result {
                     test_runner::TestResult::Ignored => {
-                        println!("test {} ... \x1b[1;33mignored\x1b[0m", test.desc.name.as_slice());
+                        log::info!("test {} ... \x1b[1;33m{}\x1b[0m", test.desc.name.as_slice(), display_ignored(&test.desc));
                         ignored_tests_count += 1;
                     }
 
                     test_runner::TestResult::Ok => {
-                        println!("test {} ... \x1b[1;32mok\x1b[0m", test.desc.name.as_slice());
+                        log::info!("test {} ... \x1b[1;32mok\x1b[0m", test.desc.name.as_slice());
                     }
 
                     | test_runner::TestResult::Failed
                     | test_runner::TestResult::FailedMsg(_)
                     | test_runner::TestResult::CrashedMsg(_) => {
-                        println!("test {} ... \x1b[1;31mFAILED\x1b[0m", test.desc.name.as_slice());
+                        log::info!("test {} ... \x1b[1;31mFAILED\x1b[0m", test.desc.name.as_slice());
                         failed_tests_count += 1;
                     }
 
@@ -750,14 +1681,19 @@ fn mutest_simulate_main<S: SubstMap>(args: &[&str], tests: Vec<test::TestDescAnd
         Ok(test_runner::Flow::Continue)
     };
 
-    let test_run_strategy = match mutant.is_unsafe() {
+    // Memory limits can only be applied to tests run in isolated child processes.
+    // Compile-time mutants can only be evaluated in their own executables, which are run as isolated child processes.
+    let is_unsafe = !matches!(mutation.safety, MutationSafety::Safe);
+    let test_run_strategy = match is_unsafe || child_process_limits.max_memory.is_some() || CFG_MUTANTS.load(atomic::Ordering::Relaxed) {
         false => test_runner::TestRunStrategy::InProcess(thread_pool),
         true => test_runner::TestRunStrategy::InIsolatedChildProcess({
             let mutant_id = mutant.id;
+            let mutation_id = mutation.id;
             Arc::new(move |cmd| {
                 cmd.env(MUTEST_ISOLATED_WORKER_MUTANT_ID, mutant_id.to_string());
+                if is_batched { cmd.env(MUTEST_ISOLATED_WORKER_MUTATION_ID, mutation_id.to_string()); }
             })
-        }),
+        }, child_process_limits),
     };
 
     match test_runner::run_tests(tests_to_run, on_test_event, test_run_strategy, false) {
@@ -765,7 +1701,7 @@ fn mutest_simulate_main<S: SubstMap>(args: &[&str], tests: Vec<test::TestDescAnd
         Err(_) => { process::exit(ERROR_EXIT_CODE); }
     }
 
-    println!("test result: {result}. {passed} passed; {failed} failed; {ignored} ignored",
+    log::info!("test result: {result}. {passed} passed; {failed} failed; {ignored} ignored",
         result = match failed_tests_count {
             0 => "\x1b[1;32mok\x1b[0m",
             _ => "\x1b[1;31mFAILED\x1b[0m",
@@ -776,7 +1712,7 @@ fn mutest_simulate_main<S: SubstMap>(args: &[&str], tests: Vec<test::TestDescAnd
     );
 
     if report_timings {
-        println!("\nfinished in {total:.2?}",
+        log::info!("\nfinished in {total:.2?}",
             total = t_start.elapsed(),
         );
     }
@@ -786,6 +1722,14 @@ fn mutest_simulate_main<S: SubstMap>(args: &[&str], tests: Vec<test::TestDescAnd
     }
 }
 
+/// Entry point of test harnesses built with compile-time mutants, where each mutant is built into a separate
+/// executable next to the test harness, with the substitutions of its mutations enabled by `cfg` flags.
+/// Tests are evaluated against a mutant by running them in the executable of the mutant.
+pub fn mutest_main_static_cfg<S: SubstMap>(tests: &[&test::TestDescAndFn], mutants: &'static [&'static MutantMeta<S>], active_mutant_handle: &'static ActiveMutantHandle<S>) {
+    CFG_MUTANTS.store(true, atomic::Ordering::Relaxed);
+    mutest_main_static(tests, mutants, active_mutant_handle)
+}
+
 pub fn mutest_main_static<S: SubstMap>(tests: &[&test::TestDescAndFn], mutants: &'static [&'static MutantMeta<S>], active_mutant_handle: &'static ActiveMutantHandle<S>) {
     if let Ok(test_name) = env::var(test_runner::TEST_SUBPROCESS_INVOCATION) {
         env::remove_var(test_runner::TEST_SUBPROCESS_INVOCATION);
@@ -799,20 +1743,80 @@ pub fn mutest_main_static<S: SubstMap>(tests: &[&test::TestDescAndFn], mutants:
 
     let args = env::args().collect::<Vec<_>>();
     let args = args.iter().map(String::as_ref).collect::<Vec<&str>>();
+
+    let verbosity = args.iter().filter(|&arg| *arg == "-v").count() as u8;
+    let log_file_path = args.iter().flat_map(|arg| arg.strip_prefix("--log-file=")).next().map(Path::new);
+    if let Err(e) = log::init(verbosity, log_file_path) {
+        let log_file_path = log_file_path.expect("log file error without log file");
+        log::error!("cannot open log file `{}`: {e}", log_file_path.display());
+        process::exit(ERROR_EXIT_CODE);
+    }
+
     let owned_tests = tests.iter().map(|test| make_owned_test_def(test)).collect::<Vec<_>>();
 
-    if let Some(mutation_id) = args.iter().flat_map(|arg| arg.strip_prefix("--simulate=")).next().and_then(|mutation_id| mutation_id.parse::<u32>().ok()) {
-        let Some(mutant) = mutants.iter().find(|mutant| mutant.mutations.iter().any(|mutation| mutation.id == mutation_id)) else {
-            println!("cannot find mutation with id {mutation_id}");
+    if let Some(simulate_arg) = args.iter().flat_map(|arg| arg.strip_prefix("--simulate=")).next() {
+        // Mutations are referred to either by their id, or by their stable id, which is printed as 16 hex digits.
+        let is_mutation = |mutation: &MutationMeta| match simulate_arg.len() {
+            16 => u64::from_str_radix(simulate_arg, 16).is_ok_and(|stable_id| mutation.stable_id == stable_id),
+            _ => simulate_arg.parse::<u32>().is_ok_and(|mutation_id| mutation.id == mutation_id),
+        };
+        let Some((mutant, mutation)) = mutants.iter()
+            .find_map(|&mutant| Some((mutant, *mutant.mutations.iter().find(|&&mutation| is_mutation(mutation))?)))
+        else {
+            log::error!("cannot find mutation `{simulate_arg}`");
             process::exit(ERROR_EXIT_CODE);
         };
-        if mutant.mutations.len() > 1 {
-            println!("cannot simulate mutation: mutation is not in a singleton mutant, disable mutation batching");
+        // The substitutions of compile-time mutants are fixed in their executables, so batched mutations cannot be
+        // simulated in isolation.
+        if mutant.mutations.len() > 1 && CFG_MUTANTS.load(atomic::Ordering::Relaxed) {
+            log::error!("cannot simulate mutation: mutation is batched into a compile-time mutant with other mutations, disable mutation batching");
             process::exit(ERROR_EXIT_CODE);
         }
 
-        return mutest_simulate_main(&args, owned_tests, mutant, active_mutant_handle);
+        return mutest_simulate_main(&args, owned_tests, mutant, mutation, active_mutant_handle);
     }
 
     mutest_main(&args, owned_tests, mutants, active_mutant_handle)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::parse_child_process_limits;
+
+    #[test]
+    fn test_child_process_limits_default_to_unlimited() {
+        let limits = parse_child_process_limits(&["--use-thread-pool"]);
+        assert_eq!(limits.max_concurrent_processes, None);
+        assert_eq!(limits.max_memory, None);
+    }
+
+    #[test]
+    fn test_child_process_limits_parsed_from_args() {
+        let limits = parse_child_process_limits(&["--max-test-processes=4"]);
+        assert_eq!(limits.max_concurrent_processes, NonZeroUsize::new(4));
+        assert_eq!(limits.max_memory, None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_child_process_memory_limit_parsed_in_megabytes() {
+        let limits = parse_child_process_limits(&["--test-memory-limit=512", "--max-test-processes=2"]);
+        assert_eq!(limits.max_concurrent_processes, NonZeroUsize::new(2));
+        assert_eq!(limits.max_memory, Some(512 * 1024 * 1024));
+    }
+
+    #[test]
+    #[should_panic(expected = "maximum number of test processes must be a positive integer")]
+    fn test_zero_max_test_processes_rejected() {
+        parse_child_process_limits(&["--max-test-processes=0"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[should_panic(expected = "test memory limit must be a positive integer")]
+    fn test_zero_test_memory_limit_rejected() {
+        parse_child_process_limits(&["--test-memory-limit=0"]);
+    }
+}
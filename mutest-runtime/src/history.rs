@@ -0,0 +1,266 @@
+//! History of mutation analysis results across runs, used to track the mutation score of a crate over time.
+//!
+//! Histories are stored in `mutest-history.toml` files, to which a record is appended after each run, using the
+//! following subset of TOML:
+//!
+//! ```toml
+//! [[run]]
+//! timestamp = 1760000000
+//! version = "0.1.0"
+//! commit = "9f3c1a0"
+//! score = 87.50
+//! total = 120
+//! undetected = 15
+//! timed_out = 2
+//! crashed = 0
+//! ops.math_op_add_sub_swap.total = 12
+//! ops.math_op_add_sub_swap.undetected = 3
+//! ```
+//!
+//! The `version` and `commit` keys are optional. The `score` is a percentage, and is omitted if the run had no
+//! mutations. Per-operator counts are keyed by the name of the operator.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::io::{self, Write as _};
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OpCounts {
+    pub total: usize,
+    pub undetected: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HistoryRecord {
+    /// Time of the run, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// Version of the analyzed crate.
+    pub version: Option<String>,
+    /// Commit of the analyzed code, if it is checked out from a Git repository.
+    pub commit: Option<String>,
+    pub score: Option<f64>,
+    pub total: usize,
+    pub undetected: usize,
+    pub timed_out: usize,
+    pub crashed: usize,
+    pub ops: BTreeMap<String, OpCounts>,
+}
+
+impl HistoryRecord {
+    /// Serialize the record as a `[[run]]` table, which can be appended to a history file.
+    pub fn to_toml(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "[[run]]").unwrap();
+        writeln!(out, "timestamp = {}", self.timestamp).unwrap();
+        if let Some(version) = &self.version { writeln!(out, "version = {}", escape_str(version)).unwrap(); }
+        if let Some(commit) = &self.commit { writeln!(out, "commit = {}", escape_str(commit)).unwrap(); }
+        if let Some(score) = self.score { writeln!(out, "score = {score:.2}").unwrap(); }
+        writeln!(out, "total = {}", self.total).unwrap();
+        writeln!(out, "undetected = {}", self.undetected).unwrap();
+        writeln!(out, "timed_out = {}", self.timed_out).unwrap();
+        writeln!(out, "crashed = {}", self.crashed).unwrap();
+        for (op_name, op_counts) in &self.ops {
+            writeln!(out, "ops.{op_name}.total = {}", op_counts.total).unwrap();
+            writeln!(out, "ops.{op_name}.undetected = {}", op_counts.undetected).unwrap();
+        }
+        out
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct History {
+    pub records: Vec<HistoryRecord>,
+}
+
+impl History {
+    pub fn parse(src: &str) -> Result<Self, String> {
+        let mut records = vec![];
+        let mut current_record: Option<HistoryRecord> = None;
+
+        for (i, line) in src.lines().enumerate() {
+            let line_no = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue; }
+
+            if line == "[[run]]" {
+                records.extend(current_record.take());
+                current_record = Some(Default::default());
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("line {line_no}: expected `[[run]]` or `key = value`"));
+            };
+            let Some(record) = &mut current_record else {
+                return Err(format!("line {line_no}: key outside of `[[run]]` table"));
+            };
+
+            let value = value.trim();
+            let parse_count = |key: &str| value.parse::<usize>().map_err(|_| format!("line {line_no}: `{key}` must be a non-negative integer"));
+            match key.trim() {
+                "timestamp" => record.timestamp = value.parse::<u64>().map_err(|_| format!("line {line_no}: `timestamp` must be a non-negative integer"))?,
+                "version" => record.version = Some(parse_str(value).ok_or_else(|| format!("line {line_no}: `version` must be a string"))?),
+                "commit" => record.commit = Some(parse_str(value).ok_or_else(|| format!("line {line_no}: `commit` must be a string"))?),
+                "score" => record.score = Some(value.parse::<f64>().map_err(|_| format!("line {line_no}: `score` must be a number"))?),
+                "total" => record.total = parse_count("total")?,
+                "undetected" => record.undetected = parse_count("undetected")?,
+                "timed_out" => record.timed_out = parse_count("timed_out")?,
+                "crashed" => record.crashed = parse_count("crashed")?,
+                key if let Some(op_key) = key.strip_prefix("ops.") => {
+                    let Some((op_name, count_key)) = op_key.rsplit_once('.') else {
+                        return Err(format!("line {line_no}: unknown key `{key}`"));
+                    };
+                    let op_counts = record.ops.entry(op_name.to_owned()).or_default();
+                    match count_key {
+                        "total" => op_counts.total = parse_count(key)?,
+                        "undetected" => op_counts.undetected = parse_count(key)?,
+                        _ => return Err(format!("line {line_no}: unknown key `{key}`")),
+                    }
+                }
+                key => return Err(format!("line {line_no}: unknown key `{key}`")),
+            }
+        }
+
+        records.extend(current_record.take());
+
+        Ok(Self { records })
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let src = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::parse(&src)
+    }
+
+    /// Append the record to the history file, creating the file if it does not exist.
+    pub fn append(path: &Path, record: &HistoryRecord) -> io::Result<()> {
+        let is_new = !path.exists();
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+
+        let mut out = String::new();
+        match is_new {
+            true => writeln!(out, "# Mutation analysis results appended by `cargo mutest run --history-file`.").unwrap(),
+            false => writeln!(out).unwrap(),
+        }
+        out.push_str(&record.to_toml());
+
+        file.write_all(out.as_bytes())
+    }
+
+    /// Print the mutation score of each run, with the change of the score compared to the previous run.
+    pub fn print_trend(&self) {
+        if self.records.is_empty() {
+            println!("no runs recorded");
+            return;
+        }
+
+        let mut previous_score = None;
+        for record in &self.records {
+            let revision = match (&record.version, &record.commit) {
+                (Some(version), Some(commit)) => format!(" (v{version}; {commit})"),
+                (Some(version), None) => format!(" (v{version})"),
+                (None, Some(commit)) => format!(" ({commit})"),
+                (None, None) => String::new(),
+            };
+
+            let score = match record.score {
+                Some(score) => format!("{score:.2}%"),
+                None => "none".to_owned(),
+            };
+            let delta = match (previous_score, record.score) {
+                (Some(previous_score), Some(score)) => format!(" ({:+.2})", score - previous_score),
+                _ => String::new(),
+            };
+
+            println!("{date}{revision}: {score:>7}{delta}. {undetected} undetected; {total} total",
+                date = format_utc_timestamp(record.timestamp),
+                undetected = record.undetected,
+                total = record.total,
+            );
+
+            if record.score.is_some() { previous_score = record.score; }
+        }
+    }
+}
+
+fn escape_str(str: &str) -> String {
+    format!("\"{}\"", str.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn parse_str(value: &str) -> Option<String> {
+    let inner = value.strip_prefix('"')?.strip_suffix('"')?;
+
+    let mut str = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                '\\' => str.push('\\'),
+                '"' => str.push('"'),
+                _ => return None,
+            },
+            '"' => return None,
+            c => str.push(c),
+        }
+    }
+
+    Some(str)
+}
+
+/// Format seconds since the Unix epoch as a UTC date and time (e.g. `2025-10-09 08:53:20`).
+fn format_utc_timestamp(timestamp: u64) -> String {
+    let (days, secs_of_day) = (timestamp / 86400, timestamp % 86400);
+
+    // Convert days since the Unix epoch to a civil date, see http://howardhinnant.github.io/date_algorithms.html#civil_from_days.
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02}",
+        hour = secs_of_day / 3600,
+        minute = secs_of_day / 60 % 60,
+        second = secs_of_day % 60,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{History, HistoryRecord, OpCounts, format_utc_timestamp};
+
+    #[test]
+    fn test_roundtrip_record() {
+        let record = HistoryRecord {
+            timestamp: 1760000000,
+            version: Some("0.1.0".to_owned()),
+            commit: Some("9f3c1a0".to_owned()),
+            score: Some(87.5),
+            total: 120,
+            undetected: 15,
+            timed_out: 2,
+            crashed: 0,
+            ops: [("math_op_add_sub_swap".to_owned(), OpCounts { total: 12, undetected: 3 })].into_iter().collect(),
+        };
+
+        let history = History::parse(&format!("{}\n{}", record.to_toml(), record.to_toml())).unwrap();
+        assert_eq!(history.records, vec![record.clone(), record]);
+    }
+
+    #[test]
+    fn test_parse_unknown_op_key() {
+        assert!(History::parse("[[run]]\nops.eq_op_invert.detected = 1\n").is_err());
+    }
+
+    #[test]
+    fn test_format_utc_timestamp() {
+        assert_eq!(format_utc_timestamp(0), "1970-01-01 00:00:00");
+        assert_eq!(format_utc_timestamp(1760000000), "2025-10-09 08:53:20");
+        assert_eq!(format_utc_timestamp(951782400), "2000-02-29 00:00:00");
+    }
+}
@@ -0,0 +1,167 @@
+//! Detection of resources leaked by the tests of a mutant, which may affect the results of subsequent mutants
+//! evaluated in the same process (see `--check-leaks`).
+//!
+//! Tests which time out are abandoned while still running, and tests may leave background threads or open files
+//! behind. Between mutants, the threads of abandoned tests which are still running, the other threads of the process
+//! (i.e. background threads spawned by tests), and the open file descriptors of the process are compared against the
+//! previous check. Threads of cancelled tests are abandoned deliberately, and are not reported as leaks.
+//! Threads are only counted on Linux, and open file descriptors are only counted on Linux and macOS.
+
+use std::fmt;
+use std::fs;
+
+/// Number of threads of the process, if they can be counted on this platform.
+pub fn threads_count() -> Option<usize> {
+    if !cfg!(target_os = "linux") { return None; }
+    Some(fs::read_dir("/proc/self/task").ok()?.count())
+}
+
+/// Number of open file descriptors of the process, if they can be counted on this platform.
+pub fn open_fds_count() -> Option<usize> {
+    let fd_dir = match () {
+        _ if cfg!(target_os = "linux") => "/proc/self/fd",
+        _ if cfg!(target_os = "macos") => "/dev/fd",
+        _ => return None,
+    };
+    Some(fs::read_dir(fd_dir).ok()?.count())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Leak {
+    /// Threads of abandoned tests which are still running.
+    LingeringThreads(usize),
+    /// Threads spawned by tests which are still running, other than the threads of abandoned tests.
+    SpawnedThreads(usize),
+    FileDescriptors(usize),
+}
+
+impl fmt::Display for Leak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::LingeringThreads(1) => write!(f, "1 lingering thread"),
+            Self::LingeringThreads(count) => write!(f, "{count} lingering threads"),
+            Self::SpawnedThreads(1) => write!(f, "1 background thread"),
+            Self::SpawnedThreads(count) => write!(f, "{count} background threads"),
+            Self::FileDescriptors(1) => write!(f, "1 file descriptor"),
+            Self::FileDescriptors(count) => write!(f, "{count} file descriptors"),
+        }
+    }
+}
+
+pub struct LeakCheck {
+    lingering_threads_count: usize,
+    spawned_threads_count: Option<usize>,
+    open_fds_count: Option<usize>,
+}
+
+impl LeakCheck {
+    /// Start checking for leaks, relative to the current state of the process.
+    pub fn new() -> Self {
+        Self { lingering_threads_count: 0, spawned_threads_count: threads_count(), open_fds_count: open_fds_count() }
+    }
+
+    /// Resources leaked since the previous check, given the current number of lingering threads of abandoned tests,
+    /// the number of those which belong to cancelled tests, the number of threads of the process (see
+    /// [`threads_count`]), and the number of open file descriptors (see [`open_fds_count`]). Leaks are only reported
+    /// once, by the check following them.
+    pub fn check(&mut self, lingering_threads_count: usize, cancelled_threads_count: usize, threads_count: Option<usize>, open_fds_count: Option<usize>) -> Vec<Leak> {
+        let mut leaks = vec![];
+
+        let lingering_threads_count = lingering_threads_count - cancelled_threads_count;
+        if lingering_threads_count > self.lingering_threads_count {
+            leaks.push(Leak::LingeringThreads(lingering_threads_count - self.lingering_threads_count));
+        }
+        self.lingering_threads_count = lingering_threads_count;
+
+        // The threads of abandoned tests, including cancelled ones, are replaced in the thread pool, and so are
+        // discounted from the threads of the process.
+        let spawned_threads_count = threads_count.map(|threads_count| threads_count.saturating_sub(lingering_threads_count + cancelled_threads_count));
+        if let (Some(spawned_threads_count), Some(prev_spawned_threads_count)) = (spawned_threads_count, self.spawned_threads_count) && spawned_threads_count > prev_spawned_threads_count {
+            leaks.push(Leak::SpawnedThreads(spawned_threads_count - prev_spawned_threads_count));
+        }
+        self.spawned_threads_count = spawned_threads_count;
+
+        if let (Some(open_fds_count), Some(prev_open_fds_count)) = (open_fds_count, self.open_fds_count) && open_fds_count > prev_open_fds_count {
+            leaks.push(Leak::FileDescriptors(open_fds_count - prev_open_fds_count));
+        }
+        self.open_fds_count = open_fds_count;
+
+        leaks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Leak, LeakCheck};
+
+    #[test]
+    fn test_lingering_threads_reported_once() {
+        let mut leak_check = LeakCheck { lingering_threads_count: 0, spawned_threads_count: None, open_fds_count: None };
+
+        assert_eq!(leak_check.check(2, 0, None, None), [Leak::LingeringThreads(2)]);
+        assert_eq!(leak_check.check(2, 0, None, None), []);
+        assert_eq!(leak_check.check(1, 0, None, None), []);
+        assert_eq!(leak_check.check(3, 0, None, None), [Leak::LingeringThreads(2)]);
+    }
+
+    #[test]
+    fn test_threads_of_cancelled_tests_not_reported() {
+        let mut leak_check = LeakCheck { lingering_threads_count: 0, spawned_threads_count: Some(4), open_fds_count: None };
+
+        assert_eq!(leak_check.check(2, 2, Some(6), None), []);
+        assert_eq!(leak_check.check(3, 2, Some(7), None), [Leak::LingeringThreads(1)]);
+        assert_eq!(leak_check.check(1, 0, Some(5), None), []);
+    }
+
+    #[test]
+    fn test_spawned_threads_reported_once() {
+        let mut leak_check = LeakCheck { lingering_threads_count: 0, spawned_threads_count: Some(4), open_fds_count: None };
+
+        assert_eq!(leak_check.check(0, 0, Some(6), None), [Leak::SpawnedThreads(2)]);
+        assert_eq!(leak_check.check(0, 0, Some(6), None), []);
+        // Threads of abandoned tests are not reported as spawned threads.
+        assert_eq!(leak_check.check(1, 0, Some(7), None), [Leak::LingeringThreads(1)]);
+        assert_eq!(leak_check.check(1, 0, Some(5), None), []);
+        assert_eq!(leak_check.check(1, 0, Some(6), None), [Leak::SpawnedThreads(1)]);
+        // Threads are not compared if they could not be counted.
+        assert_eq!(leak_check.check(1, 0, None, None), []);
+        assert_eq!(leak_check.check(1, 0, Some(9), None), []);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_leaked_background_threads() {
+        use std::sync::{Arc, Mutex, mpsc};
+        use std::thread;
+
+        let mut leak_check = LeakCheck::new();
+
+        // Spawn a number of blocked background threads, so that the leak is not masked by the threads of other tests
+        // exiting concurrently.
+        let (tx, rx) = mpsc::channel::<()>();
+        let rx = Arc::new(Mutex::new(rx));
+        let threads = (0..8).map(|_| {
+            let rx = rx.clone();
+            thread::spawn(move || { let _ = rx.lock().unwrap().recv(); })
+        }).collect::<Vec<_>>();
+
+        let leaks = leak_check.check(0, 0, super::threads_count(), None);
+        assert!(leaks.iter().any(|leak| matches!(leak, Leak::SpawnedThreads(_))), "expected leaked threads, found {leaks:?}");
+
+        drop(tx);
+        for thread in threads {
+            thread.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_leaked_file_descriptors() {
+        let mut leak_check = LeakCheck { lingering_threads_count: 0, spawned_threads_count: None, open_fds_count: Some(8) };
+
+        assert_eq!(leak_check.check(1, 0, None, Some(10)), [Leak::LingeringThreads(1), Leak::FileDescriptors(2)]);
+        assert_eq!(leak_check.check(1, 0, None, Some(9)), []);
+        // File descriptors are not compared if they could not be counted.
+        assert_eq!(leak_check.check(1, 0, None, None), []);
+        assert_eq!(leak_check.check(1, 0, None, Some(12)), []);
+    }
+}
@@ -5,10 +5,17 @@
 #![feature(iter_array_chunks)]
 #![feature(let_chains)]
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
 #![feature(test)]
 #![feature(internal_output_capture)]
+#[cfg(feature = "std")]
 extern crate test;
 
+#[cfg(feature = "atomic-handle")]
+extern crate alloc;
+
+#[cfg(feature = "std")]
 pub mod build {
     use std::path::Path;
 
@@ -30,20 +37,70 @@ pub mod build {
     }
 }
 
+#[cfg(feature = "std")]
+pub mod leak_check;
+#[cfg(feature = "std")]
+pub mod log;
+
+#[cfg(feature = "std")]
+pub mod mutant_selection;
+#[cfg(feature = "std")]
+pub mod test_filter;
+#[cfg(feature = "std")]
 pub mod test_runner;
+#[cfg(feature = "std")]
+pub mod test_scheduler;
+#[cfg(feature = "std")]
+pub mod test_timings;
+#[cfg(feature = "std")]
 pub mod thread_pool;
+#[cfg(feature = "std")]
+pub mod tui;
+#[cfg(feature = "std")]
+pub mod verdict_cache;
 
+#[cfg(feature = "std")]
 pub mod data_structures;
 
+#[cfg(feature = "std")]
+pub mod baseline;
+#[cfg(feature = "std")]
 pub mod detections;
+#[cfg(feature = "std")]
 pub mod flakiness;
+#[cfg(feature = "std")]
+pub mod github;
+#[cfg(feature = "std")]
+pub mod history;
+#[cfg(feature = "std")]
+pub mod property_tests;
+#[cfg(feature = "std")]
+pub use property_tests::register_property_tests;
+#[cfg(feature = "std")]
+pub mod reset_hooks;
+#[cfg(feature = "std")]
+pub use reset_hooks::register_reset_hook;
+#[cfg(feature = "std")]
+pub mod results;
+#[cfg(feature = "std")]
+pub mod sarif;
+#[cfg(feature = "std")]
 pub mod subsumption;
 
+#[cfg(feature = "std")]
 mod config;
+#[cfg(feature = "std")]
 pub use config::*;
 
+#[cfg(feature = "std")]
 mod harness;
+#[cfg(feature = "std")]
 pub use harness::*;
 
+// NOTE: The following modules make up the `no_std`-compatible subset of the runtime, which is sufficient for building
+//       meta-mutants and evaluating them with a custom test harness.
+mod handle;
+pub use handle::*;
+
 mod metadata;
 pub use metadata::*;
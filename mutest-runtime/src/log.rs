@@ -0,0 +1,187 @@
+//! Logging of status messages, with levels, and an optional log file.
+//!
+//! Messages are rendered to stdout as-is, so that the human-readable output is unaffected by the logging layer.
+//! Warnings and errors are labelled by their level, like compiler diagnostics, but are also rendered to stdout, where
+//! they have always been printed, unless stdout is suppressed.
+//! Debug messages are only rendered with increased verbosity.
+//! If a log file is given, all messages, regardless of verbosity, are additionally appended to the log file, prefixed
+//! by their timestamp and level.
+
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{self, AtomicBool};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl Level {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Error => "ERROR",
+            Self::Warn => "WARN",
+            Self::Info => "INFO",
+            Self::Debug => "DEBUG",
+        }
+    }
+}
+
+struct Logger {
+    verbosity: u8,
+    log_file: Option<Mutex<File>>,
+}
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+const DEFAULT_LOGGER: Logger = Logger { verbosity: 0, log_file: None };
+
+fn logger() -> &'static Logger {
+    LOGGER.get().unwrap_or(&DEFAULT_LOGGER)
+}
+
+/// Initialize the logger of the process. Messages logged before initialization are only rendered to stdout, with the
+/// default verbosity. Subsequent initializations are ignored.
+///
+/// The log file is opened in append mode, so that multiple processes can log into the same file.
+pub fn init(verbosity: u8, log_file_path: Option<&Path>) -> io::Result<()> {
+    let log_file = match log_file_path {
+        Some(log_file_path) => Some(Mutex::new(OpenOptions::new().create(true).append(true).open(log_file_path)?)),
+        None => None,
+    };
+
+    let _ = LOGGER.set(Logger { verbosity, log_file });
+    Ok(())
+}
+
+static STDOUT_SUPPRESSED: AtomicBool = AtomicBool::new(false);
+
+/// Suppress the rendering of messages to stdout, e.g. while the terminal UI is drawn on the screen. Warnings and
+/// errors are rendered to stderr instead, and all messages are still appended to the log file.
+pub fn suppress_stdout(suppressed: bool) {
+    STDOUT_SUPPRESSED.store(suppressed, atomic::Ordering::Relaxed);
+}
+
+fn renders_to_terminal(verbosity: u8, level: Level) -> bool {
+    match level {
+        Level::Debug => verbosity >= 1,
+        _ => true,
+    }
+}
+
+/// Returns `true` if messages of the level are rendered anywhere. Useful for skipping the computation of values which
+/// are only needed for debug messages.
+pub fn enabled(level: Level) -> bool {
+    let logger = logger();
+    renders_to_terminal(logger.verbosity, level) || logger.log_file.is_some()
+}
+
+/// The message as rendered to the terminal. Warnings and errors are labelled by their level, after any leading blank
+/// lines used for spacing.
+fn terminal_message(level: Level, message: &str) -> String {
+    let label = match level {
+        Level::Error => "error: ",
+        Level::Warn => "warning: ",
+        Level::Info | Level::Debug => return message.to_owned(),
+    };
+
+    let body = message.trim_start_matches('\n');
+    if body.is_empty() { return message.to_owned(); }
+    format!("{spacing}{label}{body}", spacing = &message[..(message.len() - body.len())])
+}
+
+/// The message as appended to the log file, with each line prefixed by the timestamp and level.
+/// Blank lines only used for spacing the human-readable output are not logged.
+fn log_file_record(timestamp: Duration, level: Level, message: &str) -> String {
+    let mut record = String::new();
+    for line in message.trim_matches('\n').lines() {
+        record.push_str(&format!("[{secs}.{millis:03}] {level:<5} {line}\n",
+            secs = timestamp.as_secs(),
+            millis = timestamp.subsec_millis(),
+            level = level.as_str(),
+        ));
+    }
+    record
+}
+
+pub fn log(level: Level, args: fmt::Arguments<'_>) {
+    let logger = logger();
+
+    if renders_to_terminal(logger.verbosity, level) {
+        let stdout_suppressed = STDOUT_SUPPRESSED.load(atomic::Ordering::Relaxed);
+        match level {
+            Level::Error | Level::Warn if stdout_suppressed => eprintln!("{}", terminal_message(level, &args.to_string())),
+            Level::Error | Level::Warn => println!("{}", terminal_message(level, &args.to_string())),
+            Level::Info | Level::Debug => if !stdout_suppressed { println!("{args}"); },
+        }
+    }
+
+    if let Some(log_file) = &logger.log_file {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let record = log_file_record(timestamp, level, &args.to_string());
+
+        let mut log_file = log_file.lock().unwrap_or_else(|e| e.into_inner());
+        // Logging must never interrupt the run, so write errors are explicitly ignored.
+        let _ = log_file.write_all(record.as_bytes());
+    }
+}
+
+pub macro error($($arg:tt)*) {
+    $crate::log::log($crate::log::Level::Error, format_args!($($arg)*))
+}
+
+pub macro warn($($arg:tt)*) {
+    $crate::log::log($crate::log::Level::Warn, format_args!($($arg)*))
+}
+
+pub macro info($($arg:tt)*) {
+    $crate::log::log($crate::log::Level::Info, format_args!($($arg)*))
+}
+
+pub macro debug($($arg:tt)*) {
+    $crate::log::log($crate::log::Level::Debug, format_args!($($arg)*))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Level, log_file_record, renders_to_terminal, terminal_message};
+
+    #[test]
+    fn test_debug_messages_rendered_with_verbosity() {
+        assert!(!renders_to_terminal(0, Level::Debug));
+        assert!(renders_to_terminal(1, Level::Debug));
+        assert!(renders_to_terminal(0, Level::Info));
+        assert!(renders_to_terminal(0, Level::Warn));
+        assert!(renders_to_terminal(0, Level::Error));
+    }
+
+    #[test]
+    fn test_terminal_message_labelled_by_level() {
+        assert_eq!(terminal_message(Level::Info, "ran 3 out of 4 tests"), "ran 3 out of 4 tests");
+        assert_eq!(terminal_message(Level::Warn, "cannot write cache"), "warning: cannot write cache");
+        assert_eq!(terminal_message(Level::Error, "cannot open file"), "error: cannot open file");
+        // The label follows leading blank lines, and blank lines are not labelled.
+        assert_eq!(terminal_message(Level::Warn, "\n2 threads leaked"), "\nwarning: 2 threads leaked");
+        assert_eq!(terminal_message(Level::Warn, ""), "");
+    }
+
+    #[test]
+    fn test_log_file_record() {
+        let timestamp = Duration::from_millis(1_700_000_000_042);
+
+        assert_eq!(log_file_record(timestamp, Level::Warn, "\ntests leaked\nnote: isolated\n"), concat!(
+            "[1700000000.042] WARN  tests leaked\n",
+            "[1700000000.042] WARN  note: isolated\n",
+        ));
+        assert_eq!(log_file_record(timestamp, Level::Info, ""), "");
+    }
+}
@@ -1,7 +1,18 @@
 pub use phf::phf_map as static_map;
 
+/// Version of the schema of the metadata generated into meta-mutants by `mutest-emit`.
+///
+/// The generated code statically asserts that it matches this version when it is compiled. It must be bumped
+/// together with `mutest_emit::codegen::harness::METADATA_VERSION` whenever the metadata types change.
+pub const METADATA_VERSION: u32 = 2;
+
 pub type TestPath = &'static str;
 
+/// Index of an interned string in the [`MetadataTables`] of the program.
+pub type StrIdx = u32;
+/// Index of a test in the [`MetadataTables`] of the program.
+pub type TestIdx = u32;
+
 pub type SubstLocIdx = usize;
 
 pub trait SubstMap: Sized + Clone {
@@ -11,18 +22,28 @@ pub trait SubstMap: Sized + Clone {
     ///
     /// The substitution location index must be valid for the substitution map.
     unsafe fn subst_at_unchecked(&self, subst_loc_idx: SubstLocIdx) -> Option<SubstMeta>;
+
+    /// Remove the substitutions of all mutations other than the given mutation, leaving the substitutions of a
+    /// singleton mutant of the mutation.
+    fn retain_mutation(&mut self, mutation_id: u32);
 }
 
 impl<const N: usize> SubstMap for [Option<SubstMeta>; N] {
-    #[inline]
+    #[inline(always)]
     fn subst_at(&self, subst_loc_idx: SubstLocIdx) -> Option<SubstMeta> {
         self[subst_loc_idx]
     }
 
-    #[inline]
+    #[inline(always)]
     unsafe fn subst_at_unchecked(&self, subst_loc_idx: SubstLocIdx) -> Option<SubstMeta> {
         *self.get_unchecked(subst_loc_idx)
     }
+
+    fn retain_mutation(&mut self, mutation_id: u32) {
+        for subst in self.iter_mut() {
+            if subst.is_some_and(|subst| subst.mutation.id != mutation_id) { *subst = None; }
+        }
+    }
 }
 
 // NOTE: This function must be a standalone function not on the SubstMap trait (and corresponding impl)
@@ -55,15 +76,126 @@ pub enum MutationSafety {
     Unsafe,
 }
 
+/// Tables shared by the metadata of all mutations of a program, which reference their entries by index, rather than
+/// embedding them. This keeps the metadata of large crates compact.
+#[derive(Debug)]
+pub struct MetadataTables {
+    /// Interned strings, referenced by [`StrIdx`].
+    pub strs: &'static [&'static str],
+    /// Index of each test, referenced by [`TestIdx`].
+    pub test_idxs: phf::Map<TestPath, TestIdx>,
+    /// Original source locations of the generated code of the program.
+    pub span_map: &'static SpanMap,
+}
+
+/// Mapping of locations in the generated code of the program back to the original source of the crate.
+#[derive(Debug)]
+pub struct SpanMap {
+    /// File name of the generated code, as reported in panic locations.
+    pub generated_file: &'static str,
+    /// Files of the original source, referenced by index from the entries.
+    pub files: &'static [&'static str],
+    /// Entries of `(generated_line, generated_column, file_idx, line, column)`, sorted by their generated location.
+    /// Lines and columns are 1-based.
+    pub entries: &'static [(u32, u32, u32, u32, u32)],
+}
+
+impl SpanMap {
+    /// Find the original location corresponding to the location in the generated code, returning its file, line,
+    /// and column.
+    pub fn original_location(&self, file: &str, line: u32, column: u32) -> Option<(&'static str, u32, u32)> {
+        if file != self.generated_file { return None; }
+
+        // Locations map to the closest preceding entry.
+        let i = self.entries.partition_point(|&(generated_line, generated_column, _, _, _)| (generated_line, generated_column) <= (line, column));
+        let &(_, _, file_idx, line, column) = self.entries.get(i.checked_sub(1)?)?;
+        Some((self.files[file_idx as usize], line, column))
+    }
+}
+
 #[derive(Debug)]
 pub struct MutationMeta {
     pub id: u32,
+    /// Identifier of the mutation which is stable across runs, unlike [`id`](Self::id), which is assigned
+    /// sequentially. Suitable as a key for storing results of the mutation.
+    pub stable_id: u64,
     pub safety: MutationSafety,
-    pub op_name: &'static str,
-    pub display_name: &'static str,
-    pub display_location: &'static str,
-    pub reachable_from: phf::Map<TestPath, usize>,
-    pub undetected_diagnostic: &'static str,
+    pub tables: &'static MetadataTables,
+    pub op_name: StrIdx,
+    /// Operators of the duplicate mutations merged into this mutation, which are attributed the mutation as well.
+    pub merged_op_names: &'static [StrIdx],
+    pub display_name: StrIdx,
+    pub display_location: StrIdx,
+    /// Definition path of the function containing the mutation (e.g. `parser::parse_expr`).
+    pub target_path: StrIdx,
+    /// Tests the mutation is reachable from, with their distance from the mutation, sorted by test index.
+    pub reachable_from: &'static [(TestIdx, u32)],
+    /// Descriptions of the substitutions of the mutation, separated by newlines.
+    pub substs_descr: StrIdx,
+    pub undetected_diagnostic: StrIdx,
+}
+
+impl MutationMeta {
+    #[inline]
+    fn str(&self, str_idx: StrIdx) -> &'static str {
+        self.tables.strs[str_idx as usize]
+    }
+
+    /// The stable id in its textual form, as printed by the tools.
+    #[cfg(feature = "std")]
+    pub fn stable_id_str(&self) -> String {
+        format!("{:016x}", self.stable_id)
+    }
+
+    pub fn op_name(&self) -> &'static str {
+        self.str(self.op_name)
+    }
+
+    /// The operator of the mutation, followed by the operators of the duplicate mutations merged into it.
+    pub fn op_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        core::iter::once(self.op_name()).chain(self.merged_op_names.iter().map(|&op_name| self.str(op_name)))
+    }
+
+    /// The operators of the mutation, as reported (e.g. `call_delete, call_value_default_shadow`).
+    #[cfg(feature = "std")]
+    pub fn op_names_descr(&self) -> String {
+        self.op_names().collect::<Vec<_>>().join(", ")
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        self.str(self.display_name)
+    }
+
+    pub fn display_location(&self) -> &'static str {
+        self.str(self.display_location)
+    }
+
+    pub fn target_path(&self) -> &'static str {
+        self.str(self.target_path)
+    }
+
+    pub fn substs_descr(&self) -> &'static str {
+        self.str(self.substs_descr)
+    }
+
+    pub fn undetected_diagnostic(&self) -> &'static str {
+        self.str(self.undetected_diagnostic)
+    }
+
+    /// Distance of the mutation from the test, if the mutation is reachable from the test.
+    pub fn distance_from(&self, test_path: &str) -> Option<usize> {
+        let test_idx = *self.tables.test_idxs.get(test_path)?;
+        let i = self.reachable_from.binary_search_by_key(&test_idx, |&(test_idx, _)| test_idx).ok()?;
+        Some(self.reachable_from[i].1 as usize)
+    }
+
+    pub fn is_reachable_from(&self, test_path: &str) -> bool {
+        self.distance_from(test_path).is_some()
+    }
+
+    pub fn reachable_from_count(&self) -> usize {
+        self.reachable_from.len()
+    }
 }
 
 #[derive(Debug)]
@@ -78,3 +210,28 @@ impl<S: SubstMap> MutantMeta<S> {
         self.mutations.iter().any(|m| !matches!(m.safety, MutationSafety::Safe))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SpanMap;
+
+    static SPAN_MAP: SpanMap = SpanMap {
+        generated_file: "src/lib.rs",
+        files: &["src/lib.rs", "src/foo.rs"],
+        entries: &[
+            (3, 1, 0, 10, 1),
+            (3, 12, 0, 11, 5),
+            (7, 5, 1, 2, 9),
+        ],
+    };
+
+    #[test]
+    fn test_original_location() {
+        assert_eq!(SPAN_MAP.original_location("src/lib.rs", 1, 1), None);
+        assert_eq!(SPAN_MAP.original_location("src/lib.rs", 3, 1), Some(("src/lib.rs", 10, 1)));
+        assert_eq!(SPAN_MAP.original_location("src/lib.rs", 3, 11), Some(("src/lib.rs", 10, 1)));
+        assert_eq!(SPAN_MAP.original_location("src/lib.rs", 5, 1), Some(("src/lib.rs", 11, 5)));
+        assert_eq!(SPAN_MAP.original_location("src/lib.rs", 9, 2), Some(("src/foo.rs", 2, 9)));
+        assert_eq!(SPAN_MAP.original_location("src/foo.rs", 9, 2), None);
+    }
+}
@@ -0,0 +1,99 @@
+//! Selection of the subset of mutants and mutations of the meta-mutant which are evaluated, by their IDs.
+//!
+//! The mutants and mutations are selected using the `--mutants=<IDS>` and `--mutations=<IDS>` arguments of the test
+//! harness, where the IDs are given as a comma-separated list of IDs and inclusive ranges of IDs (e.g. `1,5,9-12`).
+//! A mutation is evaluated if both its mutant and the mutation itself are selected. Since the mutations batched into a
+//! mutant are reachable from disjoint sets of tests, the selected mutations of a mutant can be evaluated without the
+//! other mutations of the mutant.
+
+use std::ops::RangeInclusive;
+
+/// List of IDs, given as individual IDs and inclusive ranges of IDs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IdList {
+    pub ranges: Vec<RangeInclusive<u32>>,
+}
+
+impl IdList {
+    pub fn contains(&self, id: u32) -> bool {
+        self.ranges.iter().any(|range| range.contains(&id))
+    }
+
+    /// Parse a comma-separated list of IDs and inclusive ranges of IDs, e.g. `1,5,9-12`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let ranges = s.split(',')
+            .map(|part| {
+                let parse_id = |id: &str| id.trim().parse::<u32>().map_err(|_| format!("invalid id `{id}`"));
+                match part.split_once('-') {
+                    Some((start, end)) => {
+                        let (start, end) = (parse_id(start)?, parse_id(end)?);
+                        if start > end { return Err(format!("invalid id range `{part}`: range is empty")); }
+                        Ok(start..=end)
+                    }
+                    None => parse_id(part).map(|id| id..=id),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { ranges })
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MutantSelection {
+    /// IDs of the evaluated mutants, or all mutants if not given.
+    pub mutant_ids: Option<IdList>,
+    /// IDs of the evaluated mutations, or all mutations if not given.
+    pub mutation_ids: Option<IdList>,
+}
+
+impl MutantSelection {
+    pub fn is_empty(&self) -> bool {
+        self.mutant_ids.is_none() && self.mutation_ids.is_none()
+    }
+
+    pub fn includes(&self, mutant_id: u32, mutation_id: u32) -> bool {
+        self.mutant_ids.as_ref().map_or(true, |mutant_ids| mutant_ids.contains(mutant_id))
+            && self.mutation_ids.as_ref().map_or(true, |mutation_ids| mutation_ids.contains(mutation_id))
+    }
+
+    /// Parse the selection from the `--mutants=<IDS>` and `--mutations=<IDS>` arguments of the test harness.
+    pub fn from_args(args: &[&str]) -> Result<Self, String> {
+        let parse_arg = |prefix: &str| {
+            args.iter().flat_map(|arg| arg.strip_prefix(prefix)).next()
+                .map(IdList::parse)
+                .transpose()
+        };
+
+        Ok(Self {
+            mutant_ids: parse_arg("--mutants=").map_err(|e| format!("invalid mutant ids: {e}"))?,
+            mutation_ids: parse_arg("--mutations=").map_err(|e| format!("invalid mutation ids: {e}"))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IdList, MutantSelection};
+
+    #[test]
+    fn test_parse_id_list() {
+        assert_eq!(IdList::parse("1,5,9-12"), Ok(IdList { ranges: vec![1..=1, 5..=5, 9..=12] }));
+        assert_eq!(IdList::parse("3"), Ok(IdList { ranges: vec![3..=3] }));
+        assert!(IdList::parse("").is_err());
+        assert!(IdList::parse("1,x").is_err());
+        assert!(IdList::parse("12-9").is_err());
+    }
+
+    #[test]
+    fn test_includes() {
+        let selection = MutantSelection::from_args(&["--mutants=1-3", "--mutations=2,7"]).unwrap();
+
+        assert!(selection.includes(1, 2));
+        assert!(selection.includes(3, 7));
+        assert!(!selection.includes(1, 3));
+        assert!(!selection.includes(4, 7));
+
+        assert!(MutantSelection::default().includes(4, 7));
+    }
+}
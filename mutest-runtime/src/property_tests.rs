@@ -0,0 +1,60 @@
+//! Property-based tests (e.g. using `proptest` or `quickcheck`), which run many randomly generated cases, and shrink
+//! failing cases to minimal ones.
+//!
+//! Property tests are detected at compile-time, and are registered by the generated test harness. Against mutants,
+//! only a reduced number of cases are generated (see `--property-test-cases`), and shrinking is disabled where the
+//! library supports it, as only the detection of the mutant matters, rather than its minimal failing case.
+//! `quickcheck` cannot be configured to skip shrinking, so failing `quickcheck` cases are still shrunk. The property
+//! testing libraries are configured using their environment variables, which are inherited by isolated test processes.
+//! Variables which are already set are left unchanged.
+
+use std::env;
+use std::sync::RwLock;
+
+/// Number of cases generated by property tests, if not specified.
+pub const DEFAULT_PROPERTY_TEST_CASES: usize = 32;
+
+static PROPERTY_TESTS: RwLock<Vec<&'static str>> = RwLock::new(Vec::new());
+
+/// Register the names of the property tests of the crate.
+pub fn register_property_tests(test_names: &'static [&'static str]) {
+    PROPERTY_TESTS.write().unwrap_or_else(|e| e.into_inner()).extend_from_slice(test_names);
+}
+
+pub fn property_tests() -> Vec<&'static str> {
+    PROPERTY_TESTS.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Environment variables configuring property testing libraries to generate the given number of cases, without
+/// shrinking failing `proptest` cases, or persisting them in the source tree.
+pub fn env_config(cases: usize) -> Vec<(&'static str, String)> {
+    vec![
+        ("PROPTEST_CASES", cases.to_string()),
+        ("PROPTEST_MAX_SHRINK_ITERS", "0".to_owned()),
+        ("PROPTEST_DISABLE_FAILURE_PERSISTENCE", "1".to_owned()),
+        ("QUICKCHECK_TESTS", cases.to_string()),
+    ]
+}
+
+/// Configure property testing libraries to generate the given number of cases, without shrinking where supported.
+/// Must be called before any tests are run, as the libraries read their configuration once.
+pub fn configure(cases: usize) {
+    for (var, value) in env_config(cases) {
+        if env::var_os(var).is_some() { continue; }
+        env::set_var(var, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::env_config;
+
+    #[test]
+    fn test_env_config_disables_shrinking() {
+        let env_config = env_config(8);
+
+        assert!(env_config.contains(&("PROPTEST_CASES", "8".to_owned())));
+        assert!(env_config.contains(&("QUICKCHECK_TESTS", "8".to_owned())));
+        assert!(env_config.contains(&("PROPTEST_MAX_SHRINK_ITERS", "0".to_owned())));
+    }
+}
@@ -0,0 +1,27 @@
+//! Hooks registered by the program under test to reset its global state (e.g. caches in statics), so that tests can
+//! be reliably evaluated in-process, without isolating them in child processes.
+//!
+//! Functions marked with `#[mutest::reset_hook]` are registered by the generated test harness. Reset hooks are run
+//! without any mutant active between the evaluation of mutants, and after each test run in-process, on the thread of
+//! the test. As tests run in parallel, reset hooks must be safe to run concurrently with other tests.
+
+use std::sync::RwLock;
+
+static RESET_HOOKS: RwLock<Vec<fn()>> = RwLock::new(Vec::new());
+
+/// Register a hook to reset the global state of the program between test runs.
+pub fn register_reset_hook(reset_hook: fn()) {
+    RESET_HOOKS.write().unwrap_or_else(|e| e.into_inner()).push(reset_hook);
+}
+
+pub fn has_reset_hooks() -> bool {
+    !RESET_HOOKS.read().unwrap_or_else(|e| e.into_inner()).is_empty()
+}
+
+/// Run the registered reset hooks, in the order they were registered.
+pub fn run_reset_hooks() {
+    let reset_hooks = RESET_HOOKS.read().unwrap_or_else(|e| e.into_inner()).clone();
+    for reset_hook in reset_hooks {
+        reset_hook();
+    }
+}
@@ -0,0 +1,574 @@
+//! Results of mutation analysis, saved with `--results-file`, so that reports can be regenerated from them with
+//! `cargo mutest report`, without rebuilding the test harness, or re-running the tests.
+//!
+//! Results are saved as JSON, with the result of each evaluated mutation, and the result of each test run against it:
+//!
+//! ```text
+//! {
+//!   "tests": ["tests::test_parse"],
+//!   "mutations": [
+//!     { "id": 1, "stable_id": "0123456789abcdef", "op_name": "eq_op_invert", ..., "result": "detected", "accepted": false,
+//!       "results_per_test": { "tests::test_parse": "detected" } }
+//!   ]
+//! }
+//! ```
+
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::flakiness::escape_csv_field;
+use crate::harness::MutationTestResult;
+use crate::sarif::escape_json_str;
+use crate::verdict_cache::{parse_result_name, result_name};
+
+pub struct MutationRecord {
+    pub id: u32,
+    pub stable_id: u64,
+    pub op_name: String,
+    pub display_name: String,
+    pub display_location: String,
+    pub target_path: String,
+    /// Descriptions of the substitutions of the mutation, separated by newlines.
+    pub substs_descr: String,
+    pub safe: bool,
+    pub result: MutationTestResult,
+    /// Whether the mutation is undetected, but accepted by the baseline.
+    pub accepted: bool,
+    /// Results of the tests run against the mutation. Tests which were not run against the mutation are omitted.
+    pub results_per_test: Vec<(String, MutationTestResult)>,
+}
+
+impl MutationRecord {
+    /// The stable id in its textual form, as printed by the tools.
+    pub fn stable_id_str(&self) -> String {
+        format!("{:016x}", self.stable_id)
+    }
+
+    pub fn test_result(&self, test_name: &str) -> Option<MutationTestResult> {
+        self.results_per_test.iter().find(|(name, _)| name == test_name).map(|&(_, result)| result)
+    }
+}
+
+#[derive(Default)]
+pub struct SavedResults {
+    /// Names of the tests evaluated against the mutations.
+    pub tests: Vec<String>,
+    /// Evaluated mutations, sorted by id.
+    pub mutations: Vec<MutationRecord>,
+}
+
+/// Counts of mutation results, as printed in the summary.
+#[derive(Clone, Copy, Default)]
+struct ResultCounts {
+    total: usize,
+    undetected: usize,
+    timed_out: usize,
+    crashed: usize,
+}
+
+impl ResultCounts {
+    fn count<'a>(mutations: impl Iterator<Item = &'a MutationRecord>) -> Self {
+        let mut counts = Self::default();
+        for mutation in mutations {
+            counts.total += 1;
+            match mutation.result {
+                MutationTestResult::Undetected => counts.undetected += 1,
+                MutationTestResult::TimedOut => counts.timed_out += 1,
+                MutationTestResult::Crashed => counts.crashed += 1,
+                MutationTestResult::Detected | MutationTestResult::FlakyDetected => {}
+            }
+        }
+        counts
+    }
+
+    fn score_str(&self) -> String {
+        match self.total {
+            0 => "none".to_owned(),
+            _ => format!("{:.2}%", (self.total - self.undetected) as f64 / self.total as f64 * 100_f64),
+        }
+    }
+}
+
+impl SavedResults {
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "{{").unwrap();
+        writeln!(out, "  \"tests\": [{}],", self.tests.iter().map(|test_name| escape_json_str(test_name)).collect::<Vec<_>>().join(", ")).unwrap();
+        writeln!(out, "  \"mutations\": [").unwrap();
+        for (i, mutation) in self.mutations.iter().enumerate() {
+            writeln!(out, "    {{").unwrap();
+            writeln!(out, "      \"id\": {},", mutation.id).unwrap();
+            writeln!(out, "      \"stable_id\": {},", escape_json_str(&mutation.stable_id_str())).unwrap();
+            writeln!(out, "      \"op_name\": {},", escape_json_str(&mutation.op_name)).unwrap();
+            writeln!(out, "      \"display_name\": {},", escape_json_str(&mutation.display_name)).unwrap();
+            writeln!(out, "      \"display_location\": {},", escape_json_str(&mutation.display_location)).unwrap();
+            writeln!(out, "      \"target_path\": {},", escape_json_str(&mutation.target_path)).unwrap();
+            writeln!(out, "      \"substs_descr\": {},", escape_json_str(&mutation.substs_descr)).unwrap();
+            writeln!(out, "      \"safe\": {},", mutation.safe).unwrap();
+            writeln!(out, "      \"result\": \"{}\",", result_name(mutation.result)).unwrap();
+            writeln!(out, "      \"accepted\": {},", mutation.accepted).unwrap();
+            writeln!(out, "      \"results_per_test\": {{{}}}",
+                mutation.results_per_test.iter()
+                    .map(|(test_name, result)| format!(" {}: \"{}\"", escape_json_str(test_name), result_name(*result)))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ).unwrap();
+            writeln!(out, "    }}{}", if i + 1 < self.mutations.len() { "," } else { "" }).unwrap();
+        }
+        writeln!(out, "  ]").unwrap();
+        writeln!(out, "}}").unwrap();
+        out
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_json())
+    }
+
+    pub fn from_json(src: &str) -> Result<Self, String> {
+        let results = json::parse(src)?;
+
+        let str_field = |value: &json::Value, field: &str| -> Result<String, String> {
+            value.field(field)?.as_str().map(ToOwned::to_owned).ok_or_else(|| format!("`{field}` must be a string"))
+        };
+        let bool_field = |value: &json::Value, field: &str| -> Result<bool, String> {
+            value.field(field)?.as_bool().ok_or_else(|| format!("`{field}` must be a boolean"))
+        };
+        let parse_result = |result: &json::Value| -> Result<MutationTestResult, String> {
+            let result = result.as_str().ok_or("result must be a string")?;
+            parse_result_name(result).ok_or_else(|| format!("invalid result `{result}`"))
+        };
+
+        let tests = results.field("tests")?.as_array().ok_or("`tests` must be an array")?.iter()
+            .map(|test_name| test_name.as_str().map(ToOwned::to_owned).ok_or("test names must be strings"))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut mutations = vec![];
+        for mutation in results.field("mutations")?.as_array().ok_or("`mutations` must be an array")? {
+            let id = mutation.field("id")?.as_u32().ok_or("`id` must be a mutation id")?;
+            let stable_id = str_field(mutation, "stable_id")?;
+            mutations.push(MutationRecord {
+                id,
+                stable_id: u64::from_str_radix(&stable_id, 16).map_err(|_| format!("invalid stable id `{stable_id}`"))?,
+                op_name: str_field(mutation, "op_name")?,
+                display_name: str_field(mutation, "display_name")?,
+                display_location: str_field(mutation, "display_location")?,
+                target_path: str_field(mutation, "target_path")?,
+                substs_descr: str_field(mutation, "substs_descr")?,
+                safe: bool_field(mutation, "safe")?,
+                result: parse_result(mutation.field("result")?)?,
+                accepted: bool_field(mutation, "accepted")?,
+                results_per_test: mutation.field("results_per_test")?.as_object().ok_or("`results_per_test` must be an object")?.iter()
+                    .map(|(test_name, result)| Ok((test_name.clone(), parse_result(result)?)))
+                    .collect::<Result<_, String>>()?,
+            });
+        }
+
+        Ok(Self { tests, mutations })
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let src = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::from_json(&src)
+    }
+
+    /// Summary of the mutation scores, followed by the undetected mutations, as printed at the end of a run.
+    pub fn to_text_summary(&self) -> String {
+        let mut out = String::new();
+
+        let all = ResultCounts::count(self.mutations.iter());
+        let safe = ResultCounts::count(self.mutations.iter().filter(|mutation| mutation.safe));
+        let unsafe_ = ResultCounts::count(self.mutations.iter().filter(|mutation| !mutation.safe));
+        for (heading, counts) in [("mutations", all), ("     safe", safe), ("   unsafe", unsafe_)] {
+            writeln!(out, "{heading}: {score}. {detected} detected ({timed_out} timed out; {crashed} crashed); {undetected} undetected; {total} total",
+                score = counts.score_str(),
+                detected = counts.total - counts.undetected,
+                timed_out = counts.timed_out,
+                crashed = counts.crashed,
+                undetected = counts.undetected,
+                total = counts.total,
+            ).unwrap();
+        }
+
+        let undetected_mutations = self.mutations.iter().filter(|mutation| mutation.result == MutationTestResult::Undetected).collect::<Vec<_>>();
+        if !undetected_mutations.is_empty() {
+            writeln!(out, "\nundetected mutations:").unwrap();
+            for mutation in undetected_mutations {
+                writeln!(out, "  - [{op_name}] {display_name} at {display_location}{accepted}",
+                    op_name = mutation.op_name,
+                    display_name = mutation.display_name,
+                    display_location = mutation.display_location,
+                    accepted = if mutation.accepted { " (accepted by baseline)" } else { "" },
+                ).unwrap();
+            }
+        }
+
+        out
+    }
+
+    /// Test-mutation detection matrix as CSV, with one row for each mutation, and one column for the result of each
+    /// test. Tests which were not run against a mutation have an empty result.
+    pub fn to_matrix_csv(&self) -> String {
+        let mut out = String::new();
+        write!(out, "mutation_id,stable_id,op_name,display_location,result").unwrap();
+        for test_name in &self.tests {
+            write!(out, ",{}", escape_csv_field(test_name)).unwrap();
+        }
+        writeln!(out).unwrap();
+
+        for mutation in &self.mutations {
+            write!(out, "{id},{stable_id},{op_name},{display_location},{result}",
+                id = mutation.id,
+                stable_id = mutation.stable_id_str(),
+                op_name = escape_csv_field(&mutation.op_name),
+                display_location = escape_csv_field(&mutation.display_location),
+                result = result_name(mutation.result),
+            ).unwrap();
+            for test_name in &self.tests {
+                write!(out, ",{}", mutation.test_result(test_name).map(result_name).unwrap_or_default()).unwrap();
+            }
+            writeln!(out).unwrap();
+        }
+
+        out
+    }
+
+    /// Standalone HTML page with the summary, and a table of the mutations and the tests detecting them.
+    pub fn to_html(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "<!DOCTYPE html>").unwrap();
+        writeln!(out, "<html>").unwrap();
+        writeln!(out, "<head>").unwrap();
+        writeln!(out, "<meta charset=\"utf-8\">").unwrap();
+        writeln!(out, "<title>mutest-rs report</title>").unwrap();
+        writeln!(out, "<style>").unwrap();
+        writeln!(out, "body {{ font-family: sans-serif; }}").unwrap();
+        writeln!(out, "table {{ border-collapse: collapse; }}").unwrap();
+        writeln!(out, "td, th {{ border: 1px solid #ccc; padding: 2px 6px; text-align: left; vertical-align: top; }}").unwrap();
+        writeln!(out, ".undetected {{ background: #fdd; }}").unwrap();
+        writeln!(out, ".accepted {{ background: #ffd; }}").unwrap();
+        writeln!(out, "</style>").unwrap();
+        writeln!(out, "</head>").unwrap();
+        writeln!(out, "<body>").unwrap();
+        writeln!(out, "<h1>mutest-rs report</h1>").unwrap();
+        writeln!(out, "<pre>{}</pre>", escape_html(&self.to_text_summary())).unwrap();
+        writeln!(out, "<table>").unwrap();
+        writeln!(out, "<tr><th>id</th><th>result</th><th>operator</th><th>mutation</th><th>location</th><th>detected by</th></tr>").unwrap();
+        for mutation in &self.mutations {
+            let class = match mutation.result {
+                MutationTestResult::Undetected if mutation.accepted => "accepted",
+                MutationTestResult::Undetected => "undetected",
+                _ => "",
+            };
+            let detecting_tests = mutation.results_per_test.iter()
+                .filter(|(_, result)| matches!(result, MutationTestResult::Detected | MutationTestResult::TimedOut | MutationTestResult::Crashed))
+                .map(|(test_name, _)| escape_html(test_name))
+                .collect::<Vec<_>>();
+
+            writeln!(out, "<tr class=\"{class}\"><td>{id}</td><td>{result}</td><td>{op_name}</td><td>{display_name}</td><td>{display_location}</td><td>{detecting_tests}</td></tr>",
+                id = mutation.id,
+                result = result_name(mutation.result),
+                op_name = escape_html(&mutation.op_name),
+                display_name = escape_html(&mutation.display_name),
+                display_location = escape_html(&mutation.display_location),
+                detecting_tests = detecting_tests.join("<br>"),
+            ).unwrap();
+        }
+        writeln!(out, "</table>").unwrap();
+        writeln!(out, "</body>").unwrap();
+        writeln!(out, "</html>").unwrap();
+        out
+    }
+}
+
+fn escape_html(str: &str) -> String {
+    let mut escaped = String::with_capacity(str.len());
+    for c in str.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Minimal JSON parser for reading saved results, as the runtime has no dependency on a JSON library.
+mod json {
+    use std::iter::Peekable;
+    use std::str::Chars;
+
+    pub enum Value {
+        Null,
+        Bool(bool),
+        Number(f64),
+        String(String),
+        Array(Vec<Value>),
+        /// Fields of the object, in their order in the source.
+        Object(Vec<(String, Value)>),
+    }
+
+    impl Value {
+        /// The field of the object, or an error if the value is not an object, or it has no such field.
+        pub fn field(&self, field: &str) -> Result<&Value, String> {
+            self.as_object().and_then(|fields| fields.iter().find(|(name, _)| name == field))
+                .map(|(_, value)| value)
+                .ok_or_else(|| format!("missing field `{field}`"))
+        }
+
+        pub fn as_bool(&self) -> Option<bool> {
+            match *self { Value::Bool(value) => Some(value), _ => None }
+        }
+
+        pub fn as_u32(&self) -> Option<u32> {
+            match *self {
+                Value::Number(value) if value.fract() == 0_f64 && value >= 0_f64 && value <= u32::MAX as f64 => Some(value as u32),
+                _ => None,
+            }
+        }
+
+        pub fn as_str(&self) -> Option<&str> {
+            match self { Value::String(value) => Some(value), _ => None }
+        }
+
+        pub fn as_array(&self) -> Option<&[Value]> {
+            match self { Value::Array(values) => Some(values), _ => None }
+        }
+
+        pub fn as_object(&self) -> Option<&[(String, Value)]> {
+            match self { Value::Object(fields) => Some(fields), _ => None }
+        }
+    }
+
+    struct Parser<'a> {
+        chars: Peekable<Chars<'a>>,
+    }
+
+    impl Parser<'_> {
+        fn skip_whitespace(&mut self) {
+            while self.chars.next_if(|c| c.is_ascii_whitespace()).is_some() {}
+        }
+
+        fn expect(&mut self, expected: char) -> Result<(), String> {
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some(c) if c == expected => Ok(()),
+                Some(c) => Err(format!("expected `{expected}`, found `{c}`")),
+                None => Err(format!("expected `{expected}`, found end of input")),
+            }
+        }
+
+        fn keyword(&mut self, keyword: &str, value: Value) -> Result<Value, String> {
+            for expected in keyword.chars() {
+                if self.chars.next() != Some(expected) { return Err(format!("expected `{keyword}`")); }
+            }
+            Ok(value)
+        }
+
+        fn value(&mut self) -> Result<Value, String> {
+            self.skip_whitespace();
+            match self.chars.peek() {
+                Some('n') => self.keyword("null", Value::Null),
+                Some('t') => self.keyword("true", Value::Bool(true)),
+                Some('f') => self.keyword("false", Value::Bool(false)),
+                Some('"') => Ok(Value::String(self.string()?)),
+                Some('[') => {
+                    self.chars.next();
+                    let mut values = vec![];
+                    self.skip_whitespace();
+                    if self.chars.next_if_eq(&']').is_some() { return Ok(Value::Array(values)); }
+                    loop {
+                        values.push(self.value()?);
+                        self.skip_whitespace();
+                        match self.chars.next() {
+                            Some(',') => continue,
+                            Some(']') => return Ok(Value::Array(values)),
+                            _ => return Err("expected `,` or `]` in array".to_owned()),
+                        }
+                    }
+                }
+                Some('{') => {
+                    self.chars.next();
+                    let mut fields = vec![];
+                    self.skip_whitespace();
+                    if self.chars.next_if_eq(&'}').is_some() { return Ok(Value::Object(fields)); }
+                    loop {
+                        self.skip_whitespace();
+                        let name = self.string()?;
+                        self.expect(':')?;
+                        fields.push((name, self.value()?));
+                        self.skip_whitespace();
+                        match self.chars.next() {
+                            Some(',') => continue,
+                            Some('}') => return Ok(Value::Object(fields)),
+                            _ => return Err("expected `,` or `}` in object".to_owned()),
+                        }
+                    }
+                }
+                Some(c) if *c == '-' || c.is_ascii_digit() => {
+                    let mut number = String::new();
+                    while let Some(c) = self.chars.next_if(|c| matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E')) {
+                        number.push(c);
+                    }
+                    number.parse::<f64>().map(Value::Number).map_err(|_| format!("invalid number `{number}`"))
+                }
+                Some(c) => Err(format!("unexpected `{c}`")),
+                None => Err("unexpected end of input".to_owned()),
+            }
+        }
+
+        fn hex_escape(&mut self) -> Result<u32, String> {
+            let mut code = 0;
+            for _ in 0..4 {
+                let digit = self.chars.next().and_then(|c| c.to_digit(16)).ok_or("invalid unicode escape")?;
+                code = code * 16 + digit;
+            }
+            Ok(code)
+        }
+
+        fn string(&mut self) -> Result<String, String> {
+            if self.chars.next() != Some('"') { return Err("expected string".to_owned()); }
+
+            let mut string = String::new();
+            loop {
+                match self.chars.next().ok_or("unterminated string")? {
+                    '"' => return Ok(string),
+                    '\\' => match self.chars.next().ok_or("unterminated string")? {
+                        '"' => string.push('"'),
+                        '\\' => string.push('\\'),
+                        '/' => string.push('/'),
+                        'b' => string.push('\u{8}'),
+                        'f' => string.push('\u{c}'),
+                        'n' => string.push('\n'),
+                        'r' => string.push('\r'),
+                        't' => string.push('\t'),
+                        'u' => {
+                            let mut code = self.hex_escape()?;
+                            // Characters outside the basic multilingual plane are escaped as surrogate pairs.
+                            if (0xd800..0xdc00).contains(&code) {
+                                if self.chars.next() != Some('\\') || self.chars.next() != Some('u') { return Err("unpaired surrogate in unicode escape".to_owned()); }
+                                let low = self.hex_escape()?;
+                                code = 0x10000 + ((code - 0xd800) << 10) + (low.wrapping_sub(0xdc00) & 0x3ff);
+                            }
+                            string.push(char::from_u32(code).ok_or("invalid unicode escape")?);
+                        }
+                        c => return Err(format!("invalid escape `\\{c}`")),
+                    },
+                    c => string.push(c),
+                }
+            }
+        }
+    }
+
+    pub fn parse(src: &str) -> Result<Value, String> {
+        let mut parser = Parser { chars: src.chars().peekable() };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.chars.next().is_some() { return Err("trailing characters after JSON value".to_owned()); }
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::harness::MutationTestResult;
+
+    use super::{MutationRecord, SavedResults, escape_html};
+
+    fn mk_mutation(id: u32, result: MutationTestResult, results_per_test: &[(&str, MutationTestResult)]) -> MutationRecord {
+        MutationRecord {
+            id,
+            stable_id: id as u64,
+            op_name: "eq_op_invert".to_owned(),
+            display_name: "invert equality operator `==` to `!=`".to_owned(),
+            display_location: format!("src/lib.rs:{id}:5: {id}:11"),
+            target_path: "f".to_owned(),
+            substs_descr: String::new(),
+            safe: true,
+            result,
+            accepted: false,
+            results_per_test: results_per_test.iter().map(|&(test_name, result)| (test_name.to_owned(), result)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_matrix_csv() {
+        let saved_results = SavedResults {
+            tests: vec!["tests::a".to_owned(), "tests::b".to_owned()],
+            mutations: vec![
+                mk_mutation(1, MutationTestResult::Detected, &[("tests::a", MutationTestResult::Detected)]),
+                mk_mutation(2, MutationTestResult::Undetected, &[("tests::a", MutationTestResult::Undetected), ("tests::b", MutationTestResult::Undetected)]),
+            ],
+        };
+
+        assert_eq!(saved_results.to_matrix_csv(), "\
+mutation_id,stable_id,op_name,display_location,result,tests::a,tests::b
+1,0000000000000001,eq_op_invert,src/lib.rs:1:5: 1:11,detected,detected,
+2,0000000000000002,eq_op_invert,src/lib.rs:2:5: 2:11,undetected,undetected,undetected
+");
+    }
+
+    #[test]
+    fn test_text_summary() {
+        let saved_results = SavedResults {
+            tests: vec!["tests::a".to_owned()],
+            mutations: vec![
+                mk_mutation(1, MutationTestResult::TimedOut, &[("tests::a", MutationTestResult::TimedOut)]),
+                mk_mutation(2, MutationTestResult::Undetected, &[("tests::a", MutationTestResult::Undetected)]),
+            ],
+        };
+
+        assert_eq!(saved_results.to_text_summary(), "\
+mutations: 50.00%. 1 detected (1 timed out; 0 crashed); 1 undetected; 2 total
+     safe: 50.00%. 1 detected (1 timed out; 0 crashed); 1 undetected; 2 total
+   unsafe: none. 0 detected (0 timed out; 0 crashed); 0 undetected; 0 total
+
+undetected mutations:
+  - [eq_op_invert] invert equality operator `==` to `!=` at src/lib.rs:2:5: 2:11
+");
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let mut escaped_mutation = mk_mutation(2, MutationTestResult::Undetected, &[("tests::\"quoted\"\\path", MutationTestResult::Undetected), ("tests::b", MutationTestResult::TimedOut)]);
+        escaped_mutation.display_name = "replace `\"a\"` with `'\\n'`".to_owned();
+        escaped_mutation.substs_descr = "line 1\n\tline 2\u{1}".to_owned();
+        escaped_mutation.safe = false;
+        escaped_mutation.accepted = true;
+
+        let saved_results = SavedResults {
+            tests: vec!["tests::\"quoted\"\\path".to_owned(), "tests::b".to_owned(), "tests::ünïcode".to_owned()],
+            mutations: vec![
+                mk_mutation(1, MutationTestResult::Detected, &[("tests::b", MutationTestResult::Detected)]),
+                escaped_mutation,
+                mk_mutation(3, MutationTestResult::Crashed, &[]),
+            ],
+        };
+
+        let parsed_results = SavedResults::from_json(&saved_results.to_json()).unwrap();
+        assert_eq!(parsed_results.tests, saved_results.tests);
+        assert_eq!(parsed_results.mutations.len(), saved_results.mutations.len());
+        for (parsed, saved) in parsed_results.mutations.iter().zip(&saved_results.mutations) {
+            assert_eq!((parsed.id, parsed.stable_id, &parsed.op_name, &parsed.display_name, &parsed.display_location), (saved.id, saved.stable_id, &saved.op_name, &saved.display_name, &saved.display_location));
+            assert_eq!((&parsed.target_path, &parsed.substs_descr, parsed.safe, parsed.result, parsed.accepted), (&saved.target_path, &saved.substs_descr, saved.safe, saved.result, saved.accepted));
+            assert_eq!(parsed.results_per_test, saved.results_per_test);
+        }
+        assert_eq!(parsed_results.to_json(), saved_results.to_json());
+    }
+
+    #[test]
+    fn test_from_json_requires_top_level_fields() {
+        assert!(SavedResults::from_json(r#"{"tests": []}"#).is_err_and(|e| e.contains("`mutations`")));
+        assert!(SavedResults::from_json(r#"{"mutations": []}"#).is_err_and(|e| e.contains("`tests`")));
+        assert!(SavedResults::from_json("[]").is_err());
+        assert!(SavedResults::from_json(r#"{"tests": [], "mutations": []}"#).is_ok());
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(escape_html("a < b && \"c\""), "a &lt; b &amp;&amp; &quot;c&quot;");
+    }
+}
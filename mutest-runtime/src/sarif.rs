@@ -0,0 +1,181 @@
+//! Export of undetected mutations in the SARIF 2.1.0 format, for consumption by code scanning tools.
+//!
+//! Each undetected mutation is reported as a result of the rule named after its mutation operator. Results are
+//! fingerprinted by the stable id of the mutation, so that code scanning tools can track them across runs.
+//!
+//! See https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html.
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::metadata::MutationMeta;
+use crate::results::MutationRecord;
+
+/// Undetected mutation reported as a SARIF result, either from the metadata of the test harness, or from saved results.
+struct SarifResult<'a> {
+    op_name: &'a str,
+    display_name: &'a str,
+    display_location: &'a str,
+    substs_descr: &'a str,
+    stable_id_str: String,
+    accepted: bool,
+}
+
+/// Region of a display location (e.g. `src/lib.rs:42:5: 42:10`), as start and end line and column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Region {
+    start_line: u32,
+    start_column: u32,
+    end_line: u32,
+    end_column: u32,
+}
+
+/// Returns the file and region of the display location of a mutation (e.g. `src/lib.rs:42:5: 42:10`).
+fn parse_display_location(display_location: &str) -> Option<(&str, Region)> {
+    let (lo, hi) = display_location.rsplit_once(": ")?;
+    let (end_line, end_column) = hi.split_once(':')?;
+    let mut parts = lo.rsplitn(3, ':');
+    let start_column = parts.next()?;
+    let start_line = parts.next()?;
+    let file = parts.next()?;
+
+    Some((file, Region {
+        start_line: start_line.parse().ok()?,
+        start_column: start_column.parse().ok()?,
+        end_line: end_line.parse().ok()?,
+        end_column: end_column.parse().ok()?,
+    }))
+}
+
+pub(crate) fn escape_json_str(str: &str) -> String {
+    let mut escaped = String::with_capacity(str.len() + 2);
+    escaped.push('"');
+    for c in str.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => write!(escaped, "\\u{:04x}", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// Serialize the undetected mutations as a SARIF log. Mutations which are accepted (e.g. by a baseline) are
+/// reported as externally suppressed results.
+pub fn to_sarif(undetected_mutations: &[&MutationMeta], is_accepted: impl Fn(&MutationMeta) -> bool) -> String {
+    let results = undetected_mutations.iter()
+        .map(|mutation| SarifResult {
+            op_name: mutation.op_name(),
+            display_name: mutation.display_name(),
+            display_location: mutation.display_location(),
+            substs_descr: mutation.substs_descr(),
+            stable_id_str: mutation.stable_id_str(),
+            accepted: is_accepted(mutation),
+        })
+        .collect::<Vec<_>>();
+    results_to_sarif(&results)
+}
+
+/// Serialize the undetected mutations of saved results as a SARIF log, see [`to_sarif`].
+pub fn saved_results_to_sarif(undetected_mutations: &[&MutationRecord]) -> String {
+    let results = undetected_mutations.iter()
+        .map(|mutation| SarifResult {
+            op_name: &mutation.op_name,
+            display_name: &mutation.display_name,
+            display_location: &mutation.display_location,
+            substs_descr: &mutation.substs_descr,
+            stable_id_str: mutation.stable_id_str(),
+            accepted: mutation.accepted,
+        })
+        .collect::<Vec<_>>();
+    results_to_sarif(&results)
+}
+
+fn results_to_sarif(undetected_mutations: &[SarifResult]) -> String {
+    let op_names = undetected_mutations.iter().map(|mutation| mutation.op_name).collect::<BTreeSet<_>>();
+
+    let mut out = String::new();
+    writeln!(out, "{{").unwrap();
+    writeln!(out, "  \"$schema\": \"https://json.schemastore.org/sarif-2.1.0.json\",").unwrap();
+    writeln!(out, "  \"version\": \"2.1.0\",").unwrap();
+    writeln!(out, "  \"runs\": [{{").unwrap();
+    writeln!(out, "    \"tool\": {{").unwrap();
+    writeln!(out, "      \"driver\": {{").unwrap();
+    writeln!(out, "        \"name\": \"mutest-rs\",").unwrap();
+    writeln!(out, "        \"informationUri\": \"https://mutest.rs\",").unwrap();
+    writeln!(out, "        \"rules\": [").unwrap();
+    for (i, op_name) in op_names.iter().enumerate() {
+        let separator = if i + 1 < op_names.len() { "," } else { "" };
+        writeln!(out, "          {{ \"id\": {id}, \"shortDescription\": {{ \"text\": {descr} }} }}{separator}",
+            id = escape_json_str(op_name),
+            descr = escape_json_str(&format!("Mutation of operator `{op_name}` was not detected by any test.")),
+        ).unwrap();
+    }
+    writeln!(out, "        ]").unwrap();
+    writeln!(out, "      }}").unwrap();
+    writeln!(out, "    }},").unwrap();
+    writeln!(out, "    \"results\": [").unwrap();
+    for (i, mutation) in undetected_mutations.iter().enumerate() {
+        let mut message = format!("[{op_name}] mutation survived: {display_name}",
+            op_name = mutation.op_name,
+            display_name = mutation.display_name,
+        );
+        if !mutation.substs_descr.is_empty() {
+            write!(message, "\n{}", mutation.substs_descr).unwrap();
+        }
+
+        writeln!(out, "      {{").unwrap();
+        writeln!(out, "        \"ruleId\": {},", escape_json_str(mutation.op_name)).unwrap();
+        writeln!(out, "        \"level\": \"warning\",").unwrap();
+        writeln!(out, "        \"message\": {{ \"text\": {} }},", escape_json_str(&message)).unwrap();
+        if let Some((file, region)) = parse_display_location(mutation.display_location) {
+            writeln!(out, "        \"locations\": [{{").unwrap();
+            writeln!(out, "          \"physicalLocation\": {{").unwrap();
+            writeln!(out, "            \"artifactLocation\": {{ \"uri\": {}, \"uriBaseId\": \"%SRCROOT%\" }},", escape_json_str(&file.replace('\\', "/"))).unwrap();
+            writeln!(out, "            \"region\": {{ \"startLine\": {}, \"startColumn\": {}, \"endLine\": {}, \"endColumn\": {} }}",
+                region.start_line, region.start_column, region.end_line, region.end_column,
+            ).unwrap();
+            writeln!(out, "          }}").unwrap();
+            writeln!(out, "        }}],").unwrap();
+        }
+        if mutation.accepted {
+            writeln!(out, "        \"suppressions\": [{{ \"kind\": \"external\" }}],").unwrap();
+        }
+        writeln!(out, "        \"partialFingerprints\": {{ \"mutestStableId/v1\": {} }}", escape_json_str(&mutation.stable_id_str)).unwrap();
+        writeln!(out, "      }}{}", if i + 1 < undetected_mutations.len() { "," } else { "" }).unwrap();
+    }
+    writeln!(out, "    ]").unwrap();
+    writeln!(out, "  }}]").unwrap();
+    writeln!(out, "}}").unwrap();
+
+    out
+}
+
+pub fn write(path: &Path, undetected_mutations: &[&MutationMeta], is_accepted: impl Fn(&MutationMeta) -> bool) -> io::Result<()> {
+    fs::write(path, to_sarif(undetected_mutations, is_accepted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Region, escape_json_str, parse_display_location};
+
+    #[test]
+    fn test_parse_display_location() {
+        assert_eq!(parse_display_location("src/lib.rs:42:5: 43:10"), Some(("src/lib.rs", Region { start_line: 42, start_column: 5, end_line: 43, end_column: 10 })));
+        assert_eq!(parse_display_location("C:\\src\\lib.rs:7:1: 9:2"), Some(("C:\\src\\lib.rs", Region { start_line: 7, start_column: 1, end_line: 9, end_column: 2 })));
+        assert_eq!(parse_display_location("<unknown>"), None);
+    }
+
+    #[test]
+    fn test_escape_json_str() {
+        assert_eq!(escape_json_str("a \"b\"\n\\c\u{1}"), r#""a \"b\"\n\\c\u0001""#);
+    }
+}
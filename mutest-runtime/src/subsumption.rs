@@ -1,8 +1,10 @@
+use std::fmt::Write;
 use std::iter;
 
 use crate::data_structures::TestArray;
 use crate::detections::MutationDetectionMatrix;
 use crate::harness::MutationTestResult;
+use crate::log;
 use crate::metadata::{MutantMeta, SubstMap};
 use crate::test_runner;
 
@@ -49,8 +51,15 @@ where
     let mut has_subsuming_test = false;
 
     for (mutation_test_result, other_mutation_test_result) in iter::zip(mutation_test_results, other_mutation_test_results) {
-        let mutation_test_result = mutation_test_result.unwrap_or(MutationTestResult::Undetected);
-        let other_mutation_test_result = other_mutation_test_result.unwrap_or(MutationTestResult::Undetected);
+        // Flaky detections are discarded, and are considered as undetected.
+        let mutation_test_result = match mutation_test_result {
+            None | Some(MutationTestResult::FlakyDetected) => MutationTestResult::Undetected,
+            Some(mutation_test_result) => mutation_test_result,
+        };
+        let other_mutation_test_result = match other_mutation_test_result {
+            None | Some(MutationTestResult::FlakyDetected) => MutationTestResult::Undetected,
+            Some(other_mutation_test_result) => other_mutation_test_result,
+        };
 
         if mutation_test_result != MutationTestResult::Undetected { has_detecting_test = true; }
         if other_mutation_test_result != MutationTestResult::Undetected { other_has_detecting_test = true; }
@@ -134,35 +143,39 @@ pub fn print_mutation_subsumption_matrix<S: SubstMap>(mutation_subsumption_matri
     let total_mutations_count: usize = mutants.iter().map(|mutant| mutant.mutations.len()).sum();
     let mutation_id_w = total_mutations_count.checked_ilog10().unwrap_or(0) as usize + 1;
 
+    let mut line = String::new();
+
     // Print mutation ID numbers in 10's for matrix heading, like so `1        10        20...`.
-    print!("{:w$}", "", w = mutation_id_w + 1);
+    write!(line, "{:w$}", "", w = mutation_id_w + 1).unwrap();
     for mutation_idx in mutation_subsumption_matrix.iter_mutation_ids() {
         if mutation_idx == 1 {
-            print!("{mutation_idx:<9}");
+            write!(line, "{mutation_idx:<9}").unwrap();
         } else if mutation_idx % 10 == 0 {
-            print!("{mutation_idx:<10}");
+            write!(line, "{mutation_idx:<10}").unwrap();
         }
     }
-    println!();
+    log::info!("{line}");
+    line.clear();
 
     // Print mutation ID numbers' last digits for matrix heading, like so `12345678901234567890123...`.
-    print!("{:w$}", "", w = mutation_id_w + 1);
+    write!(line, "{:w$}", "", w = mutation_id_w + 1).unwrap();
     let mut mutation_id_chunks = mutation_subsumption_matrix.iter_mutation_ids().array_chunks::<10>();
     while let Some(_) = mutation_id_chunks.next() {
-        print!("1234567890");
+        line.push_str("1234567890");
     }
     if let Some(last_mutation_id_chunk) = mutation_id_chunks.into_remainder() {
-        print!("{}", &"1234567890"[..last_mutation_id_chunk.count()]);
+        line.push_str(&"1234567890"[..last_mutation_id_chunk.count()]);
     }
     // Print additional headers.
-    print!(" {:>mutation_id_w$}", "<=");
-    print!(" {:>mutation_id_w$}", ">=");
-    print!(" {:>mutation_id_w$}", "=");
-    println!();
+    write!(line, " {:>mutation_id_w$}", "<=").unwrap();
+    write!(line, " {:>mutation_id_w$}", ">=").unwrap();
+    write!(line, " {:>mutation_id_w$}", "=").unwrap();
+    log::info!("{line}");
+    line.clear();
 
     // Print one matrix row for each mutation for mutation-mutation subsumptions.
     for mutation_id in mutation_subsumption_matrix.iter_mutation_ids() {
-        print!("{:>mutation_id_w$} ", mutation_id);
+        write!(line, "{:>mutation_id_w$} ", mutation_id).unwrap();
 
         let mut subsumes_count: usize = 0;
         let mut subsumed_count: usize = 0;
@@ -171,15 +184,15 @@ pub fn print_mutation_subsumption_matrix<S: SubstMap>(mutation_subsumption_matri
         for (other_mutation_idx, subsumption) in mutation_subsumption_matrix.subsumptions_of(mutation_id).iter().enumerate() {
             let other_mutation_id = other_mutation_idx as u32 + 1;
             if other_mutation_id == mutation_id {
-                print!(" ");
+                line.push(' ');
                 continue;
             }
 
             match subsumption {
-                MutationSubsumption::No => print!("."),
-                MutationSubsumption::Subsumed => print!("<"),
-                MutationSubsumption::Subsumes => print!(">"),
-                MutationSubsumption::Indistinguishable => print!("="),
+                MutationSubsumption::No => line.push('.'),
+                MutationSubsumption::Subsumed => line.push('<'),
+                MutationSubsumption::Subsumes => line.push('>'),
+                MutationSubsumption::Indistinguishable => line.push('='),
             }
 
             if subsumption.subsumes() { subsumes_count += 1; }
@@ -187,24 +200,25 @@ pub fn print_mutation_subsumption_matrix<S: SubstMap>(mutation_subsumption_matri
             if subsumption.indistinguishable() { indistinguishable_count += 1; }
         }
 
-        print!(" {:>mutation_id_w$}", subsumed_count);
-        print!(" {:>mutation_id_w$}", subsumes_count);
-        print!(" {:>mutation_id_w$}", indistinguishable_count);
+        write!(line, " {:>mutation_id_w$}", subsumed_count).unwrap();
+        write!(line, " {:>mutation_id_w$}", subsumes_count).unwrap();
+        write!(line, " {:>mutation_id_w$}", indistinguishable_count).unwrap();
 
         let mutation = mutants.iter().find_map(|mutant| mutant.mutations.iter().find(|mutation| mutation.id == mutation_id)).unwrap();
-        print!(" {}", mutation.op_name);
+        write!(line, " {}", mutation.op_name()).unwrap();
 
-        println!();
+        log::info!("{line}");
+        line.clear();
     }
-    println!();
+    log::info!("");
 
     // Print legend of symbols used in the matrix.
-    println!("legend: .: none; <: subsumed; >: subsumes; =: indistinguishable");
-    println!();
+    log::info!("legend: .: none; <: subsumed; >: subsumes; =: indistinguishable");
+    log::info!("");
 
     if warn_non_exhaustive {
-        println!("warning: mutation subsumption matrix may be inaccurate as not all tests were evaluated, rerun with `--exhaustive`");
-        println!();
+        log::warn!("mutation subsumption matrix may be inaccurate as not all tests were evaluated, rerun with `--exhaustive`");
+        log::info!("");
     }
 }
 
@@ -325,4 +339,15 @@ mod tests {
             MutationSubsumption::Indistinguishable,
         );
     }
+
+    #[test]
+    fn test_flaky_detections_considered_undetected_subsumption() {
+        assert_eq!(
+            compute_mutation_subsumption(
+                [Some(MutationTestResult::Detected), Some(MutationTestResult::FlakyDetected), Some(MutationTestResult::Undetected)],
+                [Some(MutationTestResult::Detected), Some(MutationTestResult::Detected), Some(MutationTestResult::FlakyDetected)],
+            ),
+            MutationSubsumption::Subsumed,
+        );
+    }
 }
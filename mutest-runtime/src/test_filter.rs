@@ -0,0 +1,53 @@
+//! Filtering of the tests which participate in mutation analysis by their names.
+//!
+//! Filters follow the semantics of the filters of libtest: a test is included if its name contains any of the
+//! filters, or if no filters are given, and if its name does not contain any of the skip filters. The same filter is
+//! applied both by the driver, when computing the reachability of mutations from tests, and by the test harness, when
+//! profiling tests and evaluating mutations.
+
+#[derive(Clone, Debug, Default)]
+pub struct TestFilter {
+    /// Substrings of the names of the included tests.
+    pub filters: Vec<String>,
+    /// Substrings of the names of the excluded tests.
+    pub skip: Vec<String>,
+}
+
+impl TestFilter {
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty() && self.skip.is_empty()
+    }
+
+    pub fn matches(&self, test_name: &str) -> bool {
+        (self.filters.is_empty() || self.filters.iter().any(|filter| test_name.contains(filter.as_str())))
+            && !self.skip.iter().any(|skip| test_name.contains(skip.as_str()))
+    }
+
+    /// Parse the filters from the `--test-filter=<PATTERN>` and `--skip-test=<PATTERN>` arguments of the test harness.
+    pub fn from_args(args: &[&str]) -> Self {
+        Self {
+            filters: args.iter().flat_map(|arg| arg.strip_prefix("--test-filter=")).map(ToOwned::to_owned).collect(),
+            skip: args.iter().flat_map(|arg| arg.strip_prefix("--skip-test=")).map(ToOwned::to_owned).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TestFilter;
+
+    #[test]
+    fn test_matches() {
+        let test_filter = TestFilter {
+            filters: vec!["parser::".to_owned(), "lexer::".to_owned()],
+            skip: vec!["slow".to_owned()],
+        };
+
+        assert!(test_filter.matches("parser::tests::parse_expr"));
+        assert!(test_filter.matches("lexer::tests::lex_ident"));
+        assert!(!test_filter.matches("parser::tests::parse_slow_input"));
+        assert!(!test_filter.matches("codegen::tests::emit_fn"));
+
+        assert!(TestFilter::default().matches("codegen::tests::emit_fn"));
+    }
+}
@@ -1,4 +1,5 @@
 use std::any::Any;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::collections::hash_map::DefaultHasher;
 use std::env;
@@ -13,6 +14,9 @@ use std::sync::mpsc;
 use std::thread;
 use std::time::{Duration, Instant};
 
+use crate::log;
+use crate::reset_hooks;
+use crate::metadata::SpanMap;
 use crate::thread_pool::{self, ThreadPool};
 
 mod test {
@@ -22,10 +26,19 @@ mod test {
     pub use ::test::test::*;
 }
 
+/// Resource limits of tests run in isolated child processes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ChildProcessLimits {
+    /// Maximum number of child processes running concurrently, independently from the test concurrency.
+    pub max_concurrent_processes: Option<NonZeroUsize>,
+    /// Maximum size of the virtual memory of each child process, in bytes.
+    pub max_memory: Option<u64>,
+}
+
 #[derive(Clone)]
 pub enum TestRunStrategy {
     InProcess(Option<ThreadPool>),
-    InIsolatedChildProcess(Arc<dyn Fn(&mut process::Command) + Send + Sync>),
+    InIsolatedChildProcess(Arc<dyn Fn(&mut process::Command) + Send + Sync>, ChildProcessLimits),
 }
 
 impl fmt::Debug for TestRunStrategy {
@@ -35,9 +48,10 @@ impl fmt::Debug for TestRunStrategy {
                 f.debug_tuple("InProcess")
                     .field(thread_pool).finish()
             }
-            Self::InIsolatedChildProcess(_) => {
+            Self::InIsolatedChildProcess(_, limits) => {
                 f.debug_tuple("InIsolatedChildProcess")
-                    .field(&format_args!("_")).finish()
+                    .field(&format_args!("_"))
+                    .field(limits).finish()
             }
         }
     }
@@ -63,6 +77,15 @@ impl ThreadHandle {
             Self::ThreadPoolThread(job_handle) => job_handle.is_finished(),
         }
     }
+
+    /// Abandon the thread running a test which will not be waited on anymore (e.g. because it has timed out).
+    pub fn abandon(&self) {
+        match self {
+            // Standalone threads are not shared with other tests, so they do not hold up any other tests.
+            Self::StandaloneThread(_) => {}
+            Self::ThreadPoolThread(job_handle) => job_handle.abandon(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -134,6 +157,24 @@ impl TestResult {
         result
     }
 
+    /// Interprets the outcome of a test function run under [`panic::catch_unwind`].
+    ///
+    /// An error returned by a `fn() -> Result<(), E>` test is a test failure, regardless of whether the test is expected
+    /// to panic. In particular, the error is never matched against the expected message of a
+    /// `#[should_panic(expected = ..)]` test, as it was not raised by a panic.
+    pub fn from_test_fn_result(
+        test_should_panic: test::ShouldPanic,
+        test_fn_result: Result<Result<(), String>, Box<dyn Any + Send + 'static>>,
+        test_timeout: Option<Duration>,
+        task_exec_time: Option<Duration>,
+    ) -> Self {
+        match test_fn_result {
+            Ok(Ok(())) => TestResult::from_task(test_should_panic, Ok(()), test_timeout, task_exec_time),
+            Ok(Err(err)) => TestResult::FailedMsg(err),
+            Err(panic_payload) => TestResult::from_task(test_should_panic, Err(panic_payload.as_ref()), test_timeout, task_exec_time),
+        }
+    }
+
     pub fn from_exit_status(
         exit_status: process::ExitStatus,
         test_timeout: Option<Duration>,
@@ -173,6 +214,8 @@ pub struct Test {
     pub desc: test::TestDesc,
     pub test_fn: test::TestFn,
     pub timeout: Option<Duration>,
+    /// Execution time above which a benchmark is considered failed. Only applies to benchmarks.
+    pub bench_slowdown_limit: Option<Duration>,
 }
 
 #[derive(Debug)]
@@ -187,12 +230,34 @@ pub struct CompletedTest {
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ControlMsg {
     KillChildProcess,
+    /// Cooperatively cancel a test running in-process, see `is_cancelled`.
+    Cancel,
+}
+
+thread_local! {
+    /// Control channel of the test running in-process on the current thread, and whether it has been cancelled.
+    static IN_PROCESS_TEST_CONTROL: RefCell<Option<(mpsc::Receiver<ControlMsg>, bool)>> = const { RefCell::new(None) };
+}
+
+/// Returns `true` if the test running in-process on the current thread has been cancelled (e.g. because the mutation
+/// it evaluates has already been detected by another test), and its result will be discarded.
+///
+/// Tests running in-process cannot be halted, so long-running code can poll this to stop early.
+pub fn is_cancelled() -> bool {
+    IN_PROCESS_TEST_CONTROL.with_borrow_mut(|control| {
+        let Some((control_ch, cancelled)) = control else { return false; };
+        if !*cancelled {
+            *cancelled = matches!(control_ch.try_recv(), Ok(ControlMsg::Cancel));
+        }
+        *cancelled
+    })
 }
 
 fn run_test_in_process(
     id: test::TestId,
     desc: test::TestDesc,
     test_fn: Box<dyn FnOnce() -> Result<(), String> + Send>,
+    control_ch: Option<mpsc::Receiver<ControlMsg>>,
     monitor_ch: mpsc::Sender<CompletedTest>,
     test_timeout: Option<Duration>,
     no_capture: bool,
@@ -203,27 +268,21 @@ fn run_test_in_process(
         io::set_output_capture(Some(io_buffer.clone()));
     }
 
-    fn fold_err<T, E>(result: Result<Result<T, E>, Box<dyn Any + Send>>) -> Result<T, Box<dyn Any + Send>>
-    where
-        E: Send + 'static,
-    {
-        match result {
-            Ok(Err(e)) => Err(Box::new(e)),
-            Ok(Ok(v)) => Ok(v),
-            Err(e) => Err(e),
-        }
-    }
+    IN_PROCESS_TEST_CONTROL.set(control_ch.map(|control_ch| (control_ch, false)));
 
     let start = Instant::now();
-    let result = fold_err(panic::catch_unwind(panic::AssertUnwindSafe(test_fn)));
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(test_fn));
     let exec_time = start.elapsed();
 
+    // Reset the global state left behind by the test, so that it does not affect subsequent tests run in-process.
+    // Panics of reset hooks are not attributed to the test.
+    let _ = panic::catch_unwind(reset_hooks::run_reset_hooks);
+
+    IN_PROCESS_TEST_CONTROL.set(None);
+
     io::set_output_capture(None);
 
-    let test_result = match result {
-        Ok(_) => TestResult::from_task(desc.should_panic, Ok(()), test_timeout, Some(exec_time)),
-        Err(e) => TestResult::from_task(desc.should_panic, Err(e.as_ref()), test_timeout, Some(exec_time)),
-    };
+    let test_result = TestResult::from_test_fn_result(desc.should_panic, result, test_timeout, Some(exec_time));
 
     let stdout = io_buffer.lock().unwrap_or_else(|e| e.into_inner()).to_vec();
     let completed_test = CompletedTest { id, desc, result: test_result, exec_time: Some(exec_time), stdout };
@@ -239,10 +298,34 @@ fn run_test_in_process(
 
 pub static TEST_SUBPROCESS_INVOCATION: &str = "__ISOLATED_TEST_CASE";
 
+/// Limit the size of the virtual memory of the spawned process, making allocations fail beyond the limit, rather than
+/// exhausting the memory of the system.
+#[cfg(unix)]
+fn limit_memory(cmd: &mut Command, max_memory: u64) {
+    use std::os::unix::process::CommandExt;
+
+    // SAFETY: `setrlimit` is async-signal-safe, and the closure does not allocate.
+    unsafe {
+        cmd.pre_exec(move || {
+            let limit = libc::rlimit { rlim_cur: max_memory as libc::rlim_t, rlim_max: max_memory as libc::rlim_t };
+            match libc::setrlimit(libc::RLIMIT_AS, &limit) {
+                0 => Ok(()),
+                _ => Err(io::Error::last_os_error()),
+            }
+        });
+    }
+}
+
+#[cfg(not(unix))]
+fn limit_memory(_cmd: &mut Command, _max_memory: u64) {
+    panic!("memory limits are not supported on this platform");
+}
+
 fn spawn_test_subprocess(
     id: test::TestId,
     desc: test::TestDesc,
     cmd_hook: Arc<dyn Fn(&mut process::Command) + Send + Sync>,
+    limits: ChildProcessLimits,
     control_ch: Option<mpsc::Receiver<ControlMsg>>,
     monitor_ch: mpsc::Sender<CompletedTest>,
     test_timeout: Option<Duration>,
@@ -264,15 +347,19 @@ fn spawn_test_subprocess(
     // Allow caller to customize the test subprocess command.
     cmd_hook(&mut cmd);
 
+    if let Some(max_memory) = limits.max_memory {
+        limit_memory(&mut cmd, max_memory);
+    }
+
     let (test_result, exec_time, output) = 'test_exec: {
         let mut child = cmd.spawn().expect("failed to spawn subprocess for test");
 
         let start = Instant::now();
-        if let Some(test_timeout) = test_timeout {
+        if test_timeout.is_some() || control_ch.is_some() {
             loop {
                 if let Some(control_ch) = &control_ch {
                     match control_ch.try_recv() {
-                        // Kill test subprocess if the test run is stopped early.
+                        // Kill test subprocess if the test run is stopped early, or if the test is cancelled.
                         Ok(ControlMsg::KillChildProcess) => {
                             child.kill().expect("failed to kill test subprocess");
                             let output = child.wait_with_output().expect("failed to get output of killed test subprocess");
@@ -281,6 +368,9 @@ fn spawn_test_subprocess(
 
                         Err(mpsc::TryRecvError::Disconnected) => panic!("test subprocess left dangling: control channel disconnected"),
 
+                        // In-process cancellation does not apply to test subprocesses.
+                        Ok(ControlMsg::Cancel) => {}
+
                         // No control messages, continue normally.
                         Err(mpsc::TryRecvError::Empty) => {}
                     }
@@ -298,7 +388,7 @@ fn spawn_test_subprocess(
 
                 if let Some(_exit_status) = exit_status { break; }
 
-                if start.elapsed() > test_timeout {
+                if let Some(test_timeout) = test_timeout && start.elapsed() > test_timeout {
                     child.kill().expect("failed to kill test subprocess");
                     let output = child.wait_with_output().expect("failed to get output of killed test subprocess");
                     break 'test_exec (TestResult::TimedOut, start.elapsed(), output);
@@ -330,7 +420,7 @@ fn __rust_begin_short_backtrace<T, F: FnOnce() -> T>(f: F) -> T {
     test::black_box(result)
 }
 
-pub fn run_test_in_spawned_subprocess(test: test::TestDescAndFn) -> ! {
+pub fn run_test_in_spawned_subprocess(test: test::TestDescAndFn, span_map: Option<&'static SpanMap>) -> ! {
     let builtin_panic_hook = panic::take_hook();
 
     let exit_with_result = Arc::new(move |panic_info: Option<&panic::PanicInfo<'_>>| -> ! {
@@ -345,6 +435,13 @@ pub fn run_test_in_spawned_subprocess(test: test::TestDescAndFn) -> ! {
         }
         if let Some(info) = panic_info {
             builtin_panic_hook(info);
+
+            // The panic location points into the generated code, which is not what the user wrote.
+            if let Some(location) = info.location()
+                && let Some((file, line, column)) = span_map.and_then(|span_map| span_map.original_location(location.file(), location.line(), location.column()))
+            {
+                eprintln!("note: panic location {location} in the generated code corresponds to {file}:{line}:{column} in the original source");
+            }
         }
 
         match test_result {
@@ -371,11 +468,36 @@ pub fn run_test_in_spawned_subprocess(test: test::TestDescAndFn) -> ! {
         => unreachable!(),
     };
 
-    if let Err(e) = result { panic!("{e}"); }
+    // A returned error is a test failure, even for tests which are expected to panic,
+    // see `TestResult::from_test_fn_result`.
+    if let Err(e) = result {
+        eprintln!("{e}");
+        process::exit(TR_FAILED);
+    }
 
     exit_with_result(None);
 }
 
+/// Number of times the benchmarked code of a benchmark is run when evaluating the benchmark.
+const BENCH_SAMPLES_COUNT: usize = 10;
+
+/// Run the benchmarked code of the benchmark a fixed number of times, failing if the samples took longer to run than
+/// the slowdown limit.
+fn run_bench(bench_fn: fn(&mut test::Bencher) -> Result<(), String>, slowdown_limit: Option<Duration>) -> Result<(), String> {
+    let start = Instant::now();
+    for _ in 0..BENCH_SAMPLES_COUNT {
+        if is_cancelled() { return Err("benchmark cancelled".to_owned()); }
+        test::bench::run_once(bench_fn)?;
+    }
+    let exec_time = start.elapsed();
+
+    if let Some(slowdown_limit) = slowdown_limit && exec_time > slowdown_limit {
+        return Err(format!("benchmark slowed down: took {exec_time:.2?}, exceeding the limit of {slowdown_limit:.2?}"));
+    }
+
+    Ok(())
+}
+
 fn run_test(
     id: test::TestId,
     test: Test,
@@ -384,7 +506,7 @@ fn run_test(
     test_run_strategy: TestRunStrategy,
     no_capture: bool,
 ) -> Option<ThreadHandle> {
-    let Test { desc, test_fn, timeout } = test;
+    let Test { desc, test_fn, timeout, bench_slowdown_limit } = test;
 
     let ignore_because_no_process_support = match desc.should_panic {
         test::ShouldPanic::Yes | test::ShouldPanic::YesWithMessage(_) => {
@@ -394,7 +516,13 @@ fn run_test(
         _ => false,
     };
 
-    if desc.ignore || ignore_because_no_process_support {
+    // Benchmarks are only evaluated in-process, as their slowdown limit is not propagated to test subprocesses.
+    let ignore_because_bench_in_subprocess = match (&test_fn, &test_run_strategy) {
+        (test::TestFn::StaticBenchFn(_), TestRunStrategy::InIsolatedChildProcess(..)) => true,
+        _ => false,
+    };
+
+    if desc.ignore || ignore_because_no_process_support || ignore_because_bench_in_subprocess {
         let message = CompletedTest { id, desc, result: TestResult::Ignored, exec_time: None, stdout: Vec::new() };
         monitor_ch.send(message).unwrap();
         return None;
@@ -412,17 +540,17 @@ fn run_test(
     ) -> Option<ThreadHandle> {
         let thread_pool = match &test_run_strategy {
             TestRunStrategy::InProcess(thread_pool) => thread_pool.clone(),
-            TestRunStrategy::InIsolatedChildProcess(_) => None,
+            TestRunStrategy::InIsolatedChildProcess(..) => None,
         };
 
         let name = desc.name.clone();
         let run_test = move || {
             match test_run_strategy {
                 TestRunStrategy::InProcess(_)
-                => run_test_in_process(id, desc, test_fn, monitor_ch, test_timeout, no_capture),
+                => run_test_in_process(id, desc, test_fn, control_ch, monitor_ch, test_timeout, no_capture),
 
-                TestRunStrategy::InIsolatedChildProcess(cmd_hook)
-                => spawn_test_subprocess(id, desc, cmd_hook, control_ch, monitor_ch, test_timeout, no_capture),
+                TestRunStrategy::InIsolatedChildProcess(cmd_hook, limits)
+                => spawn_test_subprocess(id, desc, cmd_hook, limits, control_ch, monitor_ch, test_timeout, no_capture),
             }
         };
 
@@ -464,11 +592,15 @@ fn run_test(
             run_test_impl(id, desc, test_fn, test_run_strategy, control_ch, monitor_ch, timeout, no_capture)
         }
 
+        test::TestFn::StaticBenchFn(f) => {
+            let test_fn = Box::new(move || __rust_begin_short_backtrace(|| run_bench(f, bench_slowdown_limit)));
+            run_test_impl(id, desc, test_fn, test_run_strategy, control_ch, monitor_ch, timeout, no_capture)
+        }
+
         test::TestFn::DynTestFn(_) => {
             panic!("dynamic tests are not supported");
         }
 
-        | test::TestFn::StaticBenchFn(_)
         | test::TestFn::StaticBenchAsTestFn(_)
         | test::TestFn::DynBenchFn(_)
         | test::TestFn::DynBenchAsTestFn(_) => {
@@ -494,6 +626,8 @@ pub struct RunningTest {
     pub start_time: Instant,
     pub control_tx: mpsc::Sender<ControlMsg>,
     pub join_handle: Option<ThreadHandle>,
+    /// Whether the test has been cancelled, and its result is to be discarded, see `Flow::CancelRunning`.
+    pub cancelled: bool,
 }
 
 #[derive(Debug)]
@@ -503,9 +637,15 @@ pub enum TestEvent {
     Result(CompletedTest),
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Flow {
     Continue,
+    /// Cancel the running tests with the given names, and continue running the remaining tests.
+    /// Tests run in isolated child processes are killed, and complete as ignored. Tests run in-process cannot be halted,
+    /// so they are signalled to stop cooperatively (see `is_cancelled`), and their results are discarded. The test run
+    /// does not end until they have completed (or timed out), as they may still depend on the active mutations.
+    CancelRunning(Vec<test::TestName>),
+    /// Stop running tests. Tests still running in-process are cancelled, and waited for, like with `CancelRunning`.
     Stop,
 }
 
@@ -520,11 +660,12 @@ where
 {
     let tests = tests.into_iter().enumerate()
         .map(|(i, test)| (test::TestId(i), test))
-        .filter(|(_, test)| matches!(test.test_fn, test::TestFn::StaticTestFn(_) | test::TestFn::DynTestFn(_)))
+        .filter(|(_, test)| matches!(test.test_fn, test::TestFn::StaticTestFn(_) | test::TestFn::DynTestFn(_) | test::TestFn::StaticBenchFn(_)))
         .collect::<Vec<_>>();
 
     let concurrency = match &test_run_strategy {
         TestRunStrategy::InProcess(Some(thread_pool)) => thread_pool.max_thread_count(),
+        TestRunStrategy::InIsolatedChildProcess(_, ChildProcessLimits { max_concurrent_processes: Some(max_concurrent_processes), .. }) => max_concurrent_processes.get(),
         _ => concurrency(),
     };
 
@@ -539,6 +680,7 @@ where
     let (test_tx, test_rx) = mpsc::channel::<CompletedTest>();
 
     if concurrency == 1 {
+        // Tests are run one at a time, so no other tests are running to be cancelled by `Flow::CancelRunning`.
         macro event($event:expr) {
             if let Flow::Stop = on_test_event($event, &mut remaining_tests)? {
                 let remaining_tests = remaining_tests.into_iter().map(|(_, test)| test).collect();
@@ -547,7 +689,8 @@ where
         }
 
         while let Some((id, test)) = remaining_tests.pop() {
-            if let Some(_) = test.timeout {
+            // Test subprocesses enforce their own timeouts, see `spawn_test_subprocess`.
+            if let Some(_) = test.timeout && let TestRunStrategy::InProcess(_) = &test_run_strategy {
                 panic!("test timeout was requested but concurrency is unavailable");
             }
 
@@ -597,17 +740,96 @@ where
             }
         }
 
+        fn cancel_running_tests(test_names: &[test::TestName], running_tests: &mut RunningTestMap, test_run_strategy: &TestRunStrategy) {
+            let test_ids = running_tests.iter()
+                .filter(|(_, running_test)| test_names.contains(&running_test.desc.name))
+                .map(|(&test_id, _)| test_id)
+                .collect::<Vec<_>>();
+
+            for test_id in test_ids {
+                match test_run_strategy {
+                    // The killed test subprocess reports its completion through the test monitor channel.
+                    TestRunStrategy::InIsolatedChildProcess(..) => {
+                        // Send errors will only occur if the test subprocess already completed, see `cleanup_isolated_tests`.
+                        let _ = running_tests[&test_id].control_tx.send(ControlMsg::KillChildProcess);
+                    }
+
+                    // The cancelled test keeps running until it completes, at which point its completion is discarded.
+                    TestRunStrategy::InProcess(_) => {
+                        let running_test = running_tests.get_mut(&test_id).unwrap();
+                        // Send errors will only occur if the test already completed, in which case its completion is
+                        // still discarded.
+                        let _ = running_test.control_tx.send(ControlMsg::Cancel);
+                        running_test.cancelled = true;
+                    }
+                }
+            }
+        }
+
+        fn wait_for_cancelled_tests(running_tests: &mut RunningTestMap, lingering_tests: &mut RunningTestMap, test_rx: &mpsc::Receiver<CompletedTest>) {
+            while !running_tests.is_empty() {
+                // Sacrifice the threads of cancelled tests which exceeded their timeout, and may never finish (e.g. due
+                // to an infinite loop), leaving them lingering.
+                let now = Instant::now();
+                let timed_out_test_ids = running_tests.iter()
+                    .filter(|(_, running_test)| running_test.timeout.is_some_and(|test_timeout| running_test.start_time + test_timeout <= now))
+                    .map(|(&test_id, _)| test_id)
+                    .collect::<Vec<_>>();
+                for test_id in timed_out_test_ids {
+                    let running_test = running_tests.remove(&test_id).unwrap();
+                    if let Some(join_handle) = &running_test.join_handle {
+                        join_handle.abandon();
+                    }
+                    lingering_tests.insert(test_id, running_test);
+                }
+
+                if running_tests.is_empty() { break; }
+
+                let deadline = running_tests.values().filter_map(|test| test.timeout.map(|test_timeout| test.start_time + test_timeout)).reduce(Ord::min);
+                let completed_test = match deadline {
+                    Some(deadline) => {
+                        match test_rx.recv_deadline(deadline) {
+                            Err(mpsc::RecvTimeoutError::Timeout) => { continue; }
+                            Err(mpsc::RecvTimeoutError::Disconnected) => panic!("test monitor channel disconnected"),
+                            Ok(completed_test) => completed_test,
+                        }
+                    }
+                    None => test_rx.recv().expect("test monitor channel disconnected"),
+                };
+
+                // Completions of previously timed out tests are bogus, and are discarded like those of cancelled tests.
+                let Some(running_test) = running_tests.remove(&completed_test.id) else { continue; };
+
+                if let Some(join_handle) = running_test.join_handle {
+                    let _ = join_handle.join();
+                }
+            }
+        }
+
         macro event($event:expr) {
-            if let Flow::Stop = on_test_event($event, &mut remaining_tests)? {
-                if let TestRunStrategy::InIsolatedChildProcess(_) = &test_run_strategy {
-                    cleanup_isolated_tests(&mut running_tests, &test_rx);
+            match on_test_event($event, &mut remaining_tests)? {
+                Flow::Continue => {}
+                Flow::CancelRunning(test_names) => {
+                    cancel_running_tests(&test_names, &mut running_tests, &test_run_strategy);
                 }
+                Flow::Stop => {
+                    match &test_run_strategy {
+                        TestRunStrategy::InIsolatedChildProcess(..) => {
+                            cleanup_isolated_tests(&mut running_tests, &test_rx);
+                        }
+                        TestRunStrategy::InProcess(_) => {
+                            let test_names = running_tests.values().map(|running_test| running_test.desc.name.clone()).collect::<Vec<_>>();
+                            cancel_running_tests(&test_names, &mut running_tests, &test_run_strategy);
+                            wait_for_cancelled_tests(&mut running_tests, &mut lingering_tests, &test_rx);
+                        }
+                    }
 
-                lingering_tests.extend(running_tests.drain());
+                    lingering_tests.extend(running_tests.drain());
 
-                let remaining_tests = remaining_tests.into_iter().map(|(_, test)| test).collect();
-                let lingering_tests = lingering_tests.into_values().collect();
-                return Ok((remaining_tests, lingering_tests));
+                    let remaining_tests = remaining_tests.into_iter().map(|(_, test)| test).collect();
+                    let lingering_tests = lingering_tests.into_values().collect();
+                    return Ok((remaining_tests, lingering_tests));
+                }
             }
         }
 
@@ -623,7 +845,7 @@ where
 
                 let (control_tx, control_rx) = mpsc::channel::<ControlMsg>();
                 let join_handle = run_test(id, test, Some(control_rx), test_tx.clone(), test_run_strategy.clone(), no_capture);
-                running_tests.insert(id, RunningTest { desc, timeout, start_time: Instant::now(), control_tx, join_handle });
+                running_tests.insert(id, RunningTest { desc, timeout, start_time: Instant::now(), control_tx, join_handle, cancelled: false });
             }
 
             if let TestRunStrategy::InProcess(_) = &test_run_strategy {
@@ -634,6 +856,15 @@ where
                     let exec_time = running_test.start_time.elapsed();
                     if exec_time > test_timeout {
                         match &running_test.join_handle {
+                            // Cancelled tests have no result to report, but are similarly left lingering.
+                            Some(join_handle) if !join_handle.is_finished() && running_test.cancelled => {
+                                let running_test = running_tests.remove(&test_id).unwrap();
+                                if let Some(join_handle) = &running_test.join_handle {
+                                    join_handle.abandon();
+                                }
+                                event!(TestEvent::Queue(running_tests.len(), remaining_tests.len()));
+                                lingering_tests.insert(test_id, running_test);
+                            }
                             Some(join_handle) if !join_handle.is_finished() => {
                                 let completed_test = CompletedTest {
                                     id: test_id,
@@ -645,11 +876,16 @@ where
                                 };
 
                                 let running_test = running_tests.remove(&test_id).unwrap();
+                                // Sacrifice the thread of the timed out test, which may never finish (e.g. due to an
+                                // infinite loop), so that it does not hold up the remaining tests.
+                                if let Some(join_handle) = &running_test.join_handle {
+                                    join_handle.abandon();
+                                }
                                 event!(TestEvent::Queue(running_tests.len(), remaining_tests.len()));
                                 lingering_tests.insert(test_id, running_test);
                                 event!(TestEvent::Result(completed_test));
                             }
-                            None => eprintln!("test timed out but it cannot be halted as it is not running concurrently"),
+                            None => log::warn!("test timed out but it cannot be halted as it is not running concurrently"),
                             _ => {}
                         }
                     }
@@ -681,6 +917,14 @@ where
                 continue;
             };
 
+            if running_test.cancelled {
+                if let Some(join_handle) = running_test.join_handle {
+                    let _ = join_handle.join();
+                }
+                event!(TestEvent::Queue(running_tests.len(), remaining_tests.len()));
+                continue;
+            }
+
             if let Some(join_handle) = running_test.join_handle {
                 if let Err(_) = join_handle.join() {
                     if let TestResult::Ok = completed_test.result {
@@ -698,3 +942,196 @@ where
         return Ok((remaining_tests, lingering_tests));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::any::Any;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    use super::{Flow, Test, TestEvent, TestResult, TestRunStrategy, ThreadPool, test};
+
+    #[cfg(unix)]
+    #[test]
+    fn test_limit_memory_sets_virtual_memory_limit_of_child_process() {
+        let mut cmd = super::Command::new("sh");
+        cmd.args(["-c", "ulimit -v"]);
+        super::limit_memory(&mut cmd, 512 * 1024 * 1024);
+
+        let output = cmd.output().expect("cannot run shell");
+        assert!(output.status.success());
+        // The limit is reported in kilobytes.
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "524288");
+    }
+
+    fn static_test(name: &'static str, test_fn: fn() -> Result<(), String>) -> Test {
+        let desc = test::TestDesc {
+            name: test::TestName::StaticTestName(name),
+            ignore: false,
+            ignore_message: None,
+            source_file: "",
+            start_line: 0,
+            start_col: 0,
+            end_line: 0,
+            end_col: 0,
+            compile_fail: false,
+            no_run: false,
+            should_panic: test::ShouldPanic::No,
+            test_type: test::TestType::UnitTest,
+        };
+        Test { desc, test_fn: test::TestFn::StaticTestFn(test_fn), timeout: None, bench_slowdown_limit: None }
+    }
+
+    /// Runs a long-running test, which does not poll `is_cancelled`, alongside a fast test, whose result triggers the
+    /// given flow, then checks that a test run for the next mutant does not overlap with the cancelled test.
+    fn check_cancelled_test_does_not_outlive_mutant(
+        flow: Flow,
+        active_mutant: &'static AtomicUsize,
+        long_test_fn: fn() -> Result<(), String>,
+        long_test_finished: &'static AtomicBool,
+        long_test_observed_mutant: &'static AtomicUsize,
+        next_mutant_test_fn: fn() -> Result<(), String>,
+    ) {
+        active_mutant.store(1, Ordering::SeqCst);
+
+        let tests = vec![static_test("long", long_test_fn), static_test("fast", || Ok(()))];
+        let mut results = vec![];
+        let on_test_event = |event: TestEvent, _: &mut Vec<(test::TestId, Test)>| -> Result<_, ()> {
+            let TestEvent::Result(completed_test) = event else { return Ok(Flow::Continue); };
+            results.push(completed_test.desc.name.as_slice().to_owned());
+            match completed_test.desc.name.as_slice() {
+                "fast" => Ok(flow.clone()),
+                _ => Ok(Flow::Continue),
+            }
+        };
+        super::run_tests(tests, on_test_event, TestRunStrategy::InProcess(Some(ThreadPool::new(2, None, None))), false).unwrap();
+
+        // The cancelled test must have completed against the mutant it was started for, without reporting a result.
+        assert!(long_test_finished.load(Ordering::SeqCst));
+        assert_eq!(long_test_observed_mutant.load(Ordering::SeqCst), 1);
+        assert_eq!(results, vec!["fast".to_owned()]);
+
+        active_mutant.store(2, Ordering::SeqCst);
+
+        let mut next_mutant_results = vec![];
+        let on_test_event = |event: TestEvent, _: &mut Vec<(test::TestId, Test)>| -> Result<_, ()> {
+            if let TestEvent::Result(completed_test) = event {
+                next_mutant_results.push(completed_test.result);
+            }
+            Ok(Flow::Continue)
+        };
+        let tests = vec![static_test("next_mutant", next_mutant_test_fn), static_test("fast", || Ok(()))];
+        super::run_tests(tests, on_test_event, TestRunStrategy::InProcess(Some(ThreadPool::new(2, None, None))), false).unwrap();
+
+        assert_eq!(next_mutant_results, vec![TestResult::Ok, TestResult::Ok]);
+        assert_eq!(long_test_observed_mutant.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_cancelled_in_process_test_completes_before_next_mutant() {
+        static ACTIVE_MUTANT: AtomicUsize = AtomicUsize::new(0);
+        static LONG_TEST_FINISHED: AtomicBool = AtomicBool::new(false);
+        static LONG_TEST_OBSERVED_MUTANT: AtomicUsize = AtomicUsize::new(0);
+
+        check_cancelled_test_does_not_outlive_mutant(
+            Flow::CancelRunning(vec![test::TestName::StaticTestName("long")]),
+            &ACTIVE_MUTANT,
+            || {
+                thread::sleep(Duration::from_millis(200));
+                LONG_TEST_OBSERVED_MUTANT.store(ACTIVE_MUTANT.load(Ordering::SeqCst), Ordering::SeqCst);
+                LONG_TEST_FINISHED.store(true, Ordering::SeqCst);
+                Ok(())
+            },
+            &LONG_TEST_FINISHED,
+            &LONG_TEST_OBSERVED_MUTANT,
+            || match ACTIVE_MUTANT.load(Ordering::SeqCst) {
+                2 => Ok(()),
+                mutant => Err(format!("expected mutant 2 to be active, found {mutant}")),
+            },
+        );
+    }
+
+    #[test]
+    fn test_stopped_in_process_test_completes_before_next_mutant() {
+        static ACTIVE_MUTANT: AtomicUsize = AtomicUsize::new(0);
+        static LONG_TEST_FINISHED: AtomicBool = AtomicBool::new(false);
+        static LONG_TEST_OBSERVED_MUTANT: AtomicUsize = AtomicUsize::new(0);
+
+        check_cancelled_test_does_not_outlive_mutant(
+            Flow::Stop,
+            &ACTIVE_MUTANT,
+            || {
+                thread::sleep(Duration::from_millis(200));
+                LONG_TEST_OBSERVED_MUTANT.store(ACTIVE_MUTANT.load(Ordering::SeqCst), Ordering::SeqCst);
+                LONG_TEST_FINISHED.store(true, Ordering::SeqCst);
+                Ok(())
+            },
+            &LONG_TEST_FINISHED,
+            &LONG_TEST_OBSERVED_MUTANT,
+            || match ACTIVE_MUTANT.load(Ordering::SeqCst) {
+                2 => Ok(()),
+                mutant => Err(format!("expected mutant 2 to be active, found {mutant}")),
+            },
+        );
+    }
+
+    fn panic_payload(msg: &'static str) -> Box<dyn Any + Send + 'static> {
+        Box::new(msg)
+    }
+
+    #[test]
+    fn test_passing_test_ok() {
+        assert_eq!(TestResult::from_test_fn_result(test::ShouldPanic::No, Ok(Ok(())), None, None), TestResult::Ok);
+    }
+
+    #[test]
+    fn test_panicking_test_failed() {
+        assert_eq!(TestResult::from_test_fn_result(test::ShouldPanic::No, Err(panic_payload("boom")), None, None), TestResult::Failed);
+    }
+
+    #[test]
+    fn test_err_returning_test_failed() {
+        assert_eq!(
+            TestResult::from_test_fn_result(test::ShouldPanic::No, Ok(Err("error".to_owned())), None, None),
+            TestResult::FailedMsg("error".to_owned()),
+        );
+    }
+
+    #[test]
+    fn test_should_panic_test_panicking_ok() {
+        assert_eq!(TestResult::from_test_fn_result(test::ShouldPanic::Yes, Err(panic_payload("boom")), None, None), TestResult::Ok);
+    }
+
+    #[test]
+    fn test_should_panic_test_not_panicking_failed() {
+        assert!(matches!(
+            TestResult::from_test_fn_result(test::ShouldPanic::Yes, Ok(Ok(())), None, None),
+            TestResult::FailedMsg(_),
+        ));
+    }
+
+    #[test]
+    fn test_should_panic_test_panicking_with_expected_msg_ok() {
+        assert_eq!(
+            TestResult::from_test_fn_result(test::ShouldPanic::YesWithMessage("overflow"), Err(panic_payload("attempt to add with overflow")), None, None),
+            TestResult::Ok,
+        );
+    }
+
+    #[test]
+    fn test_should_panic_test_panicking_with_unexpected_msg_failed() {
+        assert!(matches!(
+            TestResult::from_test_fn_result(test::ShouldPanic::YesWithMessage("overflow"), Err(panic_payload("index out of bounds")), None, None),
+            TestResult::FailedMsg(_),
+        ));
+    }
+
+    #[test]
+    fn test_should_panic_test_returning_err_with_expected_msg_failed() {
+        assert_eq!(
+            TestResult::from_test_fn_result(test::ShouldPanic::YesWithMessage("overflow"), Ok(Err("overflow".to_owned())), None, None),
+            TestResult::FailedMsg("overflow".to_owned()),
+        );
+    }
+}
@@ -0,0 +1,120 @@
+//! Scheduling of the tests of a mutant across the available workers, grouped by the mutation they can detect.
+//!
+//! The mutations batched into a mutant are reachable from disjoint sets of tests, so each test is evaluated for exactly
+//! one mutation. Tests are dequeued for the mutation with the fewest tests in flight, so that the tests running
+//! concurrently are spread across as many mutations as possible. Once a mutation is detected, its other in-flight tests
+//! are redundant, so keeping few of them in flight for any one mutation reduces redundant executions. The queue is
+//! rebalanced as tests start and complete, and as the tests of detected mutations are dropped from the queue.
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+pub struct TestScheduler<M> {
+    /// Number of tests currently running for each mutation.
+    in_flight: HashMap<M, usize>,
+}
+
+impl<M: Copy + Eq + Hash> TestScheduler<M> {
+    pub fn new() -> Self {
+        Self { in_flight: Default::default() }
+    }
+
+    pub fn in_flight(&self, mutation: M) -> usize {
+        self.in_flight.get(&mutation).copied().unwrap_or(0)
+    }
+
+    /// Record that a test for the mutation started running.
+    pub fn start(&mut self, mutation: M) {
+        *self.in_flight.entry(mutation).or_default() += 1;
+    }
+
+    /// Record that a test for the mutation completed.
+    pub fn complete(&mut self, mutation: M) {
+        if let Some(in_flight) = self.in_flight.get_mut(&mutation) {
+            *in_flight = in_flight.saturating_sub(1);
+        }
+    }
+
+    /// Reorder the tests into the order in which they should be run.
+    ///
+    /// The relative order of the tests of each mutation is retained. Each next test is taken from the mutation with the
+    /// fewest tests in flight (counting the tests already placed before it), with ties broken by the order in which the
+    /// mutations first appear.
+    pub fn schedule<T>(&self, tests: &mut Vec<T>, mutation_of: impl Fn(&T) -> M) {
+        let mut groups = Vec::<(M, usize, VecDeque<T>)>::new();
+        for test in tests.drain(..) {
+            let mutation = mutation_of(&test);
+            match groups.iter_mut().find(|(m, _, _)| *m == mutation) {
+                Some((_, _, group)) => group.push_back(test),
+                None => groups.push((mutation, self.in_flight(mutation), VecDeque::from([test]))),
+            }
+        }
+
+        loop {
+            let Some((_, load, group)) = groups.iter_mut()
+                .filter(|(_, _, group)| !group.is_empty())
+                .min_by_key(|(_, load, _)| *load)
+            else { break; };
+
+            tests.push(group.pop_front().expect("empty groups are skipped"));
+            *load += 1;
+        }
+    }
+
+    /// Reorder a queue of tests, from which the next test is popped from its end, see `schedule`.
+    pub fn schedule_queue<T>(&self, queue: &mut Vec<T>, mutation_of: impl Fn(&T) -> M) {
+        queue.reverse();
+        self.schedule(queue, mutation_of);
+        queue.reverse();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TestScheduler;
+
+    fn mutation_of(test: &(u32, &str)) -> u32 {
+        test.0
+    }
+
+    #[test]
+    fn test_schedule_interleaves_mutations() {
+        let scheduler = TestScheduler::<u32>::new();
+
+        let mut tests = vec![(1, "a1"), (1, "a2"), (1, "a3"), (2, "b1"), (3, "c1"), (3, "c2")];
+        scheduler.schedule(&mut tests, mutation_of);
+
+        let names = tests.iter().map(|(_, name)| *name).collect::<Vec<_>>();
+        assert_eq!(names, ["a1", "b1", "c1", "a2", "c2", "a3"]);
+    }
+
+    #[test]
+    fn test_schedule_accounts_for_in_flight_tests() {
+        let mut scheduler = TestScheduler::<u32>::new();
+        scheduler.start(1);
+        scheduler.start(1);
+        scheduler.start(2);
+
+        let mut tests = vec![(1, "a1"), (1, "a2"), (2, "b1"), (3, "c1"), (3, "c2")];
+        scheduler.schedule(&mut tests, mutation_of);
+
+        let names = tests.iter().map(|(_, name)| *name).collect::<Vec<_>>();
+        assert_eq!(names, ["c1", "b1", "c2", "a1", "a2"]);
+
+        scheduler.complete(1);
+        scheduler.complete(1);
+        assert_eq!(scheduler.in_flight(1), 0);
+    }
+
+    #[test]
+    fn test_schedule_queue_pops_in_schedule_order() {
+        let scheduler = TestScheduler::<u32>::new();
+
+        let mut queue = vec![(2, "b2"), (2, "b1"), (1, "a2"), (1, "a1")];
+        scheduler.schedule_queue(&mut queue, mutation_of);
+
+        let mut names = vec![];
+        while let Some((_, name)) = queue.pop() { names.push(name); }
+        assert_eq!(names, ["a1", "b1", "a2", "b2"]);
+    }
+}
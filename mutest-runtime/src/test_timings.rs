@@ -0,0 +1,85 @@
+//! Execution times of tests, recorded during the profiling run of the test harness.
+//!
+//! Test timings are used by the analysis to estimate the cost of evaluating mutants, and are stored as lines of the
+//! execution time of the test in seconds, followed by the name of the test:
+//!
+//! ```text
+//! 0.001250 tests::test_parse
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct TestTimings {
+    pub exec_times: BTreeMap<String, Duration>,
+}
+
+impl TestTimings {
+    pub fn parse(src: &str) -> Result<Self, String> {
+        let mut exec_times = BTreeMap::new();
+
+        for (i, line) in src.lines().enumerate() {
+            let line_no = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue; }
+
+            let Some((exec_time, test_name)) = line.split_once(' ') else {
+                return Err(format!("line {line_no}: expected execution time followed by test name"));
+            };
+            let exec_time = exec_time.parse::<f64>().ok()
+                .and_then(|exec_time| Duration::try_from_secs_f64(exec_time).ok())
+                .ok_or_else(|| format!("line {line_no}: invalid execution time `{exec_time}`"))?;
+
+            exec_times.insert(test_name.trim().to_owned(), exec_time);
+        }
+
+        Ok(Self { exec_times })
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let src = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::parse(&src)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for (test_name, exec_time) in &self.exec_times {
+            writeln!(out, "{:.6} {test_name}", exec_time.as_secs_f64()).unwrap();
+        }
+        out
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::TestTimings;
+
+    #[test]
+    fn test_roundtrip() {
+        let test_timings = TestTimings {
+            exec_times: [
+                ("tests::test_parse".to_owned(), Duration::from_micros(1250)),
+                ("tests::test_lex".to_owned(), Duration::from_secs(2)),
+            ].into_iter().collect(),
+        };
+
+        assert_eq!(TestTimings::parse(&test_timings.to_text()).unwrap(), test_timings);
+    }
+
+    #[test]
+    fn test_parse_invalid_exec_time() {
+        assert!(TestTimings::parse("-1.0 tests::test_parse\n").is_err());
+        assert!(TestTimings::parse("tests::test_parse\n").is_err());
+    }
+}
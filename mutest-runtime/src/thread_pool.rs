@@ -2,7 +2,7 @@ use std::any::Any;
 use std::cell::UnsafeCell;
 use std::fmt;
 use std::panic;
-use std::sync::atomic::{self, AtomicU32, AtomicUsize};
+use std::sync::atomic::{self, AtomicBool, AtomicU32, AtomicUsize};
 use std::sync::{Arc, Mutex};
 use std::sync::mpsc;
 use std::thread;
@@ -19,7 +19,9 @@ fn __rust_begin_short_backtrace<T, F: FnOnce() -> T>(f: F) -> T {
 pub type Thunk<'a> = Box<dyn FnOnce() + Send + 'a>;
 
 struct Packet {
-    result: UnsafeCell<Option<Result<(), Box<dyn Any + Send + 'static>>>>
+    result: UnsafeCell<Option<Result<(), Box<dyn Any + Send + 'static>>>>,
+    /// Set when the job either finishes or is abandoned, whichever happens first.
+    settled: AtomicBool,
 }
 
 unsafe impl Sync for Packet {}
@@ -54,6 +56,7 @@ impl AtomicSingleWait {
 pub struct JobHandle {
     finished: AtomicSingleWait,
     packet: Arc<Packet>,
+    data: Arc<ThreadPoolData>,
 }
 
 impl fmt::Debug for JobHandle {
@@ -71,6 +74,16 @@ impl JobHandle {
     pub fn is_finished(&self) -> bool {
         Arc::strong_count(&self.packet) == 1
     }
+
+    /// Abandon the job if it has not finished yet (e.g. because it is stuck in an infinite loop). The thread running
+    /// the job is sacrificed and replaced by a new thread in the pool, so that the job does not hold up other jobs.
+    /// The sacrificed thread exits once the job finishes, if ever.
+    pub fn abandon(&self) {
+        if self.packet.settled.swap(true, atomic::Ordering::SeqCst) { return; }
+
+        self.data.abandoned_count.fetch_add(1, atomic::Ordering::SeqCst);
+        spawn_in_pool(self.data.clone());
+    }
 }
 
 struct ThreadPoolData {
@@ -82,6 +95,7 @@ struct ThreadPoolData {
     active_threads_count: AtomicUsize,
     queued_count: AtomicUsize,
     panic_count: AtomicUsize,
+    abandoned_count: AtomicUsize,
 }
 
 struct Sentinel<'a> {
@@ -137,10 +151,17 @@ fn spawn_in_pool(data: Arc<ThreadPoolData>) {
 
             let result = panic::catch_unwind(panic::AssertUnwindSafe(|| __rust_begin_short_backtrace(job)));
             unsafe { *packet.result.get() = Some(result) };
+            let abandoned = packet.settled.swap(true, atomic::Ordering::SeqCst);
             drop(packet);
             finished.wake_all();
 
             data.active_threads_count.fetch_sub(1, atomic::Ordering::SeqCst);
+
+            // The job was abandoned, and this thread has already been replaced in the pool.
+            if abandoned {
+                data.abandoned_count.fetch_sub(1, atomic::Ordering::SeqCst);
+                break;
+            }
         }
 
         sentinel.cancel();
@@ -164,6 +185,7 @@ impl ThreadPool {
             active_threads_count: AtomicUsize::new(0),
             queued_count: AtomicUsize::new(0),
             panic_count: AtomicUsize::new(0),
+            abandoned_count: AtomicUsize::new(0),
         });
 
         for _ in 0..size {
@@ -179,6 +201,7 @@ impl ThreadPool {
     {
         let packet = Arc::new(Packet {
             result: UnsafeCell::new(None),
+            settled: AtomicBool::new(false),
         });
 
         let finished = AtomicSingleWait::new();
@@ -186,7 +209,7 @@ impl ThreadPool {
         self.data.queued_count.fetch_add(1, atomic::Ordering::SeqCst);
         self.job_sender.send((Box::new(job), packet.clone(), finished.clone())).expect("cannot send job into queue");
 
-        JobHandle { finished, packet }
+        JobHandle { finished, packet, data: self.data.clone() }
     }
 
     pub fn max_thread_count(&self) -> usize {
@@ -204,6 +227,11 @@ impl ThreadPool {
     pub fn panic_count(&self) -> usize {
         self.data.panic_count.load(atomic::Ordering::Relaxed)
     }
+
+    /// Number of abandoned jobs which are still running on their sacrificed threads.
+    pub fn abandoned_count(&self) -> usize {
+        self.data.abandoned_count.load(atomic::Ordering::SeqCst)
+    }
 }
 
 impl Clone for ThreadPool {
@@ -223,6 +251,61 @@ impl fmt::Debug for ThreadPool {
             .field("active_count", &self.active_count())
             .field("queued_count", &self.queued_count())
             .field("panic_count", &self.panic_count())
+            .field("abandoned_count", &self.abandoned_count())
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use super::ThreadPool;
+
+    fn wait_until(mut condition: impl FnMut() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !condition() {
+            assert!(Instant::now() < deadline, "timed out waiting for condition");
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_abandoned_job_counted_until_finished() {
+        let pool = ThreadPool::new(1, None, None);
+
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let stuck_job = pool.execute(move || release_rx.recv().unwrap());
+
+        stuck_job.abandon();
+        assert_eq!(pool.abandoned_count(), 1);
+        // Abandoning the same job again has no effect.
+        stuck_job.abandon();
+        assert_eq!(pool.abandoned_count(), 1);
+
+        // The sacrificed thread is replaced, so the abandoned job does not hold up other jobs.
+        pool.execute(|| {}).join().unwrap();
+        assert_eq!(pool.abandoned_count(), 1);
+
+        release_tx.send(()).unwrap();
+        stuck_job.join().unwrap();
+        wait_until(|| pool.abandoned_count() == 0);
+    }
+
+    #[test]
+    fn test_abandoned_job_does_not_block_shutdown() {
+        let pool = ThreadPool::new(1, None, None);
+
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let stuck_job = pool.execute(move || release_rx.recv().unwrap());
+        stuck_job.abandon();
+
+        // Shutting down the pool does not wait for the abandoned job.
+        drop(pool);
+
+        release_tx.send(()).unwrap();
+        stuck_job.join().unwrap();
+    }
+}
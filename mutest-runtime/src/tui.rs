@@ -0,0 +1,349 @@
+//! Interactive terminal UI for exploring the results of mutation analysis.
+//!
+//! While mutants are evaluated, the UI shows the live status of the evaluation. Once the evaluation is finished, the
+//! mutations can be listed and inspected, and individual mutations can be evaluated again on demand, like with
+//! `--simulate`. The UI is rendered using ANSI escape sequences, and is controlled by line-based commands on stdin.
+//! While the UI is active, status messages are not rendered to stdout, but are still appended to the log file.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+use crate::harness::{MutationTestResult, MutationTestResults};
+use crate::metadata::MutationMeta;
+use crate::test_runner;
+
+const PAGE_SIZE: usize = 20;
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Label of the verdict of the mutation, colored by the verdict, and padded to the given width.
+/// Mutations which were not yet evaluated have no verdict.
+fn mutation_result_label(mutation_result: Option<MutationTestResult>, width: usize) -> String {
+    let (style, label) = match mutation_result {
+        None => ("2", "pending"),
+        Some(MutationTestResult::Undetected) => ("1;31", "undetected"),
+        Some(MutationTestResult::Detected) => ("32", "detected"),
+        Some(MutationTestResult::TimedOut) => ("33", "timed out"),
+        Some(MutationTestResult::Crashed) => ("35", "crashed"),
+        Some(MutationTestResult::FlakyDetected) => ("36", "flaky"),
+    };
+    format!("\x1b[{style}m{label:<width$}\x1b[0m")
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    NextPage,
+    PrevPage,
+    ToggleUndetectedOnly,
+    Show(u32),
+    Rerun(u32),
+    Help,
+    Quit,
+}
+
+fn parse_command(line: &str) -> Option<Command> {
+    let mut words = line.split_whitespace();
+    let command = match (words.next(), words.next()) {
+        (None, _) | (Some("n"), None) => Command::NextPage,
+        (Some("p"), None) => Command::PrevPage,
+        (Some("u"), None) => Command::ToggleUndetectedOnly,
+        (Some("r"), Some(mutation_id)) => Command::Rerun(mutation_id.parse().ok()?),
+        (Some("h" | "?"), None) => Command::Help,
+        (Some("q"), None) => Command::Quit,
+        (Some(mutation_id), None) => Command::Show(mutation_id.parse().ok()?),
+        _ => return None,
+    };
+    if words.next().is_some() { return None; }
+    Some(command)
+}
+
+pub struct Tui {
+    /// All mutations of the program, sorted by id.
+    mutations: Vec<&'static MutationMeta>,
+    results: HashMap<u32, MutationTestResults>,
+    undetected_only: bool,
+    page: usize,
+    /// Mutation whose details are shown instead of the list of mutations, if any.
+    viewed_mutation: Option<&'static MutationMeta>,
+    /// Message shown below the rendered screen until the next command.
+    message: Option<String>,
+}
+
+impl Tui {
+    pub fn new(mutations: impl IntoIterator<Item = &'static MutationMeta>) -> Self {
+        let mut mutations = mutations.into_iter().collect::<Vec<_>>();
+        mutations.sort_unstable_by_key(|mutation| mutation.id);
+
+        Self { mutations, results: HashMap::new(), undetected_only: false, page: 0, viewed_mutation: None, message: None }
+    }
+
+    pub fn record(&mut self, mutation_id: u32, results: MutationTestResults) {
+        self.results.insert(mutation_id, results);
+    }
+
+    fn mutation_result(&self, mutation_id: u32) -> Option<MutationTestResult> {
+        self.results.get(&mutation_id).map(|results| results.result)
+    }
+
+    fn counts_line(&self) -> String {
+        let count = |result| self.results.values().filter(|results| results.result == result).count();
+        format!("{evaluated} of {total} mutations evaluated: {detected} detected; {timed_out} timed out; {crashed} crashed; {undetected} undetected",
+            evaluated = self.results.len(),
+            total = self.mutations.len(),
+            detected = count(MutationTestResult::Detected),
+            timed_out = count(MutationTestResult::TimedOut),
+            crashed = count(MutationTestResult::Crashed),
+            undetected = count(MutationTestResult::Undetected),
+        )
+    }
+
+    fn print_mutation_line(&self, mutation: &MutationMeta) {
+        println!("{id:>5}  {result} [{op_names}] {display_name} at {display_location}",
+            id = mutation.id,
+            result = mutation_result_label(self.mutation_result(mutation.id), 10),
+            op_names = mutation.op_names_descr(),
+            display_name = mutation.display_name(),
+            display_location = mutation.display_location(),
+        );
+    }
+
+    /// Render the live status of the evaluation, with the most recently evaluated mutations.
+    pub fn render_progress(&self, recent_mutations: &[&'static MutationMeta]) {
+        print!("{CLEAR_SCREEN}");
+        println!("\x1b[1mmutest\x1b[0m: evaluating mutants\n");
+        println!("{}\n", self.counts_line());
+        for mutation in recent_mutations.iter().rev().take(PAGE_SIZE) {
+            self.print_mutation_line(mutation);
+        }
+        let _ = io::stdout().flush();
+    }
+
+    fn listed_mutations(&self) -> Vec<&'static MutationMeta> {
+        self.mutations.iter()
+            .filter(|mutation| !self.undetected_only || self.mutation_result(mutation.id) == Some(MutationTestResult::Undetected))
+            .copied()
+            .collect()
+    }
+
+    fn render_list(&self) {
+        let listed_mutations = self.listed_mutations();
+        let pages_count = listed_mutations.len().div_ceil(PAGE_SIZE).max(1);
+
+        print!("{CLEAR_SCREEN}");
+        println!("\x1b[1mmutest\x1b[0m: {listed} (page {page} of {pages_count})\n",
+            listed = match self.undetected_only {
+                true => "undetected mutations",
+                false => "all mutations",
+            },
+            page = self.page + 1,
+        );
+        println!("{}\n", self.counts_line());
+        for mutation in listed_mutations.iter().skip(self.page * PAGE_SIZE).take(PAGE_SIZE) {
+            self.print_mutation_line(mutation);
+        }
+        println!("\n[n]ext page, [p]revious page, [u]ndetected only, <id> show mutation, r <id> re-run mutation, [q]uit");
+    }
+
+    fn render_mutation(&self, mutation: &MutationMeta, tests: &[test_runner::Test]) {
+        print!("{CLEAR_SCREEN}");
+        println!("\x1b[1mmutation {id}\x1b[0m ({stable_id}): {result}\n",
+            id = mutation.id,
+            stable_id = mutation.stable_id_str(),
+            result = mutation_result_label(self.mutation_result(mutation.id), 0),
+        );
+        println!("[{op_names}] {display_name}", op_names = mutation.op_names_descr(), display_name = mutation.display_name());
+        println!("  at {display_location}", display_location = mutation.display_location());
+        println!("  in {target_path}\n", target_path = mutation.target_path());
+
+        // The diagnostic shows the original code, with the substitutions of the mutation.
+        print!("{}", mutation.undetected_diagnostic());
+
+        let mut reaching_tests = tests.iter()
+            .filter_map(|test| Some((test.desc.name.as_slice(), mutation.distance_from(test.desc.name.as_slice())?, &test.desc.name)))
+            .collect::<Vec<_>>();
+        reaching_tests.sort_by_key(|&(test_name, distance, _)| (distance, test_name));
+
+        println!("\nreached by {count} {descr}:",
+            count = reaching_tests.len(),
+            descr = match reaching_tests.len() {
+                1 => "test",
+                _ => "tests",
+            },
+        );
+        let results_per_test = self.results.get(&mutation.id).map(|results| &results.results_per_test);
+        for (test_name, distance, test_desc_name) in reaching_tests {
+            let test_result = results_per_test.and_then(|results_per_test| results_per_test.get(test_desc_name).copied().flatten());
+            println!("  {test_name} (distance {distance}): {result}",
+                result = match test_result {
+                    Some(test_result) => mutation_result_label(Some(test_result), 0),
+                    None => "\x1b[2mnot run\x1b[0m".to_owned(),
+                },
+            );
+        }
+
+        println!("\n<enter> back to list, r <id> re-run mutation, [q]uit");
+    }
+
+    /// Apply the command to the state of the UI. Returns `false` once the user quits.
+    fn handle_command(&mut self, command: Option<Command>, rerun: &mut impl FnMut(&'static MutationMeta) -> Result<MutationTestResults, String>) -> bool {
+        match command {
+            Some(Command::Quit) => return false,
+            Some(Command::NextPage) if self.viewed_mutation.is_some() => self.viewed_mutation = None,
+            Some(Command::NextPage) => {
+                let pages_count = self.listed_mutations().len().div_ceil(PAGE_SIZE).max(1);
+                self.page = (self.page + 1).min(pages_count - 1);
+            }
+            Some(Command::PrevPage) => {
+                self.viewed_mutation = None;
+                self.page = self.page.saturating_sub(1);
+            }
+            Some(Command::ToggleUndetectedOnly) => {
+                self.viewed_mutation = None;
+                self.undetected_only = !self.undetected_only;
+                self.page = 0;
+            }
+            Some(Command::Show(mutation_id) | Command::Rerun(mutation_id)) if !self.mutations.iter().any(|mutation| mutation.id == mutation_id) => {
+                self.message = Some(format!("no mutation with id {mutation_id}"));
+            }
+            Some(Command::Show(mutation_id)) => {
+                self.viewed_mutation = self.mutations.iter().find(|mutation| mutation.id == mutation_id).copied();
+            }
+            Some(Command::Rerun(mutation_id)) => {
+                let Some(mutation) = self.mutations.iter().find(|mutation| mutation.id == mutation_id).copied() else { unreachable!() };
+
+                println!("re-running mutation {mutation_id}");
+                match rerun(mutation) {
+                    Ok(results) => {
+                        self.record(mutation_id, results);
+                        self.viewed_mutation = Some(mutation);
+                    }
+                    Err(e) => self.message = Some(format!("cannot re-run mutation {mutation_id}: {e}")),
+                }
+            }
+            Some(Command::Help) | None => {
+                self.message = Some("commands: [n]ext page, [p]revious page, [u]ndetected only, <id> show mutation, r <id> re-run mutation, [q]uit".to_owned());
+            }
+        }
+
+        true
+    }
+
+    /// Explore the results interactively, until the user quits. Mutations are evaluated again on demand using the
+    /// given function, which returns the new results of the mutation.
+    pub fn explore(&mut self, tests: &[test_runner::Test], mut rerun: impl FnMut(&'static MutationMeta) -> Result<MutationTestResults, String>) {
+        self.page = 0;
+        self.viewed_mutation = None;
+        self.render_list();
+
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else { break; };
+
+            if !self.handle_command(parse_command(&line), &mut rerun) { break; }
+
+            match self.viewed_mutation {
+                Some(mutation) => self.render_mutation(mutation, tests),
+                None => self.render_list(),
+            }
+            if let Some(message) = self.message.take() {
+                println!("\x1b[1;33m{message}\x1b[0m");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::harness::{MutationTestResult, MutationTestResults};
+    use crate::metadata::{MetadataTables, MutationMeta, MutationSafety, SpanMap, static_map};
+
+    use super::{Command, Tui, parse_command};
+
+    static TABLES: MetadataTables = MetadataTables {
+        strs: &["arg_default_shadow", "replace `a` with `Default::default()`", "src/lib.rs:1:1: 1:2", "add", ""],
+        test_idxs: static_map! {},
+        span_map: &SpanMap { generated_file: "src/lib.rs", files: &[], entries: &[] },
+    };
+
+    static MUTATION_1: MutationMeta = MutationMeta {
+        id: 1, stable_id: 1, safety: MutationSafety::Safe, tables: &TABLES,
+        op_name: 0, merged_op_names: &[], display_name: 1, display_location: 2, target_path: 3,
+        reachable_from: &[], substs_descr: 4, undetected_diagnostic: 4,
+    };
+
+    static MUTATION_2: MutationMeta = MutationMeta {
+        id: 2, stable_id: 2, safety: MutationSafety::Safe, tables: &TABLES,
+        op_name: 0, merged_op_names: &[], display_name: 1, display_location: 2, target_path: 3,
+        reachable_from: &[], substs_descr: 4, undetected_diagnostic: 4,
+    };
+
+    fn no_rerun(_mutation: &'static MutationMeta) -> Result<MutationTestResults, String> {
+        panic!("unexpected re-run");
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command(""), Some(Command::NextPage));
+        assert_eq!(parse_command("n"), Some(Command::NextPage));
+        assert_eq!(parse_command(" p "), Some(Command::PrevPage));
+        assert_eq!(parse_command("u"), Some(Command::ToggleUndetectedOnly));
+        assert_eq!(parse_command("12"), Some(Command::Show(12)));
+        assert_eq!(parse_command("r 12"), Some(Command::Rerun(12)));
+        assert_eq!(parse_command("?"), Some(Command::Help));
+        assert_eq!(parse_command("q"), Some(Command::Quit));
+
+        assert_eq!(parse_command("r"), None);
+        assert_eq!(parse_command("r x"), None);
+        assert_eq!(parse_command("n 2"), None);
+        assert_eq!(parse_command("r 1 2"), None);
+        assert_eq!(parse_command("quit"), None);
+    }
+
+    #[test]
+    fn test_paging_and_filtering() {
+        let mut tui = Tui::new([&MUTATION_2, &MUTATION_1]);
+        assert_eq!(tui.mutations.iter().map(|mutation| mutation.id).collect::<Vec<_>>(), [1, 2]);
+
+        // All mutations fit on a single page.
+        assert!(tui.handle_command(Some(Command::NextPage), &mut no_rerun));
+        assert_eq!(tui.page, 0);
+        assert!(tui.handle_command(Some(Command::PrevPage), &mut no_rerun));
+        assert_eq!(tui.page, 0);
+
+        tui.record(2, MutationTestResults { result: MutationTestResult::Detected, ..Default::default() });
+        tui.record(1, MutationTestResults { result: MutationTestResult::Undetected, ..Default::default() });
+        assert!(tui.handle_command(Some(Command::ToggleUndetectedOnly), &mut no_rerun));
+        assert_eq!(tui.listed_mutations().iter().map(|mutation| mutation.id).collect::<Vec<_>>(), [1]);
+
+        assert!(tui.handle_command(None, &mut no_rerun));
+        assert!(tui.message.take().is_some_and(|message| message.starts_with("commands:")));
+
+        assert!(!tui.handle_command(Some(Command::Quit), &mut no_rerun));
+    }
+
+    #[test]
+    fn test_show_and_rerun_mutation() {
+        let mut tui = Tui::new([&MUTATION_1, &MUTATION_2]);
+
+        assert!(tui.handle_command(Some(Command::Show(2)), &mut no_rerun));
+        assert_eq!(tui.viewed_mutation.map(|mutation| mutation.id), Some(2));
+        // Going to the next page from a mutation returns to the list.
+        assert!(tui.handle_command(Some(Command::NextPage), &mut no_rerun));
+        assert!(tui.viewed_mutation.is_none());
+
+        assert!(tui.handle_command(Some(Command::Rerun(3)), &mut no_rerun));
+        assert_eq!(tui.message.take().as_deref(), Some("no mutation with id 3"));
+
+        let mut rerun = |mutation: &'static MutationMeta| {
+            assert_eq!(mutation.id, 1);
+            Ok(MutationTestResults { result: MutationTestResult::Detected, ..Default::default() })
+        };
+        assert!(tui.handle_command(Some(Command::Rerun(1)), &mut rerun));
+        assert_eq!(tui.mutation_result(1), Some(MutationTestResult::Detected));
+        assert_eq!(tui.viewed_mutation.map(|mutation| mutation.id), Some(1));
+
+        let mut failing_rerun = |_mutation: &'static MutationMeta| Err("mutant cannot be activated".to_owned());
+        assert!(tui.handle_command(Some(Command::Rerun(2)), &mut failing_rerun));
+        assert_eq!(tui.message.take().as_deref(), Some("cannot re-run mutation 2: mutant cannot be activated"));
+        assert_eq!(tui.mutation_result(2), None);
+    }
+}
@@ -0,0 +1,220 @@
+//! Results of tests against mutations, recorded across runs, so that test runs with a known, deterministic outcome can
+//! be skipped in subsequent flakiness analysis iterations and resumed runs (see `--flakes-min-confidence`).
+//!
+//! Verdicts are keyed by the stable id of the mutation and the name of the test, and are stored as lines of the stable
+//! id, the result, and the number of runs in which the result was observed, followed by the name of the test. Results
+//! which differed across runs are stored as `flaky`, and are never skipped.
+//!
+//! Verdicts are only valid for the build of the test harness they were recorded with, as changes to the code or the
+//! tests may change the results. The cache is therefore headed by the fingerprint of the build, and caches of other
+//! builds are discarded:
+//!
+//! ```text
+//! build fedcba9876543210
+//! 0123456789abcdef detected 3 tests::test_parse
+//! 0123456789abcdef flaky 0 tests::test_lex
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::hash::{DefaultHasher, Hasher};
+use std::io;
+use std::path::Path;
+
+use crate::harness::MutationTestResult;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// The same result was observed in each of the given number of runs.
+    Consistent(MutationTestResult, usize),
+    /// Different results were observed across runs.
+    Flaky,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct VerdictCache {
+    /// Fingerprint of the build of the test harness which the verdicts were recorded with, see `build_fingerprint`.
+    pub build_fingerprint: Option<u64>,
+    pub verdicts: BTreeMap<(u64, String), Verdict>,
+}
+
+/// Fingerprint of the build of the test harness executable, which is the hash of its contents. Any change to the code
+/// or the tests of the crate results in a different fingerprint.
+pub fn build_fingerprint(executable_path: &Path) -> io::Result<u64> {
+    let mut hasher = DefaultHasher::new();
+    hasher.write(&fs::read(executable_path)?);
+    Ok(hasher.finish())
+}
+
+/// Name of the result, as stored in verdict caches and saved results.
+pub fn result_name(result: MutationTestResult) -> &'static str {
+    match result {
+        MutationTestResult::Undetected => "undetected",
+        MutationTestResult::Detected => "detected",
+        MutationTestResult::TimedOut => "timed_out",
+        MutationTestResult::Crashed => "crashed",
+        MutationTestResult::FlakyDetected => "flaky_detected",
+    }
+}
+
+pub fn parse_result_name(name: &str) -> Option<MutationTestResult> {
+    match name {
+        "undetected" => Some(MutationTestResult::Undetected),
+        "detected" => Some(MutationTestResult::Detected),
+        "timed_out" => Some(MutationTestResult::TimedOut),
+        "crashed" => Some(MutationTestResult::Crashed),
+        "flaky_detected" => Some(MutationTestResult::FlakyDetected),
+        _ => None,
+    }
+}
+
+impl VerdictCache {
+    pub fn new(build_fingerprint: u64) -> Self {
+        Self { build_fingerprint: Some(build_fingerprint), verdicts: Default::default() }
+    }
+
+    /// Returns `true` if the verdicts were recorded with the given build of the test harness.
+    pub fn is_valid_for_build(&self, build_fingerprint: u64) -> bool {
+        self.build_fingerprint == Some(build_fingerprint)
+    }
+
+    /// Record the result of a run of the test against the mutation.
+    pub fn record(&mut self, mutation_stable_id: u64, test_name: &str, result: MutationTestResult) {
+        let verdict = self.verdicts.entry((mutation_stable_id, test_name.to_owned())).or_insert(Verdict::Consistent(result, 0));
+        *verdict = match *verdict {
+            Verdict::Consistent(consistent_result, runs_count) if consistent_result == result => Verdict::Consistent(result, runs_count + 1),
+            _ => Verdict::Flaky,
+        };
+    }
+
+    /// The result of the test against the mutation, if the same result was observed in at least the given number of
+    /// runs, and never differed.
+    pub fn known_result(&self, mutation_stable_id: u64, test_name: &str, min_confidence: usize) -> Option<MutationTestResult> {
+        match self.verdicts.get(&(mutation_stable_id, test_name.to_owned()))? {
+            &Verdict::Consistent(result, runs_count) if runs_count >= min_confidence => Some(result),
+            _ => None,
+        }
+    }
+
+    pub fn parse(src: &str) -> Result<Self, String> {
+        let mut build_fingerprint = None;
+        let mut verdicts = BTreeMap::new();
+
+        for (i, line) in src.lines().enumerate() {
+            let line_no = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue; }
+
+            if let Some(fingerprint) = line.strip_prefix("build ") {
+                let fingerprint = u64::from_str_radix(fingerprint.trim(), 16).map_err(|_| format!("line {line_no}: invalid build fingerprint `{fingerprint}`"))?;
+                build_fingerprint = Some(fingerprint);
+                continue;
+            }
+
+            let mut parts = line.splitn(4, ' ');
+            let (Some(stable_id), Some(result), Some(runs_count), Some(test_name)) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+                return Err(format!("line {line_no}: expected stable id, result, and runs count, followed by test name"));
+            };
+            let stable_id = u64::from_str_radix(stable_id, 16).map_err(|_| format!("line {line_no}: invalid stable id `{stable_id}`"))?;
+            let runs_count = runs_count.parse::<usize>().map_err(|_| format!("line {line_no}: invalid runs count `{runs_count}`"))?;
+            let verdict = match result {
+                "flaky" => Verdict::Flaky,
+                _ => {
+                    let result = parse_result_name(result).ok_or_else(|| format!("line {line_no}: invalid result `{result}`"))?;
+                    Verdict::Consistent(result, runs_count)
+                }
+            };
+
+            verdicts.insert((stable_id, test_name.trim().to_owned()), verdict);
+        }
+
+        Ok(Self { build_fingerprint, verdicts })
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let src = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::parse(&src)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        if let Some(build_fingerprint) = self.build_fingerprint {
+            writeln!(out, "build {build_fingerprint:016x}").unwrap();
+        }
+        for ((stable_id, test_name), verdict) in &self.verdicts {
+            let (result, runs_count) = match *verdict {
+                Verdict::Consistent(result, runs_count) => (result_name(result), runs_count),
+                Verdict::Flaky => ("flaky", 0),
+            };
+            writeln!(out, "{stable_id:016x} {result} {runs_count} {test_name}").unwrap();
+        }
+        out
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::process;
+
+    use crate::harness::MutationTestResult;
+
+    use super::{Verdict, VerdictCache, build_fingerprint};
+
+    #[test]
+    fn test_roundtrip() {
+        let verdict_cache = VerdictCache {
+            build_fingerprint: Some(0xfedcba9876543210),
+            verdicts: [
+                ((0x0123456789abcdef, "tests::test_parse".to_owned()), Verdict::Consistent(MutationTestResult::Detected, 3)),
+                ((0x0123456789abcdef, "tests::test_lex".to_owned()), Verdict::Flaky),
+            ].into_iter().collect(),
+        };
+
+        assert_eq!(VerdictCache::parse(&verdict_cache.to_text()).unwrap(), verdict_cache);
+    }
+
+    #[test]
+    fn test_known_result_requires_consistent_runs() {
+        let mut verdict_cache = VerdictCache::default();
+        verdict_cache.record(1, "tests::test_parse", MutationTestResult::Detected);
+        verdict_cache.record(1, "tests::test_parse", MutationTestResult::Detected);
+        verdict_cache.record(2, "tests::test_parse", MutationTestResult::Detected);
+        verdict_cache.record(2, "tests::test_parse", MutationTestResult::Undetected);
+
+        assert_eq!(verdict_cache.known_result(1, "tests::test_parse", 2), Some(MutationTestResult::Detected));
+        assert_eq!(verdict_cache.known_result(1, "tests::test_parse", 3), None);
+        assert_eq!(verdict_cache.known_result(2, "tests::test_parse", 1), None);
+        assert_eq!(verdict_cache.known_result(3, "tests::test_parse", 1), None);
+    }
+
+    #[test]
+    fn test_rebuilt_test_harness_invalidates_verdicts() {
+        let harness_path = env::temp_dir().join(format!("mutest-verdict-cache-harness-{}", process::id()));
+
+        fs::write(&harness_path, "fn test_parse() { assert!(parse(\"1\").is_ok()); }").unwrap();
+        let original_build_fingerprint = build_fingerprint(&harness_path).unwrap();
+
+        let mut verdict_cache = VerdictCache::new(original_build_fingerprint);
+        verdict_cache.record(1, "tests::test_parse", MutationTestResult::Detected);
+        let verdict_cache = VerdictCache::parse(&verdict_cache.to_text()).unwrap();
+        assert!(verdict_cache.is_valid_for_build(original_build_fingerprint));
+
+        // Editing the test changes the build of the test harness, and thus invalidates the verdicts recorded with it.
+        fs::write(&harness_path, "fn test_parse() { assert!(parse(\"1\").is_err()); }").unwrap();
+        let edited_build_fingerprint = build_fingerprint(&harness_path).unwrap();
+        assert_ne!(edited_build_fingerprint, original_build_fingerprint);
+        assert!(!verdict_cache.is_valid_for_build(edited_build_fingerprint));
+
+        // Verdict caches without a fingerprint are never valid.
+        assert!(!VerdictCache::default().is_valid_for_build(original_build_fingerprint));
+
+        fs::remove_file(&harness_path).unwrap();
+    }
+}
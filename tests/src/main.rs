@@ -158,6 +158,22 @@ fn log_test(test_name: &str, result: TestResult, reason: Option<&str>) {
     );
 }
 
+/// Replace the execution times reported by test harnesses (e.g. `tests::test took 1.23ms`), which vary between runs.
+fn normalize_run_output(out: &str) -> String {
+    let mut normalized_out = String::with_capacity(out.len());
+    for line in out.split_inclusive('\n') {
+        match line.rsplit_once(" took ") {
+            Some((prefix, _)) => {
+                normalized_out.push_str(prefix);
+                normalized_out.push_str(" took [TIME]");
+                if line.ends_with('\n') { normalized_out.push('\n'); }
+            }
+            None => normalized_out.push_str(line),
+        }
+    }
+    normalized_out
+}
+
 fn run_test(path: &Path, aux_dir_path: &Path, root_dir: &Path, opts: &Opts, results: &mut TestRunResults) {
     if !path.is_file() { return; }
     if !path.extension().is_some_and(|v| v == "rs") { return; }
@@ -212,6 +228,8 @@ fn run_test(path: &Path, aux_dir_path: &Path, root_dir: &Path, opts: &Opts, resu
     }
 
     let mut expect_command_fail = false;
+    // Run tests build the test harness, then run it, with their expectations applying to the output of the harness.
+    let mut run = false;
     let mut expectations = BTreeSet::new();
     let mut mutest_prints = BTreeSet::new();
     let mutest_subcommand = {
@@ -219,14 +237,17 @@ fn run_test(path: &Path, aux_dir_path: &Path, root_dir: &Path, opts: &Opts, resu
 
         for directive in &directives {
             match directive.as_str() {
-                subcommand @ ("print-tests" | "print-call-graph" | "print-targets" | "print-mutants" | "print-code" | "build" | "run") => {
+                subcommand @ ("print-tests" | "print-call-graph" | "print-targets" | "print-ops" | "print-mutants" | "print-patches" | "print-code" | "build" | "run") => {
                     if let Some(previous_subcommand) = mutest_subcommand && previous_subcommand != "print" {
                         results.ignored_tests_count += 1;
                         log_test(&name, TestResult::Ignored, Some("invalid directives"));
                         return;
                     }
                     match subcommand {
-                        "run" => mutest_subcommand = Some("build"),
+                        "run" => {
+                            run = true;
+                            mutest_subcommand = Some("build");
+                        }
                         "print-tests" => {
                             mutest_prints.insert("tests");
                             mutest_subcommand.get_or_insert("print");
@@ -239,10 +260,18 @@ fn run_test(path: &Path, aux_dir_path: &Path, root_dir: &Path, opts: &Opts, resu
                             mutest_prints.insert("targets");
                             mutest_subcommand.get_or_insert("print");
                         }
+                        "print-ops" => {
+                            mutest_prints.insert("ops");
+                            mutest_subcommand.get_or_insert("print");
+                        }
                         "print-mutants" => {
                             mutest_prints.insert("mutants");
                             mutest_subcommand.get_or_insert("print");
                         }
+                        "print-patches" => {
+                            mutest_prints.insert("patches");
+                            mutest_subcommand.get_or_insert("print");
+                        }
                         "print-code" => {
                             mutest_prints.insert("code");
                             mutest_subcommand.get_or_insert("print");
@@ -267,11 +296,13 @@ fn run_test(path: &Path, aux_dir_path: &Path, root_dir: &Path, opts: &Opts, resu
                 "stderr: empty" => { expectations.insert(Expectation::StdErr { empty: true }); }
 
                 _ if directive.starts_with("aux-build:") => {}
+                _ if directive.starts_with("manifest-dir:") => {}
                 _ if directive.starts_with("rustc-flags:") => {}
                 _ if directive.starts_with("verify:") => {}
                 _ if directive.starts_with("mutation-operators:") => {}
                 _ if directive.starts_with("mutest-flags:") => {}
                 _ if directive.starts_with("mutest-subcommand-flags:") => {}
+                _ if directive.starts_with("run-flags:") => {}
 
                 _ => {
                     results.ignored_tests_count += 1;
@@ -351,6 +382,8 @@ fn run_test(path: &Path, aux_dir_path: &Path, root_dir: &Path, opts: &Opts, resu
         }
     }
 
+    let harness_path = Path::new(BUILD_OUT_DIR).join(format!("{test_crate_name}{}", env::consts::EXE_SUFFIX));
+
     let mut cmd = Command::new("target/release/mutest-driver");
     // We need to invoke mutest-driver as a rustc wrapper. This must be the first argument.
     cmd.arg("/dummy/rustc");
@@ -369,6 +402,13 @@ fn run_test(path: &Path, aux_dir_path: &Path, root_dir: &Path, opts: &Opts, resu
 
     cmd.env("MUTEST_SEARCH_PATH", "target/release");
 
+    // Mimic Cargo, which sets the absolute path of the package directory, e.g. to discover the integration tests of
+    // the package.
+    if let Some(manifest_dir) = directives.iter().find_map(|d| d.strip_prefix("manifest-dir:").map(str::trim)) {
+        let manifest_dir = env::current_dir().expect("cannot get current directory").join(path.parent().expect("invalid test path")).join(manifest_dir);
+        cmd.env("CARGO_MANIFEST_DIR", manifest_dir);
+    }
+
     #[cfg(windows)]
     cmd.args(["-L", windows_lib.as_str()]);
 
@@ -423,10 +463,9 @@ fn run_test(path: &Path, aux_dir_path: &Path, root_dir: &Path, opts: &Opts, resu
         eprintln!("stderr:\n{}", stderr);
     }
 
-    let expected_exit_code = match (expect_command_fail, mutest_subcommand) {
-        (true, _) => 1,
-        (_, "run") => 101,
-        (false, _) => 0,
+    let expected_exit_code = match expect_command_fail {
+        true => 1,
+        false => 0,
     };
 
     if output.status.code() != Some(expected_exit_code) {
@@ -440,6 +479,48 @@ fn run_test(path: &Path, aux_dir_path: &Path, root_dir: &Path, opts: &Opts, resu
         return;
     }
 
+    let (stdout, stderr) = match run {
+        false => (stdout, stderr),
+        true => {
+            let mut cmd = Command::new(&harness_path);
+            directives.iter().filter_map(|d| d.strip_prefix("run-flags:").map(str::trim))
+                .flat_map(|flags| flags.split(" ").filter(|flag| !flag.is_empty()))
+                .for_each(|flag| { cmd.arg(flag); });
+
+            if opts.verbosity >= 1 {
+                eprintln!("running {cmd:?}");
+            }
+
+            let output = cmd.output().expect("cannot spawn test harness");
+            let stdout = normalize_run_output(&String::from_utf8(output.stdout).unwrap());
+            let stderr = normalize_run_output(&String::from_utf8(output.stderr).unwrap());
+            if opts.verbosity >= 1 {
+                if let Some(exit_code) = output.status.code() {
+                    eprintln!("exited with code {exit_code}");
+                }
+                eprintln!("stdout:\n{}", stdout);
+                eprintln!("stderr:\n{}", stderr);
+            }
+
+            // Test harnesses exit with an error if any mutations were not detected, which run tests are expected to
+            // exhibit.
+            let expected_exit_code = 101;
+
+            if output.status.code() != Some(expected_exit_code) {
+                results.failed_tests_count += 1;
+                log_test(&name, TestResult::Failed, Some(&match output.status.code() {
+                    Some(exit_code) => format!("test harness exited with code {exit_code}, expected {expected_exit_code}"),
+                    None => format!("test harness exited without exit code, expected {expected_exit_code}"),
+                }));
+                eprintln!("stdout:\n{}", stdout);
+                eprintln!("stderr:\n{}", stderr);
+                return;
+            }
+
+            (stdout, stderr)
+        }
+    };
+
     if opts.bless {
         let bless_verdicts = expectations.iter()
             .map(|expectation| expectation.bless(&path, &stdout, &stderr, opts.dry_run))
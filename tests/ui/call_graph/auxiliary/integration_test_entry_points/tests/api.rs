@@ -0,0 +1,11 @@
+mod common;
+
+#[test]
+fn test_add() {
+    assert_eq!(crate::add(1, 2), 3);
+}
+
+#[test]
+fn test_double() {
+    common::assert_doubled(2);
+}
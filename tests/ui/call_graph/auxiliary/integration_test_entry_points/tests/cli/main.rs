@@ -0,0 +1,4 @@
+#[test]
+fn test_double() {
+    assert_eq!(crate::double(1), 2);
+}
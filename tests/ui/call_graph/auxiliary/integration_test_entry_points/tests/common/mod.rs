@@ -0,0 +1,3 @@
+pub fn assert_doubled(a: u32) {
+    assert_eq!(crate::double(a), 2 * a);
+}
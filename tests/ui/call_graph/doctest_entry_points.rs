@@ -0,0 +1,18 @@
+//@ print-targets
+//@ stdout
+//@ stderr: empty
+//@ mutest-flags: --doctests
+
+/// Adds two numbers.
+///
+/// ```
+/// # use crate::add as sum;
+/// assert_eq!(sum(1, 2), 3);
+/// ```
+///
+/// ```ignore
+/// assert_eq!(crate::add(1, 1), 3);
+/// ```
+fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
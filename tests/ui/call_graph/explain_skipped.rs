@@ -0,0 +1,31 @@
+//@ print-targets
+//@ mutest-flags: --depth=1 --call-graph-depth=2 --explain-skipped
+//@ stdout
+//@ stderr: empty
+
+const fn c() -> u32 { 1 }
+
+fn f() -> u32 {
+    g() + c()
+}
+
+fn g() -> u32 { 2 }
+
+pub fn unreached() {}
+
+#[mutest::skip]
+fn skipped() {}
+
+pub trait T {
+    fn required(&self);
+}
+
+#[cfg(test)]
+fn helper() {}
+
+#[test]
+fn test_f() {
+    f();
+    skipped();
+    helper();
+}
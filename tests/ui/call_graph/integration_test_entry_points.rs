@@ -0,0 +1,14 @@
+//@ print-tests
+//@ print-targets
+//@ stdout
+//@ stderr: empty
+//@ mutest-flags: --integration-tests
+//@ manifest-dir: auxiliary/integration_test_entry_points
+
+fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+fn double(a: u32) -> u32 {
+    add(a, a)
+}
@@ -0,0 +1,15 @@
+//@ print-call-graph
+//@ mutest-flags: --graph-format=json
+//@ stdout
+//@ stderr: empty
+
+fn f() {
+    g();
+}
+
+fn g() {}
+
+#[test]
+fn test() {
+    f();
+}
@@ -0,0 +1,20 @@
+//@ print-targets
+//@ mutest-flags: --targets-format=json --with-tests
+//@ stdout
+//@ stderr: empty
+
+fn f() {
+    g();
+}
+
+fn g() {}
+
+#[test]
+fn test_f() {
+    f();
+}
+
+#[test]
+fn test_g() {
+    g();
+}
@@ -0,0 +1,20 @@
+//@ print-targets
+//@ stdout
+//@ stderr: empty
+
+#![allow(unused)]
+
+fn tested_fn() -> bool {
+    true
+}
+
+fn untested_fn() {}
+
+#[cfg_attr(mutest, mutest::test)]
+fn check_tested_fn() {
+    assert!(tested_fn());
+}
+
+fn call_untested_fn() {
+    untested_fn();
+}
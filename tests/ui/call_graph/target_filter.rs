@@ -0,0 +1,22 @@
+//@ print-targets
+//@ mutest-flags: --mutate-only=lexer::* --skip-target=lexer::skipped --explain-skipped
+//@ stdout
+//@ stderr: empty
+
+mod lexer {
+    pub fn next_token() {}
+
+    pub fn skipped() {}
+}
+
+mod parser {
+    pub fn parse() {
+        super::lexer::next_token();
+        super::lexer::skipped();
+    }
+}
+
+#[test]
+fn test_parse() {
+    parser::parse();
+}
@@ -0,0 +1,25 @@
+//@ print-targets
+//@ stdout
+//@ stderr: empty
+
+fn default_impl() {}
+
+trait HasDefault {
+    fn required(&self);
+
+    fn provided(&self) {
+        default_impl();
+        self.required();
+    }
+}
+
+struct ImplsHasDefault;
+
+impl HasDefault for ImplsHasDefault {
+    fn required(&self) {}
+}
+
+#[test]
+fn test() {
+    ImplsHasDefault.provided();
+}
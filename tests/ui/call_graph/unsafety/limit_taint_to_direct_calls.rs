@@ -0,0 +1,21 @@
+//@ print-targets
+//@ mutest-flags: --unsafe-taint=direct
+//@ stdout
+//@ stderr: empty
+
+#![allow(unused_unsafe)]
+
+fn len() {}
+
+fn offset_from() {
+    len();
+}
+
+fn reserve_inner() {
+    unsafe { offset_from(); }
+}
+
+#[test]
+fn test() {
+    reserve_inner();
+}
@@ -0,0 +1,24 @@
+//@ print-targets
+//@ stdout
+//@ stderr: empty
+
+#![allow(unused_unsafe)]
+
+fn offset_from() {}
+
+fn reserve_inner() {
+    unsafe { offset_from(); }
+}
+
+#[mutest::unsafe_policy = "risky"]
+mod risky {
+    pub fn reserve_inner() {
+        unsafe { super::offset_from(); }
+    }
+}
+
+#[test]
+fn test() {
+    reserve_inner();
+    risky::reserve_inner();
+}
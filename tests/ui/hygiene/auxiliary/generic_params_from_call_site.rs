@@ -0,0 +1,33 @@
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+use proc_macro::{Delimiter, Group, Ident, Punct, Spacing, Span, TokenStream, TokenTree};
+
+/// Generates an impl block for `Name<Param>`, reusing the generic param tokens of the call-site in the generics
+/// of the impl, but referring to the generic param with call-site spans in the generated body.
+fn impl_with_body(input: TokenStream, body: impl FnOnce(&str) -> String) -> TokenStream {
+    let mut tokens = input.into_iter();
+    let Some(name) = tokens.next() else { panic!("expected `Name<Param>`") };
+    let param = tokens.skip(1)
+        .take_while(|token| !matches!(token, TokenTree::Punct(punct) if punct.as_char() == '>'))
+        .collect::<TokenStream>();
+
+    let mut output = TokenStream::new();
+    output.extend([TokenTree::from(Ident::new("impl", Span::call_site())), Punct::new('<', Spacing::Alone).into()]);
+    output.extend(param.clone());
+    output.extend([TokenTree::from(Punct::new('>', Spacing::Alone)), name, Punct::new('<', Spacing::Alone).into()]);
+    output.extend(param.clone());
+    output.extend([TokenTree::from(Punct::new('>', Spacing::Alone))]);
+    output.extend([TokenTree::from(Group::new(Delimiter::Brace, body(&param.to_string()).parse().unwrap()))]);
+    output
+}
+
+#[proc_macro]
+pub fn impl_identity(input: TokenStream) -> TokenStream {
+    impl_with_body(input, |param| format!("fn identity(x: {param}) -> {param} {{ let y: {param} = x; y }}"))
+}
+
+#[proc_macro]
+pub fn impl_ref_identity(input: TokenStream) -> TokenStream {
+    impl_with_body(input, |param| format!("fn ref_identity(x: &{param} u8) -> &{param} u8 {{ x }}"))
+}
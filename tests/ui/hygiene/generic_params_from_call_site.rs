@@ -0,0 +1,14 @@
+//@ build
+//@ stderr: empty
+//@ aux-build: generic_params_from_call_site.rs
+
+#![allow(dead_code)]
+
+extern crate generic_params_from_call_site;
+use generic_params_from_call_site::{impl_identity, impl_ref_identity};
+
+struct Wrapper<T>(T);
+impl_identity!(Wrapper<T>);
+
+struct Ref<'a>(&'a u8);
+impl_ref_identity!(Ref<'a>);
@@ -0,0 +1,24 @@
+//@ build
+//@ stderr: empty
+
+#![no_implicit_prelude]
+
+fn checked_double(x: i32) -> ::core::option::Option<i32> {
+    x.checked_mul(2)
+}
+
+pub fn checked_quadruple(x: i32) -> ::core::option::Option<i32> {
+    match checked_double(x) {
+        ::core::option::Option::Some(y) => checked_double(y),
+        ::core::option::Option::None => ::core::option::Option::None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[::core::prelude::v1::test]
+    fn test_checked_quadruple() {
+        ::core::assert_eq!(::core::option::Option::Some(4), super::checked_quadruple(1));
+        ::core::assert_eq!(::core::option::Option::None, super::checked_quadruple(::core::primitive::i32::MAX));
+    }
+}
@@ -0,0 +1,22 @@
+//@ build
+//@ stderr: empty
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+pub fn evens(xs: &[u32]) -> Vec<u32> {
+    xs.iter().copied().filter(|x| x % 2 == 0).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    #[test]
+    fn test_evens() {
+        assert_eq!(vec![2, 4], super::evens(&[1, 2, 3, 4]));
+    }
+}
@@ -0,0 +1,31 @@
+//@ print-tests
+//@ stdout
+
+fn tested_fn() {}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn active_test() {
+        super::tested_fn();
+    }
+
+    #[test]
+    #[ignore]
+    fn ignored_test() {
+        super::tested_fn();
+    }
+
+    #[test]
+    #[ignore = "requires network access"]
+    fn ignored_test_with_reason() {
+        super::tested_fn();
+    }
+
+    #[test]
+    #[should_panic(expected = "explicit panic")]
+    fn should_panic_test() {
+        super::tested_fn();
+        panic!("explicit panic");
+    }
+}
@@ -0,0 +1,14 @@
+//@ print-mutants
+//@ mutest-flags: --explain=2
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: eq_op_invert, math_op_add_sub_swap
+
+fn f(a: u32) -> bool {
+    a + 1 == 2
+}
+
+#[test]
+fn test_f() {
+    assert!(f(1));
+}
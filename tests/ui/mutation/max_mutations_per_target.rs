@@ -0,0 +1,18 @@
+//@ print-mutants
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: eq_op_invert
+//@ mutest-flags: --max-mutations-per-target=2
+
+fn f(a: u32) -> bool {
+    let b = a == 1;
+    let c = a == 2;
+    let d = a == 3;
+    let e = a == 4;
+    b || c || d || e
+}
+
+#[test]
+fn test() {
+    f(1);
+}
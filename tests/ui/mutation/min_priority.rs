@@ -0,0 +1,14 @@
+//@ print-mutants
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: math_op_add_sub_swap, math_op_add_mul_swap, bit_op_or_and_swap
+//@ mutest-flags: --min-priority=high
+
+fn f(a: u32, b: u32) -> u32 {
+    (a + b) | b
+}
+
+#[test]
+fn test() {
+    f(1, 2);
+}
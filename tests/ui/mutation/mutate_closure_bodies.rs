@@ -0,0 +1,16 @@
+//@ print-mutants
+//@ build
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: eq_op_invert
+
+fn f(values: &[u32]) -> usize {
+    let is_zero = |v: &&u32| **v == 0;
+    let _ = async { values.len() == 0 };
+    values.iter().filter(is_zero).count()
+}
+
+#[test]
+fn test() {
+    f(&[0, 1, 2]);
+}
@@ -0,0 +1,18 @@
+//@ print-mutants
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: eq_op_invert
+//@ mutest-flags: --mutate-macro-expansions
+
+macro_rules! is_zero {
+    ($v:expr) => { $v == 0 };
+}
+
+fn f(a: u32, b: u32) -> bool {
+    is_zero!(a) && b == 1
+}
+
+#[test]
+fn test() {
+    f(0, 1);
+}
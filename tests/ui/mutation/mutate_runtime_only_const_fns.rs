@@ -0,0 +1,22 @@
+//@ print-mutants
+//@ build
+//@ stdout
+//@ stderr: empty
+//@ mutest-flags: --mutate-const-fns
+//@ mutation-operators: eq_op_invert
+
+const fn is_zero(v: u32) -> bool {
+    v == 0
+}
+
+const fn is_one(v: u32) -> bool {
+    v == 1
+}
+
+const IS_ONE: bool = is_one(1);
+
+#[test]
+fn test() {
+    assert!(is_zero(0));
+    assert!(is_one(1) && IS_ONE);
+}
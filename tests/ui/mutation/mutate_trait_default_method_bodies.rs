@@ -0,0 +1,24 @@
+//@ print-mutants
+//@ build
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: eq_op_invert
+
+trait IsZero {
+    fn value(&self) -> u32;
+
+    fn is_zero(&self) -> bool {
+        self.value() == 0
+    }
+}
+
+struct Zero;
+
+impl IsZero for Zero {
+    fn value(&self) -> u32 { 0 }
+}
+
+#[test]
+fn test() {
+    assert!(Zero.is_zero());
+}
@@ -0,0 +1,23 @@
+//@ print-mutants
+//@ build
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: bool_return_flip
+
+#![allow(unused)]
+
+fn is_even(n: usize) -> bool {
+    if n == 0 { return true; }
+    let f = |m: usize| -> bool { return m % 2 == 0; };
+    f(n)
+}
+
+fn count(n: usize) -> usize {
+    n
+}
+
+#[test]
+fn test() {
+    is_even(2);
+    count(2);
+}
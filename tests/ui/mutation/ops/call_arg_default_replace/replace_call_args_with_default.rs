@@ -0,0 +1,27 @@
+//@ print-mutants
+//@ build
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: call_arg_default_replace
+
+#![allow(unused)]
+
+struct S;
+
+fn g(n: usize, s: S, v: &str) -> usize {
+    n + v.len()
+}
+
+fn h<T>(t: T) -> T {
+    t
+}
+
+fn f() {
+    let _ = g(1, S, "foo");
+    let _ = h(-1_i32);
+}
+
+#[test]
+fn test() {
+    f();
+}
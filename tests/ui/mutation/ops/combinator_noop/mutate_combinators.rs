@@ -0,0 +1,16 @@
+//@ print-mutants
+//@ build
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: combinator_noop
+
+fn f(values: &[u32]) -> Vec<u32> {
+    let mut values = values.iter().filter(|v| **v > 1).copied().map(|v| v * 2).collect::<Vec<_>>();
+    values.sort();
+    values
+}
+
+#[test]
+fn test() {
+    f(&[1, 2, 3]);
+}
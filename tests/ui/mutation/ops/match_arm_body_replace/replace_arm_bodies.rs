@@ -0,0 +1,26 @@
+//@ print-mutants
+//@ build
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: match_arm_body_replace
+
+fn f(v: Option<u32>) -> u32 {
+    match v {
+        Some(0) => 1,
+        Some(x) => x * 2,
+        None => 0,
+    }
+}
+
+fn g(v: bool, out: &mut Vec<u32>) {
+    match v {
+        true => out.push(1),
+        false => {}
+    }
+}
+
+#[test]
+fn test() {
+    f(Some(1));
+    g(true, &mut vec![]);
+}
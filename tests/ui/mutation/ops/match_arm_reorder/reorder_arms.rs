@@ -0,0 +1,26 @@
+//@ print-mutants
+//@ build
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: match_arm_reorder
+
+fn classify(n: u32) -> u32 {
+    match n {
+        0 => 1,
+        n if n % 2 == 0 => 2,
+        _ => 3,
+    }
+}
+
+fn unwrap_or_zero(v: Option<u32>) -> u32 {
+    match v {
+        Some(x) => x,
+        None => 0,
+    }
+}
+
+#[test]
+fn test() {
+    classify(1);
+    unwrap_or_zero(Some(1));
+}
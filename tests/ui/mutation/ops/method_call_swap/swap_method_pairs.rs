@@ -0,0 +1,14 @@
+//@ print-mutants
+//@ build
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: method_call_swap
+
+fn f(a: u32, b: u32) -> u32 {
+    a.min(b).saturating_add(1).count_ones()
+}
+
+#[test]
+fn test() {
+    f(1, 2);
+}
@@ -0,0 +1,67 @@
+//@ print-mutants
+//@ build
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: math_op_add_sub_swap
+
+use std::ops::{Add, Sub};
+
+#[derive(Clone, Copy)]
+struct Meters(u32);
+
+impl Add for Meters {
+    type Output = Self;
+
+    #[mutest::skip]
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Sub for Meters {
+    type Output = Self;
+
+    #[mutest::skip]
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl<'a> Add for &'a Meters {
+    type Output = Meters;
+
+    #[mutest::skip]
+    fn add(self, other: Self) -> Meters {
+        Meters(self.0 + other.0)
+    }
+}
+
+struct BigNum(u64);
+
+impl Add for BigNum {
+    type Output = Self;
+
+    #[mutest::skip]
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl<'a> Sub for &'a BigNum {
+    type Output = BigNum;
+
+    #[mutest::skip]
+    fn sub(self, other: Self) -> BigNum {
+        BigNum(self.0 - other.0)
+    }
+}
+
+fn f(a: &Meters, b: &Meters, c: BigNum, d: BigNum) {
+    let _ = a + b;
+    let _ = c + d;
+}
+
+#[test]
+fn test() {
+    f(&Meters(2), &Meters(1), BigNum(2), BigNum(1));
+}
@@ -0,0 +1,22 @@
+//@ print-mutants
+//@ build
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: math_op_add_sub_swap
+
+use std::ops::{Add, Sub};
+
+fn add_sub<T: Add<Output = T> + Sub<Output = T>>(a: T, b: T) -> T {
+    a + b
+}
+
+fn add_only<T: Add<Output = T>>(a: T, b: T) -> T {
+    a + b
+}
+
+#[test]
+fn test() {
+    add_sub(1_usize, 2);
+    add_sub(1_i32, 2);
+    add_only(1_u8, 2);
+}
@@ -0,0 +1,19 @@
+//@ print-mutants
+//@ build
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: math_op_add_sub_swap
+
+use std::ops::{Add, AddAssign};
+
+fn f(a: u32, b: u32, c: &mut u32) -> u32 {
+    let mut d = a.add(b);
+    d.add_assign(a);
+    c.add_assign(Add::add(a, b));
+    d
+}
+
+#[test]
+fn test() {
+    f(2, 1, &mut 0);
+}
@@ -0,0 +1,25 @@
+//@ print-mutants
+//@ build
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: option_result_combinator_swap
+
+#![allow(unused)]
+
+const DEFAULT: usize = 1;
+
+fn f(a: Option<usize>, b: Result<(usize, usize), ()>, c: usize) {
+    let _ = a.unwrap_or(1);
+    let _ = a.unwrap_or(DEFAULT);
+    let _ = a.unwrap_or(c);
+    let _ = a.unwrap_or_else(|| 2);
+    let _ = a.unwrap_or_else(|| c);
+    let _ = a.ok_or(());
+    let _ = a.ok_or_else(|| Some(c));
+    let _ = b.unwrap_or((c, 3));
+}
+
+#[test]
+fn test() {
+    f(Some(1), Ok((1, 2)), 3);
+}
@@ -0,0 +1,23 @@
+//@ print-mutants
+//@ build
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: option_result_combinator_swap
+
+#![allow(unused)]
+
+fn default() -> usize { 1 }
+fn error() {}
+
+fn f(a: Option<usize>, b: Result<usize, ()>) {
+    let _ = a.unwrap_or(default());
+    let _ = a.unwrap_or_else(|| default());
+    let _ = a.ok_or(error());
+    let _ = b.unwrap_or(default());
+    let _ = b.unwrap_or_else(|_| default());
+}
+
+#[test]
+fn test() {
+    f(Some(1), Ok(1));
+}
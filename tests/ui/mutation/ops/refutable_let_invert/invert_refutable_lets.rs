@@ -0,0 +1,29 @@
+//@ print-mutants
+//@ build
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: refutable_let_invert
+
+fn classify(value: Option<u32>) -> &'static str {
+    if let Some(_) = value {
+        "some"
+    } else {
+        "none"
+    }
+}
+
+fn unwrap_or_zero(value: Option<u32>) -> u32 {
+    if let Some(v) = value { v } else { 0 }
+}
+
+fn first_or_zero(values: &[u32]) -> u32 {
+    let Some(first) = values.first().copied() else { return 0; };
+    first
+}
+
+#[test]
+fn test() {
+    classify(Some(1));
+    unwrap_or_zero(Some(1));
+    first_or_zero(&[1]);
+}
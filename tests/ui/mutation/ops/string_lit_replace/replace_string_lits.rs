@@ -0,0 +1,17 @@
+//@ print-mutants
+//@ build
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: string_lit_replace
+
+fn greeting(name: &str) -> String {
+    match name {
+        "" => String::from("Hello!"),
+        _ => format!("Hello, {name}!"),
+    }
+}
+
+#[test]
+fn test() {
+    greeting("world");
+}
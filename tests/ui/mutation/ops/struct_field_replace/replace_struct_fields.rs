@@ -0,0 +1,21 @@
+//@ print-mutants
+//@ build
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: struct_field_replace
+
+struct Range {
+    start: u32,
+    end: u32,
+    label: String,
+}
+
+fn range(start: u32, end: u32) -> Range {
+    Range { start, end, label: String::from("range") }
+}
+
+#[test]
+fn test() {
+    let range = range(1, 2);
+    assert_eq!((range.start, range.end, range.label.as_str()), (1, 2, "range"));
+}
@@ -0,0 +1,19 @@
+//@ print-mutants
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: eq_op_invert, math_op_add_sub_swap
+
+fn f(a: u32) -> bool {
+    // mutest: ignore-next-line
+    let b = a + 1 == 2;
+    let c = a + 2 == 3; // mutest: ignore(eq_op_invert)
+    // mutest: ignore(math_op_add_sub_swap)
+    let d = a + 3 == 4;
+    let e = a + 4 == 5;
+    b || c || d || e
+}
+
+#[test]
+fn test() {
+    f(1);
+}
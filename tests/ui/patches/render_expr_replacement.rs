@@ -0,0 +1,13 @@
+//@ print-patches
+//@ stdout
+//@ stderr: empty
+//@ mutation-operators: eq_op_invert
+
+fn f(a: u32) -> bool {
+    a == 9
+}
+
+#[test]
+fn test() {
+    f(9);
+}
@@ -0,0 +1,27 @@
+//@ print-targets
+//@ stdout
+//@ stderr: empty
+
+fn help_program() {}
+
+#[cfg(test)]
+#[cfg_attr(mutest, mutest::mutate_cfg_test)]
+fn help_test_opted_in() {}
+
+#[cfg(test)]
+mod tests {
+    fn help_test() {}
+
+    #[cfg_attr(mutest, mutest::mutate_cfg_test)]
+    mod support {
+        pub fn help_support() {}
+    }
+
+    #[test]
+    fn test1() {
+        help_test();
+        support::help_support();
+        super::help_test_opted_in();
+        super::help_program();
+    }
+}
@@ -0,0 +1,27 @@
+//@ print-targets
+//@ stdout
+//@ stderr: empty
+
+#[cfg_attr(mutest, mutest::note = "owned by the parser team")]
+fn noted_fn() {}
+
+#[cfg_attr(mutest, mutest::note = "owned by the storage team")]
+mod storage {
+    pub fn noted_by_mod() {}
+
+    #[cfg_attr(mutest, mutest::note = "owned by the cache team")]
+    pub fn noted_by_fn_in_mod() {}
+}
+
+fn not_noted() {}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test() {
+        super::noted_fn();
+        super::storage::noted_by_mod();
+        super::storage::noted_by_fn_in_mod();
+        super::not_noted();
+    }
+}
@@ -0,0 +1,22 @@
+//@ build
+//@ stderr: empty
+
+pub fn reverse(v: &[u32]) -> Vec<u32> {
+    v.iter().rev().copied().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    fn cases() -> usize {
+        std::env::var("PROPTEST_CASES").ok().and_then(|cases| cases.parse().ok()).unwrap_or(256)
+    }
+
+    #[test]
+    #[cfg_attr(mutest, mutest::property_test)]
+    fn prop_reverse_involutive() {
+        for i in 0..cases() as u32 {
+            let v = (0..i % 8).map(|j| i.wrapping_mul(31).wrapping_add(j)).collect::<Vec<_>>();
+            assert_eq!(super::reverse(&super::reverse(&v)), v);
+        }
+    }
+}
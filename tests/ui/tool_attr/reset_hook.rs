@@ -0,0 +1,53 @@
+//@ run
+//@ stdout
+//@ mutation-operators: eq_op_invert
+//@ run-flags: --format=github
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static CACHE_HITS: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg_attr(mutest, mutest::reset_hook)]
+fn reset_cache_hits() {
+    CACHE_HITS.store(0, Ordering::SeqCst);
+}
+
+pub mod cache {
+    use std::sync::Mutex;
+
+    static CACHE: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+    pub fn lookup(key: usize) -> bool {
+        let mut cache = CACHE.lock().unwrap();
+        for &cached_key in cache.iter() {
+            if cached_key == key {
+                super::CACHE_HITS.fetch_add(1, super::Ordering::SeqCst);
+                return true;
+            }
+        }
+        // Inverting this check is an equivalent mutation, which is only undetected if the cache entries left behind by
+        // previous tests and mutants are cleared by the reset hooks.
+        if cache.len() == 0 {
+            cache.reserve(16);
+        }
+        cache.push(key);
+        false
+    }
+
+    #[cfg_attr(mutest, mutest::reset_hook)]
+    pub(crate) fn reset_cache() {
+        CACHE.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    #[test]
+    fn test() {
+        assert!(!super::cache::lookup(1));
+        assert!(super::cache::lookup(1));
+        assert_eq!(1, super::CACHE_HITS.load(Ordering::SeqCst));
+    }
+}